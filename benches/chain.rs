@@ -0,0 +1,131 @@
+//! Criterion suite for the chain runtime; scenarios and usage in `modulink_rs::bench`.
+
+use criterion::measurement::{Measurement, ValueFormatter};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use modulink_rs::bench::{self, NoopMiddleware, CONTEXT_SIZES};
+use modulink_rs::context::Context;
+use modulink_rs::middleware::Middleware;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+// Counts heap allocations for the `chain/allocations` group
+struct CountingAlloc;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Criterion measurement of heap allocations instead of time.
+struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = usize;
+    type Value = usize;
+    fn start(&self) -> usize {
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }
+    fn end(&self, start: usize) -> usize {
+        ALLOCATIONS.load(Ordering::Relaxed) - start
+    }
+    fn add(&self, v1: &usize, v2: &usize) -> usize {
+        v1 + v2
+    }
+    fn zero(&self) -> usize {
+        0
+    }
+    fn to_f64(&self, value: &usize) -> f64 {
+        *value as f64
+    }
+    fn formatter(&self) -> &dyn ValueFormatter {
+        self
+    }
+}
+
+impl ValueFormatter for Allocations {
+    fn scale_values(&self, _typical: f64, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+    fn scale_throughputs(&self, _typical: f64, _throughput: &Throughput, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+}
+
+fn chains(c: &mut Criterion) {
+    let rt = tokio::runtime::Runtime::new().unwrap();
+    let scenarios = bench::scenarios();
+    let mut groups: Vec<&str> = scenarios.iter().map(|s| s.group()).collect();
+    groups.dedup();
+    for group_name in groups {
+        let mut group = c.benchmark_group(group_name);
+        for scenario in scenarios.iter().filter(|s| s.group() == group_name) {
+            let parameter = &scenario.name[group_name.len() + 1..];
+            group.bench_function(BenchmarkId::from_parameter(parameter), |b| {
+                b.to_async(&rt).iter(|| scenario.chain.run(black_box(scenario.input.clone())))
+            });
+        }
+        group.finish();
+    }
+}
+
+fn context(c: &mut Criterion) {
+    let mut insert = c.benchmark_group("context/insert");
+    for size in CONTEXT_SIZES {
+        let ctx = bench::context_with_keys(size);
+        insert.bench_function(BenchmarkId::from_parameter(size), |b| {
+            b.iter(|| black_box(ctx.clone()).insert("user_id", 42))
+        });
+    }
+    insert.finish();
+
+    let mut get = c.benchmark_group("context/get");
+    for size in CONTEXT_SIZES {
+        let ctx = bench::context_with_keys(size);
+        let key = format!("key{}", size / 2);
+        get.bench_function(BenchmarkId::from_parameter(size), |b| b.iter(|| black_box(&ctx).get::<usize>(&key)));
+    }
+    get.finish();
+}
+
+fn allocations(c: &mut Criterion<Allocations>) {
+    // A single thread, so idle runtime workers don't add to the count
+    let rt = tokio::runtime::Builder::new_current_thread().build().unwrap();
+    let extra: Vec<Arc<dyn Middleware<Context>>> = vec![Arc::new(NoopMiddleware)];
+    let mut group = c.benchmark_group("chain/allocations");
+    for (parameter, capacity) in [("pooled", 64), ("unpooled", 0)] {
+        let mut chain = bench::linear_chain(10);
+        chain.set_pool_capacity(capacity);
+        group.bench_function(BenchmarkId::from_parameter(parameter), |b| {
+            b.to_async(&rt).iter(|| chain.run_with(black_box(Context::new()), &extra))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, chains, context);
+criterion_group! {
+    name = allocation_benches;
+    // Allocation counts barely vary, which the plots cannot draw
+    config = Criterion::default().with_measurement(Allocations).without_plots();
+    targets = allocations
+}
+criterion_main!(benches, allocation_benches);
@@ -0,0 +1,4 @@
+//! The monitoring page served at `GET /admin/dashboard`: plain HTML and script with no
+//! external assets, polling the admin routes it is mounted with.
+
+pub(super) const DASHBOARD_HTML: &str = include_str!("dashboard.html");
@@ -0,0 +1,541 @@
+//! Admin API for registered chains and their runs.
+//!
+//! [`Admin`] keeps a bounded history of finished runs of the chains it watches
+//! ([`Admin::watch`], or every chain in [`crate::registry`] with
+//! [`Admin::watch_registered`]), and controls runs: [`Admin::trigger`] starts one in the
+//! background, [`Admin::cancel`] aborts a running one or drops the checkpoint of a parked
+//! or failed durable one, and [`Admin::retry`] re-runs a durable run from a named link
+//! (the counterpart of `modulink-cli retry`). A run is identified by its request id
+//! (`meta::REQUEST_ID`); runs without one get a generated id in the history. Runs started
+//! here always get a generated id, so a caller cannot pick one that collides with another
+//! run's checkpoint or history.
+//!
+//! With the `http` feature the same operations are served by [`Admin::router`], mounted
+//! into an `HttpListener` with `HttpListener::with_admin` or served on its own with
+//! [`Admin::serve`]:
+//!
+//! | Route                              | Effect                                              |
+//! |------------------------------------|-----------------------------------------------------|
+//! | `GET /admin/chains`                | registered chains with their links                  |
+//! | `POST /admin/chains/{name}/runs`   | start a run; the JSON body is the input (202)       |
+//! | `GET /admin/runs`                  | recent runs; `?chain=`, `?failed=true`, `?limit=`   |
+//! | `GET /admin/running`               | runs started here that have not finished            |
+//! | `GET /admin/stats`                 | per-chain run counts and durations ([`ChainStats`]) |
+//! | `GET /admin/runs/{id}`             | final context and report (steps, branches, status)  |
+//! | `POST /admin/runs/{id}/cancel`     | cancel the run                                      |
+//! | `POST /admin/runs/{id}/retry`      | `{"from_step", "patch"}`: re-run from a link (202)  |
+//! | `GET /admin/quotas`                | today's usage of every key ([`crate::quota`])       |
+//! | `GET /admin/quotas/{key}`          | today's usage and limits of one key                 |
+//! | `POST /admin/quotas/{key}/reset`   | forget what the key used                            |
+//! | `GET /admin/dashboard`             | the monitoring page (`dashboard` feature)           |
+//!
+//! The dashboard is a single embedded HTML page polling these routes: a topology diagram
+//! of each chain, live run statistics, and recent failures with the context changes each
+//! link made before the failure (from `RunReport::journal`, so for chains with
+//! `ChainGeneric::enable_journal`; otherwise the final context is shown).
+//!
+//! Mounted in an `HttpListener` with `HttpListener::with_jwt`, the routes require a valid
+//! bearer token like `/run`, and the token must also carry the admin role (`admin` in its
+//! `roles` claim by default, see [`Admin::with_role`]); others get 403. Without claims
+//! there is nothing to check, so [`Admin::serve`] should only listen on a private address.
+//! A run's input has its reserved (`_`) keys dropped, as on `/run`.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::admin::{Admin, RunFilter};
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::registry;
+//! use std::sync::Arc;
+//!
+//! # #[tokio::main] async fn main() {
+//! let mut chain = Chain::new();
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("done", true) })));
+//! registry::register_chain("admin_doc", Arc::new(chain));
+//!
+//! let admin = Arc::new(Admin::new());
+//! admin.watch_registered();
+//! let id = admin.trigger("admin_doc", Context::new()).unwrap();
+//! while admin.run(&id).is_none() {
+//!     tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+//! }
+//! assert_eq!(admin.runs(&RunFilter::default())[0].ctx["done"], true);
+//! # }
+//! ```
+
+#[cfg(feature = "dashboard")]
+mod dashboard;
+
+use crate::chains::{Chain, RunReport, RunStatus};
+use crate::context::{meta, Context};
+use crate::quota::{QuotaUsage, Quotas};
+use crate::registry;
+use crate::runtime::BoxFuture;
+use futures::future::{AbortHandle, Abortable};
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of finished runs kept by [`Admin::new`].
+pub const DEFAULT_HISTORY: usize = 1000;
+
+/// A finished run as kept in the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunRecord {
+    pub id: String,
+    pub chain: String,
+    pub finished_at_ms: u64,
+    /// Final context of the run.
+    pub ctx: Value,
+    /// Status, step timings, branches taken, and warnings: the run's trace.
+    pub report: RunReport,
+}
+
+/// A run started by [`Admin::trigger`] or [`Admin::retry`] that has not finished.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunningRun {
+    pub id: String,
+    pub chain: String,
+    pub started_at_ms: u64,
+}
+
+/// A registered chain as listed by the admin API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainInfo {
+    pub name: String,
+    pub version: Option<String>,
+    /// `LinkSpec::name` of every link, in order.
+    pub links: Vec<Option<String>>,
+    /// (source, target) of each conditional branch.
+    pub branches: Vec<(usize, usize)>,
+    /// Whether the chain checkpoints durable runs (so they can be retried).
+    pub durable: bool,
+}
+
+/// Run counts and durations of one chain over the runs in the history.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainStats {
+    pub chain: String,
+    pub runs: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub cancelled: usize,
+    /// Durable runs parked at a wait or awaiting an event.
+    pub waiting: usize,
+    pub mean_ms: u64,
+    pub p95_ms: u64,
+    pub last_failure_at_ms: Option<u64>,
+}
+
+/// Which runs [`Admin::runs`] returns; also the query string of `GET /admin/runs`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunFilter {
+    #[serde(default)]
+    pub chain: Option<String>,
+    /// Only runs whose status is `RunStatus::Failed`.
+    #[serde(default)]
+    pub failed: bool,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+/// Errors from admin operations.
+#[derive(Debug)]
+pub enum AdminError {
+    /// No chain is registered under this name.
+    UnknownChain(String),
+    /// No running run or checkpoint has this id.
+    UnknownRun(String),
+    /// The caller's claims lack the admin role.
+    Forbidden(String),
+    /// The request cannot be carried out (unknown link, run already running).
+    InvalidInput(String),
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for AdminError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdminError::UnknownChain(name) => write!(f, "no chain registered as '{}'", name),
+            AdminError::UnknownRun(id) => write!(f, "no run '{}' to act on", id),
+            AdminError::Forbidden(msg) => write!(f, "{}", msg),
+            AdminError::InvalidInput(msg) => write!(f, "{}", msg),
+            AdminError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+impl From<std::io::Error> for AdminError {
+    fn from(e: std::io::Error) -> Self {
+        AdminError::Io(e)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn ctx_value(ctx: &Context) -> Value {
+    Value::Object(ctx.0.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+}
+
+/// Run history and run control shared by the admin routes; see the [module docs](self).
+pub struct Admin {
+    history: Mutex<VecDeque<RunRecord>>,
+    capacity: usize,
+    running: Mutex<HashMap<String, (RunningRun, AbortHandle)>>,
+    watched: Mutex<HashSet<String>>,
+    next_id: AtomicU64,
+    quotas: Mutex<Option<Arc<Quotas>>>,
+    role: String,
+}
+
+impl Default for Admin {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_HISTORY)
+    }
+}
+
+impl Admin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Keep the last `capacity` finished runs.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Admin {
+            history: Mutex::default(),
+            capacity,
+            running: Mutex::default(),
+            watched: Mutex::default(),
+            next_id: AtomicU64::new(0),
+            quotas: Mutex::default(),
+            role: "admin".to_string(),
+        }
+    }
+    /// Require `role` instead of `admin` in the `roles` claim of callers of the routes.
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.role = role.into();
+        self
+    }
+
+    /// Record every run of `chain` finished from now on under `name`. Watching the same
+    /// name twice has no effect.
+    pub fn watch(self: &Arc<Self>, name: &str, chain: &Arc<Chain>) {
+        if !self.watched.lock().unwrap().insert(name.to_string()) {
+            return;
+        }
+        let mut outcomes = chain.subscribe();
+        let admin = Arc::downgrade(self);
+        let name = name.to_string();
+        chain.executor().spawn(Box::pin(async move {
+            while let Some(outcome) = outcomes.next().await {
+                let Some(admin) = admin.upgrade() else { break };
+                admin.record(&name, &outcome.ctx, outcome.report);
+            }
+        }));
+    }
+    /// [`Self::watch`] every chain currently in [`crate::registry`].
+    pub fn watch_registered(self: &Arc<Self>) {
+        for name in registry::chain_names() {
+            if let Some(chain) = registry::get_chain(&name) {
+                self.watch(&name, &chain);
+            }
+        }
+    }
+    /// Serve the usage of `quotas` and let it be reset (see [`crate::quota`]).
+    pub fn manage_quotas(&self, quotas: Arc<Quotas>) {
+        *self.quotas.lock().unwrap() = Some(quotas);
+    }
+    /// Today's usage of every key that used its quota.
+    pub fn quota_usage(&self) -> Result<Vec<QuotaUsage>, AdminError> {
+        Ok(self.managed_quotas()?.all_usage())
+    }
+    pub fn quota(&self, key: &str) -> Result<QuotaUsage, AdminError> {
+        Ok(self.managed_quotas()?.usage(key))
+    }
+    /// Forget what `key` used; `false` if it had used nothing.
+    pub fn reset_quota(&self, key: &str) -> Result<bool, AdminError> {
+        let reset = self.managed_quotas()?.reset(key);
+        tracing::info!(key, reset, "quota reset");
+        Ok(reset)
+    }
+    fn managed_quotas(&self) -> Result<Arc<Quotas>, AdminError> {
+        self.quotas.lock().unwrap().clone().ok_or_else(|| AdminError::InvalidInput("no quotas are managed here".to_string()))
+    }
+    /// Add a finished run of `chain` to the history, dropping the oldest beyond capacity.
+    pub fn record(&self, chain: &str, ctx: &Context, report: RunReport) {
+        let id = ctx.get::<String>(meta::REQUEST_ID).unwrap_or_else(|| self.new_id());
+        self.push(RunRecord { id, chain: chain.to_string(), finished_at_ms: now_ms(), ctx: ctx_value(ctx), report });
+    }
+
+    /// Finished runs matching `filter`, newest first.
+    pub fn runs(&self, filter: &RunFilter) -> Vec<RunRecord> {
+        self.history
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|r| filter.chain.as_ref().is_none_or(|chain| &r.chain == chain))
+            .filter(|r| !filter.failed || matches!(r.report.status, RunStatus::Failed(_)))
+            .take(filter.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect()
+    }
+    /// The latest finished run with this id.
+    pub fn run(&self, id: &str) -> Option<RunRecord> {
+        self.history.lock().unwrap().iter().find(|r| r.id == id).cloned()
+    }
+    /// Runs started by [`Self::trigger`] or [`Self::retry`] that have not finished, oldest first.
+    pub fn running(&self) -> Vec<RunningRun> {
+        let mut running: Vec<RunningRun> = self.running.lock().unwrap().values().map(|(run, _)| run.clone()).collect();
+        running.sort_by(|a, b| (a.started_at_ms, &a.id).cmp(&(b.started_at_ms, &b.id)));
+        running
+    }
+    /// Per-chain statistics over the history, sorted by chain name.
+    pub fn stats(&self) -> Vec<ChainStats> {
+        let mut by_chain: BTreeMap<String, (ChainStats, Vec<u64>)> = BTreeMap::new();
+        for record in self.history.lock().unwrap().iter() {
+            let (stats, durations) = by_chain.entry(record.chain.clone()).or_default();
+            stats.runs += 1;
+            match record.report.status {
+                RunStatus::Completed => stats.completed += 1,
+                RunStatus::Failed(_) => {
+                    stats.failed += 1;
+                    stats.last_failure_at_ms = stats.last_failure_at_ms.max(Some(record.finished_at_ms));
+                }
+                RunStatus::Cancelled => stats.cancelled += 1,
+                RunStatus::Parked { .. } | RunStatus::AwaitingEvent { .. } => stats.waiting += 1,
+            }
+            durations.push(record.report.duration.as_millis() as u64);
+        }
+        by_chain
+            .into_iter()
+            .map(|(chain, (stats, mut durations))| {
+                durations.sort_unstable();
+                let mean_ms = durations.iter().sum::<u64>() / durations.len() as u64;
+                let p95_ms = durations[(durations.len() * 95).div_ceil(100) - 1];
+                ChainStats { chain, mean_ms, p95_ms, ..stats }
+            })
+            .collect()
+    }
+    /// Registered chains, sorted by name.
+    pub fn chains(&self) -> Vec<ChainInfo> {
+        registry::chain_names()
+            .into_iter()
+            .filter_map(|name| {
+                let chain = registry::get_chain(&name)?;
+                Some(ChainInfo {
+                    version: chain.version().map(str::to_string),
+                    links: chain.link_specs().iter().map(|spec| spec.name.clone()).collect(),
+                    branches: chain.branches.iter().map(|b| (b.source, b.target)).collect(),
+                    durable: chain.checkpoint_store().is_some(),
+                    name,
+                })
+            })
+            .collect()
+    }
+
+    /// Start a durable run (`ChainGeneric::run_durable`) of the registered chain `chain` in
+    /// the background and return its generated id, which replaces any request id in `ctx`.
+    pub fn trigger(self: &Arc<Self>, chain: &str, ctx: Context) -> Result<String, AdminError> {
+        let handle = registry::get_chain(chain).ok_or_else(|| AdminError::UnknownChain(chain.to_string()))?;
+        let id = self.new_id();
+        let ctx = ctx.insert(meta::REQUEST_ID, id.clone());
+        let (run_id, run) = (id.clone(), handle.clone());
+        self.spawn(chain, &handle, &id, Box::pin(async move {
+            run.run_durable(&run_id, ctx).await;
+        }))?;
+        Ok(id)
+    }
+    /// Cancel run `id`: abort it if it was started here and is still running, and remove
+    /// its checkpoint so it is not resumed or retried. Cancelling a running run records it
+    /// as `RunStatus::Cancelled`.
+    pub async fn cancel(&self, id: &str) -> Result<(), AdminError> {
+        let aborted = self.running.lock().unwrap().remove(id);
+        if let Some((run, abort)) = &aborted {
+            abort.abort();
+            let report = RunReport::new(RunStatus::Cancelled);
+            self.push(RunRecord { id: id.to_string(), chain: run.chain.clone(), finished_at_ms: now_ms(), ctx: Value::Null, report });
+        }
+        let checkpointed = registry::chain_with_run(id).await?;
+        if let Some(store) = checkpointed.as_ref().and_then(|(_, chain)| chain.checkpoint_store()) {
+            store.remove(id).await?;
+        }
+        if aborted.is_none() && checkpointed.is_none() {
+            return Err(AdminError::UnknownRun(id.to_string()));
+        }
+        Ok(())
+    }
+    /// Re-run the checkpointed run `id` from the link named `from_step` in the background
+    /// (`ChainGeneric::retry_from_step`), merging `patch` into its context.
+    pub async fn retry(self: &Arc<Self>, id: &str, from_step: &str, patch: Option<Value>) -> Result<(), AdminError> {
+        let (name, chain) = registry::chain_with_run(id).await?.ok_or_else(|| AdminError::UnknownRun(id.to_string()))?;
+        if !chain.link_specs().iter().any(|spec| spec.name.as_deref() == Some(from_step)) {
+            return Err(AdminError::InvalidInput(format!("chain '{}' has no link named '{}'", name, from_step)));
+        }
+        let (run_id, step, run) = (id.to_string(), from_step.to_string(), chain.clone());
+        self.spawn(&name, &chain, id, Box::pin(async move {
+            if let Err(e) = run.retry_from_step(&run_id, &step, patch).await {
+                tracing::warn!(run_id = %run_id, error = %e, "retrying run failed");
+            }
+        }))
+    }
+
+    fn push(&self, record: RunRecord) {
+        let mut history = self.history.lock().unwrap();
+        history.push_front(record);
+        history.truncate(self.capacity);
+    }
+    fn new_id(&self) -> String {
+        format!("run-{}-{}", now_ms(), self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    // Run `fut` on the chain's executor as run `id`, cancellable through `Self::cancel`.
+    fn spawn(self: &Arc<Self>, name: &str, chain: &Chain, id: &str, fut: BoxFuture<'static, ()>) -> Result<(), AdminError> {
+        let (abort, registration) = AbortHandle::new_pair();
+        {
+            let mut running = self.running.lock().unwrap();
+            if running.contains_key(id) {
+                return Err(AdminError::InvalidInput(format!("run '{}' is already running", id)));
+            }
+            let run = RunningRun { id: id.to_string(), chain: name.to_string(), started_at_ms: now_ms() };
+            running.insert(id.to_string(), (run, abort));
+        }
+        let admin = Arc::downgrade(self);
+        let id = id.to_string();
+        chain.executor().spawn(Box::pin(async move {
+            let _ = Abortable::new(fut, registration).await;
+            if let Some(admin) = admin.upgrade() {
+                admin.running.lock().unwrap().remove(&id);
+            }
+        }));
+        Ok(())
+    }
+}
+
+#[cfg(feature = "http")]
+mod http {
+    use super::{Admin, AdminError, RunFilter};
+    use crate::context::{meta, Context};
+    use crate::listeners::AuthClaims;
+    use crate::policy::RolePolicy;
+    use axum::extract::{Path, Query, Request, State};
+    use axum::middleware::Next;
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde::Deserialize;
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    #[derive(Deserialize)]
+    struct RetryBody {
+        from_step: String,
+        #[serde(default)]
+        patch: Option<Value>,
+    }
+
+    fn error_response(err: AdminError) -> Response {
+        let status = match err {
+            AdminError::UnknownChain(_) | AdminError::UnknownRun(_) => StatusCode::NOT_FOUND,
+            AdminError::Forbidden(_) => StatusCode::FORBIDDEN,
+            AdminError::InvalidInput(_) => StatusCode::BAD_REQUEST,
+            AdminError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(json!({ "error": err.to_string() }))).into_response()
+    }
+
+    async fn trigger(State(admin): State<Arc<Admin>>, Path(name): Path<String>, body: Option<Json<Value>>) -> Response {
+        let mut input = body.and_then(|Json(body)| body.as_object().cloned()).unwrap_or_default();
+        meta::strip_reserved(&mut input);
+        match admin.trigger(&name, Context::from(input)) {
+            Ok(id) => (StatusCode::ACCEPTED, Json(json!({ "run_id": id }))).into_response(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    // Refuses callers whose JWT claims lack the admin role.
+    async fn require_role(State(admin): State<Arc<Admin>>, req: Request, next: Next) -> Response {
+        if let Some(AuthClaims(claims)) = req.extensions().get::<AuthClaims>() {
+            if !RolePolicy::new().roles(claims).contains(&admin.role.as_str()) {
+                return error_response(AdminError::Forbidden(format!("the admin API requires the '{}' role", admin.role)));
+            }
+        }
+        next.run(req).await
+    }
+
+    async fn run(State(admin): State<Arc<Admin>>, Path(id): Path<String>) -> Response {
+        match admin.run(&id) {
+            Some(record) => Json(record).into_response(),
+            None => error_response(AdminError::UnknownRun(id)),
+        }
+    }
+
+    async fn cancel(State(admin): State<Arc<Admin>>, Path(id): Path<String>) -> Response {
+        match admin.cancel(&id).await {
+            Ok(()) => Json(json!({ "cancelled": id })).into_response(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    async fn retry(State(admin): State<Arc<Admin>>, Path(id): Path<String>, Json(body): Json<RetryBody>) -> Response {
+        match admin.retry(&id, &body.from_step, body.patch).await {
+            Ok(()) => (StatusCode::ACCEPTED, Json(json!({ "run_id": id }))).into_response(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    async fn quota(State(admin): State<Arc<Admin>>, Path(key): Path<String>) -> Response {
+        match admin.quota(&key) {
+            Ok(usage) => Json(usage).into_response(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    async fn reset_quota(State(admin): State<Arc<Admin>>, Path(key): Path<String>) -> Response {
+        match admin.reset_quota(&key) {
+            Ok(reset) => Json(json!({ "key": key, "reset": reset })).into_response(),
+            Err(e) => error_response(e),
+        }
+    }
+
+    impl Admin {
+        /// The admin routes (see the [module docs](super)).
+        pub fn router(self: Arc<Self>) -> Router {
+            let router = Router::new();
+            #[cfg(feature = "dashboard")]
+            let router = router.route("/admin/dashboard", get(|| async { axum::response::Html(super::dashboard::DASHBOARD_HTML) }));
+            router
+                .route("/admin/chains", get(|State(a): State<Arc<Admin>>| async move { Json(a.chains()) }))
+                .route("/admin/chains/{name}/runs", post(trigger))
+                .route(
+                    "/admin/runs",
+                    get(|State(a): State<Arc<Admin>>, Query(filter): Query<RunFilter>| async move { Json(a.runs(&filter)) }),
+                )
+                .route("/admin/running", get(|State(a): State<Arc<Admin>>| async move { Json(a.running()) }))
+                .route("/admin/stats", get(|State(a): State<Arc<Admin>>| async move { Json(a.stats()) }))
+                .route("/admin/runs/{id}", get(run))
+                .route("/admin/runs/{id}/cancel", post(cancel))
+                .route("/admin/runs/{id}/retry", post(retry))
+                .route(
+                    "/admin/quotas",
+                    get(|State(a): State<Arc<Admin>>| async move { a.quota_usage().map(Json).map_err(error_response) }),
+                )
+                .route("/admin/quotas/{key}", get(quota))
+                .route("/admin/quotas/{key}/reset", post(reset_quota))
+                .layer(axum::middleware::from_fn_with_state(self.clone(), require_role))
+                .with_state(self)
+        }
+        /// Serve only the admin routes on `addr`, apart from any `HttpListener`.
+        pub async fn serve(self: Arc<Self>, addr: &str) -> std::io::Result<()> {
+            let listener = tokio::net::TcpListener::bind(addr).await?;
+            axum::serve(listener, self.router().into_make_service()).await
+        }
+    }
+}
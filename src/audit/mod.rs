@@ -0,0 +1,223 @@
+//! Tamper-evident audit log for modulink-rust
+//! One [`AuditRecord`] per run: who triggered it (auth subject, tenant, request id), which
+//! chain and version ran, a hash of the input, the decisions links recorded with
+//! `Context::record_decision`, run annotations (`ctx_tools::annotate`), and the final
+//! status. Each record carries the hash of the
+//! previous one, so editing, dropping, or reordering records breaks [`verify`].
+//!
+//! Records go to any `BaseSink<AuditRecord>`: `FileSink` for JSON lines, or a custom sink
+//! writing to a database.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::audit::{verify, AuditLog};
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::sinks::ChannelSink;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let (tx, mut rx) = futures::channel::mpsc::channel(8);
+//! let log = Arc::new(AuditLog::new(Arc::new(ChannelSink::new(tx))));
+//! let mut chain = Chain::new();
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.record_decision("approved") })));
+//! chain.use_middleware(Arc::new(log.middleware("refunds").with_version("1.2.0")));
+//! chain.run(Context::new()).await;
+//! chain.run(Context::new()).await;
+//! let records = vec![rx.try_next().unwrap().unwrap(), rx.try_next().unwrap().unwrap()];
+//! assert_eq!(records[1].decisions, vec!["approved"]);
+//! assert!(verify(&records).is_ok());
+//! # });
+//! ```
+
+use crate::chains::{RunReport, RunStatus};
+use crate::context::{meta, Context, ContextMutable};
+use crate::middleware::Middleware;
+use crate::sinks::SinkObj;
+use futures::lock::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// `prev_hash` of the first record in a log.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// One audited run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub seq: u64,
+    pub timestamp_ms: u64,
+    pub chain: String,
+    pub chain_version: Option<String>,
+    /// `sub` claim of the run's auth claims.
+    pub subject: Option<String>,
+    pub tenant: Option<String>,
+    pub request_id: Option<String>,
+    /// SHA-256 of the input context as canonical (key-sorted) JSON.
+    pub input_hash: String,
+    pub decisions: Vec<String>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, Value>,
+    pub status: RunStatus,
+    pub prev_hash: String,
+    /// SHA-256 over this record (with `hash` empty), which includes `prev_hash`.
+    pub hash: String,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes).iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// SHA-256 of a context map as canonical JSON; `serde_json` objects keep keys sorted.
+pub fn hash_context(map: &HashMap<String, Value>) -> String {
+    let canonical: serde_json::Map<String, Value> = map.iter().map(|(k, v)| (k.clone(), v.clone())).collect();
+    sha256_hex(&serde_json::to_vec(&Value::Object(canonical)).unwrap_or_default())
+}
+
+impl AuditRecord {
+    /// The hash this record should carry.
+    pub fn compute_hash(&self) -> String {
+        let unsigned = AuditRecord { hash: String::new(), ..self.clone() };
+        sha256_hex(&serde_json::to_vec(&unsigned).unwrap_or_default())
+    }
+}
+
+/// Check that `records` form an unbroken, unmodified chain. Returns the index of the first
+/// record that fails. The first record may continue an earlier segment (any `prev_hash`).
+pub fn verify(records: &[AuditRecord]) -> Result<(), usize> {
+    for (i, record) in records.iter().enumerate() {
+        if record.hash != record.compute_hash() {
+            return Err(i);
+        }
+        if i > 0 {
+            let prev = &records[i - 1];
+            if record.prev_hash != prev.hash || record.seq != prev.seq + 1 {
+                return Err(i);
+            }
+        }
+    }
+    Ok(())
+}
+
+struct Head {
+    seq: u64,
+    hash: String,
+}
+
+/// Append-only, hash-chained log of audit records, shared by every audited chain.
+pub struct AuditLog {
+    sink: SinkObj<AuditRecord>,
+    head: Mutex<Head>,
+}
+
+impl AuditLog {
+    /// Start a new log.
+    pub fn new(sink: SinkObj<AuditRecord>) -> Self {
+        AuditLog { sink, head: Mutex::new(Head { seq: 0, hash: GENESIS_HASH.to_string() }) }
+    }
+    /// Continue a log whose latest record is `last` (e.g. after a restart).
+    pub fn resume(sink: SinkObj<AuditRecord>, last: &AuditRecord) -> Self {
+        AuditLog { sink, head: Mutex::new(Head { seq: last.seq + 1, hash: last.hash.clone() }) }
+    }
+    /// Number, link, and hash `record`, then write it. The log only advances when the
+    /// sink accepts the record, so a failed write leaves no gap in the chain.
+    pub async fn append(&self, record: AuditRecord) -> std::io::Result<AuditRecord> {
+        let mut head = self.head.lock().await;
+        let mut record = AuditRecord { seq: head.seq, prev_hash: head.hash.clone(), ..record };
+        record.hash = record.compute_hash();
+        self.sink.deliver(&record).await?;
+        head.seq += 1;
+        head.hash = record.hash.clone();
+        Ok(record)
+    }
+    /// Middleware recording every run of `chain` in this log.
+    pub fn middleware(self: &Arc<Self>, chain: impl Into<String>) -> AuditMiddleware {
+        AuditMiddleware { log: self.clone(), chain: chain.into(), version: None }
+    }
+}
+
+/// Hashes each run's input at run start (kept under `_input_hash`), dropping any
+/// `_decisions` it came with, and appends an [`AuditRecord`] when the run ends. Write failures are logged, never fail the run.
+pub struct AuditMiddleware {
+    pub log: Arc<AuditLog>,
+    pub chain: String,
+    pub version: Option<String>,
+}
+
+impl AuditMiddleware {
+    pub fn with_version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+
+    fn record(&self, map: &HashMap<String, Value>, report: &RunReport) -> AuditRecord {
+        let text = |key: &str| map.get(key).and_then(Value::as_str).map(str::to_string);
+        AuditRecord {
+            seq: 0,
+            timestamp_ms: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+            chain: self.chain.clone(),
+            chain_version: self.version.clone().or_else(|| report.version.clone()),
+            subject: map.get(meta::AUTH).and_then(|c| c.get("sub")).and_then(Value::as_str).map(str::to_string),
+            tenant: text(meta::TENANT),
+            request_id: text(meta::REQUEST_ID),
+            input_hash: text(meta::INPUT_HASH).unwrap_or_default(),
+            decisions: map.get(meta::DECISIONS)
+                .and_then(Value::as_array)
+                .map(|d| d.iter().filter_map(Value::as_str).map(str::to_string).collect())
+                .unwrap_or_default(),
+            annotations: report.annotations.clone(),
+            status: report.status.clone(),
+            prev_hash: String::new(),
+            hash: String::new(),
+        }
+    }
+
+    async fn write(&self, record: AuditRecord) {
+        if let Err(e) = self.log.append(record).await {
+            tracing::warn!(chain = %self.chain, error = %e, "audit record write failed");
+        }
+    }
+}
+
+fn stamp_input(map: &mut HashMap<String, Value>) {
+    // Only decisions recorded by this run's links are audited, never ones in its input
+    map.remove(meta::DECISIONS);
+    map.remove(meta::INPUT_HASH);
+    let hash = hash_context(map);
+    map.insert(meta::INPUT_HASH.to_string(), hash.into());
+}
+
+impl Middleware<Context> for AuditMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: Context) -> Pin<Box<dyn Future<Output = Context> + Send + 'a>>
+    where
+        Context: 'a,
+    {
+        let mut ctx = ctx;
+        stamp_input(&mut ctx.0);
+        Box::pin(async move { ctx })
+    }
+    fn on_run_end<'a>(&'a self, ctx: &'a Context, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        let record = self.record(&ctx.0, report);
+        Box::pin(self.write(record))
+    }
+}
+
+impl Middleware<ContextMutable> for AuditMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: ContextMutable) -> Pin<Box<dyn Future<Output = ContextMutable> + Send + 'a>>
+    where
+        ContextMutable: 'a,
+    {
+        let mut ctx = ctx;
+        stamp_input(&mut ctx.0);
+        Box::pin(async move { ctx })
+    }
+    fn on_run_end<'a>(&'a self, ctx: &'a ContextMutable, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        let record = self.record(&ctx.0, report);
+        Box::pin(self.write(record))
+    }
+}
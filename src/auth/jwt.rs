@@ -0,0 +1,163 @@
+//! JWT validation against static keys or a JWKS.
+//!
+//! Invalid, malformed, or expired tokens fail with `Unauthorized` (401); well-formed tokens
+//! for the wrong audience or issuer fail with `Forbidden` (403).
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::auth::JwtValidator;
+//!
+//! let validator = JwtValidator::hs256(b"secret").with_audience(&["orders-api"]);
+//! assert!(validator.validate("not-a-jwt").is_err());
+//! ```
+
+use crate::auth::bearer_token;
+use crate::chains::RunError;
+use crate::context::{meta, Context, ContextMutable};
+use crate::ctx_tools;
+use crate::middleware::Middleware;
+use jsonwebtoken::errors::ErrorKind as JwtErrorKind;
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, RwLock};
+
+enum JwtKeys {
+    Static(DecodingKey),
+    Jwks(JwkSet),
+}
+
+/// Validates JWTs and returns their claims.
+pub struct JwtValidator {
+    keys: RwLock<JwtKeys>,
+    validation: Validation,
+}
+
+fn reject(err: jsonwebtoken::errors::Error) -> RunError {
+    match err.kind() {
+        JwtErrorKind::InvalidAudience | JwtErrorKind::InvalidIssuer | JwtErrorKind::InvalidSubject => {
+            RunError::forbidden(format!("token rejected: {}", err))
+        }
+        _ => RunError::unauthorized(format!("invalid token: {}", err)),
+    }
+}
+
+impl JwtValidator {
+    fn with_keys(keys: JwtKeys, validation: Validation) -> Self {
+        JwtValidator { keys: RwLock::new(keys), validation }
+    }
+    /// Validate HS256 tokens signed with a shared secret.
+    pub fn hs256(secret: &[u8]) -> Self {
+        Self::with_keys(JwtKeys::Static(DecodingKey::from_secret(secret)), Validation::new(Algorithm::HS256))
+    }
+    /// Validate RS256 tokens against a PEM-encoded RSA public key.
+    pub fn rs256_pem(pem: &[u8]) -> Result<Self, RunError> {
+        let key = DecodingKey::from_rsa_pem(pem).map_err(|e| RunError::internal(format!("invalid RSA key: {}", e)))?;
+        Ok(Self::with_keys(JwtKeys::Static(key), Validation::new(Algorithm::RS256)))
+    }
+    /// Validate tokens against a JWKS, picking the key by the token's `kid`.
+    pub fn from_jwks(jwks: JwkSet) -> Self {
+        Self::with_keys(JwtKeys::Jwks(jwks), Validation::new(Algorithm::RS256))
+    }
+    pub fn from_jwks_json(json: &str) -> Result<Self, RunError> {
+        let jwks: JwkSet = serde_json::from_str(json).map_err(|e| RunError::internal(format!("invalid JWKS: {}", e)))?;
+        Ok(Self::from_jwks(jwks))
+    }
+    /// Require the `aud` claim to contain one of `audience`.
+    pub fn with_audience(mut self, audience: &[&str]) -> Self {
+        self.validation.set_audience(audience);
+        self
+    }
+    /// Require the `iss` claim to be one of `issuer`.
+    pub fn with_issuer(mut self, issuer: &[&str]) -> Self {
+        self.validation.set_issuer(issuer);
+        self
+    }
+    /// Clock skew tolerated when checking `exp`/`nbf`, in seconds.
+    pub fn with_leeway(mut self, seconds: u64) -> Self {
+        self.validation.leeway = seconds;
+        self
+    }
+    /// Replace the JWKS (e.g. after a key rotation).
+    pub fn set_jwks(&self, jwks: JwkSet) {
+        *self.keys.write().unwrap() = JwtKeys::Jwks(jwks);
+    }
+
+    /// Validate `token` and return its claims.
+    pub fn validate(&self, token: &str) -> Result<Value, RunError> {
+        let header = decode_header(token).map_err(reject)?;
+        let keys = self.keys.read().unwrap();
+        let (key, validation) = match &*keys {
+            JwtKeys::Static(key) => (key.clone(), self.validation.clone()),
+            JwtKeys::Jwks(jwks) => {
+                let jwk = match header.kid.as_deref() {
+                    Some(kid) => jwks.find(kid),
+                    None if jwks.keys.len() == 1 => jwks.keys.first(),
+                    None => None,
+                }
+                .ok_or_else(|| RunError::unauthorized("no JWKS key matches the token"))?;
+                let key = DecodingKey::from_jwk(jwk).map_err(reject)?;
+                let mut validation = self.validation.clone();
+                validation.algorithms = vec![header.alg];
+                (key, validation)
+            }
+        };
+        decode::<Value>(token, &key, &validation).map(|data| data.claims).map_err(reject)
+    }
+
+    /// Validate the token in an `Authorization: Bearer` header value.
+    pub fn validate_header(&self, header: Option<&str>) -> Result<Value, RunError> {
+        self.validate(bearer_token(header)?)
+    }
+}
+
+/// Middleware that validates the forwarded `Authorization` value (`_authorization`) at run
+/// start, replaces it with the token's claims under `_auth`, and fails the run with
+/// `Unauthorized`/`Forbidden` when the token is missing, invalid, or expired.
+pub struct JwtMiddleware {
+    pub validator: Arc<JwtValidator>,
+}
+
+impl JwtMiddleware {
+    pub fn new(validator: Arc<JwtValidator>) -> Self {
+        JwtMiddleware { validator }
+    }
+
+    fn authorize(&self, map: &mut HashMap<String, Value>) {
+        let header = map.remove(meta::AUTHORIZATION);
+        map.remove(meta::AUTH);
+        match self.validator.validate_header(header.as_ref().and_then(Value::as_str)) {
+            Ok(claims) => {
+                map.insert(meta::AUTH.to_string(), claims);
+            }
+            Err(err) => {
+                ctx_tools::fail_run(err);
+            }
+        }
+    }
+}
+
+impl Middleware<Context> for JwtMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: Context) -> Pin<Box<dyn Future<Output = Context> + Send + 'a>>
+    where
+        Context: 'a,
+    {
+        let mut ctx = ctx;
+        self.authorize(&mut ctx.0);
+        Box::pin(async move { ctx })
+    }
+}
+
+impl Middleware<ContextMutable> for JwtMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: ContextMutable) -> Pin<Box<dyn Future<Output = ContextMutable> + Send + 'a>>
+    where
+        ContextMutable: 'a,
+    {
+        let mut ctx = ctx;
+        self.authorize(&mut ctx.0);
+        Box::pin(async move { ctx })
+    }
+}
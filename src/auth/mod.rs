@@ -0,0 +1,24 @@
+//! Authentication for modulink-rust
+//! Token validation shared by listeners (validate before the run) and middleware
+//! (validate a forwarded `Authorization` value at run start). Validated claims are
+//! placed in context metadata under `context::meta::AUTH`.
+
+#[cfg(feature = "jwt")]
+pub mod jwt;
+#[cfg(feature = "jwt")]
+pub use jwt::{JwtMiddleware, JwtValidator};
+#[cfg(feature = "oidc")]
+pub mod oidc;
+#[cfg(feature = "oidc")]
+pub use oidc::OidcProvider;
+
+use crate::chains::RunError;
+
+/// Extract the token from an `Authorization: Bearer <token>` header value.
+pub fn bearer_token(header: Option<&str>) -> Result<&str, RunError> {
+    let header = header.ok_or_else(|| RunError::unauthorized("missing Authorization header"))?;
+    match header.split_once(' ') {
+        Some((scheme, token)) if scheme.eq_ignore_ascii_case("bearer") && !token.trim().is_empty() => Ok(token.trim()),
+        _ => Err(RunError::unauthorized("Authorization header is not a bearer token")),
+    }
+}
@@ -0,0 +1,102 @@
+//! OpenID Connect discovery: build a [`JwtValidator`] from an issuer URL.
+//!
+//! `OidcProvider::discover` reads `<issuer>/.well-known/openid-configuration`, fetches the
+//! provider's JWKS, and requires `iss` to match the issuer and `aud` to contain one of the
+//! given audiences (expiry is always checked). Keys rotate on the provider side, so call
+//! [`OidcProvider::refresh_keys`] periodically or let [`OidcProvider::spawn_refresh`] do it.
+//!
+//! Example:
+//! ```rust,no_run
+//! use modulink_rs::auth::OidcProvider;
+//! use modulink_rs::listeners::HttpListener;
+//! use modulink_rs::runtime::default_executor;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # async fn example(chain: Arc<modulink_rs::Chain>) -> Result<(), modulink_rs::chains::RunError> {
+//! let provider = Arc::new(OidcProvider::discover("https://login.example.com", &["orders-api"]).await?);
+//! let _refresh = provider.spawn_refresh(default_executor(), Duration::from_secs(3600));
+//! let listener = HttpListener::for_chain(chain, "0.0.0.0:8080").with_jwt(provider.validator());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::auth::JwtValidator;
+use crate::chains::RunError;
+use crate::runtime::{self, ExecutorObj, JoinHandle};
+use jsonwebtoken::jwk::JwkSet;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Deserialize)]
+struct Discovery {
+    issuer: String,
+    jwks_uri: String,
+}
+
+/// An OIDC issuer and the validator kept in sync with its published keys.
+pub struct OidcProvider {
+    pub issuer: String,
+    pub jwks_uri: String,
+    validator: Arc<JwtValidator>,
+    client: reqwest::Client,
+}
+
+async fn get_json<R: serde::de::DeserializeOwned>(client: &reqwest::Client, url: &str) -> Result<R, RunError> {
+    let fetch_err = |e: reqwest::Error| RunError::internal(format!("fetching {} failed: {}", url, e));
+    let resp = client.get(url).send().await.and_then(|r| r.error_for_status()).map_err(fetch_err)?;
+    resp.json().await.map_err(fetch_err)
+}
+
+impl OidcProvider {
+    /// Discover `issuer`'s configuration and keys; tokens must carry one of `audience`.
+    pub async fn discover(issuer: &str, audience: &[&str]) -> Result<Self, RunError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/.well-known/openid-configuration", issuer.trim_end_matches('/'));
+        let discovery: Discovery = get_json(&client, &url).await?;
+        if discovery.issuer.trim_end_matches('/') != issuer.trim_end_matches('/') {
+            return Err(RunError::internal(format!(
+                "discovery document issuer '{}' does not match '{}'",
+                discovery.issuer, issuer
+            )));
+        }
+        let jwks: JwkSet = get_json(&client, &discovery.jwks_uri).await?;
+        let validator = JwtValidator::from_jwks(jwks)
+            .with_issuer(&[discovery.issuer.as_str()])
+            .with_audience(audience);
+        Ok(OidcProvider {
+            issuer: discovery.issuer,
+            jwks_uri: discovery.jwks_uri,
+            validator: Arc::new(validator),
+            client,
+        })
+    }
+
+    /// Validator for listeners and middleware; picks up refreshed keys automatically.
+    pub fn validator(&self) -> Arc<JwtValidator> {
+        self.validator.clone()
+    }
+
+    /// Re-fetch the JWKS and swap it into the validator.
+    pub async fn refresh_keys(&self) -> Result<(), RunError> {
+        let jwks: JwkSet = get_json(&self.client, &self.jwks_uri).await?;
+        self.validator.set_jwks(jwks);
+        Ok(())
+    }
+
+    /// Refresh the keys every `every` on `exec` until the returned handle is aborted.
+    /// Failed refreshes are logged and the previous keys stay in use.
+    pub fn spawn_refresh(self: &Arc<Self>, exec: ExecutorObj, every: Duration) -> JoinHandle<()> {
+        let provider = self.clone();
+        let timer = exec.clone();
+        runtime::spawn(exec.as_ref(), async move {
+            loop {
+                timer.sleep(every).await;
+                if let Err(err) = provider.refresh_keys().await {
+                    tracing::warn!(issuer = %provider.issuer, error = %err, "JWKS refresh failed");
+                }
+            }
+        })
+    }
+}
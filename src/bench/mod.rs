@@ -0,0 +1,129 @@
+//! Benchmark scenarios for the chain runtime.
+//!
+//! The criterion suite in `benches/chain.rs` runs these scenarios; they live here so other
+//! harnesses (an application's own benches, a profiler, another async runtime) can measure
+//! exactly the same workloads. Each [`Scenario`] is a chain plus the input it runs with:
+//!
+//! | Group               | What it measures                                           |
+//! |---------------------|------------------------------------------------------------|
+//! | `chain/links`       | fixed cost per link: chains of 1, 10, and 100 no-op links  |
+//! | `chain/branching`   | a link looping back to itself 10 and 100 times             |
+//! | `chain/middleware`  | 10 links with 0, 1, and 5 no-op middleware                 |
+//! | `chain/allocations` | heap allocations per run of 10 links with extra middleware |
+//! | `context/insert`    | clone plus insert, as a link does, on 10, 100, 1000 keys   |
+//! | `context/get`       | typed get on contexts of 10, 100, 1000 keys                |
+//!
+//! The `context/*` groups bench [`context_with_keys`] directly rather than a chain.
+//! `chain/allocations` counts allocations instead of timing them, once with the chain's
+//! pool of per-run state and once without (`ChainGeneric::set_pool_capacity(0)`).
+//!
+//! Running the suite and gating on regressions:
+//!
+//! ```text
+//! cargo bench --bench chain -- --save-baseline main   # on the base branch
+//! cargo bench --bench chain -- --baseline main        # on the change
+//! ```
+//!
+//! Criterion reports every scenario whose time moved outside its noise threshold as
+//! "Performance has regressed". A change touching the run loop, `Context`, or the link and
+//! middleware signatures should show no regression there, or explain it.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::bench;
+//! use modulink_rs::chains::RunStatus;
+//!
+//! # futures::executor::block_on(async {
+//! for scenario in bench::scenarios() {
+//!     let (_, report) = scenario.chain.run_with_report(scenario.input.clone()).await;
+//!     assert_eq!(report.status, RunStatus::Completed, "{}", scenario.name);
+//! }
+//! # });
+//! ```
+
+use crate::chains::Chain;
+use crate::context::Context;
+use crate::links::Link;
+use crate::middleware::Middleware;
+use std::sync::Arc;
+
+/// Context sizes the `context/*` benchmarks use.
+pub const CONTEXT_SIZES: [usize; 3] = [10, 100, 1000];
+
+/// A chain and the input it is benchmarked with.
+#[derive(Clone)]
+pub struct Scenario {
+    /// `<group>/<parameter>`, e.g. `chain/links/10`.
+    pub name: String,
+    pub chain: Arc<Chain>,
+    pub input: Context,
+}
+
+impl Scenario {
+    fn new(group: &str, parameter: usize, chain: Chain, input: Context) -> Self {
+        Scenario { name: format!("{}/{}", group, parameter), chain: Arc::new(chain), input }
+    }
+    /// The criterion group of the scenario (its name without the parameter).
+    pub fn group(&self) -> &str {
+        self.name.rsplit_once('/').map_or(&self.name, |(group, _)| group)
+    }
+}
+
+/// Every chain scenario, in table order (see the [module docs](self)).
+pub fn scenarios() -> Vec<Scenario> {
+    let mut all = Vec::new();
+    for links in [1, 10, 100] {
+        all.push(Scenario::new("chain/links", links, linear_chain(links), Context::new()));
+    }
+    for loops in [10, 100] {
+        all.push(Scenario::new("chain/branching", loops, looping_chain(), Context::new().insert("loops", loops)));
+    }
+    for middleware in [0, 1, 5] {
+        all.push(Scenario::new("chain/middleware", middleware, middleware_chain(10, middleware), Context::new()));
+    }
+    all
+}
+
+/// A link returning its context unchanged.
+pub fn noop_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+/// `links` no-op links in a row.
+pub fn linear_chain(links: usize) -> Chain {
+    let mut chain = Chain::new();
+    for _ in 0..links {
+        chain.add_link(noop_link());
+    }
+    chain
+}
+
+/// One link counting up in `"count"`, branching back to itself until it reaches `"loops"`.
+pub fn looping_chain() -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        let count = ctx.get::<usize>("count").unwrap_or(0);
+        ctx.insert("count", count + 1)
+    })));
+    chain.connect(0, 0, |ctx: &Context| ctx.get::<usize>("count") < ctx.get::<usize>("loops"));
+    chain
+}
+
+/// Middleware whose hooks do nothing, to measure the cost of calling them.
+pub struct NoopMiddleware;
+
+impl<T: Send + Sync> Middleware<T> for NoopMiddleware {}
+
+/// `links` no-op links with `middleware` no-op middleware.
+pub fn middleware_chain(links: usize, middleware: usize) -> Chain {
+    let mut chain = linear_chain(links);
+    for _ in 0..middleware {
+        chain.use_middleware(Arc::new(NoopMiddleware));
+    }
+    chain
+}
+
+/// A context with `keys` integer fields named `key0`, `key1`, ...
+pub fn context_with_keys(keys: usize) -> Context {
+    (0..keys).fold(Context::new(), |ctx, i| ctx.insert(format!("key{}", i), i))
+}
@@ -0,0 +1,145 @@
+//! Booting an app from a definition file.
+//!
+//! [`from_file`] reads an `AppDefinition` (see `definitions::app`), builds its chains,
+//! resolves the chain each listener runs (one from the file, else one registered with
+//! `registry::register_chain`), and constructs the listeners with their auth and limits.
+//! [`App::run`] then starts every listener and returns when all have stopped, or as soon
+//! as one fails.
+//!
+//! The file may reference environment variables (`${PORT:-8080}`) and secrets
+//! (`secret://jwt-key`); see `definitions::interpolate`. Links referenced by the chains
+//! must be registered before booting, as for any definition.
+//!
+//! Example:
+//! ```rust,no_run
+//! # async fn boot() -> Result<(), Box<dyn std::error::Error>> {
+//! let app = modulink_rs::boot::from_file("app.yaml")?;
+//! app.run().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::chains::Chain;
+use crate::definitions::{AppDefinition, DefinitionError, ListenerDefinition, WasmHost};
+use crate::listeners::ListenerAsync;
+use crate::registry;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Chains and listeners built from an `AppDefinition`.
+pub struct App {
+    chains: BTreeMap<String, Arc<Chain>>,
+    listeners: Vec<Box<dyn ListenerAsync>>,
+}
+
+/// Read and build the app defined in `path` (`.json`, `.yaml`/`.yml`, or `.toml`).
+pub fn from_file(path: impl AsRef<Path>) -> Result<App, DefinitionError> {
+    App::build(&AppDefinition::from_file(path)?, None)
+}
+
+impl App {
+    /// Build the chains and listeners of `def`, instantiating WASM links with `wasm`.
+    pub fn build(def: &AppDefinition, wasm: Option<&dyn WasmHost>) -> Result<Self, DefinitionError> {
+        let mut chains = BTreeMap::new();
+        for chain_def in &def.chains {
+            let mut chain = chain_def.build(wasm)?;
+            chain.set_name(chain_def.name.clone());
+            chains.insert(chain_def.name.clone(), Arc::new(chain));
+        }
+        let listeners = def
+            .listeners
+            .iter()
+            .map(|listener| {
+                let name = listener.chain();
+                let chain = chains.get(name).cloned().or_else(|| registry::get_chain(name));
+                build_listener(listener, chain.ok_or_else(|| DefinitionError::UnknownChain(name.to_string()))?)
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(App { chains, listeners })
+    }
+    /// A chain defined in the file.
+    pub fn chain(&self, name: &str) -> Option<Arc<Chain>> {
+        self.chains.get(name).cloned()
+    }
+    pub fn listeners(&self) -> &[Box<dyn ListenerAsync>] {
+        &self.listeners
+    }
+    /// Start every listener; returns once all have stopped, or with the first error.
+    pub async fn run(&self) -> std::io::Result<()> {
+        futures::future::try_join_all(self.listeners.iter().map(|listener| listener.start())).await?;
+        Ok(())
+    }
+}
+
+#[cfg(not(all(feature = "http", feature = "jwt")))]
+fn needs_feature(what: &str, feature: &str) -> DefinitionError {
+    DefinitionError::Listener(format!("{} need the `{}` feature", what, feature))
+}
+
+fn build_listener(def: &ListenerDefinition, chain: Arc<Chain>) -> Result<Box<dyn ListenerAsync>, DefinitionError> {
+    match def {
+        #[cfg(feature = "http")]
+        ListenerDefinition::Http { address, auth, limits, .. } => {
+            use crate::chains::RateLimiter;
+            use crate::listeners::HttpListener;
+            address
+                .parse::<std::net::SocketAddr>()
+                .map_err(|e| DefinitionError::Listener(format!("address '{}': {}", address, e)))?;
+            let mut listener = HttpListener::for_chain(chain, address.clone());
+            listener.options.forward_authorization = auth.forward_authorization;
+            listener.options.max_body_bytes = limits.max_body_bytes;
+            if let Some(rate) = limits.rate_per_second {
+                let mut limiter = RateLimiter::per_second(rate);
+                if let Some(burst) = limits.burst {
+                    limiter = limiter.with_burst(burst);
+                }
+                listener = listener.with_rate_limit(Arc::new(limiter));
+            }
+            if let Some(jwt) = &auth.jwt {
+                #[cfg(feature = "jwt")]
+                {
+                    listener = listener.with_jwt(Arc::new(jwt_validator(jwt)?));
+                }
+                #[cfg(not(feature = "jwt"))]
+                {
+                    let _ = jwt;
+                    return Err(needs_feature("JWT auth settings", "jwt"));
+                }
+            }
+            Ok(Box::new(listener))
+        }
+        #[cfg(not(feature = "http"))]
+        ListenerDefinition::Http { .. } => {
+            let _ = chain;
+            Err(needs_feature("HTTP listeners", "http"))
+        }
+        #[cfg(feature = "tokio")]
+        ListenerDefinition::Stdin { .. } => Ok(Box::new(crate::listeners::StdinListener { handler: Chain::as_link(chain) })),
+        #[cfg(not(feature = "tokio"))]
+        ListenerDefinition::Stdin { .. } => {
+            let _ = chain;
+            Err(needs_feature("stdin listeners", "tokio"))
+        }
+    }
+}
+
+#[cfg(all(feature = "http", feature = "jwt"))]
+fn jwt_validator(def: &crate::definitions::JwtDefinition) -> Result<crate::auth::JwtValidator, DefinitionError> {
+    use crate::auth::JwtValidator;
+    let mut validator = match (&def.hs256_secret, &def.rs256_pem) {
+        (Some(secret), None) => JwtValidator::hs256(secret.as_bytes()),
+        (None, Some(pem)) => JwtValidator::rs256_pem(pem.as_bytes()).map_err(|e| DefinitionError::Listener(e.message))?,
+        _ => return Err(DefinitionError::Listener("jwt needs exactly one of hs256_secret and rs256_pem".to_string())),
+    };
+    if !def.audience.is_empty() {
+        validator = validator.with_audience(&def.audience.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    if !def.issuer.is_empty() {
+        validator = validator.with_issuer(&def.issuer.iter().map(String::as_str).collect::<Vec<_>>());
+    }
+    if let Some(seconds) = def.leeway {
+        validator = validator.with_leeway(seconds);
+    }
+    Ok(validator)
+}
@@ -0,0 +1,109 @@
+use super::{CacheCounters, CacheStats, CacheStore};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+struct Entry {
+    value: Value,
+    expires: Option<Instant>,
+    // Position in `Lru::order`; larger is more recently used
+    tick: u64,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, Entry>,
+    order: BTreeMap<u64, String>,
+    tick: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: &str) {
+        self.tick += 1;
+        let tick = self.tick;
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.order.remove(&entry.tick);
+            entry.tick = tick;
+            self.order.insert(tick, key.to_string());
+        }
+    }
+    fn remove(&mut self, key: &str) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.order.remove(&entry.tick);
+        }
+    }
+}
+
+/// In-process LRU cache holding at most `capacity` entries.
+pub struct MemoryCache {
+    capacity: usize,
+    lru: Mutex<Lru>,
+    counters: CacheCounters,
+}
+
+impl MemoryCache {
+    pub fn new(capacity: usize) -> Self {
+        MemoryCache { capacity, lru: Mutex::default(), counters: CacheCounters::default() }
+    }
+    pub fn len(&self) -> usize {
+        self.lru.lock().unwrap().entries.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl CacheStore for MemoryCache {
+    async fn get(&self, key: &str) -> std::io::Result<Option<Value>> {
+        let mut lru = self.lru.lock().unwrap();
+        let expired = match lru.entries.get(key) {
+            Some(entry) => entry.expires.is_some_and(|at| at <= Instant::now()),
+            None => {
+                self.counters.lookup(false);
+                return Ok(None);
+            }
+        };
+        if expired {
+            lru.remove(key);
+            self.counters.lookup(false);
+            return Ok(None);
+        }
+        lru.touch(key);
+        self.counters.lookup(true);
+        Ok(lru.entries.get(key).map(|entry| entry.value.clone()))
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> std::io::Result<()> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let mut lru = self.lru.lock().unwrap();
+        lru.remove(key);
+        while lru.entries.len() >= self.capacity {
+            let Some((_, oldest)) = lru.order.pop_first() else { break };
+            lru.entries.remove(&oldest);
+            self.counters.evicted();
+        }
+        let expires = ttl.map(|ttl| Instant::now() + ttl);
+        lru.entries.insert(key.to_string(), Entry { value, expires, tick: 0 });
+        lru.touch(key);
+        self.counters.set();
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> std::io::Result<()> {
+        self.lru.lock().unwrap().remove(key);
+        Ok(())
+    }
+
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.counters.snapshot()
+    }
+}
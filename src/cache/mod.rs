@@ -0,0 +1,85 @@
+//! Cache stores: one key/value interface with TTLs for every feature that caches results.
+//!
+//! A [`CacheStore`] holds JSON values by key. [`MemoryCache`] is an in-process LRU;
+//! [`RedisCache`] shares entries between processes through a [`RedisClient`]. Stores keep
+//! [`CacheStats`] so hit rates look the same whichever backend is configured.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::cache::{CacheStore, MemoryCache};
+//! use std::time::Duration;
+//!
+//! # futures::executor::block_on(async {
+//! let cache = MemoryCache::new(1_000);
+//! cache.set("rate:EUR", serde_json::json!(1.08), Some(Duration::from_secs(60))).await.unwrap();
+//! assert_eq!(cache.get("rate:EUR").await.unwrap(), Some(serde_json::json!(1.08)));
+//! assert_eq!(cache.stats().hits, 1);
+//! # });
+//! ```
+
+pub mod memory;
+pub mod redis;
+pub use memory::MemoryCache;
+pub use redis::{RedisCache, RedisClient};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[async_trait]
+pub trait CacheStore: Send + Sync {
+    /// The value stored under `key`, unless missing or expired.
+    async fn get(&self, key: &str) -> std::io::Result<Option<Value>>;
+    /// Store `value` under `key`, expiring after `ttl` if given.
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> std::io::Result<()>;
+    async fn remove(&self, key: &str) -> std::io::Result<()>;
+    /// Store name/type
+    fn name(&self) -> &'static str;
+    fn stats(&self) -> CacheStats;
+}
+
+pub type CacheStoreObj = Arc<dyn CacheStore>;
+
+/// Counters reported by a cache store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub sets: u64,
+    /// Entries dropped to make room (not expirations).
+    pub evictions: u64,
+}
+
+/// Thread-safe counters behind [`CacheStats`], for use by store implementations.
+#[derive(Debug, Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    sets: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl CacheCounters {
+    /// Count a lookup as a hit or a miss.
+    pub fn lookup(&self, hit: bool) {
+        let counter = if hit { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn set(&self) {
+        self.sets.fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn evicted(&self) {
+        self.evictions.fetch_add(1, Ordering::Relaxed);
+    }
+    pub fn snapshot(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            sets: self.sets.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+        }
+    }
+}
@@ -0,0 +1,68 @@
+use super::{CacheCounters, CacheStats, CacheStore};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Minimal client interface the Redis cache stores entries through.
+/// Implement it over the client of your choice (e.g. `redis::aio::ConnectionManager`,
+/// using `SET key value PX ttl`) so the library does not pull a Redis client into every build.
+#[async_trait]
+pub trait RedisClient: Send + Sync {
+    async fn get(&self, key: &str) -> std::io::Result<Option<Vec<u8>>>;
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Option<Duration>) -> std::io::Result<()>;
+    async fn del(&self, key: &str) -> std::io::Result<()>;
+}
+
+/// Cache shared between processes, stored as JSON in Redis. Expiry and eviction are left to
+/// Redis (TTLs and `maxmemory-policy`), so `evictions` stays at zero.
+pub struct RedisCache {
+    client: Arc<dyn RedisClient>,
+    prefix: String,
+    counters: CacheCounters,
+}
+
+impl RedisCache {
+    pub fn new(client: Arc<dyn RedisClient>) -> Self {
+        RedisCache { client, prefix: String::new(), counters: CacheCounters::default() }
+    }
+    /// Prepend `prefix` to every key (e.g. `"modulink:"`), to share a Redis database.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = prefix.into();
+        self
+    }
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+#[async_trait]
+impl CacheStore for RedisCache {
+    async fn get(&self, key: &str) -> std::io::Result<Option<Value>> {
+        let value = match self.client.get(&self.key(key)).await? {
+            Some(bytes) => Some(serde_json::from_slice(&bytes).map_err(std::io::Error::other)?),
+            None => None,
+        };
+        self.counters.lookup(value.is_some());
+        Ok(value)
+    }
+
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> std::io::Result<()> {
+        let bytes = serde_json::to_vec(&value).map_err(std::io::Error::other)?;
+        self.client.set(&self.key(key), bytes, ttl).await?;
+        self.counters.set();
+        Ok(())
+    }
+
+    async fn remove(&self, key: &str) -> std::io::Result<()> {
+        self.client.del(&self.key(key)).await
+    }
+
+    fn name(&self) -> &'static str {
+        "redis"
+    }
+
+    fn stats(&self) -> CacheStats {
+        self.counters.snapshot()
+    }
+}
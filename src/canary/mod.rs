@@ -0,0 +1,219 @@
+//! Canary runs: validate a new chain version against the live one before swapping it in.
+//!
+//! [`Canary`] runs every input through both the live chain and a candidate, diffs the two
+//! final contexts field by field, and keeps divergence statistics ([`CanaryReport`]).
+//! The candidate always runs as a shadow run (`ChainGeneric::run_shadow`): its links tagged
+//! with `LinkSpec::side_effects` are skipped, or replaced by the mocks given with
+//! [`Canary::shadow`], and its results go to no sinks or subscribers. [`Canary::run`] runs
+//! the live chain normally and returns its result, so the canary can stand in for the live
+//! chain while it is being validated; [`Canary::compare`] shadow-runs both, e.g. over
+//! recorded inputs.
+//!
+//! Fields that legitimately differ between runs (timestamps, generated ids) can be left
+//! out of the comparison with [`Canary::ignore`].
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::canary::Canary;
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let mut live = Chain::new();
+//! live.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("tax", 10) })));
+//! let mut candidate = Chain::new();
+//! candidate.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("tax", 12) })));
+//!
+//! let canary = Canary::new(Arc::new(live), Arc::new(candidate));
+//! let comparison = canary.compare(Context::new()).await;
+//! assert_eq!(comparison.divergences[0].path, "tax");
+//! assert_eq!(canary.report().divergence_rate, 1.0);
+//! # });
+//! ```
+
+use crate::chains::{ChainGeneric, RunReport, RunStatus, Shadow, ShadowCall};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A field whose value differs between the live and candidate results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Divergence {
+    /// Path of the field, e.g. `total` or `order.items[2].sku`.
+    pub path: String,
+    /// Value in the live result (`None` when the field is missing there).
+    pub live: Option<Value>,
+    pub candidate: Option<Value>,
+}
+
+/// How the candidate's run of one input compared to the live run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub live_status: RunStatus,
+    pub candidate_status: RunStatus,
+    pub divergences: Vec<Divergence>,
+}
+
+impl Comparison {
+    /// Whether the runs ended differently (e.g. one failed) or produced different fields.
+    pub fn diverged(&self) -> bool {
+        !self.divergences.is_empty() || std::mem::discriminant(&self.live_status) != std::mem::discriminant(&self.candidate_status)
+    }
+}
+
+/// Divergence statistics over every input compared so far.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CanaryReport {
+    pub runs: usize,
+    /// Runs whose [`Comparison::diverged`].
+    pub diverged: usize,
+    /// `diverged / runs`, or 0 before the first run.
+    pub divergence_rate: f64,
+    /// Runs that ended with a different status (completed, failed, ...).
+    pub status_mismatches: usize,
+    /// Runs where the candidate failed.
+    pub candidate_failures: usize,
+    /// Runs in which each field diverged, by path.
+    pub fields: BTreeMap<String, usize>,
+    pub live_mean_ms: f64,
+    pub candidate_mean_ms: f64,
+}
+
+#[derive(Default)]
+struct Tally {
+    report: CanaryReport,
+    live_time: Duration,
+    candidate_time: Duration,
+}
+
+type DivergenceHandler = Arc<dyn Fn(&Value, &Comparison) + Send + Sync>;
+
+/// A live chain and a candidate compared run by run; see the [module docs](self).
+pub struct Canary<T> {
+    live: Arc<ChainGeneric<T>>,
+    candidate: Arc<ChainGeneric<T>>,
+    ignore: Vec<String>,
+    shadow: Shadow<T>,
+    on_divergence: Option<DivergenceHandler>,
+    tally: Mutex<Tally>,
+}
+
+impl<T> Canary<T>
+where
+    T: Serialize + Clone + Send + Sync + 'static,
+{
+    pub fn new(live: Arc<ChainGeneric<T>>, candidate: Arc<ChainGeneric<T>>) -> Self {
+        Canary { live, candidate, ignore: Vec::new(), shadow: Shadow::new(), on_divergence: None, tally: Mutex::default() }
+    }
+    /// Leave these fields (and everything under them) out of the comparison.
+    pub fn ignore<I: IntoIterator<Item = S>, S: Into<String>>(mut self, paths: I) -> Self {
+        self.ignore.extend(paths.into_iter().map(Into::into));
+        self
+    }
+    /// Mocks for side-effecting links in shadow runs; by default they are skipped.
+    pub fn shadow(mut self, shadow: Shadow<T>) -> Self {
+        self.shadow = shadow;
+        self
+    }
+    /// Side-effecting links reached in shadow runs so far (see [`Shadow::calls`]).
+    pub fn shadow_calls(&self) -> Vec<ShadowCall> {
+        self.shadow.calls()
+    }
+    /// Call `handler` with the input and the comparison of every diverging run.
+    pub fn on_divergence<F: Fn(&Value, &Comparison) + Send + Sync + 'static>(mut self, handler: F) -> Self {
+        self.on_divergence = Some(Arc::new(handler));
+        self
+    }
+
+    /// Run `ctx` through the live chain, returning its result, and shadow-run the candidate
+    /// alongside it.
+    pub async fn run(&self, ctx: T) -> (T, RunReport) {
+        let input = ctx.clone();
+        let (live, candidate) = futures::join!(self.live.run_with_report(ctx), self.candidate.run_shadow(input.clone(), &self.shadow));
+        self.record(&input, &live, &candidate);
+        live
+    }
+
+    /// Shadow-run `ctx` through both chains and compare the results.
+    pub async fn compare(&self, ctx: T) -> Comparison {
+        let input = ctx.clone();
+        let (live, candidate) = futures::join!(self.live.run_shadow(ctx, &self.shadow), self.candidate.run_shadow(input.clone(), &self.shadow));
+        self.record(&input, &live, &candidate)
+    }
+
+    /// Divergence statistics so far.
+    pub fn report(&self) -> CanaryReport {
+        self.tally.lock().unwrap().report.clone()
+    }
+
+    fn record(&self, input: &T, live: &(T, RunReport), candidate: &(T, RunReport)) -> Comparison {
+        let mut divergences = Vec::new();
+        let (live_value, candidate_value) = (to_value(&live.0), to_value(&candidate.0));
+        diff("", Some(&live_value), Some(&candidate_value), &mut divergences);
+        divergences.retain(|d| !self.ignore.iter().any(|path| covers(path, &d.path)));
+        let comparison = Comparison { live_status: live.1.status.clone(), candidate_status: candidate.1.status.clone(), divergences };
+
+        let mut tally = self.tally.lock().unwrap();
+        tally.live_time += live.1.duration;
+        tally.candidate_time += candidate.1.duration;
+        let (live_time, candidate_time) = (tally.live_time, tally.candidate_time);
+        let report = &mut tally.report;
+        report.runs += 1;
+        if comparison.diverged() {
+            report.diverged += 1;
+        }
+        if std::mem::discriminant(&comparison.live_status) != std::mem::discriminant(&comparison.candidate_status) {
+            report.status_mismatches += 1;
+        }
+        if matches!(comparison.candidate_status, RunStatus::Failed(_)) {
+            report.candidate_failures += 1;
+        }
+        for divergence in &comparison.divergences {
+            *report.fields.entry(divergence.path.clone()).or_default() += 1;
+        }
+        report.divergence_rate = report.diverged as f64 / report.runs as f64;
+        report.live_mean_ms = live_time.as_secs_f64() * 1000.0 / report.runs as f64;
+        report.candidate_mean_ms = candidate_time.as_secs_f64() * 1000.0 / report.runs as f64;
+        drop(tally);
+
+        if comparison.diverged() {
+            tracing::debug!(divergences = comparison.divergences.len(), "canary diverged");
+            if let Some(handler) = &self.on_divergence {
+                handler(&to_value(input), &comparison);
+            }
+        }
+        comparison
+    }
+}
+
+fn to_value<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap_or(Value::Null)
+}
+
+// Whether the ignored `path` is `field` or one of its parents.
+fn covers(path: &str, field: &str) -> bool {
+    field.strip_prefix(path).is_some_and(|rest| rest.is_empty() || rest.starts_with('.') || rest.starts_with('['))
+}
+
+fn diff(path: &str, live: Option<&Value>, candidate: Option<&Value>, out: &mut Vec<Divergence>) {
+    match (live, candidate) {
+        (Some(Value::Object(a)), Some(Value::Object(b))) => {
+            let mut keys: Vec<&String> = a.keys().chain(b.keys().filter(|k| !a.contains_key(*k))).collect();
+            keys.sort();
+            for key in keys {
+                let field = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+                diff(&field, a.get(key), b.get(key), out);
+            }
+        }
+        (Some(Value::Array(a)), Some(Value::Array(b))) => {
+            for i in 0..a.len().max(b.len()) {
+                diff(&format!("{}[{}]", path, i), a.get(i), b.get(i), out);
+            }
+        }
+        (a, b) if a != b => out.push(Divergence { path: path.to_string(), live: a.cloned(), candidate: b.cloned() }),
+        _ => {}
+    }
+}
@@ -0,0 +1,54 @@
+//! Result broadcasting: every completed run is published to all subscribers of its chain.
+
+use super::report::RunReport;
+use futures::channel::mpsc;
+use std::sync::Mutex;
+
+/// Default per-subscriber buffer used by `ChainGeneric::subscribe`.
+pub const DEFAULT_SUBSCRIBER_CAPACITY: usize = 256;
+
+/// A completed run as seen by subscribers: the final context and its report.
+#[derive(Debug, Clone)]
+pub struct RunOutcome<T> {
+    pub ctx: T,
+    pub report: RunReport,
+}
+
+type Publisher<T> = Box<dyn Fn(&T, &RunReport) -> bool + Send + Sync>;
+
+/// Subscriber list owned by a chain. Publishing never blocks a run: subscribers whose
+/// buffer is full miss the outcome, and dropped receivers are pruned.
+pub(crate) struct Broadcaster<T> {
+    publishers: Mutex<Vec<Publisher<T>>>,
+}
+
+impl<T> Default for Broadcaster<T> {
+    fn default() -> Self {
+        Broadcaster { publishers: Mutex::new(Vec::new()) }
+    }
+}
+
+impl<T> Broadcaster<T> {
+    pub(crate) fn subscribe(&self, capacity: usize) -> mpsc::Receiver<RunOutcome<T>>
+    where
+        T: Clone + Send + 'static,
+    {
+        let (tx, rx) = mpsc::channel(capacity);
+        let tx = Mutex::new(tx);
+        self.publishers.lock().unwrap().push(Box::new(move |ctx: &T, report: &RunReport| {
+            let outcome = RunOutcome { ctx: ctx.clone(), report: report.clone() };
+            match tx.lock().unwrap().try_send(outcome) {
+                Ok(()) => true,
+                Err(e) => !e.is_disconnected(),
+            }
+        }));
+        rx
+    }
+
+    pub(crate) fn publish(&self, ctx: &T, report: &RunReport) {
+        let mut publishers = self.publishers.lock().unwrap();
+        if !publishers.is_empty() {
+            publishers.retain(|publish| publish(ctx, report));
+        }
+    }
+}
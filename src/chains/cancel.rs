@@ -0,0 +1,100 @@
+//! Cooperative cancellation of runs.
+//!
+//! A run started with `ChainGeneric::run_with_cancel` checks its [`CancellationToken`]
+//! before every link: once the token is cancelled, the run stops there, aborts the child
+//! runs it spawned, and reports `RunStatus::Cancelled` with the context as it was after the
+//! last link that ran. A link already running is not interrupted; long-running links can
+//! check `ctx_tools::is_cancelled` to stop early. Sub-chains embedded with
+//! `ChainGeneric::as_link` are cancelled with their parent run.
+//!
+//! A listener can, for example, cancel the token of a request's run when the client
+//! disconnects, so the remaining links are not run for nobody.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{CancellationToken, Chain, RunStatus};
+//! use modulink_rs::context::Context;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let token = CancellationToken::new();
+//! let mut chain = Chain::new();
+//! chain.add_link(Arc::new({
+//!     let token = token.clone();
+//!     move |ctx: Context| {
+//!         token.cancel();
+//!         Box::pin(async move { ctx.insert("reserved", true) })
+//!     }
+//! }));
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("charged", true) })));
+//!
+//! let (ctx, report) = chain.run_with_cancel(Context::new(), &token).await;
+//! assert_eq!(report.status, RunStatus::Cancelled);
+//! assert_eq!(ctx.get::<bool>("charged"), None);
+//! # });
+//! ```
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, Waker};
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    waiters: Mutex<Vec<Waker>>,
+}
+
+/// Cancels the runs it is given to; clones share the same state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<Inner>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel every run using this token. Cancelling twice has no further effect.
+    pub fn cancel(&self) {
+        if !self.inner.cancelled.swap(true, Ordering::SeqCst) {
+            for waker in self.inner.waiters.lock().unwrap().drain(..) {
+                waker.wake();
+            }
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// Resolves once the token is cancelled.
+    pub fn cancelled(&self) -> WaitForCancellation {
+        WaitForCancellation { token: self.clone() }
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`].
+pub struct WaitForCancellation {
+    token: CancellationToken,
+}
+
+impl Future for WaitForCancellation {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<()> {
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        let mut waiters = self.token.inner.waiters.lock().unwrap();
+        // Checked again under the lock, as `cancel` drains the waiters while holding it
+        if self.token.is_cancelled() {
+            return Poll::Ready(());
+        }
+        if !waiters.iter().any(|waker| waker.will_wake(cx.waker())) {
+            waiters.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,277 @@
+//! Durable runs: checkpoints between links, resumable after a restart or deployment.
+//!
+//! With `ChainGeneric::enable_checkpoints`, [`ChainGeneric::run_durable`] saves a
+//! [`Checkpoint`] after every link: the run id, the chain version, the next link, and the
+//! context. The checkpoint is removed when the run completes. After a crash,
+//! [`ChainGeneric::resume`] continues from the saved link. A failed run keeps its
+//! checkpoint, so it can also be re-run from any named link with
+//! [`ChainGeneric::retry_from_step`] (`modulink-cli retry`).
+//!
+//! Each checkpoint is stamped with the chain version (`ChainGeneric::set_version`; chains
+//! built from a [`ChainDefinition`](crate::definitions::ChainDefinition) use its hash).
+//! When a run resumes under a different version, the migration hook set with
+//! `ChainGeneric::on_version_change` can rewrite the checkpoint (remap link names,
+//! transform the context), and the run continues at the link with the checkpoint's link
+//! name in the new chain.
+//!
+//! Checkpoints go to a [`CheckpointStore`]: [`MemoryCheckpointStore`] for tests and
+//! single-process workers, [`FileCheckpointStore`] to survive restarts on one machine, or
+//! an implementation over a database. A checkpoint loaded by other means can be resumed
+//! directly with [`ChainGeneric::resume_from`].
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::checkpoint::MemoryCheckpointStore;
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::LinkSpec;
+//! use std::sync::Arc;
+//!
+//! let store = Arc::new(MemoryCheckpointStore::new());
+//! let mut chain = Chain::new();
+//! chain.set_version("v2");
+//! chain.enable_checkpoints(store.clone());
+//! chain.add_link_with(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("charged", true) })), LinkSpec::new().name("charge"));
+//! chain.add_link_with(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("shipped", true) })), LinkSpec::new().name("ship"));
+//! chain.on_version_change(|mut checkpoint| {
+//!     // v1 called the shipping step "dispatch"
+//!     if checkpoint.link_name.as_deref() == Some("dispatch") {
+//!         checkpoint.link_name = Some("ship".to_string());
+//!     }
+//!     Ok(checkpoint)
+//! });
+//! ```
+
+use crate::runtime::{self, default_executor, ExecutorObj};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Where a durable run stopped: the next link to run and the context at that point.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub run_id: String,
+    /// `ChainGeneric::version` of the chain that wrote the checkpoint.
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Position of the next link.
+    pub link: usize,
+    /// `LinkSpec::name` of the next link, used to find it again in a newer chain.
+    #[serde(default)]
+    pub link_name: Option<String>,
+    pub ctx: Value,
+    /// When a parked run is due to resume, in epoch milliseconds (see [`super::scheduler`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wake_at_ms: Option<u64>,
+    /// Key of the event a parked run waits for (see `std_links::await_event`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub awaiting_event: Option<String>,
+}
+
+/// Where checkpoints are kept between restarts (a database table, a key-value store).
+#[async_trait::async_trait]
+pub trait CheckpointStore: Send + Sync {
+    async fn save(&self, checkpoint: &Checkpoint) -> std::io::Result<()>;
+    async fn load(&self, run_id: &str) -> std::io::Result<Option<Checkpoint>>;
+    async fn remove(&self, run_id: &str) -> std::io::Result<()>;
+    /// Parked checkpoints whose `wake_at_ms` is at or before `now_ms`, for the scheduler.
+    async fn due(&self, now_ms: u64) -> std::io::Result<Vec<Checkpoint>> {
+        let _ = now_ms;
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "this checkpoint store cannot list due runs"))
+    }
+    /// Checkpoints of runs waiting for the event `key`.
+    async fn awaiting(&self, key: &str) -> std::io::Result<Vec<Checkpoint>> {
+        let _ = key;
+        Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "this checkpoint store cannot list runs awaiting events"))
+    }
+}
+
+pub type CheckpointStoreObj = Arc<dyn CheckpointStore>;
+
+/// Rewrites a checkpoint written by another chain version; an error stops the resume.
+pub type MigrateFn = Arc<dyn Fn(Checkpoint) -> Result<Checkpoint, String> + Send + Sync>;
+
+/// In-process store, for tests and single-process workers.
+#[derive(Default)]
+pub struct MemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, Checkpoint>>,
+}
+
+impl MemoryCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn len(&self) -> usize {
+        self.checkpoints.lock().unwrap().len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait::async_trait]
+impl CheckpointStore for MemoryCheckpointStore {
+    async fn save(&self, checkpoint: &Checkpoint) -> std::io::Result<()> {
+        self.checkpoints.lock().unwrap().insert(checkpoint.run_id.clone(), checkpoint.clone());
+        Ok(())
+    }
+    async fn load(&self, run_id: &str) -> std::io::Result<Option<Checkpoint>> {
+        Ok(self.checkpoints.lock().unwrap().get(run_id).cloned())
+    }
+    async fn remove(&self, run_id: &str) -> std::io::Result<()> {
+        self.checkpoints.lock().unwrap().remove(run_id);
+        Ok(())
+    }
+    async fn due(&self, now_ms: u64) -> std::io::Result<Vec<Checkpoint>> {
+        let checkpoints = self.checkpoints.lock().unwrap();
+        let mut due: Vec<Checkpoint> =
+            checkpoints.values().filter(|c| c.wake_at_ms.is_some_and(|at| at <= now_ms)).cloned().collect();
+        due.sort_by_key(|c| c.wake_at_ms);
+        Ok(due)
+    }
+    async fn awaiting(&self, key: &str) -> std::io::Result<Vec<Checkpoint>> {
+        let checkpoints = self.checkpoints.lock().unwrap();
+        Ok(checkpoints.values().filter(|c| c.awaiting_event.as_deref() == Some(key)).cloned().collect())
+    }
+}
+
+/// Keeps each checkpoint as a JSON file in a directory, written atomically (to a temporary
+/// file, then renamed), so a crash mid-write leaves the previous checkpoint intact. File
+/// I/O runs on the blocking pool of the store's executor.
+pub struct FileCheckpointStore {
+    pub dir: PathBuf,
+    // Serializes writes with the scans of `due` and `awaiting`.
+    lock: Arc<Mutex<()>>,
+    executor: ExecutorObj,
+}
+
+impl FileCheckpointStore {
+    /// A store in `dir`, created if missing.
+    pub fn new(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(FileCheckpointStore { dir, lock: Arc::new(Mutex::new(())), executor: default_executor() })
+    }
+    /// Executor whose blocking pool does the file I/O (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    // The file of `run_id`; characters other than ASCII letters, digits, `-`, and `_` are
+    // percent-encoded so any run id makes a safe file name.
+    fn path(&self, run_id: &str) -> PathBuf {
+        let mut name = String::with_capacity(run_id.len() + 5);
+        for byte in run_id.bytes() {
+            match byte {
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' | b'_' => name.push(byte as char),
+                other => name.push_str(&format!("%{:02X}", other)),
+            }
+        }
+        name.push_str(".json");
+        self.dir.join(name)
+    }
+
+    fn read(path: &Path) -> io::Result<Option<Checkpoint>> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map(Some).map_err(io::Error::other),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Run `io` on the executor's blocking pool, under the lock.
+    async fn blocking<R: Send + 'static>(&self, io: impl FnOnce() -> io::Result<R> + Send + 'static) -> io::Result<R> {
+        let lock = self.lock.clone();
+        runtime::spawn_blocking(self.executor.as_ref(), move || {
+            let _guard = lock.lock().unwrap();
+            io()
+        })
+        .await
+        .unwrap_or_else(|e| Err(io::Error::other(e)))
+    }
+
+    // Every stored checkpoint matching `keep`.
+    async fn scan(&self, keep: impl Fn(&Checkpoint) -> bool + Send + 'static) -> io::Result<Vec<Checkpoint>> {
+        let dir = self.dir.clone();
+        self.blocking(move || {
+            let mut found = Vec::new();
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "json") {
+                    found.extend(Self::read(&path)?.filter(&keep));
+                }
+            }
+            Ok(found)
+        })
+        .await
+    }
+}
+
+#[async_trait::async_trait]
+impl CheckpointStore for FileCheckpointStore {
+    async fn save(&self, checkpoint: &Checkpoint) -> io::Result<()> {
+        let bytes = serde_json::to_vec(checkpoint).map_err(io::Error::other)?;
+        let path = self.path(&checkpoint.run_id);
+        self.blocking(move || {
+            let tmp = path.with_extension("json.tmp");
+            fs::write(&tmp, bytes)?;
+            fs::rename(&tmp, &path)
+        })
+        .await
+    }
+    async fn load(&self, run_id: &str) -> io::Result<Option<Checkpoint>> {
+        let path = self.path(run_id);
+        self.blocking(move || Self::read(&path)).await
+    }
+    async fn remove(&self, run_id: &str) -> io::Result<()> {
+        let path = self.path(run_id);
+        self.blocking(move || match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        })
+        .await
+    }
+    async fn due(&self, now_ms: u64) -> io::Result<Vec<Checkpoint>> {
+        let mut due = self.scan(move |c| c.wake_at_ms.is_some_and(|at| at <= now_ms)).await?;
+        due.sort_by_key(|c| c.wake_at_ms);
+        Ok(due)
+    }
+    async fn awaiting(&self, key: &str) -> io::Result<Vec<Checkpoint>> {
+        let key = key.to_string();
+        self.scan(move |c| c.awaiting_event.as_deref() == Some(key.as_str())).await
+    }
+}
+
+// JSON merge patch (RFC 7386): objects merge recursively, `null` removes a field, and
+// anything else replaces the target.
+pub(crate) fn merge_patch(target: &mut Value, patch: Value) {
+    let Value::Object(fields) = patch else {
+        *target = patch;
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Default::default());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in fields {
+            if value.is_null() {
+                target.remove(&key);
+            } else {
+                merge_patch(target.entry(key).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+// What a chain needs to write and read checkpoints for its context type.
+pub(crate) struct Checkpointing<T> {
+    pub(crate) store: CheckpointStoreObj,
+    pub(crate) snapshot: Arc<dyn Fn(&T) -> Value + Send + Sync>,
+    pub(crate) restore: Arc<dyn Fn(Value) -> Result<T, String> + Send + Sync>,
+    pub(crate) migrate: Option<MigrateFn>,
+}
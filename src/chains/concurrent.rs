@@ -0,0 +1,45 @@
+//! Concurrent execution of commutative links.
+//!
+//! Enrichment stages often consist of links that each read the context and add keys no
+//! other link touches (a profile lookup, an order count, a risk score). Such links can be
+//! tagged with `LinkSpec::commutative`; once a chain has a merge function
+//! (`ChainGeneric::enable_concurrent_links`), consecutive tagged links run concurrently,
+//! each on its own clone of the context, and their results are merged before the next link.
+//!
+//! A group ends at the first untagged link and after any link that is the source of a
+//! branch, so branching behaves as if the links ran one after another. Middleware sees a
+//! group as one step: `before` runs with the shared input, `after` with the merged result.
+//! The run path and step timings still list every link of the group.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::combinators::merge_changes;
+//! use modulink_rs::links::{Link, LinkSpec};
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let profile: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("name", "Ada") }));
+//! let risk: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("risk", "low") }));
+//!
+//! let mut chain = Chain::new();
+//! chain.enable_concurrent_links(merge_changes);
+//! chain.add_link_with(profile, LinkSpec::new().provides(["name"]).commutative());
+//! chain.add_link_with(risk, LinkSpec::new().provides(["risk"]).commutative());
+//!
+//! let (ctx, report) = chain.run_with_report(Context::new()).await;
+//! assert_eq!(ctx.get::<String>("risk").as_deref(), Some("low"));
+//! assert_eq!(report.steps.len(), 2);
+//! # });
+//! ```
+
+use std::sync::Arc;
+
+type MergeFn<T> = Arc<dyn Fn(T, Vec<T>) -> T + Send + Sync>;
+
+/// How a chain clones the context for, and merges the results of, a concurrent group.
+pub(crate) struct ConcurrentLinks<T> {
+    pub(crate) clone: fn(&T) -> T,
+    pub(crate) merge: MergeFn<T>,
+}
@@ -0,0 +1,126 @@
+//! Structured run errors: why a run stopped early.
+//!
+//! Links and middleware fail the current run with `ctx_tools::fail_run`; the chain stops
+//! before the next step and the error ends up in `RunReport::status`, carrying the path of
+//! links the run took to get there.
+
+use serde::{Deserialize, Serialize};
+
+/// Category of a run failure. Listeners map these to protocol status codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ErrorKind {
+    /// Missing or invalid credentials (HTTP 401).
+    Unauthorized,
+    /// Credentials valid but access denied, including cross-tenant access (HTTP 403).
+    Forbidden,
+    /// Input was rejected (HTTP 400).
+    InvalidInput,
+    /// A configured resource limit was exceeded (HTTP 413/429/503 depending on the limit).
+    LimitExceeded,
+    /// The caller used up its quota (see `quota`) (HTTP 429).
+    QuotaExceeded,
+    /// No permit was available under a rate limit (see `chains::rate_limit`) (HTTP 429).
+    RateLimited,
+    /// A link or middleware panicked (HTTP 500).
+    Panicked,
+    /// Any other failure (HTTP 500).
+    Internal,
+}
+
+impl ErrorKind {
+    /// Closest HTTP status code for this kind of failure.
+    pub fn http_status(&self) -> u16 {
+        match self {
+            ErrorKind::Unauthorized => 401,
+            ErrorKind::Forbidden => 403,
+            ErrorKind::InvalidInput => 400,
+            ErrorKind::LimitExceeded => 413,
+            ErrorKind::QuotaExceeded | ErrorKind::RateLimited => 429,
+            ErrorKind::Panicked | ErrorKind::Internal => 500,
+        }
+    }
+}
+
+/// A structured failure recorded against a run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunError {
+    pub kind: ErrorKind,
+    pub message: String,
+    /// Links executed before the failure, in order, ending with the link that was running.
+    /// Filled in by the chain when the run ends; empty if no link ran.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub path: Vec<PathStep>,
+}
+
+/// One executed link in a run's path.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PathStep {
+    /// Position of the link in the chain.
+    pub link: usize,
+    /// `LinkSpec::name` of the link, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Target of the branch taken after this link, if one was.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<usize>,
+}
+
+impl std::fmt::Display for PathStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.link)?;
+        if let Some(name) = &self.name {
+            write!(f, ":{}", name)?;
+        }
+        if let Some(target) = self.branch {
+            write!(f, " (branch to {})", target)?;
+        }
+        Ok(())
+    }
+}
+
+impl RunError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        RunError { kind, message: message.into(), path: Vec::new() }
+    }
+    pub fn with_path(mut self, path: Vec<PathStep>) -> Self {
+        self.path = path;
+        self
+    }
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unauthorized, message)
+    }
+    pub fn forbidden(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Forbidden, message)
+    }
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+    pub fn limit_exceeded(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::LimitExceeded, message)
+    }
+    pub fn quota_exceeded(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::QuotaExceeded, message)
+    }
+    pub fn rate_limited(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::RateLimited, message)
+    }
+    pub fn panicked(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Panicked, message)
+    }
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+}
+
+impl std::fmt::Display for RunError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}: {}", self.kind, self.message)?;
+        if !self.path.is_empty() {
+            let path: Vec<String> = self.path.iter().map(ToString::to_string).collect();
+            write!(f, " [path: {}]", path.join(" -> "))?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for RunError {}
@@ -0,0 +1,78 @@
+//! Fallible links and error routing.
+//!
+//! A link added with `ChainGeneric::add_fallible_link` returns `Result<C, RunError>`
+//! (`links::FallibleLinkGeneric`), so it can signal failure without stashing an error key
+//! in the context or panicking. Where the run goes after an `Err` is the link's
+//! [`ErrorRoute`]: the chain default set with `ChainGeneric::set_error_route`, or the one
+//! given for that link with `ChainGeneric::route_errors`.
+//!
+//! A skipped or rerouted failure is recorded as a run warning, and the error stays
+//! readable for the rest of the run with `ctx_tools::last_error`, e.g. by the handler link.
+//! Either way the run continues with the context the failed link was given.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, ErrorRoute, RunError};
+//! use modulink_rs::context::Context;
+//! use modulink_rs::ctx_tools;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let mut chain = Chain::new();
+//! chain.add_fallible_link(Arc::new(|ctx: Context| Box::pin(async move {
+//!     match ctx.get::<u32>("qty") {
+//!         Some(qty) if qty > 0 => Ok(ctx),
+//!         _ => Err(RunError::invalid_input("qty must be positive")),
+//!     }
+//! })));
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("charged", true) })));
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+//!     let reason = ctx_tools::last_error().map(|err| err.message);
+//!     ctx.insert("rejected", reason)
+//! })));
+//! chain.route_errors(0, ErrorRoute::Jump(2));
+//!
+//! let ctx = chain.try_run(Context::new().insert("qty", 0)).await.unwrap();
+//! assert_eq!(ctx.get::<bool>("charged"), None);
+//! assert_eq!(ctx.get::<String>("rejected").as_deref(), Some("qty must be positive"));
+//! # });
+//! ```
+
+use super::{LinkGeneric, RunScope};
+use crate::links::FallibleLinkGeneric;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Where a run goes when a fallible link returns `Err`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorRoute {
+    /// Fail the run with the error (the default).
+    #[default]
+    Abort,
+    /// Continue with the next link.
+    Skip,
+    /// Continue at the handler link at this position.
+    Jump(usize),
+}
+
+// A plain link running `link`: an `Err` is handed to the run loop through the scope,
+// and the link's input is passed on, so the input is cloned before every call.
+pub(crate) fn wrap<T: Clone + Send + 'static>(link: FallibleLinkGeneric<T>) -> LinkGeneric<T> {
+    Arc::new(move |ctx: T| {
+        let link = link.clone();
+        Box::pin(async move {
+            let input = ctx.clone();
+            match link(ctx).await {
+                Ok(ctx) => ctx,
+                Err(err) => {
+                    match RunScope::current() {
+                        Some(scope) => scope.link_failed(err),
+                        None => tracing::warn!(error = %err, "fallible link failed outside of a run"),
+                    }
+                    input
+                }
+            }
+        })
+    })
+}
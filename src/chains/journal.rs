@@ -0,0 +1,40 @@
+//! Change journal: the key-level mutations each link made during a run.
+//!
+//! Opt in with `ChainGeneric::enable_journal`. The chain snapshots the context (as a JSON
+//! object) around every link and records what changed, in order, in `RunReport::journal`.
+//! Sinks receive the journal through `BaseSink::deliver_changes`, so they can apply deltas
+//! to a downstream store instead of rewriting the whole document. Changes made by
+//! middleware outside of links (e.g. `on_run_start`) are not journaled.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// One key-level mutation made by link `link`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Change {
+    Set { link: usize, key: String, value: Value },
+    Remove { link: usize, key: String },
+}
+
+pub(crate) type SnapshotFn<T> = Arc<dyn Fn(&T) -> Map<String, Value> + Send + Sync>;
+
+pub(crate) fn snapshot<T: Serialize>(ctx: &T) -> Map<String, Value> {
+    match serde_json::to_value(ctx) {
+        Ok(Value::Object(map)) => map,
+        _ => Map::new(),
+    }
+}
+
+/// Changes turning `before` into `after`: sets in key order, then removals in key order.
+pub(crate) fn diff(link: usize, before: &Map<String, Value>, after: &Map<String, Value>) -> Vec<Change> {
+    let mut sets: Vec<(&String, &Value)> = after.iter().filter(|(k, v)| before.get(*k) != Some(*v)).collect();
+    sets.sort_by_key(|(k, _)| *k);
+    let mut removed: Vec<&String> = before.keys().filter(|k| !after.contains_key(*k)).collect();
+    removed.sort();
+    sets.into_iter()
+        .map(|(key, value)| Change::Set { link, key: key.clone(), value: value.clone() })
+        .chain(removed.into_iter().map(|key| Change::Remove { link, key: key.clone() }))
+        .collect()
+}
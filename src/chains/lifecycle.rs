@@ -0,0 +1,52 @@
+//! Lifecycle hooks: work done once before a chain serves its first run, and once after its
+//! last.
+//!
+//! Links and middleware that need to open connection pools, load models, or compile
+//! templates implement [`Initialize`]. `ChainGeneric::warm_up` runs every registered
+//! initializer, so the first request doesn't pay the cold-start cost; `HttpListener::for_chain`
+//! warms its chain up before accepting connections.
+//!
+//! Links and middleware that buffer writes or hold connections implement [`Shutdown`].
+//! `ChainGeneric::shutdown` runs every registered hook; `HttpListener::for_chain` calls it
+//! once a graceful shutdown has drained in-flight requests.
+//!
+//! Example:
+//! ```rust
+//! use async_trait::async_trait;
+//! use modulink_rs::chains::{Chain, Initialize};
+//! use std::sync::Arc;
+//!
+//! struct Templates;
+//!
+//! #[async_trait]
+//! impl Initialize for Templates {
+//!     async fn init(&self) -> std::io::Result<()> {
+//!         // compile templates here
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut chain = Chain::new();
+//! chain.add_initializer(Arc::new(Templates));
+//! futures::executor::block_on(chain.warm_up()).unwrap();
+//! ```
+
+use async_trait::async_trait;
+use std::sync::Arc;
+
+#[async_trait]
+pub trait Initialize: Send + Sync {
+    /// Prepare for the first run. Called once per `warm_up`, in registration order.
+    async fn init(&self) -> std::io::Result<()>;
+}
+
+pub type InitializeObj = Arc<dyn Initialize>;
+
+#[async_trait]
+pub trait Shutdown: Send + Sync {
+    /// Flush buffers, close connections, persist state. Called once per `shutdown`,
+    /// in reverse registration order.
+    async fn shutdown(&self) -> std::io::Result<()>;
+}
+
+pub type ShutdownObj = Arc<dyn Shutdown>;
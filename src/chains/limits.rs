@@ -0,0 +1,76 @@
+//! Per-run resource limits.
+//!
+//! Exceeding a limit fails the run with `ErrorKind::LimitExceeded` (HTTP 413), so a
+//! multi-tenant host can bound what any one chain costs it.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, ResourceLimits};
+//! use std::time::Duration;
+//!
+//! let mut chain = Chain::new();
+//! chain.set_limits(
+//!     ResourceLimits::new()
+//!         .with_max_wall_time(Duration::from_secs(30))
+//!         .with_max_context_bytes(1 << 20)
+//!         .with_max_children(16),
+//! );
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Caps enforced on every run of a chain; `None` means unlimited.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// Wall-clock time for the whole run, children included. A run that overruns is
+    /// abandoned mid-link, so it returns an empty (default) context.
+    pub max_wall_time: Option<Duration>,
+    /// Size of the context serialized as JSON, checked on input and after every link.
+    pub max_context_bytes: Option<usize>,
+    /// Child runs spawned with `ctx_tools::spawn_child`; further spawns fail the run.
+    pub max_children: Option<usize>,
+    /// Link executions per run, counting repeats when branches loop back.
+    pub max_steps: Option<usize>,
+}
+
+impl ResourceLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_max_wall_time(mut self, dur: Duration) -> Self {
+        self.max_wall_time = Some(dur);
+        self
+    }
+    pub fn with_max_context_bytes(mut self, bytes: usize) -> Self {
+        self.max_context_bytes = Some(bytes);
+        self
+    }
+    pub fn with_max_children(mut self, children: usize) -> Self {
+        self.max_children = Some(children);
+        self
+    }
+    pub fn with_max_steps(mut self, steps: usize) -> Self {
+        self.max_steps = Some(steps);
+        self
+    }
+}
+
+pub(crate) type ContextSizeFn<T> = Arc<dyn Fn(&T) -> usize + Send + Sync>;
+pub(crate) type FallbackFn<T> = Arc<dyn Fn() -> T + Send + Sync>;
+
+/// Context-type operations the limits need, captured where `T: Serialize + Default` is known.
+pub(crate) struct LimitHooks<T> {
+    pub size: ContextSizeFn<T>,
+    pub fallback: FallbackFn<T>,
+}
+
+impl<T: Serialize + Default + 'static> LimitHooks<T> {
+    pub fn new() -> Self {
+        LimitHooks {
+            size: Arc::new(|ctx: &T| serde_json::to_vec(ctx).map(|v| v.len()).unwrap_or(0)),
+            fallback: Arc::new(T::default),
+        }
+    }
+}
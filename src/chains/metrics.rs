@@ -0,0 +1,146 @@
+//! Per-link execution metrics.
+//!
+//! A chain with `ChainGeneric::enable_metrics` records, for every link, how often it ran,
+//! how long it took, and how often it failed; `ChainGeneric::metrics` returns a
+//! serializable [`ChainMetrics`] snapshot to hand to a monitoring system. A link counts as
+//! failed when the run failed at it, or when it was a fallible link whose error was
+//! skipped or routed to a handler. A link run twice by a loop counts twice; retries of a
+//! link count once. Shadow runs are not recorded.
+//!
+//! Max durations are exact; p50 and p95 are taken over the last [`SAMPLE_WINDOW`] calls
+//! of each link.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::LinkSpec;
+//! use std::sync::Arc;
+//!
+//! let mut chain = Chain::new();
+//! chain.add_link_with(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("a", 1) })), LinkSpec::new().name("load"));
+//! chain.enable_metrics();
+//! # futures::executor::block_on(async {
+//! chain.run(Context::new()).await;
+//! chain.run(Context::new()).await;
+//! # });
+//! let metrics = chain.metrics().unwrap();
+//! assert_eq!(metrics.runs, 2);
+//! assert_eq!(metrics.links[0].name.as_deref(), Some("load"));
+//! assert_eq!((metrics.links[0].calls, metrics.links[0].errors), (2, 0));
+//! ```
+
+use super::{RunReport, RunStatus};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Calls of a link the p50 and p95 durations are taken over.
+pub const SAMPLE_WINDOW: usize = 1024;
+
+/// Metrics of one chain, as of when they were taken.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ChainMetrics {
+    /// Runs recorded, failed ones included.
+    pub runs: u64,
+    pub failed_runs: u64,
+    /// One entry per link that ran at least once, by position.
+    pub links: Vec<LinkMetrics>,
+}
+
+/// Metrics of one link.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinkMetrics {
+    pub link: usize,
+    /// `LinkSpec::name` of the link, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub calls: u64,
+    pub errors: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+    pub max: Duration,
+}
+
+#[derive(Default)]
+struct LinkSamples {
+    name: Option<String>,
+    calls: u64,
+    errors: u64,
+    max: Duration,
+    recent: VecDeque<Duration>,
+}
+
+#[derive(Default)]
+struct State {
+    runs: u64,
+    failed_runs: u64,
+    links: BTreeMap<usize, LinkSamples>,
+}
+
+/// Collects the metrics of a chain's runs.
+#[derive(Default)]
+pub(crate) struct MetricsCollector {
+    state: Mutex<State>,
+}
+
+impl MetricsCollector {
+    // Record a finished run from its report.
+    pub(crate) fn record_run(&self, report: &RunReport) {
+        let mut state = self.state.lock().unwrap();
+        state.runs += 1;
+        for step in &report.steps {
+            let samples = state.links.entry(step.link).or_default();
+            samples.name.clone_from(&step.name);
+            samples.calls += 1;
+            samples.max = samples.max.max(step.duration);
+            if samples.recent.len() == SAMPLE_WINDOW {
+                samples.recent.pop_front();
+            }
+            samples.recent.push_back(step.duration);
+        }
+        if let RunStatus::Failed(err) = &report.status {
+            state.failed_runs += 1;
+            if let Some(step) = err.path.last() {
+                state.links.entry(step.link).or_default().errors += 1;
+            }
+        }
+    }
+    // Record an error of link `idx` the run carried on after.
+    pub(crate) fn record_error(&self, idx: usize) {
+        self.state.lock().unwrap().links.entry(idx).or_default().errors += 1;
+    }
+    pub(crate) fn snapshot(&self) -> ChainMetrics {
+        let state = self.state.lock().unwrap();
+        ChainMetrics {
+            runs: state.runs,
+            failed_runs: state.failed_runs,
+            links: state
+                .links
+                .iter()
+                .map(|(&link, samples)| {
+                    let mut sorted: Vec<Duration> = samples.recent.iter().copied().collect();
+                    sorted.sort();
+                    LinkMetrics {
+                        link,
+                        name: samples.name.clone(),
+                        calls: samples.calls,
+                        errors: samples.errors,
+                        p50: percentile(&sorted, 50),
+                        p95: percentile(&sorted, 95),
+                        max: samples.max,
+                    }
+                })
+                .collect(),
+        }
+    }
+}
+
+// Nearest-rank percentile of `sorted`; zero when empty.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    match sorted.len() {
+        0 => Duration::ZERO,
+        len => sorted[((len * pct).div_ceil(100)).clamp(1, len) - 1],
+    }
+}
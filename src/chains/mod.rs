@@ -14,36 +14,377 @@
 //!
 //! Advanced/generic APIs may use `mut` for performance, but must document the tradeoff.
 
+pub mod broadcast;
+pub mod cancel;
+pub mod checkpoint;
+pub mod concurrent;
+pub mod error;
+pub mod fallible;
+pub mod journal;
+pub mod lifecycle;
+pub mod limits;
+pub mod metrics;
+pub mod on_error;
+pub mod pool;
+pub mod rate_limit;
+pub mod report;
+pub mod retry;
+pub mod scheduler;
+pub mod scope;
+pub mod shadow;
+pub mod state;
+pub mod stepper;
+pub mod topology;
+pub mod trace;
+pub mod typed;
+pub mod validate;
+
+pub use broadcast::RunOutcome;
+pub use cancel::CancellationToken;
+pub use checkpoint::{Checkpoint, CheckpointStore, CheckpointStoreObj, FileCheckpointStore, MemoryCheckpointStore};
+pub use error::{ErrorKind, PathStep, RunError};
+pub use fallible::ErrorRoute;
+pub use journal::Change;
+pub use lifecycle::{Initialize, InitializeObj, Shutdown, ShutdownObj};
+pub use limits::ResourceLimits;
+pub use metrics::{ChainMetrics, LinkMetrics};
+pub use pool::PoolStats;
+pub use rate_limit::{RateLimit, RateLimitMode, RateLimiter};
+pub use report::{RunReport, RunStatus, StepTiming};
+pub use retry::{Backoff, RetryPolicy};
+pub use scheduler::Scheduler;
+pub use scope::RunScope;
+pub use shadow::{Shadow, ShadowCall};
+pub use state::SharedState;
+pub use stepper::ChainStepper;
+pub use topology::{EdgeKind, Topology, TopologyEdge, TopologyNode};
+pub use trace::{ExecutionTrace, Replay, ReplayStep, TraceRecorder, TraceStep};
+pub use typed::{TypedChain, TypedChainBuilder};
+pub use validate::{ValidationError, ValidationReport, ValidationWarning};
+
+use crate::context::SerializationPolicy;
+use crate::links::{FallibleLinkGeneric, LinkSpec};
+use crate::middleware::MiddlewareExecution;
+use crate::runtime::{self, default_executor, ExecutorObj};
+use crate::sinks::{DeadLetterSinkObj, Failure, SinkObj};
+use broadcast::{Broadcaster, DEFAULT_SUBSCRIBER_CAPACITY};
+use checkpoint::Checkpointing;
+use concurrent::ConcurrentLinks;
+use futures::channel::mpsc;
+use futures::future::join_all;
+use futures::{Stream, StreamExt};
+use limits::LimitHooks;
+use metrics::MetricsCollector;
+use on_error::ErrorChain;
+use pool::{Pool, DEFAULT_POOL_CAPACITY};
+use retry::Retries;
+use trace::Tracing;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
 use std::sync::Arc;
-use std::future::Future;
-use std::pin::Pin;
+use std::time::Instant;
 
 // Generic Chain: works with any context type (Context, MutableContext, or user-defined)
 pub struct ChainGeneric<T> {
-    links: Vec<Arc<dyn Fn(T) -> Pin<Box<dyn Future<Output = T> + Send>> + Send + Sync>>,
+    name: Option<String>,
+    version: Option<String>,
+    links: Vec<LinkGeneric<T>>,
+    specs: Vec<LinkSpec>,
+    input_keys: Vec<String>,
     middleware: Vec<Arc<dyn crate::middleware::Middleware<T>>>,
     pub branches: Vec<Branch<T>>,
+    executor: ExecutorObj,
+    broadcaster: Broadcaster<T>,
+    sinks: Vec<SinkObj<T>>,
+    dead_letters: Vec<DeadLetterSinkObj<T>>,
+    error_chain: Option<ErrorChain<T>>,
+    limits: ResourceLimits,
+    rate_limit: Option<RateLimit>,
+    link_rate_limits: HashMap<usize, RateLimit>,
+    metrics: Option<MetricsCollector>,
+    limit_hooks: Option<LimitHooks<T>>,
+    initializers: Vec<InitializeObj>,
+    shutdown_hooks: Vec<ShutdownObj>,
+    journal: Option<journal::SnapshotFn<T>>,
+    traces: Option<Tracing<T>>,
+    checkpoints: Option<Checkpointing<T>>,
+    scopes: Pool<Arc<RunScope>>,
+    middleware_buffers: Pool<Vec<Arc<dyn crate::middleware::Middleware<T>>>>,
+    serialization: SerializationPolicy,
+    error_route: ErrorRoute,
+    error_routes: HashMap<usize, ErrorRoute>,
+    middleware_execution: MiddlewareExecution,
+    concurrent: Option<ConcurrentLinks<T>>,
+    retries: Option<Retries<T>>,
+    state: Option<SharedState>,
 }
 
 pub struct Branch<T> {
     pub source: usize,
     pub target: usize,
     pub condition: Arc<dyn Fn(&T) -> bool + Send + Sync>,
+    /// Added with `ChainGeneric::jump`: the condition always holds.
+    pub always: bool,
+    /// What the condition checks, for [`topology`]; see `ChainGeneric::connect_described`.
+    pub description: Option<String>,
 }
 
 impl<T: 'static + Send> ChainGeneric<T> {
     pub fn new() -> Self {
-        ChainGeneric { links: Vec::new(), middleware: Vec::new(), branches: Vec::new() }
+        ChainGeneric {
+            name: None,
+            version: None,
+            links: Vec::new(),
+            specs: Vec::new(),
+            input_keys: Vec::new(),
+            middleware: Vec::new(),
+            branches: Vec::new(),
+            executor: default_executor(),
+            broadcaster: Broadcaster::default(),
+            sinks: Vec::new(),
+            dead_letters: Vec::new(),
+            error_chain: None,
+            limits: ResourceLimits::default(),
+            rate_limit: None,
+            link_rate_limits: HashMap::new(),
+            metrics: None,
+            limit_hooks: None,
+            initializers: Vec::new(),
+            shutdown_hooks: Vec::new(),
+            journal: None,
+            traces: None,
+            checkpoints: None,
+            scopes: Pool::new(DEFAULT_POOL_CAPACITY),
+            middleware_buffers: Pool::new(DEFAULT_POOL_CAPACITY),
+            serialization: SerializationPolicy::Panic,
+            error_route: ErrorRoute::Abort,
+            error_routes: HashMap::new(),
+            middleware_execution: MiddlewareExecution::Sequential,
+            concurrent: None,
+            retries: None,
+            state: None,
+        }
+    }
+    /// Name used in generated documentation.
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = Some(name.into());
+    }
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    /// Version stamped on run reports and checkpoints (see [`checkpoint`]).
+    pub fn set_version(&mut self, version: impl Into<String>) {
+        self.version = Some(version.into());
+    }
+    pub fn version(&self) -> Option<&str> {
+        self.version.as_deref()
     }
-    pub fn add_link(&mut self, link: Arc<dyn Fn(T) -> Pin<Box<dyn Future<Output = T> + Send>> + Send + Sync>) {
+    pub fn add_link(&mut self, link: LinkGeneric<T>) {
+        self.add_link_with(link, LinkSpec::default());
+    }
+    /// Add a link together with the keys it requires and provides (see [`Self::validate`]).
+    pub fn add_link_with(&mut self, link: LinkGeneric<T>, spec: LinkSpec) {
         self.links.push(link);
+        self.specs.push(spec);
+    }
+    /// Declare the keys every input context carries.
+    pub fn declare_input<I: IntoIterator<Item = S>, S: Into<String>>(&mut self, keys: I) {
+        self.input_keys.extend(keys.into_iter().map(Into::into));
+    }
+    /// Names of the attached middleware, in order.
+    pub fn middleware_names(&self) -> Vec<String> {
+        self.middleware.iter().map(|mw| mw.name().to_string()).collect()
+    }
+    pub fn limits(&self) -> &ResourceLimits {
+        &self.limits
+    }
+    /// Make `state` readable by the links of every run (see [`state`]).
+    pub fn set_state(&mut self, state: SharedState) {
+        self.state = Some(state);
+    }
+    pub fn state(&self) -> Option<&SharedState> {
+        self.state.as_ref()
+    }
+    /// How `Context::insert` handles values that fail to serialize during runs of this
+    /// chain; panicking by default. Set `Skip` or `RecordError` when links insert user
+    /// data, so a bad value cannot crash the listener serving the chain.
+    pub fn set_serialization_policy(&mut self, policy: SerializationPolicy) {
+        self.serialization = policy;
+    }
+    pub fn serialization_policy(&self) -> SerializationPolicy {
+        self.serialization
+    }
+    /// Markdown documentation of this chain (see [`crate::docs`]).
+    pub fn document(&self) -> String {
+        crate::docs::ChainDoc::from_chain(self).to_markdown()
+    }
+    /// HTML documentation of this chain (see [`crate::docs`]).
+    pub fn document_html(&self) -> String {
+        crate::docs::ChainDoc::from_chain(self).to_html()
+    }
+    /// The links of this chain and the edges between them (see [`topology`]).
+    pub fn topology(&self) -> Topology {
+        let next = (1..self.links.len()).map(|link| TopologyEdge { source: link - 1, target: link, kind: EdgeKind::Next, condition: None });
+        let branches = self.branches.iter().map(|b| TopologyEdge {
+            source: b.source,
+            target: b.target,
+            kind: if b.always { EdgeKind::Jump } else { EdgeKind::Branch },
+            condition: b.description.clone(),
+        });
+        let errors = (0..self.links.len()).filter_map(|link| match self.error_route(link) {
+            ErrorRoute::Jump(handler) => Some(TopologyEdge { source: link, target: handler, kind: EdgeKind::Error, condition: None }),
+            _ => None,
+        });
+        Topology {
+            name: self.name.clone(),
+            version: self.version.clone(),
+            input: self.input_keys.clone(),
+            nodes: self.specs.iter().enumerate().map(|(link, spec)| TopologyNode { link, spec: spec.clone() }).collect(),
+            edges: next.chain(branches).chain(errors).collect(),
+        }
+    }
+    /// Graphviz DOT source for this chain (see [`Topology::to_dot`]).
+    pub fn to_dot(&self) -> String {
+        self.topology().to_dot()
+    }
+    /// Mermaid flowchart source for this chain (see [`Topology::to_mermaid`]).
+    pub fn to_mermaid(&self) -> String {
+        self.topology().to_mermaid()
+    }
+    pub fn link_specs(&self) -> &[LinkSpec] {
+        &self.specs
+    }
+    pub fn input_keys(&self) -> &[String] {
+        &self.input_keys
+    }
+    /// Check that every link's required keys are provided by the declared input or by
+    /// upstream links on every path, that branches stay in range, that link names are
+    /// unique, and that no loop is endless; the errors of [`Self::validation_report`].
+    pub fn validate(&self) -> Result<(), Vec<ValidationError>> {
+        self.validation_report().into_result()
+    }
+    /// The errors [`Self::validate`] checks for, plus warnings (unreachable links, loops
+    /// without a step limit) and the loops runs can take (see [`validate`]).
+    pub fn validation_report(&self) -> ValidationReport {
+        let branches: Vec<validate::Edge> =
+            self.branches.iter().map(|b| validate::Edge { source: b.source, target: b.target, always: b.always }).collect();
+        let error_jumps: Vec<(usize, usize)> = (0..self.links.len())
+            .filter_map(|link| match self.error_route(link) {
+                ErrorRoute::Jump(handler) => Some((link, handler)),
+                _ => None,
+            })
+            .collect();
+        validate::validate(&self.input_keys, &self.specs, &branches, &error_jumps, self.limits.max_steps.is_some())
     }
     pub fn use_middleware(&mut self, mw: Arc<dyn crate::middleware::Middleware<T>>) {
         self.middleware.push(mw);
     }
+    /// Await the hooks of all middleware concurrently instead of one after another (see
+    /// [`MiddlewareExecution`]).
+    pub fn set_middleware_execution(&mut self, execution: MiddlewareExecution) {
+        self.middleware_execution = execution;
+    }
+    pub fn middleware_execution(&self) -> MiddlewareExecution {
+        self.middleware_execution
+    }
+    /// Attach `mw`, running each of its hooks only when `predicate` holds for the context
+    /// the hook sees (e.g. a `debug` flag), so expensive middleware costs nothing otherwise.
+    pub fn use_middleware_if<F>(&mut self, mw: Arc<dyn crate::middleware::Middleware<T>>, predicate: F)
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.middleware.push(Arc::new(crate::middleware::ConditionalMiddleware::new(mw, predicate)));
+    }
+    /// Attach `mw` with its panics and run failures contained per `containment`, e.g. so a
+    /// broken metrics exporter only costs a warning (see `middleware::ContainedMiddleware`).
+    pub fn use_contained_middleware(&mut self, mw: Arc<dyn crate::middleware::Middleware<T>>, containment: crate::middleware::Containment)
+    where
+        T: Clone + Sync,
+    {
+        self.middleware.push(Arc::new(crate::middleware::ContainedMiddleware::new(mw, containment)));
+    }
+    /// Run `init` on [`Self::warm_up`], e.g. for a link that needs a connection pool.
+    pub fn add_initializer(&mut self, init: InitializeObj) {
+        self.initializers.push(init);
+    }
+    /// Initialize middleware (see `Middleware::initializer`), then registered initializers,
+    /// in order. Stops at the first error.
+    pub async fn warm_up(&self) -> std::io::Result<()> {
+        for mw in &self.middleware {
+            if let Some(init) = mw.initializer() {
+                init.init().await?;
+            }
+        }
+        for init in &self.initializers {
+            init.init().await?;
+        }
+        Ok(())
+    }
+    /// Run `hook` on [`Self::shutdown`], e.g. for a link that buffers writes.
+    pub fn add_shutdown(&mut self, hook: ShutdownObj) {
+        self.shutdown_hooks.push(hook);
+    }
+    /// Run registered shutdown hooks, then middleware ones (see `Middleware::shutdown_hook`),
+    /// each in reverse order: the mirror image of [`Self::warm_up`]. Every hook runs even if
+    /// an earlier one fails; the first error is returned.
+    pub async fn shutdown(&self) -> std::io::Result<()> {
+        let hooks = self.shutdown_hooks.iter().rev().map(|hook| hook.as_ref());
+        let mw_hooks = self.middleware.iter().rev().filter_map(|mw| mw.shutdown_hook());
+        let mut result = Ok(());
+        for hook in hooks.chain(mw_hooks) {
+            if let Err(e) = hook.shutdown().await {
+                tracing::warn!(error = %e, "shutdown hook failed");
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+        result
+    }
+    /// Deliver the final context of every run to `sink`.
+    /// Delivery failures are logged and do not fail the run.
+    pub fn pipe_to(&mut self, sink: SinkObj<T>) {
+        self.sinks.push(sink);
+    }
+    /// Hand every failed run to `sink`, with the link that failed and the error (see
+    /// [`crate::sinks::dead_letter`]). Delivery failures are logged.
+    pub fn dead_letter_to(&mut self, sink: DeadLetterSinkObj<T>) {
+        self.dead_letters.push(sink);
+    }
+    /// Replace the executor used for background work (defaults to `runtime::default_executor()`).
+    pub fn set_executor(&mut self, executor: ExecutorObj) {
+        self.executor = executor;
+    }
+    pub fn executor(&self) -> &ExecutorObj {
+        &self.executor
+    }
     pub fn link_count(&self) -> usize {
         self.links.len()
     }
+    /// Add `link` under `name`, so branches can refer to it with [`Self::connect_named`].
+    pub fn add_named_link(&mut self, name: impl Into<String>, link: LinkGeneric<T>) {
+        self.add_link_with(link, LinkSpec::new().name(name));
+    }
+    /// Position of the link named `name` (its `LinkSpec::name`).
+    pub fn link_position(&self, name: &str) -> Option<usize> {
+        self.specs.iter().position(|spec| spec.name.as_deref() == Some(name))
+    }
+    pub fn link_name(&self, link: usize) -> Option<&str> {
+        self.specs.get(link)?.name.as_deref()
+    }
+    /// Like [`Self::connect`], between links given by name, so the branch keeps pointing at
+    /// the right links when others are added before them.
+    pub fn connect_named<F>(&mut self, source: &str, target: &str, condition: F) -> Result<(), ValidationError>
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        let position = |name: &str| self.link_position(name).ok_or_else(|| ValidationError::UnknownLink(name.to_string()));
+        let (source, target) = (position(source)?, position(target)?);
+        self.connect(source, target, condition);
+        Ok(())
+    }
     pub fn connect<F>(&mut self, source: usize, target: usize, condition: F)
     where
         F: Fn(&T) -> bool + Send + Sync + 'static,
@@ -52,28 +393,830 @@ impl<T: 'static + Send> ChainGeneric<T> {
             source,
             target,
             condition: Arc::new(condition),
+            always: false,
+            description: None,
         });
     }
+    /// [`Self::connect`], with a description of the condition shown by [`Self::topology`].
+    pub fn connect_described<F>(&mut self, source: usize, target: usize, description: impl Into<String>, condition: F)
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.connect(source, target, condition);
+        if let Some(branch) = self.branches.last_mut() {
+            branch.description = Some(description.into());
+        }
+    }
+    /// Always continue at `target` after link `source`, unless an earlier branch of
+    /// `source` is taken. Unlike `connect(source, target, |_| true)`, validation knows the
+    /// branch is always taken (see [`validate`]).
+    pub fn jump(&mut self, source: usize, target: usize) {
+        self.branches.push(Branch { source, target, condition: Arc::new(|_: &T| true), always: true, description: None });
+    }
+    /// Where runs go when a fallible link fails, unless [`Self::route_errors`] says
+    /// otherwise for that link (see [`fallible`]). Aborting by default.
+    pub fn set_error_route(&mut self, route: ErrorRoute) {
+        self.error_route = route;
+    }
+    /// Where runs go when the fallible link at position `link` fails.
+    pub fn route_errors(&mut self, link: usize, route: ErrorRoute) {
+        self.error_routes.insert(link, route);
+    }
+    pub fn error_route(&self, link: usize) -> ErrorRoute {
+        self.error_routes.get(&link).copied().unwrap_or(self.error_route)
+    }
+    /// Take a permit from `limit` at the start of every run (see [`rate_limit`]).
+    pub fn set_rate_limit(&mut self, limit: RateLimit) {
+        self.rate_limit = Some(limit);
+    }
+    /// Take a permit from `limit` before every call of the link at position `link`.
+    pub fn rate_limit_link(&mut self, link: usize, limit: RateLimit) {
+        self.link_rate_limits.insert(link, limit);
+    }
+    /// Record per-link call counts, durations, and errors (see [`metrics`]).
+    pub fn enable_metrics(&mut self) {
+        self.metrics.get_or_insert_with(MetricsCollector::default);
+    }
+    /// Metrics recorded so far; `None` unless [`Self::enable_metrics`] was called.
+    pub fn metrics(&self) -> Option<ChainMetrics> {
+        self.metrics.as_ref().map(MetricsCollector::snapshot)
+    }
+    /// The retry policy of the link at position `link`, if it has one.
+    pub fn retry_policy(&self, link: usize) -> Option<&RetryPolicy> {
+        self.retries.as_ref()?.policy(link)
+    }
     pub async fn run(&self, ctx: T) -> T {
+        self.run_with_report(ctx).await.0
+    }
+    /// Run the chain, returning the final context, or the error the run failed with.
+    pub async fn try_run(&self, ctx: T) -> Result<T, RunError> {
+        match self.run_with_report(ctx).await {
+            (_, RunReport { status: RunStatus::Failed(err), .. }) => Err(err),
+            (ctx, _) => Ok(ctx),
+        }
+    }
+    /// Same as [`Self::run_with_report`]: the final context plus status, timings, branches
+    /// taken, retries, and warnings of the run.
+    pub async fn run_report(&self, ctx: T) -> (T, RunReport) {
+        self.run_with_report(ctx).await
+    }
+    /// Run the chain and return the final context with its completion report.
+    /// Child runs spawned with `ctx_tools::spawn_child` are awaited before this returns,
+    /// and are aborted if this future is dropped first.
+    pub async fn run_with_report(&self, ctx: T) -> (T, RunReport) {
+        self.run_from(ctx, 0, None).await
+    }
+    // Run starting at link `start`; with a `run_id`, checkpoint after every link.
+    async fn run_from(&self, ctx: T, start: usize, run_id: Option<&str>) -> (T, RunReport) {
+        self.run_in(self.new_scope(), ctx, start, run_id, None, &self.middleware).await
+    }
+    /// Run with `extra` middleware attached for this run only, after the chain's own, e.g.
+    /// a verbose tracer for a request that asked for one. Other runs of the chain are not
+    /// affected. Initializers and shutdown hooks of `extra` are not run.
+    pub async fn run_with(&self, ctx: T, extra: &[Arc<dyn crate::middleware::Middleware<T>>]) -> (T, RunReport) {
+        if extra.is_empty() {
+            return self.run_with_report(ctx).await;
+        }
+        let mut middleware = self.middleware_buffers.take(Vec::new);
+        middleware.extend(self.middleware.iter().chain(extra).cloned());
+        let outcome = self.run_in(self.new_scope(), ctx, 0, None, None, &middleware).await;
+        middleware.clear();
+        self.middleware_buffers.put(middleware);
+        outcome
+    }
+    /// Run until `token` is cancelled (see [`cancel`]): the run stops before the next link
+    /// and reports `RunStatus::Cancelled`.
+    pub async fn run_with_cancel(&self, ctx: T, token: &CancellationToken) -> (T, RunReport) {
+        let scope = self.new_scope();
+        scope.set_cancellation(token.clone());
+        self.run_in(scope, ctx, 0, None, None, &self.middleware).await
+    }
+    /// Run without side effects (see [`shadow`]): links tagged with `LinkSpec::side_effects`
+    /// are skipped or replaced by the mocks in `shadow`, which records each of them. The
+    /// result is neither delivered to sinks nor published to subscribers.
+    pub async fn run_shadow(&self, ctx: T, shadow: &Shadow<T>) -> (T, RunReport) {
+        let scope = self.new_scope();
+        scope.set_shadow();
+        self.run_in(scope, ctx, 0, None, Some(shadow), &self.middleware).await
+    }
+    /// Run the chain on every context of `input`, up to `max_concurrent` runs at a time
+    /// (0 counts as 1), yielding each final context as its run completes, so not in input
+    /// order. Like [`Self::run`], failed runs yield their context as it was when they
+    /// stopped; subscribe (see [`Self::subscribe`]) or use middleware to see their reports.
+    pub fn run_stream<'a, S>(&'a self, input: S, max_concurrent: usize) -> impl Stream<Item = T> + 'a
+    where
+        S: Stream<Item = T> + 'a,
+    {
+        input.map(move |ctx| self.run(ctx)).buffer_unordered(max_concurrent.max(1))
+    }
+    /// Run the chain on every context of `batch`, up to `max_concurrent` runs at a time
+    /// (0 counts as 1), returning the outcome of each as [`Self::try_run`] would, in the
+    /// order of `batch`. A failed run does not stop the others.
+    pub async fn run_all(&self, batch: Vec<T>, max_concurrent: usize) -> Vec<Result<T, RunError>> {
+        futures::stream::iter(batch).map(|ctx| self.try_run(ctx)).buffered(max_concurrent.max(1)).collect().await
+    }
+    /// Start a run on `ctx` that advances one link per `ChainStepper::step` (see [`stepper`]).
+    pub fn stepper(&self, ctx: T) -> ChainStepper<'_, T> {
+        ChainStepper::new(self, ctx)
+    }
+    /// The links a run on `ctx` would execute, in order, without executing any: links are
+    /// no-ops, so every branch condition is evaluated against `ctx` as given. Middleware
+    /// is not called and error routes are not followed. Since the context never changes,
+    /// a loop whose condition holds would never end; the walk stops before visiting a link
+    /// a second time.
+    pub fn dry_run(&self, ctx: &T) -> Vec<PathStep> {
+        let mut path: Vec<PathStep> = Vec::new();
         let mut idx = 0;
+        while idx < self.links.len() && !path.iter().any(|step| step.link == idx) {
+            let end = self.group_end(idx);
+            path.extend((idx..end).map(|link| PathStep { link, name: self.specs[link].name.clone(), branch: None }));
+            match self.branches.iter().find(|b| b.source == end - 1 && (b.condition)(ctx)) {
+                Some(branch) => {
+                    path.last_mut().expect("a group has at least one link").branch = Some(branch.target);
+                    idx = branch.target;
+                }
+                None => idx = end,
+            }
+        }
+        path
+    }
+    // Run with `middleware` (the chain's, plus any given for this run only).
+    async fn run_in(
+        &self,
+        scope: Arc<RunScope>,
+        ctx: T,
+        start: usize,
+        run_id: Option<&str>,
+        shadow: Option<&Shadow<T>>,
+        middleware: &[Arc<dyn crate::middleware::Middleware<T>>],
+    ) -> (T, RunReport) {
+        if run_id.is_some() && self.checkpoints.is_some() {
+            scope.set_durable();
+        }
+        scope.set_serialization_policy(self.serialization);
+        if let Some(state) = &self.state {
+            scope.set_state(state.clone());
+        }
+        let started = Instant::now();
+        let tracing = self.traces.as_ref().filter(|_| !scope.is_shadow());
+        let input = tracing.map(|tracing| (tracing.snapshot)(&ctx));
+        let run = async {
+            let ctx = scope.enter(self.run_links(ctx, &scope, start, run_id, shadow, middleware)).await;
+            if scope.is_cancelled() {
+                scope.cancel_children();
+            }
+            let children = scope.join_children().await;
+            (ctx, children)
+        };
+        let (ctx, children) = match (self.limits.max_wall_time, &self.limit_hooks) {
+            (Some(dur), Some(hooks)) => match runtime::timeout(self.executor.as_ref(), dur, run).await {
+                Some(done) => done,
+                None => {
+                    scope.fail(RunError::limit_exceeded(format!("run exceeded wall time limit of {:?}", dur)));
+                    scope.cancel_children();
+                    ((hooks.fallback)(), Vec::new())
+                }
+            },
+            _ => run.await,
+        };
+        let mut report = scope.report(scope.status(), started.elapsed(), children);
+        report.version = self.version.clone();
+        if let (Some(metrics), false) = (&self.metrics, scope.is_shadow()) {
+            metrics.record_run(&report);
+        }
+        if let (Some(tracing), Some(input)) = (tracing, input) {
+            tracing.recorder.record(ExecutionTrace {
+                chain: self.name.clone(),
+                version: self.version.clone(),
+                input,
+                steps: scope.take_trace(),
+                output: (tracing.snapshot)(&ctx),
+                status: report.status.clone(),
+                duration: report.duration,
+            });
+        }
+        let ctx = match (&report.status, &self.error_chain) {
+            (RunStatus::Failed(error), Some(handler)) if !scope.is_shadow() => {
+                let (ctx, handled) = Self::run_error_chain(handler, ctx, error.clone()).await;
+                if let RunStatus::Failed(err) = &handled.status {
+                    report.warnings.push(format!("error chain failed: {}", err));
+                }
+                report.children.push(handled);
+                ctx
+            }
+            _ => ctx,
+        };
+        if let (Some(run_id), Some(checkpoints), RunStatus::Completed) = (run_id, &self.checkpoints, &report.status) {
+            if let Err(e) = checkpoints.store.remove(run_id).await {
+                tracing::warn!(run_id, error = %e, "removing checkpoint failed");
+            }
+        }
+        if self.concurrent_middleware(middleware) {
+            join_all(middleware.iter().map(|mw| mw.on_run_end(&ctx, &report))).await;
+        } else {
+            for mw in middleware {
+                mw.on_run_end(&ctx, &report).await;
+            }
+        }
+        if !scope.is_shadow() {
+            for sink in &self.sinks {
+                let delivered = match self.journal {
+                    Some(_) => sink.deliver_changes(&ctx, &report.journal).await,
+                    None => sink.deliver(&ctx).await,
+                };
+                if let Err(e) = delivered {
+                    tracing::warn!(sink = sink.name(), error = %e, "sink delivery failed");
+                }
+            }
+            if let (RunStatus::Failed(error), false) = (&report.status, self.dead_letters.is_empty()) {
+                let step = error.path.last();
+                let failure = Failure {
+                    link: step.map(|step| step.link),
+                    link_name: step.and_then(|step| step.name.clone()),
+                    error: error.clone(),
+                    run_id: run_id.map(str::to_string),
+                };
+                for sink in &self.dead_letters {
+                    if let Err(e) = sink.dead_letter(&ctx, &failure).await {
+                        tracing::warn!(sink = sink.name(), error = %e, "dead-letter delivery failed");
+                    }
+                }
+            }
+            self.broadcaster.publish(&ctx, &report);
+        }
+        if let Some(scope) = scope.recycle() {
+            self.scopes.put(scope);
+        }
+        (ctx, report)
+    }
+    // Run the error chain (see [`on_error`]) on the context of a run that failed with `error`.
+    fn run_error_chain(handler: &ErrorChain<T>, ctx: T, error: RunError) -> futures::future::BoxFuture<'_, (T, RunReport)> {
+        Box::pin(async move {
+            let scope = handler.chain.new_scope();
+            scope.set_last_error(error);
+            handler.chain.run_in(scope, ctx, 0, None, None, &handler.chain.middleware).await
+        })
+    }
+    // A run scope from the pool (see [`pool`]), or a fresh one.
+    fn new_scope(&self) -> Arc<RunScope> {
+        self.scopes.take(|| RunScope::with_max_children(self.limits.max_children))
+    }
+    // A scope for links run outside of `run_in`, set up as a run would set it up.
+    fn scope_for_run(&self) -> Arc<RunScope> {
+        let scope = self.new_scope();
+        scope.set_serialization_policy(self.serialization);
+        if let Some(state) = &self.state {
+            scope.set_state(state.clone());
+        }
+        scope
+    }
+    /// How often runs reused pooled state instead of allocating (see [`pool`]).
+    pub fn pool_stats(&self) -> PoolStats {
+        self.scopes.stats()
+    }
+    /// Keep at most `capacity` idle runs' state for reuse (64 by default); 0 turns
+    /// pooling off (see [`pool`]).
+    pub fn set_pool_capacity(&mut self, capacity: usize) {
+        self.scopes = Pool::new(capacity);
+        self.middleware_buffers = Pool::new(capacity);
+    }
+    /// Receive the outcome (final context + report) of every run completed after this call.
+    /// Slow subscribers skip outcomes once their buffer is full instead of stalling runs.
+    pub fn subscribe(&self) -> mpsc::Receiver<RunOutcome<T>>
+    where
+        T: Clone,
+    {
+        self.subscribe_with_capacity(DEFAULT_SUBSCRIBER_CAPACITY)
+    }
+    pub fn subscribe_with_capacity(&self, capacity: usize) -> mpsc::Receiver<RunOutcome<T>>
+    where
+        T: Clone,
+    {
+        self.broadcaster.subscribe(capacity)
+    }
+    async fn run_links(
+        &self,
+        ctx: T,
+        scope: &RunScope,
+        start: usize,
+        run_id: Option<&str>,
+        shadow: Option<&Shadow<T>>,
+        middleware: &[Arc<dyn crate::middleware::Middleware<T>>],
+    ) -> T {
+        let mut idx = start;
         let mut ctx = ctx;
+        for mw in middleware {
+            ctx = mw.on_run_start(ctx).await;
+        }
+        if scope.failure().is_some() || !self.within_context_limit(&ctx, scope) {
+            return ctx;
+        }
+        if let Some(limit) = &self.rate_limit {
+            if !self.admit(limit, scope, "chain").await {
+                return ctx;
+            }
+        }
+        // A fresh durable run is checkpointed before its first link, so it can be retried
+        // even if that link fails
+        if start == 0 && !self.save_checkpoint(self.checkpoint_at(run_id, idx, &ctx, scope), scope).await {
+            return ctx;
+        }
+        let mut steps = 0;
         while idx < self.links.len() {
-            for mw in &self.middleware {
-                mw.before(&ctx).await;
+            if scope.is_cancelled() {
+                break;
             }
-            ctx = (self.links[idx].clone())(ctx).await;
-            for mw in &self.middleware {
-                mw.after(&ctx).await;
+            let end = self.group_end(idx);
+            let last = end - 1;
+            steps += end - idx;
+            if let Some(max) = self.limits.max_steps.filter(|max| steps > *max) {
+                scope.fail(RunError::limit_exceeded(format!("run exceeded {} link steps", max)));
+                break;
             }
-            // Check for branch
-            if let Some(branch) = self.branches.iter().find(|b| b.source == idx && (b.condition)(&ctx)) {
+            if self.concurrent_middleware(middleware) {
+                join_all(middleware.iter().map(|mw| mw.before(&ctx))).await;
+            } else {
+                for mw in middleware {
+                    mw.before(&ctx).await;
+                }
+            }
+            // A failed run stops before the next link (see `ctx_tools::fail_run`)
+            if scope.failure().is_some() {
+                break;
+            }
+            let mut admitted = true;
+            for link in idx..end {
+                if let Some(limit) = self.link_rate_limits.get(&link) {
+                    admitted = admitted && self.admit(limit, scope, &format!("link {}", link)).await;
+                }
+            }
+            if !admitted {
+                break;
+            }
+            let before = self.journal.as_ref().map(|snapshot| snapshot(&ctx));
+            let traced = self.traces.as_ref().filter(|_| !scope.is_shadow()).map(|tracing| ((tracing.snapshot)(&ctx), Instant::now()));
+            match &self.concurrent {
+                Some(concurrent) if end - idx > 1 => ctx = self.run_group(ctx, idx..end, scope, shadow, concurrent).await,
+                _ => {
+                    scope.enter_link(idx, self.specs[idx].name.clone());
+                    let link_started = Instant::now();
+                    if let Some(link) = self.link_at(idx, &ctx, shadow) {
+                        ctx = self.call_link(idx, link, ctx, scope).await;
+                    }
+                    scope.exit_link(link_started.elapsed());
+                }
+            }
+            if let (Some(before), Some(snapshot)) = (before, &self.journal) {
+                scope.record_changes(journal::diff(idx, &before, &snapshot(&ctx)));
+            }
+            if let (Some((before, started)), Some(tracing)) = (traced, &self.traces) {
+                scope.record_trace_step(TraceStep {
+                    link: idx,
+                    end,
+                    name: self.specs[idx].name.clone(),
+                    before,
+                    after: (tracing.snapshot)(&ctx),
+                    duration: started.elapsed(),
+                    branch: None,
+                });
+            }
+            if self.concurrent_middleware(middleware) {
+                join_all(middleware.iter().map(|mw| mw.after(&ctx))).await;
+            } else {
+                for mw in middleware {
+                    mw.after(&ctx).await;
+                }
+            }
+            let handler = scope.take_link_error().and_then(|err| self.route_link_error(last, err, scope));
+            if scope.failure().is_some() || !self.within_context_limit(&ctx, scope) {
+                break;
+            }
+            // An error handler takes precedence over branches
+            if let Some(handler) = handler {
+                scope.take_branch(handler);
+                idx = handler;
+            } else if let Some(branch) = self.branches.iter().find(|b| b.source == last && (b.condition)(&ctx)) {
+                scope.take_branch(branch.target);
                 idx = branch.target;
             } else {
-                idx += 1;
+                idx = end;
+            }
+            if !self.save_checkpoint(self.checkpoint_at(run_id, idx, &ctx, scope), scope).await {
+                break;
+            }
+            // A parked run continues from the checkpoint just saved (see `Scheduler`)
+            if scope.parked().is_some() || scope.awaiting_event().is_some() {
+                break;
             }
         }
         ctx
     }
+    // The link to run at `idx`: shadow runs skip side-effecting links, or run their mocks.
+    // With an error chain, panics are caught (see [`on_error`]).
+    fn link_at(&self, idx: usize, ctx: &T, shadow: Option<&Shadow<T>>) -> Option<LinkGeneric<T>> {
+        let link = match shadow.filter(|_| self.specs[idx].side_effects) {
+            Some(shadow) => shadow.intercept(idx, &self.specs[idx], ctx),
+            None => Some(self.links[idx].clone()),
+        }?;
+        match &self.error_chain {
+            Some(handler) => Some(on_error::guard(link, handler.clone)),
+            None => Some(link),
+        }
+    }
+    // Call `link` (at `idx`), calling it again on its input while it fails and its retry
+    // policy allows (see [`retry`]).
+    async fn call_link(&self, idx: usize, link: LinkGeneric<T>, ctx: T, scope: &RunScope) -> T {
+        let Some((retries, policy)) = self.retries.as_ref().and_then(|retries| Some((retries, retries.policy(idx)?))) else {
+            return link(ctx).await;
+        };
+        let mut ctx = ctx;
+        let mut attempt = 1;
+        loop {
+            let input = (attempt < policy.max_attempts).then(|| (retries.clone)(&ctx));
+            let output = link(ctx).await;
+            let failed = scope.failure().or_else(|| scope.link_error()).filter(retry::is_retryable);
+            match (input, failed) {
+                (Some(input), Some(err)) => {
+                    scope.clear_failure();
+                    scope.take_link_error();
+                    scope.record_retry();
+                    scope.warn(format!("link {} failed on attempt {}, retrying: {}", idx, attempt, err));
+                    self.executor.sleep(policy.jittered_delay(attempt)).await;
+                    ctx = input;
+                    attempt += 1;
+                }
+                _ => {
+                    if attempt > 1 {
+                        scope.record_attempts(idx, attempt);
+                    }
+                    return output;
+                }
+            }
+        }
+    }
+    // End of the group of commutative links starting at `idx` (see [`concurrent`]);
+    // `idx + 1` when the link runs on its own.
+    fn group_end(&self, idx: usize) -> usize {
+        let mut end = idx + 1;
+        if self.concurrent.is_some() {
+            while end < self.links.len()
+                && self.specs[end - 1].commutative
+                && self.specs[end].commutative
+                && !self.branches.iter().any(|b| b.source == end - 1)
+            {
+                end += 1;
+            }
+        }
+        end
+    }
+    // Run the links in `group` concurrently on clones of `ctx` and merge their results.
+    async fn run_group(&self, ctx: T, group: std::ops::Range<usize>, scope: &RunScope, shadow: Option<&Shadow<T>>, concurrent: &ConcurrentLinks<T>) -> T {
+        let runs = group.clone().map(|idx| {
+            let (link, input) = (self.link_at(idx, &ctx, shadow), (concurrent.clone)(&ctx));
+            async move {
+                let started = Instant::now();
+                let output = match link {
+                    Some(link) => link(input).await,
+                    None => input,
+                };
+                (output, started.elapsed())
+            }
+        });
+        let results = join_all(runs).await;
+        let mut outputs = Vec::with_capacity(results.len());
+        for (idx, (output, took)) in group.zip(results) {
+            scope.enter_link(idx, self.specs[idx].name.clone());
+            scope.exit_link(took);
+            outputs.push(output);
+        }
+        (concurrent.merge)(ctx, outputs)
+    }
+    fn concurrent_middleware(&self, middleware: &[Arc<dyn crate::middleware::Middleware<T>>]) -> bool {
+        self.middleware_execution == MiddlewareExecution::Concurrent && middleware.len() > 1
+    }
+    // Route the error fallible link `idx` returned; the handler to continue at, if any.
+    fn route_link_error(&self, idx: usize, err: RunError, scope: &RunScope) -> Option<usize> {
+        let route = self.error_route(idx);
+        if route == ErrorRoute::Abort {
+            scope.fail(err);
+            return None;
+        }
+        scope.warn(match route {
+            ErrorRoute::Jump(handler) => format!("link {} failed, continuing at {}: {}", idx, handler, err),
+            _ => format!("link {} failed, skipped: {}", idx, err),
+        });
+        if let (Some(metrics), false) = (&self.metrics, scope.is_shadow()) {
+            metrics.record_error(idx);
+        }
+        scope.set_last_error(err);
+        match route {
+            ErrorRoute::Jump(handler) => Some(handler),
+            _ => None,
+        }
+    }
+    // Take a permit from `limit` for `what`, waiting for it or failing the run as its mode
+    // says; `false` if the run failed.
+    async fn admit(&self, limit: &RateLimit, scope: &RunScope, what: &str) -> bool {
+        match limit.mode {
+            RateLimitMode::Wait => {
+                let wait = limit.limiter.reserve();
+                if !wait.is_zero() {
+                    self.executor.sleep(wait).await;
+                }
+                true
+            }
+            RateLimitMode::Reject => match limit.limiter.try_acquire() {
+                Ok(()) => true,
+                Err(wait) => {
+                    scope.fail(RunError::rate_limited(format!("{} is over its rate limit, next permit in {:?}", what, wait)));
+                    false
+                }
+            },
+        }
+    }
+    // The checkpoint at link `idx` of a durable run.
+    fn checkpoint_at(&self, run_id: Option<&str>, idx: usize, ctx: &T, scope: &RunScope) -> Option<Checkpoint> {
+        let (run_id, checkpoints) = (run_id?, self.checkpoints.as_ref()?);
+        Some(Checkpoint {
+            run_id: run_id.to_string(),
+            version: self.version.clone(),
+            link: idx,
+            link_name: self.specs.get(idx).and_then(|spec| spec.name.clone()),
+            ctx: (checkpoints.snapshot)(ctx),
+            wake_at_ms: scope.parked(),
+            awaiting_event: scope.awaiting_event(),
+        })
+    }
+    // Save `checkpoint`, if any; `false` (and the run failed) if the store could not save it.
+    async fn save_checkpoint(&self, checkpoint: Option<Checkpoint>, scope: &RunScope) -> bool {
+        let (Some(checkpoint), Some(checkpoints)) = (checkpoint, &self.checkpoints) else {
+            return true;
+        };
+        if let Err(e) = checkpoints.store.save(&checkpoint).await {
+            scope.fail(RunError::internal(format!("saving checkpoint failed: {}", e)));
+            return false;
+        }
+        true
+    }
+    fn within_context_limit(&self, ctx: &T, scope: &RunScope) -> bool {
+        let (Some(max), Some(hooks)) = (self.limits.max_context_bytes, &self.limit_hooks) else {
+            return true;
+        };
+        let size = (hooks.size)(ctx);
+        if size > max {
+            scope.fail(RunError::limit_exceeded(format!("context is {} bytes, limit is {}", size, max)));
+            return false;
+        }
+        true
+    }
+}
+
+impl<T: 'static + Send + Sync> ChainGeneric<T> {
+    /// A link running `chain` as a single step: the context goes through all of its links,
+    /// middleware, and branches, and continues with the result. A failed sub-run fails the
+    /// parent run with its error, and its warnings are added to the parent's; cancelling the
+    /// parent run cancels the sub-run. Sinks and subscribers of `chain` see each sub-run as
+    /// a run of its own.
+    pub fn as_link(chain: Arc<Self>) -> LinkGeneric<T> {
+        Arc::new(move |ctx: T| {
+            let chain = chain.clone();
+            Box::pin(async move {
+                let (ctx, report) = match RunScope::current().and_then(|scope| scope.cancellation()) {
+                    Some(token) => chain.run_with_cancel(ctx, &token).await,
+                    None => chain.run_with_report(ctx).await,
+                };
+                if let Some(scope) = RunScope::current() {
+                    for warning in report.warnings {
+                        scope.warn(warning);
+                    }
+                    if let RunStatus::Failed(err) = report.status {
+                        scope.fail(err);
+                    }
+                }
+                ctx
+            })
+        })
+    }
+    /// Embed `chain` as the next step (see [`Self::as_link`]). The step is named after the
+    /// chain, requires its declared input, provides what its links provide, and is tagged
+    /// with side effects when any of its links is, so shadow runs skip it.
+    pub fn add_chain(&mut self, chain: Arc<Self>) {
+        let provides: std::collections::BTreeSet<&String> = chain.specs.iter().flat_map(|spec| &spec.provides).collect();
+        let mut spec = LinkSpec::new().requires(&chain.input_keys).provides(provides);
+        if let Some(name) = &chain.name {
+            spec = spec.name(name);
+        }
+        if chain.specs.iter().any(|spec| spec.side_effects) {
+            spec = spec.side_effects();
+        }
+        self.add_link_with(Self::as_link(chain), spec);
+    }
+}
+
+impl<T: 'static + Send + Clone> ChainGeneric<T> {
+    /// Run consecutive links tagged with `LinkSpec::commutative` concurrently, combining
+    /// the original context and their results (in link order) with `merge`, e.g.
+    /// `links::combinators::merge_changes` (see [`concurrent`]).
+    pub fn enable_concurrent_links<M>(&mut self, merge: M)
+    where
+        M: Fn(T, Vec<T>) -> T + Send + Sync + 'static,
+    {
+        self.concurrent = Some(ConcurrentLinks { clone: T::clone, merge: Arc::new(merge) });
+    }
+    /// Retry every failing link with `policy` (see [`retry`]), unless [`Self::retry_link`]
+    /// gave it a policy of its own.
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retries_mut().default = Some(policy);
+    }
+    /// Retry the link at position `link` with `policy`; `RetryPolicy::new(1)` exempts it
+    /// from the chain-wide policy.
+    pub fn retry_link(&mut self, link: usize, policy: RetryPolicy) {
+        self.retries_mut().links.insert(link, policy);
+    }
+    /// Route the context of every failed run, panics included, through `chain` (see
+    /// [`on_error`]); the run returns that chain's result.
+    pub fn on_error(&mut self, chain: Arc<Self>) {
+        self.error_chain = Some(ErrorChain { chain, clone: T::clone });
+    }
+    fn retries_mut(&mut self) -> &mut Retries<T> {
+        self.retries.get_or_insert_with(|| Retries { clone: T::clone, default: None, links: HashMap::new() })
+    }
+    /// Add a link that returns `Result`; on `Err` the run follows [`Self::error_route`] of
+    /// the link (see [`fallible`]).
+    pub fn add_fallible_link(&mut self, link: FallibleLinkGeneric<T>) {
+        self.add_fallible_link_with(link, LinkSpec::default());
+    }
+    pub fn add_fallible_link_with(&mut self, link: FallibleLinkGeneric<T>, spec: LinkSpec) {
+        self.add_link_with(fallible::wrap(link), spec);
+    }
+    /// Add `links` as a single step running them concurrently, combining their results with
+    /// `merge` (see `links::combinators::parallel`). For `Context`, `merge_changes` keeps
+    /// every link's changes.
+    pub fn add_parallel<M>(&mut self, links: Vec<LinkGeneric<T>>, merge: M)
+    where
+        M: Fn(T, Vec<T>) -> T + Send + Sync + 'static,
+    {
+        self.add_link(crate::links::parallel(links, merge));
+    }
+}
+
+impl<T: 'static + Send + Serialize + Default> ChainGeneric<T> {
+    /// Enforce `limits` on every run (see [`ResourceLimits`]).
+    pub fn set_limits(&mut self, limits: ResourceLimits) {
+        self.limits = limits;
+        self.limit_hooks = Some(LimitHooks::new());
+        // Pooled scopes carry the old child limit
+        self.scopes.clear();
+    }
+}
+
+impl<T: 'static + Send + Serialize> ChainGeneric<T> {
+    /// Record the key-level changes each link makes in `RunReport::journal`, and deliver
+    /// them to sinks with `BaseSink::deliver_changes` (see [`journal`]).
+    pub fn enable_journal(&mut self) {
+        self.journal = Some(Arc::new(journal::snapshot::<T>));
+    }
+    /// Record an [`ExecutionTrace`] of every run into `recorder` (see [`trace`]).
+    pub fn record_traces(&mut self, recorder: Arc<TraceRecorder>) {
+        self.traces = Some(Tracing { recorder, snapshot: trace::snapshot::<T> });
+    }
+}
+
+impl<T: 'static + Send + Serialize + DeserializeOwned> ChainGeneric<T> {
+    /// Save checkpoints of runs started with [`Self::run_durable`] to `store`.
+    pub fn enable_checkpoints(&mut self, store: CheckpointStoreObj) {
+        self.checkpoints = Some(Checkpointing {
+            store,
+            snapshot: Arc::new(|ctx: &T| serde_json::to_value(ctx).unwrap_or_default()),
+            restore: Arc::new(|value| serde_json::from_value(value).map_err(|e| e.to_string())),
+            migrate: None,
+        });
+    }
+    /// Rewrite checkpoints written by another chain version before resuming them.
+    /// Has no effect until checkpoints are enabled.
+    pub fn on_version_change<F>(&mut self, migrate: F)
+    where
+        F: Fn(Checkpoint) -> Result<Checkpoint, String> + Send + Sync + 'static,
+    {
+        if let Some(checkpoints) = &mut self.checkpoints {
+            checkpoints.migrate = Some(Arc::new(migrate));
+        }
+    }
+    /// Run with a checkpoint after every link, so [`Self::resume`] can continue the run
+    /// under `run_id` after a restart. Without checkpoints enabled this is a plain run.
+    pub async fn run_durable(&self, run_id: &str, ctx: T) -> (T, RunReport) {
+        self.run_from(ctx, 0, Some(run_id)).await
+    }
+    /// Continue a durable run from its last checkpoint, migrating it first if it was
+    /// written by another chain version. Fails with `NotFound` when there is no checkpoint.
+    pub async fn resume(&self, run_id: &str) -> std::io::Result<(T, RunReport)> {
+        let checkpoints = self.checkpoints.as_ref().ok_or_else(|| std::io::Error::other("checkpoints are not enabled"))?;
+        let checkpoint = checkpoints.store.load(run_id).await?.ok_or_else(|| {
+            std::io::Error::new(std::io::ErrorKind::NotFound, format!("no checkpoint for run '{}'", run_id))
+        })?;
+        self.resume_from(checkpoint).await
+    }
+    /// Continue the run saved in `checkpoint` (see [`Self::resume`]).
+    pub async fn resume_from(&self, checkpoint: Checkpoint) -> std::io::Result<(T, RunReport)> {
+        let run_id = checkpoint.run_id.clone();
+        let (link, ctx) = self.migrate_checkpoint(checkpoint)?;
+        let ctx = self.restore_checkpoint(ctx)?;
+        Ok(self.run_from(ctx, link, Some(&run_id)).await)
+    }
+    /// Re-run a durable run from the link named `step` (a `LinkSpec::name`), e.g. after
+    /// fixing the data or the code that made it fail. The context is the one in the run's
+    /// checkpoint (migrated like [`Self::resume`]), with `patch` applied as a JSON merge
+    /// patch: its fields replace those in the context and `null` fields are removed.
+    pub async fn retry_from_step(&self, run_id: &str, step: &str, patch: Option<serde_json::Value>) -> std::io::Result<(T, RunReport)> {
+        use std::io::{Error, ErrorKind};
+        let checkpoints = self.checkpoints.as_ref().ok_or_else(|| Error::other("checkpoints are not enabled"))?;
+        let checkpoint = checkpoints.store.load(run_id).await?.ok_or_else(|| {
+            Error::new(ErrorKind::NotFound, format!("no checkpoint for run '{}'", run_id))
+        })?;
+        let link = self.specs.iter().position(|spec| spec.name.as_deref() == Some(step)).ok_or_else(|| {
+            Error::new(ErrorKind::InvalidInput, format!("chain has no link named '{}'", step))
+        })?;
+        let (_, mut ctx) = self.migrate_checkpoint(checkpoint)?;
+        if let Some(patch) = patch {
+            if !patch.is_object() {
+                return Err(Error::new(ErrorKind::InvalidInput, "context patch must be a JSON object"));
+            }
+            checkpoint::merge_patch(&mut ctx, patch);
+        }
+        let ctx = self.restore_checkpoint(ctx)?;
+        Ok(self.run_from(ctx, link, Some(run_id)).await)
+    }
+    /// Execute the steps of `trace` again, each on its recorded input (see [`trace`]).
+    /// Fails with `InvalidInput` when a step names links this chain does not have or its
+    /// context does not deserialize.
+    pub async fn replay(&self, trace: &ExecutionTrace) -> std::io::Result<Replay> {
+        let invalid = |msg: String| std::io::Error::new(std::io::ErrorKind::InvalidInput, msg);
+        let mut steps = Vec::with_capacity(trace.steps.len());
+        for step in &trace.steps {
+            if step.link >= step.end || step.end > self.links.len() {
+                return Err(invalid(format!("trace step {}..{} is not in this chain of {} links", step.link, step.end, self.links.len())));
+            }
+            let name = self.specs[step.link].name.clone();
+            if self.specs[step.link..step.end].iter().any(|spec| spec.side_effects) {
+                steps.push(ReplayStep { link: step.link, name, recorded: step.after.clone(), replayed: step.after.clone(), error: None, skipped: true });
+                continue;
+            }
+            let ctx: T = serde_json::from_value(step.before.clone()).map_err(|e| invalid(format!("trace step at link {}: {}", step.link, e)))?;
+            let scope = self.scope_for_run();
+            let run = async {
+                match &self.concurrent {
+                    Some(concurrent) if step.end - step.link > 1 => self.run_group(ctx, step.link..step.end, &scope, None, concurrent).await,
+                    _ => match self.link_at(step.link, &ctx, None) {
+                        Some(link) => self.call_link(step.link, link, ctx, &scope).await,
+                        None => ctx,
+                    },
+                }
+            };
+            let ctx = scope.enter(run).await;
+            let error = scope.failure().or_else(|| scope.take_link_error());
+            let replayed = trace::snapshot(&ctx);
+            if let Some(scope) = scope.recycle() {
+                self.scopes.put(scope);
+            }
+            steps.push(ReplayStep { link: step.link, name, recorded: step.after.clone(), replayed, error, skipped: false });
+        }
+        Ok(Replay { steps })
+    }
+    // The link to continue at and the context of `checkpoint`, migrated to this version.
+    fn migrate_checkpoint(&self, checkpoint: Checkpoint) -> std::io::Result<(usize, serde_json::Value)> {
+        use std::io::{Error, ErrorKind};
+        let checkpoints = self.checkpoints.as_ref().ok_or_else(|| Error::other("checkpoints are not enabled"))?;
+        if checkpoint.version == self.version {
+            return Ok((checkpoint.link, checkpoint.ctx));
+        }
+        let from = checkpoint.version.clone().unwrap_or_default();
+        let checkpoint = match &checkpoints.migrate {
+            Some(migrate) => migrate(checkpoint)
+                .map_err(|e| Error::new(ErrorKind::InvalidData, format!("migrating checkpoint from version '{}': {}", from, e)))?,
+            None => checkpoint,
+        };
+        let link = match &checkpoint.link_name {
+            Some(name) => self.link_position(name).ok_or_else(|| {
+                Error::new(ErrorKind::InvalidData, format!("checkpoint link '{}' is not in this chain version", name))
+            })?,
+            None => checkpoint.link,
+        };
+        Ok((link, checkpoint.ctx))
+    }
+    fn restore_checkpoint(&self, ctx: serde_json::Value) -> std::io::Result<T> {
+        use std::io::{Error, ErrorKind};
+        let checkpoints = self.checkpoints.as_ref().ok_or_else(|| Error::other("checkpoints are not enabled"))?;
+        (checkpoints.restore)(ctx).map_err(|e| Error::new(ErrorKind::InvalidData, e))
+    }
+    pub(crate) fn checkpoint_store(&self) -> Option<&CheckpointStoreObj> {
+        self.checkpoints.as_ref().map(|checkpoints| &checkpoints.store)
+    }
+}
+
+impl<T: 'static + Send> Default for ChainGeneric<T> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 // Ergonomic defaults
@@ -0,0 +1,68 @@
+//! Error-handling chains.
+//!
+//! A chain given one with `ChainGeneric::on_error` hands the context of every run that
+//! fails, whether by `ctx_tools::fail_run`, a fallible link's `Err` routed to `Abort`, a
+//! limit, or a panic, to the error chain, and the run returns what that chain makes of
+//! it. The error chain's links read the failure with `ctx_tools::last_error`: its kind,
+//! message, and `path` (the failing link is the last step).
+//!
+//! With an error chain set, a link that panics fails the run with a `Panicked` error
+//! instead of unwinding through the caller, and the run continues to the error chain with
+//! the context the link was given. The run still reports `RunStatus::Failed` with the
+//! original error, so `try_run` and the listeners see the failure; the error chain's own
+//! report is added to the run's `children`. Shadow runs do not call the error chain.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, ErrorKind};
+//! use modulink_rs::context::Context;
+//! use modulink_rs::ctx_tools;
+//! use std::sync::Arc;
+//!
+//! let mut on_error = Chain::new();
+//! on_error.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+//!     let error = ctx_tools::last_error().unwrap();
+//!     ctx.insert("error", error.message).insert("compensated", true)
+//! })));
+//!
+//! let mut chain = Chain::new();
+//! chain.add_link(Arc::new(|_ctx: Context| Box::pin(async move { panic!("ledger unreachable") })));
+//! chain.on_error(Arc::new(on_error));
+//!
+//! let (ctx, report) = futures::executor::block_on(chain.run_with_report(Context::new()));
+//! assert_eq!(ctx.get::<String>("error").as_deref(), Some("ledger unreachable"));
+//! assert!(matches!(report.status, modulink_rs::chains::RunStatus::Failed(err) if err.kind == ErrorKind::Panicked));
+//! ```
+
+use super::{ChainGeneric, LinkGeneric, RunError, RunScope};
+use crate::runtime::panic_message;
+use futures::FutureExt;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+/// The error chain of a chain, and how it snapshots a link's input.
+pub(crate) struct ErrorChain<T> {
+    pub(crate) chain: Arc<ChainGeneric<T>>,
+    pub(crate) clone: fn(&T) -> T,
+}
+
+// `link`, failing the run with `Panicked` when it panics and passing its input on then.
+pub(crate) fn guard<T: Send + 'static>(link: LinkGeneric<T>, clone: fn(&T) -> T) -> LinkGeneric<T> {
+    Arc::new(move |ctx: T| {
+        let link = link.clone();
+        Box::pin(async move {
+            let input = clone(&ctx);
+            match AssertUnwindSafe(link(ctx)).catch_unwind().await {
+                Ok(ctx) => ctx,
+                Err(payload) => {
+                    let err = RunError::panicked(panic_message(payload));
+                    match RunScope::current() {
+                        Some(scope) => scope.fail(err),
+                        None => tracing::warn!(error = %err, "link panicked outside of a run"),
+                    }
+                    input
+                }
+            }
+        })
+    })
+}
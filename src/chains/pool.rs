@@ -0,0 +1,76 @@
+//! Reuse of per-run state across runs of the same chain.
+//!
+//! Every run needs a `RunScope`, and a run with extra middleware (`ChainGeneric::run_with`)
+//! a vector of the middleware to call. Instead of allocating these per run, a chain keeps
+//! finished ones in a small pool and hands them to later runs, which cuts allocator
+//! traffic for chains triggered at high rates (e.g. behind an `HttpListener`). A scope's
+//! path and children buffers keep their capacity; what ends up in the `RunReport` (step
+//! timings, warnings, annotations, ...) and the execution trace is moved out, not copied.
+//! A scope is only reused when nothing else holds on to it once its run has finished.
+//! [`PoolStats`] (`ChainGeneric::pool_stats`) shows how well this works, and
+//! `ChainGeneric::set_pool_capacity` sizes or disables the pool.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// Idle scopes a chain keeps at most; runs beyond this many in parallel allocate.
+pub(crate) const DEFAULT_POOL_CAPACITY: usize = 64;
+
+/// Counters of a chain's pool of per-run state.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolStats {
+    /// Runs that had to allocate fresh state.
+    pub allocated: u64,
+    /// Runs that reused the state of an earlier run.
+    pub reused: u64,
+    /// State waiting in the pool for the next run.
+    pub idle: usize,
+}
+
+pub(crate) struct Pool<T> {
+    idle: Mutex<Vec<T>>,
+    capacity: usize,
+    allocated: AtomicU64,
+    reused: AtomicU64,
+}
+
+impl<T> Pool<T> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Pool { idle: Mutex::default(), capacity, allocated: AtomicU64::new(0), reused: AtomicU64::new(0) }
+    }
+
+    /// A pooled item, or a new one from `make` when the pool is empty.
+    pub(crate) fn take(&self, make: impl FnOnce() -> T) -> T {
+        match self.idle.lock().unwrap().pop() {
+            Some(item) => {
+                self.reused.fetch_add(1, Ordering::Relaxed);
+                item
+            }
+            None => {
+                self.allocated.fetch_add(1, Ordering::Relaxed);
+                make()
+            }
+        }
+    }
+
+    /// Return `item` for reuse; it is dropped if the pool is full.
+    pub(crate) fn put(&self, item: T) {
+        let mut idle = self.idle.lock().unwrap();
+        if idle.len() < self.capacity {
+            idle.push(item);
+        }
+    }
+
+    pub(crate) fn clear(&self) {
+        self.idle.lock().unwrap().clear();
+    }
+
+    pub(crate) fn stats(&self) -> PoolStats {
+        PoolStats {
+            allocated: self.allocated.load(Ordering::Relaxed),
+            reused: self.reused.load(Ordering::Relaxed),
+            idle: self.idle.lock().unwrap().len(),
+        }
+    }
+}
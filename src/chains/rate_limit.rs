@@ -0,0 +1,106 @@
+//! Rate limits on runs and links.
+//!
+//! A [`RateLimiter`] is a token bucket: it holds up to `burst` permits and refills at
+//! `rate` permits per second. `ChainGeneric::set_rate_limit` takes a permit at the start
+//! of every run, `ChainGeneric::rate_limit_link` before every call of one link. With
+//! [`RateLimitMode::Reject`] a run that finds no permit fails with
+//! `ErrorKind::RateLimited` (HTTP 429, so the HTTP listener answers over-limit requests
+//! with it); with [`RateLimitMode::Wait`] it waits its turn for the next permit instead.
+//!
+//! A limiter is shared through its `Arc`: give the same one to several chains or links
+//! to have them draw on one budget.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, ErrorKind, RateLimit, RateLimiter, RunStatus};
+//! use modulink_rs::context::Context;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let mut chain = Chain::new();
+//! chain.set_rate_limit(RateLimit::reject(Arc::new(RateLimiter::per_second(10.0).with_burst(1))));
+//! assert_eq!(chain.run_with_report(Context::new()).await.1.status, RunStatus::Completed);
+//! match chain.run_with_report(Context::new()).await.1.status {
+//!     RunStatus::Failed(err) => assert_eq!(err.kind, ErrorKind::RateLimited),
+//!     other => panic!("unexpected {:?}", other),
+//! }
+//! # });
+//! ```
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// A token bucket; see the [module docs](self).
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    // Permits available (negative when waiters have reserved ahead), as of the instant.
+    bucket: Mutex<(f64, Instant)>,
+}
+
+impl RateLimiter {
+    /// `rate` permits per second, with a burst of one second's worth (at least 1).
+    pub fn per_second(rate: f64) -> Self {
+        let burst = rate.max(1.0);
+        RateLimiter { rate: rate.max(f64::MIN_POSITIVE), burst, bucket: Mutex::new((burst, Instant::now())) }
+    }
+    /// Hold at most `burst` permits (at least 1); the bucket starts full.
+    pub fn with_burst(self, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        RateLimiter { burst, bucket: Mutex::new((burst, Instant::now())), ..self }
+    }
+    /// Take a permit if one is available; otherwise how long until one will be.
+    pub fn try_acquire(&self) -> Result<(), Duration> {
+        let mut bucket = self.refill();
+        if bucket.0 >= 1.0 {
+            bucket.0 -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - bucket.0) / self.rate))
+        }
+    }
+    /// Reserve the next permit, returning how long to wait before using it; zero when one
+    /// is available now.
+    pub fn reserve(&self) -> Duration {
+        let mut bucket = self.refill();
+        bucket.0 -= 1.0;
+        match bucket.0 {
+            left if left >= 0.0 => Duration::ZERO,
+            left => Duration::from_secs_f64(-left / self.rate),
+        }
+    }
+    fn refill(&self) -> std::sync::MutexGuard<'_, (f64, Instant)> {
+        let mut bucket = self.bucket.lock().unwrap();
+        let now = Instant::now();
+        let refilled = bucket.0 + now.duration_since(bucket.1).as_secs_f64() * self.rate;
+        *bucket = (refilled.min(self.burst), now);
+        bucket
+    }
+}
+
+/// What a run does when no permit is available.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RateLimitMode {
+    /// Fail the run with `ErrorKind::RateLimited` (the default).
+    #[default]
+    Reject,
+    /// Wait for the next permit.
+    Wait,
+}
+
+/// A limiter and what to do when it runs out.
+#[derive(Debug, Clone)]
+pub struct RateLimit {
+    pub limiter: Arc<RateLimiter>,
+    pub mode: RateLimitMode,
+}
+
+impl RateLimit {
+    pub fn reject(limiter: Arc<RateLimiter>) -> Self {
+        RateLimit { limiter, mode: RateLimitMode::Reject }
+    }
+    pub fn wait(limiter: Arc<RateLimiter>) -> Self {
+        RateLimit { limiter, mode: RateLimitMode::Wait }
+    }
+}
@@ -0,0 +1,89 @@
+//! Run reports: how a chain run ended, how long each step took, and any child runs it spawned.
+
+use super::error::RunError;
+use super::journal::Change;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Final status of a chain run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RunStatus {
+    /// All links ran to the end of the chain.
+    Completed,
+    /// The run was aborted or cancelled before finishing.
+    Cancelled,
+    /// The run panicked or was failed by a link or middleware.
+    Failed(RunError),
+    /// A durable run stopped at a wait and is checkpointed until `wake_at_ms` (epoch
+    /// milliseconds); a `Scheduler` resumes it then.
+    Parked { wake_at_ms: u64 },
+    /// A durable run stopped until the event `key` is delivered (see `std_links::await_event`).
+    AwaitingEvent { key: String },
+}
+
+/// Completion report for a chain run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunReport {
+    pub status: RunStatus,
+    /// `ChainGeneric::version` of the chain that ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Wall time of the whole run, including waiting for children.
+    #[serde(default)]
+    pub duration: Duration,
+    /// Every link executed, in order; a link run twice by a loop appears twice.
+    #[serde(default)]
+    pub steps: Vec<StepTiming>,
+    /// (source, target) of every branch taken, in order.
+    #[serde(default)]
+    pub branches: Vec<(usize, usize)>,
+    /// Retries recorded with `ctx_tools::record_retry`.
+    #[serde(default)]
+    pub retries: u32,
+    /// Calls of each link that was retried (see `chains::retry`), by position; the latest
+    /// execution counts for a link a loop ran more than once.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub attempts: BTreeMap<usize, u32>,
+    /// Non-fatal problems recorded with `ctx_tools::warn`.
+    #[serde(default)]
+    pub warnings: Vec<String>,
+    /// Key-level changes made by links, when `ChainGeneric::enable_journal` is on.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub journal: Vec<Change>,
+    /// Tags attached with `ctx_tools::annotate` / `Context::annotate`: run metadata that
+    /// is not business data, kept out of the context.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, Value>,
+    /// Reports of child runs started with `ctx_tools::spawn_child`, in spawn order.
+    pub children: Vec<RunReport>,
+}
+
+/// Time spent in one executed link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StepTiming {
+    pub link: usize,
+    /// `LinkSpec::name` of the link, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub duration: Duration,
+}
+
+impl RunReport {
+    pub fn new(status: RunStatus) -> Self {
+        RunReport {
+            status,
+            version: None,
+            duration: Duration::ZERO,
+            steps: Vec::new(),
+            branches: Vec::new(),
+            retries: 0,
+            attempts: BTreeMap::new(),
+            warnings: Vec::new(),
+            journal: Vec::new(),
+            annotations: BTreeMap::new(),
+            children: Vec::new(),
+        }
+    }
+}
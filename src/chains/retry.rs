@@ -0,0 +1,124 @@
+//! Link retries with backoff.
+//!
+//! Links calling flaky services can be given a [`RetryPolicy`], chain-wide with
+//! `ChainGeneric::set_retry_policy` or for one link with `ChainGeneric::retry_link`. When
+//! such a link fails (`ctx_tools::fail_run`, or `Err` from a fallible link), it is called
+//! again with the context it was given the first time, after the policy's backoff, until it
+//! succeeds or runs out of attempts. Only the last failure counts: it fails the run, or is
+//! routed like any fallible link error.
+//!
+//! Failures of kind `Unauthorized`, `Forbidden`, `InvalidInput`, and `QuotaExceeded` are
+//! not retried, as the same input fails the same way again. Links in a concurrent group (see
+//! [`super::concurrent`]) run once, since a failure there cannot be told apart per link.
+//!
+//! Every retry counts in `RunReport::retries` and is recorded as a run warning, and
+//! `RunReport::attempts` has the attempts of each link that needed more than one.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Backoff, Chain, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! let mut chain = Chain::new();
+//! chain.set_retry_policy(RetryPolicy {
+//!     max_attempts: 4,
+//!     backoff: Backoff::Exponential { initial: Duration::from_millis(100), max: Duration::from_secs(2) },
+//!     jitter: 0.2,
+//! });
+//! ```
+
+use super::error::{ErrorKind, RunError};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::time::Duration;
+
+/// Wait between two attempts of a link.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backoff {
+    /// The same wait before every retry.
+    Fixed(Duration),
+    /// `initial` before the first retry, doubling for each one after, up to `max`.
+    Exponential { initial: Duration, max: Duration },
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff::Fixed(Duration::ZERO)
+    }
+}
+
+/// How often, and how far apart, a failing link is tried.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Calls of the link at most, the first included; 0 and 1 both mean no retries.
+    pub max_attempts: u32,
+    #[serde(default)]
+    pub backoff: Backoff,
+    /// Fraction of each wait (0.0 to 1.0) taken off at random, so runs that failed
+    /// together do not all retry at the same moment.
+    #[serde(default)]
+    pub jitter: f64,
+}
+
+impl RetryPolicy {
+    /// Up to `max_attempts` calls, retrying right away.
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy { max_attempts, ..Self::default() }
+    }
+    pub fn with_backoff(mut self, backoff: Backoff) -> Self {
+        self.backoff = backoff;
+        self
+    }
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// The wait before retry number `retry` (1 for the second attempt), jitter not applied.
+    pub fn delay(&self, retry: u32) -> Duration {
+        match self.backoff {
+            Backoff::Fixed(wait) => wait,
+            Backoff::Exponential { initial, max } => {
+                let factor = 2u32.checked_pow(retry.saturating_sub(1)).unwrap_or(u32::MAX);
+                initial.checked_mul(factor).unwrap_or(max).min(max)
+            }
+        }
+    }
+
+    // `delay` with a random part of `jitter` taken off.
+    pub(crate) fn jittered_delay(&self, retry: u32) -> Duration {
+        let delay = self.delay(retry);
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 || delay.is_zero() {
+            return delay;
+        }
+        delay.mul_f64(1.0 - jitter * random_unit())
+    }
+}
+
+// A number in [0, 1); `RandomState` is seeded randomly per instance, which is random
+// enough to spread retries without a dependency on `rand`.
+pub(crate) fn random_unit() -> f64 {
+    (RandomState::new().hash_one(0u8) >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Whether a link failing with `err` is worth calling again.
+pub fn is_retryable(err: &RunError) -> bool {
+    !matches!(err.kind, ErrorKind::Unauthorized | ErrorKind::Forbidden | ErrorKind::InvalidInput | ErrorKind::QuotaExceeded)
+}
+
+/// The retry policies of a chain, and how it snapshots a link's input.
+pub(crate) struct Retries<T> {
+    pub(crate) clone: fn(&T) -> T,
+    pub(crate) default: Option<RetryPolicy>,
+    pub(crate) links: HashMap<usize, RetryPolicy>,
+}
+
+impl<T> Retries<T> {
+    pub(crate) fn policy(&self, link: usize) -> Option<&RetryPolicy> {
+        self.links.get(&link).or(self.default.as_ref())
+    }
+}
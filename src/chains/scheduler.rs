@@ -0,0 +1,104 @@
+//! Scheduler: resumes parked durable runs when their wait is over.
+//!
+//! A durable run (`ChainGeneric::run_durable`) that reaches a wait link
+//! (`std_links::wait_for` / `wait_until`) is checkpointed with a wake-up time and reported
+//! as `RunStatus::Parked`; nothing is held in memory while it waits. The [`Scheduler`]
+//! polls the chain's checkpoint store for due runs (`CheckpointStore::due`) and resumes
+//! them, so one process can carry workflows that wait for days.
+//!
+//! A due run is un-parked in the store before it resumes: if the resumed run fails, its
+//! checkpoint stays for `ChainGeneric::resume` instead of being picked up on every poll.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, MemoryCheckpointStore, RunStatus, Scheduler};
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::wait_for;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! # futures::executor::block_on(async {
+//! let mut chain = Chain::new();
+//! chain.enable_checkpoints(Arc::new(MemoryCheckpointStore::new()));
+//! chain.add_link(wait_for(Duration::from_millis(10)));
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("reminded", true) })));
+//! let chain = Arc::new(chain);
+//!
+//! let (_, report) = chain.run_durable("invoice-7", Context::new()).await;
+//! assert!(matches!(report.status, RunStatus::Parked { .. }));
+//! std::thread::sleep(Duration::from_millis(20));
+//! let resumed = Scheduler::new(chain).run_due().await.unwrap();
+//! assert_eq!(resumed[0].1.status, RunStatus::Completed);
+//! # });
+//! ```
+
+use super::{ChainGeneric, RunReport, RunStatus};
+use crate::listeners::BaseListenerAsync;
+use crate::runtime::{default_executor, ExecutorObj};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn now_ms() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+/// Resumes the parked runs of one chain; see the [module docs](self).
+pub struct Scheduler<T> {
+    chain: Arc<ChainGeneric<T>>,
+    poll_interval: Duration,
+    executor: ExecutorObj,
+}
+
+impl<T: 'static + Send + Serialize + DeserializeOwned> Scheduler<T> {
+    pub fn new(chain: Arc<ChainGeneric<T>>) -> Self {
+        Scheduler { chain, poll_interval: Duration::from_secs(1), executor: default_executor() }
+    }
+    /// How often `start` checks for due runs (default one second).
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+    /// Executor providing the poll timer (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// Resume every run that is due now, one after another; returns their run ids and reports.
+    pub async fn run_due(&self) -> std::io::Result<Vec<(String, RunReport)>> {
+        let store = self.chain.checkpoint_store().ok_or_else(|| std::io::Error::other("checkpoints are not enabled"))?;
+        let mut resumed = Vec::new();
+        for mut checkpoint in store.due(now_ms()).await? {
+            checkpoint.wake_at_ms = None;
+            store.save(&checkpoint).await?;
+            let run_id = checkpoint.run_id.clone();
+            match self.chain.resume_from(checkpoint).await {
+                Ok((_, report)) => {
+                    if let RunStatus::Failed(err) = &report.status {
+                        tracing::warn!(run_id = %run_id, error = %err, "resumed run failed");
+                    }
+                    resumed.push((run_id, report));
+                }
+                Err(e) => tracing::warn!(run_id = %run_id, error = %e, "resuming parked run failed"),
+            }
+        }
+        Ok(resumed)
+    }
+}
+
+#[async_trait]
+impl<T: 'static + Send + Sync + Serialize + DeserializeOwned> BaseListenerAsync for Scheduler<T> {
+    /// Poll for due runs every `poll_interval` until the store fails.
+    async fn start(&self) -> std::io::Result<()> {
+        loop {
+            self.run_due().await?;
+            self.executor.sleep(self.poll_interval).await;
+        }
+    }
+    fn name(&self) -> &'static str {
+        "scheduler"
+    }
+}
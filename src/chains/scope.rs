@@ -0,0 +1,472 @@
+//! Run scopes: the structured-concurrency boundary of a single chain run.
+//!
+//! Every `ChainGeneric::run` executes inside a [`RunScope`]. Child runs spawned with
+//! `ctx_tools::spawn_child` register with the scope of the run that spawned them; the parent
+//! waits for them before it completes, and dropping the parent (abort) aborts them.
+
+use super::cancel::CancellationToken;
+use super::error::{PathStep, RunError};
+use super::journal::Change;
+use super::report::{RunReport, RunStatus, StepTiming};
+use super::state::SharedState;
+use super::trace::TraceStep;
+use crate::context::SerializationPolicy;
+use futures::channel::oneshot;
+use futures::future::AbortHandle;
+use serde_json::Value;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use std::task::{Context as TaskContext, Poll};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<RunScope>>> = const { RefCell::new(None) };
+    // The failure slot of the code being polled on this thread, if it has one
+    static SLOT: RefCell<Option<Arc<FailureSlot>>> = const { RefCell::new(None) };
+}
+
+struct Child {
+    abort: AbortHandle,
+    report: Option<oneshot::Receiver<RunReport>>,
+}
+
+// The children of a scope, aborted when the scope is dropped.
+#[derive(Default)]
+struct Children(Mutex<Vec<Child>>);
+
+impl Children {
+    fn abort_all(&self) {
+        for child in self.0.lock().unwrap().iter() {
+            child.abort.abort();
+        }
+    }
+}
+
+impl Drop for Children {
+    fn drop(&mut self) {
+        self.abort_all();
+    }
+}
+
+/// Cancellation scope shared by a run and the children it spawns.
+#[derive(Default)]
+pub struct RunScope {
+    children: Children,
+    failure: Mutex<Option<RunError>>,
+    max_children: Option<usize>,
+    path: Mutex<Vec<PathStep>>,
+    steps: Mutex<Vec<StepTiming>>,
+    retries: AtomicU32,
+    attempts: Mutex<BTreeMap<usize, u32>>,
+    warnings: Mutex<Vec<String>>,
+    journal: Mutex<Vec<Change>>,
+    trace: Mutex<Vec<TraceStep>>,
+    annotations: Mutex<BTreeMap<String, Value>>,
+    durable: AtomicBool,
+    shadow: AtomicBool,
+    serialization: Mutex<SerializationPolicy>,
+    link_error: Mutex<Option<RunError>>,
+    last_error: Mutex<Option<RunError>>,
+    parked: Mutex<Option<u64>>,
+    awaiting: Mutex<Option<String>>,
+    cancellation: Mutex<Option<CancellationToken>>,
+    state: Mutex<Option<SharedState>>,
+}
+
+impl RunScope {
+    pub fn new() -> Arc<Self> {
+        Arc::new(RunScope::default())
+    }
+
+    /// Scope that fails the run with `LimitExceeded` once more than `max` children spawn.
+    pub fn with_max_children(max: Option<usize>) -> Arc<Self> {
+        Arc::new(RunScope { max_children: max, ..Default::default() })
+    }
+
+    /// Scope for one attempt at a link of this run (see `links::combinators`). It shares
+    /// the run's state, cancellation token, mode (durable, shadow), serialization policy,
+    /// and child limit, and records failures, warnings, and the rest on its own until
+    /// [`Self::adopt`] folds them into the run.
+    pub(crate) fn attempt_scope(&self) -> Arc<Self> {
+        Arc::new(RunScope {
+            max_children: self.max_children,
+            durable: AtomicBool::new(self.is_durable()),
+            shadow: AtomicBool::new(self.is_shadow()),
+            serialization: Mutex::new(self.serialization_policy()),
+            cancellation: Mutex::new(self.cancellation()),
+            state: Mutex::new(self.state()),
+            ..Default::default()
+        })
+    }
+
+    /// Take over what a successful attempt recorded in `attempt`: warnings, annotations,
+    /// retries, a fallible link's error, and a request to park or wait for an event.
+    pub(crate) fn adopt(&self, attempt: &RunScope) {
+        self.warnings.lock().unwrap().append(&mut attempt.warnings.lock().unwrap());
+        self.annotations.lock().unwrap().append(&mut attempt.annotations.lock().unwrap());
+        self.retries.fetch_add(attempt.retries.load(Ordering::Relaxed), Ordering::Relaxed);
+        if let Some(err) = attempt.take_link_error() {
+            self.link_failed(err);
+        }
+        if let Some(wake_at_ms) = attempt.parked() {
+            self.park(wake_at_ms);
+        }
+        if let Some(key) = attempt.awaiting_event() {
+            self.await_event(key);
+        }
+    }
+
+    /// The scope of the run currently being polled on this thread, if any.
+    pub fn current() -> Option<Arc<RunScope>> {
+        CURRENT.with(|c| c.borrow().clone())
+    }
+
+    /// Poll `fut` with this scope installed as the current scope.
+    pub fn enter<F: Future>(self: &Arc<Self>, fut: F) -> Scoped<F> {
+        Scoped { scope: self.clone(), fut: Box::pin(fut) }
+    }
+
+    /// Register a child; returns `false` (and fails the run) if the child limit is reached.
+    pub(crate) fn register(&self, abort: AbortHandle, report: oneshot::Receiver<RunReport>) -> bool {
+        let mut children = self.children.0.lock().unwrap();
+        if let Some(max) = self.max_children {
+            if children.len() >= max {
+                drop(children);
+                self.fail(RunError::limit_exceeded(format!("run spawned more than {} children", max)));
+                return false;
+            }
+        }
+        children.push(Child { abort, report: Some(report) });
+        true
+    }
+
+    /// Record `err` as the reason this run failed. The first failure wins.
+    pub fn fail(&self, err: RunError) {
+        let Some(err) = FailureSlot::divert(self, err) else { return };
+        let mut failure = self.failure.lock().unwrap();
+        if failure.is_none() {
+            *failure = Some(err);
+        }
+    }
+
+    pub fn failure(&self) -> Option<RunError> {
+        self.failure.lock().unwrap().clone()
+    }
+
+    pub(crate) fn clear_failure(&self) {
+        *self.failure.lock().unwrap() = None;
+    }
+
+    /// Record that link `link` (named `name`) is about to run.
+    pub(crate) fn enter_link(&self, link: usize, name: Option<String>) {
+        self.path.lock().unwrap().push(PathStep { link, name, branch: None });
+    }
+
+    /// Record how long the link entered last took.
+    pub(crate) fn exit_link(&self, duration: Duration) {
+        let last = self.path.lock().unwrap().last().cloned();
+        if let Some(step) = last {
+            self.steps.lock().unwrap().push(StepTiming { link: step.link, name: step.name, duration });
+        }
+    }
+
+    /// Record that the link that just ran branched to `target`.
+    pub(crate) fn take_branch(&self, target: usize) {
+        if let Some(step) = self.path.lock().unwrap().last_mut() {
+            step.branch = Some(target);
+        }
+        if let Some(step) = self.trace.lock().unwrap().last_mut() {
+            step.branch = Some(target);
+        }
+    }
+
+    /// Links executed so far, in order.
+    pub fn path(&self) -> Vec<PathStep> {
+        self.path.lock().unwrap().clone()
+    }
+
+    pub(crate) fn record_changes(&self, changes: Vec<Change>) {
+        self.journal.lock().unwrap().extend(changes);
+    }
+
+    pub(crate) fn record_trace_step(&self, step: TraceStep) {
+        self.trace.lock().unwrap().push(step);
+    }
+
+    pub(crate) fn take_trace(&self) -> Vec<TraceStep> {
+        std::mem::take(&mut *self.trace.lock().unwrap())
+    }
+
+    /// Tag the run with `key` (the latest value wins).
+    pub fn annotate(&self, key: impl Into<String>, value: Value) {
+        self.annotations.lock().unwrap().insert(key.into(), value);
+    }
+
+    /// Whether the run checkpoints after each link (`ChainGeneric::run_durable`).
+    pub fn is_durable(&self) -> bool {
+        self.durable.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_durable(&self) {
+        self.durable.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the run is a shadow run (`ChainGeneric::run_shadow`), without side effects.
+    pub fn is_shadow(&self) -> bool {
+        self.shadow.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_shadow(&self) {
+        self.shadow.store(true, Ordering::Relaxed);
+    }
+
+    /// How `Context::insert` handles unserializable values in this run.
+    pub fn serialization_policy(&self) -> SerializationPolicy {
+        *self.serialization.lock().unwrap()
+    }
+
+    pub(crate) fn set_serialization_policy(&self, policy: SerializationPolicy) {
+        *self.serialization.lock().unwrap() = policy;
+    }
+
+    /// Record that the fallible link running returned `err`, for the chain to route.
+    pub(crate) fn link_failed(&self, err: RunError) {
+        *self.link_error.lock().unwrap() = Some(err);
+    }
+
+    pub(crate) fn take_link_error(&self) -> Option<RunError> {
+        self.link_error.lock().unwrap().take()
+    }
+
+    pub(crate) fn link_error(&self) -> Option<RunError> {
+        self.link_error.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set_last_error(&self, err: RunError) {
+        *self.last_error.lock().unwrap() = Some(err);
+    }
+
+    /// The latest fallible link error that was skipped or routed to a handler.
+    pub fn last_error(&self) -> Option<RunError> {
+        self.last_error.lock().unwrap().clone()
+    }
+
+    /// Stop the run after the current link until `wake_at_ms` (epoch milliseconds).
+    pub fn park(&self, wake_at_ms: u64) {
+        *self.parked.lock().unwrap() = Some(wake_at_ms);
+    }
+
+    pub fn parked(&self) -> Option<u64> {
+        *self.parked.lock().unwrap()
+    }
+
+    /// Stop the run after the current link until the event `key` is delivered.
+    pub fn await_event(&self, key: impl Into<String>) {
+        *self.awaiting.lock().unwrap() = Some(key.into());
+    }
+
+    pub fn awaiting_event(&self) -> Option<String> {
+        self.awaiting.lock().unwrap().clone()
+    }
+
+    pub(crate) fn set_cancellation(&self, token: CancellationToken) {
+        *self.cancellation.lock().unwrap() = Some(token);
+    }
+
+    /// The token the run was started with (`ChainGeneric::run_with_cancel`), if any.
+    pub fn cancellation(&self) -> Option<CancellationToken> {
+        self.cancellation.lock().unwrap().clone()
+    }
+
+    /// Whether the run's cancellation token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.lock().unwrap().as_ref().is_some_and(CancellationToken::is_cancelled)
+    }
+
+    pub(crate) fn set_state(&self, state: SharedState) {
+        *self.state.lock().unwrap() = Some(state);
+    }
+
+    /// The shared state of the chain running (`ChainGeneric::set_state`), if it has one.
+    pub fn state(&self) -> Option<SharedState> {
+        self.state.lock().unwrap().clone()
+    }
+
+    pub fn record_retry(&self) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Record that link `link` took `attempts` calls to finish (see `chains::retry`).
+    pub(crate) fn record_attempts(&self, link: usize, attempts: u32) {
+        self.attempts.lock().unwrap().insert(link, attempts);
+    }
+
+    pub fn warn(&self, warning: impl Into<String>) {
+        self.warnings.lock().unwrap().push(warning.into());
+    }
+
+    /// How the run ended, from what the scope recorded.
+    pub(crate) fn status(&self) -> RunStatus {
+        match (self.failure(), self.parked(), self.awaiting_event()) {
+            (Some(err), _, _) => RunStatus::Failed(err.with_path(self.path())),
+            (None, _, _) if self.is_cancelled() => RunStatus::Cancelled,
+            (None, Some(wake_at_ms), _) => RunStatus::Parked { wake_at_ms },
+            (None, None, Some(key)) => RunStatus::AwaitingEvent { key },
+            (None, None, None) => RunStatus::Completed,
+        }
+    }
+
+    /// Fill in a report for this run from what the scope recorded, moving the recorded
+    /// steps, attempts, warnings, journal, and annotations out of the scope.
+    pub(crate) fn report(&self, status: RunStatus, duration: Duration, children: Vec<RunReport>) -> RunReport {
+        let branches = self.path.lock().unwrap().iter().filter_map(|step| step.branch.map(|target| (step.link, target))).collect();
+        // The next run on a pooled scope likely records as many steps again
+        let mut steps = self.steps.lock().unwrap();
+        let capacity = steps.len();
+        RunReport {
+            status,
+            version: None,
+            duration,
+            steps: std::mem::replace(&mut *steps, Vec::with_capacity(capacity)),
+            branches,
+            retries: self.retries.load(Ordering::Relaxed),
+            attempts: std::mem::take(&mut *self.attempts.lock().unwrap()),
+            warnings: std::mem::take(&mut *self.warnings.lock().unwrap()),
+            journal: std::mem::take(&mut *self.journal.lock().unwrap()),
+            annotations: std::mem::take(&mut *self.annotations.lock().unwrap()),
+            children,
+        }
+    }
+
+    /// Clear the scope for another run, keeping its buffers' capacity (see `chains::pool`).
+    /// `None` while anything else still holds the scope.
+    pub(crate) fn recycle(mut self: Arc<Self>) -> Option<Arc<Self>> {
+        let scope = Arc::get_mut(&mut self)?;
+        scope.children.0.get_mut().unwrap().clear();
+        *scope.failure.get_mut().unwrap() = None;
+        scope.path.get_mut().unwrap().clear();
+        scope.steps.get_mut().unwrap().clear();
+        *scope.retries.get_mut() = 0;
+        scope.attempts.get_mut().unwrap().clear();
+        scope.warnings.get_mut().unwrap().clear();
+        scope.journal.get_mut().unwrap().clear();
+        scope.trace.get_mut().unwrap().clear();
+        scope.annotations.get_mut().unwrap().clear();
+        *scope.durable.get_mut() = false;
+        *scope.shadow.get_mut() = false;
+        *scope.serialization.get_mut().unwrap() = SerializationPolicy::Panic;
+        *scope.link_error.get_mut().unwrap() = None;
+        *scope.last_error.get_mut().unwrap() = None;
+        *scope.parked.get_mut().unwrap() = None;
+        *scope.awaiting.get_mut().unwrap() = None;
+        *scope.cancellation.get_mut().unwrap() = None;
+        *scope.state.get_mut().unwrap() = None;
+        Some(self)
+    }
+
+    /// Abort every child that is still running.
+    pub fn cancel_children(&self) {
+        self.children.abort_all();
+    }
+
+    /// Wait for all registered children, including ones spawned while waiting.
+    /// Abort handles stay registered while waiting so a dropped parent still cancels them.
+    pub(crate) async fn join_children(&self) -> Vec<RunReport> {
+        let mut reports = Vec::new();
+        loop {
+            let next = {
+                let mut children = self.children.0.lock().unwrap();
+                match children.get_mut(reports.len()) {
+                    Some(child) => child.report.take(),
+                    None => return reports,
+                }
+            };
+            let report = match next {
+                Some(rx) => rx.await.unwrap_or_else(|_| RunReport::new(RunStatus::Cancelled)),
+                None => RunReport::new(RunStatus::Cancelled),
+            };
+            reports.push(report);
+        }
+    }
+}
+
+/// Where the failures a piece of code reports for a run go instead of the run, so they
+/// can be told apart from failures reported by anything else running concurrently (see
+/// `middleware::ContainedMiddleware`).
+pub(crate) struct FailureSlot {
+    scope: Arc<RunScope>,
+    failure: Mutex<Option<RunError>>,
+}
+
+impl FailureSlot {
+    pub(crate) fn new(scope: Arc<RunScope>) -> Arc<Self> {
+        Arc::new(FailureSlot { scope, failure: Mutex::default() })
+    }
+
+    /// Poll `fut` with failures of the slot's run going to the slot.
+    pub(crate) fn enter<F: Future>(self: &Arc<Self>, fut: F) -> Slotted<F> {
+        Slotted { slot: self.clone(), fut: Box::pin(fut) }
+    }
+
+    /// The first failure reported while the slot was entered.
+    pub(crate) fn take(&self) -> Option<RunError> {
+        self.failure.lock().unwrap().take()
+    }
+
+    // Keep `err` in the slot being polled if it is for `scope`; otherwise hand it back.
+    fn divert(scope: &RunScope, err: RunError) -> Option<RunError> {
+        let slot = SLOT.with(|slot| slot.borrow().clone()).filter(|slot| std::ptr::eq(Arc::as_ptr(&slot.scope), scope));
+        let Some(slot) = slot else { return Some(err) };
+        slot.failure.lock().unwrap().get_or_insert(err);
+        None
+    }
+}
+
+/// Future adapter returned by [`FailureSlot::enter`].
+pub(crate) struct Slotted<F> {
+    slot: Arc<FailureSlot>,
+    fut: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Slotted<F> {
+    type Output = F::Output;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<F::Output> {
+        let _restore = RestoreSlot(SLOT.with(|s| s.replace(Some(self.slot.clone()))));
+        self.fut.as_mut().poll(cx)
+    }
+}
+
+struct RestoreSlot(Option<Arc<FailureSlot>>);
+
+impl Drop for RestoreSlot {
+    fn drop(&mut self) {
+        SLOT.with(|s| *s.borrow_mut() = self.0.take());
+    }
+}
+
+/// Future adapter returned by [`RunScope::enter`].
+pub struct Scoped<F> {
+    scope: Arc<RunScope>,
+    fut: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<F::Output> {
+        let _restore = Restore(CURRENT.with(|c| c.replace(Some(self.scope.clone()))));
+        self.fut.as_mut().poll(cx)
+    }
+}
+
+// Puts the previous scope back even if the inner poll panics.
+struct Restore(Option<Arc<RunScope>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        CURRENT.with(|c| *c.borrow_mut() = self.0.take());
+    }
+}
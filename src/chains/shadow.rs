@@ -0,0 +1,71 @@
+//! Shadow runs: execute a chain without its side effects.
+//!
+//! Links that act on the outside world (charging a card, sending an email, writing to a
+//! shared store) are tagged with `LinkSpec::side_effects`. `ChainGeneric::run_shadow` runs
+//! the chain with such links skipped, the context passing through unchanged, unless a mock
+//! is registered under the link's name with [`Shadow::mock`]; then the mock runs in its
+//! place. Either way, every tagged link reached is recorded as a [`ShadowCall`] with the
+//! context it would have received.
+//!
+//! Untagged links run normally. They can still tell they are in a shadow run with
+//! `ctx_tools::is_shadow`. Shadow results are not delivered to sinks or subscribers.
+//! Dry runs (`modulink-cli backfill --dry-run`) and canaries (`crate::canary`) run this way.
+
+use crate::chains::LinkGeneric;
+use crate::links::LinkSpec;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A side-effecting link reached during a shadow run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ShadowCall {
+    /// Index of the link in the chain.
+    pub link: usize,
+    pub name: Option<String>,
+    /// The context the link was called with.
+    pub input: Value,
+    /// Whether a mock ran in the link's place (otherwise it was skipped).
+    pub mocked: bool,
+}
+
+/// Mocks and recorded calls for shadow runs; see the [module docs](self).
+pub struct Shadow<T> {
+    mocks: HashMap<String, LinkGeneric<T>>,
+    calls: Mutex<Vec<ShadowCall>>,
+    snapshot: fn(&T) -> Value,
+}
+
+impl<T: Serialize> Shadow<T> {
+    /// Skip every side-effecting link.
+    pub fn new() -> Self {
+        Shadow { mocks: HashMap::new(), calls: Mutex::default(), snapshot: |ctx| serde_json::to_value(ctx).unwrap_or(Value::Null) }
+    }
+}
+
+impl<T: Serialize> Default for Shadow<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Shadow<T> {
+    /// Run `mock` instead of the side-effecting link named `name`.
+    pub fn mock(mut self, name: impl Into<String>, mock: LinkGeneric<T>) -> Self {
+        self.mocks.insert(name.into(), mock);
+        self
+    }
+    /// Calls recorded so far, in order, across every run using this `Shadow`.
+    pub fn calls(&self) -> Vec<ShadowCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    // Record a call of tagged link `link`; the mock to run instead, if any.
+    pub(crate) fn intercept(&self, link: usize, spec: &LinkSpec, ctx: &T) -> Option<LinkGeneric<T>> {
+        let mock = spec.name.as_ref().and_then(|name| self.mocks.get(name)).cloned();
+        let call = ShadowCall { link, name: spec.name.clone(), input: (self.snapshot)(ctx), mocked: mock.is_some() };
+        self.calls.lock().unwrap().push(call);
+        mock
+    }
+}
@@ -0,0 +1,109 @@
+//! State shared across runs.
+//!
+//! Some state outlives a single run but belongs to one process: counters, caches,
+//! connection registries. Instead of globals captured by link closures, such values go in
+//! a [`SharedState`] given to the chain with `ChainGeneric::set_state`. Links read them
+//! during a run with `ctx_tools::state::<S>()`, one value per type, so use a struct of your
+//! own (or a newtype) for each piece of state.
+//!
+//! Values are shared, not copied per run: anything a link changes must use interior
+//! mutability (atomics, `Mutex`, `RwLock`). The same handle can be given to several
+//! chains, which then share the values.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, SharedState};
+//! use modulink_rs::context::Context;
+//! use modulink_rs::ctx_tools;
+//! use std::sync::atomic::{AtomicU64, Ordering};
+//! use std::sync::Arc;
+//!
+//! #[derive(Default)]
+//! struct Visits(AtomicU64);
+//!
+//! # futures::executor::block_on(async {
+//! let state = SharedState::new();
+//! state.insert(Visits::default());
+//!
+//! let mut chain = Chain::new();
+//! chain.set_state(state.clone());
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+//!     let visits = ctx_tools::state::<Visits>().unwrap();
+//!     ctx.insert("visit", visits.0.fetch_add(1, Ordering::SeqCst) + 1)
+//! })));
+//!
+//! chain.run(Context::new()).await;
+//! let ctx = chain.run(Context::new()).await;
+//! assert_eq!(ctx.get::<u64>("visit"), Some(2));
+//! assert_eq!(state.get::<Visits>().unwrap().0.load(Ordering::SeqCst), 2);
+//! # });
+//! ```
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+type Values = HashMap<TypeId, Arc<dyn Any + Send + Sync>>;
+
+/// A set of values, one per type, shared by every run of the chains it is given to.
+/// Clones are handles to the same values.
+#[derive(Clone, Default)]
+pub struct SharedState {
+    values: Arc<RwLock<Values>>,
+}
+
+impl SharedState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store `value`, replacing (and returning) the value of type `S` stored before.
+    /// Runs holding the old value keep it until they drop it.
+    pub fn insert<S: Any + Send + Sync>(&self, value: S) -> Option<Arc<S>> {
+        let old = self.values.write().unwrap().insert(TypeId::of::<S>(), Arc::new(value));
+        old.and_then(downcast)
+    }
+
+    pub fn get<S: Any + Send + Sync>(&self) -> Option<Arc<S>> {
+        self.values.read().unwrap().get(&TypeId::of::<S>()).cloned().and_then(downcast)
+    }
+
+    /// The value of type `S`, storing the one `init` makes if there is none yet. When two
+    /// callers race, both may call `init`, but only the first value is kept.
+    pub fn get_or_insert_with<S: Any + Send + Sync>(&self, init: impl FnOnce() -> S) -> Arc<S> {
+        if let Some(value) = self.get() {
+            return value;
+        }
+        // `init` runs without the lock held, so it may use the state itself
+        let value: Arc<dyn Any + Send + Sync> = Arc::new(init());
+        let value = self.values.write().unwrap().entry(TypeId::of::<S>()).or_insert(value).clone();
+        downcast(value).expect("shared state value stored under the wrong type")
+    }
+
+    pub fn remove<S: Any + Send + Sync>(&self) -> Option<Arc<S>> {
+        self.values.write().unwrap().remove(&TypeId::of::<S>()).and_then(downcast)
+    }
+
+    pub fn contains<S: Any + Send + Sync>(&self) -> bool {
+        self.values.read().unwrap().contains_key(&TypeId::of::<S>())
+    }
+
+    pub fn len(&self) -> usize {
+        self.values.read().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn downcast<S: Any + Send + Sync>(value: Arc<dyn Any + Send + Sync>) -> Option<Arc<S>> {
+    value.downcast().ok()
+}
+
+impl fmt::Debug for SharedState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedState").field("values", &self.len()).finish()
+    }
+}
@@ -0,0 +1,141 @@
+//! Running a chain one link at a time.
+//!
+//! `ChainGeneric::stepper` starts a run that only advances when asked:
+//! [`ChainStepper::step`] runs the next link (or concurrent group) and returns it with the
+//! branch it took, and between steps the context can be read ([`ChainStepper::ctx`]) and
+//! changed ([`ChainStepper::ctx_mut`]). [`ChainStepper::finish`] runs the rest;
+//! [`ChainStepper::abort`] stops where the run is. Both return the context and report as
+//! `run_with_report` would.
+//!
+//! Links, branches, error routes, and retries behave as in a run, and `ctx_tools` works
+//! inside links. Middleware, rate limits, checkpoints, sinks, and subscribers are not
+//! involved: a stepped run is for debugging and for testing the hops of a long chain.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, RunStatus};
+//! use modulink_rs::context::Context;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let mut chain = Chain::new();
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("total", 100) })));
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+//!     let total = ctx.get::<i64>("total").unwrap();
+//!     ctx.insert("tax", total / 5)
+//! })));
+//!
+//! let mut stepper = chain.stepper(Context::new());
+//! assert_eq!(stepper.step().await.unwrap().link, 0);
+//! assert_eq!(stepper.ctx().get::<i64>("total"), Some(100));
+//! *stepper.ctx_mut() = stepper.ctx().clone().insert("total", 50);
+//! let (ctx, report) = stepper.finish().await;
+//! assert_eq!(ctx.get::<i64>("tax"), Some(10));
+//! assert_eq!(report.status, RunStatus::Completed);
+//! # });
+//! ```
+
+use super::{ChainGeneric, PathStep, RunReport, RunScope, RunStatus};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// A run of a chain advanced link by link; see the [module docs](self).
+pub struct ChainStepper<'a, T> {
+    chain: &'a ChainGeneric<T>,
+    scope: Arc<RunScope>,
+    // `None` only while a step is running
+    ctx: Option<T>,
+    next: Option<usize>,
+    started: Instant,
+}
+
+impl<'a, T: 'static + Send> ChainStepper<'a, T> {
+    pub(crate) fn new(chain: &'a ChainGeneric<T>, ctx: T) -> Self {
+        let next = (!chain.links.is_empty()).then_some(0);
+        ChainStepper { chain, scope: chain.scope_for_run(), ctx: Some(ctx), next, started: Instant::now() }
+    }
+
+    /// The context as the last step left it.
+    pub fn ctx(&self) -> &T {
+        self.ctx.as_ref().expect("context is only taken while a step runs")
+    }
+    /// The context the next step will be given.
+    pub fn ctx_mut(&mut self) -> &mut T {
+        self.ctx.as_mut().expect("context is only taken while a step runs")
+    }
+    /// Position of the link the next step runs; `None` once the run is over.
+    pub fn next_link(&self) -> Option<usize> {
+        self.next
+    }
+    pub fn is_done(&self) -> bool {
+        self.next.is_none()
+    }
+    /// Links executed so far, in order.
+    pub fn path(&self) -> Vec<PathStep> {
+        self.scope.path()
+    }
+
+    /// Run the next link, or concurrent group of links, and follow its error route or
+    /// branch. Returns the (last) link that ran, or `None` when the run was already over.
+    pub async fn step(&mut self) -> Option<PathStep> {
+        let chain = self.chain;
+        let idx = self.next?;
+        let end = chain.group_end(idx);
+        let ctx = self.ctx.take().expect("context is only taken while a step runs");
+        let scope = &self.scope;
+        let run = async {
+            match &chain.concurrent {
+                Some(concurrent) if end - idx > 1 => chain.run_group(ctx, idx..end, scope, None, concurrent).await,
+                _ => {
+                    scope.enter_link(idx, chain.specs[idx].name.clone());
+                    let started = Instant::now();
+                    let ctx = match chain.link_at(idx, &ctx, None) {
+                        Some(link) => chain.call_link(idx, link, ctx, scope).await,
+                        None => ctx,
+                    };
+                    scope.exit_link(started.elapsed());
+                    ctx
+                }
+            }
+        };
+        let ctx = scope.enter(run).await;
+        let last = end - 1;
+        let handler = scope.take_link_error().and_then(|err| chain.route_link_error(last, err, scope));
+        self.next = if scope.failure().is_some() || scope.is_cancelled() || scope.parked().is_some() || scope.awaiting_event().is_some() {
+            None
+        } else if let Some(handler) = handler {
+            scope.take_branch(handler);
+            Some(handler)
+        } else if let Some(branch) = chain.branches.iter().find(|b| b.source == last && (b.condition)(&ctx)) {
+            scope.take_branch(branch.target);
+            Some(branch.target)
+        } else {
+            Some(end).filter(|end| *end < chain.links.len())
+        };
+        self.ctx = Some(ctx);
+        scope.path().pop()
+    }
+
+    /// Run the remaining links and end the run.
+    pub async fn finish(mut self) -> (T, RunReport) {
+        while self.step().await.is_some() {}
+        self.end(None).await
+    }
+
+    /// End the run before the next link; it reports `Cancelled` unless it already failed.
+    pub async fn abort(self) -> (T, RunReport) {
+        let status = match self.scope.failure() {
+            Some(_) => None,
+            None => Some(RunStatus::Cancelled),
+        };
+        self.end(status).await
+    }
+
+    async fn end(mut self, status: Option<RunStatus>) -> (T, RunReport) {
+        let children = self.scope.join_children().await;
+        let status = status.unwrap_or_else(|| self.scope.status());
+        let mut report = self.scope.report(status, self.started.elapsed(), children);
+        report.version = self.chain.version.clone();
+        (self.ctx.take().expect("context is only taken while a step runs"), report)
+    }
+}
@@ -0,0 +1,165 @@
+//! The shape of a chain as data.
+//!
+//! `ChainGeneric::topology` describes a chain as a graph: one [`TopologyNode`] per link,
+//! carrying its `LinkSpec`, and one [`TopologyEdge`] per way a run can move between links:
+//! on to the next link, along a branch or jump, or to an error handler. Branch conditions
+//! are code, so an edge only describes its condition when the branch was added with
+//! `ChainGeneric::connect_described` (chains built from definitions describe theirs). The
+//! graph serializes to JSON for visualizers, docs generators, and other tools, and renders
+//! as Graphviz DOT ([`Topology::to_dot`], `ChainGeneric::to_dot`) or as a Mermaid flowchart
+//! for Markdown on GitHub and GitLab ([`Topology::to_mermaid`], `ChainGeneric::to_mermaid`).
+//! Both draw links as boxes (bold when they have side effects), `Next` edges solid,
+//! branches dashed and labeled with their condition, jumps dashed, and error handlers red.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, EdgeKind};
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::{Link, LinkSpec};
+//! use std::sync::Arc;
+//!
+//! let noop: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx }));
+//! let mut chain = Chain::new();
+//! chain.add_link_with(noop.clone(), LinkSpec::new().name("score"));
+//! chain.add_link_with(noop.clone(), LinkSpec::new().name("review"));
+//! chain.add_link_with(noop, LinkSpec::new().name("approve"));
+//! chain.connect_described(0, 2, "score >= 0.8", |ctx: &Context| ctx.get::<f64>("score").unwrap_or(0.0) >= 0.8);
+//!
+//! let topology = chain.topology();
+//! assert_eq!(topology.nodes[1].spec.name.as_deref(), Some("review"));
+//! let branch = topology.edges.iter().find(|edge| edge.kind == EdgeKind::Branch).unwrap();
+//! assert_eq!((branch.source, branch.target, branch.condition.as_deref()), (0, 2, Some("score >= 0.8")));
+//! ```
+
+use crate::links::LinkSpec;
+use serde::{Deserialize, Serialize};
+
+/// A chain's links and the edges between them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Topology {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Keys declared with `ChainGeneric::declare_input`.
+    #[serde(default)]
+    pub input: Vec<String>,
+    /// One node per link, by position.
+    pub nodes: Vec<TopologyNode>,
+    /// `Next` edges in link order, then branches and jumps in the order runs try them, then
+    /// error handlers.
+    pub edges: Vec<TopologyEdge>,
+}
+
+/// One link of a chain.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopologyNode {
+    pub link: usize,
+    #[serde(flatten)]
+    pub spec: LinkSpec,
+}
+
+/// A way a run can move from link `source` to link `target`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TopologyEdge {
+    pub source: usize,
+    pub target: usize,
+    pub kind: EdgeKind,
+    /// What the branch checks, if it was described.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// On to the next link, when no branch is taken.
+    Next,
+    /// A branch added with `ChainGeneric::connect`, taken when its condition holds.
+    Branch,
+    /// A branch added with `ChainGeneric::jump`, always taken.
+    Jump,
+    /// Where a failed fallible link continues (`ErrorRoute::Jump`).
+    Error,
+}
+
+impl Topology {
+    /// The node of the link named `name`.
+    pub fn node(&self, name: &str) -> Option<&TopologyNode> {
+        self.nodes.iter().find(|node| node.spec.name.as_deref() == Some(name))
+    }
+    /// Edges leaving link `link`.
+    pub fn edges_from(&self, link: usize) -> impl Iterator<Item = &TopologyEdge> {
+        self.edges.iter().filter(move |edge| edge.source == link)
+    }
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+
+    /// Graphviz DOT source for the graph; render with e.g. `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        let mut out = format!("digraph {} {{\n    node [shape=box];\n", quote(self.name.as_deref().unwrap_or("chain")));
+        for node in &self.nodes {
+            let style = if node.spec.side_effects { ", style=bold" } else { "" };
+            out.push_str(&format!("    L{} [label={}{}];\n", node.link, quote(&node_label(node)), style));
+        }
+        for edge in &self.edges {
+            let attrs = match (edge.kind, &edge.condition) {
+                (EdgeKind::Next, _) => String::new(),
+                (EdgeKind::Branch, Some(condition)) => format!(" [style=dashed, label={}]", quote(condition)),
+                (EdgeKind::Branch, None) => " [style=dashed, label=\"branch\"]".to_string(),
+                (EdgeKind::Jump, _) => " [style=dashed, label=\"always\"]".to_string(),
+                (EdgeKind::Error, _) => " [style=dotted, color=red, label=\"on error\"]".to_string(),
+            };
+            out.push_str(&format!("    L{} -> L{}{};\n", edge.source, edge.target, attrs));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid `flowchart TD` source for the graph.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for node in &self.nodes {
+            out.push_str(&format!("    L{}[\"{}\"]\n", node.link, mermaid_text(&node_label(node))));
+        }
+        for edge in &self.edges {
+            let arrow = match (edge.kind, &edge.condition) {
+                (EdgeKind::Next, _) => "-->".to_string(),
+                (EdgeKind::Branch, Some(condition)) => format!("-.->|\"{}\"|", mermaid_text(condition)),
+                (EdgeKind::Branch, None) => "-.->|branch|".to_string(),
+                (EdgeKind::Jump, _) => "-.->|always|".to_string(),
+                (EdgeKind::Error, _) => "-.->|on error|".to_string(),
+            };
+            out.push_str(&format!("    L{} {} L{}\n", edge.source, arrow, edge.target));
+        }
+        let bold: Vec<String> = self.nodes.iter().filter(|node| node.spec.side_effects).map(|node| format!("L{}", node.link)).collect();
+        if !bold.is_empty() {
+            out.push_str(&format!("    classDef sideEffects stroke-width:3px\n    class {} sideEffects\n", bold.join(",")));
+        }
+        for (i, edge) in self.edges.iter().enumerate() {
+            if edge.kind == EdgeKind::Error {
+                out.push_str(&format!("    linkStyle {} stroke:red\n", i));
+            }
+        }
+        out
+    }
+}
+
+fn node_label(node: &TopologyNode) -> String {
+    match (&node.spec.name, &node.spec.description) {
+        (Some(name), _) => format!("{}: {}", node.link, name),
+        (None, Some(description)) => format!("{}: {}", node.link, description),
+        (None, None) => format!("Link {}", node.link),
+    }
+}
+
+// `text` for a quoted Mermaid label.
+fn mermaid_text(text: &str) -> String {
+    text.replace('"', "#quot;").replace('\n', " ")
+}
+
+// `text` as a DOT string literal.
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
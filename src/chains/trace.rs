@@ -0,0 +1,178 @@
+//! Execution traces: what every step of a run was given and made, for debugging.
+//!
+//! A chain given a [`TraceRecorder`] with `ChainGeneric::record_traces` snapshots the
+//! context (as JSON) around every link of every run and keeps an [`ExecutionTrace`]: the
+//! input, each step with its context before and after, its duration, and the branch it
+//! took, the final status, and the output. The recorder keeps the latest traces; a trace
+//! serializes to JSON to be kept with an incident.
+//!
+//! `ChainGeneric::replay` re-executes the recorded steps, in the recorded order, each on
+//! the context it was recorded with, and reports where the output differs from what was
+//! recorded ([`Replay::first_divergence`]): run a production trace against a fixed build
+//! to see the fix take effect at the step that went wrong. Branch conditions are not
+//! evaluated again, and links marked `LinkSpec::side_effects` are not called; their
+//! recorded output stands in for them. Shadow runs are not traced.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, TraceRecorder};
+//! use modulink_rs::context::Context;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let recorder = Arc::new(TraceRecorder::new());
+//! let mut chain = Chain::new();
+//! chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+//!     let total = ctx.get::<i64>("total").unwrap_or(0);
+//!     ctx.insert("total", total * 2)
+//! })));
+//! chain.record_traces(recorder.clone());
+//! chain.run(Context::new().insert("total", 21)).await;
+//!
+//! let trace = recorder.last().unwrap();
+//! assert_eq!(trace.steps[0].after["total"], 42);
+//! let replay = chain.replay(&trace).await.unwrap();
+//! assert!(replay.first_divergence().is_none());
+//! # });
+//! ```
+
+use super::{RunError, RunStatus};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Traces a recorder keeps unless given another capacity.
+pub const DEFAULT_TRACE_CAPACITY: usize = 100;
+
+/// One recorded run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    /// `ChainGeneric::name` and `version` of the chain that ran.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// The context the run was started with.
+    pub input: Value,
+    pub steps: Vec<TraceStep>,
+    /// The context the run ended with, before any error chain.
+    pub output: Value,
+    pub status: RunStatus,
+    pub duration: Duration,
+}
+
+/// One executed step of a recorded run.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TraceStep {
+    pub link: usize,
+    /// Links `link..end` ran as one concurrent group (see `chains::concurrent`); `link + 1`
+    /// for a link on its own.
+    pub end: usize,
+    /// `LinkSpec::name` of the link, if it has one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub before: Value,
+    pub after: Value,
+    pub duration: Duration,
+    /// The link a branch or error handler continued at; `None` when the run went on to
+    /// the next link or stopped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<usize>,
+}
+
+impl ExecutionTrace {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Keeps the latest traces of the chains recording into it.
+pub struct TraceRecorder {
+    capacity: usize,
+    traces: Mutex<VecDeque<ExecutionTrace>>,
+}
+
+impl Default for TraceRecorder {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_TRACE_CAPACITY)
+    }
+}
+
+impl TraceRecorder {
+    /// Keep the latest [`DEFAULT_TRACE_CAPACITY`] traces.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Keep the latest `capacity` traces (at least 1).
+    pub fn with_capacity(capacity: usize) -> Self {
+        TraceRecorder { capacity: capacity.max(1), traces: Mutex::new(VecDeque::new()) }
+    }
+    pub fn record(&self, trace: ExecutionTrace) {
+        let mut traces = self.traces.lock().unwrap();
+        if traces.len() == self.capacity {
+            traces.pop_front();
+        }
+        traces.push_back(trace);
+    }
+    /// Traces kept, oldest first.
+    pub fn traces(&self) -> Vec<ExecutionTrace> {
+        self.traces.lock().unwrap().iter().cloned().collect()
+    }
+    pub fn last(&self) -> Option<ExecutionTrace> {
+        self.traces.lock().unwrap().back().cloned()
+    }
+    pub fn clear(&self) {
+        self.traces.lock().unwrap().clear();
+    }
+}
+
+/// A recorded step executed again by `ChainGeneric::replay`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayStep {
+    pub link: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub recorded: Value,
+    pub replayed: Value,
+    /// The error the step failed the run or its link with, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<RunError>,
+    /// The link has side effects and was not called; `replayed` is the recorded output.
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+impl ReplayStep {
+    /// The replayed output equals the recorded one.
+    pub fn matches(&self) -> bool {
+        self.recorded == self.replayed
+    }
+}
+
+/// Outcome of replaying a trace, step by step.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Replay {
+    pub steps: Vec<ReplayStep>,
+}
+
+impl Replay {
+    /// The first step whose output differs from the recorded one.
+    pub fn first_divergence(&self) -> Option<&ReplayStep> {
+        self.steps.iter().find(|step| !step.matches())
+    }
+}
+
+/// How a chain snapshots contexts for traces, and where it records them.
+pub(crate) struct Tracing<T> {
+    pub(crate) recorder: Arc<TraceRecorder>,
+    pub(crate) snapshot: fn(&T) -> Value,
+}
+
+pub(crate) fn snapshot<T: Serialize>(ctx: &T) -> Value {
+    serde_json::to_value(ctx).unwrap_or_default()
+}
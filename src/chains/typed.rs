@@ -0,0 +1,100 @@
+//! Type-state chain builder: each link is a typed step from one context type to the next,
+//! so a pipeline whose steps don't line up fails to compile instead of failing at runtime.
+//!
+//! Use distinct types as markers for "what the context holds so far":
+//!
+//! ```rust
+//! use modulink_rs::chains::TypedChainBuilder;
+//!
+//! struct Raw(String);
+//! struct Parsed { amount: u64 }
+//! struct Scored { amount: u64, risky: bool }
+//!
+//! async fn parse(raw: Raw) -> Parsed { Parsed { amount: raw.0.parse().unwrap_or(0) } }
+//! async fn score(p: Parsed) -> Scored { Scored { amount: p.amount, risky: p.amount > 1_000 } }
+//!
+//! let chain = TypedChainBuilder::<Raw>::new().link(parse).link(score).build();
+//! let scored = futures::executor::block_on(chain.run(Raw("5000".to_string())));
+//! assert!(scored.risky && scored.amount == 5000);
+//! ```
+//!
+//! Skipping a step that produces what the next one needs does not compile:
+//!
+//! ```rust,compile_fail
+//! use modulink_rs::chains::TypedChainBuilder;
+//!
+//! struct Raw(String);
+//! struct Parsed { amount: u64 }
+//! struct Scored { risky: bool }
+//!
+//! async fn score(p: Parsed) -> Scored { Scored { risky: p.amount > 1_000 } }
+//!
+//! let chain = TypedChainBuilder::<Raw>::new().link(score).build();
+//! ```
+
+use crate::runtime::BoxFuture;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+type Step<I, O> = Arc<dyn Fn(I) -> BoxFuture<'static, O> + Send + Sync>;
+
+/// Builder whose type records the input type `I` and the current output type `O`.
+pub struct TypedChainBuilder<I, O = I> {
+    step: Step<I, O>,
+    _marker: PhantomData<fn(I) -> O>,
+}
+
+impl<I: Send + 'static> TypedChainBuilder<I, I> {
+    pub fn new() -> Self {
+        TypedChainBuilder { step: Arc::new(|input: I| Box::pin(async move { input })), _marker: PhantomData }
+    }
+}
+
+impl<I: Send + 'static> Default for TypedChainBuilder<I, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I: Send + 'static, O: Send + 'static> TypedChainBuilder<I, O> {
+    /// Append a link taking the current output type; the builder's output becomes `N`.
+    pub fn link<N, F, Fut>(self, link: F) -> TypedChainBuilder<I, N>
+    where
+        F: Fn(O) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = N> + Send + 'static,
+        N: Send + 'static,
+    {
+        let prev = self.step;
+        let link = Arc::new(link);
+        TypedChainBuilder {
+            step: Arc::new(move |input: I| {
+                let prev = prev.clone();
+                let link = link.clone();
+                Box::pin(async move { link(prev(input).await).await })
+            }),
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn build(self) -> TypedChain<I, O> {
+        TypedChain { step: self.step }
+    }
+}
+
+/// A pipeline from `I` to `O` whose link types were checked when it was built.
+pub struct TypedChain<I, O> {
+    step: Step<I, O>,
+}
+
+impl<I, O> Clone for TypedChain<I, O> {
+    fn clone(&self) -> Self {
+        TypedChain { step: self.step.clone() }
+    }
+}
+
+impl<I: Send + 'static, O: Send + 'static> TypedChain<I, O> {
+    pub async fn run(&self, input: I) -> O {
+        (self.step)(input).await
+    }
+}
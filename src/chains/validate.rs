@@ -0,0 +1,271 @@
+//! Build-time checks for chains: data flow and control flow.
+//!
+//! A key is available at a link when the declared input or some upstream link provides it
+//! on *every* path that reaches the link, following fall-through, branch, and error-route
+//! edges. Links added without a `LinkSpec` require and provide nothing.
+//!
+//! Branch conditions are opaque closures, so a conditional branch may or may not be taken;
+//! only a branch added with `ChainGeneric::jump` is known to always be. A loop from which
+//! the end of the chain cannot be reached runs forever ([`ValidationError::EndlessLoop`]).
+//! Other loops depend on their branch conditions to end; they are listed in
+//! [`ValidationReport::loops`], with a warning when the chain has no
+//! `ResourceLimits::max_steps` to stop them. Links no run can get to are warned about too.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, ValidationError};
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::{Link, LinkSpec};
+//! use std::sync::Arc;
+//!
+//! let noop: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx }));
+//! let mut chain = Chain::new();
+//! chain.declare_input(["order_id"]);
+//! chain.add_link_with(noop.clone(), LinkSpec::new().requires(["order_id"]).provides(["order"]));
+//! chain.add_link_with(noop, LinkSpec::new().requires(["order", "customer"]));
+//! assert_eq!(
+//!     chain.validate(),
+//!     Err(vec![ValidationError::MissingKey { link: 1, key: "customer".to_string() }])
+//! );
+//! ```
+
+use crate::links::LinkSpec;
+use std::collections::BTreeSet;
+
+/// A problem found by `ChainGeneric::validate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationError {
+    /// Link `link` requires `key`, but some path reaches it without the key being provided.
+    MissingKey { link: usize, key: String },
+    /// A branch points outside the link list.
+    BranchOutOfRange { source: usize, target: usize },
+    /// No link has this name (`ChainGeneric::connect_named`).
+    UnknownLink(String),
+    /// Several links share this name, so name lookups only find the first.
+    DuplicateName(String),
+    /// These links form a loop runs never get out of to the end of the chain, as
+    /// `ChainGeneric::jump` branches cut off every way out.
+    EndlessLoop(Vec<usize>),
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationError::MissingKey { link, key } => {
+                write!(f, "link {} requires '{}', which is not provided on every path", link, key)
+            }
+            ValidationError::BranchOutOfRange { source, target } => {
+                write!(f, "branch {} -> {} is out of range", source, target)
+            }
+            ValidationError::UnknownLink(name) => write!(f, "no link is named '{}'", name),
+            ValidationError::DuplicateName(name) => write!(f, "several links are named '{}'", name),
+            ValidationError::EndlessLoop(links) => write!(f, "links {:?} loop without an exit", links),
+        }
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Something `ChainGeneric::validation_report` found that does not stop the chain from
+/// working, but may not be intended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// No run ever gets to this link.
+    Unreachable(usize),
+    /// These links form a loop, and the chain sets no `ResourceLimits::max_steps`, so only
+    /// their branch conditions end it.
+    UnboundedLoop(Vec<usize>),
+}
+
+impl std::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ValidationWarning::Unreachable(link) => write!(f, "link {} is unreachable", link),
+            ValidationWarning::UnboundedLoop(links) => write!(f, "links {:?} loop with no step limit", links),
+        }
+    }
+}
+
+/// Everything `ChainGeneric::validation_report` found about a chain.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Problems that make runs fail or misbehave.
+    pub errors: Vec<ValidationError>,
+    pub warnings: Vec<ValidationWarning>,
+    /// The links of each loop runs can take (links that can reach each other), in order of
+    /// their first link.
+    pub loops: Vec<Vec<usize>>,
+}
+
+impl ValidationReport {
+    /// Whether no errors were found; warnings do not count.
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+    pub fn into_result(self) -> Result<(), Vec<ValidationError>> {
+        if self.errors.is_empty() {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
+}
+
+/// A branch as validation sees it.
+pub(crate) struct Edge {
+    pub(crate) source: usize,
+    pub(crate) target: usize,
+    /// Added with `ChainGeneric::jump`: taken whenever it is reached.
+    pub(crate) always: bool,
+}
+
+/// Check `specs` (one per link) against `input`, the `branches` (in the order the chain
+/// tries them), and the error-route jumps (source, handler); `bounded` when runs have a
+/// step limit.
+pub(crate) fn validate(
+    input: &[String],
+    specs: &[LinkSpec],
+    branches: &[Edge],
+    error_jumps: &[(usize, usize)],
+    bounded: bool,
+) -> ValidationReport {
+    let n = specs.len();
+    let mut report = ValidationReport::default();
+    report.errors.extend(
+        branches
+            .iter()
+            .filter(|b| b.source >= n || b.target >= n)
+            .map(|b| ValidationError::BranchOutOfRange { source: b.source, target: b.target }),
+    );
+    let mut names = BTreeSet::new();
+    for name in specs.iter().filter_map(|spec| spec.name.as_deref()) {
+        if !names.insert(name) {
+            report.errors.push(ValidationError::DuplicateName(name.to_string()));
+        }
+    }
+    if n == 0 {
+        return report;
+    }
+
+    let succs = successors(n, branches, error_jumps);
+    let mut preds: Vec<Vec<usize>> = vec![Vec::new(); n];
+    for (i, out) in succs.iter().enumerate() {
+        for &t in out.iter().filter(|&&t| t < n) {
+            preds[t].push(i);
+        }
+    }
+
+    // Keys available on entry to each link; `None` means not yet reached (top of the lattice)
+    let entry: BTreeSet<String> = input.iter().cloned().collect();
+    let mut avail_in: Vec<Option<BTreeSet<String>>> = vec![None; n];
+    let out = |i: usize, avail_in: &[Option<BTreeSet<String>>]| {
+        avail_in[i].as_ref().map(|keys| {
+            let mut keys = keys.clone();
+            keys.extend(specs[i].provides.iter().cloned());
+            keys
+        })
+    };
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for i in 0..n {
+            let mut keys: Option<BTreeSet<String>> = if i == 0 { Some(entry.clone()) } else { None };
+            for &p in &preds[i] {
+                if let Some(from_p) = out(p, &avail_in) {
+                    keys = Some(match keys {
+                        Some(k) => k.intersection(&from_p).cloned().collect(),
+                        None => from_p,
+                    });
+                }
+            }
+            if keys != avail_in[i] {
+                avail_in[i] = keys;
+                changed = true;
+            }
+        }
+    }
+
+    for (i, spec) in specs.iter().enumerate() {
+        let Some(keys) = &avail_in[i] else { continue };
+        for key in &spec.requires {
+            if !keys.contains(key) {
+                report.errors.push(ValidationError::MissingKey { link: i, key: key.clone() });
+            }
+        }
+    }
+
+    // Node `n` stands for the end of the chain
+    let reachable = reach(0, &succs);
+    let mut rev: Vec<Vec<usize>> = vec![Vec::new(); n + 1];
+    for (i, out) in succs.iter().enumerate() {
+        for &t in out {
+            rev[t].push(i);
+        }
+    }
+    let finishing = reach(n, &rev);
+    report.warnings.extend((0..n).filter(|&i| !reachable[i]).map(ValidationWarning::Unreachable));
+
+    let mut in_loop = vec![false; n];
+    for i in (0..n).filter(|&i| reachable[i]) {
+        if in_loop[i] {
+            continue;
+        }
+        let from_i = reach(i, &succs);
+        let to_i = reach(i, &rev);
+        let members: Vec<usize> = (0..n).filter(|&j| from_i[j] && to_i[j]).collect();
+        let cycles = members.len() > 1 || succs[i].contains(&i);
+        if !cycles {
+            continue;
+        }
+        for &j in &members {
+            in_loop[j] = true;
+        }
+        // Members of a loop either all reach the end or none do
+        if !finishing[i] {
+            report.errors.push(ValidationError::EndlessLoop(members.clone()));
+        } else if !bounded {
+            report.warnings.push(ValidationWarning::UnboundedLoop(members.clone()));
+        }
+        report.loops.push(members);
+    }
+    report
+}
+
+// Where a run can go after each link (`n` for the end of the chain): its branches up to
+// the first that is always taken, the next link unless one is, and its error handler.
+fn successors(n: usize, branches: &[Edge], error_jumps: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut succs = vec![Vec::new(); n];
+    for (i, out) in succs.iter_mut().enumerate() {
+        let mut falls_through = true;
+        for branch in branches.iter().filter(|b| b.source == i && b.target < n) {
+            out.push(branch.target);
+            if branch.always {
+                falls_through = false;
+                break;
+            }
+        }
+        if falls_through {
+            out.push(i + 1);
+        }
+    }
+    for &(source, handler) in error_jumps.iter().filter(|(s, h)| *s < n && *h < n) {
+        succs[source].push(handler);
+    }
+    succs
+}
+
+// Nodes reachable from `start` along `edges`, `start` included; edges may lead to the
+// node one past the last.
+fn reach(start: usize, edges: &[Vec<usize>]) -> Vec<bool> {
+    let mut seen = vec![false; edges.len() + 1];
+    let mut stack = vec![start];
+    while let Some(i) = stack.pop() {
+        if std::mem::replace(&mut seen[i], true) {
+            continue;
+        }
+        if let Some(out) = edges.get(i) {
+            stack.extend(out.iter().copied().filter(|&t| !seen[t]));
+        }
+    }
+    seen
+}
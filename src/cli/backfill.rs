@@ -0,0 +1,308 @@
+//! `modulink-cli backfill`: replay historical inputs through a chain.
+//!
+//! The source is a `file://` spec naming JSON-lines files, with `*` and `?` allowed in the
+//! file name (`file://events/*.jsonl`); matching files are replayed in name order, one run
+//! per JSON-object line. Up to `concurrency` runs are in flight at once, and `rate` caps
+//! how fast runs start.
+//!
+//! Progress is kept in a cursor file (`.backfill-<chain>.json` unless given): the number of
+//! lines finished in each file. Runs finish out of order, but the cursor only counts the
+//! unbroken prefix of finished lines, so an interrupted backfill started again with the same
+//! cursor resumes where it stopped without skipping anything. Failed runs are reported and
+//! do not stop the backfill. A dry run shadow-runs the inputs (`ChainGeneric::run_shadow`):
+//! links tagged with `LinkSpec::side_effects` are skipped, nothing reaches the chain's
+//! sinks, and the cursor is left alone.
+
+use super::CliError;
+use crate::chains::{Chain, RunError, RunStatus, Shadow};
+use crate::context::Context;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// How often progress is printed and the cursor saved.
+const PROGRESS_EVERY: Duration = Duration::from_secs(1);
+
+/// A rate limit such as `200/s`, `1000/m`, or `3600/h` (a bare number is per second).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rate {
+    pub count: u32,
+    pub per: Duration,
+}
+
+impl Rate {
+    /// Time between two run starts.
+    pub fn interval(&self) -> Duration {
+        self.per / self.count
+    }
+}
+
+impl FromStr for Rate {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (count, unit) = s.split_once('/').unwrap_or((s, "s"));
+        let per = match unit.trim() {
+            "s" | "sec" => Duration::from_secs(1),
+            "m" | "min" => Duration::from_secs(60),
+            "h" | "hour" => Duration::from_secs(3600),
+            other => return Err(format!("unknown rate unit '{}' (use s, m, or h)", other)),
+        };
+        match count.trim().parse::<u32>() {
+            Ok(count) if count > 0 => Ok(Rate { count, per }),
+            _ => Err(format!("invalid rate '{}': expected e.g. 200/s", s)),
+        }
+    }
+}
+
+/// Lines finished per file, as saved in the cursor file.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub files: BTreeMap<String, usize>,
+}
+
+impl Cursor {
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Cursor::default()),
+            Err(e) => Err(e),
+        }
+    }
+    /// Write through a temporary file, so an interrupted save leaves the old cursor intact.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(self).unwrap_or_default())?;
+        std::fs::rename(tmp, path)
+    }
+}
+
+/// A replayed input whose run failed.
+#[derive(Debug, Clone)]
+pub struct BackfillFailure {
+    pub file: String,
+    /// 1-based line number of the input.
+    pub line: usize,
+    pub error: RunError,
+}
+
+/// What a backfill did (or, for a dry run, would do).
+#[derive(Debug, Clone, Default)]
+pub struct BackfillSummary {
+    pub files: usize,
+    /// Inputs run (shadow-run, for a dry run).
+    pub replayed: usize,
+    /// Lines skipped because the cursor had them as finished.
+    pub resumed: usize,
+    /// Non-blank lines that are not JSON objects; skipped.
+    pub invalid: usize,
+    pub failed: Vec<BackfillFailure>,
+}
+
+/// A backfill of one source through a chain; see the [module docs](self).
+#[derive(Debug, Clone)]
+pub struct Backfill {
+    source: String,
+    concurrency: usize,
+    rate: Option<Rate>,
+    cursor: Option<PathBuf>,
+    dry_run: bool,
+    progress: bool,
+}
+
+impl Backfill {
+    /// Backfill from `source`, one run at a time, without a rate limit or progress output.
+    pub fn new(source: impl Into<String>) -> Self {
+        Backfill { source: source.into(), concurrency: 1, rate: None, cursor: None, dry_run: false, progress: false }
+    }
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+    pub fn rate(mut self, rate: Rate) -> Self {
+        self.rate = Some(rate);
+        self
+    }
+    /// Cursor file; defaults to `.backfill-<chain>.json` in the working directory.
+    pub fn cursor(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cursor = Some(path.into());
+        self
+    }
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+    /// Print progress to stderr every second.
+    pub fn progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    /// Replay the source through `chain`, registered as `name` (used for the default cursor).
+    pub async fn run(&self, name: &str, chain: &Chain) -> Result<BackfillSummary, CliError> {
+        let files = source_files(&self.source)?;
+        let cursor_path = self.cursor.clone().unwrap_or_else(|| PathBuf::from(format!(".backfill-{}.json", name)));
+        let mut cursor = Cursor::load(&cursor_path)?;
+        let mut summary = BackfillSummary { files: files.len(), ..Default::default() };
+        let started = Instant::now();
+        let mut reported = Instant::now();
+        let mut starts = 0u32;
+        let shadow = Shadow::new();
+
+        for (i, file) in files.iter().enumerate() {
+            let key = file.display().to_string();
+            let done = cursor.files.get(&key).copied().unwrap_or(0);
+            let reader = BufReader::new(tokio::fs::File::open(file).await?);
+            let lines = stream::unfold(reader.lines(), |mut lines| async move { lines.next_line().await.transpose().map(|line| (line, lines)) });
+            let runs = lines
+                .enumerate()
+                .skip(done)
+                .map(|(n, line)| {
+                    let input = line.map(|line| parse_input(&line));
+                    let start = match (&input, self.rate) {
+                        (Ok(Some(Ok(_))), Some(rate)) => {
+                            starts += 1;
+                            Some(started + rate.interval() * (starts - 1))
+                        }
+                        _ => None,
+                    };
+                    let shadow = &shadow;
+                    async move {
+                        let outcome = match input? {
+                            Some(Ok(ctx)) => {
+                                if let Some(start) = start {
+                                    tokio::time::sleep_until(start.into()).await;
+                                }
+                                let (_, report) = match self.dry_run {
+                                    true => chain.run_shadow(ctx, shadow).await,
+                                    false => chain.run_with_report(ctx).await,
+                                };
+                                match report.status {
+                                    RunStatus::Failed(err) => Outcome::Failed(err),
+                                    _ => Outcome::Replayed,
+                                }
+                            }
+                            Some(Err(())) => Outcome::Invalid,
+                            None => Outcome::Blank,
+                        };
+                        Ok::<_, std::io::Error>((n, outcome))
+                    }
+                })
+                .buffered(self.concurrency);
+            let mut runs = std::pin::pin!(runs);
+
+            summary.resumed += done;
+            while let Some(result) = runs.next().await {
+                let (n, outcome) = result?;
+                match outcome {
+                    Outcome::Replayed => summary.replayed += 1,
+                    Outcome::Invalid => summary.invalid += 1,
+                    Outcome::Failed(error) => {
+                        summary.replayed += 1;
+                        if self.progress {
+                            eprintln!("[backfill] {}:{}: {}", key, n + 1, error);
+                        }
+                        summary.failed.push(BackfillFailure { file: key.clone(), line: n + 1, error });
+                    }
+                    Outcome::Blank => {}
+                }
+                // Results arrive in line order, so every line up to `n` is finished
+                cursor.files.insert(key.clone(), n + 1);
+                if reported.elapsed() >= PROGRESS_EVERY {
+                    reported = Instant::now();
+                    self.checkpoint(&cursor, &cursor_path)?;
+                    if self.progress {
+                        let per_sec = summary.replayed as f64 / started.elapsed().as_secs_f64();
+                        eprintln!(
+                            "[backfill] {}: file {}/{} line {}, {} replayed, {} failed, {:.1}/s",
+                            name,
+                            i + 1,
+                            files.len(),
+                            n + 1,
+                            summary.replayed,
+                            summary.failed.len(),
+                            per_sec
+                        );
+                    }
+                }
+            }
+            self.checkpoint(&cursor, &cursor_path)?;
+        }
+        Ok(summary)
+    }
+
+    fn checkpoint(&self, cursor: &Cursor, path: &Path) -> std::io::Result<()> {
+        if self.dry_run {
+            return Ok(());
+        }
+        cursor.save(path)
+    }
+}
+
+enum Outcome {
+    Replayed,
+    Failed(RunError),
+    Invalid,
+    Blank,
+}
+
+// None for blank lines, Err(()) for lines that are not JSON objects.
+fn parse_input(line: &str) -> Option<Result<Context, ()>> {
+    if line.trim().is_empty() {
+        return None;
+    }
+    Some(match serde_json::from_str::<serde_json::Value>(line) {
+        Ok(serde_json::Value::Object(map)) => Ok(Context::from(map)),
+        _ => Err(()),
+    })
+}
+
+/// Files named by a `file://` (or `file:`) spec, in name order.
+pub fn source_files(spec: &str) -> Result<Vec<PathBuf>, CliError> {
+    let invalid = || CliError::InvalidArgument(format!("unsupported backfill source '{}': expected file://<path>", spec));
+    let path = spec.strip_prefix("file://").or_else(|| spec.strip_prefix("file:")).ok_or_else(invalid)?;
+    let path = Path::new(path);
+    let name = path.file_name().and_then(|name| name.to_str()).ok_or_else(invalid)?;
+    if !name.contains(['*', '?']) {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_file() && entry.file_name().to_str().is_some_and(|file| wildcard_match(name, file)) {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let (pattern, name): (Vec<char>, Vec<char>) = (pattern.chars().collect(), name.chars().collect());
+    // Classic backtracking over the last `*`
+    let (mut p, mut n, mut star, mut mark) = (0, 0, None, 0);
+    while n < name.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == name[n]) {
+            p += 1;
+            n += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some(p);
+            mark = n;
+            p += 1;
+        } else if let Some(s) = star {
+            p = s + 1;
+            mark += 1;
+            n = mark;
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|c| *c == '*')
+}
@@ -0,0 +1,151 @@
+//! `modulink-cli check`: static checks of definition files, for CI.
+//!
+//! Every definition file (`.json`, `.yaml`/`.yml`, `.toml`) under the given paths is read
+//! as an `AppDefinition` with its includes and `use` references, and each of its chains is
+//! built against the registry and validated (`ChainGeneric::validation_report`). Errors are
+//! files that do not parse, links and chains nobody registered, branches out of range, and
+//! the errors of validation; warnings are deprecated links, the warnings of validation, and
+//! files that define nothing. Listeners must name a chain of their app or of the registry.
+//!
+//! Nothing is run. Secrets are not read: `secret://` references resolve to empty strings,
+//! so CI needs no access to them; environment variables are read as usual. WASM modules
+//! are not instantiated.
+
+use crate::chains::Chain;
+use crate::context::Context;
+use crate::definitions::{AppDefinition, DefinitionError, Interpolation, WasmHost, WasmModule};
+use crate::links::Link;
+use crate::registry;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// File extensions read as definitions.
+pub const DEFINITION_EXTENSIONS: [&str; 4] = ["json", "yaml", "yml", "toml"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// One problem found in a definition file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file: PathBuf,
+    /// The chain the problem is in, if it is in one.
+    pub chain: Option<String>,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}: ", self.file.display(), severity)?;
+        if let Some(chain) = &self.chain {
+            write!(f, "chain '{}': ", chain)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+/// What [`check`] found.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CheckSummary {
+    pub files: usize,
+    pub chains: usize,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CheckSummary {
+    pub fn errors(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Error).count()
+    }
+    pub fn warnings(&self) -> usize {
+        self.diagnostics.iter().filter(|d| d.severity == Severity::Warning).count()
+    }
+}
+
+// Accepts every module with a link that does nothing: `check` and `visualize` do not
+// load custom code.
+pub(super) struct NoWasm;
+
+impl WasmHost for NoWasm {
+    fn instantiate(&self, _module: &WasmModule) -> Result<Link, String> {
+        Ok(Arc::new(|ctx: Context| Box::pin(async move { ctx })))
+    }
+}
+
+/// Check the definition files at `paths`: files, or directories searched recursively.
+pub fn check(paths: &[PathBuf]) -> std::io::Result<CheckSummary> {
+    let interpolation = Interpolation::new().with_secrets(|_: &str| Ok(String::new()));
+    let mut summary = CheckSummary::default();
+    for path in paths {
+        for file in definition_files(path)? {
+            summary.files += 1;
+            check_file(&file, &interpolation, &mut summary);
+        }
+    }
+    Ok(summary)
+}
+
+/// Definition files at `path`, in name order.
+pub fn definition_files(path: &Path) -> std::io::Result<Vec<PathBuf>> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(path)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(definition_files(&path)?);
+        } else if path.extension().and_then(|ext| ext.to_str()).is_some_and(|ext| DEFINITION_EXTENSIONS.contains(&ext)) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn check_file(file: &Path, interpolation: &Interpolation, summary: &mut CheckSummary) {
+    let mut report = |chain: Option<&str>, severity: Severity, message: String| {
+        summary.diagnostics.push(Diagnostic { file: file.to_path_buf(), chain: chain.map(str::to_string), severity, message });
+    };
+    let app = match AppDefinition::from_file_with(file, interpolation) {
+        Ok(app) => app,
+        Err(err) => return report(None, Severity::Error, err.to_string()),
+    };
+    if app.chains.is_empty() && app.listeners.is_empty() {
+        report(None, Severity::Warning, "defines no chains or listeners".to_string());
+    }
+    for def in &app.chains {
+        let name = Some(def.name.as_str());
+        for warning in def.warnings() {
+            report(name, Severity::Warning, warning);
+        }
+        let chain: Chain = match def.build(Some(&NoWasm)) {
+            Ok(chain) => chain,
+            Err(err) => {
+                report(name, Severity::Error, err.to_string());
+                continue;
+            }
+        };
+        let validation = chain.validation_report();
+        for err in validation.errors {
+            report(name, Severity::Error, err.to_string());
+        }
+        for warning in validation.warnings {
+            report(name, Severity::Warning, warning.to_string());
+        }
+    }
+    for listener in &app.listeners {
+        let chain = listener.chain();
+        if !app.chains.iter().any(|def| def.name == chain) && registry::get_chain(chain).is_none() {
+            report(None, Severity::Error, DefinitionError::UnknownChain(chain.to_string()).to_string());
+        }
+    }
+    summary.chains += app.chains.len();
+}
@@ -1,38 +1,12 @@
 //! CLI entry point for modulink-rust
-//! Supports: run, visualize, doc
+//! Supports: run, visualize, doc, pipe, new, retry, backfill, check
+//!
+//! This stock binary registers no chains and no Kafka connectors, so `pipe`, `retry`, and
+//! `backfill` need a project binary instead (see `modulink_rs::cli`).
 
-use clap::{Parser, Subcommand};
+use modulink_rs::pipe::Connectors;
 
-#[derive(Parser)]
-#[command(name = "modulink-cli")]
-#[command(about = "ModuLink-Rust CLI tools", long_about = None)]
-struct Cli {
-    #[command(subcommand)]
-    command: Commands,
-}
-
-#[derive(Subcommand)]
-enum Commands {
-    /// Run a chain with input context
-    Run {
-        #[arg(short, long)]
-        input: Option<String>,
-    },
-    /// Visualize a chain as DOT/Graphviz
-    Visualize {},
-    /// Show documentation/help
-    Doc {
-        #[arg(short, long)]
-        topic: Option<String>,
-    },
-}
-
-fn main() {
-    let cli = Cli::parse();
-    match &cli.command {
-        Commands::Run { input } => {
-            println!("[CLI] Run chain with input: {:?}", input);
-            // TODO: Load chain, parse input, run chain
-        }
-    }
+#[tokio::main]
+async fn main() {
+    modulink_rs::cli::main_with(Connectors::default()).await;
 }
@@ -0,0 +1,355 @@
+//! CLI for modulink-rust
+//! Supports: run, visualize, doc, pipe, new, retry, backfill, check
+//!
+//! Chains are looked up in `crate::registry`, so a project that wants its chains on the
+//! command line ships a small binary that registers them and hands off to [`main_with`].
+//! The stock `modulink-cli` registers none: there, `pipe`, `retry`, and `backfill` fail
+//! with [`CliError::NoChains`], and only the commands that read definition files
+//! (`visualize --file`, `check`) or need no chains (`new`, `doc`) are useful.
+//!
+//! ```rust,no_run
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::pipe::Connectors;
+//! use modulink_rs::registry;
+//! use std::sync::Arc;
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     registry::register_chain("enrich", Arc::new(Chain::new()));
+//!     modulink_rs::cli::main_with(Connectors::default()).await;
+//! }
+//! ```
+
+pub mod backfill;
+pub mod check;
+pub mod scaffold;
+
+use crate::chains::{Chain, RunError, RunReport, RunStatus};
+use crate::context::Context;
+use crate::definitions::{AppDefinition, DefinitionError};
+use crate::pipe::{self, Connectors, PipeError};
+use crate::registry;
+use clap::{Parser, Subcommand};
+use backfill::{Backfill, Rate};
+use scaffold::ScaffoldKind;
+use std::path::{Path, PathBuf};
+
+/// Errors from CLI commands.
+#[derive(Debug)]
+pub enum CliError {
+    Pipe(PipeError),
+    Io(std::io::Error),
+    /// No chain is registered under this name.
+    UnknownChain(String),
+    /// A run started by the command failed.
+    RunFailed(RunError),
+    /// An argument could not be used (e.g. an unsupported source spec).
+    InvalidArgument(String),
+    /// `check` found this many problems.
+    CheckFailed(usize),
+    /// The command needs registered chains and the binary registered none (e.g. the stock
+    /// `modulink-cli`).
+    NoChains(&'static str),
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CliError::Pipe(e) => write!(f, "{}", e),
+            CliError::Io(e) => write!(f, "{}", e),
+            CliError::UnknownChain(name) => write!(f, "no chain registered as '{}'", name),
+            CliError::RunFailed(err) => write!(f, "run failed: {}", err),
+            CliError::InvalidArgument(msg) => write!(f, "{}", msg),
+            CliError::CheckFailed(count) => write!(f, "check failed with {} problem(s)", count),
+            CliError::NoChains(command) => write!(
+                f,
+                "no chains are registered; `{}` needs a project binary that registers its chains and calls `modulink_rs::cli::main_with`",
+                command
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+impl From<PipeError> for CliError {
+    fn from(e: PipeError) -> Self {
+        CliError::Pipe(e)
+    }
+}
+
+impl From<std::io::Error> for CliError {
+    fn from(e: std::io::Error) -> Self {
+        CliError::Io(e)
+    }
+}
+
+#[derive(Parser)]
+#[command(name = "modulink-cli")]
+#[command(about = "ModuLink-Rust CLI tools", long_about = None)]
+#[command(after_help = "pipe, retry, and backfill run registered chains, so they need a project binary that registers them and calls `modulink_rs::cli::main_with`; the stock modulink-cli registers none.")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Commands,
+}
+
+/// Output of `visualize`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum GraphFormat {
+    /// Graphviz DOT (`ChainGeneric::to_dot`)
+    Dot,
+    /// Mermaid flowchart (`ChainGeneric::to_mermaid`)
+    Mermaid,
+}
+
+#[derive(Subcommand)]
+pub enum Commands {
+    /// Run a chain with input context
+    Run {
+        #[arg(short, long)]
+        input: Option<String>,
+    },
+    /// Print a chain as DOT/Graphviz or Mermaid
+    /// (e.g. `visualize refunds | dot -Tsvg > refunds.svg`)
+    Visualize {
+        /// Name of a registered chain, or of a chain in `--file`; every chain of the file
+        /// when absent
+        chain: Option<String>,
+        /// Definition file to read chains from instead of the registry
+        #[arg(long)]
+        file: Option<PathBuf>,
+        #[arg(long, value_enum, default_value_t = GraphFormat::Dot)]
+        format: GraphFormat,
+    },
+    /// Show documentation; `--topic links` (the default) lists registered links
+    Doc {
+        #[arg(short, long)]
+        topic: Option<String>,
+    },
+    /// Connect a listener, a registered chain, and a sink
+    /// (e.g. `pipe --from kafka:orders --chain enrich --to http:https://example/ingest`).
+    /// Needs a project binary that registers the chain (and Kafka connectors for kafka:)
+    Pipe {
+        /// Listener spec: stdin, http:<addr>, kafka:<topic>
+        #[arg(long)]
+        from: String,
+        /// Name of a registered chain
+        #[arg(long)]
+        chain: String,
+        /// Sink spec: stdout, file:<path>, http:<url>, kafka:<topic>
+        #[arg(long)]
+        to: String,
+    },
+    /// Generate boilerplate for a link, chain, or listener (e.g. `new link my_step`)
+    New {
+        kind: ScaffoldKind,
+        /// snake_case name of the new item
+        name: String,
+        /// Project root (the directory with Cargo.toml)
+        #[arg(long, default_value = ".")]
+        path: PathBuf,
+    },
+    /// Re-run a failed durable run from a named link, optionally patching its context
+    /// (e.g. `retry --run order-17 --from-step charge --patch fix.json`).
+    /// Needs a project binary that registers the chain
+    Retry {
+        /// Run id the run was started with (`ChainGeneric::run_durable`)
+        #[arg(long)]
+        run: String,
+        /// `LinkSpec::name` of the link to continue at
+        #[arg(long)]
+        from_step: String,
+        /// Name of a registered chain; defaults to the one holding the run's checkpoint
+        #[arg(long)]
+        chain: Option<String>,
+        /// JSON file merged into the checkpointed context (JSON merge patch)
+        #[arg(long)]
+        patch: Option<PathBuf>,
+    },
+    /// Replay historical inputs through a chain
+    /// (e.g. `backfill --chain enrich --source file://events/*.jsonl --concurrency 16 --rate 200/s`).
+    /// Needs a project binary that registers the chain
+    Backfill {
+        /// Name of a registered chain
+        #[arg(long)]
+        chain: String,
+        /// JSON-lines files: file://<path>, with * and ? allowed in the file name
+        #[arg(long)]
+        source: String,
+        /// Runs in flight at once
+        #[arg(long, default_value_t = 1)]
+        concurrency: usize,
+        /// Highest rate of run starts, e.g. 200/s or 1000/m
+        #[arg(long)]
+        rate: Option<Rate>,
+        /// Cursor file to resume from; defaults to .backfill-<chain>.json
+        #[arg(long)]
+        cursor: Option<PathBuf>,
+        /// Shadow-run the inputs: skip side-effecting links and sinks, keep the cursor
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Check definition files without running them: parse, resolve links against the
+    /// registry, and validate each chain (e.g. `check pipelines/`)
+    Check {
+        /// Definition files, or directories to search for them
+        #[arg(required = true)]
+        paths: Vec<PathBuf>,
+        /// Fail on warnings too
+        #[arg(long)]
+        deny_warnings: bool,
+    },
+}
+
+pub async fn run(cli: Cli, connectors: &Connectors) -> Result<(), CliError> {
+    let needs_chains = match &cli.command {
+        Commands::Pipe { .. } => Some("pipe"),
+        Commands::Retry { .. } => Some("retry"),
+        Commands::Backfill { .. } => Some("backfill"),
+        _ => None,
+    };
+    if let Some(command) = needs_chains.filter(|_| registry::chain_names().is_empty()) {
+        return Err(CliError::NoChains(command));
+    }
+    match cli.command {
+        Commands::Run { input } => {
+            println!("[CLI] Run chain with input: {:?}", input);
+            // TODO: Load chain, parse input, run chain
+        }
+        Commands::Visualize { chain, file, format } => print!("{}", visualize(chain.as_deref(), file.as_deref(), format)?),
+        Commands::Doc { topic } => match topic.as_deref() {
+            None | Some("links") => print!("{}", links_doc()),
+            Some(other) => println!("[CLI] Unknown doc topic: {}", other),
+        },
+        Commands::Pipe { from, chain, to } => {
+            pipe::run_pipe(&from, &chain, &to, connectors).await?;
+        }
+        Commands::New { kind, name, path } => {
+            for file in scaffold::generate(kind, &name, &path)? {
+                println!("wrote {}", file.display());
+            }
+        }
+        Commands::Retry { run, from_step, chain, patch } => {
+            let (ctx, report) = retry(&run, &from_step, chain.as_deref(), patch.as_deref()).await?;
+            println!("{}", serde_json::to_string(&ctx.0).unwrap_or_default());
+            match report.status {
+                RunStatus::Failed(err) => return Err(CliError::RunFailed(err)),
+                status => eprintln!("run '{}': {:?}", run, status),
+            }
+        }
+        Commands::Backfill { chain: name, source, concurrency, rate, cursor, dry_run } => {
+            let chain = registry::get_chain(&name).ok_or_else(|| CliError::UnknownChain(name.clone()))?;
+            let mut backfill = Backfill::new(source).concurrency(concurrency).dry_run(dry_run).progress(true);
+            if let Some(rate) = rate {
+                backfill = backfill.rate(rate);
+            }
+            if let Some(cursor) = cursor {
+                backfill = backfill.cursor(cursor);
+            }
+            let summary = backfill.run(&name, &chain).await?;
+            println!(
+                "{}{} files, {} inputs {}, {} resumed past, {} invalid, {} failed",
+                if dry_run { "[dry run] " } else { "" },
+                summary.files,
+                summary.replayed,
+                if dry_run { "shadow-run" } else { "replayed" },
+                summary.resumed,
+                summary.invalid,
+                summary.failed.len()
+            );
+        }
+        Commands::Check { paths, deny_warnings } => {
+            let summary = check::check(&paths)?;
+            for diagnostic in &summary.diagnostics {
+                println!("{}", diagnostic);
+            }
+            println!(
+                "checked {} files, {} chains: {} errors, {} warnings",
+                summary.files,
+                summary.chains,
+                summary.errors(),
+                summary.warnings()
+            );
+            let problems = if deny_warnings { summary.diagnostics.len() } else { summary.errors() };
+            if problems > 0 {
+                return Err(CliError::CheckFailed(problems));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// `modulink-cli retry`: re-run `run_id` from the link named `from_step` with
+/// `ChainGeneric::retry_from_step`, merging the JSON in `patch` into its context.
+/// Without `chain`, the registered chain whose checkpoint store holds the run is used.
+pub async fn retry(run_id: &str, from_step: &str, chain: Option<&str>, patch: Option<&Path>) -> Result<(Context, RunReport), CliError> {
+    let chain = match chain {
+        Some(name) => registry::get_chain(name).ok_or_else(|| CliError::UnknownChain(name.to_string()))?,
+        None => match registry::chain_with_run(run_id).await? {
+            Some((_, chain)) => chain,
+            None => {
+                let msg = format!("no registered chain has a checkpoint for run '{}'", run_id);
+                return Err(std::io::Error::new(std::io::ErrorKind::NotFound, msg).into());
+            }
+        },
+    };
+    let patch = match patch {
+        Some(path) => {
+            let text = std::fs::read_to_string(path)?;
+            Some(serde_json::from_str(&text).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, format!("{}: {}", path.display(), e)))?)
+        }
+        None => None,
+    };
+    Ok(chain.retry_from_step(run_id, from_step, patch).await?)
+}
+
+/// `modulink-cli visualize`: the graph of the chain `name` of the registry, or of the
+/// chains of the definition file `file` (only `name`, if given), one after another.
+pub fn visualize(name: Option<&str>, file: Option<&Path>, format: GraphFormat) -> Result<String, CliError> {
+    let render = |chain: &Chain| match format {
+        GraphFormat::Dot => chain.to_dot(),
+        GraphFormat::Mermaid => chain.to_mermaid(),
+    };
+    let Some(file) = file else {
+        let name = name.ok_or_else(|| CliError::InvalidArgument("visualize needs a chain name or --file".to_string()))?;
+        let chain = registry::get_chain(name).ok_or_else(|| CliError::UnknownChain(name.to_string()))?;
+        return Ok(render(&chain));
+    };
+    let invalid = |e: DefinitionError| CliError::InvalidArgument(format!("{}: {}", file.display(), e));
+    let app = AppDefinition::from_file(file).map_err(invalid)?;
+    let defs: Vec<_> = app.chains.iter().filter(|def| name.is_none_or(|name| def.name == name)).collect();
+    if defs.is_empty() {
+        return Err(invalid(DefinitionError::UnknownChain(name.unwrap_or_default().to_string())));
+    }
+    let mut out = String::new();
+    for def in defs {
+        let mut chain = def.build(Some(&check::NoWasm)).map_err(invalid)?;
+        chain.set_name(def.name.clone());
+        out.push_str(&render(&chain));
+    }
+    Ok(out)
+}
+
+/// One line per registered link with its version, description, and deprecation notice.
+pub fn links_doc() -> String {
+    registry::link_names()
+        .into_iter()
+        .map(|name| {
+            let metadata = registry::link_metadata(&name).unwrap_or_default().to_string();
+            if metadata.is_empty() {
+                format!("{}\n", name)
+            } else {
+                format!("{}: {}\n", name, metadata)
+            }
+        })
+        .collect()
+}
+
+/// Parse process arguments, run the command, and exit non-zero on error.
+pub async fn main_with(connectors: Connectors) {
+    let cli = Cli::parse();
+    if let Err(e) = run(cli, &connectors).await {
+        eprintln!("modulink-cli: {}", e);
+        std::process::exit(1);
+    }
+}
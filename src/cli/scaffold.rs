@@ -0,0 +1,230 @@
+//! `modulink-cli new`: boilerplate for links, chains, and listeners.
+//!
+//! Files go where this crate's own layout puts them: `src/links/<name>.rs`,
+//! `src/chains/<name>.rs`, or `src/listeners/<name>.rs`, each with a test stub in
+//! `tests/test_<name>.rs`. The module is declared in the directory's `mod.rs` (created if
+//! missing), and the directory in `src/lib.rs`. Links and chains come with a `register()`
+//! function that adds them to `modulink_rs::registry`, so definitions and
+//! `modulink-cli pipe` can find them.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ScaffoldKind {
+    Link,
+    Chain,
+    Listener,
+}
+
+impl ScaffoldKind {
+    fn dir(self) -> &'static str {
+        match self {
+            ScaffoldKind::Link => "links",
+            ScaffoldKind::Chain => "chains",
+            ScaffoldKind::Listener => "listeners",
+        }
+    }
+}
+
+fn is_snake_case(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+fn source(kind: ScaffoldKind, name: &str) -> String {
+    match kind {
+        ScaffoldKind::Link => format!(
+            r#"use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::registry::{{self, LinkMetadata}};
+use std::sync::Arc;
+
+/// TODO: describe what `{name}` does.
+pub fn {name}() -> Link {{
+    Arc::new(|ctx: Context| Box::pin(async move {{
+        ctx
+    }}))
+}}
+
+/// Register `{name}` so chain definitions can reference it by name.
+pub fn register() {{
+    registry::register_link_with(
+        "{name}",
+        {name}(),
+        LinkMetadata::new().version("0.1.0").description("TODO: describe {name}"),
+    );
+}}
+"#
+        ),
+        ScaffoldKind::Chain => format!(
+            r#"use modulink_rs::chains::Chain;
+use modulink_rs::registry;
+use std::sync::Arc;
+
+/// TODO: add the links of `{name}`.
+pub fn {name}() -> Chain {{
+    let mut chain = Chain::new();
+    chain.set_name("{name}");
+    chain
+}}
+
+/// Register `{name}` so `modulink-cli pipe --chain {name}` can run it.
+pub fn register() {{
+    registry::register_chain("{name}", Arc::new({name}()));
+}}
+"#
+        ),
+        ScaffoldKind::Listener => format!(
+            r#"use async_trait::async_trait;
+use modulink_rs::links::{{Link, ListenerAsync}};
+
+/// TODO: describe where `{name}` receives events from.
+pub struct {ty} {{
+    pub handler: Link,
+}}
+
+#[async_trait]
+impl ListenerAsync for {ty} {{
+    async fn start(&self) -> std::io::Result<()> {{
+        // TODO: receive events, turn each into a Context, and call `(self.handler)(ctx).await`
+        Ok(())
+    }}
+    fn name(&self) -> &'static str {{
+        "{name}"
+    }}
+}}
+"#,
+            ty = camel_case(name)
+        ),
+    }
+}
+
+fn test_source(kind: ScaffoldKind, crate_name: &str, name: &str) -> String {
+    let path = format!("{}::{}::{}", crate_name, kind.dir(), name);
+    match kind {
+        ScaffoldKind::Link => format!(
+            r#"use modulink_rs::context::Context;
+
+#[tokio::test]
+async fn test_{name}() {{
+    let link = {path}();
+    let ctx = link(Context::new()).await;
+    // TODO: assert on the keys `{name}` provides
+    let _ = ctx;
+}}
+"#
+        ),
+        ScaffoldKind::Chain => format!(
+            r#"use modulink_rs::context::Context;
+
+#[tokio::test]
+async fn test_{name}() {{
+    let chain = {path}();
+    assert_eq!(chain.validate(), Ok(()));
+    let ctx = chain.run(Context::new()).await;
+    // TODO: assert on the final context
+    let _ = ctx;
+}}
+"#
+        ),
+        ScaffoldKind::Listener => format!(
+            r#"use modulink_rs::context::Context;
+use modulink_rs::links::ListenerAsync;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_{name}() {{
+    let listener = {path}::{ty} {{
+        handler: Arc::new(|ctx: Context| Box::pin(async move {{ ctx }})),
+    }};
+    assert_eq!(listener.name(), "{name}");
+}}
+"#,
+            ty = camel_case(name)
+        ),
+    }
+}
+
+// Package name from Cargo.toml, as used in paths from integration tests.
+fn crate_name(root: &Path) -> String {
+    let manifest = fs::read_to_string(root.join("Cargo.toml")).unwrap_or_default();
+    manifest
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("name"))
+        .filter_map(|rest| rest.trim_start().strip_prefix('='))
+        .map(|value| value.trim().trim_matches('"').replace('-', "_"))
+        .next()
+        .unwrap_or_else(|| "crate".to_string())
+}
+
+fn write_new(path: &Path, contents: &str) -> io::Result<()> {
+    if path.exists() {
+        return Err(io::Error::new(io::ErrorKind::AlreadyExists, format!("{} already exists", path.display())));
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, contents)
+}
+
+/// Generate the files for a new `kind` named `name` in the project at `root`.
+/// Returns the files created or changed. Existing files are never overwritten.
+pub fn generate(kind: ScaffoldKind, name: &str, root: &Path) -> io::Result<Vec<PathBuf>> {
+    if !is_snake_case(name) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, format!("'{}' is not a snake_case name", name)));
+    }
+    let dir = root.join("src").join(kind.dir());
+    let file = dir.join(format!("{}.rs", name));
+    let test = root.join("tests").join(format!("test_{}.rs", name));
+    write_new(&file, &source(kind, name))?;
+    write_new(&test, &test_source(kind, &crate_name(root), name))?;
+
+    let mod_rs = dir.join("mod.rs");
+    let decl = format!("pub mod {};", name);
+    if !fs::read_to_string(&mod_rs).unwrap_or_default().lines().any(|line| line.trim() == decl) {
+        append_line(&mod_rs, &decl)?;
+    }
+    let mut changed = vec![file, test, mod_rs];
+    if let Some(lib_rs) = declare_in_lib(root, kind.dir())? {
+        changed.push(lib_rs);
+    }
+    Ok(changed)
+}
+
+fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    let mut contents = fs::read_to_string(path).unwrap_or_default();
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    contents.push_str(line);
+    contents.push('\n');
+    fs::write(path, contents)
+}
+
+// Declare `src/<dir>` in `src/lib.rs` when the crate has a lib target that doesn't yet.
+fn declare_in_lib(root: &Path, dir: &str) -> io::Result<Option<PathBuf>> {
+    let lib_rs = root.join("src").join("lib.rs");
+    let Ok(lib) = fs::read_to_string(&lib_rs) else { return Ok(None) };
+    let declared = lib.lines().any(|line| {
+        let line = line.trim();
+        line == format!("mod {};", dir) || line == format!("pub mod {};", dir)
+    });
+    if declared {
+        return Ok(None);
+    }
+    append_line(&lib_rs, &format!("pub mod {};", dir))?;
+    Ok(Some(lib_rs))
+}
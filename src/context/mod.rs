@@ -14,10 +14,129 @@
 //! ```
 //!
 //! Advanced/generic APIs (ContextMutable) may use `mut` for performance, but must document the tradeoff.
+//!
+//! # Metadata
+//! Keys starting with `_` are reserved for run metadata (tenant, auth claims, request ids)
+//! rather than business data. Well-known keys live in [`meta`].
+//!
+//! # Unserializable values
+//! `insert` stores values as JSON. When a value fails to serialize (a map with non-string
+//! keys, a failing `Serialize` impl), what happens is up to the chain running the link: see
+//! [`SerializationPolicy`] and `ChainGeneric::set_serialization_policy`. Outside of a run,
+//! and by default, `insert` panics. `try_insert` returns the error instead.
+
+pub mod query;
+pub mod snapshot;
+pub mod values;
+
+pub use snapshot::ContextCheckpoint;
 
+use crate::chains::RunScope;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+/// Reserved metadata keys.
+pub mod meta {
+    /// Tenant id the run belongs to (see `crate::tenant`).
+    pub const TENANT: &str = "_tenant";
+    /// Validated auth claims (e.g. JWT claims), set by auth middleware or listeners.
+    pub const AUTH: &str = "_auth";
+    /// Raw `Authorization` header forwarded by a listener for auth middleware to validate.
+    pub const AUTHORIZATION: &str = "_authorization";
+    /// Correlation id of the request that started the run (`X-Request-Id`).
+    pub const REQUEST_ID: &str = "_request_id";
+    /// W3C `traceparent` header of the incoming request, when valid.
+    pub const TRACEPARENT: &str = "_traceparent";
+    /// Decisions recorded by links for the audit log (see `crate::audit`).
+    pub const DECISIONS: &str = "_decisions";
+    /// Hash of the run's input context, set by `audit::AuditMiddleware`.
+    pub const INPUT_HASH: &str = "_input_hash";
+    /// Request line of the HTTP request that started the run: `{"method", "path"}`.
+    pub const HTTP: &str = "_http";
+    /// Values that failed to serialize under [`SerializationPolicy::RecordError`]: an
+    /// object of the error message by key.
+    pub const SERIALIZATION_ERRORS: &str = "_serialization_errors";
+
+    /// Whether `key` is reserved for metadata: every key starting with `_`, including
+    /// the ones above. Reserved keys are set by the runtime, never taken from clients.
+    pub fn is_reserved(key: &str) -> bool {
+        key.starts_with('_')
+    }
+
+    /// Drop every reserved key from `map`. Listeners call this on each payload they build
+    /// a context from, before adding metadata of their own.
+    pub fn strip_reserved(map: &mut serde_json::Map<String, serde_json::Value>) {
+        map.retain(|key, _| !is_reserved(key));
+    }
+}
+
+/// What `insert` does with a value that fails to serialize during a run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SerializationPolicy {
+    /// Panic, failing the link (the default, and the behavior outside of a run).
+    #[default]
+    Panic,
+    /// Leave the context unchanged and add a run warning.
+    Skip,
+    /// Leave the key unchanged, add a run warning, and record the error under
+    /// [`meta::SERIALIZATION_ERRORS`] so later links can react to it.
+    RecordError,
+}
+
+/// A value `Context::try_insert` could not serialize, with the context it was inserted into.
+#[derive(Debug)]
+pub struct InsertError {
+    pub key: String,
+    pub error: serde_json::Error,
+    /// The context, unchanged.
+    pub ctx: Context,
+}
+
+impl std::fmt::Display for InsertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "could not serialize '{}': {}", self.key, self.error)
+    }
+}
+
+impl std::error::Error for InsertError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+// Apply the current run's policy to `key`, whose value failed to serialize.
+fn serialization_failed(map: &mut HashMap<String, Value>, key: String, error: serde_json::Error) {
+    let scope = RunScope::current();
+    let policy = scope.as_ref().map_or(SerializationPolicy::Panic, |scope| scope.serialization_policy());
+    let message = format!("could not serialize '{}': {}", key, error);
+    match policy {
+        SerializationPolicy::Panic => panic!("{}", message),
+        SerializationPolicy::Skip => {}
+        SerializationPolicy::RecordError => {
+            let errors = map.entry(meta::SERIALIZATION_ERRORS.to_string()).or_insert_with(|| Value::Object(Default::default()));
+            if !errors.is_object() {
+                *errors = Value::Object(Default::default());
+            }
+            if let Value::Object(errors) = errors {
+                errors.insert(key.to_string(), error.to_string().into());
+            }
+        }
+    }
+    tracing::warn!(key = %key, error = %error, "context value not serialized");
+    if let Some(scope) = scope {
+        scope.warn(message);
+    }
+}
+
+fn push_decision(map: &mut HashMap<String, Value>, decision: String) {
+    match map.entry(meta::DECISIONS.to_string()).or_insert_with(|| Value::Array(Vec::new())) {
+        Value::Array(decisions) => decisions.push(decision.into()),
+        other => *other = Value::Array(vec![decision.into()]),
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct Context(pub HashMap<String, Value>);
@@ -27,14 +146,93 @@ impl Context {
         Context(HashMap::new())
     }
     // Immutable insert: returns a new Context with the value inserted
+    // An unserializable value is handled by the run's `SerializationPolicy` (see module docs)
     pub fn insert<K: Into<String>, V: Serialize>(self, key: K, value: V) -> Self {
-        let mut new_ctx = self;
-        new_ctx.0.insert(key.into(), serde_json::to_value(value).unwrap());
-        new_ctx
+        match self.try_insert(key, value) {
+            Ok(ctx) => ctx,
+            Err(InsertError { key, error, mut ctx }) => {
+                serialization_failed(&mut ctx.0, key, error);
+                ctx
+            }
+        }
+    }
+    /// Insert `value`, or hand the context back in the error if it fails to serialize.
+    pub fn try_insert<K: Into<String>, V: Serialize>(self, key: K, value: V) -> Result<Self, InsertError> {
+        let key = key.into();
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                let mut new_ctx = self;
+                new_ctx.0.insert(key, value);
+                Ok(new_ctx)
+            }
+            Err(error) => Err(InsertError { key, error, ctx: self }),
+        }
     }
     pub fn get<V: for<'de> Deserialize<'de>>(&self, key: &str) -> Option<V> {
         self.0.get(key).and_then(|v| serde_json::from_value(v.clone()).ok())
     }
+    /// Insert `at` as an RFC3339 timestamp (see [`values`]).
+    pub fn insert_time<K: Into<String>>(self, key: K, at: SystemTime) -> Self {
+        self.insert(key, values::format_rfc3339(at))
+    }
+    pub fn get_time(&self, key: &str) -> Option<SystemTime> {
+        self.0.get(key)?.as_str().and_then(values::parse_rfc3339)
+    }
+    /// Insert `duration` as milliseconds (see [`values`]).
+    pub fn insert_duration<K: Into<String>>(self, key: K, duration: Duration) -> Self {
+        self.insert(key, duration.as_millis() as u64)
+    }
+    pub fn get_duration(&self, key: &str) -> Option<Duration> {
+        self.0.get(key)?.as_u64().map(Duration::from_millis)
+    }
+    /// Insert `bytes` as a base64 string (see [`values`]).
+    pub fn insert_bytes<K: Into<String>>(self, key: K, bytes: impl AsRef<[u8]>) -> Self {
+        self.insert(key, values::encode_base64(bytes))
+    }
+    pub fn get_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        self.0.get(key)?.as_str().and_then(values::decode_base64)
+    }
+    /// Tag the context with the tenant it belongs to.
+    pub fn with_tenant(self, tenant: impl Into<String>) -> Self {
+        self.insert(meta::TENANT, tenant.into())
+    }
+    pub fn tenant(&self) -> Option<String> {
+        self.get(meta::TENANT)
+    }
+    /// Record a decision taken during the run (e.g. "approved: under limit") for auditing.
+    pub fn record_decision(self, decision: impl Into<String>) -> Self {
+        let mut ctx = self;
+        push_decision(&mut ctx.0, decision.into());
+        ctx
+    }
+    /// Tag the current run, leaving the context unchanged (see `ctx_tools::annotate`).
+    pub fn annotate(&self, key: impl Into<String>, value: impl Serialize) -> bool {
+        crate::ctx_tools::annotate(key, value)
+    }
+    /// Values matching the JSONPath `path` (see [`query`]).
+    pub fn query(&self, path: &str) -> Result<Vec<Value>, String> {
+        Ok(query::JsonPath::parse(path)?.select(&self.0))
+    }
+    /// A copy of the context as it is now, to roll back to (see [`snapshot`]).
+    pub fn checkpoint(&self) -> ContextCheckpoint {
+        ContextCheckpoint::new(&self.0)
+    }
+    /// The context as it was when `checkpoint` was taken.
+    pub fn rollback_to(self, checkpoint: &ContextCheckpoint) -> Self {
+        Context(checkpoint.entries())
+    }
+}
+
+impl From<serde_json::Map<String, Value>> for Context {
+    fn from(map: serde_json::Map<String, Value>) -> Self {
+        Context(map.into_iter().collect())
+    }
+}
+
+impl From<Context> for serde_json::Map<String, Value> {
+    fn from(ctx: Context) -> Self {
+        ctx.0.into_iter().collect()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -45,10 +243,65 @@ impl ContextMutable {
         ContextMutable(HashMap::new())
     }
     // Mutable insert: mutates in place
+    // An unserializable value is handled by the run's `SerializationPolicy` (see module docs)
     pub fn insert<K: Into<String>, V: Serialize>(&mut self, key: K, value: V) {
-        self.0.insert(key.into(), serde_json::to_value(value).unwrap());
+        let key = key.into();
+        if let Err(error) = self.try_insert(key.clone(), value) {
+            serialization_failed(&mut self.0, key, error);
+        }
+    }
+    /// Insert `value`, leaving the context unchanged if it fails to serialize.
+    pub fn try_insert<K: Into<String>, V: Serialize>(&mut self, key: K, value: V) -> Result<(), serde_json::Error> {
+        self.0.insert(key.into(), serde_json::to_value(value)?);
+        Ok(())
     }
     pub fn get<V: for<'de> Deserialize<'de>>(&self, key: &str) -> Option<V> {
         self.0.get(key).and_then(|v| serde_json::from_value(v.clone()).ok())
     }
+    /// Insert `at` as an RFC3339 timestamp (see [`values`]).
+    pub fn insert_time<K: Into<String>>(&mut self, key: K, at: SystemTime) {
+        self.insert(key, values::format_rfc3339(at));
+    }
+    pub fn get_time(&self, key: &str) -> Option<SystemTime> {
+        self.0.get(key)?.as_str().and_then(values::parse_rfc3339)
+    }
+    /// Insert `duration` as milliseconds (see [`values`]).
+    pub fn insert_duration<K: Into<String>>(&mut self, key: K, duration: Duration) {
+        self.insert(key, duration.as_millis() as u64);
+    }
+    pub fn get_duration(&self, key: &str) -> Option<Duration> {
+        self.0.get(key)?.as_u64().map(Duration::from_millis)
+    }
+    /// Insert `bytes` as a base64 string (see [`values`]).
+    pub fn insert_bytes<K: Into<String>>(&mut self, key: K, bytes: impl AsRef<[u8]>) {
+        self.insert(key, values::encode_base64(bytes));
+    }
+    pub fn get_bytes(&self, key: &str) -> Option<Vec<u8>> {
+        self.0.get(key)?.as_str().and_then(values::decode_base64)
+    }
+    pub fn set_tenant(&mut self, tenant: impl Into<String>) {
+        self.insert(meta::TENANT, tenant.into());
+    }
+    pub fn tenant(&self) -> Option<String> {
+        self.get(meta::TENANT)
+    }
+    pub fn record_decision(&mut self, decision: impl Into<String>) {
+        push_decision(&mut self.0, decision.into());
+    }
+    /// Tag the current run, leaving the context unchanged (see `ctx_tools::annotate`).
+    pub fn annotate(&self, key: impl Into<String>, value: impl Serialize) -> bool {
+        crate::ctx_tools::annotate(key, value)
+    }
+    /// Values matching the JSONPath `path` (see [`query`]).
+    pub fn query(&self, path: &str) -> Result<Vec<Value>, String> {
+        Ok(query::JsonPath::parse(path)?.select(&self.0))
+    }
+    /// A copy of the context as it is now, to roll back to (see [`snapshot`]).
+    pub fn checkpoint(&self) -> ContextCheckpoint {
+        ContextCheckpoint::new(&self.0)
+    }
+    /// Undo every change made since `checkpoint` was taken.
+    pub fn rollback_to(&mut self, checkpoint: &ContextCheckpoint) {
+        self.0 = checkpoint.entries();
+    }
 }
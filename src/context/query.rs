@@ -0,0 +1,212 @@
+//! JSONPath queries over a context.
+//!
+//! Supported syntax: `$` (the context), `.field` and `['field']`, `[index]` (negative
+//! counts from the end), `.*` and `[*]`, `..` (every descendant, e.g. `$..id`), and filters
+//! `[?(<expression>)]` where `@` is the candidate element and the expression uses the
+//! condition language of [`crate::definitions::Expression`].
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use serde_json::json;
+//!
+//! let ctx = Context::new().insert("items", json!([
+//!     { "id": "a", "price": 5 },
+//!     { "id": "b", "price": 25 },
+//! ]));
+//! assert_eq!(ctx.query("$.items[?(@.price > 10)].id").unwrap(), vec![json!("b")]);
+//! assert_eq!(ctx.query("$..price").unwrap(), vec![json!(5), json!(25)]);
+//! ```
+
+use crate::definitions::Expression;
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Field(String),
+    Index(i64),
+    Wildcard,
+    /// The node itself and every node below it.
+    Descendants,
+    Filter(Expression),
+}
+
+/// A parsed JSONPath query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JsonPath {
+    source: String,
+    steps: Vec<Step>,
+}
+
+// The context map at the root, or a value inside it.
+#[derive(Clone, Copy)]
+enum Node<'a> {
+    Root(&'a HashMap<String, Value>),
+    Value(&'a Value),
+}
+
+impl<'a> Node<'a> {
+    fn children(self) -> Vec<&'a Value> {
+        match self {
+            Node::Root(map) => {
+                let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+                entries.sort_by_key(|(k, _)| *k);
+                entries.into_iter().map(|(_, v)| v).collect()
+            }
+            Node::Value(Value::Object(map)) => map.values().collect(),
+            Node::Value(Value::Array(items)) => items.iter().collect(),
+            Node::Value(_) => Vec::new(),
+        }
+    }
+    fn field(self, key: &str) -> Option<&'a Value> {
+        match self {
+            Node::Root(map) => map.get(key),
+            Node::Value(value) => value.get(key),
+        }
+    }
+    fn to_value(self) -> Value {
+        match self {
+            Node::Root(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), v.clone())).collect()),
+            Node::Value(value) => value.clone(),
+        }
+    }
+}
+
+fn descendants<'a>(node: Node<'a>, out: &mut Vec<Node<'a>>) {
+    out.push(node);
+    for child in node.children() {
+        descendants(Node::Value(child), out);
+    }
+}
+
+// Index of the `)` closing the `(` at `open`, skipping quoted strings.
+fn closing_paren(chars: &[char], open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut quote = None;
+    for (i, &c) in chars.iter().enumerate().skip(open) {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+impl JsonPath {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        Self::parse_nested(source, 0)
+    }
+    // Parse a query `depth` levels into an enclosing expression, which its filters continue.
+    pub(crate) fn parse_nested(source: &str, depth: usize) -> Result<Self, String> {
+        let chars: Vec<char> = source.trim().chars().collect();
+        if chars.first() != Some(&'$') {
+            return Err("a query starts with '$'".to_string());
+        }
+        let ident_len = |from: usize| chars[from..].iter().take_while(|c| c.is_alphanumeric() || **c == '_' || **c == '-').count();
+        let mut steps = Vec::new();
+        let mut i = 1;
+        while i < chars.len() {
+            match chars[i] {
+                '.' if chars.get(i + 1) == Some(&'.') => {
+                    steps.push(Step::Descendants);
+                    i += 2;
+                    if chars.get(i) == Some(&'[') {
+                        continue;
+                    }
+                    if chars.get(i) == Some(&'*') {
+                        steps.push(Step::Wildcard);
+                        i += 1;
+                        continue;
+                    }
+                    let len = ident_len(i);
+                    if len == 0 {
+                        return Err(format!("expected a name after '..' at {}", i));
+                    }
+                    steps.push(Step::Field(chars[i..i + len].iter().collect()));
+                    i += len;
+                }
+                '.' => {
+                    i += 1;
+                    if chars.get(i) == Some(&'*') {
+                        steps.push(Step::Wildcard);
+                        i += 1;
+                        continue;
+                    }
+                    let len = ident_len(i);
+                    if len == 0 {
+                        return Err(format!("expected a name after '.' at {}", i));
+                    }
+                    steps.push(Step::Field(chars[i..i + len].iter().collect()));
+                    i += len;
+                }
+                '[' => {
+                    let (step, end) = match chars.get(i + 1) {
+                        Some('*') => (Step::Wildcard, i + 2),
+                        Some(&q @ ('\'' | '"')) => {
+                            let len = chars[i + 2..].iter().position(|&c| c == q).ok_or("unterminated string")?;
+                            (Step::Field(chars[i + 2..i + 2 + len].iter().collect()), i + 3 + len)
+                        }
+                        Some('?') if chars.get(i + 2) == Some(&'(') => {
+                            let close = closing_paren(&chars, i + 2).ok_or("unclosed filter")?;
+                            let filter: String = chars[i + 3..close].iter().collect();
+                            (Step::Filter(Expression::parse_filter(&filter, depth)?), close + 1)
+                        }
+                        _ => {
+                            let len = chars[i + 1..].iter().take_while(|c| c.is_ascii_digit() || **c == '-').count();
+                            let text: String = chars[i + 1..i + 1 + len].iter().collect();
+                            let index = text.parse().map_err(|_| format!("invalid index '{}' at {}", text, i))?;
+                            (Step::Index(index), i + 1 + len)
+                        }
+                    };
+                    if chars.get(end) != Some(&']') {
+                        return Err(format!("expected ']' at {}", end));
+                    }
+                    steps.push(step);
+                    i = end + 1;
+                }
+                other => return Err(format!("unexpected '{}' at {}", other, i)),
+            }
+        }
+        Ok(JsonPath { source: source.to_string(), steps })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Values matching this query in the context map `root`, in document order.
+    pub fn select(&self, root: &HashMap<String, Value>) -> Vec<Value> {
+        let mut nodes = vec![Node::Root(root)];
+        for step in &self.steps {
+            let mut next = Vec::new();
+            for node in nodes {
+                match step {
+                    Step::Field(key) => next.extend(node.field(key).map(Node::Value)),
+                    Step::Index(i) => {
+                        if let Node::Value(Value::Array(items)) = node {
+                            let i = if *i < 0 { items.len() as i64 + i } else { *i };
+                            next.extend(usize::try_from(i).ok().and_then(|i| items.get(i)).map(Node::Value));
+                        }
+                    }
+                    Step::Wildcard => next.extend(node.children().into_iter().map(Node::Value)),
+                    Step::Descendants => descendants(node, &mut next),
+                    Step::Filter(expr) => next.extend(
+                        node.children().into_iter().filter(|child| expr.eval_filter(root, child)).map(Node::Value),
+                    ),
+                }
+            }
+            nodes = next;
+        }
+        nodes.into_iter().map(Node::to_value).collect()
+    }
+}
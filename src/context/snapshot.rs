@@ -0,0 +1,56 @@
+//! Checkpoints a link can roll its context back to.
+//!
+//! A link that transforms the context tentatively takes a [`ContextCheckpoint`] first
+//! (`Context::checkpoint`), and when the transformation fails part-way rolls back to it
+//! (`Context::rollback_to`) and carries on, instead of failing the whole run. Everything
+//! since the checkpoint is undone, metadata such as recorded decisions included.
+//!
+//! There is no structural sharing: taking a checkpoint deep-copies every value in the
+//! context, as cloning the context does, so it costs time and memory in proportion to the
+//! context's size. Rolling back copies the entries again. Cloning the checkpoint itself
+//! is cheap, so rolling back to it more than once or handing it to a helper adds nothing.
+//! Take checkpoints around tentative steps, not on every link of a run with a large
+//! context.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//!
+//! let ctx = Context::new().insert("total", 100);
+//! let checkpoint = ctx.checkpoint();
+//! let ctx = ctx.insert("total", 90).insert("discount", "SPRING");
+//! // The discount turned out to be invalid: undo it, keep going
+//! let ctx = ctx.rollback_to(&checkpoint);
+//! assert_eq!(ctx.get::<i64>("total"), Some(100));
+//! assert_eq!(ctx.get::<String>("discount"), None);
+//! ```
+
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The entries of a context at the moment it was taken.
+#[derive(Debug, Clone, Default)]
+pub struct ContextCheckpoint {
+    entries: Arc<HashMap<String, Value>>,
+}
+
+impl ContextCheckpoint {
+    pub(crate) fn new(entries: &HashMap<String, Value>) -> Self {
+        ContextCheckpoint { entries: Arc::new(entries.clone()) }
+    }
+    pub(crate) fn entries(&self) -> HashMap<String, Value> {
+        self.entries.as_ref().clone()
+    }
+    /// Keys whose value differs between the checkpoint and `entries`, sorted.
+    pub fn changed_keys(&self, entries: &HashMap<String, Value>) -> Vec<String> {
+        let mut changed: Vec<String> = entries
+            .iter()
+            .filter(|(key, value)| self.entries.get(*key) != Some(*value))
+            .map(|(key, _)| key.to_string())
+            .chain(self.entries.keys().filter(|key| !entries.contains_key(*key)).map(|key| key.to_string()))
+            .collect();
+        changed.sort();
+        changed
+    }
+}
@@ -0,0 +1,199 @@
+//! Encodings for values JSON has no type for.
+//!
+//! Timestamps, durations, and binary blobs are stored in the context the same way
+//! everywhere, so links written by different teams read each other's values:
+//!
+//! | Rust type    | JSON                                             |
+//! |--------------|--------------------------------------------------|
+//! | `SystemTime` | RFC3339 string in UTC, `2024-05-01T12:30:00.25Z` |
+//! | `Duration`   | integer milliseconds                             |
+//! | bytes        | standard base64 string (with padding)            |
+//!
+//! `Context` and `ContextMutable` have `insert_*`/`get_*` accessors for each; typed
+//! contexts use the same encodings with `#[serde(with = "...")]` and the modules
+//! [`as_rfc3339`], [`as_millis`], and [`as_base64`]. Reading accepts any RFC3339 offset
+//! (`+02:00`) and converts to UTC.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! let ctx = Context::new()
+//!     .insert_time("created_at", UNIX_EPOCH + Duration::from_secs(1_714_566_600))
+//!     .insert_duration("timeout", Duration::from_secs(2))
+//!     .insert_bytes("avatar", [0xff, 0xd8]);
+//! assert_eq!(ctx.get::<String>("created_at").as_deref(), Some("2024-05-01T12:30:00Z"));
+//! assert_eq!(ctx.get::<u64>("timeout"), Some(2000));
+//! assert_eq!(ctx.get_bytes("avatar"), Some(vec![0xff, 0xd8]));
+//! ```
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// `at` as an RFC3339 UTC timestamp, with as many fractional digits as it needs.
+pub fn format_rfc3339(at: SystemTime) -> String {
+    let (secs, nanos) = match at.duration_since(UNIX_EPOCH) {
+        Ok(d) => (d.as_secs() as i64, d.subsec_nanos()),
+        Err(e) => {
+            let d = e.duration();
+            match d.subsec_nanos() {
+                0 => (-(d.as_secs() as i64), 0),
+                n => (-(d.as_secs() as i64) - 1, 1_000_000_000 - n),
+            }
+        }
+    };
+    let (days, secs_of_day) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+    let (year, month, day) = civil_from_days(days);
+    let mut out = format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60
+    );
+    if nanos > 0 {
+        out.push_str(format!(".{:09}", nanos).trim_end_matches('0'));
+    }
+    out.push('Z');
+    out
+}
+
+/// Parse an RFC3339 timestamp (`T` or space separated, `Z` or numeric offset).
+pub fn parse_rfc3339(s: &str) -> Option<SystemTime> {
+    let b = s.as_bytes();
+    if b.len() < 20 || b[4] != b'-' || b[7] != b'-' || !matches!(b[10], b'T' | b't' | b' ') || b[13] != b':' || b[16] != b':' {
+        return None;
+    }
+    let num = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = s.get(range)?;
+        digits.bytes().all(|c| c.is_ascii_digit()).then(|| digits.parse().ok())?
+    };
+    let (year, month, day) = (num(0..4)?, num(5..7)?, num(8..10)?);
+    let (hour, minute, second) = (num(11..13)?, num(14..16)?, num(17..19)?);
+    if !(1..=12).contains(&month) || day < 1 || day > days_in_month(year, month) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let mut rest = &s[19..];
+    let mut nanos = 0u32;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let digits = fraction.bytes().take_while(u8::is_ascii_digit).count();
+        if digits == 0 {
+            return None;
+        }
+        let padded = format!("{:0<9}", &fraction[..digits.min(9)]);
+        nanos = padded.parse().ok()?;
+        rest = &fraction[digits..];
+    }
+    let offset = match rest {
+        "Z" | "z" => 0,
+        _ => {
+            let b = rest.as_bytes();
+            if b.len() != 6 || b[3] != b':' {
+                return None;
+            }
+            let sign = match b[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            let (h, m): (i64, i64) = (rest[1..3].parse().ok()?, rest[4..6].parse().ok()?);
+            sign * (h * 3600 + m * 60)
+        }
+    };
+
+    // A leap second is read as the last second of the minute
+    let secs = days_from_civil(year, month, day) * 86_400 + hour * 3600 + minute * 60 + second.min(59) - offset;
+    let since_epoch = Duration::new(secs.unsigned_abs(), 0);
+    let whole = if secs >= 0 { UNIX_EPOCH.checked_add(since_epoch)? } else { UNIX_EPOCH.checked_sub(since_epoch)? };
+    whole.checked_add(Duration::from_nanos(nanos.into()))
+}
+
+/// `bytes` as a standard base64 string.
+pub fn encode_base64(bytes: impl AsRef<[u8]>) -> String {
+    STANDARD.encode(bytes)
+}
+
+pub fn decode_base64(s: &str) -> Option<Vec<u8>> {
+    STANDARD.decode(s).ok()
+}
+
+// Days since 1970-01-01 of a proleptic Gregorian date (Howard Hinnant's algorithm).
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    (if month <= 2 { yoe + era * 400 + 1 } else { yoe + era * 400 }, month, day)
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// `#[serde(with = "modulink_rs::context::values::as_rfc3339")]` for `SystemTime` fields.
+pub mod as_rfc3339 {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::SystemTime;
+
+    pub fn serialize<S: Serializer>(at: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::format_rfc3339(*at))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        super::parse_rfc3339(&s).ok_or_else(|| D::Error::custom(format!("invalid RFC3339 timestamp '{}'", s)))
+    }
+}
+
+/// `#[serde(with = "modulink_rs::context::values::as_millis")]` for `Duration` fields.
+pub mod as_millis {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_millis)
+    }
+}
+
+/// `#[serde(with = "modulink_rs::context::values::as_base64")]` for `Vec<u8>` fields.
+pub mod as_base64 {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer, B: AsRef<[u8]>>(bytes: &B, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::encode_base64(bytes))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        super::decode_base64(&s).ok_or_else(|| D::Error::custom("invalid base64"))
+    }
+}
@@ -0,0 +1,140 @@
+//! Context tools for use inside links.
+//! Helpers that act on the run currently executing (its scope, children, etc).
+//!
+//! Example (fan out a sub-chain from inside a link):
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::ctx_tools;
+//! use std::sync::Arc;
+//!
+//! let audit = Arc::new(Chain::new());
+//! let link: modulink_rs::links::Link = Arc::new(move |ctx: Context| {
+//!     let audit = audit.clone();
+//!     Box::pin(async move {
+//!         let _child = ctx_tools::spawn_child(audit, ctx.clone());
+//!         ctx
+//!     })
+//! });
+//! ```
+
+use crate::chains::report::{RunReport, RunStatus};
+use crate::chains::{ChainGeneric, RunError, RunScope};
+use crate::runtime::{panic_message, JoinError, JoinHandle};
+use futures::channel::oneshot;
+use futures::future::{AbortHandle, Abortable, FutureExt};
+use std::any::Any;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+
+/// Spawn a run of `chain` on its executor, tied to the cancellation scope of the current run.
+///
+/// The parent run waits for the child before completing, reports its status in
+/// `RunReport::children`, and aborts it if the parent itself is aborted. Outside of a run
+/// the child is spawned detached. Awaiting the returned handle yields the child's final context.
+///
+/// Past the run's `ResourceLimits::max_children` the child is not started, the parent fails
+/// with `LimitExceeded`, and the handle resolves to `JoinError::Aborted`.
+pub fn spawn_child<T: Send + 'static>(chain: Arc<ChainGeneric<T>>, ctx: T) -> JoinHandle<T> {
+    let (tx, rx) = oneshot::channel();
+    let (report_tx, report_rx) = oneshot::channel();
+    let (abort, reg) = AbortHandle::new_pair();
+    if let Some(scope) = RunScope::current() {
+        if !scope.register(abort.clone(), report_rx) {
+            return JoinHandle::from_parts(rx, abort);
+        }
+    }
+    let exec = chain.executor().clone();
+    let run = Abortable::new(AssertUnwindSafe(async move { chain.run_with_report(ctx).await }).catch_unwind(), reg);
+    exec.spawn(Box::pin(async move {
+        let (report, res) = match run.await {
+            Ok(Ok((ctx, report))) => (report, Ok(ctx)),
+            Ok(Err(payload)) => {
+                let msg = panic_message(payload);
+                (RunReport::new(RunStatus::Failed(RunError::panicked(msg.clone()))), Err(JoinError::Panicked(msg)))
+            }
+            Err(_) => (RunReport::new(RunStatus::Cancelled), Err(JoinError::Aborted)),
+        };
+        let _ = report_tx.send(report);
+        let _ = tx.send(res);
+    }));
+    JoinHandle::from_parts(rx, abort)
+}
+
+/// Fail the current run with `err`. The chain stops before running another link and
+/// reports `RunStatus::Failed(err)`. Returns `false` when called outside of a run.
+pub fn fail_run(err: RunError) -> bool {
+    match RunScope::current() {
+        Some(scope) => {
+            scope.fail(err);
+            true
+        }
+        None => false,
+    }
+}
+
+/// The failure recorded against the current run so far, if any.
+pub fn run_failure() -> Option<RunError> {
+    RunScope::current().and_then(|scope| scope.failure())
+}
+
+/// The latest error a fallible link returned in the current run that was skipped or routed
+/// to a handler (see `chains::fallible`), so the handler can tell what went wrong.
+pub fn last_error() -> Option<RunError> {
+    RunScope::current().and_then(|scope| scope.last_error())
+}
+
+/// Whether the current run is a shadow run (`ChainGeneric::run_shadow`, used by canaries
+/// and dry runs). Links tagged with `LinkSpec::side_effects` are skipped or mocked there;
+/// untagged links with incidental effects can check this to skip them. `false` outside of a run.
+pub fn is_shadow() -> bool {
+    RunScope::current().is_some_and(|scope| scope.is_shadow())
+}
+
+/// Whether the current run has been cancelled (see `chains::cancel`), so a long-running
+/// link can stop early. `false` outside of a run.
+pub fn is_cancelled() -> bool {
+    RunScope::current().is_some_and(|scope| scope.is_cancelled())
+}
+
+/// The value of type `S` in the shared state of the chain running (see `chains::state`).
+/// `None` outside of a run, or if the chain has no such value.
+pub fn state<S: Any + Send + Sync>() -> Option<Arc<S>> {
+    RunScope::current()?.state()?.get()
+}
+
+/// Count a retry against the current run (reported in `RunReport::retries`).
+/// Returns `false` when called outside of a run.
+pub fn record_retry() -> bool {
+    match RunScope::current() {
+        Some(scope) => {
+            scope.record_retry();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Record a non-fatal problem against the current run (reported in `RunReport::warnings`).
+/// Returns `false` when called outside of a run.
+pub fn warn(warning: impl Into<String>) -> bool {
+    match RunScope::current() {
+        Some(scope) => {
+            scope.warn(warning);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Tag the current run with `key` = `value` (reported in `RunReport::annotations`, audit
+/// records, and metrics labels, and emitted as a trace event). Annotations are run metadata,
+/// not business data, so they never enter the context. Returns `false` outside of a run.
+pub fn annotate(key: impl Into<String>, value: impl serde::Serialize) -> bool {
+    let Some(scope) = RunScope::current() else { return false };
+    let key = key.into();
+    let value = serde_json::to_value(value).unwrap_or(serde_json::Value::Null);
+    tracing::debug!(target: "modulink::run", key = %key, value = %value, "run annotated");
+    scope.annotate(key, value);
+    true
+}
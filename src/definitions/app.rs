@@ -0,0 +1,248 @@
+//! App definitions: chains and the listeners that trigger them, in one file.
+//!
+//! An app definition lists [`ChainDefinition`]s and [`ListenerDefinition`]s; each listener
+//! names the chain it runs, either one defined in the same file or one registered with
+//! `registry::register_chain`. `boot::from_file` builds and wires everything.
+//!
+//! ```yaml
+//! chains:
+//!   - name: orders
+//!     links:
+//!       - link: lookup_order
+//!       - link: charge
+//! listeners:
+//!   - type: http
+//!     chain: orders
+//!     address: 0.0.0.0:8080
+//!     auth:
+//!       jwt: { hs256_secret: change-me, audience: [orders-api] }
+//!     limits: { max_body_bytes: 65536, rate_per_second: 50, burst: 100 }
+//!   - type: stdin
+//!     chain: orders
+//! ```
+//!
+//! JSON is always accepted; YAML needs the `yaml` feature and TOML the `toml` feature.
+//! Files may reference environment variables and secrets (see `definitions::interpolate`).
+//!
+//! Large apps can be split across files. `include` lists files whose chains and listeners
+//! join the including file's, and a link `use: common/validation.yaml#validate_input` runs
+//! chain `validate_input` of another file as one step; `use: validate_input` names a chain
+//! of the same app. Paths are relative to the file they appear in. [`AppDefinition::from_file`]
+//! resolves both, replacing each resolved `use` with the chain definition it names, so the
+//! result is self-contained; a `use` naming no chain of the app is left to the registry.
+//!
+//! ```yaml
+//! include: [payments.yaml]
+//! chains:
+//!   - name: orders
+//!     links:
+//!       - use: common/validation.yaml#validate_input
+//!       - link: lookup_order
+//!       - use: charge  # defined in payments.yaml
+//! ```
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::definitions::{AppDefinition, ListenerDefinition};
+//!
+//! let app = AppDefinition::from_json(r#"{
+//!     "chains": [ { "name": "orders", "links": [] } ],
+//!     "listeners": [ { "type": "http", "chain": "orders", "address": "127.0.0.1:8080" } ]
+//! }"#).unwrap();
+//! assert_eq!(app.listeners[0].chain(), "orders");
+//! assert!(matches!(app.listeners[0], ListenerDefinition::Http { .. }));
+//! ```
+
+use super::{ChainDefinition, DefinitionError, Interpolation, LinkDefinition};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Chains and listeners described as data.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppDefinition {
+    /// Files whose chains and listeners are added to this definition's; emptied once read.
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub chains: Vec<ChainDefinition>,
+    #[serde(default)]
+    pub listeners: Vec<ListenerDefinition>,
+}
+
+/// A listener and the chain it runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ListenerDefinition {
+    /// An `HttpListener` serving `POST /run` on `address`.
+    Http {
+        chain: String,
+        address: String,
+        #[serde(default)]
+        auth: AuthDefinition,
+        #[serde(default)]
+        limits: ListenerLimits,
+    },
+    /// A `StdinListener`, one run per JSON line.
+    Stdin { chain: String },
+}
+
+impl ListenerDefinition {
+    /// Name of the chain the listener runs.
+    pub fn chain(&self) -> &str {
+        match self {
+            ListenerDefinition::Http { chain, .. } | ListenerDefinition::Stdin { chain } => chain,
+        }
+    }
+}
+
+/// How an HTTP listener authenticates requests.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AuthDefinition {
+    /// Validate bearer JWTs before each run (feature `jwt`).
+    #[serde(default)]
+    pub jwt: Option<JwtDefinition>,
+    /// Copy the raw `Authorization` header into `_authorization` for auth middleware.
+    #[serde(default)]
+    pub forward_authorization: bool,
+}
+
+/// JWT validation settings; exactly one of `hs256_secret` and `rs256_pem` is required.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct JwtDefinition {
+    #[serde(default)]
+    pub hs256_secret: Option<String>,
+    /// PEM-encoded RSA public key.
+    #[serde(default)]
+    pub rs256_pem: Option<String>,
+    #[serde(default)]
+    pub audience: Vec<String>,
+    #[serde(default)]
+    pub issuer: Vec<String>,
+    /// Clock skew tolerated on `exp`/`nbf`, in seconds.
+    #[serde(default)]
+    pub leeway: Option<u64>,
+}
+
+/// Request limits of an HTTP listener.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ListenerLimits {
+    /// Largest accepted request body; bigger bodies get 413 without a run.
+    #[serde(default)]
+    pub max_body_bytes: Option<usize>,
+    /// Runs started per second; requests over the limit get 429 without a run.
+    #[serde(default)]
+    pub rate_per_second: Option<f64>,
+    /// Requests accepted at once above the steady rate (one second's worth by default).
+    #[serde(default)]
+    pub burst: Option<u32>,
+}
+
+impl AppDefinition {
+    pub fn from_json(json: &str) -> Result<Self, DefinitionError> {
+        serde_json::from_str(json).map_err(|e| DefinitionError::Parse(e.to_string()))
+    }
+
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(yaml: &str) -> Result<Self, DefinitionError> {
+        serde_yaml::from_str(yaml).map_err(|e| DefinitionError::Parse(e.to_string()))
+    }
+
+    #[cfg(feature = "toml")]
+    pub fn from_toml(toml: &str) -> Result<Self, DefinitionError> {
+        toml::from_str(toml).map_err(|e| DefinitionError::Parse(e.to_string()))
+    }
+
+    /// Read a definition, choosing the format by extension: `.json`, `.yaml`/`.yml`, or `.toml`.
+    /// Included files and `use` references are read too (see the [module docs](self)), and
+    /// `${NAME}` and `secret://` references are resolved from the process environment and
+    /// `/run/secrets` (see [`Interpolation`]).
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, DefinitionError> {
+        Self::from_file_with(path, &Interpolation::new())
+    }
+
+    /// [`Self::from_file`], resolving references with `interpolation`.
+    pub fn from_file_with(path: impl AsRef<Path>, interpolation: &Interpolation) -> Result<Self, DefinitionError> {
+        Self::read(path.as_ref(), interpolation, &mut Vec::new())
+    }
+
+    // Read `path` with its includes and `use` references; `open` holds the files being read.
+    fn read(path: &Path, interpolation: &Interpolation, open: &mut Vec<PathBuf>) -> Result<Self, DefinitionError> {
+        let canonical = path.canonicalize().map_err(|e| DefinitionError::Parse(format!("{}: {}", path.display(), e)))?;
+        if open.contains(&canonical) {
+            return Err(DefinitionError::Include(format!("{} includes itself", path.display())));
+        }
+        open.push(canonical);
+        let mut def = Self::parse_file(path, interpolation)?;
+        let dir = path.parent().unwrap_or(Path::new(""));
+        for include in std::mem::take(&mut def.include) {
+            let included = Self::read(&dir.join(include), interpolation, open)?;
+            def.chains.extend(included.chains);
+            def.listeners.extend(included.listeners);
+        }
+        for (i, chain) in def.chains.iter().enumerate() {
+            if def.chains[..i].iter().any(|other| other.name == chain.name) {
+                return Err(DefinitionError::Include(format!("chain '{}' is defined twice", chain.name)));
+            }
+        }
+        let mut chains = def.chains.clone();
+        for chain in &mut chains {
+            def.resolve_uses(chain, dir, interpolation, open, &mut vec![chain.name.clone()])?;
+        }
+        def.chains = chains;
+        open.pop();
+        Ok(def)
+    }
+
+    // Replace the `use` links of `chain` with the chains they name; `using` holds the
+    // chains being resolved, to catch chains using themselves.
+    fn resolve_uses(
+        &self,
+        chain: &mut ChainDefinition,
+        dir: &Path,
+        interpolation: &Interpolation,
+        open: &mut Vec<PathBuf>,
+        using: &mut Vec<String>,
+    ) -> Result<(), DefinitionError> {
+        for link in &mut chain.links {
+            let LinkDefinition::Use { target } = link else { continue };
+            let used = match target.split_once('#') {
+                Some((file, name)) if !file.is_empty() => Self::read(&dir.join(file), interpolation, open)?
+                    .chains
+                    .into_iter()
+                    .find(|chain| chain.name == name)
+                    .ok_or_else(|| DefinitionError::UnknownChain(target.clone()))?,
+                split => {
+                    let name = split.map_or(target.as_str(), |(_, name)| name);
+                    let Some(found) = self.chains.iter().find(|chain| chain.name == name) else { continue };
+                    if using.iter().any(|chain| chain == name) {
+                        return Err(DefinitionError::Include(format!("chain '{}' uses itself", name)));
+                    }
+                    let mut found = found.clone();
+                    using.push(found.name.clone());
+                    self.resolve_uses(&mut found, dir, interpolation, open, using)?;
+                    using.pop();
+                    found
+                }
+            };
+            *link = LinkDefinition::Chain { chain: Box::new(used) };
+        }
+        Ok(())
+    }
+
+    fn parse_file(path: &Path, interpolation: &Interpolation) -> Result<Self, DefinitionError> {
+        let text = std::fs::read_to_string(path).map_err(|e| DefinitionError::Parse(format!("{}: {}", path.display(), e)))?;
+        match path.extension().and_then(|ext| ext.to_str()).unwrap_or_default() {
+            "json" => interpolation.load(&text, |text| serde_json::from_str::<Value>(text)),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => interpolation.load(&text, |text| serde_yaml::from_str::<Value>(text)),
+            #[cfg(not(feature = "yaml"))]
+            "yaml" | "yml" => Err(DefinitionError::Parse("reading YAML definitions needs the `yaml` feature".to_string())),
+            #[cfg(feature = "toml")]
+            "toml" => interpolation.load(&text, toml::from_str::<Value>),
+            #[cfg(not(feature = "toml"))]
+            "toml" => Err(DefinitionError::Parse("reading TOML definitions needs the `toml` feature".to_string())),
+            _ => Err(DefinitionError::Parse(format!("{}: unknown definition format", path.display()))),
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Branch condition expressions for definitions.
+//!
+//! Closures can't be written in a config file, so a branch's `when` may be an expression
+//! over the context, parsed once when the definition is built:
+//!
+//! ```text
+//! ctx.error == true && ctx.retries < 3
+//! !ctx.order.paid || ctx.items[0].sku == "gift-card"
+//! ```
+//!
+//! Paths start at `ctx` and descend with `.field` or `[index]`; a missing path is `null`.
+//! A JSONPath query (`$.items[?(@.price > 10)]`, see [`crate::context::query`]) is the
+//! matching value, an array when several match, or `null` when none do.
+//! Literals are numbers, strings (single or double quoted), `true`, `false`, and `null`.
+//! Operators, loosest first: `||`, `&&`, `!`, then `==`, `!=`, `<`, `<=`, `>`, `>=`.
+//! Ordering compares numbers with numbers and strings with strings; anything else is false.
+//! A bare operand is true unless it is `null` or `false`, as for key conditions.
+//! Expressions nesting deeper than [`MAX_DEPTH`] levels are rejected.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::definitions::Expression;
+//!
+//! let expr = Expression::parse("ctx.error == true && ctx.retries < 3").unwrap();
+//! assert!(expr.eval(&Context::new().insert("error", true).insert("retries", 1)));
+//! assert!(!expr.eval(&Context::new().insert("error", true).insert("retries", 3)));
+//! ```
+
+use crate::context::query::JsonPath;
+use crate::context::Context;
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Field(String),
+    Index(usize),
+}
+
+/// Where a path starts: the context, or (in JSONPath filters) the candidate element `@`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Base {
+    Ctx,
+    Current,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Literal(Value),
+    Path(Base, Vec<Segment>),
+    Query(JsonPath),
+    Not(Box<Node>),
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Cmp(CmpOp, Box<Node>, Box<Node>),
+}
+
+/// A parsed condition expression.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Str(String),
+    Query(String),
+    At,
+    Dot,
+    LBracket,
+    RBracket,
+    LParen,
+    RParen,
+    Not,
+    And,
+    Or,
+    Cmp(CmpOp),
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = src.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '.' => (Token::Dot, 1),
+            '@' => (Token::At, 1),
+            '$' => {
+                let len = query_len(&chars[i..]);
+                (Token::Query(chars[i..i + len].iter().collect()), len)
+            }
+            '[' => (Token::LBracket, 1),
+            ']' => (Token::RBracket, 1),
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            '&' if next == Some('&') => (Token::And, 2),
+            '|' if next == Some('|') => (Token::Or, 2),
+            '=' if next == Some('=') => (Token::Cmp(CmpOp::Eq), 2),
+            '!' if next == Some('=') => (Token::Cmp(CmpOp::Ne), 2),
+            '!' => (Token::Not, 1),
+            '<' if next == Some('=') => (Token::Cmp(CmpOp::Le), 2),
+            '<' => (Token::Cmp(CmpOp::Lt), 1),
+            '>' if next == Some('=') => (Token::Cmp(CmpOp::Ge), 2),
+            '>' => (Token::Cmp(CmpOp::Gt), 1),
+            '"' | '\'' => {
+                let end = chars[i + 1..].iter().position(|&ch| ch == c).ok_or_else(|| format!("unterminated string at {}", i))?;
+                (Token::Str(chars[i + 1..i + 1 + end].iter().collect()), end + 2)
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let len = 1 + chars[i + 1..].iter().take_while(|ch| ch.is_ascii_digit() || **ch == '.').count();
+                let text: String = chars[i..i + len].iter().collect();
+                (Token::Number(text.parse().map_err(|_| format!("invalid number '{}'", text))?), len)
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let len = chars[i..].iter().take_while(|ch| ch.is_alphanumeric() || **ch == '_' || **ch == '-').count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            other => return Err(format!("unexpected '{}' at {}", other, i)),
+        };
+        tokens.push(token);
+        i += len;
+    }
+    Ok(tokens)
+}
+
+// Length of the JSONPath query at the start of `chars`: up to whitespace or an operator
+// outside of brackets and quotes.
+fn query_len(chars: &[char]) -> usize {
+    let mut depth = 0i32;
+    let mut quote = None;
+    for (i, &c) in chars.iter().enumerate() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '[' | '(') => depth += 1,
+            (None, ']') => depth -= 1,
+            (None, ')') if depth > 0 => depth -= 1,
+            (None, c) if depth == 0 && (c.is_whitespace() || ")&|=!<>".contains(c)) => return i,
+            _ => {}
+        }
+    }
+    chars.len()
+}
+
+/// Deepest nesting (parentheses, `!`, chained operators, and JSONPath filters) an
+/// expression may have.
+pub const MAX_DEPTH: usize = 128;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    // `@` is only meaningful inside JSONPath filters
+    in_filter: bool,
+    // depth of the tree being built, counting enclosing filters
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            other => Err(format!("expected {:?}, found {:?}", expected, other)),
+        }
+    }
+    // Descend one level; parsing recurses, so untrusted input must not nest without bound.
+    fn nest(&mut self) -> Result<(), String> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(format!("expression nests deeper than {} levels", MAX_DEPTH));
+        }
+        Ok(())
+    }
+
+    // Chained operators build a left-deep tree, so each one counts as a level.
+    fn or(&mut self) -> Result<Node, String> {
+        let depth = self.depth;
+        let mut node = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            self.nest()?;
+            node = Node::Or(Box::new(node), Box::new(self.and()?));
+        }
+        self.depth = depth;
+        Ok(node)
+    }
+    fn and(&mut self) -> Result<Node, String> {
+        let depth = self.depth;
+        let mut node = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            self.nest()?;
+            node = Node::And(Box::new(node), Box::new(self.not()?));
+        }
+        self.depth = depth;
+        Ok(node)
+    }
+    fn not(&mut self) -> Result<Node, String> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            self.nest()?;
+            let node = Node::Not(Box::new(self.not()?));
+            self.depth -= 1;
+            return Ok(node);
+        }
+        self.cmp()
+    }
+    fn cmp(&mut self) -> Result<Node, String> {
+        let left = self.operand()?;
+        if let Some(Token::Cmp(op)) = self.peek().cloned() {
+            self.pos += 1;
+            return Ok(Node::Cmp(op, Box::new(left), Box::new(self.operand()?)));
+        }
+        Ok(left)
+    }
+    fn operand(&mut self) -> Result<Node, String> {
+        match self.next() {
+            Some(Token::LParen) => {
+                self.nest()?;
+                let node = self.or()?;
+                self.expect(Token::RParen)?;
+                self.depth -= 1;
+                Ok(node)
+            }
+            Some(Token::Number(n)) => Ok(Node::Literal(serde_json::Number::from_f64(n).map(Value::Number).unwrap_or(Value::Null))),
+            Some(Token::Str(s)) => Ok(Node::Literal(Value::String(s))),
+            Some(Token::Query(query)) => {
+                self.nest()?;
+                let query = JsonPath::parse_nested(&query, self.depth)?;
+                self.depth -= 1;
+                Ok(Node::Query(query))
+            }
+            Some(Token::At) if self.in_filter => self.path(Base::Current),
+            Some(Token::At) => Err("'@' is only valid inside a JSONPath filter".to_string()),
+            Some(Token::Ident(word)) => match word.as_str() {
+                "true" => Ok(Node::Literal(Value::Bool(true))),
+                "false" => Ok(Node::Literal(Value::Bool(false))),
+                "null" => Ok(Node::Literal(Value::Null)),
+                "ctx" => self.path(Base::Ctx),
+                other => Err(format!("unknown name '{}' (paths start with 'ctx.')", other)),
+            },
+            other => Err(format!("expected a value, found {:?}", other)),
+        }
+    }
+    fn path(&mut self, base: Base) -> Result<Node, String> {
+        let mut segments = Vec::new();
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Ident(field)) => segments.push(Segment::Field(field)),
+                        other => return Err(format!("expected a field name after '.', found {:?}", other)),
+                    }
+                }
+                Some(Token::LBracket) => {
+                    self.pos += 1;
+                    match self.next() {
+                        Some(Token::Number(n)) if n >= 0.0 && n.fract() == 0.0 => segments.push(Segment::Index(n as usize)),
+                        Some(Token::Str(field)) => segments.push(Segment::Field(field)),
+                        other => return Err(format!("expected an index, found {:?}", other)),
+                    }
+                    self.expect(Token::RBracket)?;
+                }
+                _ => break,
+            }
+        }
+        if segments.is_empty() && base == Base::Ctx {
+            return Err("'ctx' must be followed by a path, e.g. ctx.status".to_string());
+        }
+        Ok(Node::Path(base, segments))
+    }
+}
+
+fn truthy(value: &Value) -> bool {
+    !matches!(value, Value::Null | Value::Bool(false))
+}
+
+// What an expression is evaluated against.
+struct Env<'a> {
+    ctx: &'a HashMap<String, Value>,
+    current: Option<&'a Value>,
+}
+
+fn lookup(env: &Env, base: Base, segments: &[Segment]) -> Value {
+    let (mut current, rest) = match base {
+        Base::Current => (env.current, segments),
+        Base::Ctx => match segments.first() {
+            Some(Segment::Field(key)) => (env.ctx.get(key.as_str()), &segments[1..]),
+            _ => (None, segments),
+        },
+    };
+    for segment in rest {
+        current = current.and_then(|value| match segment {
+            Segment::Field(key) => value.get(key),
+            Segment::Index(i) => value.get(i),
+        });
+    }
+    current.cloned().unwrap_or(Value::Null)
+}
+
+fn compare(op: CmpOp, left: &Value, right: &Value) -> bool {
+    let ordering = match (left, right) {
+        (Value::Number(a), Value::Number(b)) => a.as_f64().zip(b.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)),
+        (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+        _ => None,
+    };
+    match op {
+        CmpOp::Eq => ordering.map_or(left == right, |o| o == Ordering::Equal),
+        CmpOp::Ne => !ordering.map_or(left == right, |o| o == Ordering::Equal),
+        CmpOp::Lt => ordering == Some(Ordering::Less),
+        CmpOp::Le => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        CmpOp::Gt => ordering == Some(Ordering::Greater),
+        CmpOp::Ge => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+    }
+}
+
+fn value(node: &Node, env: &Env) -> Value {
+    match node {
+        Node::Literal(v) => v.clone(),
+        Node::Path(base, segments) => lookup(env, *base, segments),
+        Node::Query(query) => {
+            let mut matches = query.select(env.ctx);
+            match matches.len() {
+                0 => Value::Null,
+                1 => matches.remove(0),
+                _ => Value::Array(matches),
+            }
+        }
+        other => Value::Bool(eval(other, env)),
+    }
+}
+
+fn eval(node: &Node, env: &Env) -> bool {
+    match node {
+        Node::Not(inner) => !eval(inner, env),
+        Node::And(a, b) => eval(a, env) && eval(b, env),
+        Node::Or(a, b) => eval(a, env) || eval(b, env),
+        Node::Cmp(op, a, b) => compare(*op, &value(a, env), &value(b, env)),
+        leaf => truthy(&value(leaf, env)),
+    }
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        Self::parse_with(source, false, 0)
+    }
+    /// Parse a JSONPath filter body, where `@` is the candidate element, `depth` levels
+    /// into the enclosing expression.
+    pub(crate) fn parse_filter(source: &str, depth: usize) -> Result<Self, String> {
+        Self::parse_with(source, true, depth)
+    }
+    fn parse_with(source: &str, in_filter: bool, depth: usize) -> Result<Self, String> {
+        let mut parser = Parser { tokens: tokenize(source)?, pos: 0, in_filter, depth };
+        let root = parser.or()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("unexpected {:?} after expression", token));
+        }
+        Ok(Expression { source: source.to_string(), root })
+    }
+    pub fn eval(&self, ctx: &Context) -> bool {
+        eval(&self.root, &Env { ctx: &ctx.0, current: None })
+    }
+    pub(crate) fn eval_filter(&self, ctx: &HashMap<String, Value>, current: &Value) -> bool {
+        eval(&self.root, &Env { ctx, current: Some(current) })
+    }
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
@@ -0,0 +1,177 @@
+//! Environment and secret references in definition files.
+//!
+//! Definitions read with an [`Interpolation`] (`AppDefinition::from_file`,
+//! `ChainDefinition::from_json_with`) may reference their environment, so one file serves
+//! every deployment:
+//!
+//! - `${NAME}` is replaced with the environment variable `NAME`; reading fails when it is
+//!   not set. `${NAME:-default}` falls back to `default` when it is unset or empty.
+//!   Replacement happens on the text before it is parsed, so placeholders work for numbers
+//!   and booleans too; in JSON, put placeholders for strings inside the quotes. `$$` is a
+//!   literal `$`.
+//! - A string value of the form `secret://name` is replaced, after parsing, with the secret
+//!   `name` from a [`SecretResolver`]; by default the file `name` under `/run/secrets`
+//!   ([`FileSecrets`]), where Docker and Kubernetes mount secrets.
+//!
+//! `ChainDefinition::from_json` does neither: definitions from untrusted users must not
+//! read the host's environment.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::definitions::{ChainDefinition, Interpolation};
+//!
+//! let interpolation = Interpolation::new()
+//!     .with_vars([("STAGE", "prod")])
+//!     .with_secrets(|name: &str| Ok(format!("value of {}", name)));
+//! let def = ChainDefinition::from_json_with(
+//!     r#"{ "name": "orders-${STAGE}-${REGION:-eu}", "input": ["secret://api-key"], "links": [] }"#,
+//!     &interpolation,
+//! ).unwrap();
+//! assert_eq!(def.name, "orders-prod-eu");
+//! assert_eq!(def.input, vec!["value of api-key"]);
+//! ```
+
+use super::DefinitionError;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+/// Prefix of string values resolved as secrets.
+pub const SECRET_PREFIX: &str = "secret://";
+
+/// Where Docker and Kubernetes mount secrets; the default [`FileSecrets`] directory.
+pub const DEFAULT_SECRETS_DIR: &str = "/run/secrets";
+
+/// Looks up the secrets `secret://` references name.
+pub trait SecretResolver: Send + Sync {
+    fn resolve(&self, name: &str) -> Result<String, String>;
+}
+
+impl<F> SecretResolver for F
+where
+    F: Fn(&str) -> Result<String, String> + Send + Sync,
+{
+    fn resolve(&self, name: &str) -> Result<String, String> {
+        self(name)
+    }
+}
+
+/// Secrets stored one per file under a directory, without a trailing newline.
+pub struct FileSecrets {
+    dir: PathBuf,
+}
+
+impl FileSecrets {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileSecrets { dir: dir.into() }
+    }
+}
+
+impl SecretResolver for FileSecrets {
+    fn resolve(&self, name: &str) -> Result<String, String> {
+        // Names may have directories, but must stay under `dir`
+        if !Path::new(name).components().all(|part| matches!(part, Component::Normal(_))) {
+            return Err(format!("invalid secret name '{}'", name));
+        }
+        let path = self.dir.join(name);
+        let secret = std::fs::read_to_string(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+    }
+}
+
+type Vars = Arc<dyn Fn(&str) -> Option<String> + Send + Sync>;
+
+/// Where `${NAME}` and `secret://` references are looked up; see the [module docs](self).
+#[derive(Clone)]
+pub struct Interpolation {
+    vars: Vars,
+    secrets: Arc<dyn SecretResolver>,
+}
+
+impl Default for Interpolation {
+    fn default() -> Self {
+        Interpolation { vars: Arc::new(|name| std::env::var(name).ok()), secrets: Arc::new(FileSecrets::new(DEFAULT_SECRETS_DIR)) }
+    }
+}
+
+impl Interpolation {
+    /// The process environment, and secrets under [`DEFAULT_SECRETS_DIR`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Look variables up in `vars` instead of the process environment.
+    pub fn with_vars<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        let vars: HashMap<String, String> = vars.into_iter().map(|(k, v)| (k.into(), v.into())).collect();
+        self.vars = Arc::new(move |name| vars.get(name).cloned());
+        self
+    }
+    pub fn with_secrets(mut self, secrets: impl SecretResolver + 'static) -> Self {
+        self.secrets = Arc::new(secrets);
+        self
+    }
+
+    /// Replace the `${...}` placeholders in `text`.
+    pub fn interpolate(&self, text: &str) -> Result<String, DefinitionError> {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(at) = rest.find('$') {
+            out.push_str(&rest[..at]);
+            rest = &rest[at..];
+            if let Some(after) = rest.strip_prefix("$$") {
+                out.push('$');
+                rest = after;
+            } else if let Some(body) = rest.strip_prefix("${") {
+                let end = body.find('}').ok_or_else(|| DefinitionError::Unresolved("placeholder '${' is never closed".to_string()))?;
+                let (name, default) = match body[..end].split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (&body[..end], None),
+                };
+                let value = match ((self.vars)(name).filter(|value| !value.is_empty() || default.is_none()), default) {
+                    (Some(value), _) => value,
+                    (None, Some(default)) => default.to_string(),
+                    (None, None) => return Err(DefinitionError::Unresolved(format!("environment variable {} is not set", name))),
+                };
+                out.push_str(&value);
+                rest = &body[end + 1..];
+            } else {
+                out.push('$');
+                rest = &rest[1..];
+            }
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Replace every `secret://` string in `value`.
+    pub fn resolve_secrets(&self, value: &mut Value) -> Result<(), DefinitionError> {
+        match value {
+            Value::String(text) => {
+                if let Some(name) = text.strip_prefix(SECRET_PREFIX) {
+                    *text = self.secrets.resolve(name).map_err(|e| DefinitionError::Unresolved(format!("secret '{}': {}", name, e)))?;
+                }
+            }
+            Value::Array(items) => items.iter_mut().try_for_each(|item| self.resolve_secrets(item))?,
+            Value::Object(fields) => fields.values_mut().try_for_each(|field| self.resolve_secrets(field))?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    // Interpolate `text`, parse it with `parse`, resolve secrets, and deserialize.
+    pub(crate) fn load<T, E>(&self, text: &str, parse: impl FnOnce(&str) -> Result<Value, E>) -> Result<T, DefinitionError>
+    where
+        T: DeserializeOwned,
+        E: std::fmt::Display,
+    {
+        let mut value = parse(&self.interpolate(text)?).map_err(|e| DefinitionError::Parse(e.to_string()))?;
+        self.resolve_secrets(&mut value)?;
+        serde_json::from_value(value).map_err(|e| DefinitionError::Parse(e.to_string()))
+    }
+}
@@ -0,0 +1,291 @@
+//! Chain definitions: chains described as data (JSON) instead of code.
+//!
+//! A definition lists links by the name they were registered under
+//! (`registry::register_link`), as WASM modules for custom code, as validation rules
+//! (`std_links::validate`), or as other chains run as one link (`use`, see
+//! `definitions::app` for references across files), plus branches between link positions. Native code is never part of a definition, so a definition can only do
+//! what the host's registered links and WASM host allow; see [`SandboxProfile`] for
+//! running definitions from untrusted users. An [`AppDefinition`] adds the listeners that
+//! trigger the chains (see `boot::from_file`).
+//!
+//! ```json
+//! {
+//!   "name": "refunds",
+//!   "links": [
+//!     { "link": "lookup_order" },
+//!     { "validate": { "rules": [ { "rule": "required", "field": "order.total" } ], "fail": true } },
+//!     { "wasm": { "module": "policies/score.wasm", "function": "score" } },
+//!     { "link": "issue_refund" }
+//!   ],
+//!   "branches": [
+//!     { "from": 2, "to": 0, "when": { "key": "retry", "equals": true } },
+//!     { "from": 2, "to": 3, "when": "ctx.score >= 0.8 && ctx.order.total < 500" }
+//!   ]
+//! }
+//! ```
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::definitions::ChainDefinition;
+//! use modulink_rs::registry;
+//! use std::sync::Arc;
+//!
+//! registry::register_link("greet", Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("greeting", "hi") })));
+//! let def = ChainDefinition::from_json(r#"{ "name": "hello", "links": [ { "link": "greet" } ] }"#).unwrap();
+//! let chain = def.build(None).unwrap();
+//! assert_eq!(chain.link_count(), 1);
+//! ```
+
+pub mod app;
+pub mod expr;
+pub mod interpolate;
+pub mod sandbox;
+pub use app::{AppDefinition, AuthDefinition, JwtDefinition, ListenerDefinition, ListenerLimits};
+pub use expr::Expression;
+pub use interpolate::{FileSecrets, Interpolation, SecretResolver};
+pub use sandbox::SandboxProfile;
+
+use crate::chains::{Chain, ResourceLimits};
+use crate::context::Context;
+use crate::links::{Link, LinkSpec};
+use crate::registry;
+use crate::std_links::{self, Rules};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Errors raised while parsing, checking, or building a definition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefinitionError {
+    /// The definition is not valid JSON for this format.
+    Parse(String),
+    /// A link references a name nobody registered.
+    UnknownLink(String),
+    /// A listener or `use` link references a chain neither defined nor registered.
+    UnknownChain(String),
+    /// An included or referenced file could not be used: a cycle, or a chain defined twice.
+    Include(String),
+    /// A listener's settings are invalid, or need a feature that is not enabled.
+    Listener(String),
+    /// An environment variable or secret the definition references could not be read.
+    Unresolved(String),
+    /// A branch points outside the link list.
+    InvalidBranch { from: usize, to: usize },
+    /// The definition uses something its sandbox profile forbids.
+    NotAllowed(String),
+    /// The definition needs a WASM host and none was supplied, or the host rejected a module.
+    Wasm(String),
+}
+
+impl std::fmt::Display for DefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefinitionError::Parse(msg) => write!(f, "invalid definition: {}", msg),
+            DefinitionError::UnknownLink(name) => write!(f, "no link registered as '{}'", name),
+            DefinitionError::UnknownChain(name) => write!(f, "no chain defined or registered as '{}'", name),
+            DefinitionError::Include(msg) => write!(f, "include: {}", msg),
+            DefinitionError::Listener(msg) => write!(f, "invalid listener: {}", msg),
+            DefinitionError::Unresolved(msg) => write!(f, "unresolved reference: {}", msg),
+            DefinitionError::InvalidBranch { from, to } => write!(f, "branch {} -> {} is out of range", from, to),
+            DefinitionError::NotAllowed(msg) => write!(f, "not allowed by sandbox: {}", msg),
+            DefinitionError::Wasm(msg) => write!(f, "wasm: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionError {}
+
+/// A chain described as data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ChainDefinition {
+    pub name: String,
+    /// Keys every input context carries, for `ChainGeneric::validate`.
+    #[serde(default)]
+    pub input: Vec<String>,
+    pub links: Vec<LinkDefinition>,
+    #[serde(default)]
+    pub branches: Vec<BranchDefinition>,
+}
+
+/// One step of a definition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LinkDefinition {
+    /// A link registered with `registry::register_link`.
+    Registry { link: String },
+    /// Custom code compiled to WASM, instantiated through a [`WasmHost`].
+    Wasm { wasm: WasmModule },
+    /// A `std_links::validate` link checking these rules.
+    Validate { validate: Rules },
+    /// Another chain run as one link (see `ChainGeneric::as_link`): `file#name` for chain
+    /// `name` of an app definition file, relative to the referencing file, or a chain name
+    /// alone for a chain of the same app or, failing that, of the registry. `from_file`
+    /// replaces references it can resolve with [`LinkDefinition::Chain`].
+    Use {
+        #[serde(rename = "use")]
+        target: String,
+    },
+    /// A chain defined inline, run as one link.
+    Chain { chain: Box<ChainDefinition> },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WasmModule {
+    pub module: String,
+    pub function: String,
+}
+
+/// Jump from link `from` to link `to` when `when` holds (always, if absent).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BranchDefinition {
+    pub from: usize,
+    pub to: usize,
+    #[serde(default)]
+    pub when: Option<When>,
+}
+
+/// A branch condition: an [`Expression`] string, or a single-key [`Condition`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum When {
+    Expr(String),
+    Key(Condition),
+}
+
+/// Branch condition on one context key: equal to `equals`, or truthy when `equals` is absent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Condition {
+    pub key: String,
+    #[serde(default)]
+    pub equals: Option<Value>,
+}
+
+impl Condition {
+    pub fn holds(&self, ctx: &Context) -> bool {
+        let value = ctx.0.get(self.key.as_str());
+        match (&self.equals, value) {
+            (Some(expected), Some(value)) => value == expected,
+            (Some(_), None) => false,
+            (None, value) => !matches!(value, None | Some(Value::Null) | Some(Value::Bool(false))),
+        }
+    }
+}
+
+/// Turns WASM modules referenced by definitions into links. The library does not bundle
+/// a WASM engine; implement this on top of wasmtime, wasmer, or similar.
+pub trait WasmHost: Send + Sync {
+    fn instantiate(&self, module: &WasmModule) -> Result<Link, String>;
+}
+
+impl ChainDefinition {
+    pub fn from_json(json: &str) -> Result<Self, DefinitionError> {
+        serde_json::from_str(json).map_err(|e| DefinitionError::Parse(e.to_string()))
+    }
+
+    /// Parse a definition that references environment variables or secrets (see
+    /// [`interpolate`]). Never use this for definitions from untrusted users.
+    pub fn from_json_with(json: &str, interpolation: &Interpolation) -> Result<Self, DefinitionError> {
+        interpolation.load(json, |text| serde_json::from_str::<Value>(text))
+    }
+
+    /// Non-fatal problems, such as references to deprecated links.
+    pub fn warnings(&self) -> Vec<String> {
+        self.links
+            .iter()
+            .flat_map(|def| match def {
+                LinkDefinition::Registry { link } => registry::link_metadata(link)
+                    .and_then(|m| m.deprecated)
+                    .map(|notice| format!("chain '{}' uses deprecated link '{}': {}", self.name, link, notice))
+                    .into_iter()
+                    .collect(),
+                LinkDefinition::Chain { chain } => chain.warnings(),
+                LinkDefinition::Wasm { .. } | LinkDefinition::Validate { .. } | LinkDefinition::Use { .. } => Vec::new(),
+            })
+            .collect()
+    }
+
+    /// SHA-256 (hex) of the definition: the chain version stamped on run reports and
+    /// checkpoints of chains built from it.
+    pub fn version_hash(&self) -> String {
+        let bytes = serde_json::to_vec(self).unwrap_or_default();
+        Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    /// Build a runnable chain, resolving registry links and instantiating WASM modules.
+    /// The chain's version is [`ChainDefinition::version_hash`].
+    /// Warnings (see [`ChainDefinition::warnings`]) are logged.
+    pub fn build(&self, wasm: Option<&dyn WasmHost>) -> Result<Chain, DefinitionError> {
+        self.build_with(wasm, None)
+    }
+
+    /// Check the definition against `profile`, then build it with the profile's resource
+    /// limits applied to every run, of the chain and of each inline chain in it.
+    pub fn build_sandboxed(&self, profile: &SandboxProfile, wasm: Option<&dyn WasmHost>) -> Result<Chain, DefinitionError> {
+        profile.check(self)?;
+        self.build_with(wasm, Some(&profile.limits))
+    }
+
+    // Build the chain and its inline chains, each with `limits` when given.
+    fn build_with(&self, wasm: Option<&dyn WasmHost>, limits: Option<&ResourceLimits>) -> Result<Chain, DefinitionError> {
+        for warning in self.warnings() {
+            tracing::warn!(chain = %self.name, "{}", warning);
+        }
+        let mut chain = Chain::new();
+        chain.set_version(self.version_hash());
+        chain.declare_input(self.input.iter().cloned());
+        for def in &self.links {
+            let (link, spec) = match def {
+                LinkDefinition::Registry { link } => {
+                    let found = registry::get_link(link).ok_or_else(|| DefinitionError::UnknownLink(link.clone()))?;
+                    (found, registry::link_metadata(link).unwrap_or_default().spec().name(link.clone()))
+                }
+                LinkDefinition::Wasm { wasm: module } => {
+                    let host = wasm.ok_or_else(|| DefinitionError::Wasm(format!("no WASM host for {}", module.module)))?;
+                    (host.instantiate(module).map_err(DefinitionError::Wasm)?, LinkSpec::default())
+                }
+                LinkDefinition::Validate { validate: rules } => {
+                    let link = std_links::validate(rules.clone()).map_err(|e| DefinitionError::Parse(format!("validate: {}", e)))?;
+                    (link, LinkSpec::new().name("validate").provides([rules.into.clone()]))
+                }
+                LinkDefinition::Use { target } => {
+                    let found = registry::get_chain(target).ok_or_else(|| DefinitionError::UnknownChain(target.clone()))?;
+                    (Chain::as_link(found), LinkSpec::new().name(target.clone()))
+                }
+                LinkDefinition::Chain { chain: def } => {
+                    let mut sub = def.build_with(wasm, limits)?;
+                    sub.set_name(def.name.clone());
+                    (Chain::as_link(Arc::new(sub)), LinkSpec::new().name(def.name.clone()))
+                }
+            };
+            chain.add_link_with(link, spec);
+        }
+        for branch in &self.branches {
+            if branch.from >= self.links.len() || branch.to >= self.links.len() {
+                return Err(DefinitionError::InvalidBranch { from: branch.from, to: branch.to });
+            }
+            match &branch.when {
+                None => chain.jump(branch.from, branch.to),
+                Some(When::Key(condition)) => {
+                    let description = match &condition.equals {
+                        Some(expected) => format!("{} == {}", condition.key, expected),
+                        None => condition.key.clone(),
+                    };
+                    let condition = condition.clone();
+                    chain.connect_described(branch.from, branch.to, description, move |ctx: &Context| condition.holds(ctx));
+                }
+                Some(When::Expr(source)) => {
+                    let expr = Expression::parse(source).map_err(|e| {
+                        DefinitionError::Parse(format!("branch {} -> {}: '{}': {}", branch.from, branch.to, source, e))
+                    })?;
+                    chain.connect_described(branch.from, branch.to, source.clone(), move |ctx: &Context| expr.eval(ctx));
+                }
+            }
+        }
+        if let Some(limits) = limits {
+            chain.set_limits(limits.clone());
+        }
+        Ok(chain)
+    }
+}
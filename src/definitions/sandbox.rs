@@ -0,0 +1,146 @@
+//! Sandbox profile for definitions supplied by untrusted users.
+//!
+//! A profile allowlists the registry links a definition may reference, caps its size,
+//! branches, loops (branches jumping backwards), branch expression length, and how deeply
+//! inline chains nest, decides whether WASM custom code is accepted (not by default), and
+//! carries [`ResourceLimits`] applied to every run of the built chain and of each inline
+//! chain in it. Links, branches, and loops are counted across the whole definition, inline
+//! chains included. The default limits bound wall time, context size, child runs, and link
+//! steps, so even an allowed loop cannot run forever.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::definitions::{ChainDefinition, DefinitionError, SandboxProfile};
+//!
+//! let profile = SandboxProfile::new().allow_link("lookup_order");
+//! let def = ChainDefinition::from_json(r#"{ "name": "x", "links": [ { "link": "delete_everything" } ] }"#).unwrap();
+//! assert!(matches!(profile.check(&def), Err(DefinitionError::NotAllowed(_))));
+//! ```
+
+use super::{ChainDefinition, DefinitionError, LinkDefinition, When};
+use crate::chains::ResourceLimits;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SandboxProfile {
+    /// Registry links and chains definitions may reference; nothing else resolves.
+    pub allowed_links: BTreeSet<String>,
+    /// Accept WASM custom code.
+    pub allow_wasm: bool,
+    pub max_links: usize,
+    pub max_branches: usize,
+    /// Branches whose target is at or before their source.
+    pub max_loops: usize,
+    /// Longest branch `when` expression, in bytes.
+    pub max_expression_len: usize,
+    /// Deepest nesting of inline chains; 0 allows none.
+    #[serde(default = "default_max_depth")]
+    pub max_depth: usize,
+    pub limits: ResourceLimits,
+}
+
+fn default_max_depth() -> usize {
+    4
+}
+
+// Links, branches, and loops seen so far across a definition and its inline chains.
+#[derive(Default)]
+struct Totals {
+    links: usize,
+    branches: usize,
+    loops: usize,
+}
+
+impl Default for SandboxProfile {
+    fn default() -> Self {
+        SandboxProfile {
+            allowed_links: BTreeSet::new(),
+            allow_wasm: false,
+            max_links: 64,
+            max_branches: 32,
+            max_loops: 4,
+            max_expression_len: 1024,
+            max_depth: default_max_depth(),
+            limits: ResourceLimits::new()
+                .with_max_wall_time(Duration::from_secs(30))
+                .with_max_context_bytes(1 << 20)
+                .with_max_children(16)
+                .with_max_steps(1_000),
+        }
+    }
+}
+
+impl SandboxProfile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn allow_link(mut self, name: impl Into<String>) -> Self {
+        self.allowed_links.insert(name.into());
+        self
+    }
+    /// Accept definitions that contain WASM modules.
+    pub fn allow_wasm(mut self) -> Self {
+        self.allow_wasm = true;
+        self
+    }
+    /// Reject definitions that contain WASM modules (the default).
+    pub fn deny_wasm(mut self) -> Self {
+        self.allow_wasm = false;
+        self
+    }
+    pub fn with_limits(mut self, limits: ResourceLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Check `def` without building it.
+    pub fn check(&self, def: &ChainDefinition) -> Result<(), DefinitionError> {
+        self.check_nested(def, 0, &mut Totals::default())
+    }
+
+    // Check `def`, an inline chain `depth` levels down, adding its counts to `totals`.
+    fn check_nested(&self, def: &ChainDefinition, depth: usize, totals: &mut Totals) -> Result<(), DefinitionError> {
+        let not_allowed = |msg: String| Err(DefinitionError::NotAllowed(msg));
+        if depth > self.max_depth {
+            return not_allowed(format!("inline chain '{}' nested {} deep, limit is {}", def.name, depth, self.max_depth));
+        }
+        totals.links += def.links.len();
+        if totals.links > self.max_links {
+            return not_allowed(format!("{} links, limit is {}", totals.links, self.max_links));
+        }
+        totals.branches += def.branches.len();
+        if totals.branches > self.max_branches {
+            return not_allowed(format!("{} branches, limit is {}", totals.branches, self.max_branches));
+        }
+        for branch in &def.branches {
+            if let Some(When::Expr(source)) = &branch.when {
+                if source.len() > self.max_expression_len {
+                    let limit = self.max_expression_len;
+                    return not_allowed(format!("branch {} -> {}: expression of {} bytes, limit is {}", branch.from, branch.to, source.len(), limit));
+                }
+            }
+        }
+        totals.loops += def.branches.iter().filter(|b| b.to <= b.from).count();
+        if totals.loops > self.max_loops {
+            return not_allowed(format!("{} loops, limit is {}", totals.loops, self.max_loops));
+        }
+        for link in &def.links {
+            match link {
+                LinkDefinition::Registry { link } if !self.allowed_links.contains(link) => {
+                    return not_allowed(format!("link '{}' is not allowlisted", link));
+                }
+                LinkDefinition::Use { target } if !self.allowed_links.contains(target) => {
+                    return not_allowed(format!("chain '{}' is not allowlisted", target));
+                }
+                LinkDefinition::Wasm { wasm } if !self.allow_wasm => {
+                    return not_allowed(format!("WASM module '{}'", wasm.module));
+                }
+                LinkDefinition::Chain { chain } => self.check_nested(chain, depth + 1, totals)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,163 @@
+//! Documentation generator for chains.
+//!
+//! `ChainGeneric::document()` renders markdown meant to be committed next to the chain:
+//! a Mermaid topology diagram, each link's description and required/provided keys
+//! (from `LinkSpec`), the middleware stack, and how failures and limits end a run.
+//! `document_html()` renders the same content as a standalone HTML page.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::{Link, LinkSpec};
+//! use std::sync::Arc;
+//!
+//! let noop: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx }));
+//! let mut chain = Chain::new();
+//! chain.set_name("refunds");
+//! chain.add_link_with(noop, LinkSpec::new().description("Look up the order").provides(["order"]));
+//! let md = chain.document();
+//! assert!(md.starts_with("# refunds"));
+//! assert!(md.contains("| 0 | Look up the order |  | `order` |"));
+//! ```
+
+use crate::chains::{ChainGeneric, ResourceLimits};
+use crate::links::LinkSpec;
+
+/// Everything the generator knows about a chain, independent of the output format.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainDoc {
+    pub name: String,
+    pub input: Vec<String>,
+    pub links: Vec<LinkSpec>,
+    /// (source, target) of each conditional branch.
+    pub branches: Vec<(usize, usize)>,
+    pub middleware: Vec<String>,
+    pub limits: ResourceLimits,
+}
+
+fn link_label(i: usize, spec: &LinkSpec) -> String {
+    match &spec.description {
+        Some(d) => format!("{}: {}", i, d),
+        None => format!("Link {}", i),
+    }
+}
+
+fn keys(keys: &[String]) -> String {
+    keys.iter().map(|k| format!("`{}`", k)).collect::<Vec<_>>().join(", ")
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+impl ChainDoc {
+    pub fn from_chain<T: Send + 'static>(chain: &ChainGeneric<T>) -> Self {
+        ChainDoc {
+            name: chain.name().unwrap_or("Chain").to_string(),
+            input: chain.input_keys().to_vec(),
+            links: chain.link_specs().to_vec(),
+            branches: chain.branches.iter().map(|b| (b.source, b.target)).collect(),
+            middleware: chain.middleware_names(),
+            limits: chain.limits().clone(),
+        }
+    }
+
+    /// Mermaid flowchart: solid edges run in order, dotted edges are conditional branches.
+    pub fn mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for (i, spec) in self.links.iter().enumerate() {
+            out.push_str(&format!("    L{}[\"{}\"]\n", i, link_label(i, spec).replace('"', "'")));
+        }
+        for i in 1..self.links.len() {
+            out.push_str(&format!("    L{} --> L{}\n", i - 1, i));
+        }
+        for (source, target) in &self.branches {
+            out.push_str(&format!("    L{} -. branch .-> L{}\n", source, target));
+        }
+        out
+    }
+
+    /// How a run ends early: failures, plus any configured resource limits.
+    fn error_handling(&self) -> Vec<String> {
+        let mut lines = vec!["A link or middleware that fails the run (`ctx_tools::fail_run`) stops it before the next link; the run reports `Failed` with the error.".to_string()];
+        let limits = &self.limits;
+        if let Some(d) = limits.max_wall_time {
+            lines.push(format!("Wall time limit: {:?}", d));
+        }
+        if let Some(b) = limits.max_context_bytes {
+            lines.push(format!("Context size limit: {} bytes", b));
+        }
+        if let Some(c) = limits.max_children {
+            lines.push(format!("Child run limit: {}", c));
+        }
+        if let Some(s) = limits.max_steps {
+            lines.push(format!("Link step limit: {}", s));
+        }
+        lines
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!("# {}\n\n## Topology\n\n```mermaid\n{}```\n", self.name, self.mermaid());
+        if !self.input.is_empty() {
+            md.push_str(&format!("\n## Input\n\n{}\n", keys(&self.input)));
+        }
+        md.push_str("\n## Links\n\n| # | Description | Requires | Provides |\n|---|---|---|---|\n");
+        for (i, spec) in self.links.iter().enumerate() {
+            md.push_str(&format!(
+                "| {} | {} | {} | {} |\n",
+                i,
+                spec.description.as_deref().unwrap_or(""),
+                keys(&spec.requires),
+                keys(&spec.provides)
+            ));
+        }
+        if !self.middleware.is_empty() {
+            md.push_str("\n## Middleware\n\n");
+            for mw in &self.middleware {
+                md.push_str(&format!("- {}\n", mw));
+            }
+        }
+        md.push_str("\n## Error handling\n\n");
+        for line in self.error_handling() {
+            md.push_str(&format!("- {}\n", line));
+        }
+        md
+    }
+
+    pub fn to_html(&self) -> String {
+        let list = |items: &[String]| items.iter().map(|i| format!("<code>{}</code>", escape_html(i))).collect::<Vec<_>>().join(", ");
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n<h2>Topology</h2>\n<pre class=\"mermaid\">\n{1}</pre>\n",
+            escape_html(&self.name),
+            escape_html(&self.mermaid())
+        );
+        if !self.input.is_empty() {
+            html.push_str(&format!("<h2>Input</h2>\n<p>{}</p>\n", list(&self.input)));
+        }
+        html.push_str("<h2>Links</h2>\n<table>\n<tr><th>#</th><th>Description</th><th>Requires</th><th>Provides</th></tr>\n");
+        for (i, spec) in self.links.iter().enumerate() {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                i,
+                escape_html(spec.description.as_deref().unwrap_or("")),
+                list(&spec.requires),
+                list(&spec.provides)
+            ));
+        }
+        html.push_str("</table>\n");
+        if !self.middleware.is_empty() {
+            html.push_str("<h2>Middleware</h2>\n<ul>\n");
+            for mw in &self.middleware {
+                html.push_str(&format!("<li>{}</li>\n", escape_html(mw)));
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("<h2>Error handling</h2>\n<ul>\n");
+        for line in self.error_handling() {
+            html.push_str(&format!("<li>{}</li>\n", escape_html(&line)));
+        }
+        html.push_str("</ul>\n</body>\n</html>\n");
+        html
+    }
+}
@@ -3,6 +3,27 @@ pub mod chains;
 pub mod middleware;
 pub mod links;
 pub mod listeners;
+pub mod sinks;
+pub mod registry;
+pub mod tenant;
+pub mod auth;
+pub mod policy;
+pub mod quota;
+pub mod schema;
+pub mod audit;
+pub mod admin;
+pub mod canary;
+pub mod bench;
+pub mod cache;
+pub mod definitions;
+pub mod boot;
+pub mod docs;
+pub mod pipe;
+pub mod std_links;
+#[cfg(feature = "cli")]
+pub mod cli;
+pub mod runtime;
+pub mod ctx_tools;
 
 /// Re-export macros for use throughout the crate
 #[macro_use]
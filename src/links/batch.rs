@@ -0,0 +1,147 @@
+//! Batching: collect contexts from concurrent runs and process them with one call.
+//!
+//! Runs reaching a batched link wait until `max_size` contexts are buffered or `window`
+//! has passed since the first one, whichever comes first. The batch link then runs once
+//! with all of them (a bulk insert, a bulk API call) and each run continues with its own
+//! result, matched by position. If the batch link panics or returns the wrong number of
+//! results, every run in the batch fails with its input context.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::batch::{Batch, BatchLink};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let bulk_insert: BatchLink<Context> = Arc::new(|batch: Vec<Context>| Box::pin(async move {
+//!     // one round trip for the whole batch
+//!     batch.into_iter().map(|ctx| ctx.insert("stored", true)).collect()
+//! }));
+//! let store = Batch::new(100, Duration::from_millis(20)).wrap(bulk_insert);
+//! ```
+
+use super::LinkGeneric;
+use crate::chains::RunError;
+use crate::ctx_tools;
+use crate::runtime::{default_executor, panic_message, BoxFuture, ExecutorObj};
+use futures::channel::oneshot;
+use futures::FutureExt;
+use std::panic::AssertUnwindSafe;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A link processing many contexts at once; returns one result per input, in order.
+pub type BatchLink<T> = Arc<dyn Fn(Vec<T>) -> BoxFuture<'static, Vec<T>> + Send + Sync>;
+
+type Reply<T> = oneshot::Sender<Result<T, RunError>>;
+
+struct Pending<T> {
+    items: Vec<(T, Reply<T>)>,
+    // Bumped every time a batch is taken, so a stale window timer flushes nothing
+    generation: u64,
+}
+
+struct Batcher<T> {
+    max_size: usize,
+    window: Duration,
+    executor: ExecutorObj,
+    link: BatchLink<T>,
+    pending: Mutex<Pending<T>>,
+}
+
+impl<T: Send + 'static> Batcher<T> {
+    fn take(&self, pending: &mut Pending<T>) -> Vec<(T, Reply<T>)> {
+        pending.generation += 1;
+        std::mem::take(&mut pending.items)
+    }
+
+    fn flush(&self, batch: Vec<(T, Reply<T>)>) {
+        let link = self.link.clone();
+        self.executor.spawn(Box::pin(async move {
+            let (inputs, replies): (Vec<T>, Vec<Reply<T>>) = batch.into_iter().unzip();
+            let size = inputs.len();
+            let outcome = AssertUnwindSafe(link(inputs)).catch_unwind().await;
+            match outcome {
+                Ok(results) if results.len() == size => {
+                    for (reply, result) in replies.into_iter().zip(results) {
+                        let _ = reply.send(Ok(result));
+                    }
+                }
+                Ok(results) => {
+                    let err = RunError::internal(format!("batch link returned {} results for {} inputs", results.len(), size));
+                    replies.into_iter().for_each(|reply| drop(reply.send(Err(err.clone()))));
+                }
+                Err(payload) => {
+                    let err = RunError::panicked(panic_message(payload));
+                    replies.into_iter().for_each(|reply| drop(reply.send(Err(err.clone()))));
+                }
+            }
+        }));
+    }
+
+    fn push(self: &Arc<Self>, ctx: T) -> oneshot::Receiver<Result<T, RunError>> {
+        let (tx, rx) = oneshot::channel();
+        let mut pending = self.pending.lock().unwrap();
+        pending.items.push((ctx, tx));
+        if pending.items.len() >= self.max_size {
+            let batch = self.take(&mut pending);
+            drop(pending);
+            self.flush(batch);
+        } else if pending.items.len() == 1 {
+            let generation = pending.generation;
+            let (this, timer) = (self.clone(), self.executor.sleep(self.window));
+            self.executor.spawn(Box::pin(async move {
+                timer.await;
+                let mut pending = this.pending.lock().unwrap();
+                if pending.generation == generation && !pending.items.is_empty() {
+                    let batch = this.take(&mut pending);
+                    drop(pending);
+                    this.flush(batch);
+                }
+            }));
+        }
+        rx
+    }
+}
+
+/// Batching options; see the [module docs](self).
+pub struct Batch {
+    max_size: usize,
+    window: Duration,
+    executor: ExecutorObj,
+}
+
+impl Batch {
+    pub fn new(max_size: usize, window: Duration) -> Self {
+        Batch { max_size: max_size.max(1), window, executor: default_executor() }
+    }
+    /// Executor running the window timer and the batch link (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    /// A link that adds each context to the current batch and continues with its result.
+    pub fn wrap<T: Clone + Send + 'static>(&self, link: BatchLink<T>) -> LinkGeneric<T> {
+        let batcher = Arc::new(Batcher {
+            max_size: self.max_size,
+            window: self.window,
+            executor: self.executor.clone(),
+            link,
+            pending: Mutex::new(Pending { items: Vec::new(), generation: 0 }),
+        });
+        Arc::new(move |ctx: T| {
+            let input = ctx.clone();
+            let reply = batcher.push(ctx);
+            Box::pin(async move {
+                let err = match reply.await {
+                    Ok(Ok(result)) => return result,
+                    Ok(Err(err)) => err,
+                    Err(_) => RunError::internal("batch was dropped before it ran"),
+                };
+                ctx_tools::fail_run(err);
+                input
+            })
+        })
+    }
+}
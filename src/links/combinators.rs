@@ -0,0 +1,244 @@
+//! Link combinators: links built from other links.
+//!
+//! For [`race`] and [`Hedge`], each alternative runs in its own run scope, so an
+//! alternative that fails (`ctx_tools::fail_run`) or panics only loses its attempt instead
+//! of failing the whole run. The scope shares the run's state, cancellation, and mode, and
+//! the warnings and annotations of the attempt that wins are kept.
+//!
+//! - [`race`] runs alternatives concurrently and keeps the first success.
+//! - [`Hedge`] starts a second attempt of a slow link and keeps whichever finishes first.
+//! - [`parallel`] runs independent links concurrently and merges all their results
+//!   (`ChainGeneric::add_parallel`).
+//!
+//! Example (ask two redundant providers, keep whichever answers first):
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::{combinators::race, Link};
+//! use std::sync::Arc;
+//!
+//! let primary: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("rate", 1.1) }));
+//! let mirror: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("rate", 1.1) }));
+//! let fetch_rate = race(vec![primary, mirror]);
+//! ```
+
+use super::LinkGeneric;
+use crate::chains::{RunError, RunScope};
+use crate::ctx_tools;
+use crate::runtime::{default_executor, panic_message, BoxFuture, ExecutorObj};
+use crate::context::Context;
+use futures::future::{join_all, select, select_all, Either, FutureExt};
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A failed attempt: the context it left behind (none if it panicked) and why it failed.
+pub(crate) type Failed<T> = (Option<T>, RunError);
+
+/// Run `link` on `ctx` in a scope of its own, derived from the current run's (see
+/// `RunScope::attempt_scope`). On success, children it spawns are awaited and what it
+/// recorded is handed to the run.
+pub(crate) fn attempt<T: Send + 'static>(link: LinkGeneric<T>, ctx: T) -> BoxFuture<'static, Result<T, Failed<T>>> {
+    let parent = RunScope::current();
+    let scope = parent.as_ref().map_or_else(RunScope::new, |parent| parent.attempt_scope());
+    Box::pin(async move {
+        match AssertUnwindSafe(scope.enter(link(ctx))).catch_unwind().await {
+            Ok(ctx) => match scope.failure() {
+                None => {
+                    scope.join_children().await;
+                    if let Some(parent) = parent {
+                        parent.adopt(&scope);
+                    }
+                    Ok(ctx)
+                }
+                Some(err) => Err((Some(ctx), err)),
+            },
+            Err(payload) => Err((None, RunError::panicked(panic_message(payload)))),
+        }
+    })
+}
+
+// Fail the current run with the error of `failed`, continuing with its context if it left one.
+pub(crate) fn give_up<T>((ctx, err): Failed<T>, original: T) -> T {
+    ctx_tools::fail_run(err);
+    ctx.unwrap_or(original)
+}
+
+/// Run `links` concurrently on clones of the context and continue with the first one to
+/// succeed; the others are dropped (cancelled) at that point. If every alternative fails,
+/// the run fails with the last failure.
+pub fn race<T: Clone + Send + 'static>(links: Vec<LinkGeneric<T>>) -> LinkGeneric<T> {
+    let links = Arc::new(links);
+    Arc::new(move |ctx: T| {
+        let links = links.clone();
+        Box::pin(async move {
+            if links.is_empty() {
+                return ctx;
+            }
+            let mut pending: Vec<_> = links.iter().map(|link| attempt(link.clone(), ctx.clone())).collect();
+            let mut last = None;
+            while !pending.is_empty() {
+                let (result, _, rest) = select_all(pending).await;
+                pending = rest;
+                match result {
+                    Ok(ctx) => return ctx,
+                    Err(failed) => last = Some(failed),
+                }
+            }
+            give_up(last.expect("at least one alternative ran"), ctx)
+        })
+    })
+}
+
+/// Run `links` concurrently on clones of the context, then combine the original context
+/// and their results, in link order, with `merge` (e.g. [`merge_changes`]).
+///
+/// Unlike [`race`], the links run in the scope of the current run: a link that fails the
+/// run fails it for all of them, and their warnings and children belong to the run.
+///
+/// Example (enrich from three independent services at once):
+/// ```rust
+/// use modulink_rs::context::Context;
+/// use modulink_rs::links::combinators::{merge_changes, parallel};
+/// use modulink_rs::links::Link;
+/// use std::sync::Arc;
+///
+/// # futures::executor::block_on(async {
+/// let profile: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("name", "Ada") }));
+/// let orders: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("orders", 3) }));
+/// let risk: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("risk", "low") }));
+/// let enrich = parallel(vec![profile, orders, risk], merge_changes);
+///
+/// let ctx = enrich(Context::new().insert("user_id", 7)).await;
+/// assert_eq!(ctx.get::<u32>("orders"), Some(3));
+/// assert_eq!(ctx.get::<u32>("user_id"), Some(7));
+/// # });
+/// ```
+pub fn parallel<T, M>(links: Vec<LinkGeneric<T>>, merge: M) -> LinkGeneric<T>
+where
+    T: Clone + Send + 'static,
+    M: Fn(T, Vec<T>) -> T + Send + Sync + 'static,
+{
+    let (links, merge) = (Arc::new(links), Arc::new(merge));
+    Arc::new(move |ctx: T| {
+        let (links, merge) = (links.clone(), merge.clone());
+        Box::pin(async move {
+            let results = join_all(links.iter().map(|link| link(ctx.clone()))).await;
+            merge(ctx, results)
+        })
+    })
+}
+
+/// Merge for [`parallel`]: apply the keys each result added, changed, or removed relative
+/// to `original`, in link order, so when two links change the same key the later one wins.
+pub fn merge_changes(original: Context, results: Vec<Context>) -> Context {
+    let mut merged = original.clone();
+    for result in results {
+        for key in original.0.keys().filter(|key| !result.0.contains_key(key.as_str())) {
+            merged.0.remove(key.as_str());
+        }
+        for (key, value) in result.0 {
+            if original.0.get(key.as_str()) != Some(&value) {
+                merged.0.insert(key, value);
+            }
+        }
+    }
+    merged
+}
+
+/// Counters shared by every link wrapped with the same [`Hedge`].
+#[derive(Debug, Default)]
+pub struct HedgeStats {
+    calls: AtomicU64,
+    hedged: AtomicU64,
+    hedge_wins: AtomicU64,
+}
+
+impl HedgeStats {
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+    /// Calls that started a second attempt because the first exceeded the delay.
+    pub fn hedged(&self) -> u64 {
+        self.hedged.load(Ordering::Relaxed)
+    }
+    /// Hedged calls where the second attempt finished first.
+    pub fn hedge_wins(&self) -> u64 {
+        self.hedge_wins.load(Ordering::Relaxed)
+    }
+}
+
+/// Hedged execution: if a link hasn't finished within `delay` (typically its p95 latency),
+/// start a second attempt on a clone of the input and keep whichever succeeds first.
+///
+/// A first attempt that fails before the delay fails the run as usual; hedging only
+/// targets slow calls, not errors.
+///
+/// Example:
+/// ```rust
+/// use modulink_rs::context::Context;
+/// use modulink_rs::links::{combinators::Hedge, Link};
+/// use std::sync::Arc;
+/// use std::time::Duration;
+///
+/// let lookup: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("found", true) }));
+/// let hedge = Hedge::new(Duration::from_millis(80));
+/// let lookup = hedge.wrap(lookup);
+/// let stats = hedge.stats();
+/// ```
+pub struct Hedge {
+    delay: Duration,
+    executor: ExecutorObj,
+    stats: Arc<HedgeStats>,
+}
+
+impl Hedge {
+    pub fn new(delay: Duration) -> Self {
+        Hedge { delay, executor: default_executor(), stats: Arc::default() }
+    }
+    /// Executor providing the delay timer (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+    pub fn stats(&self) -> Arc<HedgeStats> {
+        self.stats.clone()
+    }
+
+    pub fn wrap<T: Clone + Send + 'static>(&self, link: LinkGeneric<T>) -> LinkGeneric<T> {
+        let (delay, executor, stats) = (self.delay, self.executor.clone(), self.stats.clone());
+        Arc::new(move |ctx: T| {
+            let (link, executor, stats) = (link.clone(), executor.clone(), stats.clone());
+            Box::pin(async move {
+                stats.calls.fetch_add(1, Ordering::Relaxed);
+                let first = attempt(link.clone(), ctx.clone());
+                let first = match select(first, executor.sleep(delay)).await {
+                    Either::Left((Ok(done), _)) => return done,
+                    Either::Left((Err(failed), _)) => return give_up(failed, ctx),
+                    Either::Right((_, first)) => first,
+                };
+                stats.hedged.fetch_add(1, Ordering::Relaxed);
+                let second = attempt(link, ctx.clone());
+                // One attempt failed; the run continues with the other
+                let (other, other_is_hedge) = match select(first, second).await {
+                    Either::Left((Ok(done), _)) => return done,
+                    Either::Right((Ok(done), _)) => {
+                        stats.hedge_wins.fetch_add(1, Ordering::Relaxed);
+                        return done;
+                    }
+                    Either::Left((Err(_), second)) => (second, true),
+                    Either::Right((Err(_), first)) => (first, false),
+                };
+                match other.await {
+                    Ok(done) => {
+                        if other_is_hedge {
+                            stats.hedge_wins.fetch_add(1, Ordering::Relaxed);
+                        }
+                        done
+                    }
+                    Err(last) => give_up(last, ctx),
+                }
+            })
+        })
+    }
+}
@@ -15,6 +15,15 @@
 //!
 //! Advanced/generic links may use `mut` for performance, but must document the tradeoff.
 
+pub mod batch;
+pub mod combinators;
+pub mod options;
+pub mod stream;
+pub mod window;
+pub use combinators::{parallel, race};
+pub use options::LinkOptions;
+
+use crate::chains::RunError;
 use crate::context::Context;
 use std::future::Future;
 use std::pin::Pin;
@@ -28,6 +37,62 @@ pub type LinkGeneric<C> = Arc<dyn Fn(C) -> Pin<Box<dyn Future<Output = C> + Send
 /// For backward compatibility and ergonomic usage, export as Link.
 pub type Link = LinkGeneric<Context>;
 
+/// A link that can fail, added with `ChainGeneric::add_fallible_link`; its chain decides
+/// where the run goes on `Err` (see `chains::fallible`).
+pub type FallibleLinkGeneric<C> = Arc<dyn Fn(C) -> Pin<Box<dyn Future<Output = Result<C, RunError>> + Send>> + Send + Sync>;
+
+pub type FallibleLink = FallibleLinkGeneric<Context>;
+
+/// What a link declares about itself when added with `ChainGeneric::add_link_with`:
+/// the context keys it reads and writes, checked by `ChainGeneric::validate`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct LinkSpec {
+    /// Short identifier used in run error paths and docs.
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub requires: Vec<String>,
+    pub provides: Vec<String>,
+    /// The link acts outside the run (payments, emails, writes); see `chains::shadow`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub side_effects: bool,
+    /// The link only adds keys no neighbouring link reads or writes, so it may run
+    /// concurrently with them; see `chains::concurrent`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub commutative: bool,
+}
+
+impl LinkSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    pub fn requires<I: IntoIterator<Item = S>, S: Into<String>>(mut self, keys: I) -> Self {
+        self.requires.extend(keys.into_iter().map(Into::into));
+        self
+    }
+    pub fn provides<I: IntoIterator<Item = S>, S: Into<String>>(mut self, keys: I) -> Self {
+        self.provides.extend(keys.into_iter().map(Into::into));
+        self
+    }
+    /// Tag the link as side-effecting, so shadow runs skip or mock it.
+    pub fn side_effects(mut self) -> Self {
+        self.side_effects = true;
+        self
+    }
+    /// Tag the link as commutative with its tagged neighbours, so they can run concurrently.
+    pub fn commutative(mut self) -> Self {
+        self.commutative = true;
+        self
+    }
+}
+
 // --- Core API Exports ---
 
 
@@ -41,3 +106,6 @@ pub use crate::middleware::Middleware;
 // Listener system: ergonomic exports for sync and async listeners
 pub use crate::listeners::ListenerSync;
 pub use crate::listeners::ListenerAsync;
+
+// Sinks: where results go, symmetrical to listeners
+pub use crate::sinks::Sink;
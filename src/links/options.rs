@@ -0,0 +1,110 @@
+//! Per-link execution options: a timeout, and a fallback that lets the run continue when
+//! the link times out or fails.
+//!
+//! The fallback runs on the link's *input* context, so a non-critical enrichment step can
+//! degrade gracefully: either to another link (a cache, a cheaper provider) or, for
+//! `Context`, to a fixed set of default values. Every fallback is logged and recorded as a
+//! warning in the run report.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::{Link, LinkOptions};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let enrich: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("segment", "gold") }));
+//! let enrich = LinkOptions::new()
+//!     .timeout(Duration::from_millis(200))
+//!     .fallback_values([("segment", "unknown")])
+//!     .wrap(enrich);
+//! ```
+
+use super::combinators::{attempt, give_up};
+use super::LinkGeneric;
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::runtime::{self, default_executor, ExecutorObj};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct LinkOptions<T> {
+    timeout: Option<Duration>,
+    fallback: Option<LinkGeneric<T>>,
+    executor: ExecutorObj,
+}
+
+impl<T: Clone + Send + 'static> LinkOptions<T> {
+    pub fn new() -> Self {
+        LinkOptions { timeout: None, fallback: None, executor: default_executor() }
+    }
+    /// Give up on the link after `timeout`, failing the run unless a fallback is set.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+    /// On timeout or failure, run `fallback` on the link's input instead of failing the run.
+    pub fn fallback(mut self, fallback: LinkGeneric<T>) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+    /// Executor providing the timeout timer (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    pub fn wrap(&self, link: LinkGeneric<T>) -> LinkGeneric<T> {
+        let (timeout, fallback, executor) = (self.timeout, self.fallback.clone(), self.executor.clone());
+        Arc::new(move |ctx: T| {
+            let (link, fallback, executor) = (link.clone(), fallback.clone(), executor.clone());
+            Box::pin(async move {
+                let run = attempt(link, ctx.clone());
+                let result = match timeout {
+                    Some(dur) => runtime::timeout(executor.as_ref(), dur, run)
+                        .await
+                        .unwrap_or_else(|| Err((None, RunError::limit_exceeded(format!("link timed out after {:?}", dur))))),
+                    None => run.await,
+                };
+                match (result, fallback) {
+                    (Ok(done), _) => done,
+                    (Err((_, err)), Some(fallback)) => {
+                        tracing::warn!(error = %err, "link failed, using fallback");
+                        ctx_tools::warn(format!("fallback used: {}", err));
+                        fallback(ctx).await
+                    }
+                    (Err(failed), None) => give_up(failed, ctx),
+                }
+            })
+        })
+    }
+}
+
+impl<T: Clone + Send + 'static> Default for LinkOptions<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LinkOptions<Context> {
+    /// On timeout or failure, insert `values` into the link's input and continue.
+    pub fn fallback_values<I, K, V>(self, values: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Serialize,
+    {
+        let values: Vec<(String, Value)> = values
+            .into_iter()
+            .map(|(k, v)| (k.into(), serde_json::to_value(v).unwrap_or(Value::Null)))
+            .collect();
+        let values = Arc::new(values);
+        self.fallback(Arc::new(move |ctx: Context| {
+            let values = values.clone();
+            Box::pin(async move { values.iter().fold(ctx, |ctx, (k, v)| ctx.insert(k.clone(), v)) })
+        }))
+    }
+}
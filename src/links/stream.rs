@@ -0,0 +1,114 @@
+//! Streaming links: links that emit many items instead of returning one value.
+//!
+//! A [`Producer`] gets an [`Emitter`] along with the context and emits items as it finds
+//! them (pages of an API, rows of a file, search hits). [`collect`] gathers everything
+//! emitted into an array under a context key. [`pipeline`] hands each item to a map
+//! [`Stage`] the moment it is emitted, so producing and processing overlap within one
+//! run, and gathers the stage results, in emission order, instead.
+//!
+//! The channel between the two holds [`EMIT_BUFFER`] items; once it is full, `emit`
+//! waits for the consumer, so a fast producer cannot run ahead of a slow stage. The array
+//! is complete once the producer has returned and every clone of its emitter is dropped.
+//!
+//! Example (fetch pages and enrich each item while the next page loads):
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::stream::{pipeline, Producer, Stage};
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let pages: Producer<u32> = Arc::new(|ctx: Context, mut emitter| Box::pin(async move {
+//!     for id in 1..=3 {
+//!         emitter.emit(id).await;
+//!     }
+//!     ctx
+//! }));
+//! let enrich: Stage<u32, String> = Arc::new(|id| Box::pin(async move { format!("item-{}", id) }));
+//! let link = pipeline("items", pages, enrich, 4);
+//!
+//! let ctx = link(Context::new()).await;
+//! assert_eq!(ctx.get::<Vec<String>>("items"), Some(vec!["item-1".into(), "item-2".into(), "item-3".into()]));
+//! # });
+//! ```
+
+use super::Link;
+use crate::context::Context;
+use crate::runtime::BoxFuture;
+use futures::channel::mpsc;
+use futures::{SinkExt, StreamExt};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::Arc;
+
+/// Items an emitter can get ahead of its consumer before `emit` waits.
+pub const EMIT_BUFFER: usize = 32;
+
+/// Handle a [`Producer`] emits items through; clone it to emit from several tasks.
+pub struct Emitter<I> {
+    tx: mpsc::Sender<I>,
+}
+
+impl<I> Clone for Emitter<I> {
+    fn clone(&self) -> Self {
+        Emitter { tx: self.tx.clone() }
+    }
+}
+
+impl<I> Emitter<I> {
+    /// Emit `item`, waiting while the buffer is full. `false` once the consumer is gone
+    /// (e.g. the run was aborted), in which case the producer should stop.
+    pub async fn emit(&mut self, item: I) -> bool {
+        self.tx.send(item).await.is_ok()
+    }
+}
+
+/// A link body emitting items; returns the context the run continues with.
+pub type Producer<I> = Arc<dyn Fn(Context, Emitter<I>) -> BoxFuture<'static, Context> + Send + Sync>;
+
+/// Per-item processing applied by [`pipeline`].
+pub type Stage<I, O> = Arc<dyn Fn(I) -> BoxFuture<'static, O> + Send + Sync>;
+
+// Run `producer` on `ctx` while `consume` drains what it emits; the producer's context and
+// the consumer's result.
+async fn drive<I, R, F>(producer: &Producer<I>, ctx: Context, consume: impl FnOnce(mpsc::Receiver<I>) -> F) -> (Context, R)
+where
+    F: Future<Output = R>,
+{
+    let (tx, rx) = mpsc::channel(EMIT_BUFFER);
+    futures::join!(producer(ctx, Emitter { tx }), consume(rx))
+}
+
+/// A link running `producer` and storing everything it emits, in order, as an array at `key`.
+pub fn collect<I>(key: impl Into<String>, producer: Producer<I>) -> Link
+where
+    I: Serialize + Send + 'static,
+{
+    let key = key.into();
+    Arc::new(move |ctx: Context| {
+        let (key, producer) = (key.clone(), producer.clone());
+        Box::pin(async move {
+            let (ctx, items) = drive(&producer, ctx, |rx| rx.collect::<Vec<I>>()).await;
+            ctx.insert(key, items)
+        })
+    })
+}
+
+/// A link running `producer` and passing each emitted item to `stage` right away, with up
+/// to `concurrency` items in the stage at once. The stage results are stored as an array
+/// at `key`, in the order the items were emitted.
+pub fn pipeline<I, O>(key: impl Into<String>, producer: Producer<I>, stage: Stage<I, O>, concurrency: usize) -> Link
+where
+    I: Send + 'static,
+    O: Serialize + Send + 'static,
+{
+    let key = key.into();
+    let concurrency = concurrency.max(1);
+    Arc::new(move |ctx: Context| {
+        let (key, producer, stage) = (key.clone(), producer.clone(), stage.clone());
+        Box::pin(async move {
+            let consume = |rx: mpsc::Receiver<I>| rx.map(|item| stage(item)).buffered(concurrency).collect::<Vec<O>>();
+            let (ctx, results) = drive(&producer, ctx, consume).await;
+            ctx.insert(key, results)
+        })
+    })
+}
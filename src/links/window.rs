@@ -0,0 +1,213 @@
+//! Windowed aggregates over event streams.
+//!
+//! A [`WindowState`] keeps the recent events seen by a chain (one chain run per event, as
+//! driven by a listener), grouped by a context field, and writes aggregates over the
+//! current window into each event's context: counts, sums, and distinct counts. State
+//! lives in the process, so no external store is needed.
+//!
+//! - `tumbling(size)`: fixed, non-overlapping windows (`[0, size)`, `[size, 2 * size)`, ...).
+//! - `sliding(size)`: the `size` leading up to each event.
+//!
+//! Event time comes from an epoch-milliseconds field set with `event_time`, or the wall
+//! clock when it is unset or missing.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::window::WindowState;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let window = Arc::new(
+//!     WindowState::sliding(Duration::from_secs(60))
+//!         .key_by("user")
+//!         .event_time("ts")
+//!         .count("clicks_1m")
+//!         .sum("amount", "spend_1m")
+//!         .distinct("page", "pages_1m"),
+//! );
+//! let mut chain = Chain::new();
+//! chain.add_link(window.clone().link());
+//! # futures::executor::block_on(async {
+//! let event = |ts: u64, page: &str| Context::new().insert("user", "u1").insert("ts", ts).insert("amount", 5).insert("page", page);
+//! chain.run(event(1_000, "/a")).await;
+//! let ctx = chain.run(event(2_000, "/b")).await;
+//! assert_eq!(ctx.get::<u64>("clicks_1m"), Some(2));
+//! assert_eq!(ctx.get::<f64>("spend_1m"), Some(10.0));
+//! assert_eq!(ctx.get::<u64>("pages_1m"), Some(2));
+//! # });
+//! ```
+
+use super::Link;
+use crate::context::Context;
+use serde_json::Value;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Keys with no events left are dropped after this many recorded events.
+const SWEEP_EVERY: u64 = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Tumbling,
+    Sliding,
+}
+
+#[derive(Debug, Clone)]
+enum Aggregate {
+    Count,
+    Sum(String),
+    Distinct(String),
+}
+
+struct Event {
+    at: u64,
+    // Values of the fields read by `sum` and `distinct`, by field name
+    fields: HashMap<String, Value>,
+}
+
+#[derive(Default)]
+struct Windows {
+    keys: HashMap<String, VecDeque<Event>>,
+    recorded: u64,
+}
+
+/// Windowed aggregates keyed by a context field; see the [module docs](self).
+pub struct WindowState {
+    kind: Kind,
+    size: u64,
+    key: Option<String>,
+    event_time: Option<String>,
+    aggregates: Vec<(String, Aggregate)>,
+    windows: Mutex<Windows>,
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+fn key_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl WindowState {
+    fn new(kind: Kind, size: Duration) -> Self {
+        WindowState {
+            kind,
+            size: (size.as_millis() as u64).max(1),
+            key: None,
+            event_time: None,
+            aggregates: Vec::new(),
+            windows: Mutex::default(),
+        }
+    }
+    pub fn tumbling(size: Duration) -> Self {
+        Self::new(Kind::Tumbling, size)
+    }
+    pub fn sliding(size: Duration) -> Self {
+        Self::new(Kind::Sliding, size)
+    }
+    /// Keep separate windows per value of this field (one shared window when unset or missing).
+    pub fn key_by(mut self, field: impl Into<String>) -> Self {
+        self.key = Some(field.into());
+        self
+    }
+    /// Read event time from this field, in milliseconds since the epoch.
+    pub fn event_time(mut self, field: impl Into<String>) -> Self {
+        self.event_time = Some(field.into());
+        self
+    }
+    /// Write the number of events in the window to `output`.
+    pub fn count(mut self, output: impl Into<String>) -> Self {
+        self.aggregates.push((output.into(), Aggregate::Count));
+        self
+    }
+    /// Write the sum of the numeric `field` over the window to `output`, as a float.
+    pub fn sum(mut self, field: impl Into<String>, output: impl Into<String>) -> Self {
+        self.aggregates.push((output.into(), Aggregate::Sum(field.into())));
+        self
+    }
+    /// Write the number of distinct values of `field` in the window to `output`.
+    pub fn distinct(mut self, field: impl Into<String>, output: impl Into<String>) -> Self {
+        self.aggregates.push((output.into(), Aggregate::Distinct(field.into())));
+        self
+    }
+
+    // Whether an event at `at` is still in the window of an event at `now`.
+    fn in_window(&self, at: u64, now: u64) -> bool {
+        match self.kind {
+            Kind::Tumbling => at / self.size == now / self.size,
+            Kind::Sliding => at <= now && at + self.size > now,
+        }
+    }
+
+    fn compute(&self, events: &[&Event]) -> BTreeMap<String, Value> {
+        self.aggregates
+            .iter()
+            .map(|(output, aggregate)| {
+                let value = match aggregate {
+                    Aggregate::Count => Value::from(events.len()),
+                    Aggregate::Sum(field) => {
+                        Value::from(events.iter().filter_map(|e| e.fields.get(field)?.as_f64()).sum::<f64>())
+                    }
+                    Aggregate::Distinct(field) => {
+                        let distinct: BTreeSet<String> = events.iter().filter_map(|e| e.fields.get(field)).map(Value::to_string).collect();
+                        Value::from(distinct.len())
+                    }
+                };
+                (output.clone(), value)
+            })
+            .collect()
+    }
+
+    /// Record an event and return the aggregates over its window, including it.
+    pub fn record(&self, ctx: &Context) -> BTreeMap<String, Value> {
+        let key = self.key.as_ref().and_then(|field| ctx.0.get(field.as_str())).map(key_value).unwrap_or_default();
+        let at = self.event_time.as_ref().and_then(|field| ctx.0.get(field.as_str())?.as_u64()).unwrap_or_else(now_millis);
+        let fields = self
+            .aggregates
+            .iter()
+            .filter_map(|(_, aggregate)| match aggregate {
+                Aggregate::Count => None,
+                Aggregate::Sum(field) | Aggregate::Distinct(field) => Some((field.clone(), ctx.0.get(field.as_str())?.clone())),
+            })
+            .collect();
+
+        let mut windows = self.windows.lock().unwrap();
+        windows.recorded += 1;
+        if windows.recorded.is_multiple_of(SWEEP_EVERY) {
+            windows.keys.retain(|_, events| events.back().is_some_and(|e| self.in_window(e.at, at.max(e.at))));
+        }
+        let events = windows.keys.entry(key).or_default();
+        // Keep events ordered by time, even when they arrive slightly out of order
+        let position = events.iter().rposition(|e| e.at <= at).map_or(0, |i| i + 1);
+        events.insert(position, Event { at, fields });
+        let newest = events.back().map_or(at, |e| e.at);
+        while events.front().is_some_and(|e| !self.in_window(e.at, newest)) {
+            events.pop_front();
+        }
+        let current: Vec<&Event> = events.iter().filter(|e| self.in_window(e.at, at)).collect();
+        self.compute(&current)
+    }
+
+    /// Aggregates over the latest window for `key` without recording an event.
+    pub fn aggregates(&self, key: &str) -> Option<BTreeMap<String, Value>> {
+        let windows = self.windows.lock().unwrap();
+        let events = windows.keys.get(key)?;
+        let newest = events.back()?.at;
+        Some(self.compute(&events.iter().filter(|e| self.in_window(e.at, newest)).collect::<Vec<_>>()))
+    }
+
+    /// A link recording each context as an event and inserting the window aggregates.
+    pub fn link(self: Arc<Self>) -> Link {
+        Arc::new(move |ctx: Context| {
+            let aggregates = self.record(&ctx);
+            Box::pin(async move { aggregates.into_iter().fold(ctx, |ctx, (output, value)| ctx.insert(output, value)) })
+        })
+    }
+}
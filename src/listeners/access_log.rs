@@ -0,0 +1,83 @@
+//! Access logging and request correlation for HTTP listeners.
+//!
+//! Every request gets a correlation id: the incoming `X-Request-Id` if present, else the
+//! trace id of a valid W3C `traceparent`, else a fresh UUID. The id is echoed back in the
+//! `X-Request-Id` response header, stored in the run context under `meta::REQUEST_ID`, and
+//! logged as `run_id` in one `tracing` event per request (target `modulink::access`).
+
+use axum::body::HttpBody;
+use axum::extract::Request;
+use axum::http::{header::CONTENT_LENGTH, HeaderMap, HeaderName, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+use std::time::Instant;
+
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+pub const TRACEPARENT_HEADER: HeaderName = HeaderName::from_static("traceparent");
+
+/// Correlation data for one request, available to handlers as a request extension.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestCorrelation {
+    pub request_id: String,
+    pub traceparent: Option<String>,
+}
+
+impl RequestCorrelation {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let header = |name: &HeaderName| {
+            headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim).filter(|v| !v.is_empty())
+        };
+        let traceparent = header(&TRACEPARENT_HEADER).filter(|v| trace_id(v).is_some()).map(str::to_string);
+        let request_id = header(&REQUEST_ID_HEADER)
+            .map(str::to_string)
+            .or_else(|| traceparent.as_deref().and_then(trace_id).map(str::to_string))
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+        RequestCorrelation { request_id, traceparent }
+    }
+}
+
+/// Trace id of a W3C `traceparent` (`00-<32 hex>-<16 hex>-<2 hex>`), or `None` if malformed.
+pub fn trace_id(traceparent: &str) -> Option<&str> {
+    let parts: Vec<&str> = traceparent.split('-').collect();
+    let hex = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_hexdigit() && !b.is_ascii_uppercase());
+    match parts.as_slice() {
+        [version, trace, parent, flags]
+            if hex(version, 2) && *version != "ff" && hex(trace, 32) && hex(parent, 16) && hex(flags, 2)
+                && trace.bytes().any(|b| b != b'0') && parent.bytes().any(|b| b != b'0') =>
+        {
+            Some(trace)
+        }
+        _ => None,
+    }
+}
+
+/// axum middleware: attach [`RequestCorrelation`], echo `X-Request-Id`, and emit the access log.
+pub async fn access_log(mut req: Request, next: Next) -> Response {
+    let start = Instant::now();
+    let correlation = RequestCorrelation::from_headers(req.headers());
+    let method = req.method().clone();
+    let path = req.uri().path().to_string();
+    let request_bytes = req.headers().get(CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok())
+        .or_else(|| req.body().size_hint().exact());
+    let request_id = correlation.request_id.clone();
+    req.extensions_mut().insert(correlation);
+
+    let mut resp = next.run(req).await;
+    if let Ok(value) = HeaderValue::from_str(&request_id) {
+        resp.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    tracing::info!(
+        target: "modulink::access",
+        method = %method,
+        path = %path,
+        status = resp.status().as_u16(),
+        latency_ms = start.elapsed().as_secs_f64() * 1000.0,
+        request_bytes,
+        response_bytes = resp.body().size_hint().exact(),
+        run_id = %request_id,
+        "request"
+    );
+    resp
+}
@@ -1,55 +1,264 @@
-
-
-
-
-use axum::{Router, routing::post, extract::State, Json};
-use crate::context::Context;
+use axum::{Extension, Router, routing::post, extract::{DefaultBodyLimit, State}, Json};
+use axum::extract::rejection::JsonRejection;
+use axum::http::{HeaderMap, Method, StatusCode, Uri, header::AUTHORIZATION};
+use axum::response::{IntoResponse, Response};
+use crate::chains::{Chain, RateLimiter, RunError, RunStatus};
+use crate::context::{meta, Context};
+use crate::links::Link;
 use crate::listeners::BaseListenerAsync;
-use std::sync::Arc;
+use crate::listeners::access_log::{access_log, RequestCorrelation};
+use crate::listeners::http_options::{CorsConfig, HttpListenerOptions};
+use crate::runtime::BoxFuture;
+use crate::admin::Admin;
+use crate::std_links::{Approvals, Events};
 use std::net::SocketAddr;
+use std::sync::Arc;
 use async_trait::async_trait;
 
+/// Claims of the bearer JWT validated for a request (`HttpListener::with_jwt`), available
+/// to the handlers of every route the listener serves as a request extension.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuthClaims(pub serde_json::Value);
+
+impl AuthClaims {
+    /// The `sub` claim, if it is a string.
+    pub fn subject(&self) -> Option<&str> {
+        self.0.get("sub").and_then(|sub| sub.as_str())
+    }
+}
 
+/// Handler invoked for each request; returns the final context and how the run ended.
+pub type HttpHandler = Arc<dyn Fn(Context) -> BoxFuture<'static, (Context, RunStatus)> + Send + Sync>;
 
 /// Default ergonomic HTTP listener for modulink-rust using axum.
 /// Accepts a handler (chain) and address.
+///
+/// `POST /run` takes a JSON object as the input context and responds with the final
+/// context. Reserved keys (starting with `_`, see `context::meta`) are dropped from the
+/// input; the listener sets the ones it knows. Failed runs respond with the status code of
+/// their `ErrorKind` and a `{"error": {...}}` body.
+///
+/// Each request is access-logged and correlated by `X-Request-Id`/`traceparent`
+/// (see [`crate::listeners::access_log`]).
 pub struct HttpListener {
-    pub handler: Arc<dyn Fn(Context) -> std::pin::Pin<Box<dyn std::future::Future<Output = Context> + Send>> + Send + Sync>,
+    pub handler: HttpHandler,
     pub addr: String,
+    pub options: HttpListenerOptions,
+    // Warmed up before accepting connections, shut down after a graceful shutdown
+    chain: Option<Arc<Chain>>,
+    shutdown_signal: Option<ShutdownSignal>,
+    approvals: Option<Arc<Approvals>>,
+    events: Option<Arc<Events>>,
+    admin: Option<Arc<Admin>>,
+}
+
+/// Future factory that resolves when the listener should stop accepting connections.
+pub type ShutdownSignal = Arc<dyn Fn() -> BoxFuture<'static, ()> + Send + Sync>;
+
+impl HttpListener {
+    /// Listener for a plain link/closure; every response is reported as 200.
+    pub fn new(handler: Link, addr: impl Into<String>) -> Self {
+        let handler: HttpHandler = Arc::new(move |ctx: Context| {
+            let run = handler(ctx);
+            Box::pin(async move { (run.await, RunStatus::Completed) })
+        });
+        HttpListener { handler, addr: addr.into(), options: HttpListenerOptions::default(), chain: None, shutdown_signal: None, approvals: None, events: None, admin: None }
+    }
+    /// Listener that runs `chain` and maps failed runs to HTTP error statuses.
+    /// The chain is warmed up (`ChainGeneric::warm_up`) before the listener binds, and shut
+    /// down (`ChainGeneric::shutdown`) after a graceful shutdown.
+    pub fn for_chain(chain: Arc<Chain>, addr: impl Into<String>) -> Self {
+        let warm = chain.clone();
+        let handler: HttpHandler = Arc::new(move |ctx: Context| {
+            let chain = chain.clone();
+            Box::pin(async move {
+                let (ctx, report) = chain.run_with_report(ctx).await;
+                (ctx, report.status)
+            })
+        });
+        HttpListener { handler, addr: addr.into(), options: HttpListenerOptions::default(), chain: Some(warm), shutdown_signal: None, approvals: None, events: None, admin: None }
+    }
+    pub fn with_options(mut self, options: HttpListenerOptions) -> Self {
+        self.options = options;
+        self
+    }
+    /// Handle CORS preflight and add CORS headers per `cors`.
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.options.cors = Some(cors);
+        self
+    }
+    /// Compress responses with gzip or brotli, as negotiated by `Accept-Encoding`.
+    pub fn with_compression(mut self) -> Self {
+        self.options.compression = true;
+        self
+    }
+    /// Reject request bodies larger than `bytes` with 413.
+    pub fn with_max_body_bytes(mut self, bytes: usize) -> Self {
+        self.options.max_body_bytes = Some(bytes);
+        self
+    }
+    /// Stop accepting connections once the future returned by `signal` resolves (e.g.
+    /// `tokio::signal::ctrl_c`), finish in-flight requests, then return from `start`.
+    pub fn with_graceful_shutdown<F, Fut>(mut self, signal: F) -> Self
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = ()> + Send + 'static,
+    {
+        self.shutdown_signal = Some(Arc::new(move || Box::pin(signal())));
+        self
+    }
+    /// Serve the approval routes of `approvals` next to `/run` (see [`crate::std_links::approval`]).
+    pub fn with_approvals(mut self, approvals: Arc<Approvals>) -> Self {
+        self.approvals = Some(approvals);
+        self
+    }
+    /// Serve `POST /events/{key}` delivering callbacks to `events` (see [`crate::std_links::event`]).
+    pub fn with_events(mut self, events: Arc<Events>) -> Self {
+        self.events = Some(events);
+        self
+    }
+    /// Serve the admin routes of `admin` under `/admin` (see [`crate::admin`]).
+    pub fn with_admin(mut self, admin: Arc<Admin>) -> Self {
+        self.admin = Some(admin);
+        self
+    }
+    /// Answer requests over `limiter`'s rate with 429 instead of running them.
+    pub fn with_rate_limit(mut self, limiter: Arc<RateLimiter>) -> Self {
+        self.options.rate_limit = Some(limiter);
+        self
+    }
+    /// Validate bearer JWTs with `validator` on every route the listener serves: `/run`
+    /// and the approval, event, and admin routes.
+    #[cfg(feature = "jwt")]
+    pub fn with_jwt(mut self, validator: Arc<crate::auth::JwtValidator>) -> Self {
+        self.options.jwt = Some(validator);
+        self
+    }
+}
+
+struct ListenerState {
+    handler: HttpHandler,
+    options: HttpListenerOptions,
 }
 
+pub(crate) fn error_response(err: &RunError) -> Response {
+    let status = StatusCode::from_u16(err.kind.http_status()).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    (status, Json(serde_json::json!({ "error": err }))).into_response()
+}
 
+async fn run_handler(
+    State(state): State<Arc<ListenerState>>,
+    Extension(correlation): Extension<RequestCorrelation>,
+    claims: Option<Extension<AuthClaims>>,
+    method: Method,
+    uri: Uri,
+    headers: HeaderMap,
+    body: Result<Json<serde_json::Value>, JsonRejection>,
+) -> Response {
+    if let Some(Err(wait)) = state.options.rate_limit.as_ref().map(|limiter| limiter.try_acquire()) {
+        return error_response(&RunError::rate_limited(format!("too many requests, next permit in {:?}", wait)));
+    }
+    let body = match body {
+        Ok(Json(body)) => body,
+        Err(rejection) if rejection.status() == StatusCode::PAYLOAD_TOO_LARGE => {
+            return error_response(&RunError::limit_exceeded("request body too large"));
+        }
+        Err(rejection) => return rejection.into_response(),
+    };
+    let mut map = body.as_object().cloned().unwrap_or_default();
+    // Metadata (auth, tenant, correlation, audit) is only ever set by the listener and the
+    // chain, never taken from the request body
+    meta::strip_reserved(&mut map);
+    map.insert(meta::HTTP.to_string(), serde_json::json!({ "method": method.as_str(), "path": uri.path() }));
+    map.insert(meta::REQUEST_ID.to_string(), correlation.request_id.into());
+    if let Some(traceparent) = correlation.traceparent {
+        map.insert(meta::TRACEPARENT.to_string(), traceparent.into());
+    }
+    if let Some(Extension(AuthClaims(claims))) = claims {
+        map.insert(meta::AUTH.to_string(), claims);
+    }
+    let authorization = headers.get(AUTHORIZATION).and_then(|v| v.to_str().ok());
+    if state.options.forward_authorization {
+        if let Some(value) = authorization {
+            map.insert(meta::AUTHORIZATION.to_string(), value.into());
+        }
+    }
+    let ctx = Context::from(map);
+    let (result, status) = (state.handler)(ctx).await;
+    if let RunStatus::Failed(err) = status {
+        return error_response(&err);
+    }
+    // Convert HashMap to serde_json::Map for correct JSON response
+    let map: serde_json::Map<String, serde_json::Value> = result.into();
+    Json(serde_json::Value::Object(map)).into_response()
+}
 
+// Rejects requests without a valid bearer JWT; passes the claims on as `AuthClaims`.
+#[cfg(feature = "jwt")]
+async fn jwt_auth(
+    State(validator): State<Arc<crate::auth::JwtValidator>>,
+    mut req: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> Response {
+    let authorization = req.headers().get(AUTHORIZATION).and_then(|v| v.to_str().ok());
+    match validator.validate_header(authorization) {
+        Ok(claims) => {
+            req.extensions_mut().insert(AuthClaims(claims));
+            next.run(req).await
+        }
+        Err(err) => error_response(&err),
+    }
+}
 
 #[async_trait]
 impl BaseListenerAsync for HttpListener {
     async fn start(&self) -> std::io::Result<()> {
-        let handler = self.handler.clone();
         let addr: SocketAddr = self.addr.parse().expect("Invalid address");
-
-        // Axum handler closure
-        let handler_clone = handler.clone();
-        let app = Router::new()
-            .route("/run", post(
-                move |State(handler): State<Arc<dyn Fn(Context) -> std::pin::Pin<Box<dyn std::future::Future<Output = Context> + Send>> + Send + Sync>>, Json(body): Json<serde_json::Value>| {
-                    let handler = handler.clone();
-                    async move {
-                        let map = body.as_object().cloned().unwrap_or_default();
-                        let ctx = Context(map.into_iter().collect());
-                        let result = handler(ctx).await;
-                        // Convert HashMap to serde_json::Map for correct JSON response
-                        let map: serde_json::Map<String, serde_json::Value> = result.0.into_iter().collect();
-                        Json(serde_json::Value::Object(map))
-                    }
-                }
-            ))
-            .with_state(handler_clone);
+        if let Some(chain) = &self.chain {
+            chain.warm_up().await?;
+        }
+        let state = Arc::new(ListenerState { handler: self.handler.clone(), options: self.options.clone() });
+        let mut app = Router::new()
+            .route("/run", post(run_handler))
+            .with_state(state);
+        if let Some(approvals) = &self.approvals {
+            app = app.merge(approvals.clone().router());
+        }
+        if let Some(events) = &self.events {
+            app = app.merge(events.clone().router());
+        }
+        if let Some(admin) = &self.admin {
+            app = app.merge(admin.clone().router());
+        }
+        // Inside CORS, so preflight requests are answered without a token
+        #[cfg(feature = "jwt")]
+        if let Some(validator) = &self.options.jwt {
+            app = app.layer(axum::middleware::from_fn_with_state(validator.clone(), jwt_auth));
+        }
+        if let Some(limit) = self.options.max_body_bytes {
+            app = app.layer(DefaultBodyLimit::max(limit));
+        }
+        if self.options.compression {
+            app = app.layer(tower_http::compression::CompressionLayer::new());
+        }
+        if let Some(cors) = &self.options.cors {
+            app = app.layer(cors.layer());
+        }
+        let app = app.layer(axum::middleware::from_fn(access_log));
 
         // Use axum::serve (hyper::Server)
         use axum::serve;
         use tokio::net::TcpListener;
-        let listener = TcpListener::bind(addr).await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?;
-        serve(listener, app.into_make_service()).await.map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))
+        let listener = TcpListener::bind(addr).await.map_err(std::io::Error::other)?;
+        let server = serve(listener, app.into_make_service());
+        match &self.shutdown_signal {
+            Some(signal) => server.with_graceful_shutdown(signal()).await?,
+            None => server.await?,
+        }
+        match &self.chain {
+            Some(chain) => chain.shutdown().await,
+            None => Ok(()),
+        }
     }
     fn name(&self) -> &'static str {
         "http"
@@ -0,0 +1,141 @@
+//! Configuration for `HttpListener`.
+
+use axum::http::{HeaderName, HeaderValue, Method};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+/// Optional behaviour for `HttpListener`.
+#[derive(Clone, Default)]
+pub struct HttpListenerOptions {
+    /// Validate `Authorization: Bearer` JWTs on every route; claims are placed under `_auth`
+    /// and requests with missing, invalid, or expired tokens get 401/403 without a run.
+    #[cfg(feature = "jwt")]
+    pub jwt: Option<std::sync::Arc<crate::auth::JwtValidator>>,
+    /// Copy the raw `Authorization` header into `_authorization` so auth middleware
+    /// (e.g. `auth::JwtMiddleware`) can validate it at run start.
+    pub forward_authorization: bool,
+    /// Answer CORS preflight requests and add CORS headers to responses.
+    pub cors: Option<CorsConfig>,
+    /// Compress responses with gzip or brotli when the client sends `Accept-Encoding`.
+    pub compression: bool,
+    /// Largest accepted request body in bytes; bigger bodies get 413 without a run.
+    /// `None` keeps axum's default of 2 MiB.
+    pub max_body_bytes: Option<usize>,
+    /// Take a permit for every request; requests that find none get 429 without a run.
+    pub rate_limit: Option<std::sync::Arc<crate::chains::RateLimiter>>,
+}
+
+/// Cross-origin policy for browser clients. `"*"` in a list allows anything.
+///
+/// Example:
+/// ```rust
+/// use modulink_rs::listeners::CorsConfig;
+///
+/// let cors = CorsConfig::new()
+///     .allow_origin("https://app.example.com")
+///     .allow_header("authorization");
+/// assert_eq!(cors.allowed_methods, vec!["POST", "OPTIONS"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Vec<String>,
+    pub allowed_headers: Vec<String>,
+    /// Allow cookies/Authorization on cross-origin requests. Not combinable with `"*"` lists.
+    pub allow_credentials: bool,
+    /// How long browsers may cache a preflight response.
+    pub max_age: Option<Duration>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        CorsConfig {
+            allowed_origins: Vec::new(),
+            allowed_methods: vec!["POST".to_string(), "OPTIONS".to_string()],
+            allowed_headers: vec!["content-type".to_string()],
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+}
+
+fn is_any(list: &[String]) -> bool {
+    list.iter().any(|v| v == "*")
+}
+
+impl CorsConfig {
+    /// No origins allowed until added with `allow_origin`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Any origin, method, and header. Intended for local development.
+    pub fn permissive() -> Self {
+        CorsConfig {
+            allowed_origins: vec!["*".to_string()],
+            allowed_methods: vec!["*".to_string()],
+            allowed_headers: vec!["*".to_string()],
+            allow_credentials: false,
+            max_age: None,
+        }
+    }
+    pub fn allow_origin(mut self, origin: impl Into<String>) -> Self {
+        self.allowed_origins.push(origin.into());
+        self
+    }
+    pub fn allow_method(mut self, method: impl Into<String>) -> Self {
+        self.allowed_methods.push(method.into());
+        self
+    }
+    pub fn allow_header(mut self, header: impl Into<String>) -> Self {
+        self.allowed_headers.push(header.into());
+        self
+    }
+    pub fn allow_credentials(mut self, allow: bool) -> Self {
+        self.allow_credentials = allow;
+        self
+    }
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Build the tower layer. Entries that are not valid origins, methods, or header
+    /// names are skipped with a warning.
+    pub fn layer(&self) -> CorsLayer {
+        let mut layer = CorsLayer::new();
+        layer = if is_any(&self.allowed_origins) {
+            layer.allow_origin(Any)
+        } else {
+            let origins: Vec<HeaderValue> = self.allowed_origins.iter().filter_map(|o| parse_or_warn(o, "origin", |v| HeaderValue::from_str(v).ok())).collect();
+            layer.allow_origin(AllowOrigin::list(origins))
+        };
+        layer = if is_any(&self.allowed_methods) {
+            layer.allow_methods(Any)
+        } else {
+            let methods: Vec<Method> = self.allowed_methods.iter().filter_map(|m| parse_or_warn(m, "method", |v| Method::from_bytes(v.to_ascii_uppercase().as_bytes()).ok())).collect();
+            layer.allow_methods(methods)
+        };
+        layer = if is_any(&self.allowed_headers) {
+            layer.allow_headers(Any)
+        } else {
+            let headers: Vec<HeaderName> = self.allowed_headers.iter().filter_map(|h| parse_or_warn(h, "header", |v| HeaderName::from_bytes(v.as_bytes()).ok())).collect();
+            layer.allow_headers(headers)
+        };
+        if self.allow_credentials {
+            layer = layer.allow_credentials(true);
+        }
+        if let Some(max_age) = self.max_age {
+            layer = layer.max_age(max_age);
+        }
+        layer
+    }
+}
+
+fn parse_or_warn<T>(value: &str, what: &str, parse: impl Fn(&str) -> Option<T>) -> Option<T> {
+    let parsed = parse(value);
+    if parsed.is_none() {
+        tracing::warn!(value, what, "ignoring invalid CORS entry");
+    }
+    parsed
+}
@@ -0,0 +1,41 @@
+use crate::context::{meta, Context};
+use crate::links::Link;
+use crate::listeners::BaseListenerAsync;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Minimal consumer interface the Kafka listener reads through.
+/// Implement it over the client of your choice (e.g. `rdkafka::consumer::StreamConsumer`).
+#[async_trait]
+pub trait KafkaConsumer: Send + Sync {
+    /// Next message payload from `topic`; `None` once the consumer is closed.
+    async fn recv(&self, topic: &str) -> std::io::Result<Option<Vec<u8>>>;
+}
+
+/// Runs the handler once per JSON-object message on a Kafka topic.
+/// Messages that are not JSON objects are logged and skipped; reserved (`_`) keys are dropped
+/// from the rest.
+pub struct KafkaListener {
+    pub consumer: Arc<dyn KafkaConsumer>,
+    pub topic: String,
+    pub handler: Link,
+}
+
+#[async_trait]
+impl BaseListenerAsync for KafkaListener {
+    async fn start(&self) -> std::io::Result<()> {
+        while let Some(payload) = self.consumer.recv(&self.topic).await? {
+            match serde_json::from_slice::<serde_json::Value>(&payload) {
+                Ok(serde_json::Value::Object(mut map)) => {
+                    meta::strip_reserved(&mut map);
+                    let _ = (self.handler)(Context::from(map)).await;
+                }
+                _ => tracing::warn!(topic = %self.topic, "skipping non-object Kafka message"),
+            }
+        }
+        Ok(())
+    }
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+}
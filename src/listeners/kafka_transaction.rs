@@ -0,0 +1,130 @@
+//! Exactly-once consume-transform-produce over Kafka transactions.
+//!
+//! [`KafkaTransactionalListener`] runs a chain on each message of an input topic and
+//! publishes the result to an output topic inside a Kafka transaction that also commits
+//! the consumed offset. The output and the offset become visible together or not at all,
+//! so a crash never loses a message or publishes its result twice (consumers of the
+//! output topic read with `isolation.level=read_committed`).
+//!
+//! Per message:
+//! 1. `begin_transaction`
+//! 2. run the chain on the message, minus its reserved (`_`) keys; if the run completed,
+//!    `send` the result to the output topic
+//! 3. `send_offsets` (the message offset + 1, for the consumer group)
+//! 4. `commit_transaction`
+//!
+//! A failed run commits its offset without output, like a skipped message. If producing
+//! or committing fails, the transaction is aborted, the consumer is rewound to the
+//! message, and `start` returns the error; restarting the listener reprocesses it.
+
+use crate::chains::{Chain, RunStatus};
+use crate::context::{meta, Context};
+use crate::listeners::BaseListenerAsync;
+use crate::sinks::KafkaProducer;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// A consumed message and its position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KafkaRecord {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub payload: Vec<u8>,
+}
+
+/// Consumer interface for transactional pipelines: messages with their offsets.
+/// Implement it over the client of your choice with auto-commit disabled.
+#[async_trait]
+pub trait KafkaRecordConsumer: Send + Sync {
+    /// Next message from `topic`; `None` once the consumer is closed.
+    async fn recv_record(&self, topic: &str) -> std::io::Result<Option<KafkaRecord>>;
+    /// Consumer group whose offsets the transaction commits.
+    fn group_id(&self) -> &str;
+    /// Move back to `record` so it is delivered again after an aborted transaction.
+    async fn rewind(&self, record: &KafkaRecord) -> std::io::Result<()>;
+}
+
+/// Producer with Kafka transactions (a `transactional.id` is configured on the client).
+#[async_trait]
+pub trait KafkaTransactionalProducer: KafkaProducer {
+    async fn begin_transaction(&self) -> std::io::Result<()>;
+    /// Commit `offset` for `topic`/`partition` in `group_id` as part of the open transaction.
+    async fn send_offsets(&self, group_id: &str, topic: &str, partition: i32, offset: i64) -> std::io::Result<()>;
+    async fn commit_transaction(&self) -> std::io::Result<()>;
+    async fn abort_transaction(&self) -> std::io::Result<()>;
+}
+
+type KeyFn = Arc<dyn Fn(&Context) -> Option<String> + Send + Sync>;
+
+/// Runs a chain per message and publishes results with exactly-once semantics;
+/// see the [module docs](self).
+pub struct KafkaTransactionalListener {
+    consumer: Arc<dyn KafkaRecordConsumer>,
+    producer: Arc<dyn KafkaTransactionalProducer>,
+    chain: Arc<Chain>,
+    input: String,
+    output: String,
+    key: Option<KeyFn>,
+}
+
+impl KafkaTransactionalListener {
+    pub fn new(
+        consumer: Arc<dyn KafkaRecordConsumer>,
+        producer: Arc<dyn KafkaTransactionalProducer>,
+        chain: Arc<Chain>,
+        input: impl Into<String>,
+        output: impl Into<String>,
+    ) -> Self {
+        KafkaTransactionalListener { consumer, producer, chain, input: input.into(), output: output.into(), key: None }
+    }
+    /// Derive the output message key from the result (e.g. for partitioning by customer).
+    pub fn with_key<F>(mut self, key: F) -> Self
+    where
+        F: Fn(&Context) -> Option<String> + Send + Sync + 'static,
+    {
+        self.key = Some(Arc::new(key));
+        self
+    }
+
+    // Everything after `begin_transaction`; any error aborts the transaction.
+    async fn process(&self, record: &KafkaRecord) -> std::io::Result<()> {
+        match serde_json::from_slice::<serde_json::Value>(&record.payload) {
+            Ok(serde_json::Value::Object(mut map)) => {
+                meta::strip_reserved(&mut map);
+                let (ctx, report) = self.chain.run_with_report(Context::from(map)).await;
+                if report.status == RunStatus::Completed {
+                    let payload = serde_json::to_vec(&ctx).map_err(std::io::Error::other)?;
+                    let key = self.key.as_ref().and_then(|f| f(&ctx));
+                    self.producer.send(&self.output, key.as_deref(), payload).await?;
+                } else {
+                    tracing::warn!(topic = %record.topic, offset = record.offset, "run failed; committing offset without output");
+                }
+            }
+            _ => tracing::warn!(topic = %record.topic, offset = record.offset, "skipping non-object Kafka message"),
+        }
+        let group = self.consumer.group_id();
+        self.producer.send_offsets(group, &record.topic, record.partition, record.offset + 1).await?;
+        self.producer.commit_transaction().await
+    }
+}
+
+#[async_trait]
+impl BaseListenerAsync for KafkaTransactionalListener {
+    async fn start(&self) -> std::io::Result<()> {
+        while let Some(record) = self.consumer.recv_record(&self.input).await? {
+            self.producer.begin_transaction().await?;
+            if let Err(e) = self.process(&record).await {
+                if let Err(abort) = self.producer.abort_transaction().await {
+                    tracing::error!(error = %abort, "aborting Kafka transaction failed");
+                }
+                self.consumer.rewind(&record).await?;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+    fn name(&self) -> &'static str {
+        "kafka-transactional"
+    }
+}
@@ -1,5 +1,26 @@
+pub mod kafka_listener;
+pub use kafka_listener::{KafkaConsumer, KafkaListener};
+pub mod kafka_transaction;
+pub use kafka_transaction::{KafkaRecord, KafkaRecordConsumer, KafkaTransactionalListener, KafkaTransactionalProducer};
+pub mod trigger;
+pub use trigger::{Debounce, Throttle};
+
+// The HTTP listener needs the `http` feature (axum) and the stdin listener needs tokio;
+// the listener traits themselves are runtime-neutral.
+#[cfg(feature = "http")]
+pub mod access_log;
+#[cfg(feature = "http")]
 pub mod http_listener;
-pub use http_listener::HttpListener;
+#[cfg(feature = "http")]
+pub mod http_options;
+#[cfg(feature = "http")]
+pub use http_listener::{AuthClaims, HttpListener};
+#[cfg(feature = "http")]
+pub use http_options::{CorsConfig, HttpListenerOptions};
+#[cfg(feature = "tokio")]
+pub mod stdin_listener;
+#[cfg(feature = "tokio")]
+pub use stdin_listener::StdinListener;
 
 use async_trait::async_trait;
 
@@ -0,0 +1,35 @@
+use crate::context::{meta, Context};
+use crate::links::Link;
+use crate::listeners::BaseListenerAsync;
+use async_trait::async_trait;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// Runs the handler once per JSON-object line read from stdin, until EOF.
+/// Blank lines are ignored; lines that are not JSON objects are logged and skipped.
+/// Reserved (`_`) keys are dropped from each line.
+pub struct StdinListener {
+    pub handler: Link,
+}
+
+#[async_trait]
+impl BaseListenerAsync for StdinListener {
+    async fn start(&self) -> std::io::Result<()> {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+        while let Some(line) = lines.next_line().await? {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<serde_json::Value>(&line) {
+                Ok(serde_json::Value::Object(mut map)) => {
+                    meta::strip_reserved(&mut map);
+                    let _ = (self.handler)(Context::from(map)).await;
+                }
+                _ => tracing::warn!("skipping non-object stdin line"),
+            }
+        }
+        Ok(())
+    }
+    fn name(&self) -> &'static str {
+        "stdin"
+    }
+}
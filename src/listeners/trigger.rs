@@ -0,0 +1,176 @@
+//! Debounce and throttle for listener handlers.
+//!
+//! Wrap a listener's handler so a burst of trigger events (file saves, config pushes,
+//! MQTT retained messages) collapses into a single run. Events are combined with a merge
+//! function (by default the latest event wins). The wrapped handler returns immediately
+//! with its input; the collapsed run happens in the background on the executor, so
+//! listeners that await each event still see the whole burst.
+//!
+//! - [`Debounce`]: run once the events have been quiet for `quiet`.
+//! - [`Throttle`]: run at most once per `interval`: the first event runs right away, later
+//!   events in the interval are merged into one run when it ends.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::Link;
+//! use modulink_rs::listeners::trigger::Debounce;
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let reload: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx }));
+//! let handler = Debounce::new(Duration::from_millis(200)).wrap(reload);
+//! ```
+
+use crate::links::LinkGeneric;
+use crate::runtime::{default_executor, ExecutorObj};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+type MergeFn<T> = Arc<dyn Fn(T, T) -> T + Send + Sync>;
+
+struct State<T> {
+    pending: Option<T>,
+    // Debounce: bumped by every event, so only the last event's timer fires
+    generation: u64,
+    // Throttle: whether an interval is open
+    open: bool,
+}
+
+struct Shared<T> {
+    handler: LinkGeneric<T>,
+    merge: MergeFn<T>,
+    executor: ExecutorObj,
+    state: Mutex<State<T>>,
+}
+
+impl<T: Send + 'static> Shared<T> {
+    fn new(handler: LinkGeneric<T>, merge: MergeFn<T>, executor: ExecutorObj) -> Arc<Self> {
+        Arc::new(Shared { handler, merge, executor, state: Mutex::new(State { pending: None, generation: 0, open: false }) })
+    }
+    fn add(&self, state: &mut State<T>, ctx: T) {
+        state.pending = Some(match state.pending.take() {
+            Some(earlier) => (self.merge)(earlier, ctx),
+            None => ctx,
+        });
+    }
+    fn run(&self, ctx: T) {
+        let run = (self.handler)(ctx);
+        self.executor.spawn(Box::pin(async move {
+            run.await;
+        }));
+    }
+
+    fn debounce(self: &Arc<Self>, ctx: T, quiet: Duration) {
+        let mut state = self.state.lock().unwrap();
+        self.add(&mut state, ctx);
+        state.generation += 1;
+        let (generation, this, timer) = (state.generation, self.clone(), self.executor.sleep(quiet));
+        self.executor.spawn(Box::pin(async move {
+            timer.await;
+            let ready = {
+                let mut state = this.state.lock().unwrap();
+                if state.generation == generation { state.pending.take() } else { None }
+            };
+            if let Some(ctx) = ready {
+                this.run(ctx);
+            }
+        }));
+    }
+
+    fn throttle(self: &Arc<Self>, ctx: T, interval: Duration) {
+        let mut state = self.state.lock().unwrap();
+        if state.open {
+            self.add(&mut state, ctx);
+            return;
+        }
+        state.open = true;
+        drop(state);
+        self.run(ctx);
+        self.close_after(interval);
+    }
+
+    // End the throttle interval after `interval`, running whatever arrived during it.
+    fn close_after(self: &Arc<Self>, interval: Duration) {
+        let (this, timer) = (self.clone(), self.executor.sleep(interval));
+        self.executor.spawn(Box::pin(async move {
+            timer.await;
+            let trailing = {
+                let mut state = this.state.lock().unwrap();
+                let trailing = state.pending.take();
+                state.open = trailing.is_some();
+                trailing
+            };
+            if let Some(ctx) = trailing {
+                this.run(ctx);
+                this.close_after(interval);
+            }
+        }));
+    }
+}
+
+fn latest<T>() -> MergeFn<T> {
+    Arc::new(|_earlier: T, latest: T| latest)
+}
+
+/// Run the handler once events have been quiet for `quiet`.
+pub struct Debounce<T> {
+    quiet: Duration,
+    merge: MergeFn<T>,
+    executor: ExecutorObj,
+}
+
+impl<T: Clone + Send + 'static> Debounce<T> {
+    pub fn new(quiet: Duration) -> Self {
+        Debounce { quiet, merge: latest(), executor: default_executor() }
+    }
+    /// Combine an earlier pending event with a newer one (default: keep the newer).
+    pub fn merge_with<F: Fn(T, T) -> T + Send + Sync + 'static>(mut self, merge: F) -> Self {
+        self.merge = Arc::new(merge);
+        self
+    }
+    /// Executor running timers and the collapsed runs (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+    pub fn wrap(&self, handler: LinkGeneric<T>) -> LinkGeneric<T> {
+        let shared = Shared::new(handler, self.merge.clone(), self.executor.clone());
+        let quiet = self.quiet;
+        Arc::new(move |ctx: T| {
+            shared.debounce(ctx.clone(), quiet);
+            Box::pin(async move { ctx })
+        })
+    }
+}
+
+/// Run the handler at most once per `interval`.
+pub struct Throttle<T> {
+    interval: Duration,
+    merge: MergeFn<T>,
+    executor: ExecutorObj,
+}
+
+impl<T: Clone + Send + 'static> Throttle<T> {
+    pub fn new(interval: Duration) -> Self {
+        Throttle { interval, merge: latest(), executor: default_executor() }
+    }
+    /// Combine an earlier pending event with a newer one (default: keep the newer).
+    pub fn merge_with<F: Fn(T, T) -> T + Send + Sync + 'static>(mut self, merge: F) -> Self {
+        self.merge = Arc::new(merge);
+        self
+    }
+    /// Executor running timers and the collapsed runs (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+    pub fn wrap(&self, handler: LinkGeneric<T>) -> LinkGeneric<T> {
+        let shared = Shared::new(handler, self.merge.clone(), self.executor.clone());
+        let interval = self.interval;
+        Arc::new(move |ctx: T| {
+            shared.throttle(ctx.clone(), interval);
+            Box::pin(async move { ctx })
+        })
+    }
+}
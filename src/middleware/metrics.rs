@@ -0,0 +1,162 @@
+//! Run metrics: latency and failure counts per chain, sliced by labels taken from the context.
+//!
+//! Each label is extracted from the final context of a run (e.g. tenant, or `_http.path`
+//! set by the HTTP listener) or read from the run's annotations (`ctx_tools::annotate`). To keep the number of series bounded, each label accepts at
+//! most `max_values` distinct values; later values are recorded as [`OTHER`]. A run without
+//! a value for a label is recorded as [`UNKNOWN`].
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::{meta, Context};
+//! use modulink_rs::middleware::MetricsMiddleware;
+//! use std::sync::Arc;
+//!
+//! let metrics = Arc::new(
+//!     MetricsMiddleware::new("refunds")
+//!         .label_key("tenant", meta::TENANT)
+//!         .label_key("route", "_http.path")
+//!         .with_max_values(50),
+//! );
+//! let mut chain = Chain::new();
+//! chain.use_middleware(metrics.clone());
+//! # futures::executor::block_on(chain.run(Context::new().with_tenant("acme")));
+//! let series = metrics.snapshot();
+//! assert_eq!(series[0].labels["tenant"], "acme");
+//! assert_eq!(series[0].labels["route"], "unknown");
+//! ```
+
+use super::Middleware;
+use crate::chains::{RunReport, RunStatus};
+use crate::context::Context;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Label value recorded once a label has reached its distinct-value cap.
+pub const OTHER: &str = "other";
+/// Label value recorded when the extractor finds nothing.
+pub const UNKNOWN: &str = "unknown";
+
+type Extractor<T> = Arc<dyn Fn(&T, &RunReport) -> Option<String> + Send + Sync>;
+
+/// Aggregated metrics for one chain and combination of label values.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricSeries {
+    /// Always contains `chain`, plus one entry per declared label.
+    pub labels: BTreeMap<String, String>,
+    pub runs: u64,
+    pub failures: u64,
+    pub total_duration: Duration,
+    pub max_duration: Duration,
+}
+
+#[derive(Default)]
+struct State {
+    seen: HashMap<String, HashSet<String>>,
+    series: BTreeMap<Vec<(String, String)>, MetricSeries>,
+}
+
+pub struct MetricsMiddleware<T> {
+    chain: String,
+    labels: Vec<(String, Extractor<T>)>,
+    max_values: usize,
+    state: Mutex<State>,
+}
+
+impl<T> MetricsMiddleware<T> {
+    pub fn new(chain: impl Into<String>) -> Self {
+        MetricsMiddleware { chain: chain.into(), labels: Vec::new(), max_values: 100, state: Mutex::default() }
+    }
+    /// Add a label whose value is computed from the final context of each run.
+    pub fn label<F>(mut self, name: impl Into<String>, extract: F) -> Self
+    where
+        F: Fn(&T) -> Option<String> + Send + Sync + 'static,
+    {
+        self.labels.push((name.into(), Arc::new(move |ctx: &T, _: &RunReport| extract(ctx))));
+        self
+    }
+    /// Add a label read from the run annotation `key`.
+    pub fn label_annotation(mut self, name: impl Into<String>, key: impl Into<String>) -> Self {
+        let key = key.into();
+        self.labels.push((name.into(), Arc::new(move |_: &T, report: &RunReport| report.annotations.get(&key).and_then(label_value))));
+        self
+    }
+    /// Distinct values accepted per label before the rest are recorded as [`OTHER`] (default 100).
+    pub fn with_max_values(mut self, max: usize) -> Self {
+        self.max_values = max;
+        self
+    }
+
+    /// Current value of every series, ordered by labels.
+    pub fn snapshot(&self) -> Vec<MetricSeries> {
+        self.state.lock().unwrap().series.values().cloned().collect()
+    }
+
+    fn record(&self, ctx: &T, report: &RunReport) {
+        let mut state = self.state.lock().unwrap();
+        let mut key = vec![("chain".to_string(), self.chain.clone())];
+        for (name, extract) in &self.labels {
+            let value = match extract(ctx, report) {
+                Some(value) => {
+                    let seen = state.seen.entry(name.clone()).or_default();
+                    if seen.contains(&value) || seen.len() < self.max_values {
+                        seen.insert(value.clone());
+                        value
+                    } else {
+                        OTHER.to_string()
+                    }
+                }
+                None => UNKNOWN.to_string(),
+            };
+            key.push((name.clone(), value));
+        }
+        let series = state.series.entry(key.clone()).or_insert_with(|| MetricSeries {
+            labels: key.into_iter().collect(),
+            runs: 0,
+            failures: 0,
+            total_duration: Duration::ZERO,
+            max_duration: Duration::ZERO,
+        });
+        series.runs += 1;
+        if matches!(report.status, RunStatus::Failed(_)) {
+            series.failures += 1;
+        }
+        series.total_duration += report.duration;
+        series.max_duration = series.max_duration.max(report.duration);
+    }
+}
+
+impl MetricsMiddleware<Context> {
+    /// Add a label read from a context key; dots descend into objects (`_http.path`).
+    pub fn label_key(self, name: impl Into<String>, path: impl Into<String>) -> Self {
+        let path = path.into();
+        self.label(name, move |ctx: &Context| {
+            let mut parts = path.split('.');
+            let mut value = ctx.0.get(parts.next()?)?;
+            for part in parts {
+                value = value.get(part)?;
+            }
+            label_value(value)
+        })
+    }
+}
+
+fn label_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Null => None,
+        other => Some(other.to_string()),
+    }
+}
+
+impl<T: Send + Sync + 'static> Middleware<T> for MetricsMiddleware<T> {
+    fn on_run_end<'a>(&'a self, ctx: &'a T, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        self.record(ctx, report);
+        Box::pin(async {})
+    }
+}
@@ -1,12 +1,51 @@
 //! Middleware trait for modulink-rust
-//! Trait with async before/after hooks.
+//! Trait with async before/after hooks, plus run-start (may rewrite the context) and
+//! run-end (sees the final status) hooks.
 
+pub mod metrics;
+pub mod slo;
+pub use metrics::{MetricSeries, MetricsMiddleware};
+pub use slo::{AlertHandler, Slo, SloMiddleware};
+
+use crate::chains::scope::FailureSlot;
+use crate::chains::{Initialize, RunError, RunReport, RunScope, Shutdown};
 use crate::context::Context;
+use crate::runtime::panic_message;
+use futures::FutureExt;
 use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::Arc;
 
 pub trait Middleware<T>: Send + Sync {
+    /// Name shown in generated documentation; defaults to the type name.
+    fn name(&self) -> &str {
+        let full = std::any::type_name::<Self>();
+        full.rsplit("::").next().unwrap_or(full)
+    }
+    /// Middleware that implements [`Initialize`] returns itself here so
+    /// `ChainGeneric::warm_up` initializes it.
+    fn initializer(&self) -> Option<&dyn Initialize> {
+        None
+    }
+    /// Middleware that implements [`Shutdown`] returns itself here so
+    /// `ChainGeneric::shutdown` runs it.
+    fn shutdown_hook(&self) -> Option<&dyn Shutdown> {
+        None
+    }
+    /// Called once per run, before the first link. Unlike `before`/`after` it owns the
+    /// context and may rewrite it (e.g. replace a raw token with validated claims).
+    fn on_run_start<'a>(&'a self, ctx: T) -> Pin<Box<dyn Future<Output = T> + Send + 'a>>
+    where
+        T: Send + 'a,
+    {
+        Box::pin(async move { ctx })
+    }
+    /// Called once per run after it finished (children included), with its report.
+    fn on_run_end<'a>(&'a self, ctx: &'a T, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        let _ = (ctx, report);
+        Box::pin(async {})
+    }
     fn before<'a>(&'a self, ctx: &'a T) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
         Box::pin(async move { let _ = ctx; })
     }
@@ -17,6 +56,171 @@ pub trait Middleware<T>: Send + Sync {
 
 pub type MiddlewareObj = Arc<dyn Middleware<Context>>;
 
+/// How a chain awaits the hooks of its middleware (see `ChainGeneric::set_middleware_execution`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MiddlewareExecution {
+    /// One after another, in the order they were attached (the default).
+    #[default]
+    Sequential,
+    /// The `before`, `after`, and `on_run_end` hooks of all middleware at once, waiting
+    /// for all of them before moving on. For independent middleware (metrics, logging,
+    /// audit) whose hooks wait on I/O. `on_run_start` hooks still run in order, since each
+    /// may rewrite the context the next one sees.
+    Concurrent,
+}
+
+/// Middleware that only runs its hooks when `predicate` holds for the context the hook sees
+/// (see `ChainGeneric::use_middleware_if`).
+pub struct ConditionalMiddleware<T> {
+    inner: Arc<dyn Middleware<T>>,
+    predicate: Arc<dyn Fn(&T) -> bool + Send + Sync>,
+}
+
+impl<T> ConditionalMiddleware<T> {
+    pub fn new<F>(inner: Arc<dyn Middleware<T>>, predicate: F) -> Self
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        ConditionalMiddleware { inner, predicate: Arc::new(predicate) }
+    }
+}
+
+impl<T: Send + Sync> Middleware<T> for ConditionalMiddleware<T> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+    fn initializer(&self) -> Option<&dyn Initialize> {
+        self.inner.initializer()
+    }
+    fn shutdown_hook(&self) -> Option<&dyn Shutdown> {
+        self.inner.shutdown_hook()
+    }
+    fn on_run_start<'a>(&'a self, ctx: T) -> Pin<Box<dyn Future<Output = T> + Send + 'a>>
+    where
+        T: Send + 'a,
+    {
+        if (self.predicate)(&ctx) {
+            self.inner.on_run_start(ctx)
+        } else {
+            Box::pin(async move { ctx })
+        }
+    }
+    fn on_run_end<'a>(&'a self, ctx: &'a T, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        if (self.predicate)(ctx) {
+            self.inner.on_run_end(ctx, report)
+        } else {
+            Box::pin(async {})
+        }
+    }
+    fn before<'a>(&'a self, ctx: &'a T) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        if (self.predicate)(ctx) {
+            self.inner.before(ctx)
+        } else {
+            Box::pin(async {})
+        }
+    }
+    fn after<'a>(&'a self, ctx: &'a T) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        if (self.predicate)(ctx) {
+            self.inner.after(ctx)
+        } else {
+            Box::pin(async {})
+        }
+    }
+}
+
+/// What happens when a hook of a contained middleware panics or fails the run (see
+/// `ChainGeneric::use_contained_middleware`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Containment {
+    /// Log the failure, record it as a run warning, and carry on as if the hook had done
+    /// nothing, so an observability bug cannot break the pipeline.
+    LogAndContinue,
+    /// Fail the run with the failure; a panic becomes a `Panicked` run error instead of
+    /// unwinding through the chain.
+    FailRun,
+}
+
+/// Middleware whose hook failures are contained according to a [`Containment`] policy.
+///
+/// A failure is a panic in a hook, or a hook failing the run with `ctx_tools::fail_run`.
+/// `on_run_end` runs after the run's status is settled, so its failures are only logged.
+pub struct ContainedMiddleware<T> {
+    inner: Arc<dyn Middleware<T>>,
+    containment: Containment,
+}
+
+impl<T> ContainedMiddleware<T> {
+    pub fn new(inner: Arc<dyn Middleware<T>>, containment: Containment) -> Self {
+        ContainedMiddleware { inner, containment }
+    }
+
+    // Await `hook`; `None` if it panicked. `scope` is the run it may fail. Failures the hook
+    // reports go to a slot of its own, so a failure of anything running alongside it (with
+    // `MiddlewareExecution::Concurrent`) is never taken for the hook's.
+    async fn contain<R>(&self, hook: &str, scope: Option<Arc<RunScope>>, fut: impl Future<Output = R>) -> Option<R> {
+        let slot = scope.clone().map(FailureSlot::new);
+        let fut = AssertUnwindSafe(fut).catch_unwind();
+        let outcome = match &slot {
+            Some(slot) => slot.enter(fut).await,
+            None => fut.await,
+        };
+        let (result, err) = match outcome {
+            Ok(result) => (Some(result), None),
+            Err(payload) => (None, Some(RunError::panicked(panic_message(payload)))),
+        };
+        let Some(err) = err.or_else(|| slot.and_then(|slot| slot.take())) else { return result };
+        match (self.containment, &scope) {
+            (Containment::FailRun, Some(scope)) => scope.fail(err),
+            _ => {
+                tracing::warn!(middleware = self.inner.name(), hook, error = %err, "middleware failure contained");
+                if let Some(scope) = &scope {
+                    scope.warn(format!("middleware {} failed in {}: {}", self.inner.name(), hook, err));
+                }
+            }
+        }
+        result
+    }
+}
+
+impl<T: Clone + Send + Sync> Middleware<T> for ContainedMiddleware<T> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+    fn initializer(&self) -> Option<&dyn Initialize> {
+        self.inner.initializer()
+    }
+    fn shutdown_hook(&self) -> Option<&dyn Shutdown> {
+        self.inner.shutdown_hook()
+    }
+    fn on_run_start<'a>(&'a self, ctx: T) -> Pin<Box<dyn Future<Output = T> + Send + 'a>>
+    where
+        T: Send + 'a,
+    {
+        Box::pin(async move {
+            let original = ctx.clone();
+            let started = self.contain("on_run_start", RunScope::current(), async move { self.inner.on_run_start(ctx).await });
+            started.await.unwrap_or(original)
+        })
+    }
+    fn on_run_end<'a>(&'a self, ctx: &'a T, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            self.contain("on_run_end", None, async move { self.inner.on_run_end(ctx, report).await }).await;
+        })
+    }
+    fn before<'a>(&'a self, ctx: &'a T) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            self.contain("before", RunScope::current(), async move { self.inner.before(ctx).await }).await;
+        })
+    }
+    fn after<'a>(&'a self, ctx: &'a T) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            self.contain("after", RunScope::current(), async move { self.inner.after(ctx).await }).await;
+        })
+    }
+}
+
 // Built-in Logging middleware
 pub struct LoggingMiddleware;
 
@@ -0,0 +1,277 @@
+//! Link-level SLOs: latency and error-rate objectives per named link, with alerts.
+//!
+//! [`SloMiddleware`] reads each finished run's report: every executed link
+//! (`RunReport::steps`) is a latency sample for that link, and a failed run counts as an
+//! error of the link its failure path ends at. Samples are kept over a sliding window per
+//! [`Slo`]; once a window holds `min_samples`, the objective is evaluated after every run.
+//! The [`AlertHandler`] is called when an objective starts being breached
+//! ([`AlertState::Firing`]) and again when it recovers ([`AlertState::Resolved`]).
+//!
+//! Links are matched by `LinkSpec::name`. [`LogAlerts`] logs alerts with `tracing`;
+//! [`SinkAlerts`] delivers them to any sink, e.g. an `HttpSink` posting to a webhook.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::links::LinkSpec;
+//! use modulink_rs::middleware::slo::{LogAlerts, Slo, SloMiddleware};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let slos = Arc::new(
+//!     SloMiddleware::new(Arc::new(LogAlerts)).slo(
+//!         Slo::for_link("charge")
+//!             .latency(0.95, Duration::from_millis(200))
+//!             .error_rate(0.01)
+//!             .window(Duration::from_secs(300)),
+//!     ),
+//! );
+//! let mut chain = Chain::new();
+//! chain.add_link_with(Arc::new(|ctx: Context| Box::pin(async move { ctx })), LinkSpec::new().name("charge"));
+//! chain.use_middleware(slos.clone());
+//! # futures::executor::block_on(chain.run(Context::new()));
+//! assert_eq!(slos.status()[0].samples, 1);
+//! ```
+
+use super::Middleware;
+use crate::chains::{RunReport, RunStatus};
+use crate::sinks::SinkObj;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Objectives for one link; see the [module docs](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slo {
+    pub link: String,
+    /// (quantile, threshold): e.g. `(0.95, 200ms)` for "p95 under 200ms".
+    pub latency: Option<(f64, Duration)>,
+    /// Highest acceptable share of runs failing at this link.
+    pub max_error_rate: Option<f64>,
+    pub window: Duration,
+    /// Samples needed in the window before the objectives are evaluated.
+    pub min_samples: usize,
+}
+
+impl Slo {
+    /// An SLO for the link named `link`, over a 5 minute window of at least 20 samples.
+    pub fn for_link(link: impl Into<String>) -> Self {
+        Slo { link: link.into(), latency: None, max_error_rate: None, window: Duration::from_secs(300), min_samples: 20 }
+    }
+    pub fn latency(mut self, quantile: f64, threshold: Duration) -> Self {
+        self.latency = Some((quantile, threshold));
+        self
+    }
+    pub fn error_rate(mut self, max: f64) -> Self {
+        self.max_error_rate = Some(max);
+        self
+    }
+    pub fn window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+    pub fn min_samples(mut self, min: usize) -> Self {
+        self.min_samples = min;
+        self
+    }
+}
+
+/// Which objective an alert is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Objective {
+    Latency,
+    ErrorRate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AlertState {
+    Firing,
+    Resolved,
+}
+
+/// An objective of a link starting or ceasing to be breached.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Alert {
+    pub link: String,
+    pub objective: Objective,
+    pub state: AlertState,
+    /// The measured value: the latency quantile in milliseconds, or the error rate.
+    pub observed: f64,
+    /// The objective's threshold, in the same unit as `observed`.
+    pub threshold: f64,
+    /// Samples in the window the value was measured over.
+    pub samples: usize,
+}
+
+/// Receives SLO alerts.
+#[async_trait]
+pub trait AlertHandler: Send + Sync {
+    async fn alert(&self, alert: &Alert);
+}
+
+/// Logs firing alerts as warnings and resolved ones as info.
+pub struct LogAlerts;
+
+#[async_trait]
+impl AlertHandler for LogAlerts {
+    async fn alert(&self, alert: &Alert) {
+        match alert.state {
+            AlertState::Firing => tracing::warn!(link = %alert.link, objective = ?alert.objective, observed = alert.observed, threshold = alert.threshold, "SLO breached"),
+            AlertState::Resolved => tracing::info!(link = %alert.link, objective = ?alert.objective, observed = alert.observed, threshold = alert.threshold, "SLO recovered"),
+        }
+    }
+}
+
+/// Delivers alerts to a sink (e.g. `HttpSink` for a webhook); delivery failures are logged.
+pub struct SinkAlerts {
+    sink: SinkObj<Alert>,
+}
+
+impl SinkAlerts {
+    pub fn new(sink: SinkObj<Alert>) -> Self {
+        SinkAlerts { sink }
+    }
+}
+
+#[async_trait]
+impl AlertHandler for SinkAlerts {
+    async fn alert(&self, alert: &Alert) {
+        if let Err(e) = self.sink.deliver(alert).await {
+            tracing::warn!(sink = self.sink.name(), error = %e, "delivering SLO alert failed");
+        }
+    }
+}
+
+/// Current measurements of one SLO, as returned by [`SloMiddleware::status`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SloStatus {
+    pub link: String,
+    pub samples: usize,
+    /// Latency at the objective's quantile, in milliseconds (`None` without a latency objective).
+    pub latency_ms: Option<f64>,
+    pub error_rate: f64,
+    pub latency_breached: bool,
+    pub error_rate_breached: bool,
+}
+
+struct Tracked {
+    slo: Slo,
+    // (when, latency, failed)
+    samples: VecDeque<(Instant, Duration, bool)>,
+    latency_breached: bool,
+    error_rate_breached: bool,
+}
+
+impl Tracked {
+    fn measure(&self) -> SloStatus {
+        let n = self.samples.len();
+        let latency_ms = self.slo.latency.filter(|_| n > 0).map(|(quantile, _)| {
+            let mut latencies: Vec<Duration> = self.samples.iter().map(|(_, latency, _)| *latency).collect();
+            latencies.sort_unstable();
+            let rank = ((n as f64 * quantile).ceil() as usize).clamp(1, n);
+            latencies[rank - 1].as_secs_f64() * 1000.0
+        });
+        let failed = self.samples.iter().filter(|(_, _, failed)| *failed).count();
+        SloStatus {
+            link: self.slo.link.clone(),
+            samples: n,
+            latency_ms,
+            error_rate: if n == 0 { 0.0 } else { failed as f64 / n as f64 },
+            latency_breached: self.latency_breached,
+            error_rate_breached: self.error_rate_breached,
+        }
+    }
+
+    // Record the samples of one run and return the alerts caused by state changes.
+    fn observe(&mut self, now: Instant, samples: impl Iterator<Item = (Duration, bool)>) -> Vec<Alert> {
+        self.samples.extend(samples.map(|(latency, failed)| (now, latency, failed)));
+        while self.samples.front().is_some_and(|(at, _, _)| now.duration_since(*at) > self.slo.window) {
+            self.samples.pop_front();
+        }
+        if self.samples.len() < self.slo.min_samples {
+            return Vec::new();
+        }
+        let status = self.measure();
+        let mut alerts = Vec::new();
+        if let (Some((_, threshold)), Some(observed)) = (self.slo.latency, status.latency_ms) {
+            let threshold = threshold.as_secs_f64() * 1000.0;
+            let breached = observed > threshold;
+            if breached != self.latency_breached {
+                self.latency_breached = breached;
+                alerts.push(self.alert(Objective::Latency, breached, observed, threshold));
+            }
+        }
+        if let Some(threshold) = self.slo.max_error_rate {
+            let breached = status.error_rate > threshold;
+            if breached != self.error_rate_breached {
+                self.error_rate_breached = breached;
+                alerts.push(self.alert(Objective::ErrorRate, breached, status.error_rate, threshold));
+            }
+        }
+        alerts
+    }
+
+    fn alert(&self, objective: Objective, breached: bool, observed: f64, threshold: f64) -> Alert {
+        let state = if breached { AlertState::Firing } else { AlertState::Resolved };
+        Alert { link: self.slo.link.clone(), objective, state, observed, threshold, samples: self.samples.len() }
+    }
+}
+
+/// Middleware tracking [`Slo`]s over the runs of the chains it is attached to.
+pub struct SloMiddleware {
+    handler: Arc<dyn AlertHandler>,
+    tracked: Mutex<Vec<Tracked>>,
+}
+
+impl SloMiddleware {
+    pub fn new(handler: Arc<dyn AlertHandler>) -> Self {
+        SloMiddleware { handler, tracked: Mutex::default() }
+    }
+    pub fn slo(self, slo: Slo) -> Self {
+        self.tracked.lock().unwrap().push(Tracked { slo, samples: VecDeque::new(), latency_breached: false, error_rate_breached: false });
+        self
+    }
+    /// Current measurements of every SLO, in declaration order.
+    pub fn status(&self) -> Vec<SloStatus> {
+        self.tracked.lock().unwrap().iter().map(Tracked::measure).collect()
+    }
+
+    fn record(&self, report: &RunReport) -> Vec<Alert> {
+        let failed_link = match &report.status {
+            RunStatus::Failed(err) => err.path.last().and_then(|step| step.name.clone()),
+            _ => None,
+        };
+        let now = Instant::now();
+        let mut tracked = self.tracked.lock().unwrap();
+        let mut alerts = Vec::new();
+        for slo in tracked.iter_mut() {
+            let steps: Vec<_> = report.steps.iter().filter(|step| step.name.as_deref() == Some(slo.slo.link.as_str())).collect();
+            if steps.is_empty() {
+                continue;
+            }
+            // The last execution of the failing link is the one that failed
+            let last = steps.len() - 1;
+            let failed = failed_link.as_deref() == Some(slo.slo.link.as_str());
+            let samples = steps.iter().enumerate().map(|(i, step)| (step.duration, failed && i == last));
+            alerts.extend(slo.observe(now, samples));
+        }
+        alerts
+    }
+}
+
+impl<T: Send + Sync + 'static> Middleware<T> for SloMiddleware {
+    fn on_run_end<'a>(&'a self, ctx: &'a T, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        let _ = ctx;
+        let alerts = self.record(report);
+        Box::pin(async move {
+            for alert in &alerts {
+                self.handler.alert(alert).await;
+            }
+        })
+    }
+}
@@ -0,0 +1,189 @@
+//! Pipes: listener → chain → sink, wired from URI-style specs.
+//!
+//! Specs are `scheme:target` strings, as used by `modulink-cli pipe`:
+//!
+//! | Spec                    | `--from` (listener)        | `--to` (sink)               |
+//! |-------------------------|----------------------------|-----------------------------|
+//! | `stdin` / `stdout`      | JSON lines on stdin        | JSON lines on stdout        |
+//! | `http:<addr or url>`    | `HttpListener` on `<addr>` | POST to `<url>` (`http-sink`) |
+//! | `kafka:<topic>`         | `KafkaListener` on topic   | `KafkaSink` to topic        |
+//! | `file:<path>`           | —                          | `FileSink` appending JSON lines |
+//!
+//! Kafka specs need a client supplied through [`Connectors`].
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::pipe::{SinkSpec, SourceSpec};
+//!
+//! let from: SourceSpec = "kafka:orders".parse().unwrap();
+//! let to: SinkSpec = "http:https://example.com/ingest".parse().unwrap();
+//! assert_eq!(from, SourceSpec::Kafka("orders".to_string()));
+//! assert_eq!(to, SinkSpec::Http("https://example.com/ingest".to_string()));
+//! ```
+
+use crate::chains::Chain;
+use crate::context::Context;
+use crate::links::Link;
+use crate::listeners::{BaseListenerAsync, KafkaConsumer, KafkaListener};
+use crate::registry;
+use crate::sinks::{FileSink, KafkaProducer, KafkaSink, SinkObj, StdoutSink};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Errors raised while building or running a pipe.
+#[derive(Debug)]
+pub enum PipeError {
+    /// The spec string could not be parsed.
+    InvalidSpec(String),
+    /// No chain is registered under this name.
+    UnknownChain(String),
+    /// The spec needs a client that was not supplied in [`Connectors`].
+    MissingConnector(&'static str),
+    /// The spec is valid but its backend is not compiled in (feature disabled).
+    Unsupported(String),
+    /// The listener failed while running.
+    Io(std::io::Error),
+}
+
+impl std::fmt::Display for PipeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PipeError::InvalidSpec(spec) => write!(f, "invalid spec: {}", spec),
+            PipeError::UnknownChain(name) => write!(f, "no chain registered as '{}'", name),
+            PipeError::MissingConnector(what) => write!(f, "no {} configured", what),
+            PipeError::Unsupported(spec) => write!(f, "unsupported in this build: {}", spec),
+            PipeError::Io(e) => write!(f, "listener failed: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for PipeError {}
+
+impl From<std::io::Error> for PipeError {
+    fn from(e: std::io::Error) -> Self {
+        PipeError::Io(e)
+    }
+}
+
+/// Where runs come from (`--from`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceSpec {
+    Stdin,
+    Http(String),
+    Kafka(String),
+}
+
+/// Where results go (`--to`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SinkSpec {
+    Stdout,
+    File(PathBuf),
+    Http(String),
+    Kafka(String),
+}
+
+fn split_spec(spec: &str) -> (&str, &str) {
+    match spec.split_once(':') {
+        Some((scheme, target)) => (scheme, target),
+        None => (spec, ""),
+    }
+}
+
+fn non_empty(spec: &str, target: &str) -> Result<String, PipeError> {
+    if target.is_empty() {
+        Err(PipeError::InvalidSpec(spec.to_string()))
+    } else {
+        Ok(target.to_string())
+    }
+}
+
+impl FromStr for SourceSpec {
+    type Err = PipeError;
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        match split_spec(spec) {
+            ("stdin", "") => Ok(SourceSpec::Stdin),
+            ("http", target) => non_empty(spec, target).map(SourceSpec::Http),
+            ("kafka", target) => non_empty(spec, target).map(SourceSpec::Kafka),
+            _ => Err(PipeError::InvalidSpec(spec.to_string())),
+        }
+    }
+}
+
+impl FromStr for SinkSpec {
+    type Err = PipeError;
+    fn from_str(spec: &str) -> Result<Self, Self::Err> {
+        match split_spec(spec) {
+            ("stdout", "") => Ok(SinkSpec::Stdout),
+            ("file", target) => non_empty(spec, target).map(|p| SinkSpec::File(PathBuf::from(p))),
+            ("http", target) => non_empty(spec, target).map(SinkSpec::Http),
+            ("kafka", target) => non_empty(spec, target).map(SinkSpec::Kafka),
+            _ => Err(PipeError::InvalidSpec(spec.to_string())),
+        }
+    }
+}
+
+/// Clients for backends the library does not bundle.
+#[derive(Clone, Default)]
+pub struct Connectors {
+    pub kafka_producer: Option<Arc<dyn KafkaProducer>>,
+    pub kafka_consumer: Option<Arc<dyn KafkaConsumer>>,
+}
+
+pub fn build_sink(spec: &SinkSpec, connectors: &Connectors) -> Result<SinkObj, PipeError> {
+    match spec {
+        SinkSpec::Stdout => Ok(Arc::new(StdoutSink)),
+        SinkSpec::File(path) => Ok(Arc::new(FileSink::new(path))),
+        #[cfg(feature = "http-sink")]
+        SinkSpec::Http(url) => Ok(Arc::new(crate::sinks::HttpSink::new(url.clone()))),
+        #[cfg(not(feature = "http-sink"))]
+        SinkSpec::Http(url) => Err(PipeError::Unsupported(format!("http:{} (enable the `http-sink` feature)", url))),
+        SinkSpec::Kafka(topic) => {
+            let producer = connectors.kafka_producer.clone().ok_or(PipeError::MissingConnector("Kafka producer"))?;
+            Ok(Arc::new(KafkaSink::new(producer, topic.clone())))
+        }
+    }
+}
+
+pub fn build_listener(spec: &SourceSpec, handler: Link, connectors: &Connectors) -> Result<Box<dyn BaseListenerAsync>, PipeError> {
+    match spec {
+        #[cfg(feature = "tokio")]
+        SourceSpec::Stdin => Ok(Box::new(crate::listeners::StdinListener { handler })),
+        #[cfg(not(feature = "tokio"))]
+        SourceSpec::Stdin => Err(PipeError::Unsupported("stdin (enable the `tokio` feature)".to_string())),
+        #[cfg(feature = "http")]
+        SourceSpec::Http(addr) => Ok(Box::new(crate::listeners::HttpListener::new(handler, addr.clone()))),
+        #[cfg(not(feature = "http"))]
+        SourceSpec::Http(addr) => Err(PipeError::Unsupported(format!("http:{} (enable the `http` feature)", addr))),
+        SourceSpec::Kafka(topic) => {
+            let consumer = connectors.kafka_consumer.clone().ok_or(PipeError::MissingConnector("Kafka consumer"))?;
+            Ok(Box::new(KafkaListener { consumer, topic: topic.clone(), handler }))
+        }
+    }
+}
+
+/// Handler that runs `chain` and delivers its result to `sink`.
+pub fn pipe_handler(chain: Arc<Chain>, sink: SinkObj) -> Link {
+    Arc::new(move |ctx: Context| {
+        let chain = chain.clone();
+        let sink = sink.clone();
+        Box::pin(async move {
+            let result = chain.run(ctx).await;
+            if let Err(e) = sink.deliver(&result).await {
+                tracing::warn!(sink = sink.name(), error = %e, "sink delivery failed");
+            }
+            result
+        })
+    })
+}
+
+/// Build the listener, chain, and sink described by the specs and run until the listener stops.
+pub async fn run_pipe(from: &str, chain: &str, to: &str, connectors: &Connectors) -> Result<(), PipeError> {
+    let source: SourceSpec = from.parse()?;
+    let sink_spec: SinkSpec = to.parse()?;
+    let chain = registry::get_chain(chain).ok_or_else(|| PipeError::UnknownChain(chain.to_string()))?;
+    let sink = build_sink(&sink_spec, connectors)?;
+    let listener = build_listener(&source, pipe_handler(chain, sink), connectors)?;
+    listener.start().await?;
+    Ok(())
+}
@@ -0,0 +1,183 @@
+//! Authorization policies for modulink-rust
+//! Decide from the run's auth claims (`context::meta::AUTH`) whether it may run a chain, or
+//! reach a sensitive link. Denials fail the run with a structured `RunError`: `Unauthorized`
+//! when there are no claims, `Forbidden` when the claims lack a grant.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::policy::{Policy, PolicyRequest, RolePolicy};
+//! use serde_json::json;
+//!
+//! let policy = RolePolicy::new()
+//!     .allow_chain("support", "refunds")
+//!     .allow_link("finance", "refunds", "issue_refund");
+//! let claims = json!({"sub": "ada", "roles": ["support"]});
+//! assert!(policy.authorize(&PolicyRequest::chain(Some(&claims), "refunds")).is_ok());
+//! assert!(policy.authorize(&PolicyRequest::link(Some(&claims), "refunds", "issue_refund")).is_err());
+//! ```
+
+use crate::chains::RunError;
+use crate::context::{meta, Context, ContextMutable};
+use crate::ctx_tools;
+use crate::links::Link;
+use crate::middleware::Middleware;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// What a run is trying to do, and who it runs as.
+#[derive(Debug, Clone, Copy)]
+pub struct PolicyRequest<'a> {
+    /// Validated auth claims of the run, if any.
+    pub claims: Option<&'a Value>,
+    pub chain: &'a str,
+    /// Set when checking a single sensitive link rather than the whole chain.
+    pub link: Option<&'a str>,
+}
+
+impl<'a> PolicyRequest<'a> {
+    pub fn chain(claims: Option<&'a Value>, chain: &'a str) -> Self {
+        PolicyRequest { claims, chain, link: None }
+    }
+    pub fn link(claims: Option<&'a Value>, chain: &'a str, link: &'a str) -> Self {
+        PolicyRequest { claims, chain, link: Some(link) }
+    }
+}
+
+/// Authorization hook; implement it to delegate to an external engine (OPA, Cedar, ...).
+pub trait Policy: Send + Sync {
+    fn authorize(&self, req: &PolicyRequest<'_>) -> Result<(), RunError>;
+}
+
+pub type PolicyObj = Arc<dyn Policy>;
+
+/// Role-based policy: roles come from a claim (`roles` by default, either an array or a
+/// space-separated string) and are granted chains or individual links. `"*"` matches any
+/// chain or link. A chain grant does not cover links guarded with [`guard_link`]; those
+/// need their own grant.
+#[derive(Debug, Clone)]
+pub struct RolePolicy {
+    roles_claim: String,
+    chains: HashMap<String, HashSet<String>>,
+    links: HashMap<String, HashSet<(String, String)>>,
+}
+
+impl Default for RolePolicy {
+    fn default() -> Self {
+        RolePolicy { roles_claim: "roles".to_string(), chains: HashMap::new(), links: HashMap::new() }
+    }
+}
+
+fn matches(grant: &str, name: &str) -> bool {
+    grant == "*" || grant == name
+}
+
+impl RolePolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Read roles from `claim` instead of `roles` (e.g. `groups`, `scope`).
+    pub fn with_roles_claim(mut self, claim: impl Into<String>) -> Self {
+        self.roles_claim = claim.into();
+        self
+    }
+    pub fn allow_chain(mut self, role: impl Into<String>, chain: impl Into<String>) -> Self {
+        self.chains.entry(role.into()).or_default().insert(chain.into());
+        self
+    }
+    pub fn allow_link(mut self, role: impl Into<String>, chain: impl Into<String>, link: impl Into<String>) -> Self {
+        self.links.entry(role.into()).or_default().insert((chain.into(), link.into()));
+        self
+    }
+
+    /// Roles carried by `claims`.
+    pub fn roles<'a>(&self, claims: &'a Value) -> Vec<&'a str> {
+        match claims.get(&self.roles_claim) {
+            Some(Value::Array(roles)) => roles.iter().filter_map(Value::as_str).collect(),
+            Some(Value::String(roles)) => roles.split_whitespace().collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn granted(&self, role: &str, req: &PolicyRequest<'_>) -> bool {
+        match req.link {
+            None => self.chains.get(role).is_some_and(|chains| chains.iter().any(|c| matches(c, req.chain))),
+            Some(link) => self.links.get(role).is_some_and(|links| {
+                links.iter().any(|(c, l)| matches(c, req.chain) && matches(l, link))
+            }),
+        }
+    }
+}
+
+impl Policy for RolePolicy {
+    fn authorize(&self, req: &PolicyRequest<'_>) -> Result<(), RunError> {
+        let claims = req.claims.ok_or_else(|| RunError::unauthorized("run has no auth claims"))?;
+        if self.roles(claims).into_iter().any(|role| self.granted(role, req)) {
+            return Ok(());
+        }
+        Err(match req.link {
+            None => RunError::forbidden(format!("not allowed to run chain '{}'", req.chain)),
+            Some(link) => RunError::forbidden(format!("not allowed to run link '{}' of chain '{}'", link, req.chain)),
+        })
+    }
+}
+
+fn enforce(policy: &dyn Policy, req: PolicyRequest<'_>) -> bool {
+    match policy.authorize(&req) {
+        Ok(()) => true,
+        Err(err) => {
+            let subject = req.claims.and_then(|c| c.get("sub")).and_then(Value::as_str).unwrap_or("");
+            tracing::warn!(target: "modulink::policy", chain = req.chain, link = req.link, subject, error = %err, "policy denied");
+            ctx_tools::fail_run(err);
+            false
+        }
+    }
+}
+
+/// Middleware that checks the policy for `chain` at run start and fails denied runs.
+pub struct PolicyMiddleware {
+    pub policy: PolicyObj,
+    pub chain: String,
+}
+
+impl PolicyMiddleware {
+    pub fn new(policy: PolicyObj, chain: impl Into<String>) -> Self {
+        PolicyMiddleware { policy, chain: chain.into() }
+    }
+}
+
+impl Middleware<Context> for PolicyMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: Context) -> Pin<Box<dyn Future<Output = Context> + Send + 'a>>
+    where
+        Context: 'a,
+    {
+        enforce(self.policy.as_ref(), PolicyRequest::chain(ctx.0.get(meta::AUTH), &self.chain));
+        Box::pin(async move { ctx })
+    }
+}
+
+impl Middleware<ContextMutable> for PolicyMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: ContextMutable) -> Pin<Box<dyn Future<Output = ContextMutable> + Send + 'a>>
+    where
+        ContextMutable: 'a,
+    {
+        enforce(self.policy.as_ref(), PolicyRequest::chain(ctx.0.get(meta::AUTH), &self.chain));
+        Box::pin(async move { ctx })
+    }
+}
+
+/// Wrap a sensitive link so it only runs when the policy grants `chain`/`link`; otherwise
+/// the context passes through untouched and the run fails.
+pub fn guard_link(policy: PolicyObj, chain: impl Into<String>, link_name: impl Into<String>, link: Link) -> Link {
+    let chain = chain.into();
+    let link_name = link_name.into();
+    Arc::new(move |ctx: Context| {
+        if enforce(policy.as_ref(), PolicyRequest::link(ctx.0.get(meta::AUTH), &chain, &link_name)) {
+            link(ctx)
+        } else {
+            Box::pin(async move { ctx })
+        }
+    })
+}
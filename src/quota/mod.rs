@@ -0,0 +1,283 @@
+//! Per-key quotas for modulink-rust
+//! Callers identified by an API key (by default the `sub` claim of `context::meta::AUTH`)
+//! get a daily budget of runs and of compute time (run wall time). [`QuotaMiddleware`]
+//! checks the budget before each run and fails runs over it with
+//! `ErrorKind::QuotaExceeded` (HTTP 429); what a run used is counted when it ends, against
+//! the key it was admitted under (kept in the run annotation [`QUOTA_KEY_ANNOTATION`]). Usage
+//! resets at midnight UTC, and can be inspected and reset early through [`Quotas`] or the
+//! admin API (`Admin::manage_quotas`).
+//!
+//! A [`Quotas`] is shared: give the same one to the middleware of several chains to have
+//! them draw on one budget per key. [`QuotaMiddleware::per_tenant`] counts keys separately
+//! for each tenant (see `tenant::TenantQuotas`).
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, RunStatus, ErrorKind};
+//! use modulink_rs::context::{meta, Context};
+//! use modulink_rs::quota::{QuotaLimits, QuotaMiddleware, Quotas};
+//! use serde_json::json;
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(1)));
+//! let mut chain = Chain::new();
+//! chain.use_middleware(Arc::new(QuotaMiddleware::new(quotas.clone())));
+//!
+//! let request = || Context::new().insert(meta::AUTH, json!({"sub": "key-1"}));
+//! assert_eq!(chain.run_with_report(request()).await.1.status, RunStatus::Completed);
+//! match chain.run_with_report(request()).await.1.status {
+//!     RunStatus::Failed(err) => assert_eq!(err.kind, ErrorKind::QuotaExceeded),
+//!     other => panic!("unexpected {:?}", other),
+//! }
+//! assert_eq!(quotas.usage("key-1").requests, 1);
+//! # });
+//! ```
+
+use crate::chains::{RunError, RunReport};
+use crate::context::{meta, Context, ContextMutable};
+use crate::ctx_tools;
+use crate::middleware::Middleware;
+use crate::tenant::TenantScope;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Daily budget of one key; `None` means unlimited.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaLimits {
+    pub requests_per_day: Option<u64>,
+    /// Run wall time, children included. A run is admitted while the key is under the
+    /// budget, so the last run of a day may take it over.
+    pub compute_per_day: Option<Duration>,
+}
+
+impl QuotaLimits {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn with_requests_per_day(mut self, requests: u64) -> Self {
+        self.requests_per_day = Some(requests);
+        self
+    }
+    pub fn with_compute_per_day(mut self, compute: Duration) -> Self {
+        self.compute_per_day = Some(compute);
+        self
+    }
+}
+
+/// What a key used on `day`, against its limits.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct QuotaUsage {
+    pub key: String,
+    /// Days since 1970-01-01 (UTC).
+    pub day: u64,
+    pub requests: u64,
+    pub compute: Duration,
+    pub limits: QuotaLimits,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Usage {
+    day: u64,
+    requests: u64,
+    compute: Duration,
+}
+
+fn day_of(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH).map(|d| d.as_secs() / 86_400).unwrap_or(0)
+}
+
+/// Limits and usage of every key.
+#[derive(Debug, Default)]
+pub struct Quotas {
+    default: QuotaLimits,
+    limits: RwLock<HashMap<String, QuotaLimits>>,
+    usage: Mutex<HashMap<String, Usage>>,
+}
+
+impl Quotas {
+    /// Quotas with `default` for every key not given limits of its own.
+    pub fn new(default: QuotaLimits) -> Self {
+        Quotas { default, ..Self::default() }
+    }
+    /// Give `key` its own limits (e.g. a paid plan).
+    pub fn set_limits(&self, key: impl Into<String>, limits: QuotaLimits) {
+        self.limits.write().unwrap().insert(key.into(), limits);
+    }
+    pub fn limits(&self, key: &str) -> QuotaLimits {
+        self.limits.read().unwrap().get(key).copied().unwrap_or(self.default)
+    }
+
+    /// Count a request by `key` now, if it is within its quota.
+    pub fn acquire(&self, key: &str) -> Result<(), RunError> {
+        self.acquire_at(key, SystemTime::now())
+    }
+    /// [`Self::acquire`] at `at`.
+    pub fn acquire_at(&self, key: &str, at: SystemTime) -> Result<(), RunError> {
+        let limits = self.limits(key);
+        let mut usage = self.usage.lock().unwrap();
+        let usage = current(&mut usage, key, day_of(at));
+        if limits.requests_per_day.is_some_and(|max| usage.requests >= max) {
+            return Err(RunError::quota_exceeded(format!("'{}' used its {} requests for today", key, usage.requests)));
+        }
+        if let Some(max) = limits.compute_per_day.filter(|max| usage.compute >= *max) {
+            return Err(RunError::quota_exceeded(format!("'{}' used its {:?} of compute for today", key, max)));
+        }
+        usage.requests += 1;
+        Ok(())
+    }
+    /// Count `compute` of a run by `key` that ended now.
+    pub fn record_compute(&self, key: &str, compute: Duration) {
+        self.record_compute_at(key, compute, SystemTime::now());
+    }
+    pub fn record_compute_at(&self, key: &str, compute: Duration, at: SystemTime) {
+        let mut usage = self.usage.lock().unwrap();
+        current(&mut usage, key, day_of(at)).compute += compute;
+    }
+
+    /// What `key` used today.
+    pub fn usage(&self, key: &str) -> QuotaUsage {
+        self.usage_at(key, SystemTime::now())
+    }
+    pub fn usage_at(&self, key: &str, at: SystemTime) -> QuotaUsage {
+        let day = day_of(at);
+        let usage = self.usage.lock().unwrap().get(key).copied().filter(|u| u.day == day).unwrap_or_default();
+        QuotaUsage { key: key.to_string(), day, requests: usage.requests, compute: usage.compute, limits: self.limits(key) }
+    }
+    /// Usage of every key that used anything today, sorted by key.
+    pub fn all_usage(&self) -> Vec<QuotaUsage> {
+        let now = SystemTime::now();
+        let mut keys: Vec<String> = {
+            let day = day_of(now);
+            self.usage.lock().unwrap().iter().filter(|(_, u)| u.day == day).map(|(k, _)| k.clone()).collect()
+        };
+        keys.sort();
+        keys.iter().map(|key| self.usage_at(key, now)).collect()
+    }
+    /// Forget what `key` used; `false` if nothing was recorded for it.
+    pub fn reset(&self, key: &str) -> bool {
+        self.usage.lock().unwrap().remove(key).is_some()
+    }
+    pub fn reset_all(&self) {
+        self.usage.lock().unwrap().clear();
+    }
+}
+
+// The usage of `key` on `day`, starting over when the stored usage is from another day.
+fn current<'a>(usage: &'a mut HashMap<String, Usage>, key: &str, day: u64) -> &'a mut Usage {
+    let usage = usage.entry(key.to_string()).or_default();
+    if usage.day != day {
+        *usage = Usage { day, ..Usage::default() };
+    }
+    usage
+}
+
+/// Run annotation holding the key [`QuotaMiddleware`] admitted the run under.
+pub const QUOTA_KEY_ANNOTATION: &str = "quota_key";
+
+/// Where [`QuotaMiddleware`] finds the key a run is counted against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuotaKey {
+    /// A claim of the run's auth claims (`context::meta::AUTH`).
+    Claim(String),
+    /// A string context key, e.g. `context::meta::TENANT`.
+    ContextKey(String),
+}
+
+/// Middleware enforcing [`Quotas`] on every run; see the [module docs](self).
+pub struct QuotaMiddleware {
+    pub quotas: std::sync::Arc<Quotas>,
+    pub key: QuotaKey,
+    /// Fail runs without a key with `Unauthorized` instead of letting them run uncounted.
+    pub require_key: bool,
+    /// Scope keys by the run's tenant (`context::meta::TENANT`).
+    pub per_tenant: bool,
+}
+
+impl QuotaMiddleware {
+    /// Count runs against the `sub` claim of their auth claims.
+    pub fn new(quotas: std::sync::Arc<Quotas>) -> Self {
+        QuotaMiddleware { quotas, key: QuotaKey::Claim("sub".to_string()), require_key: false, per_tenant: false }
+    }
+    pub fn with_key(mut self, key: QuotaKey) -> Self {
+        self.key = key;
+        self
+    }
+    pub fn require_key(mut self) -> Self {
+        self.require_key = true;
+        self
+    }
+    /// Count each tenant's use of a key apart, under `tenant/<id>/<key>`. Runs without a
+    /// tenant are counted under the bare key.
+    pub fn per_tenant(mut self) -> Self {
+        self.per_tenant = true;
+        self
+    }
+
+    fn key_of(&self, map: &HashMap<String, Value>) -> Option<String> {
+        let value = match &self.key {
+            QuotaKey::Claim(claim) => map.get(meta::AUTH)?.get(claim)?,
+            QuotaKey::ContextKey(key) => map.get(key.as_str())?,
+        };
+        let key = value.as_str()?;
+        match map.get(meta::TENANT).and_then(Value::as_str).filter(|_| self.per_tenant) {
+            Some(tenant) => Some(TenantScope::for_tenant(tenant).key(key)),
+            None => Some(key.to_string()),
+        }
+    }
+
+    fn admit(&self, map: &HashMap<String, Value>) {
+        let result = match self.key_of(map) {
+            Some(key) => self.quotas.acquire(&key).map(|()| {
+                // Charged at run end, whatever the links do to the context meanwhile
+                ctx_tools::annotate(QUOTA_KEY_ANNOTATION, key);
+            }),
+            None if self.require_key => Err(RunError::unauthorized("run has no quota key")),
+            None => Ok(()),
+        };
+        if let Err(err) = result {
+            tracing::warn!(target: "modulink::quota", error = %err, "run rejected");
+            ctx_tools::fail_run(err);
+        }
+    }
+
+    // Rejected runs were not counted as requests, and have no admitted key
+    fn account(&self, report: &RunReport) {
+        if let Some(key) = report.annotations.get(QUOTA_KEY_ANNOTATION).and_then(Value::as_str) {
+            self.quotas.record_compute(key, report.duration);
+        }
+    }
+}
+
+impl Middleware<Context> for QuotaMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: Context) -> Pin<Box<dyn Future<Output = Context> + Send + 'a>>
+    where
+        Context: 'a,
+    {
+        self.admit(&ctx.0);
+        Box::pin(async move { ctx })
+    }
+    fn on_run_end<'a>(&'a self, _ctx: &'a Context, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        self.account(report);
+        Box::pin(async {})
+    }
+}
+
+impl Middleware<ContextMutable> for QuotaMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: ContextMutable) -> Pin<Box<dyn Future<Output = ContextMutable> + Send + 'a>>
+    where
+        ContextMutable: 'a,
+    {
+        self.admit(&ctx.0);
+        Box::pin(async move { ctx })
+    }
+    fn on_run_end<'a>(&'a self, _ctx: &'a ContextMutable, report: &'a RunReport) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        self.account(report);
+        Box::pin(async {})
+    }
+}
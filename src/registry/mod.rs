@@ -0,0 +1,391 @@
+//! Chain and link registry for modulink-rust
+//! Process-wide maps of named chains and links, so tools like the CLI can look chains up
+//! by name and chain definitions (`crate::definitions`) can reference links by name.
+//!
+//! Names may be namespaced with `::` (`billing::enrich`); [`Registry::namespace`] registers
+//! and looks up names under a prefix, and [`Registry::links`] / [`Registry::find_links`]
+//! list registered links with their metadata. The free functions here work on
+//! [`Registry::global`]; a separate [`Registry`] can be made for tests or plugins.
+//!
+//! Links and chains can also register themselves from anywhere in the program with
+//! [`register_link!`](crate::register_link) and [`register_chain!`](crate::register_chain),
+//! instead of from a hand-maintained registration function. They are collected at link
+//! time and added to the global registry on its first use; a self-registered chain is
+//! built the first time it is looked up, so it can use registered links.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::registry::{self, Registry};
+//! use std::sync::Arc;
+//!
+//! registry::register_chain("enrich", Arc::new(Chain::new()));
+//! assert!(registry::get_chain("enrich").is_some());
+//!
+//! let billing = Registry::global().namespace("billing");
+//! billing.register_link("tax", Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("tax", 0.2) })));
+//! assert!(registry::get_link("billing::tax").is_some());
+//! assert_eq!(billing.link_names(), vec!["tax"]);
+//! ```
+
+use crate::chains::Chain;
+use crate::links::{Link, LinkSpec};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::{Arc, OnceLock, RwLock};
+
+#[doc(hidden)]
+pub use inventory;
+
+/// Separator between a namespace and a name.
+pub const NAMESPACE_SEPARATOR: &str = "::";
+
+/// The namespace of `name` (`billing` for `billing::enrich`); `None` for a bare name.
+pub fn namespace_of(name: &str) -> Option<&str> {
+    name.rsplit_once(NAMESPACE_SEPARATOR).map(|(namespace, _)| namespace)
+}
+
+/// A link as registered: its full name, the link, and its metadata.
+#[derive(Clone)]
+pub struct RegisteredLink {
+    pub name: String,
+    pub link: Link,
+    pub metadata: LinkMetadata,
+}
+
+/// A link submitted with [`register_link!`](crate::register_link).
+#[doc(hidden)]
+pub struct SubmittedLink {
+    pub name: &'static str,
+    pub link: fn() -> Link,
+    pub metadata: fn() -> LinkMetadata,
+}
+
+/// A chain submitted with [`register_chain!`](crate::register_chain).
+#[doc(hidden)]
+pub struct SubmittedChain {
+    pub name: &'static str,
+    pub chain: fn() -> Chain,
+}
+
+inventory::collect!(SubmittedLink);
+inventory::collect!(SubmittedChain);
+
+/// Named chains and links, safe to use from any thread; see the [module docs](self).
+#[derive(Default)]
+pub struct Registry {
+    chains: RwLock<BTreeMap<String, Arc<Chain>>>,
+    // Chains to build on first lookup.
+    pending: RwLock<BTreeMap<String, fn() -> Chain>>,
+    links: RwLock<BTreeMap<String, RegisteredLink>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// The process-wide registry, holding every self-registered link and chain.
+    pub fn global() -> &'static Registry {
+        static GLOBAL: OnceLock<Registry> = OnceLock::new();
+        GLOBAL.get_or_init(|| {
+            let registry = Registry::new();
+            for submitted in inventory::iter::<SubmittedLink> {
+                registry.register_link_with(submitted.name, (submitted.link)(), (submitted.metadata)());
+            }
+            let mut pending = registry.pending.write().unwrap();
+            for submitted in inventory::iter::<SubmittedChain> {
+                pending.insert(submitted.name.to_string(), submitted.chain);
+            }
+            drop(pending);
+            registry
+        })
+    }
+
+    /// Register `chain` under `name`, replacing any chain previously registered with that name.
+    pub fn register_chain(&self, name: impl Into<String>, chain: Arc<Chain>) {
+        let name = name.into();
+        self.pending.write().unwrap().remove(&name);
+        self.chains.write().unwrap().insert(name, chain);
+    }
+    pub fn get_chain(&self, name: &str) -> Option<Arc<Chain>> {
+        if let Some(chain) = self.chains.read().unwrap().get(name) {
+            return Some(chain.clone());
+        }
+        // Built outside the locks, as building may look up links; the first chain stored wins.
+        let build = *self.pending.read().unwrap().get(name)?;
+        let chain = Arc::new(build());
+        self.pending.write().unwrap().remove(name);
+        Some(self.chains.write().unwrap().entry(name.to_string()).or_insert(chain).clone())
+    }
+    /// Names of all registered chains, sorted.
+    pub fn chain_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.chains.read().unwrap().keys().cloned().collect();
+        names.extend(self.pending.read().unwrap().keys().cloned());
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Register `link` under `name`, replacing any link previously registered with that name.
+    pub fn register_link(&self, name: impl Into<String>, link: Link) {
+        self.register_link_with(name, link, LinkMetadata::default());
+    }
+    /// Register `link` with a version, description, or deprecation notice.
+    pub fn register_link_with(&self, name: impl Into<String>, link: Link, metadata: LinkMetadata) {
+        let name = name.into();
+        self.links.write().unwrap().insert(name.clone(), RegisteredLink { name, link, metadata });
+    }
+    pub fn get_link(&self, name: &str) -> Option<Link> {
+        self.links.read().unwrap().get(name).map(|registered| registered.link.clone())
+    }
+    pub fn link_metadata(&self, name: &str) -> Option<LinkMetadata> {
+        self.links.read().unwrap().get(name).map(|registered| registered.metadata.clone())
+    }
+    /// Names of all registered links, sorted.
+    pub fn link_names(&self) -> Vec<String> {
+        self.links.read().unwrap().keys().cloned().collect()
+    }
+    /// The registered links, sorted by name; only those directly in `namespace` when given
+    /// (`Some("")` for names without a namespace).
+    pub fn links(&self, namespace: Option<&str>) -> Vec<RegisteredLink> {
+        self.find_links(|name, _| namespace.is_none_or(|ns| namespace_of(name).unwrap_or("") == ns))
+    }
+    /// The registered links for which `matches(name, metadata)` holds, sorted by name, e.g.
+    /// every link that provides a key or is deprecated.
+    pub fn find_links<F: Fn(&str, &LinkMetadata) -> bool>(&self, matches: F) -> Vec<RegisteredLink> {
+        self.links.read().unwrap().values().filter(|registered| matches(&registered.name, &registered.metadata)).cloned().collect()
+    }
+    /// Every namespace a link or chain is registered in, sorted; nested namespaces are
+    /// listed whole (`billing::eu`), not with their parents.
+    pub fn namespaces(&self) -> Vec<String> {
+        let mut namespaces: Vec<String> =
+            self.link_names().iter().chain(self.chain_names().iter()).filter_map(|name| namespace_of(name)).map(str::to_string).collect();
+        namespaces.sort();
+        namespaces.dedup();
+        namespaces
+    }
+    /// Register and look up names under `namespace`.
+    pub fn namespace(&self, namespace: impl Into<String>) -> Namespace<'_> {
+        Namespace { registry: self, prefix: namespace.into() }
+    }
+}
+
+/// A view of a [`Registry`] under one namespace: names given to it are prefixed with
+/// `namespace::`.
+pub struct Namespace<'a> {
+    registry: &'a Registry,
+    prefix: String,
+}
+
+impl Namespace<'_> {
+    /// The full name of `name` in this namespace.
+    pub fn qualify(&self, name: &str) -> String {
+        format!("{}{}{}", self.prefix, NAMESPACE_SEPARATOR, name)
+    }
+    /// The namespace `name` inside this one.
+    pub fn namespace(&self, name: &str) -> Namespace<'_> {
+        Namespace { registry: self.registry, prefix: self.qualify(name) }
+    }
+    pub fn register_chain(&self, name: &str, chain: Arc<Chain>) {
+        self.registry.register_chain(self.qualify(name), chain);
+    }
+    pub fn get_chain(&self, name: &str) -> Option<Arc<Chain>> {
+        self.registry.get_chain(&self.qualify(name))
+    }
+    pub fn register_link(&self, name: &str, link: Link) {
+        self.registry.register_link(self.qualify(name), link);
+    }
+    pub fn register_link_with(&self, name: &str, link: Link, metadata: LinkMetadata) {
+        self.registry.register_link_with(self.qualify(name), link, metadata);
+    }
+    pub fn get_link(&self, name: &str) -> Option<Link> {
+        self.registry.get_link(&self.qualify(name))
+    }
+    /// Names of the links directly in this namespace, without the prefix, sorted.
+    pub fn link_names(&self) -> Vec<String> {
+        let prefix = self.qualify("");
+        self.registry.links(Some(&self.prefix)).into_iter().map(|registered| registered.name[prefix.len()..].to_string()).collect()
+    }
+}
+
+/// Register `chain` under `name`, replacing any chain previously registered with that name.
+pub fn register_chain(name: impl Into<String>, chain: Arc<Chain>) {
+    Registry::global().register_chain(name, chain);
+}
+
+pub fn get_chain(name: &str) -> Option<Arc<Chain>> {
+    Registry::global().get_chain(name)
+}
+
+/// Names of all registered chains, sorted.
+pub fn chain_names() -> Vec<String> {
+    Registry::global().chain_names()
+}
+
+/// The registered chain whose checkpoint store holds a checkpoint for `run_id`, with its
+/// name (see `ChainGeneric::enable_checkpoints`).
+pub async fn chain_with_run(run_id: &str) -> std::io::Result<Option<(String, Arc<Chain>)>> {
+    for name in chain_names() {
+        let Some(chain) = get_chain(&name) else { continue };
+        let Some(store) = chain.checkpoint_store() else { continue };
+        if store.load(run_id).await?.is_some() {
+            return Ok(Some((name, chain)));
+        }
+    }
+    Ok(None)
+}
+
+/// Descriptive metadata for a registered link, shown by `modulink-cli doc`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkMetadata {
+    pub version: Option<String>,
+    pub description: Option<String>,
+    /// Deprecation notice (e.g. "use `fetch_order_v2`"); definitions referencing the link warn.
+    pub deprecated: Option<String>,
+    /// Context keys the link reads; see `links::LinkSpec`.
+    #[serde(default)]
+    pub requires: Vec<String>,
+    /// Context keys the link writes.
+    #[serde(default)]
+    pub provides: Vec<String>,
+    /// See `links::LinkSpec::side_effects`.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub side_effects: bool,
+}
+
+impl LinkMetadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.version = Some(version.into());
+        self
+    }
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = Some(description.into());
+        self
+    }
+    pub fn deprecated(mut self, notice: impl Into<String>) -> Self {
+        self.deprecated = Some(notice.into());
+        self
+    }
+    pub fn requires<I: IntoIterator<Item = S>, S: Into<String>>(mut self, keys: I) -> Self {
+        self.requires.extend(keys.into_iter().map(Into::into));
+        self
+    }
+    pub fn provides<I: IntoIterator<Item = S>, S: Into<String>>(mut self, keys: I) -> Self {
+        self.provides.extend(keys.into_iter().map(Into::into));
+        self
+    }
+    pub fn side_effects(mut self) -> Self {
+        self.side_effects = true;
+        self
+    }
+    /// The chain-level spec for a link registered with this metadata.
+    pub fn spec(&self) -> LinkSpec {
+        LinkSpec {
+            name: None,
+            description: self.description.clone(),
+            requires: self.requires.clone(),
+            provides: self.provides.clone(),
+            side_effects: self.side_effects,
+            commutative: false,
+        }
+    }
+}
+
+impl std::fmt::Display for LinkMetadata {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(version) = &self.version {
+            parts.push(format!("v{}", version));
+        }
+        if let Some(description) = &self.description {
+            parts.push(description.clone());
+        }
+        if let Some(notice) = &self.deprecated {
+            parts.push(format!("[deprecated: {}]", notice));
+        }
+        if self.side_effects {
+            parts.push("[side effects]".to_string());
+        }
+        write!(f, "{}", parts.join(" - "))
+    }
+}
+
+/// Register `link` under `name`, replacing any link previously registered with that name.
+pub fn register_link(name: impl Into<String>, link: Link) {
+    Registry::global().register_link(name, link);
+}
+
+/// Register `link` with a version, description, or deprecation notice.
+pub fn register_link_with(name: impl Into<String>, link: Link, metadata: LinkMetadata) {
+    Registry::global().register_link_with(name, link, metadata);
+}
+
+pub fn get_link(name: &str) -> Option<Link> {
+    Registry::global().get_link(name)
+}
+
+pub fn link_metadata(name: &str) -> Option<LinkMetadata> {
+    Registry::global().link_metadata(name)
+}
+
+/// Names of all registered links, sorted.
+pub fn link_names() -> Vec<String> {
+    Registry::global().link_names()
+}
+
+/// Register a link with the global registry from anywhere in the program, without calling
+/// `register_link` at startup; see the [registry docs](crate::registry). The link
+/// expression is evaluated when the registry is first used, and must not use the registry.
+///
+/// ```rust
+/// use modulink_rs::context::Context;
+/// use modulink_rs::registry::{self, LinkMetadata};
+/// use std::sync::Arc;
+///
+/// modulink_rs::register_link!("billing::charge", Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("charged", true) })));
+/// modulink_rs::register_link!("billing::refund", Arc::new(|ctx: Context| Box::pin(async move { ctx })), LinkMetadata::new().side_effects());
+///
+/// assert!(registry::get_link("billing::charge").is_some());
+/// assert!(registry::link_metadata("billing::refund").unwrap().side_effects);
+/// ```
+#[macro_export]
+macro_rules! register_link {
+    ($name:expr, $link:expr $(,)?) => {
+        $crate::register_link!($name, $link, $crate::registry::LinkMetadata::default());
+    };
+    ($name:expr, $link:expr, $metadata:expr $(,)?) => {
+        $crate::registry::inventory::submit! {
+            $crate::registry::SubmittedLink { name: $name, link: || $link, metadata: || $metadata }
+        }
+    };
+}
+
+/// Register a chain with the global registry from anywhere in the program; the chain
+/// expression is evaluated the first time the chain is looked up, and may use registered
+/// links.
+///
+/// ```rust
+/// use modulink_rs::chains::Chain;
+/// use modulink_rs::registry;
+///
+/// modulink_rs::register_chain!("billing::invoice", {
+///     let mut chain = Chain::new();
+///     chain.set_name("invoice");
+///     chain
+/// });
+///
+/// assert!(registry::chain_names().contains(&"billing::invoice".to_string()));
+/// assert_eq!(registry::get_chain("billing::invoice").unwrap().name(), Some("invoice"));
+/// ```
+#[macro_export]
+macro_rules! register_chain {
+    ($name:expr, $chain:expr $(,)?) => {
+        $crate::registry::inventory::submit! {
+            $crate::registry::SubmittedChain { name: $name, chain: || $chain }
+        }
+    };
+}
@@ -0,0 +1,155 @@
+//! Deterministic executor for tests.
+//! Tasks only run when the test drives them, and time only moves when the test advances it.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::runtime::{Executor, MockExecutor};
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//! use std::sync::Arc;
+//! use std::time::Duration;
+//!
+//! let exec = MockExecutor::new();
+//! let done = Arc::new(AtomicBool::new(false));
+//! let flag = done.clone();
+//! let sleep = exec.sleep(Duration::from_secs(60));
+//! exec.spawn(Box::pin(async move {
+//!     sleep.await;
+//!     flag.store(true, Ordering::SeqCst);
+//! }));
+//! exec.run_until_stalled();
+//! assert!(!done.load(Ordering::SeqCst));
+//! exec.advance(Duration::from_secs(60));
+//! assert!(done.load(Ordering::SeqCst));
+//! ```
+
+use super::{BoxFuture, Executor};
+use futures::task::{waker, ArcWake};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context as TaskContext, Poll, Waker};
+use std::time::Duration;
+
+#[derive(Default)]
+struct MockState {
+    tasks: Vec<BoxFuture<'static, ()>>,
+    blocking: Vec<Box<dyn FnOnce() + Send>>,
+    now: Duration,
+    sleepers: Vec<(Duration, Waker)>,
+}
+
+struct Notify(AtomicBool);
+
+impl ArcWake for Notify {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        arc_self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Executor with manual task polling and a virtual clock.
+#[derive(Clone, Default)]
+pub struct MockExecutor {
+    state: Arc<Mutex<MockState>>,
+    notify: Arc<Notify>,
+}
+
+impl Default for Notify {
+    fn default() -> Self {
+        Notify(AtomicBool::new(false))
+    }
+}
+
+impl MockExecutor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Virtual time elapsed since the executor was created.
+    pub fn now(&self) -> Duration {
+        self.state.lock().unwrap().now
+    }
+    /// Number of spawned tasks that have not completed yet.
+    pub fn pending_tasks(&self) -> usize {
+        self.state.lock().unwrap().tasks.len()
+    }
+    /// Poll every spawned task until none of them can make further progress.
+    pub fn run_until_stalled(&self) {
+        loop {
+            let blocking = std::mem::take(&mut self.state.lock().unwrap().blocking);
+            for f in blocking {
+                f();
+            }
+            let tasks = std::mem::take(&mut self.state.lock().unwrap().tasks);
+            if tasks.is_empty() {
+                return;
+            }
+            self.notify.0.store(false, Ordering::SeqCst);
+            let task_waker = waker(self.notify.clone());
+            let mut cx = TaskContext::from_waker(&task_waker);
+            let before = tasks.len();
+            let mut pending = Vec::with_capacity(before);
+            for mut task in tasks {
+                if task.as_mut().poll(&mut cx).is_pending() {
+                    pending.push(task);
+                }
+            }
+            let completed = pending.len() < before;
+            let mut state = self.state.lock().unwrap();
+            let spawned = !state.tasks.is_empty() || !state.blocking.is_empty();
+            pending.append(&mut state.tasks);
+            state.tasks = pending;
+            drop(state);
+            if !completed && !spawned && !self.notify.0.load(Ordering::SeqCst) {
+                return;
+            }
+        }
+    }
+    /// Move the virtual clock forward, wake expired sleeps, and run tasks until stalled.
+    pub fn advance(&self, dur: Duration) {
+        let expired: Vec<Waker> = {
+            let mut state = self.state.lock().unwrap();
+            state.now += dur;
+            let now = state.now;
+            let (ready, waiting) = std::mem::take(&mut state.sleepers)
+                .into_iter()
+                .partition(|(deadline, _)| *deadline <= now);
+            state.sleepers = waiting;
+            ready.into_iter().map(|(_, w)| w).collect()
+        };
+        for w in expired {
+            w.wake();
+        }
+        self.run_until_stalled();
+    }
+}
+
+impl Executor for MockExecutor {
+    fn spawn(&self, fut: BoxFuture<'static, ()>) {
+        self.state.lock().unwrap().tasks.push(fut);
+    }
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        self.state.lock().unwrap().blocking.push(f);
+    }
+    fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()> {
+        let deadline = self.now() + dur;
+        Box::pin(MockSleep { state: self.state.clone(), deadline })
+    }
+}
+
+struct MockSleep {
+    state: Arc<Mutex<MockState>>,
+    deadline: Duration,
+}
+
+impl Future for MockSleep {
+    type Output = ();
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.now >= self.deadline {
+            Poll::Ready(())
+        } else {
+            state.sleepers.push((self.deadline, cx.waker().clone()));
+            Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Runtime abstraction for modulink-rust
+//! Small trait for spawning and timers so the core types stay runtime-neutral.
+//!
+//! Chains, links, and middleware only depend on `std` and `futures`. Anything that needs
+//! a background task or a timer goes through an [`Executor`], so the crate works the same
+//! on tokio, async-std, smol, or a hand-rolled executor. Each chain carries its own
+//! executor (`ChainGeneric::set_executor`), defaulting to [`default_executor`].
+//!
+//! Example (custom executor for another runtime):
+//! ```rust
+//! use modulink_rs::runtime::{BoxFuture, Executor};
+//! use std::time::Duration;
+//!
+//! struct BlockOnExecutor;
+//!
+//! impl Executor for BlockOnExecutor {
+//!     fn spawn(&self, fut: BoxFuture<'static, ()>) {
+//!         std::thread::spawn(move || futures::executor::block_on(fut));
+//!     }
+//!     fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+//!         std::thread::spawn(f);
+//!     }
+//!     fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()> {
+//!         // A timer thread completes the future; the task itself never blocks.
+//!         let (tx, rx) = futures::channel::oneshot::channel::<()>();
+//!         std::thread::spawn(move || {
+//!             std::thread::sleep(dur);
+//!             let _ = tx.send(());
+//!         });
+//!         Box::pin(async move {
+//!             let _ = rx.await;
+//!         })
+//!     }
+//! }
+//! ```
+
+pub mod mock;
+pub use mock::MockExecutor;
+
+use futures::channel::oneshot;
+use futures::future::{AbortHandle, Abortable, Either, FutureExt};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+/// Boxed, sendable future used across the executor boundary.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Spawning and timer primitives required by chain features that run work in the background.
+pub trait Executor: Send + Sync {
+    /// Spawn a detached task.
+    fn spawn(&self, fut: BoxFuture<'static, ()>);
+    /// Run a blocking closure off the async worker threads.
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>);
+    /// Return a future that resolves after `dur`.
+    fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()>;
+}
+
+pub type ExecutorObj = Arc<dyn Executor>;
+
+/// Executor backed by the ambient tokio runtime.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TokioExecutor;
+
+#[cfg(feature = "tokio")]
+impl Executor for TokioExecutor {
+    fn spawn(&self, fut: BoxFuture<'static, ()>) {
+        tokio::spawn(fut);
+    }
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        tokio::task::spawn_blocking(f);
+    }
+    fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep(dur))
+    }
+}
+
+/// Fallback executor using plain OS threads; works without any async runtime.
+/// One thread per spawned task or timer, so prefer a real runtime's executor in production.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadExecutor;
+
+impl Executor for ThreadExecutor {
+    fn spawn(&self, fut: BoxFuture<'static, ()>) {
+        std::thread::spawn(move || futures::executor::block_on(fut));
+    }
+    fn spawn_blocking(&self, f: Box<dyn FnOnce() + Send>) {
+        std::thread::spawn(f);
+    }
+    fn sleep(&self, dur: Duration) -> BoxFuture<'static, ()> {
+        let (tx, rx) = oneshot::channel::<()>();
+        std::thread::spawn(move || {
+            std::thread::sleep(dur);
+            let _ = tx.send(());
+        });
+        Box::pin(async move {
+            let _ = rx.await;
+        })
+    }
+}
+
+/// The executor used when none is supplied: tokio when the `tokio` feature is enabled.
+#[cfg(feature = "tokio")]
+pub fn default_executor() -> ExecutorObj {
+    Arc::new(TokioExecutor)
+}
+
+/// The executor used when none is supplied: OS threads without the `tokio` feature.
+#[cfg(not(feature = "tokio"))]
+pub fn default_executor() -> ExecutorObj {
+    Arc::new(ThreadExecutor)
+}
+
+/// Why a spawned task did not produce a value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinError {
+    /// The task was aborted through its handle.
+    Aborted,
+    /// The task panicked; carries the panic message when it was a string.
+    Panicked(String),
+}
+
+impl std::fmt::Display for JoinError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JoinError::Aborted => write!(f, "task aborted"),
+            JoinError::Panicked(msg) => write!(f, "task panicked: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for JoinError {}
+
+/// Awaitable, abortable handle to a task spawned with [`spawn`].
+pub struct JoinHandle<R> {
+    rx: oneshot::Receiver<Result<R, JoinError>>,
+    abort: AbortHandle,
+}
+
+impl<R> JoinHandle<R> {
+    pub(crate) fn from_parts(rx: oneshot::Receiver<Result<R, JoinError>>, abort: AbortHandle) -> Self {
+        JoinHandle { rx, abort }
+    }
+    /// Abort the task; awaiting the handle then yields `JoinError::Aborted`.
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+    /// Handle that can abort the task without owning the join handle.
+    pub fn abort_handle(&self) -> AbortHandle {
+        self.abort.clone()
+    }
+}
+
+impl<R> Future for JoinHandle<R> {
+    type Output = Result<R, JoinError>;
+    fn poll(mut self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Self::Output> {
+        match self.rx.poll_unpin(cx) {
+            Poll::Ready(Ok(res)) => Poll::Ready(res),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(JoinError::Aborted)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
+
+pub(crate) fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        s.to_string()
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+/// Spawn a future on `exec` and get back a handle to its output.
+pub fn spawn<F, R>(exec: &dyn Executor, fut: F) -> JoinHandle<R>
+where
+    F: Future<Output = R> + Send + 'static,
+    R: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    let (abort, reg) = AbortHandle::new_pair();
+    let task = Abortable::new(AssertUnwindSafe(fut).catch_unwind(), reg);
+    exec.spawn(Box::pin(async move {
+        let res = match task.await {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(payload)) => Err(JoinError::Panicked(panic_message(payload))),
+            Err(_) => Err(JoinError::Aborted),
+        };
+        let _ = tx.send(res);
+    }));
+    JoinHandle { rx, abort }
+}
+
+/// Run a blocking closure through `exec.spawn_blocking` and await its result.
+pub async fn spawn_blocking<F, R>(exec: &dyn Executor, f: F) -> Result<R, JoinError>
+where
+    F: FnOnce() -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    exec.spawn_blocking(Box::new(move || {
+        let res = std::panic::catch_unwind(AssertUnwindSafe(f))
+            .map_err(|payload| JoinError::Panicked(panic_message(payload)));
+        let _ = tx.send(res);
+    }));
+    rx.await.unwrap_or(Err(JoinError::Aborted))
+}
+
+/// Await `fut`, giving up after `dur` as measured by `exec`. Returns `None` on timeout.
+pub async fn timeout<F: Future>(exec: &dyn Executor, dur: Duration, fut: F) -> Option<F::Output> {
+    let fut = std::pin::pin!(fut);
+    match futures::future::select(fut, exec.sleep(dur)).await {
+        Either::Left((value, _)) => Some(value),
+        Either::Right(_) => None,
+    }
+}
@@ -0,0 +1,318 @@
+//! Context schema inference and drift detection.
+//!
+//! [`SchemaMiddleware`] infers the shape of the context at every point of a chain: the
+//! input (`"input"`) and the context after each link (the link's `LinkSpec::name`, or
+//! `#<index>` for unnamed links). Shapes accumulate over runs into a [`ContextSchema`],
+//! which can be saved as JSON and loaded back as the baseline of a later deployment.
+//!
+//! Against a baseline, each observed context is checked for [`Drift`]: fields the baseline
+//! has never seen, and values whose JSON type the baseline does not allow (an integer where
+//! numbers were seen is not drift). Fields missing from a context are not drift, since
+//! optional fields are common. Points the baseline does not cover are not checked. Each
+//! run with drift gets a warning in `RunReport::warnings`, and each distinct drift is
+//! logged and passed to the [`SchemaMiddleware::on_drift`] handler once.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::schema::{Drift, SchemaMiddleware};
+//! use std::sync::Arc;
+//!
+//! # futures::executor::block_on(async {
+//! let learn = Arc::new(SchemaMiddleware::new());
+//! let mut chain = Chain::new();
+//! chain.use_middleware(learn.clone());
+//! chain.run(Context::new().insert("order_id", 7)).await;
+//! let baseline = learn.observed();
+//!
+//! let check = Arc::new(SchemaMiddleware::new().with_baseline(baseline));
+//! let mut chain = Chain::new();
+//! chain.use_middleware(check.clone());
+//! let (_, report) = chain.run_with_report(Context::new().insert("order_id", "7")).await;
+//! assert!(matches!(&check.drift()[0], Drift::TypeChanged { path, .. } if path == "order_id"));
+//! assert_eq!(report.warnings.len(), 1);
+//! # });
+//! ```
+
+use crate::chains::RunScope;
+use crate::middleware::Middleware;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// Point name of the input context.
+pub const INPUT: &str = "input";
+
+/// JSON type of an observed value; integers are told apart from other numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JsonType {
+    Null,
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => JsonType::Null,
+            Value::Bool(_) => JsonType::Boolean,
+            Value::Number(n) if n.is_i64() || n.is_u64() => JsonType::Integer,
+            Value::Number(_) => JsonType::Number,
+            Value::String(_) => JsonType::String,
+            Value::Array(_) => JsonType::Array,
+            Value::Object(_) => JsonType::Object,
+        }
+    }
+    fn name(self) -> &'static str {
+        match self {
+            JsonType::Null => "null",
+            JsonType::Boolean => "boolean",
+            JsonType::Integer => "integer",
+            JsonType::Number => "number",
+            JsonType::String => "string",
+            JsonType::Array => "array",
+            JsonType::Object => "object",
+        }
+    }
+}
+
+/// The union of every value seen at one place: its types, the fields of objects, and the
+/// shape of array items.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Shape {
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub types: BTreeSet<JsonType>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub properties: BTreeMap<String, Shape>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub items: Option<Box<Shape>>,
+}
+
+impl Shape {
+    pub fn of(value: &Value) -> Self {
+        let mut shape = Shape::default();
+        shape.observe(value);
+        shape
+    }
+    /// Widen the shape to cover `value`.
+    pub fn observe(&mut self, value: &Value) {
+        self.types.insert(JsonType::of(value));
+        match value {
+            Value::Object(fields) => {
+                for (key, field) in fields {
+                    self.properties.entry(key.clone()).or_default().observe(field);
+                }
+            }
+            Value::Array(items) => {
+                let shape = self.items.get_or_insert_with(Default::default);
+                for item in items {
+                    shape.observe(item);
+                }
+            }
+            _ => {}
+        }
+    }
+    /// Widen the shape to cover everything `other` covers.
+    pub fn merge(&mut self, other: &Shape) {
+        self.types.extend(other.types.iter().copied());
+        for (key, shape) in &other.properties {
+            self.properties.entry(key.clone()).or_default().merge(shape);
+        }
+        if let Some(items) = &other.items {
+            self.items.get_or_insert_with(Default::default).merge(items);
+        }
+    }
+    /// How `observed` departs from this (baseline) shape, with paths like `order.items[].sku`.
+    pub fn drift(&self, point: &str, observed: &Shape) -> Vec<Drift> {
+        let mut out = Vec::new();
+        self.drift_into(point, "", observed, &mut out);
+        out
+    }
+
+    fn allows(&self, ty: JsonType) -> bool {
+        self.types.contains(&ty) || (ty == JsonType::Integer && self.types.contains(&JsonType::Number))
+    }
+
+    fn drift_into(&self, point: &str, path: &str, observed: &Shape, out: &mut Vec<Drift>) {
+        if !path.is_empty() && !self.types.is_empty() && !observed.types.iter().all(|ty| self.allows(*ty)) {
+            out.push(Drift::TypeChanged {
+                point: point.to_string(),
+                path: path.to_string(),
+                baseline: self.types.clone(),
+                observed: observed.types.clone(),
+            });
+        }
+        for (key, shape) in &observed.properties {
+            let field = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+            match self.properties.get(key) {
+                Some(baseline) => baseline.drift_into(point, &field, shape, out),
+                // Only objects the baseline saw can gain fields
+                None if self.types.contains(&JsonType::Object) => {
+                    out.push(Drift::NewField { point: point.to_string(), path: field, types: shape.types.clone() })
+                }
+                None => {}
+            }
+        }
+        if let (Some(baseline), Some(items)) = (&self.items, &observed.items) {
+            baseline.drift_into(point, &format!("{}[]", path), items, out);
+        }
+    }
+
+    /// The shape as a JSON Schema document (draft 2020-12 keywords).
+    pub fn to_json_schema(&self) -> Value {
+        let mut schema = serde_json::Map::new();
+        let mut types: Vec<&str> = self.types.iter().map(|ty| ty.name()).collect();
+        if self.types.contains(&JsonType::Number) {
+            types.retain(|ty| *ty != "integer");
+        }
+        match types.as_slice() {
+            [] => {}
+            [ty] => {
+                schema.insert("type".to_string(), json!(ty));
+            }
+            many => {
+                schema.insert("type".to_string(), json!(many));
+            }
+        }
+        if !self.properties.is_empty() {
+            let properties: serde_json::Map<String, Value> =
+                self.properties.iter().map(|(key, shape)| (key.clone(), shape.to_json_schema())).collect();
+            schema.insert("properties".to_string(), Value::Object(properties));
+        }
+        if let Some(items) = &self.items {
+            schema.insert("items".to_string(), items.to_json_schema());
+        }
+        Value::Object(schema)
+    }
+}
+
+/// A departure of an observed context from the baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Drift {
+    /// A field the baseline never saw at this point.
+    NewField { point: String, path: String, types: BTreeSet<JsonType> },
+    /// A value of a type the baseline does not allow at this path.
+    TypeChanged { point: String, path: String, baseline: BTreeSet<JsonType>, observed: BTreeSet<JsonType> },
+}
+
+impl std::fmt::Display for Drift {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names = |types: &BTreeSet<JsonType>| types.iter().map(|ty| ty.name()).collect::<Vec<_>>().join("|");
+        match self {
+            Drift::NewField { point, path, types } => write!(f, "schema drift at {}: new field '{}' ({})", point, path, names(types)),
+            Drift::TypeChanged { point, path, baseline, observed } => {
+                write!(f, "schema drift at {}: '{}' is {}, baseline {}", point, path, names(observed), names(baseline))
+            }
+        }
+    }
+}
+
+/// Shapes of the context at each point of a chain, keyed by point name.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContextSchema {
+    pub points: BTreeMap<String, Shape>,
+}
+
+impl ContextSchema {
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap_or_default()
+    }
+    /// Every point as a JSON Schema document, keyed by point name.
+    pub fn to_json_schema(&self) -> Value {
+        Value::Object(self.points.iter().map(|(point, shape)| (point.clone(), shape.to_json_schema())).collect())
+    }
+}
+
+type DriftHandler = Arc<dyn Fn(&Drift) + Send + Sync>;
+
+/// Middleware inferring a [`ContextSchema`] and checking contexts against a baseline;
+/// see the [module docs](self).
+#[derive(Default)]
+pub struct SchemaMiddleware {
+    observed: Mutex<ContextSchema>,
+    baseline: Option<ContextSchema>,
+    on_drift: Option<DriftHandler>,
+    seen: Mutex<HashSet<Drift>>,
+}
+
+impl SchemaMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Check every observed context against `baseline`.
+    pub fn with_baseline(mut self, baseline: ContextSchema) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+    /// Call `handler` once for each distinct drift.
+    pub fn on_drift<F: Fn(&Drift) + Send + Sync + 'static>(mut self, handler: F) -> Self {
+        self.on_drift = Some(Arc::new(handler));
+        self
+    }
+    /// Everything observed so far, e.g. to save as the next baseline.
+    pub fn observed(&self) -> ContextSchema {
+        self.observed.lock().unwrap().clone()
+    }
+    /// Distinct drift found so far, ordered by point and path.
+    pub fn drift(&self) -> Vec<Drift> {
+        let mut drift: Vec<Drift> = self.seen.lock().unwrap().iter().cloned().collect();
+        drift.sort_by(|a, b| drift_key(a).cmp(&drift_key(b)));
+        drift
+    }
+
+    fn observe<T: Serialize>(&self, point: &str, ctx: &T) {
+        let Ok(value) = serde_json::to_value(ctx) else { return };
+        let shape = Shape::of(&value);
+        self.observed.lock().unwrap().points.entry(point.to_string()).or_default().merge(&shape);
+        let Some(baseline) = self.baseline.as_ref().and_then(|baseline| baseline.points.get(point)) else { return };
+        let scope = RunScope::current();
+        for drift in baseline.drift(point, &shape) {
+            if let Some(scope) = &scope {
+                scope.warn(drift.to_string());
+            }
+            if self.seen.lock().unwrap().insert(drift.clone()) {
+                tracing::warn!(drift = %drift, "context schema drift");
+                if let Some(handler) = &self.on_drift {
+                    handler(&drift);
+                }
+            }
+        }
+    }
+}
+
+fn drift_key(drift: &Drift) -> (&str, &str) {
+    match drift {
+        Drift::NewField { point, path, .. } | Drift::TypeChanged { point, path, .. } => (point, path),
+    }
+}
+
+impl<T: Serialize + Send + Sync> Middleware<T> for SchemaMiddleware {
+    fn on_run_start<'a>(&'a self, ctx: T) -> Pin<Box<dyn Future<Output = T> + Send + 'a>>
+    where
+        T: Send + 'a,
+    {
+        self.observe(INPUT, &ctx);
+        Box::pin(async move { ctx })
+    }
+    fn after<'a>(&'a self, ctx: &'a T) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        // The link that just ran is the last step of the run's path
+        if let Some(step) = RunScope::current().and_then(|scope| scope.path().pop()) {
+            let point = step.name.unwrap_or_else(|| format!("#{}", step.link));
+            self.observe(&point, ctx);
+        }
+        Box::pin(async {})
+    }
+}
@@ -0,0 +1,26 @@
+use crate::sinks::BaseSink;
+use async_trait::async_trait;
+use futures::channel::mpsc;
+use futures::SinkExt;
+
+/// Forwards each result into a `futures` mpsc channel.
+pub struct ChannelSink<T> {
+    pub tx: mpsc::Sender<T>,
+}
+
+impl<T> ChannelSink<T> {
+    pub fn new(tx: mpsc::Sender<T>) -> Self {
+        ChannelSink { tx }
+    }
+}
+
+#[async_trait]
+impl<T: Clone + Send + Sync + 'static> BaseSink<T> for ChannelSink<T> {
+    async fn deliver(&self, ctx: &T) -> std::io::Result<()> {
+        let mut tx = self.tx.clone();
+        tx.send(ctx.clone()).await.map_err(std::io::Error::other)
+    }
+    fn name(&self) -> &'static str {
+        "channel"
+    }
+}
@@ -0,0 +1,167 @@
+//! Dead letters: the contexts of failed runs, kept for reprocessing.
+//!
+//! Sinks attached with `ChainGeneric::dead_letter_to` are called for every run that ends
+//! `Failed`, with the context as the run left it and a [`Failure`]: the link that failed
+//! (position and `LinkSpec::name`), the error, and the run id of durable runs. Regular
+//! sinks still receive the run as well. [`FileDeadLetters`] appends them to a JSONL file
+//! that [`FileDeadLetters::load`] (or, outside of async code, [`FileDeadLetters::read`])
+//! loads back; [`ChannelDeadLetters`] forwards them to a channel.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, RunError};
+//! use modulink_rs::context::Context;
+//! use modulink_rs::ctx_tools;
+//! use modulink_rs::links::LinkSpec;
+//! use modulink_rs::sinks::{ChannelDeadLetters, FailedRun};
+//! use futures::StreamExt;
+//! use std::sync::Arc;
+//!
+//! let (tx, mut failures) = futures::channel::mpsc::channel::<FailedRun<Context>>(16);
+//! let mut chain = Chain::new();
+//! chain.dead_letter_to(Arc::new(ChannelDeadLetters::new(tx)));
+//! chain.add_link_with(Arc::new(|ctx: Context| Box::pin(async move {
+//!     ctx_tools::fail_run(RunError::invalid_input("no sku"));
+//!     ctx
+//! })), LinkSpec::new().name("price"));
+//!
+//! futures::executor::block_on(async {
+//!     chain.run(Context::new().insert("order", 7)).await;
+//!     let failed = failures.next().await.unwrap();
+//!     assert_eq!(failed.failure.link_name.as_deref(), Some("price"));
+//!     assert_eq!(failed.ctx.get::<u32>("order"), Some(7));
+//! });
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::runtime::{self, default_executor, ExecutorObj};
+use async_trait::async_trait;
+use futures::channel::mpsc;
+use futures::SinkExt;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Why and where a run failed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Failure {
+    /// Position of the link that failed; `None` if the run failed before any link ran.
+    pub link: Option<usize>,
+    /// `LinkSpec::name` of that link.
+    #[serde(default)]
+    pub link_name: Option<String>,
+    pub error: RunError,
+    /// Run id of a durable run (`ChainGeneric::run_durable`).
+    #[serde(default)]
+    pub run_id: Option<String>,
+}
+
+/// A failed run with the context it failed with, as stored and sent on by the
+/// implementations here.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FailedRun<T> {
+    pub ctx: T,
+    #[serde(flatten)]
+    pub failure: Failure,
+}
+
+/// Receives the failed runs of a chain; see the [module docs](self).
+#[async_trait]
+pub trait DeadLetterSink<T = Context>: Send + Sync {
+    async fn dead_letter(&self, ctx: &T, failure: &Failure) -> std::io::Result<()>;
+    fn name(&self) -> &'static str;
+}
+
+pub type DeadLetterSinkObj<T = Context> = Arc<dyn DeadLetterSink<T>>;
+
+/// Appends each failed run to a file as one JSON document per line. File I/O runs on the
+/// blocking pool of the sink's executor.
+pub struct FileDeadLetters {
+    pub path: PathBuf,
+    // Serializes appends so concurrent runs never interleave lines.
+    lock: Arc<Mutex<()>>,
+    executor: ExecutorObj,
+}
+
+impl FileDeadLetters {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileDeadLetters { path: path.into(), lock: Arc::new(Mutex::new(())), executor: default_executor() }
+    }
+    /// Executor whose blocking pool does the file I/O (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+    /// The failed runs stored in this sink's file, read on the executor's blocking pool.
+    pub async fn load<T: DeserializeOwned + Send + 'static>(&self) -> std::io::Result<Vec<FailedRun<T>>> {
+        let path = self.path.clone();
+        self.blocking(move || Self::read(path)).await
+    }
+    /// The failed runs stored in the file at `path`, oldest first; none if it does not exist.
+    /// Reads synchronously; async code uses [`Self::load`].
+    pub fn read<T: DeserializeOwned>(path: impl AsRef<Path>) -> std::io::Result<Vec<FailedRun<T>>> {
+        let file = match std::fs::File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        let mut failed = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if !line.trim().is_empty() {
+                failed.push(serde_json::from_str(&line).map_err(std::io::Error::other)?);
+            }
+        }
+        Ok(failed)
+    }
+
+    // Run `io` on the executor's blocking pool, under the lock.
+    async fn blocking<R: Send + 'static>(&self, io: impl FnOnce() -> std::io::Result<R> + Send + 'static) -> std::io::Result<R> {
+        let lock = self.lock.clone();
+        runtime::spawn_blocking(self.executor.as_ref(), move || {
+            let _guard = lock.lock().unwrap();
+            io()
+        })
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e)))
+    }
+}
+
+#[async_trait]
+impl<T: Serialize + Sync> DeadLetterSink<T> for FileDeadLetters {
+    async fn dead_letter(&self, ctx: &T, failure: &Failure) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(&FailedRun { ctx, failure: failure.clone() }).map_err(std::io::Error::other)?;
+        line.push(b'\n');
+        let path = self.path.clone();
+        self.blocking(move || OpenOptions::new().create(true).append(true).open(&path)?.write_all(&line)).await
+    }
+    fn name(&self) -> &'static str {
+        "file"
+    }
+}
+
+/// Forwards each failed run into a `futures` mpsc channel.
+pub struct ChannelDeadLetters<T> {
+    pub tx: mpsc::Sender<FailedRun<T>>,
+}
+
+impl<T> ChannelDeadLetters<T> {
+    pub fn new(tx: mpsc::Sender<FailedRun<T>>) -> Self {
+        ChannelDeadLetters { tx }
+    }
+}
+
+#[async_trait]
+impl<T: Clone + Send + Sync + 'static> DeadLetterSink<T> for ChannelDeadLetters<T> {
+    async fn dead_letter(&self, ctx: &T, failure: &Failure) -> std::io::Result<()> {
+        let mut tx = self.tx.clone();
+        tx.send(FailedRun { ctx: ctx.clone(), failure: failure.clone() }).await.map_err(std::io::Error::other)
+    }
+    fn name(&self) -> &'static str {
+        "channel"
+    }
+}
@@ -0,0 +1,47 @@
+use crate::runtime::{self, default_executor, ExecutorObj};
+use crate::sinks::BaseSink;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+/// Appends each result to a file as one JSON document per line. File I/O runs on the
+/// blocking pool of the sink's executor.
+pub struct FileSink {
+    pub path: PathBuf,
+    // Serializes appends so concurrent runs never interleave lines.
+    lock: Arc<Mutex<()>>,
+    executor: ExecutorObj,
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        FileSink { path: path.into(), lock: Arc::new(Mutex::new(())), executor: default_executor() }
+    }
+    /// Executor whose blocking pool does the file I/O (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+}
+
+#[async_trait]
+impl<T: Serialize + Sync> BaseSink<T> for FileSink {
+    async fn deliver(&self, ctx: &T) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(ctx).map_err(std::io::Error::other)?;
+        line.push(b'\n');
+        let (path, lock) = (self.path.clone(), self.lock.clone());
+        runtime::spawn_blocking(self.executor.as_ref(), move || {
+            let _guard = lock.lock().unwrap();
+            let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+            file.write_all(&line)
+        })
+        .await
+        .unwrap_or_else(|e| Err(std::io::Error::other(e)))
+    }
+    fn name(&self) -> &'static str {
+        "file"
+    }
+}
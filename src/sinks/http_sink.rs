@@ -0,0 +1,26 @@
+use crate::sinks::BaseSink;
+use async_trait::async_trait;
+use serde::Serialize;
+
+/// POSTs each result as JSON to an HTTP endpoint. Non-2xx responses are errors.
+pub struct HttpSink {
+    pub url: String,
+    client: reqwest::Client,
+}
+
+impl HttpSink {
+    pub fn new(url: impl Into<String>) -> Self {
+        HttpSink { url: url.into(), client: reqwest::Client::new() }
+    }
+}
+
+#[async_trait]
+impl<T: Serialize + Sync> BaseSink<T> for HttpSink {
+    async fn deliver(&self, ctx: &T) -> std::io::Result<()> {
+        let resp = self.client.post(&self.url).json(ctx).send().await.map_err(std::io::Error::other)?;
+        resp.error_for_status().map(|_| ()).map_err(std::io::Error::other)
+    }
+    fn name(&self) -> &'static str {
+        "http"
+    }
+}
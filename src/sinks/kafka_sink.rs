@@ -0,0 +1,47 @@
+use crate::sinks::BaseSink;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::sync::Arc;
+
+/// Minimal producer interface the Kafka sink publishes through.
+/// Implement it over the client of your choice (e.g. `rdkafka::producer::FutureProducer`)
+/// so the library does not pull a native Kafka client into every build.
+#[async_trait]
+pub trait KafkaProducer: Send + Sync {
+    async fn send(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> std::io::Result<()>;
+}
+
+type KeyFn<T> = Arc<dyn Fn(&T) -> Option<String> + Send + Sync>;
+
+/// Publishes each result as a JSON message to a Kafka topic.
+pub struct KafkaSink<T> {
+    pub producer: Arc<dyn KafkaProducer>,
+    pub topic: String,
+    key: Option<KeyFn<T>>,
+}
+
+impl<T> KafkaSink<T> {
+    pub fn new(producer: Arc<dyn KafkaProducer>, topic: impl Into<String>) -> Self {
+        KafkaSink { producer, topic: topic.into(), key: None }
+    }
+    /// Derive the message key from the context (e.g. for partitioning by customer).
+    pub fn with_key<F>(mut self, key: F) -> Self
+    where
+        F: Fn(&T) -> Option<String> + Send + Sync + 'static,
+    {
+        self.key = Some(Arc::new(key));
+        self
+    }
+}
+
+#[async_trait]
+impl<T: Serialize + Sync> BaseSink<T> for KafkaSink<T> {
+    async fn deliver(&self, ctx: &T) -> std::io::Result<()> {
+        let payload = serde_json::to_vec(ctx).map_err(std::io::Error::other)?;
+        let key = self.key.as_ref().and_then(|f| f(ctx));
+        self.producer.send(&self.topic, key.as_deref(), payload).await
+    }
+    fn name(&self) -> &'static str {
+        "kafka"
+    }
+}
@@ -0,0 +1,55 @@
+//! Sinks: where run results go.
+//! The output-side counterpart of listeners: a listener triggers runs, a sink receives
+//! the final context of each run. Attach sinks with `ChainGeneric::pipe_to`, and sinks for
+//! the contexts of failed runs with `ChainGeneric::dead_letter_to` (see [`dead_letter`]).
+//! Wrap sinks that export to reporting systems in a [`PrivacySink`] (see [`privacy`]).
+
+pub mod channel_sink;
+pub mod dead_letter;
+pub mod file_sink;
+pub mod kafka_sink;
+pub mod privacy;
+pub mod stdout_sink;
+pub use channel_sink::ChannelSink;
+pub use dead_letter::{ChannelDeadLetters, DeadLetterSink, DeadLetterSinkObj, FailedRun, Failure, FileDeadLetters};
+pub use file_sink::FileSink;
+pub use kafka_sink::{KafkaProducer, KafkaSink};
+pub use privacy::{FieldRule, PrivacyPolicy, PrivacySink};
+pub use stdout_sink::StdoutSink;
+
+// HTTP sinks need tokio + reqwest; the sink trait itself is runtime-neutral.
+#[cfg(feature = "http-sink")]
+pub mod http_sink;
+#[cfg(feature = "http-sink")]
+pub mod webhook_sink;
+#[cfg(feature = "http-sink")]
+pub use http_sink::HttpSink;
+#[cfg(feature = "http-sink")]
+pub use webhook_sink::WebhookSink;
+
+use crate::chains::Change;
+use crate::context::Context;
+use crate::runtime::BoxFuture;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Trait for async sinks (files, HTTP endpoints, message brokers, channels, etc)
+#[async_trait]
+pub trait BaseSink<T = Context>: Send + Sync {
+    /// Deliver the final context of a run
+    async fn deliver(&self, ctx: &T) -> std::io::Result<()>;
+    /// Deliver a run of a chain with the change journal enabled (`ChainGeneric::enable_journal`):
+    /// `changes` are the key-level mutations its links made, in order. Sinks that can apply
+    /// deltas override this; the default delivers the full context.
+    fn deliver_changes<'a>(&'a self, ctx: &'a T, changes: &'a [Change]) -> BoxFuture<'a, std::io::Result<()>> {
+        let _ = changes;
+        self.deliver(ctx)
+    }
+    /// Sink name/type
+    fn name(&self) -> &'static str;
+}
+
+pub type SinkObj<T = Context> = Arc<dyn BaseSink<T>>;
+
+// Ergonomic alias, matching ListenerAsync
+pub use self::BaseSink as Sink;
@@ -0,0 +1,236 @@
+//! Noise and generalization for contexts leaving for reporting systems.
+//!
+//! A [`PrivacyPolicy`] lists context fields and what to do with each: add Laplace noise
+//! to a number ([`FieldRule::Laplace`], the mechanism of differential privacy: noise of
+//! scale `sensitivity / epsilon`), round it into buckets, map a category to a coarser group,
+//! keep only the start of a code, or drop the field. Fields are dotted paths
+//! (`user.address.zip`); a path through an array applies to each of its elements.
+//!
+//! Wrap the sinks that export to analytics in a [`PrivacySink`]: it applies the policy to
+//! a copy of each context before delivering it, so the run's result and other sinks keep
+//! the exact values. (Middleware cannot do this: its hooks do not see the context the
+//! sinks receive.) A `PrivacySink` delivers the whole context even to sinks of chains
+//! with a change journal, since the changes carry the raw values.
+//!
+//! Noise comes from the standard library's randomly seeded hasher, which is unpredictable
+//! enough for reporting but not a cryptographic source; [`PrivacyPolicy::with_seed`] makes
+//! it reproducible for tests.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::sinks::{FieldRule, PrivacyPolicy};
+//!
+//! let policy = PrivacyPolicy::new()
+//!     .field("age", FieldRule::Bucket { step: 10.0 })
+//!     .field("zip", FieldRule::Prefix { keep: 3 })
+//!     .field("email", FieldRule::Suppress)
+//!     .field("spend", FieldRule::Laplace { epsilon: 1.0, sensitivity: 10.0 });
+//! let ctx = Context::new().insert("age", 37).insert("zip", "94107").insert("email", "ana@example.com").insert("spend", 120.0);
+//! let private = policy.apply(&ctx);
+//! assert_eq!(private.get::<i64>("age"), Some(30));
+//! assert_eq!(private.get::<String>("zip").as_deref(), Some("941**"));
+//! assert_eq!(private.get::<String>("email"), None);
+//! assert!(private.get::<f64>("spend").is_some());
+//! ```
+
+use crate::chains::retry::random_unit;
+use crate::chains::Change;
+use crate::context::Context;
+use crate::runtime::BoxFuture;
+use crate::sinks::{BaseSink, SinkObj};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// What [`PrivacyPolicy`] does with one field.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum FieldRule {
+    /// Add Laplace noise of scale `sensitivity / epsilon` to a number: `sensitivity` is how
+    /// much one person can change the value, and a smaller `epsilon` means more privacy.
+    /// Integers stay integers.
+    Laplace { epsilon: f64, sensitivity: f64 },
+    /// Round a number down to a multiple of `step` (ages to decades, amounts to hundreds).
+    Bucket { step: f64 },
+    /// Replace a category with its group; values in no group become `other`.
+    Generalize {
+        groups: BTreeMap<String, String>,
+        #[serde(default = "default_other")]
+        other: String,
+    },
+    /// Keep the first `keep` characters of a text and mask the rest with `*`.
+    Prefix { keep: usize },
+    /// Remove the field.
+    Suppress,
+}
+
+fn default_other() -> String {
+    "other".to_string()
+}
+
+impl FieldRule {
+    /// Check the rule's parameters: `epsilon`, `sensitivity`, and `step` must be finite and
+    /// above 0, or the noise could vanish and leak exact values. [`PrivacyPolicy::field`]
+    /// panics on invalid rules, so check rules read from configuration first.
+    pub fn validate(&self) -> Result<(), String> {
+        let positive = |name: &str, value: f64| {
+            if value.is_finite() && value > 0.0 {
+                Ok(())
+            } else {
+                Err(format!("{} must be a finite number above 0, got {}", name, value))
+            }
+        };
+        match self {
+            FieldRule::Laplace { epsilon, sensitivity } => {
+                positive("epsilon", *epsilon)?;
+                positive("sensitivity", *sensitivity)
+            }
+            FieldRule::Bucket { step } => positive("step", *step),
+            FieldRule::Generalize { .. } | FieldRule::Prefix { .. } | FieldRule::Suppress => Ok(()),
+        }
+    }
+}
+
+/// Fields to perturb or generalize, by dotted path; see the [module docs](self).
+#[derive(Debug, Default)]
+pub struct PrivacyPolicy {
+    fields: Vec<(String, FieldRule)>,
+    // splitmix64 state, when seeded
+    seed: Option<Mutex<u64>>,
+}
+
+impl PrivacyPolicy {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Apply `rule` to the field at `path`. Rules apply in the order they were added.
+    /// Panics if `rule` is invalid (see [`FieldRule::validate`]).
+    pub fn field(mut self, path: impl Into<String>, rule: FieldRule) -> Self {
+        let path = path.into();
+        if let Err(e) = rule.validate() {
+            panic!("invalid privacy rule for '{}': {}", path, e);
+        }
+        self.fields.push((path, rule));
+        self
+    }
+    /// Draw noise from a generator seeded with `seed` instead of a random source.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(Mutex::new(seed));
+        self
+    }
+
+    /// A copy of `ctx` with the policy applied.
+    pub fn apply(&self, ctx: &Context) -> Context {
+        let mut value = Value::Object(ctx.clone().into());
+        for (path, rule) in &self.fields {
+            let path: Vec<&str> = path.split('.').collect();
+            self.apply_at(&mut value, &path, rule);
+        }
+        match value {
+            Value::Object(map) => map.into(),
+            _ => Context::new(),
+        }
+    }
+
+    fn apply_at(&self, value: &mut Value, path: &[&str], rule: &FieldRule) {
+        match value {
+            Value::Array(items) => items.iter_mut().for_each(|item| self.apply_at(item, path, rule)),
+            Value::Object(fields) => match path {
+                [] => {}
+                [last] if *rule == FieldRule::Suppress => {
+                    fields.remove(*last);
+                }
+                [last] => {
+                    if let Some(field) = fields.get_mut(*last) {
+                        self.apply_rule(field, rule);
+                    }
+                }
+                [first, rest @ ..] => {
+                    if let Some(field) = fields.get_mut(*first) {
+                        self.apply_at(field, rest, rule);
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn apply_rule(&self, field: &mut Value, rule: &FieldRule) {
+        if let Value::Array(items) = field {
+            return items.iter_mut().for_each(|item| self.apply_rule(item, rule));
+        }
+        *field = match (rule, &*field) {
+            (FieldRule::Laplace { epsilon, sensitivity }, Value::Number(n)) => {
+                let noisy = n.as_f64().unwrap_or_default() + self.laplace(sensitivity / epsilon);
+                number(noisy, n.is_f64())
+            }
+            (FieldRule::Bucket { step }, Value::Number(n)) => {
+                number((n.as_f64().unwrap_or_default() / step).floor() * step, n.is_f64())
+            }
+            (FieldRule::Generalize { groups, other }, Value::String(category)) => {
+                groups.get(category).unwrap_or(other).clone().into()
+            }
+            (FieldRule::Prefix { keep }, Value::String(text)) => {
+                text.chars().enumerate().map(|(i, c)| if i < *keep { c } else { '*' }).collect::<String>().into()
+            }
+            // Values of another type than the rule expects are dropped rather than leaked
+            _ => Value::Null,
+        };
+    }
+
+    // A sample of the Laplace distribution centered on 0 with scale `scale`.
+    fn laplace(&self, scale: f64) -> f64 {
+        let u = self.unit() - 0.5;
+        -scale * u.signum() * (1.0 - 2.0 * u.abs()).max(f64::MIN_POSITIVE).ln()
+    }
+
+    // A number in [0, 1).
+    fn unit(&self) -> f64 {
+        let Some(seed) = &self.seed else { return random_unit() };
+        let mut state = seed.lock().unwrap();
+        *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = *state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        ((z ^ (z >> 31)) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+// `n` as JSON, rounded to an integer unless `float`.
+fn number(n: f64, float: bool) -> Value {
+    if float {
+        serde_json::Number::from_f64(n).map_or(Value::Null, Value::Number)
+    } else {
+        Value::from(n.round() as i64)
+    }
+}
+
+/// Delivers contexts to `inner` with a [`PrivacyPolicy`] applied.
+pub struct PrivacySink {
+    policy: PrivacyPolicy,
+    inner: SinkObj,
+}
+
+impl PrivacySink {
+    pub fn new(policy: PrivacyPolicy, inner: SinkObj) -> Self {
+        PrivacySink { policy, inner }
+    }
+}
+
+#[async_trait]
+impl BaseSink<Context> for PrivacySink {
+    async fn deliver(&self, ctx: &Context) -> std::io::Result<()> {
+        self.inner.deliver(&self.policy.apply(ctx)).await
+    }
+    // The changes hold raw values; deliver the whole, private, context instead.
+    fn deliver_changes<'a>(&'a self, ctx: &'a Context, changes: &'a [Change]) -> BoxFuture<'a, std::io::Result<()>> {
+        let _ = changes;
+        self.deliver(ctx)
+    }
+    fn name(&self) -> &'static str {
+        "privacy"
+    }
+}
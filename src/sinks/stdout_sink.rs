@@ -0,0 +1,19 @@
+use crate::sinks::BaseSink;
+use async_trait::async_trait;
+use serde::Serialize;
+use std::io::Write;
+
+/// Prints each result to stdout as one JSON document per line.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StdoutSink;
+
+#[async_trait]
+impl<T: Serialize + Sync> BaseSink<T> for StdoutSink {
+    async fn deliver(&self, ctx: &T) -> std::io::Result<()> {
+        let line = serde_json::to_string(ctx).map_err(std::io::Error::other)?;
+        writeln!(std::io::stdout().lock(), "{}", line)
+    }
+    fn name(&self) -> &'static str {
+        "stdout"
+    }
+}
@@ -0,0 +1,184 @@
+//! Webhook sink: POST each run result to one or more URLs, signed, with retries.
+//!
+//! Every delivery carries two headers so receivers can check where it came from:
+//! `X-Modulink-Timestamp` (Unix seconds) and `X-Modulink-Signature`, which is
+//! `sha256=<hex>` of the HMAC-SHA256 of `"{timestamp}.{body}"` under the shared secret.
+//! Receivers recompute it with [`signature`] (and should reject old timestamps).
+//!
+//! Failed deliveries (connection errors, timeouts, 429 and 5xx responses) are retried with
+//! the sink's [`RetryPolicy`], exponential backoff by default. Other 4xx responses are not
+//! retried. A delivery that still fails goes to the dead-letter sink, if one is set, as a
+//! [`DeadLetter`] document, and otherwise is a sink error. URLs are delivered to one after
+//! the other, each on its own.
+//!
+//! Sinks are delivered to as a run ends, so retries delay the run's return; keep the policy
+//! short for chains that answer requests.
+//!
+//! Example:
+//! ```rust,no_run
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::sinks::{FileSink, WebhookSink};
+//! use std::sync::Arc;
+//!
+//! let mut chain = Chain::new();
+//! chain.pipe_to(Arc::new(
+//!     WebhookSink::new("https://hooks.example.com/orders")
+//!         .with_secret("s3cret")
+//!         .with_dead_letter(Arc::new(FileSink::new("undelivered.jsonl"))),
+//! ));
+//! ```
+
+use crate::chains::{Backoff, RetryPolicy};
+use crate::runtime::{default_executor, ExecutorObj};
+use crate::sinks::{BaseSink, SinkObj};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const SIGNATURE_HEADER: &str = "X-Modulink-Signature";
+pub const TIMESTAMP_HEADER: &str = "X-Modulink-Timestamp";
+
+/// The `X-Modulink-Signature` value of `body` sent at `timestamp` (Unix seconds).
+pub fn signature(secret: &[u8], timestamp: u64, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    let hex: String = mac.finalize().into_bytes().iter().map(|b| format!("{:02x}", b)).collect();
+    format!("sha256={}", hex)
+}
+
+/// A delivery that failed for good, as given to the dead-letter sink.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeadLetter {
+    pub url: String,
+    pub error: String,
+    pub attempts: u32,
+    /// The run result that was not delivered.
+    pub payload: Value,
+}
+
+/// POSTs each result as signed JSON to webhook URLs; see the [module docs](self).
+pub struct WebhookSink {
+    pub urls: Vec<String>,
+    secret: Option<Vec<u8>>,
+    pub retry: RetryPolicy,
+    /// Time allowed for each attempt.
+    pub timeout: Duration,
+    dead_letter: Option<SinkObj<Value>>,
+    client: reqwest::Client,
+    executor: ExecutorObj,
+}
+
+impl WebhookSink {
+    /// A sink for `url`, unsigned, trying up to 5 times from 500ms apart up to 30s apart.
+    pub fn new(url: impl Into<String>) -> Self {
+        WebhookSink {
+            urls: vec![url.into()],
+            secret: None,
+            retry: RetryPolicy::new(5)
+                .with_backoff(Backoff::Exponential { initial: Duration::from_millis(500), max: Duration::from_secs(30) })
+                .with_jitter(0.2),
+            timeout: Duration::from_secs(10),
+            dead_letter: None,
+            client: reqwest::Client::new(),
+            executor: default_executor(),
+        }
+    }
+    /// Deliver to `url` as well.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.urls.push(url.into());
+        self
+    }
+    /// Sign deliveries with `secret`.
+    pub fn with_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.secret = Some(secret.as_ref().to_vec());
+        self
+    }
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+    /// Hand deliveries that failed for good to `sink` instead of failing.
+    pub fn with_dead_letter(mut self, sink: SinkObj<Value>) -> Self {
+        self.dead_letter = Some(sink);
+        self
+    }
+    /// Executor providing the retry backoff timer (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    // One attempt; `Err((retryable, error))` on failure.
+    async fn post(&self, url: &str, body: &[u8]) -> Result<(), (bool, String)> {
+        let mut request = self.client.post(url).timeout(self.timeout).header("content-type", "application/json");
+        if let Some(secret) = &self.secret {
+            let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+            request = request
+                .header(TIMESTAMP_HEADER, timestamp.to_string())
+                .header(SIGNATURE_HEADER, signature(secret, timestamp, body));
+        }
+        let resp = request.body(body.to_vec()).send().await.map_err(|e| (true, e.to_string()))?;
+        let status = resp.status();
+        if status.is_success() {
+            return Ok(());
+        }
+        let retryable = status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS;
+        Err((retryable, format!("{} responded {}", url, status)))
+    }
+
+    // Deliver to `url` with retries; the failed attempts and last error if it never succeeded.
+    async fn deliver_to(&self, url: &str, body: &[u8]) -> Result<(), (u32, String)> {
+        let max_attempts = self.retry.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            match self.post(url, body).await {
+                Ok(()) => return Ok(()),
+                Err((retryable, error)) if !retryable || attempt >= max_attempts => return Err((attempt, error)),
+                Err((_, error)) => {
+                    tracing::warn!(target: "modulink::webhook", url, attempt, %error, "webhook delivery failed, retrying");
+                    self.executor.sleep(self.retry.jittered_delay(attempt)).await;
+                    attempt += 1;
+                }
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<T: Serialize + Sync> BaseSink<T> for WebhookSink {
+    async fn deliver(&self, ctx: &T) -> std::io::Result<()> {
+        let body = serde_json::to_vec(ctx).map_err(std::io::Error::other)?;
+        let mut failed = Vec::new();
+        for url in &self.urls {
+            let Err((attempts, error)) = self.deliver_to(url, &body).await else { continue };
+            match &self.dead_letter {
+                Some(dead_letter) => {
+                    let payload = serde_json::from_slice(&body).unwrap_or(Value::Null);
+                    let letter = DeadLetter { url: url.clone(), error: error.clone(), attempts, payload };
+                    let letter = serde_json::to_value(letter).map_err(std::io::Error::other)?;
+                    if let Err(e) = dead_letter.deliver(&letter).await {
+                        failed.push(format!("{} (dead letter failed: {})", error, e));
+                    }
+                }
+                None => failed.push(error),
+            }
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(std::io::Error::other(failed.join("; ")))
+        }
+    }
+    fn name(&self) -> &'static str {
+        "webhook"
+    }
+}
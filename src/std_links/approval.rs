@@ -0,0 +1,298 @@
+//! Human-in-the-loop approval.
+//!
+//! [`await_approval`] parks the run at a link until someone approves or rejects it, then
+//! writes the decision into the context under [`APPROVAL_KEY`]
+//! (`{ "id", "approved", "by", "reason" }`), so later branches can route on
+//! `ctx.approval.approved`. While parked, the run is listed by [`Approvals::pending`] with
+//! a summary of selected context keys; decisions arrive through [`Approvals::decide`] or,
+//! with the `http` feature, the routes of [`Approvals::router`]
+//! (`HttpListener::with_approvals`):
+//!
+//! | Route                          | Effect                                   |
+//! |--------------------------------|------------------------------------------|
+//! | `GET /approvals`               | pending approvals                        |
+//! | `GET /approvals/{id}`          | one pending approval                     |
+//! | `POST /approvals/{id}/approve` | approve; optional `{"by", "reason"}` body |
+//! | `POST /approvals/{id}/reject`  | reject; optional `{"by", "reason"}` body  |
+//!
+//! Serve the routes from a listener with `HttpListener::with_jwt`: approvers then need a
+//! valid bearer token carrying the approver role (`approver` in its `roles` claim by
+//! default, see [`Approvals::with_role`]), and the decision is recorded as made by the
+//! token's `sub` claim, whatever the body says. A run cannot be decided by the subject that
+//! started it (its `_auth.sub`). Without a token the body's `by` is taken as given.
+//!
+//! Approval ids are generated when a run parks; the run's request id (`meta::REQUEST_ID`)
+//! is listed alongside for reference. Runs started with `ChainGeneric::run_durable` are
+//! checkpointed before the approval link, so after a restart `ChainGeneric::resume` parks
+//! them again, under a new approval id.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{await_approval, ApprovalDecision, Approvals};
+//! use std::sync::Arc;
+//!
+//! let approvals = Arc::new(Approvals::new());
+//! let mut chain = Chain::new();
+//! chain.add_link(await_approval(approvals.clone(), &["amount"]));
+//!
+//! let approver = approvals.clone();
+//! std::thread::spawn(move || loop {
+//!     if let Some(pending) = approver.pending().pop() {
+//!         approver.decide(&pending.id, ApprovalDecision::approve().by("ops@example.com"));
+//!         break;
+//!     }
+//!     std::thread::sleep(std::time::Duration::from_millis(5));
+//! });
+//! let ctx = futures::executor::block_on(chain.run(Context::new().insert("amount", 900)));
+//! assert_eq!(ctx.query("$.approval.approved").unwrap(), vec![serde_json::json!(true)]);
+//! ```
+
+use crate::chains::RunError;
+use crate::context::{meta, Context};
+use crate::ctx_tools;
+use crate::links::Link;
+use futures::channel::oneshot;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Context key the decision is written to.
+pub const APPROVAL_KEY: &str = "approval";
+
+/// A parked run waiting for a decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PendingApproval {
+    pub id: String,
+    /// The run's request id, if it has one.
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// The `sub` claim the run was started with, if any; it may not decide the approval.
+    #[serde(default)]
+    pub requested_by: Option<String>,
+    /// The context keys selected in `await_approval`, for the approver.
+    pub summary: BTreeMap<String, Value>,
+    pub requested_at_ms: u64,
+}
+
+/// An approve/reject decision and who made it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalDecision {
+    #[serde(default)]
+    pub approved: bool,
+    #[serde(default)]
+    pub by: Option<String>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl ApprovalDecision {
+    pub fn approve() -> Self {
+        ApprovalDecision { approved: true, ..Default::default() }
+    }
+    pub fn reject() -> Self {
+        ApprovalDecision { approved: false, ..Default::default() }
+    }
+    pub fn by(mut self, by: impl Into<String>) -> Self {
+        self.by = Some(by.into());
+        self
+    }
+    pub fn reason(mut self, reason: impl Into<String>) -> Self {
+        self.reason = Some(reason.into());
+        self
+    }
+}
+
+type Parked = (PendingApproval, oneshot::Sender<ApprovalDecision>);
+
+/// Runs parked at approval links, shared with whatever delivers the decisions.
+pub struct Approvals {
+    pending: Mutex<BTreeMap<String, Parked>>,
+    next_id: AtomicU64,
+    role: String,
+}
+
+impl Default for Approvals {
+    fn default() -> Self {
+        Approvals { pending: Mutex::default(), next_id: AtomicU64::new(0), role: "approver".to_string() }
+    }
+}
+
+// Withdraws the pending approval if the run is dropped while parked.
+struct Withdraw<'a> {
+    approvals: &'a Approvals,
+    id: String,
+}
+
+impl Drop for Withdraw<'_> {
+    fn drop(&mut self) {
+        self.approvals.pending.lock().unwrap().remove(&self.id);
+    }
+}
+
+impl Approvals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Require `role` instead of `approver` in the `roles` claim of callers of the routes.
+    pub fn with_role(mut self, role: impl Into<String>) -> Self {
+        self.role = role.into();
+        self
+    }
+    /// Pending approvals, ordered by id.
+    pub fn pending(&self) -> Vec<PendingApproval> {
+        self.pending.lock().unwrap().values().map(|(pending, _)| pending.clone()).collect()
+    }
+    pub fn get(&self, id: &str) -> Option<PendingApproval> {
+        self.pending.lock().unwrap().get(id).map(|(pending, _)| pending.clone())
+    }
+    /// Resume the run parked under `id` with `decision`; `false` if nothing is parked there.
+    pub fn decide(&self, id: &str, decision: ApprovalDecision) -> bool {
+        match self.pending.lock().unwrap().remove(id) {
+            Some((_, resume)) => resume.send(decision).is_ok(),
+            None => false,
+        }
+    }
+
+    // Park under a new id; the decision, or `None` if the approvals were dropped.
+    async fn park(&self, mut pending: PendingApproval) -> (String, Option<ApprovalDecision>) {
+        let (tx, rx) = oneshot::channel();
+        let id = format!("approval-{}", self.next_id.fetch_add(1, Ordering::Relaxed) + 1);
+        pending.id = id.clone();
+        pending.requested_at_ms = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0);
+        self.pending.lock().unwrap().insert(id.clone(), (pending, tx));
+        let _withdraw = Withdraw { approvals: self, id: id.clone() };
+        (id, rx.await.ok())
+    }
+}
+
+/// A link that parks the run until a decision arrives through `approvals`, then writes it
+/// to [`APPROVAL_KEY`]. `summary` selects the context keys shown to approvers.
+pub fn await_approval(approvals: Arc<Approvals>, summary: &[&str]) -> Link {
+    let summary: Vec<String> = summary.iter().map(|key| key.to_string()).collect();
+    Arc::new(move |ctx: Context| {
+        let approvals = approvals.clone();
+        let pending = PendingApproval {
+            id: String::new(),
+            request_id: ctx.get::<String>(meta::REQUEST_ID),
+            requested_by: ctx.get::<Value>(meta::AUTH).and_then(|auth| Some(auth.get("sub")?.as_str()?.to_string())),
+            summary: summary.iter().filter_map(|key| Some((key.clone(), ctx.0.get(key.as_str())?.clone()))).collect(),
+            requested_at_ms: 0,
+        };
+        Box::pin(async move {
+            match approvals.park(pending).await {
+                (id, Some(decision)) => ctx.insert(
+                    APPROVAL_KEY,
+                    serde_json::json!({ "id": id, "approved": decision.approved, "by": decision.by, "reason": decision.reason }),
+                ),
+                (id, None) => {
+                    ctx_tools::fail_run(RunError::internal(format!("approval '{}' was dropped undecided", id)));
+                    ctx
+                }
+            }
+        })
+    })
+}
+
+#[cfg(feature = "http")]
+mod http {
+    use super::{ApprovalDecision, Approvals};
+    use crate::listeners::AuthClaims;
+    use crate::policy::RolePolicy;
+    use axum::extract::{Path, Request, State};
+    use axum::middleware::Next;
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::{get, post};
+    use axum::{Extension, Json, Router};
+    use serde::Deserialize;
+    use std::sync::Arc;
+
+    #[derive(Deserialize, Default)]
+    struct DecisionBody {
+        by: Option<String>,
+        reason: Option<String>,
+    }
+
+    fn error(status: StatusCode, msg: String) -> Response {
+        (status, Json(serde_json::json!({ "error": msg }))).into_response()
+    }
+
+    fn not_found(id: &str) -> Response {
+        error(StatusCode::NOT_FOUND, format!("no pending approval '{}'", id))
+    }
+
+    // Refuses callers whose JWT claims lack the approver role.
+    async fn require_role(State(approvals): State<Arc<Approvals>>, req: Request, next: Next) -> Response {
+        if let Some(AuthClaims(claims)) = req.extensions().get::<AuthClaims>() {
+            if !RolePolicy::new().roles(claims).contains(&approvals.role.as_str()) {
+                return error(StatusCode::FORBIDDEN, format!("deciding approvals requires the '{}' role", approvals.role));
+            }
+        }
+        next.run(req).await
+    }
+
+    async fn approve(
+        State(a): State<Arc<Approvals>>,
+        Path(id): Path<String>,
+        claims: Option<Extension<AuthClaims>>,
+        body: Option<Json<DecisionBody>>,
+    ) -> Response {
+        decide(&a, id, true, claims, body)
+    }
+
+    async fn reject(
+        State(a): State<Arc<Approvals>>,
+        Path(id): Path<String>,
+        claims: Option<Extension<AuthClaims>>,
+        body: Option<Json<DecisionBody>>,
+    ) -> Response {
+        decide(&a, id, false, claims, body)
+    }
+
+    fn decide(approvals: &Approvals, id: String, approved: bool, claims: Option<Extension<AuthClaims>>, body: Option<Json<DecisionBody>>) -> Response {
+        let body = body.map(|Json(body)| body).unwrap_or_default();
+        // An authenticated approver cannot sign as someone else, nor decide their own run
+        let by = match claims {
+            Some(Extension(claims)) => {
+                let requested_by = approvals.get(&id).and_then(|pending| pending.requested_by);
+                if requested_by.is_some() && requested_by.as_deref() == claims.subject() {
+                    return error(StatusCode::FORBIDDEN, format!("approval '{}' cannot be decided by its requester", id));
+                }
+                claims.subject().map(str::to_string)
+            }
+            None => body.by,
+        };
+        let decision = ApprovalDecision { approved, by, reason: body.reason };
+        if approvals.decide(&id, decision.clone()) {
+            Json(decision).into_response()
+        } else {
+            not_found(&id)
+        }
+    }
+
+    impl Approvals {
+        /// HTTP routes listing pending approvals and accepting decisions (see the [module docs](super)).
+        pub fn router(self: Arc<Self>) -> Router {
+            Router::new()
+                .route("/approvals", get(|State(a): State<Arc<Approvals>>| async move { Json(a.pending()) }))
+                .route(
+                    "/approvals/{id}",
+                    get(|State(a): State<Arc<Approvals>>, Path(id): Path<String>| async move {
+                        match a.get(&id) {
+                            Some(pending) => Json(pending).into_response(),
+                            None => not_found(&id),
+                        }
+                    }),
+                )
+                .route("/approvals/{id}/approve", post(approve))
+                .route("/approvals/{id}/reject", post(reject))
+                .layer(axum::middleware::from_fn_with_state(self.clone(), require_role))
+                .with_state(self)
+        }
+    }
+}
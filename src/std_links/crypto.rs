@@ -0,0 +1,132 @@
+//! Hashing, signing, and id links (feature `crypto`).
+//!
+//! - [`sha256`] / [`hmac_sha256`]: digest of the value under one key, as lowercase hex
+//!   under another.
+//! - [`sign_ed25519`] / [`verify_ed25519`]: an ed25519 signature of a value, as hex, and
+//!   whether a hex signature is valid for a value (`true`/`false`).
+//! - [`uuid_v4`] / [`ulid`]: a fresh id under a key.
+//!
+//! Text values are taken as their UTF-8 bytes, any other value as its JSON serialization.
+//! A missing input fails the run with `InvalidInput`. Keys are given as raw bytes when the
+//! link is built, so they never pass through the context.
+//!
+//! Example:
+//! ```rust
+//! # #[cfg(feature = "crypto")] {
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{sha256, ulid};
+//!
+//! let mut chain = Chain::new();
+//! chain.add_link(sha256("body", "digest"));
+//! chain.add_link(ulid("event_id"));
+//! let ctx = futures::executor::block_on(chain.run(Context::new().insert("body", "abc")));
+//! assert_eq!(ctx.get::<String>("digest").unwrap(), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+//! assert_eq!(ctx.get::<String>("event_id").unwrap().len(), 26);
+//! # }
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::links::Link;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(&text[i..i + 2], 16).ok()).collect()
+}
+
+fn bytes(value: &Value) -> Vec<u8> {
+    match value {
+        Value::String(text) => text.as_bytes().to_vec(),
+        other => serde_json::to_vec(other).unwrap_or_default(),
+    }
+}
+
+// A link inserting `output(ctx, input)` under `into`, where `input` is the value under `from`.
+fn derive_link<F>(from: &str, into: &str, output: F) -> Link
+where
+    F: Fn(&Context, &Value) -> Value + Send + Sync + 'static,
+{
+    let (from, into, output) = (from.to_string(), into.to_string(), Arc::new(output));
+    Arc::new(move |ctx: Context| {
+        let (from, into, output) = (from.clone(), into.clone(), output.clone());
+        Box::pin(async move {
+            let Some(input) = ctx.get::<Value>(&from) else {
+                ctx_tools::fail_run(RunError::invalid_input(format!("nothing to read under '{}'", from)));
+                return ctx;
+            };
+            let value = output(&ctx, &input);
+            ctx.insert(into.as_str(), value)
+        })
+    })
+}
+
+// A link inserting `id()` under `into`.
+fn id_link(into: &str, id: fn() -> String) -> Link {
+    let into = into.to_string();
+    Arc::new(move |ctx: Context| {
+        let into = into.clone();
+        Box::pin(async move { ctx.insert(into.as_str(), id()) })
+    })
+}
+
+/// A link inserting the SHA-256 of the value under `from`, as hex, under `into`.
+pub fn sha256(from: &str, into: &str) -> Link {
+    derive_link(from, into, |_, input| Value::from(hex(&Sha256::digest(bytes(input)))))
+}
+
+/// A link inserting the HMAC-SHA256 of the value under `from` with `secret`, as hex,
+/// under `into`.
+pub fn hmac_sha256(secret: &[u8], from: &str, into: &str) -> Link {
+    let mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC takes keys of any length");
+    derive_link(from, into, move |_, input| {
+        let mut mac = mac.clone();
+        mac.update(&bytes(input));
+        Value::from(hex(&mac.finalize().into_bytes()))
+    })
+}
+
+/// A link signing the value under `from` with the ed25519 key whose 32-byte seed is
+/// `secret_key`, inserting the signature, as hex, under `into`.
+pub fn sign_ed25519(secret_key: &[u8; 32], from: &str, into: &str) -> Link {
+    let key = SigningKey::from_bytes(secret_key);
+    derive_link(from, into, move |_, input| Value::from(hex(&key.sign(&bytes(input)).to_bytes())))
+}
+
+/// A link checking the hex signature under `signature` against the value under `from`
+/// and the ed25519 `public_key`, inserting `true` or `false` under `into`. A missing or
+/// malformed signature is not valid. Fails when `public_key` is not a valid key.
+pub fn verify_ed25519(public_key: &[u8; 32], from: &str, signature: &str, into: &str) -> Result<Link, String> {
+    let key = VerifyingKey::from_bytes(public_key).map_err(|e| e.to_string())?;
+    let signature = signature.to_string();
+    Ok(derive_link(from, into, move |ctx, input| {
+        let valid = ctx
+            .get::<String>(&signature)
+            .and_then(|sig| unhex(&sig))
+            .and_then(|sig| Signature::from_slice(&sig).ok())
+            .is_some_and(|sig| key.verify(&bytes(input), &sig).is_ok());
+        Value::from(valid)
+    }))
+}
+
+/// A link inserting a random (v4) UUID under `into`.
+pub fn uuid_v4(into: &str) -> Link {
+    id_link(into, || uuid::Uuid::new_v4().to_string())
+}
+
+/// A link inserting a new ULID (sortable by creation time) under `into`.
+pub fn ulid(into: &str) -> Link {
+    id_link(into, || ::ulid::Ulid::new().to_string())
+}
@@ -0,0 +1,115 @@
+//! Email notifications over SMTP (feature `email`).
+//!
+//! [`notify_email`] sends an [`EmailNotification`] through a [`Mailer`] when the run
+//! reaches it. Recipients, subject, and body are templates filled from the context (see
+//! [`super::template`]), so `"Order {order_id} failed"` names the run's order. The context
+//! passes through unchanged. A context missing a template field, or an address that does
+//! not parse, fails the run with `InvalidInput`; a send the server refuses fails it with
+//! `Internal` (give the link a retry policy to try again).
+//!
+//! Example:
+//! ```rust,no_run
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::std_links::{notify_email, EmailNotification, Mailer};
+//! use std::sync::Arc;
+//!
+//! let mailer = Arc::new(Mailer::relay("smtp.example.com", "alerts", "app-password").unwrap());
+//! let mut chain = Chain::new();
+//! chain.add_link(notify_email(
+//!     mailer,
+//!     EmailNotification::new("alerts@example.com", "{owner_email}", "Order {order_id} needs review", "Total: {total}"),
+//! ));
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::links::Link;
+use crate::std_links::template;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::sync::Arc;
+
+/// An SMTP connection setup, shared by the links sending through it.
+pub struct Mailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+}
+
+impl Mailer {
+    /// Send through `host` on the submission port, over TLS, logged in as `username`.
+    pub fn relay(host: &str, username: impl Into<String>, password: impl Into<String>) -> Result<Self, String> {
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+            .map_err(|e| format!("SMTP relay '{}': {}", host, e))?
+            .credentials(Credentials::new(username.into(), password.into()))
+            .build();
+        Ok(Mailer { transport })
+    }
+    /// Send through `host:port` unencrypted and without login, e.g. a local relay or a
+    /// test server.
+    pub fn unencrypted(host: &str, port: u16) -> Self {
+        Mailer { transport: AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host).port(port).build() }
+    }
+    /// A mailer over a transport configured with lettre directly.
+    pub fn from_transport(transport: AsyncSmtpTransport<Tokio1Executor>) -> Self {
+        Mailer { transport }
+    }
+}
+
+/// A plain-text email whose fields are context templates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailNotification {
+    pub from: String,
+    pub to: Vec<String>,
+    pub subject: String,
+    pub body: String,
+}
+
+impl EmailNotification {
+    pub fn new(from: impl Into<String>, to: impl Into<String>, subject: impl Into<String>, body: impl Into<String>) -> Self {
+        EmailNotification { from: from.into(), to: vec![to.into()], subject: subject.into(), body: body.into() }
+    }
+    /// Send to `to` as well.
+    pub fn with_to(mut self, to: impl Into<String>) -> Self {
+        self.to.push(to.into());
+        self
+    }
+
+    // The message for the run with `ctx`.
+    fn render(&self, ctx: &Context) -> Result<Message, String> {
+        let mailbox = |field: &str| -> Result<Mailbox, String> {
+            let address = template::render(field, ctx)?;
+            address.parse().map_err(|e| format!("bad email address '{}': {}", address, e))
+        };
+        let mut builder = Message::builder()
+            .from(mailbox(&self.from)?)
+            .subject(template::render(&self.subject, ctx)?)
+            .header(ContentType::TEXT_PLAIN);
+        for to in &self.to {
+            builder = builder.to(mailbox(to)?);
+        }
+        builder.body(template::render(&self.body, ctx)?).map_err(|e| e.to_string())
+    }
+}
+
+/// A link sending `email` through `mailer`; see the [module docs](self).
+pub fn notify_email(mailer: Arc<Mailer>, email: EmailNotification) -> Link {
+    Arc::new(move |ctx: Context| {
+        let mailer = mailer.clone();
+        let message = email.render(&ctx);
+        Box::pin(async move {
+            let message = match message {
+                Ok(message) => message,
+                Err(msg) => {
+                    ctx_tools::fail_run(RunError::invalid_input(msg));
+                    return ctx;
+                }
+            };
+            if let Err(e) = mailer.transport.send(message).await {
+                ctx_tools::fail_run(RunError::internal(format!("sending email failed: {}", e)));
+            }
+            ctx
+        })
+    })
+}
@@ -0,0 +1,242 @@
+//! Waiting for external events (webhooks, callbacks, queue messages).
+//!
+//! [`await_event`] suspends a run until an event with a matching key is delivered through
+//! [`Events`], then merges the event payload into the context: the fields of an object
+//! payload are inserted at the top level, any other payload under [`EVENT_KEY`]. Reserved
+//! fields (`_auth`, `_tenant`, anything starting with `_`, see `context::meta`) are dropped
+//! from payloads: whoever sends an event cannot change the run's metadata.
+//!
+//! The key is a template filled from the context (see [`super::template`]), so each run
+//! waits for its own event: `await_event(events, "payment:{order_id}")` waits for
+//! `payment:o-17` in the run whose `order_id` is `"o-17"`.
+//!
+//! In a durable run (`ChainGeneric::run_durable`) the run is checkpointed and reported as
+//! `RunStatus::AwaitingEvent`; nothing is held in memory. After `Events::set_chain`,
+//! [`Events::deliver`] finds those checkpoints and resumes them. Other runs wait in memory.
+//! Events are not stored: one delivered before any run waits for its key is dropped.
+//!
+//! Events arrive through [`Events::deliver`], the handler link of [`Events::deliver_link`]
+//! (for any listener, e.g. a Kafka topic of callbacks), or with the `http` feature
+//! `POST /events/{key}` from [`Events::router`] (`HttpListener::with_events`), whose JSON
+//! body is the payload. Serve the route from a listener with `HttpListener::with_jwt` so
+//! only authenticated callers can deliver events.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{await_event, Events};
+//! use serde_json::json;
+//! use std::sync::Arc;
+//!
+//! let events = Arc::new(Events::new());
+//! let mut chain = Chain::new();
+//! chain.add_link(await_event(events.clone(), "payment:{order_id}"));
+//!
+//! let notifier = events.clone();
+//! std::thread::spawn(move || loop {
+//!     let delivered = futures::executor::block_on(notifier.deliver("payment:o-17", json!({ "paid": true }))).unwrap();
+//!     if delivered > 0 {
+//!         break;
+//!     }
+//!     std::thread::sleep(std::time::Duration::from_millis(5));
+//! });
+//! let ctx = futures::executor::block_on(chain.run(Context::new().insert("order_id", "o-17")));
+//! assert_eq!(ctx.get::<bool>("paid"), Some(true));
+//! ```
+
+use crate::chains::{Chain, RunError, RunScope, RunStatus};
+use crate::context::{meta, Context};
+use crate::ctx_tools;
+use crate::links::Link;
+use crate::std_links::template;
+use futures::channel::oneshot;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Context key a non-object event payload is written to.
+pub const EVENT_KEY: &str = "event";
+
+type Waiter = (u64, oneshot::Sender<Value>);
+
+/// Delivers events to the runs waiting for them; see the [module docs](self).
+#[derive(Default)]
+pub struct Events {
+    waiters: Mutex<HashMap<String, Vec<Waiter>>>,
+    next_serial: AtomicU64,
+    chain: Mutex<Option<Arc<Chain>>>,
+}
+
+// Removes the waiter if the run is dropped while waiting.
+struct Withdraw<'a> {
+    events: &'a Events,
+    key: String,
+    serial: u64,
+}
+
+impl Drop for Withdraw<'_> {
+    fn drop(&mut self) {
+        let mut waiters = self.events.waiters.lock().unwrap();
+        if let Some(list) = waiters.get_mut(&self.key) {
+            list.retain(|(serial, _)| *serial != self.serial);
+            if list.is_empty() {
+                waiters.remove(&self.key);
+            }
+        }
+    }
+}
+
+fn merge_value<M: Extend<(String, Value)>>(ctx: &mut M, payload: Value) {
+    match payload {
+        Value::Object(fields) => {
+            ctx.extend(fields.into_iter().filter(|(key, _)| !meta::is_reserved(key)))
+        }
+        other => ctx.extend([(EVENT_KEY.to_string(), other)]),
+    }
+}
+
+impl Events {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Also resume durable runs of `chain` waiting for delivered events (set once the
+    /// chain holding the `await_event` links is built).
+    pub fn set_chain(&self, chain: Arc<Chain>) {
+        *self.chain.lock().unwrap() = Some(chain);
+    }
+    /// Keys that in-memory runs are waiting for.
+    pub fn waiting(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.waiters.lock().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Deliver `payload` to every run waiting for `key`; returns how many runs it resumed.
+    /// Durable runs are resumed in the background on the chain's executor.
+    pub async fn deliver(&self, key: &str, payload: Value) -> std::io::Result<usize> {
+        let waiting = self.waiters.lock().unwrap().remove(key).unwrap_or_default();
+        let mut resumed = waiting.into_iter().map(|(_, tx)| tx.send(payload.clone()).is_ok()).filter(|sent| *sent).count();
+        let Some(chain) = self.chain.lock().unwrap().clone() else { return Ok(resumed) };
+        let Some(store) = chain.checkpoint_store() else { return Ok(resumed) };
+        for mut checkpoint in store.awaiting(key).await? {
+            if let Value::Object(ctx) = &mut checkpoint.ctx {
+                merge_value(ctx, payload.clone());
+            }
+            checkpoint.awaiting_event = None;
+            store.save(&checkpoint).await?;
+            let chain = chain.clone();
+            chain.executor().clone().spawn(Box::pin(async move {
+                let run_id = checkpoint.run_id.clone();
+                match chain.resume_from(checkpoint).await {
+                    Ok((_, report)) => {
+                        if let RunStatus::Failed(err) = &report.status {
+                            tracing::warn!(run_id = %run_id, error = %err, "resumed run failed");
+                        }
+                    }
+                    Err(e) => tracing::warn!(run_id = %run_id, error = %e, "resuming run on event failed"),
+                }
+            }));
+            resumed += 1;
+        }
+        Ok(resumed)
+    }
+
+    /// A handler link for listeners carrying events as messages: the key is read from
+    /// `key_field` and the rest of the context is the payload. Inserts `resumed` (the
+    /// number of runs resumed) into the context.
+    pub fn deliver_link(self: Arc<Self>, key_field: &str) -> Link {
+        let key_field = key_field.to_string();
+        Arc::new(move |ctx: Context| {
+            let events = self.clone();
+            let key_field = key_field.clone();
+            Box::pin(async move {
+                let Some(key) = ctx.get::<String>(&key_field) else {
+                    ctx_tools::fail_run(RunError::invalid_input(format!("event message has no '{}'", key_field)));
+                    return ctx;
+                };
+                let mut payload: serde_json::Map<String, Value> = ctx.clone().into();
+                payload.remove(&key_field);
+                match events.deliver(&key, Value::Object(payload)).await {
+                    Ok(resumed) => ctx.insert("resumed", resumed),
+                    Err(e) => {
+                        ctx_tools::fail_run(RunError::internal(format!("delivering event '{}' failed: {}", key, e)));
+                        ctx
+                    }
+                }
+            })
+        })
+    }
+
+    async fn wait(&self, key: String) -> Option<Value> {
+        let (tx, rx) = oneshot::channel();
+        let serial = self.next_serial.fetch_add(1, Ordering::Relaxed);
+        self.waiters.lock().unwrap().entry(key.clone()).or_default().push((serial, tx));
+        let _withdraw = Withdraw { events: self, key, serial };
+        rx.await.ok()
+    }
+}
+
+/// A link that waits for the event whose key is `key` filled from the context, then merges
+/// its payload into the context; see the [module docs](self).
+pub fn await_event(events: Arc<Events>, key: &str) -> Link {
+    let template = key.to_string();
+    Arc::new(move |ctx: Context| {
+        let events = events.clone();
+        let key = template::render(&template, &ctx);
+        Box::pin(async move {
+            let key = match key {
+                Ok(key) => key,
+                Err(msg) => {
+                    ctx_tools::fail_run(RunError::invalid_input(msg));
+                    return ctx;
+                }
+            };
+            if let Some(scope) = RunScope::current().filter(|scope| scope.is_durable()) {
+                scope.await_event(key);
+                return ctx;
+            }
+            match events.wait(key.clone()).await {
+                Some(payload) => {
+                    let mut ctx = ctx;
+                    merge_value(&mut ctx.0, payload);
+                    ctx
+                }
+                None => {
+                    ctx_tools::fail_run(RunError::internal(format!("stopped waiting for event '{}'", key)));
+                    ctx
+                }
+            }
+        })
+    })
+}
+
+#[cfg(feature = "http")]
+mod http {
+    use super::Events;
+    use axum::extract::{Path, State};
+    use axum::http::StatusCode;
+    use axum::response::{IntoResponse, Response};
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::Arc;
+
+    async fn deliver(State(events): State<Arc<Events>>, Path(key): Path<String>, body: Option<Json<Value>>) -> Response {
+        let payload = body.map(|Json(body)| body).unwrap_or(Value::Null);
+        match events.deliver(&key, payload).await {
+            Ok(0) => (StatusCode::NOT_FOUND, Json(json!({ "error": format!("no run is waiting for '{}'", key) }))).into_response(),
+            Ok(resumed) => Json(json!({ "resumed": resumed })).into_response(),
+            Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, Json(json!({ "error": e.to_string() }))).into_response(),
+        }
+    }
+
+    impl Events {
+        /// `POST /events/{key}`: deliver the JSON body to runs waiting for `key`
+        /// (404 when none is).
+        pub fn router(self: Arc<Self>) -> Router {
+            Router::new().route("/events/{key}", post(deliver)).with_state(self)
+        }
+    }
+}
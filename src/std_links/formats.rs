@@ -0,0 +1,243 @@
+//! Converting XML and YAML text in the context to and from structured values (features
+//! `xml`, `yaml`).
+//!
+//! [`parse_yaml`] / [`parse_xml`] read the text under one key and insert the parsed value
+//! under another; [`to_yaml`] / [`to_xml`] do the reverse. YAML maps onto JSON values
+//! directly. XML is mapped as:
+//! - the document becomes an object with one key, the root element's name;
+//! - an element with only text becomes that text (XML has no types, so every value is a
+//!   string), an empty one becomes `null`;
+//! - any other element becomes an object: attributes as `"@name"`, child elements by name
+//!   (an array when a name repeats), and text, if any, as `"#text"`.
+//!
+//! [`to_xml`] writes the same shape back, so `{"order": {"@id": "7", "item": ["a", "b"]}}`
+//! becomes `<order id="7"><item>a</item><item>b</item></order>`. Numbers and booleans are
+//! written as text.
+//!
+//! A missing key, text that does not parse, or a value that has no XML form (anything but
+//! an object with a single key) fails the run with `InvalidInput`.
+//!
+//! Example:
+//! ```rust
+//! # #[cfg(feature = "xml")] {
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::parse_xml;
+//! use serde_json::{json, Value};
+//!
+//! let mut chain = Chain::new();
+//! chain.add_link(parse_xml("body", "order"));
+//! let ctx = futures::executor::block_on(chain.run(Context::new().insert("body", r#"<order id="7"><sku>A-1</sku></order>"#)));
+//! assert_eq!(ctx.get::<Value>("order"), Some(json!({ "order": { "@id": "7", "sku": "A-1" } })));
+//! # }
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::links::Link;
+use serde_json::Value;
+use std::sync::Arc;
+
+type Convert = fn(Value) -> Result<Value, String>;
+
+// A link converting the value under `from` with `convert` and inserting the result under `into`.
+fn convert_link(from: &str, into: &str, convert: Convert) -> Link {
+    let from = from.to_string();
+    let into = into.to_string();
+    Arc::new(move |ctx: Context| {
+        let from = from.clone();
+        let into = into.clone();
+        Box::pin(async move {
+            let Some(value) = ctx.get::<Value>(&from) else {
+                ctx_tools::fail_run(RunError::invalid_input(format!("nothing to convert under '{}'", from)));
+                return ctx;
+            };
+            match convert(value) {
+                Ok(value) => ctx.insert(into.as_str(), value),
+                Err(msg) => {
+                    ctx_tools::fail_run(RunError::invalid_input(format!("converting '{}' failed: {}", from, msg)));
+                    ctx
+                }
+            }
+        })
+    })
+}
+
+fn text(value: Value) -> Result<String, String> {
+    match value {
+        Value::String(text) => Ok(text),
+        other => Err(format!("expected text, found {}", other)),
+    }
+}
+
+/// A link parsing the YAML text under `from` and inserting the value under `into`.
+#[cfg(feature = "yaml")]
+pub fn parse_yaml(from: &str, into: &str) -> Link {
+    convert_link(from, into, |value| serde_yaml::from_str(&text(value)?).map_err(|e| e.to_string()))
+}
+
+/// A link writing the value under `from` as YAML text under `into`.
+#[cfg(feature = "yaml")]
+pub fn to_yaml(from: &str, into: &str) -> Link {
+    convert_link(from, into, |value| serde_yaml::to_string(&value).map(Value::String).map_err(|e| e.to_string()))
+}
+
+/// A link parsing the XML text under `from` and inserting the value under `into`; see the
+/// [module docs](self) for the shape.
+#[cfg(feature = "xml")]
+pub fn parse_xml(from: &str, into: &str) -> Link {
+    convert_link(from, into, |value| xml::parse(&text(value)?))
+}
+
+/// A link writing the value under `from` as XML text under `into`; see the
+/// [module docs](self) for the shape.
+#[cfg(feature = "xml")]
+pub fn to_xml(from: &str, into: &str) -> Link {
+    convert_link(from, into, |value| xml::write(&value).map(Value::String))
+}
+
+#[cfg(feature = "xml")]
+mod xml {
+    use quick_xml::events::{BytesEnd, BytesStart, BytesText, Event};
+    use quick_xml::{Reader, Writer};
+    use serde_json::{Map, Value};
+
+    // An element being read: its name, attributes and children so far, and its text.
+    struct Open {
+        name: String,
+        fields: Map<String, Value>,
+        text: String,
+    }
+
+    impl Open {
+        fn new(start: &BytesStart) -> Result<Self, String> {
+            let mut fields = Map::new();
+            for attr in start.attributes() {
+                let attr = attr.map_err(|e| e.to_string())?;
+                let value = attr.unescape_value().map_err(|e| e.to_string())?;
+                fields.insert(format!("@{}", String::from_utf8_lossy(attr.key.as_ref())), Value::from(value.into_owned()));
+            }
+            Ok(Open { name: String::from_utf8_lossy(start.name().as_ref()).into_owned(), fields, text: String::new() })
+        }
+
+        fn close(self) -> (String, Value) {
+            let Open { name, mut fields, text } = self;
+            let value = match (fields.is_empty(), text.is_empty()) {
+                (true, true) => Value::Null,
+                (true, false) => Value::String(text),
+                (false, _) => {
+                    if !text.is_empty() {
+                        fields.insert("#text".to_string(), Value::String(text));
+                    }
+                    Value::Object(fields)
+                }
+            };
+            (name, value)
+        }
+
+        fn add_child(&mut self, name: String, value: Value) {
+            match self.fields.get_mut(&name) {
+                None => {
+                    self.fields.insert(name, value);
+                }
+                Some(Value::Array(items)) => items.push(value),
+                Some(existing) => *existing = Value::Array(vec![existing.take(), value]),
+            }
+        }
+    }
+
+    pub(super) fn parse(text: &str) -> Result<Value, String> {
+        let mut reader = Reader::from_str(text);
+        reader.config_mut().trim_text(true);
+        let mut open: Vec<Open> = Vec::new();
+        let mut root = None;
+        loop {
+            let closed = match reader.read_event().map_err(|e| e.to_string())? {
+                Event::Start(start) => {
+                    open.push(Open::new(&start)?);
+                    None
+                }
+                Event::Empty(start) => Some(Open::new(&start)?.close()),
+                Event::End(_) => open.pop().map(Open::close),
+                Event::Text(t) => {
+                    let t = t.unescape().map_err(|e| e.to_string())?;
+                    open.last_mut().ok_or("text outside the root element")?.text.push_str(&t);
+                    None
+                }
+                Event::CData(t) => {
+                    let t = String::from_utf8_lossy(&t.into_inner()).into_owned();
+                    open.last_mut().ok_or("text outside the root element")?.text.push_str(&t);
+                    None
+                }
+                Event::Eof => break,
+                _ => None,
+            };
+            if let Some((name, value)) = closed {
+                match open.last_mut() {
+                    Some(parent) => parent.add_child(name, value),
+                    None if root.is_none() => root = Some(Value::Object(Map::from_iter([(name, value)]))),
+                    None => return Err("more than one root element".to_string()),
+                }
+            }
+        }
+        if !open.is_empty() {
+            return Err(format!("unclosed element <{}>", open[open.len() - 1].name));
+        }
+        root.ok_or_else(|| "no root element".to_string())
+    }
+
+    pub(super) fn write(value: &Value) -> Result<String, String> {
+        let root = match value {
+            Value::Object(map) if map.len() == 1 => map.iter().next().unwrap(),
+            _ => return Err("expected an object with a single key, the root element".to_string()),
+        };
+        let mut writer = Writer::new(Vec::new());
+        write_element(&mut writer, root.0, root.1)?;
+        String::from_utf8(writer.into_inner()).map_err(|e| e.to_string())
+    }
+
+    fn scalar(value: &Value) -> Option<String> {
+        match value {
+            Value::String(s) => Some(s.clone()),
+            Value::Number(n) => Some(n.to_string()),
+            Value::Bool(b) => Some(b.to_string()),
+            _ => None,
+        }
+    }
+
+    fn write_element(writer: &mut Writer<Vec<u8>>, name: &str, value: &Value) -> Result<(), String> {
+        let io = |e: std::io::Error| e.to_string();
+        match value {
+            Value::Array(items) => items.iter().try_for_each(|item| write_element(writer, name, item)),
+            Value::Null => writer.write_event(Event::Empty(BytesStart::new(name))).map_err(io),
+            Value::Object(fields) => {
+                let mut start = BytesStart::new(name);
+                for (key, value) in fields.iter().filter_map(|(k, v)| Some((k.strip_prefix('@')?, v))) {
+                    let value = scalar(value).ok_or_else(|| format!("attribute '{}' of <{}> is not a scalar", key, name))?;
+                    start.push_attribute((key, value.as_str()));
+                }
+                let children: Vec<_> = fields.iter().filter(|(k, _)| !k.starts_with('@')).collect();
+                if children.is_empty() {
+                    return writer.write_event(Event::Empty(start)).map_err(io);
+                }
+                writer.write_event(Event::Start(start)).map_err(io)?;
+                for (key, value) in children {
+                    if key == "#text" {
+                        let text = scalar(value).ok_or_else(|| format!("text of <{}> is not a scalar", name))?;
+                        writer.write_event(Event::Text(BytesText::new(&text))).map_err(io)?;
+                    } else {
+                        write_element(writer, key, value)?;
+                    }
+                }
+                writer.write_event(Event::End(BytesEnd::new(name))).map_err(io)
+            }
+            scalar_value => {
+                let text = scalar(scalar_value).unwrap_or_default();
+                writer.write_event(Event::Start(BytesStart::new(name))).map_err(io)?;
+                writer.write_event(Event::Text(BytesText::new(&text))).map_err(io)?;
+                writer.write_event(Event::End(BytesEnd::new(name))).map_err(io)
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+//! Unary gRPC calls.
+//!
+//! [`grpc_call`] builds a request message from context keys, calls a unary method through
+//! a [`GrpcClient`], and merges the response back: the fields of the response are inserted
+//! at the top level, or the whole response under one key with [`GrpcCall::response_into`].
+//!
+//! Messages travel as JSON objects (proto3 JSON field names), so the library does not pull
+//! a gRPC stack into every build. Implement [`GrpcClient`] over the client of your choice:
+//! - generated stubs: register each method in [`GrpcStubs`] with a closure calling the
+//!   tonic client; the messages must derive serde (prost-build
+//!   `type_attribute(".", "#[derive(serde::Serialize, serde::Deserialize)]")`).
+//! - dynamic calls: transcode with a descriptor set (e.g. `prost-reflect`'s
+//!   `DynamicMessage`) and send through a generic tonic codec.
+//!
+//! A context missing a mapped key fails the run with `InvalidInput`. Failed calls fail it
+//! with the `ErrorKind` closest to the gRPC status code (`UNAUTHENTICATED` is
+//! `Unauthorized`, `INVALID_ARGUMENT` is `InvalidInput`, and so on; see [`GrpcCode`]).
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{grpc_call, GrpcCall, GrpcStubs};
+//! use serde::{Deserialize, Serialize};
+//! use std::sync::Arc;
+//!
+//! #[derive(Deserialize)]
+//! struct GetUserRequest { id: String }
+//! #[derive(Serialize)]
+//! struct User { name: String }
+//!
+//! // Stands in for `client.clone().get_user(req).await.map(|r| r.into_inner())`
+//! let stubs = GrpcStubs::new().method("/users.v1.Users/GetUser", |req: GetUserRequest| async move {
+//!     Ok(User { name: format!("user {}", req.id) })
+//! });
+//! let mut chain = Chain::new();
+//! chain.add_link(grpc_call(Arc::new(stubs), GrpcCall::new("/users.v1.Users/GetUser").field_as("user_id", "id")));
+//!
+//! let ctx = futures::executor::block_on(chain.run(Context::new().insert("user_id", "u-1")));
+//! assert_eq!(ctx.get::<String>("name").as_deref(), Some("user u-1"));
+//! ```
+
+use crate::chains::{ErrorKind, RunError};
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::links::Link;
+use crate::runtime::BoxFuture;
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+/// gRPC status codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GrpcCode {
+    Ok = 0,
+    Cancelled = 1,
+    Unknown = 2,
+    InvalidArgument = 3,
+    DeadlineExceeded = 4,
+    NotFound = 5,
+    AlreadyExists = 6,
+    PermissionDenied = 7,
+    ResourceExhausted = 8,
+    FailedPrecondition = 9,
+    Aborted = 10,
+    OutOfRange = 11,
+    Unimplemented = 12,
+    Internal = 13,
+    Unavailable = 14,
+    DataLoss = 15,
+    Unauthenticated = 16,
+}
+
+impl GrpcCode {
+    /// The code numbered `code`; `Unknown` for numbers outside the standard set.
+    pub fn from_i32(code: i32) -> Self {
+        use GrpcCode::*;
+        const CODES: [GrpcCode; 17] = [
+            Ok, Cancelled, Unknown, InvalidArgument, DeadlineExceeded, NotFound, AlreadyExists, PermissionDenied,
+            ResourceExhausted, FailedPrecondition, Aborted, OutOfRange, Unimplemented, Internal, Unavailable,
+            DataLoss, Unauthenticated,
+        ];
+        usize::try_from(code).ok().and_then(|i| CODES.get(i).copied()).unwrap_or(Unknown)
+    }
+
+    /// The run error kind a call failing with this code fails the run with.
+    pub fn error_kind(self) -> ErrorKind {
+        match self {
+            GrpcCode::Unauthenticated => ErrorKind::Unauthorized,
+            GrpcCode::PermissionDenied => ErrorKind::Forbidden,
+            GrpcCode::InvalidArgument | GrpcCode::FailedPrecondition | GrpcCode::OutOfRange => ErrorKind::InvalidInput,
+            GrpcCode::ResourceExhausted => ErrorKind::LimitExceeded,
+            _ => ErrorKind::Internal,
+        }
+    }
+}
+
+/// A failed call: its status code and message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrpcStatus {
+    pub code: GrpcCode,
+    pub message: String,
+}
+
+impl GrpcStatus {
+    pub fn new(code: GrpcCode, message: impl Into<String>) -> Self {
+        GrpcStatus { code, message: message.into() }
+    }
+}
+
+impl fmt::Display for GrpcStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for GrpcStatus {}
+
+/// Makes unary gRPC calls with JSON messages; see the [module docs](self).
+#[async_trait]
+pub trait GrpcClient: Send + Sync {
+    /// Call `method` (the full path, `/package.Service/Method`) with `request`.
+    async fn call(&self, method: &str, request: Value) -> Result<Value, GrpcStatus>;
+}
+
+type Stub = Arc<dyn Fn(Value) -> BoxFuture<'static, Result<Value, GrpcStatus>> + Send + Sync>;
+
+/// A [`GrpcClient`] over generated stubs, one closure per method.
+#[derive(Default, Clone)]
+pub struct GrpcStubs {
+    methods: HashMap<String, Stub>,
+}
+
+impl GrpcStubs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Serve `method` with `call`, converting its messages from and to JSON.
+    pub fn method<Req, Resp, F, Fut>(mut self, method: impl Into<String>, call: F) -> Self
+    where
+        Req: DeserializeOwned + Send + 'static,
+        Resp: Serialize,
+        F: Fn(Req) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Resp, GrpcStatus>> + Send + 'static,
+    {
+        let call = Arc::new(call);
+        let stub: Stub = Arc::new(move |request: Value| {
+            let call = call.clone();
+            Box::pin(async move {
+                let request = serde_json::from_value(request).map_err(|e| GrpcStatus::new(GrpcCode::InvalidArgument, e.to_string()))?;
+                let response = call(request).await?;
+                serde_json::to_value(response).map_err(|e| GrpcStatus::new(GrpcCode::Internal, e.to_string()))
+            })
+        });
+        self.methods.insert(method.into(), stub);
+        self
+    }
+}
+
+#[async_trait]
+impl GrpcClient for GrpcStubs {
+    async fn call(&self, method: &str, request: Value) -> Result<Value, GrpcStatus> {
+        let stub = self.methods.get(method).cloned();
+        match stub {
+            Some(stub) => stub(request).await,
+            None => Err(GrpcStatus::new(GrpcCode::Unimplemented, format!("no stub for {}", method))),
+        }
+    }
+}
+
+/// What [`grpc_call`] calls, and how its messages map to the context.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrpcCall {
+    pub method: String,
+    /// (context key, request field) pairs; dotted fields (`user.id`) build nested messages.
+    pub fields: Vec<(String, String)>,
+    pub response_key: Option<String>,
+}
+
+impl GrpcCall {
+    pub fn new(method: impl Into<String>) -> Self {
+        GrpcCall { method: method.into(), fields: Vec::new(), response_key: None }
+    }
+    /// Send context key `key` as the request field of the same name.
+    pub fn field(self, key: impl Into<String>) -> Self {
+        let key = key.into();
+        self.field_as(key.clone(), key)
+    }
+    /// Send context key `key` as request field `field`.
+    pub fn field_as(mut self, key: impl Into<String>, field: impl Into<String>) -> Self {
+        self.fields.push((key.into(), field.into()));
+        self
+    }
+    /// Insert the whole response under `key` instead of merging its fields.
+    pub fn response_into(mut self, key: impl Into<String>) -> Self {
+        self.response_key = Some(key.into());
+        self
+    }
+
+    fn request(&self, ctx: &Context) -> Result<Value, String> {
+        let mut request = Map::new();
+        for (key, field) in &self.fields {
+            let value = ctx.0.get(key.as_str()).ok_or_else(|| format!("{} request needs context field '{}'", self.method, key))?;
+            let mut message = &mut request;
+            let mut path = field.split('.').peekable();
+            while let Some(part) = path.next() {
+                if path.peek().is_none() {
+                    message.insert(part.to_string(), value.clone());
+                    break;
+                }
+                let nested = message.entry(part.to_string()).or_insert_with(|| Value::Object(Map::new()));
+                if !nested.is_object() {
+                    *nested = Value::Object(Map::new());
+                }
+                message = nested.as_object_mut().expect("just made an object");
+            }
+        }
+        Ok(Value::Object(request))
+    }
+}
+
+/// A link making `call` through `client`; see the [module docs](self).
+pub fn grpc_call(client: Arc<dyn GrpcClient>, call: GrpcCall) -> Link {
+    let call = Arc::new(call);
+    Arc::new(move |ctx: Context| {
+        let client = client.clone();
+        let call = call.clone();
+        Box::pin(async move {
+            let request = match call.request(&ctx) {
+                Ok(request) => request,
+                Err(msg) => {
+                    ctx_tools::fail_run(RunError::invalid_input(msg));
+                    return ctx;
+                }
+            };
+            match client.call(&call.method, request).await {
+                Ok(response) => match (&call.response_key, response) {
+                    (Some(key), response) => ctx.insert(key.as_str(), response),
+                    (None, Value::Object(fields)) => fields.into_iter().fold(ctx, |ctx, (key, value)| ctx.insert(key.as_str(), value)),
+                    (None, Value::Null) => ctx,
+                    (None, other) => {
+                        ctx_tools::fail_run(RunError::internal(format!("{} responded with a non-message: {}", call.method, other)));
+                        ctx
+                    }
+                },
+                Err(status) => {
+                    ctx_tools::fail_run(RunError::new(status.code.error_kind(), format!("{} failed: {}", call.method, status)));
+                    ctx
+                }
+            }
+        })
+    })
+}
@@ -0,0 +1,231 @@
+//! Reading CSV and Parquet files into the context (features `csv`, `parquet`).
+//!
+//! [`read_csv`] and [`read_parquet`] read the file whose path is in the context and insert
+//! its rows as JSON objects (column name to value). How much is read per run is set by
+//! [`Chunking`]:
+//! - `All`: every row, as one array under `into`.
+//! - `Chunks(n)`: every row, as an array of arrays of up to `n` rows.
+//! - `Paged { rows, cursor }`: up to `rows` rows from the row index under `cursor` (0 when
+//!   absent); the cursor is then set to the index to read next, or `null` at the end of the
+//!   file. Loop back to the link while the cursor is not null to work through a large file
+//!   one page per pass.
+//!
+//! Files are read on the blocking pool of the spec's executor. A context without the path fails the run with
+//! `InvalidInput`, as does a file that does not exist; a file that cannot be parsed fails
+//! it with `Internal`.
+//!
+//! Example:
+//! ```rust,no_run
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{read_csv, Chunking, ReadFile};
+//!
+//! # async fn demo() {
+//! let mut chain = Chain::new();
+//! chain.add_link(read_csv(ReadFile::new("path").with_chunking(Chunking::Chunks(500))));
+//! let ctx = chain.run(Context::new().insert("path", "exports/orders.csv")).await;
+//! let chunks = ctx.get::<Vec<Vec<serde_json::Value>>>("rows").unwrap();
+//! # }
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::links::Link;
+use crate::runtime::{self, default_executor, ExecutorObj};
+use serde_json::Value;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// How many rows a read takes, and how they are grouped; see the [module docs](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Chunking {
+    #[default]
+    All,
+    Chunks(usize),
+    Paged { rows: usize, cursor: String },
+}
+
+/// Where a file read takes its path from and puts its rows.
+#[derive(Clone)]
+pub struct ReadFile {
+    /// Context key holding the file path.
+    pub path_key: String,
+    /// Context key the rows are inserted under; `"rows"` by default.
+    pub into: String,
+    pub chunking: Chunking,
+    /// CSV only: the field delimiter, `,` by default.
+    pub delimiter: u8,
+    /// CSV only: parse integers, floats, and booleans instead of keeping every field a
+    /// string; on by default.
+    pub infer_types: bool,
+    executor: ExecutorObj,
+}
+
+impl fmt::Debug for ReadFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ReadFile")
+            .field("path_key", &self.path_key)
+            .field("into", &self.into)
+            .field("chunking", &self.chunking)
+            .field("delimiter", &self.delimiter)
+            .field("infer_types", &self.infer_types)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ReadFile {
+    /// Read the file at the path under `path_key`.
+    pub fn new(path_key: impl Into<String>) -> Self {
+        ReadFile {
+            path_key: path_key.into(),
+            into: "rows".to_string(),
+            chunking: Chunking::All,
+            delimiter: b',',
+            infer_types: true,
+            executor: default_executor(),
+        }
+    }
+    pub fn into_key(mut self, into: impl Into<String>) -> Self {
+        self.into = into.into();
+        self
+    }
+    pub fn with_chunking(mut self, chunking: Chunking) -> Self {
+        self.chunking = chunking;
+        self
+    }
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+    pub fn keep_strings(mut self) -> Self {
+        self.infer_types = false;
+        self
+    }
+    /// Executor whose blocking pool reads the file (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    // The rows to read: (rows to skip, most rows to take).
+    fn window(&self, ctx: &Context) -> (usize, Option<usize>) {
+        match &self.chunking {
+            Chunking::Paged { rows, cursor } => (ctx.get::<usize>(cursor).unwrap_or(0), Some(*rows)),
+            _ => (0, None),
+        }
+    }
+
+    // Insert `rows` read from row `start` on; `more` tells whether rows are left after them.
+    fn insert(&self, ctx: Context, rows: Vec<Value>, start: usize, more: bool) -> Context {
+        match &self.chunking {
+            Chunking::All => ctx.insert(self.into.as_str(), rows),
+            Chunking::Chunks(size) => {
+                let chunks: Vec<Vec<Value>> = rows.chunks((*size).max(1)).map(<[Value]>::to_vec).collect();
+                ctx.insert(self.into.as_str(), chunks)
+            }
+            Chunking::Paged { cursor, .. } => {
+                let next = more.then_some(start + rows.len());
+                ctx.insert(self.into.as_str(), rows).insert(cursor.as_str(), next)
+            }
+        }
+    }
+}
+
+type Reader = fn(&ReadFile, PathBuf, usize, Option<usize>) -> io::Result<(Vec<Value>, bool)>;
+
+// A link reading files with `read`, which returns the rows in the window and whether more follow.
+fn read_link(spec: ReadFile, read: Reader) -> Link {
+    let spec = Arc::new(spec);
+    Arc::new(move |ctx: Context| {
+        let spec = spec.clone();
+        Box::pin(async move {
+            let Some(path) = ctx.get::<String>(&spec.path_key) else {
+                ctx_tools::fail_run(RunError::invalid_input(format!("no file path under '{}'", spec.path_key)));
+                return ctx;
+            };
+            let (start, take) = spec.window(&ctx);
+            let reader = spec.clone();
+            let file = PathBuf::from(&path);
+            let read = runtime::spawn_blocking(spec.executor.as_ref(), move || read(&reader, file, start, take))
+                .await
+                .unwrap_or_else(|e| Err(io::Error::other(e)));
+            match read {
+                Ok((rows, more)) => spec.insert(ctx, rows, start, more),
+                Err(e) => {
+                    let msg = format!("reading '{}' failed: {}", path, e);
+                    ctx_tools::fail_run(match e.kind() {
+                        io::ErrorKind::NotFound => RunError::invalid_input(msg),
+                        _ => RunError::internal(msg),
+                    });
+                    ctx
+                }
+            }
+        })
+    })
+}
+
+// Take the rows of `rows` in the window, and whether any row follows it.
+fn take_window<I: Iterator<Item = io::Result<Value>>>(rows: I, start: usize, take: Option<usize>) -> io::Result<(Vec<Value>, bool)> {
+    let mut rows = rows.skip(start);
+    let taken = match take {
+        Some(take) => rows.by_ref().take(take).collect::<io::Result<Vec<_>>>()?,
+        None => rows.by_ref().collect::<io::Result<Vec<_>>>()?,
+    };
+    Ok((taken, rows.next().is_some()))
+}
+
+/// A link reading the CSV file at the path under `spec.path_key`; the first line holds
+/// the column names. See the [module docs](self).
+#[cfg(feature = "csv")]
+pub fn read_csv(spec: ReadFile) -> Link {
+    read_link(spec, |spec, path, start, take| {
+        let mut reader = csv::ReaderBuilder::new().delimiter(spec.delimiter).from_path(path).map_err(csv_error)?;
+        let headers = reader.headers().map_err(csv_error)?.clone();
+        let infer = spec.infer_types;
+        let rows = reader.into_records().map(|record| {
+            let record = record.map_err(csv_error)?;
+            let row: serde_json::Map<String, Value> = headers.iter().zip(record.iter()).map(|(name, field)| (name.to_string(), csv_value(field, infer))).collect();
+            Ok(Value::Object(row))
+        });
+        take_window(rows, start, take)
+    })
+}
+
+#[cfg(feature = "csv")]
+fn csv_error(e: csv::Error) -> io::Error {
+    match e.into_kind() {
+        csv::ErrorKind::Io(e) => e,
+        other => io::Error::other(format!("{:?}", other)),
+    }
+}
+
+#[cfg(feature = "csv")]
+fn csv_value(field: &str, infer: bool) -> Value {
+    if infer {
+        if let Ok(n) = field.parse::<i64>() {
+            return Value::from(n);
+        }
+        if let Some(n) = field.parse::<f64>().ok().filter(|n| n.is_finite()) {
+            return Value::from(n);
+        }
+        if let Ok(b) = field.parse::<bool>() {
+            return Value::from(b);
+        }
+    }
+    Value::from(field)
+}
+
+/// A link reading the Parquet file at the path under `spec.path_key`. See the
+/// [module docs](self).
+#[cfg(feature = "parquet")]
+pub fn read_parquet(spec: ReadFile) -> Link {
+    read_link(spec, |_, path, start, take| {
+        use parquet::file::reader::{FileReader, SerializedFileReader};
+        let reader = SerializedFileReader::new(std::fs::File::open(path)?).map_err(io::Error::other)?;
+        let rows = reader.get_row_iter(None).map_err(io::Error::other)?;
+        take_window(rows.map(|row| row.map(|row| row.to_json_value()).map_err(io::Error::other)), start, take)
+    })
+}
@@ -0,0 +1,60 @@
+//! Ready-made links for common workflow steps.
+//!
+//! - [`await_approval`]: park the run until a person approves or rejects it.
+//! - [`wait_for`] / [`wait_until`]: pause the run until a point in time.
+//! - [`await_event`]: suspend the run until a webhook, callback, or message arrives.
+//! - [`grpc_call`]: call a unary gRPC method with fields of the context.
+//! - [`kafka_produce`] / [`nats_publish`]: emit an event mid-run.
+//! - [`validate`]: check the context against declarative business rules.
+//! - [`mask_pii`]: mask or list emails, phone numbers, card numbers, and other personal
+//!   data before it is logged or sent on.
+//! - `read_csv` (feature `csv`) / `read_parquet` (feature `parquet`): read a file's rows
+//!   into the context, whole or in chunks.
+//! - `parse_xml` / `to_xml` (feature `xml`), `parse_yaml` / `to_yaml` (feature `yaml`):
+//!   convert between text payloads and structured values.
+//! - `sha256`, `hmac_sha256`, `sign_ed25519` / `verify_ed25519`, `uuid_v4`, `ulid`
+//!   (feature `crypto`): hash, sign, and id building blocks.
+//! - `notify_email` (feature `email`) / `notify_slack` (feature `slack`): send a message
+//!   filled from the context (see [`template`]).
+
+pub mod approval;
+pub mod event;
+pub mod grpc;
+pub mod pii;
+pub mod publish;
+pub mod template;
+pub mod validate;
+pub mod wait;
+#[cfg(feature = "crypto")]
+pub mod crypto;
+#[cfg(feature = "email")]
+pub mod email;
+#[cfg(any(feature = "xml", feature = "yaml"))]
+pub mod formats;
+#[cfg(any(feature = "csv", feature = "parquet"))]
+pub mod ingest;
+#[cfg(feature = "slack")]
+pub mod slack;
+pub use approval::{await_approval, ApprovalDecision, Approvals, PendingApproval};
+pub use event::{await_event, Events};
+pub use grpc::{grpc_call, GrpcCall, GrpcClient, GrpcCode, GrpcStatus, GrpcStubs};
+pub use pii::{mask_pii, CreditCard, Email, PatternDetector, Phone, PiiDetector, PiiFinding, PiiScan};
+pub use publish::{kafka_produce, nats_publish, NatsPublisher, Payload, Publish};
+pub use validate::{validate, Rule, Rules, Violation};
+pub use wait::{wait_for, wait_until, Wait};
+#[cfg(feature = "crypto")]
+pub use crypto::{hmac_sha256, sha256, sign_ed25519, ulid, uuid_v4, verify_ed25519};
+#[cfg(feature = "email")]
+pub use email::{notify_email, EmailNotification, Mailer};
+#[cfg(feature = "slack")]
+pub use slack::notify_slack;
+#[cfg(any(feature = "csv", feature = "parquet"))]
+pub use ingest::{Chunking, ReadFile};
+#[cfg(feature = "csv")]
+pub use ingest::read_csv;
+#[cfg(feature = "parquet")]
+pub use ingest::read_parquet;
+#[cfg(feature = "xml")]
+pub use formats::{parse_xml, to_xml};
+#[cfg(feature = "yaml")]
+pub use formats::{parse_yaml, to_yaml};
@@ -0,0 +1,254 @@
+//! Finding and masking personal data in the context.
+//!
+//! [`mask_pii`] scans the text values under the keys of a [`PiiScan`] (every key when none
+//! are given), nested objects and arrays included, with its [`PiiDetector`]s. Each match is
+//! masked character by character with `*`, or, with [`PiiScan::tag_only`], left as it is.
+//! Either way the matches are listed under `pii` as [`PiiFinding`]s (the path of the value
+//! and the detector that matched), never with the matched text.
+//!
+//! The default detectors are [`CreditCard`] (13 to 19 digits passing the Luhn check),
+//! [`Email`], and [`Phone`] (10 to 15 digits, optionally with `+`, spaces, dots, dashes,
+//! and parentheses); where matches overlap, the detector listed first wins. Add others with
+//! [`PiiScan::with_detector`], e.g. a [`PatternDetector`] for account numbers.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{mask_pii, PiiScan};
+//!
+//! let mut chain = Chain::new();
+//! chain.add_link(mask_pii(PiiScan::new().keys(["note"])));
+//! let ctx = futures::executor::block_on(chain.run(Context::new().insert("note", "mail ana@example.com")));
+//! assert_eq!(ctx.get::<String>("note").as_deref(), Some("mail ***************"));
+//! ```
+
+use crate::context::Context;
+use crate::links::Link;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::ops::Range;
+use std::sync::Arc;
+
+/// Finds one kind of personal data in text.
+pub trait PiiDetector: Send + Sync {
+    /// Name reported in [`PiiFinding::detector`].
+    fn name(&self) -> &str;
+    /// Byte ranges of the matches in `text`.
+    fn find(&self, text: &str) -> Vec<Range<usize>>;
+}
+
+/// A detector matching a regular expression, optionally confirmed by `check` on the match.
+pub struct PatternDetector {
+    name: String,
+    regex: Regex,
+    check: Option<fn(&str) -> bool>,
+}
+
+impl PatternDetector {
+    pub fn new(name: impl Into<String>, pattern: &str) -> Result<Self, regex::Error> {
+        Ok(PatternDetector { name: name.into(), regex: Regex::new(pattern)?, check: None })
+    }
+    /// Only report matches for which `check` holds.
+    pub fn with_check(mut self, check: fn(&str) -> bool) -> Self {
+        self.check = Some(check);
+        self
+    }
+}
+
+impl PiiDetector for PatternDetector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn find(&self, text: &str) -> Vec<Range<usize>> {
+        self.regex.find_iter(text).filter(|m| self.check.is_none_or(|check| check(m.as_str()))).map(|m| m.range()).collect()
+    }
+}
+
+/// Email addresses.
+pub struct Email;
+
+/// Phone numbers.
+pub struct Phone;
+
+/// Payment card numbers.
+pub struct CreditCard;
+
+// The built-in detectors, compiled once.
+struct Builtins {
+    email: PatternDetector,
+    phone: PatternDetector,
+    card: PatternDetector,
+}
+
+fn builtins() -> &'static Builtins {
+    static BUILTINS: std::sync::OnceLock<Builtins> = std::sync::OnceLock::new();
+    BUILTINS.get_or_init(|| Builtins {
+        email: PatternDetector::new("email", r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(?:\.[A-Za-z0-9-]+)*\.[A-Za-z]{2,}").unwrap(),
+        phone: PatternDetector::new("phone", r"\+?\(?\d[\d\s().-]{8,}\d").unwrap().with_check(|m| (10..=15).contains(&digits(m).len())),
+        card: PatternDetector::new("credit_card", r"\b\d(?:[ -]?\d){12,18}\b").unwrap().with_check(|m| luhn(&digits(m))),
+    })
+}
+
+fn digits(text: &str) -> Vec<u32> {
+    text.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, doubled) if doubled > 9 => doubled - 9,
+            (_, doubled) => doubled,
+        })
+        .sum();
+    sum.is_multiple_of(10)
+}
+
+impl PiiDetector for Email {
+    fn name(&self) -> &str {
+        "email"
+    }
+    fn find(&self, text: &str) -> Vec<Range<usize>> {
+        builtins().email.find(text)
+    }
+}
+
+impl PiiDetector for Phone {
+    fn name(&self) -> &str {
+        "phone"
+    }
+    fn find(&self, text: &str) -> Vec<Range<usize>> {
+        builtins().phone.find(text)
+    }
+}
+
+impl PiiDetector for CreditCard {
+    fn name(&self) -> &str {
+        "credit_card"
+    }
+    fn find(&self, text: &str) -> Vec<Range<usize>> {
+        builtins().card.find(text)
+    }
+}
+
+/// Personal data found at `path`: a context key, then object fields and array indexes,
+/// joined with `.` (`customer.phones.0`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PiiFinding {
+    pub path: String,
+    pub detector: String,
+}
+
+/// What a [`mask_pii`] link scans, with what, and what it does with matches.
+#[derive(Clone)]
+pub struct PiiScan {
+    /// Context keys to scan; every key when `None`.
+    pub keys: Option<Vec<String>>,
+    pub detectors: Vec<Arc<dyn PiiDetector>>,
+    /// Mask matches; when off, only list them.
+    pub mask: bool,
+    /// Context key the findings are inserted under; `"pii"` by default.
+    pub into: String,
+}
+
+impl Default for PiiScan {
+    fn default() -> Self {
+        PiiScan { keys: None, detectors: vec![Arc::new(CreditCard), Arc::new(Email), Arc::new(Phone)], mask: true, into: "pii".to_string() }
+    }
+}
+
+impl PiiScan {
+    /// Scan every key with the default detectors, masking matches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Scan only these keys.
+    pub fn keys<I: IntoIterator<Item = S>, S: Into<String>>(mut self, keys: I) -> Self {
+        self.keys = Some(keys.into_iter().map(Into::into).collect());
+        self
+    }
+    pub fn with_detector(mut self, detector: Arc<dyn PiiDetector>) -> Self {
+        self.detectors.push(detector);
+        self
+    }
+    /// Use only `detectors`, in this order.
+    pub fn with_detectors(mut self, detectors: Vec<Arc<dyn PiiDetector>>) -> Self {
+        self.detectors = detectors;
+        self
+    }
+    /// List matches without masking them.
+    pub fn tag_only(mut self) -> Self {
+        self.mask = false;
+        self
+    }
+    pub fn into_key(mut self, into: impl Into<String>) -> Self {
+        self.into = into.into();
+        self
+    }
+
+    // Scan `value` at `path`, masking matches in place when masking is on.
+    fn scan(&self, path: String, value: &mut Value, found: &mut Vec<PiiFinding>) {
+        match value {
+            Value::String(text) => {
+                let mut taken: Vec<Range<usize>> = Vec::new();
+                for detector in &self.detectors {
+                    let mut matched = false;
+                    for range in detector.find(text) {
+                        if !taken.iter().any(|t| t.start < range.end && range.start < t.end) {
+                            taken.push(range);
+                            matched = true;
+                        }
+                    }
+                    if matched {
+                        found.push(PiiFinding { path: path.clone(), detector: detector.name().to_string() });
+                    }
+                }
+                if self.mask && !taken.is_empty() {
+                    *text = text.char_indices().map(|(i, c)| if taken.iter().any(|t| t.contains(&i)) { '*' } else { c }).collect();
+                }
+            }
+            Value::Array(items) => {
+                for (i, item) in items.iter_mut().enumerate() {
+                    self.scan(format!("{}.{}", path, i), item, found);
+                }
+            }
+            Value::Object(fields) => {
+                for (key, field) in fields.iter_mut() {
+                    self.scan(format!("{}.{}", path, key), field, found);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A link scanning the context for personal data; see the [module docs](self).
+pub fn mask_pii(scan: PiiScan) -> Link {
+    let scan = Arc::new(scan);
+    Arc::new(move |ctx: Context| {
+        let scan = scan.clone();
+        Box::pin(async move {
+            let mut keys: Vec<String> = match &scan.keys {
+                Some(keys) => keys.clone(),
+                None => ctx.0.keys().map(|key| key.to_string()).filter(|key| *key != scan.into).collect(),
+            };
+            keys.sort();
+            let mut found = Vec::new();
+            let mut ctx = ctx;
+            for key in keys {
+                let Some(mut value) = ctx.get::<Value>(&key) else { continue };
+                let before = found.len();
+                scan.scan(key.clone(), &mut value, &mut found);
+                if scan.mask && found.len() > before {
+                    ctx = ctx.insert(key.as_str(), value);
+                }
+            }
+            ctx.insert(scan.into.as_str(), found)
+        })
+    })
+}
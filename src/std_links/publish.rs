@@ -0,0 +1,152 @@
+//! Publishing events mid-run (Kafka, NATS).
+//!
+//! Sinks receive a run's final context; [`kafka_produce`] and [`nats_publish`] emit a
+//! message at their place in the chain and pass the context on unchanged. What they send is
+//! described by a [`Publish`]: the topic or subject, and the Kafka message key, are
+//! templates filled from the context (see [`super::template`]), and [`Payload`] picks the
+//! part of the context sent as JSON.
+//!
+//! Both go through a client trait (`sinks::KafkaProducer`, [`NatsPublisher`]) implemented
+//! over the client of your choice. A context missing a template field or payload key fails
+//! the run with `InvalidInput`; a failed publish fails it with `Internal`.
+//!
+//! Example:
+//! ```rust
+//! use async_trait::async_trait;
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{nats_publish, NatsPublisher, Payload, Publish};
+//! use std::sync::{Arc, Mutex};
+//!
+//! #[derive(Default)]
+//! struct Recorder(Mutex<Vec<String>>);
+//!
+//! #[async_trait]
+//! impl NatsPublisher for Recorder {
+//!     async fn publish(&self, subject: &str, _payload: Vec<u8>) -> std::io::Result<()> {
+//!         self.0.lock().unwrap().push(subject.to_string());
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let nats = Arc::new(Recorder::default());
+//! let mut chain = Chain::new();
+//! chain.add_link(nats_publish(nats.clone(), Publish::to("orders.{region}.created").with_payload(Payload::Key("order".into()))));
+//!
+//! futures::executor::block_on(chain.run(Context::new().insert("region", "eu").insert("order", 17)));
+//! assert_eq!(nats.0.lock().unwrap()[0], "orders.eu.created");
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::links::Link;
+use crate::runtime::BoxFuture;
+use crate::sinks::KafkaProducer;
+use crate::std_links::template;
+use async_trait::async_trait;
+use serde_json::{Map, Value};
+use std::sync::Arc;
+
+/// Minimal publisher interface for NATS; implement it over e.g. `async_nats::Client`.
+#[async_trait]
+pub trait NatsPublisher: Send + Sync {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> std::io::Result<()>;
+}
+
+/// The part of the context a message carries.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Payload {
+    /// The whole context.
+    #[default]
+    Context,
+    /// The value under one key.
+    Key(String),
+    /// An object of the given keys and their values.
+    Keys(Vec<String>),
+}
+
+impl Payload {
+    fn select(&self, ctx: &Context) -> Result<Value, String> {
+        let get = |key: &str| ctx.0.get(key).cloned().ok_or_else(|| format!("payload needs context field '{}'", key));
+        match self {
+            Payload::Context => Ok(Value::Object(ctx.clone().into())),
+            Payload::Key(key) => get(key),
+            Payload::Keys(keys) => keys.iter().map(|key| Ok((key.clone(), get(key)?))).collect::<Result<Map<_, _>, String>>().map(Value::Object),
+        }
+    }
+}
+
+/// Where a message goes and what it carries; see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Publish {
+    /// Topic (Kafka) or subject (NATS) template.
+    pub to: String,
+    /// Message key template; Kafka only.
+    pub key: Option<String>,
+    pub payload: Payload,
+}
+
+impl Publish {
+    /// Publish the whole context to `to`.
+    pub fn to(to: impl Into<String>) -> Self {
+        Publish { to: to.into(), key: None, payload: Payload::Context }
+    }
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+    pub fn with_payload(mut self, payload: Payload) -> Self {
+        self.payload = payload;
+        self
+    }
+
+    // (destination, key, JSON payload) for the run with `ctx`.
+    fn render(&self, ctx: &Context) -> Result<(String, Option<String>, Vec<u8>), String> {
+        let to = template::render(&self.to, ctx)?;
+        let key = self.key.as_deref().map(|key| template::render(key, ctx)).transpose()?;
+        let payload = serde_json::to_vec(&self.payload.select(ctx)?).map_err(|e| e.to_string())?;
+        Ok((to, key, payload))
+    }
+}
+
+// A link rendering `publish` and handing it to `send`.
+fn publish_link<F>(publish: Publish, send: F) -> Link
+where
+    F: Fn(String, Option<String>, Vec<u8>) -> BoxFuture<'static, std::io::Result<()>> + Send + Sync + 'static,
+{
+    let send = Arc::new(send);
+    Arc::new(move |ctx: Context| {
+        let message = publish.render(&ctx);
+        let send = send.clone();
+        Box::pin(async move {
+            let (to, key, payload) = match message {
+                Ok(message) => message,
+                Err(msg) => {
+                    ctx_tools::fail_run(RunError::invalid_input(msg));
+                    return ctx;
+                }
+            };
+            if let Err(e) = send(to.clone(), key, payload).await {
+                ctx_tools::fail_run(RunError::internal(format!("publishing to '{}' failed: {}", to, e)));
+            }
+            ctx
+        })
+    })
+}
+
+/// A link producing a Kafka message described by `publish` through `producer`.
+pub fn kafka_produce(producer: Arc<dyn KafkaProducer>, publish: Publish) -> Link {
+    publish_link(publish, move |topic, key, payload| {
+        let producer = producer.clone();
+        Box::pin(async move { producer.send(&topic, key.as_deref(), payload).await })
+    })
+}
+
+/// A link publishing a NATS message described by `publish` through `publisher`.
+pub fn nats_publish(publisher: Arc<dyn NatsPublisher>, publish: Publish) -> Link {
+    publish_link(publish, move |subject, _key, payload| {
+        let publisher = publisher.clone();
+        Box::pin(async move { publisher.publish(&subject, payload).await })
+    })
+}
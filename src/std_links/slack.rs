@@ -0,0 +1,50 @@
+//! Slack notifications through incoming webhooks (feature `slack`).
+//!
+//! [`notify_slack`] posts a message to a Slack incoming-webhook URL when the run reaches
+//! it. The text is a template filled from the context (see [`super::template`]) and may
+//! use Slack's mrkdwn. The context passes through unchanged. A context missing a template
+//! field fails the run with `InvalidInput`; a post Slack refuses fails it with `Internal`
+//! (give the link a retry policy to try again).
+//!
+//! Example:
+//! ```rust,no_run
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::std_links::notify_slack;
+//!
+//! let mut chain = Chain::new();
+//! chain.add_link(notify_slack("https://hooks.slack.com/services/T000/B000/XXXX", ":warning: order *{order_id}* failed: {error}"));
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::links::Link;
+use crate::std_links::template;
+use serde_json::json;
+use std::sync::Arc;
+
+/// A link posting `text`, filled from the context, to the Slack webhook at `webhook_url`.
+pub fn notify_slack(webhook_url: &str, text: &str) -> Link {
+    let url = webhook_url.to_string();
+    let text = text.to_string();
+    let client = reqwest::Client::new();
+    Arc::new(move |ctx: Context| {
+        let url = url.clone();
+        let client = client.clone();
+        let text = template::render(&text, &ctx);
+        Box::pin(async move {
+            let text = match text {
+                Ok(text) => text,
+                Err(msg) => {
+                    ctx_tools::fail_run(RunError::invalid_input(msg));
+                    return ctx;
+                }
+            };
+            let sent = client.post(&url).json(&json!({ "text": text })).send().await.and_then(|resp| resp.error_for_status());
+            if let Err(e) = sent {
+                ctx_tools::fail_run(RunError::internal(format!("posting to Slack failed: {}", e)));
+            }
+            ctx
+        })
+    })
+}
@@ -0,0 +1,52 @@
+//! Text templates filled from the context.
+//!
+//! `{field}` is replaced by the context value under `field`: strings as they are, other
+//! values as JSON. `{a.b}` reaches into objects (`b` of the object under `a`) when the
+//! context has no key `a.b` itself. `{{` and `}}` stand for literal braces.
+
+use crate::context::Context;
+use serde_json::Value;
+
+/// Fill `template` from `ctx`; the error names the first field the context is missing.
+pub fn render(template: &str, ctx: &Context) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(at) = rest.find(['{', '}']) {
+        out.push_str(&rest[..at]);
+        let tail = &rest[at..];
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if tail.starts_with('}') {
+            return Err(format!("unmatched '}}' in template '{}'", template));
+        }
+        let close = tail.find('}').ok_or_else(|| format!("unclosed '{{' in template '{}'", template))?;
+        let field = &tail[1..close];
+        let value = lookup(ctx, field).ok_or_else(|| format!("template '{}' needs context field '{}'", template, field))?;
+        match value {
+            Value::String(s) => out.push_str(s),
+            other => out.push_str(&other.to_string()),
+        }
+        rest = &tail[close + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// The value under `field`, or under the dotted path `field` as described above.
+pub(crate) fn lookup<'a>(ctx: &'a Context, field: &str) -> Option<&'a Value> {
+    if let Some(value) = ctx.0.get(field) {
+        return Some(value);
+    }
+    let mut parts = field.split('.');
+    let mut value = ctx.0.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            Value::Array(items) => items.get(part.parse::<usize>().ok()?)?,
+            other => other.get(part)?,
+        };
+    }
+    Some(value)
+}
@@ -0,0 +1,248 @@
+//! Business-rule validation of the context.
+//!
+//! [`validate`] checks the context against a set of [`Rule`]s and inserts every violation
+//! it finds, as a list of [`Violation`]s, under `violations` (empty when the context is
+//! valid), so later links or branches can act on them. With [`Rules::fail_run`] a context
+//! with violations fails the run with `InvalidInput` instead, the violations still in it.
+//!
+//! Fields are context keys, or dotted paths into them (`order.lines.0.sku`). Apart from
+//! `required`, rules about a field pass when the field is absent or `null`. Rules are plain
+//! data, so they can be written in a chain definition (`{ "validate": { "rules": [...] } }`):
+//!
+//! ```json
+//! [
+//!   { "rule": "required", "field": "order.id" },
+//!   { "rule": "pattern", "field": "email", "regex": "^[^@\\s]+@[^@\\s]+$" },
+//!   { "rule": "range", "field": "quantity", "min": 1, "max": 100 },
+//!   { "rule": "one_of", "field": "status", "values": ["new", "paid", "shipped"] },
+//!   { "rule": "check", "expr": "ctx.ends >= ctx.starts", "message": "ends before it starts" },
+//!   { "rule": "when", "expr": "ctx.status == 'shipped'", "then": [{ "rule": "required", "field": "tracking" }] }
+//! ]
+//! ```
+//!
+//! `check` and `when` take an [`Expression`] over the whole context, for conditions
+//! across fields.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::context::Context;
+//! use modulink_rs::std_links::{validate, Rules, Violation};
+//!
+//! let rules = Rules::new().required("email").range("quantity", Some(1.0), None).one_of("status", ["new", "paid"]);
+//! let mut chain = Chain::new();
+//! chain.add_link(validate(rules).unwrap());
+//!
+//! let ctx = futures::executor::block_on(chain.run(Context::new().insert("quantity", 0).insert("status", "new")));
+//! let violations = ctx.get::<Vec<Violation>>("violations").unwrap();
+//! assert_eq!(violations.iter().map(|v| v.field.as_deref()).collect::<Vec<_>>(), vec![Some("email"), Some("quantity")]);
+//! ```
+
+use crate::chains::RunError;
+use crate::context::Context;
+use crate::ctx_tools;
+use crate::definitions::Expression;
+use crate::links::Link;
+use crate::std_links::template;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// One declarative rule; see the [module docs](self).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum Rule {
+    /// The field is present and not `null`.
+    Required { field: String },
+    /// The field is text matching `regex` (anywhere, unless anchored).
+    Pattern { field: String, regex: String },
+    /// The field is a number within `min..=max`; a missing bound is open.
+    Range {
+        field: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f64>,
+    },
+    /// The field equals one of `values`.
+    OneOf { field: String, values: Vec<Value> },
+    /// `expr` holds; reported with `message` otherwise.
+    Check { expr: String, message: String },
+    /// The rules in `then` apply when `expr` holds.
+    When { expr: String, then: Vec<Rule> },
+}
+
+/// A rule a context broke.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Violation {
+    /// The kind of rule: `required`, `pattern`, `range`, `one_of`, or `check`.
+    pub rule: String,
+    /// The field the rule is about; `None` for `check` rules.
+    pub field: Option<String>,
+    pub message: String,
+}
+
+/// The rules a [`validate`] link applies, and what it does with violations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rules {
+    pub rules: Vec<Rule>,
+    /// Context key the violations are inserted under; `"violations"` by default.
+    #[serde(default = "default_into")]
+    pub into: String,
+    /// Fail the run with `InvalidInput` when there are violations.
+    #[serde(default)]
+    pub fail: bool,
+}
+
+fn default_into() -> String {
+    "violations".to_string()
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules { rules: Vec::new(), into: default_into(), fail: false }
+    }
+}
+
+impl Rules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn rule(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+    pub fn required(self, field: impl Into<String>) -> Self {
+        self.rule(Rule::Required { field: field.into() })
+    }
+    pub fn pattern(self, field: impl Into<String>, regex: impl Into<String>) -> Self {
+        self.rule(Rule::Pattern { field: field.into(), regex: regex.into() })
+    }
+    pub fn range(self, field: impl Into<String>, min: Option<f64>, max: Option<f64>) -> Self {
+        self.rule(Rule::Range { field: field.into(), min, max })
+    }
+    pub fn one_of<I: IntoIterator<Item = V>, V: Into<Value>>(self, field: impl Into<String>, values: I) -> Self {
+        self.rule(Rule::OneOf { field: field.into(), values: values.into_iter().map(Into::into).collect() })
+    }
+    pub fn check(self, expr: impl Into<String>, message: impl Into<String>) -> Self {
+        self.rule(Rule::Check { expr: expr.into(), message: message.into() })
+    }
+    /// Apply `then`'s rules only when `expr` holds.
+    pub fn when(self, expr: impl Into<String>, then: Rules) -> Self {
+        self.rule(Rule::When { expr: expr.into(), then: then.rules })
+    }
+    pub fn into_key(mut self, into: impl Into<String>) -> Self {
+        self.into = into.into();
+        self
+    }
+    pub fn fail_run(mut self) -> Self {
+        self.fail = true;
+        self
+    }
+}
+
+// A rule with its regex and expressions parsed.
+enum Compiled {
+    Required(String),
+    Pattern(String, Regex),
+    Range(String, Option<f64>, Option<f64>),
+    OneOf(String, Vec<Value>),
+    Check(Expression, String),
+    When(Expression, Vec<Compiled>),
+}
+
+fn compile(rules: &[Rule]) -> Result<Vec<Compiled>, String> {
+    let expr = |source: &str| Expression::parse(source).map_err(|e| format!("expression '{}': {}", source, e));
+    rules
+        .iter()
+        .map(|rule| {
+            Ok(match rule {
+                Rule::Required { field } => Compiled::Required(field.clone()),
+                Rule::Pattern { field, regex } => {
+                    let regex = Regex::new(regex).map_err(|e| format!("pattern for '{}': {}", field, e))?;
+                    Compiled::Pattern(field.clone(), regex)
+                }
+                Rule::Range { field, min, max } => Compiled::Range(field.clone(), *min, *max),
+                Rule::OneOf { field, values } => Compiled::OneOf(field.clone(), values.clone()),
+                Rule::Check { expr: source, message } => Compiled::Check(expr(source)?, message.clone()),
+                Rule::When { expr: source, then } => Compiled::When(expr(source)?, compile(then)?),
+            })
+        })
+        .collect()
+}
+
+fn violation(rule: &str, field: Option<&str>, message: String) -> Violation {
+    Violation { rule: rule.to_string(), field: field.map(str::to_string), message }
+}
+
+fn check(rules: &[Compiled], ctx: &Context, found: &mut Vec<Violation>) {
+    for rule in rules {
+        match rule {
+            Compiled::Required(field) => {
+                if matches!(template::lookup(ctx, field), None | Some(Value::Null)) {
+                    found.push(violation("required", Some(field), format!("'{}' is required", field)));
+                }
+            }
+            Compiled::Pattern(field, regex) => match present(ctx, field) {
+                Some(Value::String(text)) if regex.is_match(text) => {}
+                Some(Value::String(_)) => found.push(violation("pattern", Some(field), format!("'{}' does not match {}", field, regex))),
+                Some(_) => found.push(violation("pattern", Some(field), format!("'{}' must be text", field))),
+                None => {}
+            },
+            Compiled::Range(field, min, max) => match present(ctx, field) {
+                Some(Value::Number(n)) => {
+                    let n = n.as_f64().unwrap_or_default();
+                    if min.is_some_and(|min| n < min) || max.is_some_and(|max| n > max) {
+                        let bound = |b: &Option<f64>| b.map_or("..".to_string(), |b| b.to_string());
+                        let message = format!("'{}' is {}, outside {} to {}", field, n, bound(min), bound(max));
+                        found.push(violation("range", Some(field), message));
+                    }
+                }
+                Some(_) => found.push(violation("range", Some(field), format!("'{}' must be a number", field))),
+                None => {}
+            },
+            Compiled::OneOf(field, values) => {
+                if let Some(value) = present(ctx, field).filter(|value| !values.contains(value)) {
+                    let allowed: Vec<String> = values.iter().map(Value::to_string).collect();
+                    found.push(violation("one_of", Some(field), format!("'{}' is {}, not one of {}", field, value, allowed.join(", "))));
+                }
+            }
+            Compiled::Check(expr, message) => {
+                if !expr.eval(ctx) {
+                    found.push(violation("check", None, message.clone()));
+                }
+            }
+            Compiled::When(expr, then) => {
+                if expr.eval(ctx) {
+                    check(then, ctx, found);
+                }
+            }
+        }
+    }
+}
+
+fn present<'a>(ctx: &'a Context, field: &str) -> Option<&'a Value> {
+    template::lookup(ctx, field).filter(|value| !value.is_null())
+}
+
+/// A link checking the context against `rules`; see the [module docs](self). Fails when
+/// a pattern is not a valid regex or an expression does not parse.
+pub fn validate(rules: Rules) -> Result<Link, String> {
+    let compiled = Arc::new(compile(&rules.rules)?);
+    let into = rules.into;
+    let fail = rules.fail;
+    Ok(Arc::new(move |ctx: Context| {
+        let compiled = compiled.clone();
+        let into = into.clone();
+        Box::pin(async move {
+            let mut found = Vec::new();
+            check(&compiled, &ctx, &mut found);
+            if fail && !found.is_empty() {
+                let messages: Vec<&str> = found.iter().map(|v| v.message.as_str()).collect();
+                ctx_tools::fail_run(RunError::invalid_input(messages.join("; ")));
+            }
+            ctx.insert(into.as_str(), found)
+        })
+    }))
+}
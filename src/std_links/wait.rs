@@ -0,0 +1,90 @@
+//! Waiting links: pause a run until a point in time.
+//!
+//! In a durable run (`ChainGeneric::run_durable` with checkpoints enabled) the wait parks
+//! the run: it is checkpointed with its wake-up time, the worker is released, and a
+//! [`Scheduler`](crate::chains::Scheduler) resumes it at the next link once the time has
+//! come. Anywhere else the link simply sleeps, on the executor given to [`Wait`].
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::Chain;
+//! use modulink_rs::std_links::wait_for;
+//! use std::time::Duration;
+//!
+//! let mut chain = Chain::new();
+//! // send_invoice, then:
+//! chain.add_link(wait_for(Duration::from_secs(3 * 24 * 60 * 60)));
+//! // send_reminder
+//! ```
+
+use crate::chains::RunScope;
+use crate::context::Context;
+use crate::links::Link;
+use crate::runtime::{default_executor, ExecutorObj};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn epoch_ms(at: SystemTime) -> u64 {
+    at.duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+async fn wait(ctx: Context, wake_at_ms: u64, executor: ExecutorObj) -> Context {
+    let now = epoch_ms(SystemTime::now());
+    if wake_at_ms <= now {
+        return ctx;
+    }
+    match RunScope::current() {
+        Some(scope) if scope.is_durable() => scope.park(wake_at_ms),
+        _ => executor.sleep(Duration::from_millis(wake_at_ms - now)).await,
+    }
+    ctx
+}
+
+#[derive(Clone, Copy)]
+enum WakeAt {
+    At(SystemTime),
+    After(Duration),
+}
+
+/// A waiting link's wake-up time and timer; see the [module docs](self).
+pub struct Wait {
+    wake: WakeAt,
+    executor: ExecutorObj,
+}
+
+impl Wait {
+    /// Wait until `at`.
+    pub fn until(at: SystemTime) -> Self {
+        Wait { wake: WakeAt::At(at), executor: default_executor() }
+    }
+    /// Wait `duration` from when the run reaches the link.
+    pub fn after(duration: Duration) -> Self {
+        Wait { wake: WakeAt::After(duration), executor: default_executor() }
+    }
+    /// Executor providing the timer outside durable runs (defaults to `runtime::default_executor()`).
+    pub fn with_executor(mut self, executor: ExecutorObj) -> Self {
+        self.executor = executor;
+        self
+    }
+
+    pub fn link(&self) -> Link {
+        let (wake, executor) = (self.wake, self.executor.clone());
+        Arc::new(move |ctx: Context| {
+            let wake_at = match wake {
+                WakeAt::At(at) => at,
+                WakeAt::After(duration) => SystemTime::now() + duration,
+            };
+            Box::pin(wait(ctx, epoch_ms(wake_at), executor.clone()))
+        })
+    }
+}
+
+/// A link that waits until `at`; see the [module docs](self).
+pub fn wait_until(at: SystemTime) -> Link {
+    Wait::until(at).link()
+}
+
+/// A link that waits `duration` from when the run reaches it; see the [module docs](self).
+pub fn wait_for(duration: Duration) -> Link {
+    Wait::after(duration).link()
+}
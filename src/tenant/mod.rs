@@ -0,0 +1,169 @@
+//! Multi-tenant support for modulink-rust
+//! Tenant ids live in context metadata (`context::meta::TENANT`); anything persisted on a
+//! tenant's behalf is keyed through a [`TenantScope`] so one deployment can serve many customers.
+//! [`TenantCheckpointStore`], [`TenantCache`], and [`TenantQuotas`] are views of a shared
+//! checkpoint store, cache, or [`Quotas`](crate::quota::Quotas) that only see one tenant's keys.
+//!
+//! Clients cannot choose their tenant: the HTTP, Kafka, and stdin listeners drop every
+//! reserved (`_`) key from the payloads they run (`context::meta::strip_reserved`), and
+//! [`TenantGuardMiddleware::with_claim`] sets the tenant from the validated auth claims
+//! (`context::meta::AUTH`).
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::context::Context;
+//! use modulink_rs::tenant::TenantScope;
+//!
+//! let ctx = Context::new().with_tenant("acme");
+//! let scope = TenantScope::for_tenant(ctx.tenant().unwrap());
+//! assert_eq!(scope.key("checkpoint/run-1"), "tenant/acme/checkpoint/run-1");
+//! assert!(!scope.owns("tenant/globex/checkpoint/run-1"));
+//! ```
+
+pub mod stores;
+pub use stores::{TenantCache, TenantCheckpointStore, TenantQuotas};
+
+use crate::chains::RunError;
+use crate::context::{meta, Context, ContextMutable};
+use crate::ctx_tools;
+use crate::middleware::Middleware;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Prefix shared by all tenant-scoped keys: `tenant/<id>/<key>`.
+pub const KEY_PREFIX: &str = "tenant/";
+
+/// Namespaces store keys (checkpoints, idempotency keys, queues, caches) by tenant.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TenantScope {
+    tenant: String,
+}
+
+impl TenantScope {
+    pub fn for_tenant(tenant: impl Into<String>) -> Self {
+        TenantScope { tenant: tenant.into() }
+    }
+    pub fn tenant(&self) -> &str {
+        &self.tenant
+    }
+    /// Tenant-scoped form of `key`.
+    pub fn key(&self, key: &str) -> String {
+        format!("{}{}/{}", KEY_PREFIX, self.tenant, key)
+    }
+    /// Whether `key` is scoped to this tenant.
+    pub fn owns(&self, key: &str) -> bool {
+        tenant_of_key(key) == Some(self.tenant.as_str())
+    }
+    /// The unscoped key, if `key` belongs to this tenant.
+    pub fn strip<'a>(&self, key: &'a str) -> Option<&'a str> {
+        if self.owns(key) {
+            key.get(KEY_PREFIX.len() + self.tenant.len() + 1..)
+        } else {
+            None
+        }
+    }
+}
+
+/// Tenant id encoded in a scoped key, if it is one.
+pub fn tenant_of_key(key: &str) -> Option<&str> {
+    key.strip_prefix(KEY_PREFIX)?.split_once('/').map(|(tenant, _)| tenant)
+}
+
+/// Middleware that fails the run with `Forbidden` when the context has keys scoped to a
+/// different tenant than the run's own (`_tenant`). Optionally requires every run to carry
+/// a tenant id, and takes it from an auth claim.
+#[derive(Debug, Clone, Default)]
+pub struct TenantGuardMiddleware {
+    pub require_tenant: bool,
+    /// Claim of `_auth` the run's tenant is taken from at run start.
+    pub claim: Option<String>,
+}
+
+impl TenantGuardMiddleware {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    pub fn require_tenant(mut self) -> Self {
+        self.require_tenant = true;
+        self
+    }
+    /// Set the run's tenant from `claim` of the validated auth claims, replacing any
+    /// `_tenant` the run started with (none, when the claim is missing). Attach after the
+    /// middleware that validates the token, if the listener does not.
+    pub fn with_claim(mut self, claim: impl Into<String>) -> Self {
+        self.claim = Some(claim.into());
+        self
+    }
+
+    fn derive(&self, map: &mut HashMap<String, Value>) {
+        let Some(claim) = &self.claim else { return };
+        match map.get(meta::AUTH).and_then(|auth| auth.get(claim)).and_then(Value::as_str) {
+            Some(tenant) => {
+                let tenant = Value::from(tenant);
+                map.insert(meta::TENANT.to_string(), tenant);
+            }
+            None => {
+                map.remove(meta::TENANT);
+            }
+        }
+    }
+
+    fn check(&self, map: &HashMap<String, Value>) -> Option<RunError> {
+        let tenant = map.get(meta::TENANT).and_then(Value::as_str);
+        if tenant.is_none() && self.require_tenant {
+            return Some(RunError::forbidden("run has no tenant id"));
+        }
+        for key in map.keys().map(String::as_str) {
+            if let Some(owner) = tenant_of_key(key) {
+                if Some(owner) != tenant {
+                    return Some(RunError::forbidden(format!("cross-tenant key access: {}", key)));
+                }
+            }
+        }
+        None
+    }
+
+    fn guard(&self, map: &HashMap<String, Value>) {
+        if let Some(err) = self.check(map) {
+            ctx_tools::fail_run(err);
+        }
+    }
+}
+
+impl Middleware<Context> for TenantGuardMiddleware {
+    fn on_run_start<'a>(&'a self, mut ctx: Context) -> Pin<Box<dyn Future<Output = Context> + Send + 'a>>
+    where
+        Context: 'a,
+    {
+        self.derive(&mut ctx.0);
+        Box::pin(async move { ctx })
+    }
+    fn before<'a>(&'a self, ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        self.guard(&ctx.0);
+        Box::pin(async {})
+    }
+    fn after<'a>(&'a self, ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        self.guard(&ctx.0);
+        Box::pin(async {})
+    }
+}
+
+impl Middleware<ContextMutable> for TenantGuardMiddleware {
+    fn on_run_start<'a>(&'a self, mut ctx: ContextMutable) -> Pin<Box<dyn Future<Output = ContextMutable> + Send + 'a>>
+    where
+        ContextMutable: 'a,
+    {
+        self.derive(&mut ctx.0);
+        Box::pin(async move { ctx })
+    }
+    fn before<'a>(&'a self, ctx: &'a ContextMutable) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        self.guard(&ctx.0);
+        Box::pin(async {})
+    }
+    fn after<'a>(&'a self, ctx: &'a ContextMutable) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        self.guard(&ctx.0);
+        Box::pin(async {})
+    }
+}
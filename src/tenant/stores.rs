@@ -0,0 +1,148 @@
+//! Tenant-scoped views of shared stores.
+//!
+//! Each view holds a [`TenantScope`] and stores everything under the scoped form of its
+//! keys (`tenant/<id>/<key>`), so tenants sharing one checkpoint store, cache, or
+//! [`Quotas`] can neither read nor overwrite each other's entries. Build one view per
+//! tenant, e.g. per tenant chain or when a request's tenant is known.
+//!
+//! Example:
+//! ```rust
+//! use modulink_rs::chains::{Chain, MemoryCheckpointStore};
+//! use modulink_rs::tenant::{TenantCheckpointStore, TenantScope};
+//! use std::sync::Arc;
+//!
+//! let shared = Arc::new(MemoryCheckpointStore::new());
+//! let mut acme = Chain::new();
+//! acme.enable_checkpoints(Arc::new(TenantCheckpointStore::new(TenantScope::for_tenant("acme"), shared.clone())));
+//! let mut globex = Chain::new();
+//! globex.enable_checkpoints(Arc::new(TenantCheckpointStore::new(TenantScope::for_tenant("globex"), shared)));
+//! ```
+
+use crate::cache::{CacheStats, CacheStore, CacheStoreObj};
+use crate::chains::{Checkpoint, CheckpointStore, CheckpointStoreObj, RunError};
+use crate::context::meta;
+use crate::quota::{QuotaLimits, QuotaUsage, Quotas};
+use crate::tenant::TenantScope;
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The checkpoints of one tenant in a shared [`CheckpointStore`]. Run ids are scoped, and
+/// checkpoints of runs belonging to another tenant (by their `_tenant`) are refused.
+pub struct TenantCheckpointStore {
+    scope: TenantScope,
+    inner: CheckpointStoreObj,
+}
+
+impl TenantCheckpointStore {
+    pub fn new(scope: TenantScope, inner: CheckpointStoreObj) -> Self {
+        TenantCheckpointStore { scope, inner }
+    }
+
+    // The tenant's checkpoints among `checkpoints`, with their run ids unscoped.
+    fn owned(&self, checkpoints: Vec<Checkpoint>) -> Vec<Checkpoint> {
+        checkpoints
+            .into_iter()
+            .filter_map(|mut checkpoint| {
+                checkpoint.run_id = self.scope.strip(&checkpoint.run_id)?.to_string();
+                Some(checkpoint)
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for TenantCheckpointStore {
+    async fn save(&self, checkpoint: &Checkpoint) -> std::io::Result<()> {
+        if let Some(tenant) = checkpoint.ctx.get(meta::TENANT).and_then(Value::as_str).filter(|t| *t != self.scope.tenant()) {
+            let message = format!("run '{}' of tenant '{}' cannot be checkpointed for '{}'", checkpoint.run_id, tenant, self.scope.tenant());
+            return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, message));
+        }
+        let mut scoped = checkpoint.clone();
+        scoped.run_id = self.scope.key(&checkpoint.run_id);
+        self.inner.save(&scoped).await
+    }
+    async fn load(&self, run_id: &str) -> std::io::Result<Option<Checkpoint>> {
+        let checkpoint = self.inner.load(&self.scope.key(run_id)).await?;
+        Ok(checkpoint.map(|checkpoint| Checkpoint { run_id: run_id.to_string(), ..checkpoint }))
+    }
+    async fn remove(&self, run_id: &str) -> std::io::Result<()> {
+        self.inner.remove(&self.scope.key(run_id)).await
+    }
+    async fn due(&self, now_ms: u64) -> std::io::Result<Vec<Checkpoint>> {
+        Ok(self.owned(self.inner.due(now_ms).await?))
+    }
+    async fn awaiting(&self, key: &str) -> std::io::Result<Vec<Checkpoint>> {
+        Ok(self.owned(self.inner.awaiting(key).await?))
+    }
+}
+
+/// The entries of one tenant in a shared [`CacheStore`] (response caches, idempotency
+/// keys). `stats` are those of the shared store.
+pub struct TenantCache {
+    scope: TenantScope,
+    inner: CacheStoreObj,
+}
+
+impl TenantCache {
+    pub fn new(scope: TenantScope, inner: CacheStoreObj) -> Self {
+        TenantCache { scope, inner }
+    }
+}
+
+#[async_trait]
+impl CacheStore for TenantCache {
+    async fn get(&self, key: &str) -> std::io::Result<Option<Value>> {
+        self.inner.get(&self.scope.key(key)).await
+    }
+    async fn set(&self, key: &str, value: Value, ttl: Option<Duration>) -> std::io::Result<()> {
+        self.inner.set(&self.scope.key(key), value, ttl).await
+    }
+    async fn remove(&self, key: &str) -> std::io::Result<()> {
+        self.inner.remove(&self.scope.key(key)).await
+    }
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+    fn stats(&self) -> CacheStats {
+        self.inner.stats()
+    }
+}
+
+/// The quota keys of one tenant in shared [`Quotas`]: the same API key used by two
+/// tenants is counted, limited, and reset separately for each.
+pub struct TenantQuotas {
+    scope: TenantScope,
+    quotas: Arc<Quotas>,
+}
+
+impl TenantQuotas {
+    pub fn new(scope: TenantScope, quotas: Arc<Quotas>) -> Self {
+        TenantQuotas { scope, quotas }
+    }
+    pub fn set_limits(&self, key: &str, limits: QuotaLimits) {
+        self.quotas.set_limits(self.scope.key(key), limits);
+    }
+    pub fn acquire(&self, key: &str) -> Result<(), RunError> {
+        self.quotas.acquire(&self.scope.key(key))
+    }
+    pub fn record_compute(&self, key: &str, compute: Duration) {
+        self.quotas.record_compute(&self.scope.key(key), compute);
+    }
+    /// What `key` used today, reported under the unscoped key.
+    pub fn usage(&self, key: &str) -> QuotaUsage {
+        QuotaUsage { key: key.to_string(), ..self.quotas.usage(&self.scope.key(key)) }
+    }
+    /// Usage of the tenant's keys that used anything today, sorted by key.
+    pub fn all_usage(&self) -> Vec<QuotaUsage> {
+        self.quotas
+            .all_usage()
+            .into_iter()
+            .filter_map(|usage| Some(QuotaUsage { key: self.scope.strip(&usage.key)?.to_string(), ..usage }))
+            .collect()
+    }
+    pub fn reset(&self, key: &str) -> bool {
+        self.quotas.reset(&self.scope.key(key))
+    }
+}
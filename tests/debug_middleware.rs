@@ -1,3 +1,5 @@
+#![allow(unused_imports)]
+
 use modulink_rs::context::ContextMutable;
 use modulink_rs::middleware::Middleware;
 use std::future::Future;
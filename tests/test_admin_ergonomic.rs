@@ -0,0 +1,214 @@
+//! Test the admin API for runs and chains (ergonomic pattern)
+
+use modulink_rs::admin::{Admin, AdminError, RunFilter};
+use modulink_rs::chains::{Chain, MemoryCheckpointStore, RunError, RunReport, RunStatus};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::registry;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn check_stock() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        if ctx.get::<i64>("qty").unwrap_or(0) > 10 {
+            ctx_tools::fail_run(RunError::invalid_input("out of stock"));
+            return ctx;
+        }
+        ctx.insert("reserved", true)
+    }))
+}
+
+async fn finished(admin: &Admin, id: &str) -> modulink_rs::admin::RunRecord {
+    for _ in 0..200 {
+        if let Some(record) = admin.run(id) {
+            return record;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    panic!("run {} did not finish", id);
+}
+
+#[tokio::test]
+async fn test_trigger_list_and_retry_runs() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let mut chain = Chain::new();
+    chain.set_version("v1");
+    chain.enable_checkpoints(store.clone());
+    chain.add_link_with(check_stock(), LinkSpec::new().name("reserve"));
+    registry::register_chain("admin_orders", Arc::new(chain));
+    let admin = Arc::new(Admin::new());
+    admin.watch_registered();
+
+    let info = admin.chains().into_iter().find(|c| c.name == "admin_orders").unwrap();
+    assert_eq!(info.links, vec![Some("reserve".to_string())]);
+    assert!(info.durable);
+
+    let ok = admin.trigger("admin_orders", Context::new().insert("qty", 1)).unwrap();
+    // Admin runs get their ids from the server, never from the input
+    let bad = admin.trigger("admin_orders", Context::new().insert(meta::REQUEST_ID, ok.clone()).insert("qty", 50)).unwrap();
+    assert_ne!(bad, ok);
+    assert_eq!(finished(&admin, &ok).await.report.status, RunStatus::Completed);
+    assert!(matches!(finished(&admin, &bad).await.report.status, RunStatus::Failed(_)));
+    assert!(matches!(admin.trigger("missing", Context::new()), Err(AdminError::UnknownChain(_))));
+
+    let failed = admin.runs(&RunFilter { failed: true, ..Default::default() });
+    assert_eq!(failed.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(), vec![bad.as_str()]);
+    assert_eq!(admin.runs(&RunFilter { limit: Some(1), ..Default::default() })[0].id, bad);
+    assert_eq!(admin.runs(&RunFilter { chain: Some("other".to_string()), ..Default::default() }).len(), 0);
+
+    let err = admin.retry(&bad, "ship", None).await.unwrap_err();
+    assert!(matches!(err, AdminError::InvalidInput(_)));
+    admin.retry(&bad, "reserve", Some(serde_json::json!({ "qty": 5 }))).await.unwrap();
+    for _ in 0..200 {
+        if admin.runs(&RunFilter::default()).iter().filter(|r| r.id == bad).count() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    let retried = admin.run(&bad).unwrap();
+    assert_eq!(retried.report.status, RunStatus::Completed);
+    assert_eq!(retried.ctx["reserved"], true);
+    assert!(store.is_empty());
+}
+
+#[tokio::test]
+async fn test_cancel_running_and_parked_runs() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let mut chain = Chain::new();
+    chain.enable_checkpoints(store.clone());
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        if ctx.get::<bool>("slow") == Some(true) {
+            tokio::time::sleep(Duration::from_secs(30)).await;
+        }
+        ctx
+    })));
+    chain.add_link(modulink_rs::std_links::wait_for(Duration::from_secs(3600)));
+    registry::register_chain("admin_slow", Arc::new(chain));
+    let admin = Arc::new(Admin::new());
+    admin.watch_registered();
+
+    let slow = admin.trigger("admin_slow", Context::new().insert("slow", true)).unwrap();
+    assert_eq!(admin.running()[0].id, slow);
+    admin.cancel(&slow).await.unwrap();
+    assert!(admin.running().is_empty());
+    assert_eq!(admin.run(&slow).unwrap().report.status, RunStatus::Cancelled);
+
+    let parked = admin.trigger("admin_slow", Context::new()).unwrap();
+    assert!(matches!(finished(&admin, &parked).await.report.status, RunStatus::Parked { .. }));
+    assert_eq!(store.len(), 1);
+    admin.cancel(&parked).await.unwrap();
+    assert!(store.is_empty());
+    assert!(matches!(admin.cancel(&parked).await, Err(AdminError::UnknownRun(_))));
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_admin_routes_on_http_listener() {
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+    use serde_json::{json, Value};
+
+    let mut chain = Chain::new();
+    chain.add_link_with(check_stock(), LinkSpec::new().name("reserve"));
+    let chain = Arc::new(chain);
+    registry::register_chain("admin_http", chain.clone());
+    let admin = Arc::new(Admin::new());
+    admin.watch_registered();
+    let listener = HttpListener::for_chain(chain, "127.0.0.1:8099").with_admin(admin.clone());
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let base = "http://127.0.0.1:8099/admin";
+    let chains: Value = client.get(format!("{}/chains", base)).send().await.unwrap().json().await.unwrap();
+    assert!(chains.as_array().unwrap().iter().any(|c| c["name"] == "admin_http"));
+
+    let forged = json!({ "qty": 99, "_request_id": "someone-elses-run", "_auth": { "sub": "root" } });
+    let resp = client.post(format!("{}/chains/admin_http/runs", base)).json(&forged).send().await.unwrap();
+    assert_eq!(resp.status(), 202);
+    let id = resp.json::<Value>().await.unwrap()["run_id"].as_str().unwrap().to_string();
+    assert_ne!(id, "someone-elses-run");
+    assert_eq!(finished(&admin, &id).await.ctx.get("_auth"), None);
+
+    let failed: Value = client.get(format!("{}/runs?failed=true&chain=admin_http", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(failed[0]["id"], json!(id));
+    let detail: Value = client.get(format!("{}/runs/{}", base, id)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(detail["report"]["steps"][0]["name"], "reserve");
+    assert_eq!(client.get(format!("{}/runs/nope", base)).send().await.unwrap().status(), 404);
+    assert_eq!(client.post(format!("{}/chains/nope/runs", base)).send().await.unwrap().status(), 404);
+    assert_eq!(client.post(format!("{}/runs/nope/cancel", base)).send().await.unwrap().status(), 404);
+}
+
+#[cfg(all(feature = "http", feature = "jwt"))]
+#[tokio::test]
+async fn test_admin_routes_require_the_listener_jwt() {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use modulink_rs::auth::JwtValidator;
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+    use serde_json::json;
+
+    let mut chain = Chain::new();
+    chain.add_link(check_stock());
+    let admin = Arc::new(Admin::new());
+    let listener = HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8105")
+        .with_admin(admin.clone())
+        .with_jwt(Arc::new(JwtValidator::hs256(b"admin-secret")));
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let base = "http://127.0.0.1:8105/admin";
+    assert_eq!(client.get(format!("{}/runs", base)).send().await.unwrap().status(), 401);
+    assert_eq!(client.post(format!("{}/runs/any/cancel", base)).send().await.unwrap().status(), 401);
+    let forged = encode(&Header::default(), &json!({ "sub": "eve", "exp": 4_000_000_000u64 }), &EncodingKey::from_secret(b"other")).unwrap();
+    assert_eq!(client.get(format!("{}/runs", base)).bearer_auth(forged).send().await.unwrap().status(), 401);
+
+    // A token good for /run is not enough: the admin role is required
+    let token = |claims: serde_json::Value| encode(&Header::default(), &claims, &EncodingKey::from_secret(b"admin-secret")).unwrap();
+    let user = token(json!({ "sub": "ada", "exp": 4_000_000_000u64 }));
+    assert_eq!(client.get(format!("{}/runs", base)).bearer_auth(&user).send().await.unwrap().status(), 403);
+    assert_eq!(client.post(format!("{}/quotas/any/reset", base)).bearer_auth(&user).send().await.unwrap().status(), 403);
+    let ops = token(json!({ "sub": "ops", "roles": ["admin"], "exp": 4_000_000_000u64 }));
+    assert_eq!(client.get(format!("{}/runs", base)).bearer_auth(ops).send().await.unwrap().status(), 200);
+}
+
+#[tokio::test]
+async fn test_stats_summarize_history_per_chain() {
+    let admin = Admin::new();
+    let ok = RunReport { duration: Duration::from_millis(10), ..RunReport::new(RunStatus::Completed) };
+    let failed = RunReport {
+        duration: Duration::from_millis(30),
+        ..RunReport::new(RunStatus::Failed(RunError::internal("boom")))
+    };
+    for _ in 0..3 {
+        admin.record("stats_a", &Context::new(), ok.clone());
+    }
+    admin.record("stats_a", &Context::new(), failed);
+    admin.record("stats_b", &Context::new(), RunReport::new(RunStatus::Cancelled));
+
+    let stats = admin.stats();
+    assert_eq!(stats.iter().map(|s| s.chain.as_str()).collect::<Vec<_>>(), vec!["stats_a", "stats_b"]);
+    assert_eq!((stats[0].runs, stats[0].completed, stats[0].failed), (4, 3, 1));
+    assert_eq!((stats[0].mean_ms, stats[0].p95_ms), (15, 30));
+    assert!(stats[0].last_failure_at_ms.is_some());
+    assert_eq!(stats[1].cancelled, 1);
+}
+
+#[cfg(feature = "dashboard")]
+#[tokio::test]
+async fn test_dashboard_page_is_served() {
+    let mut chain = Chain::new();
+    chain.add_link_with(check_stock(), LinkSpec::new().name("reserve"));
+    chain.connect(0, 0, |ctx: &Context| ctx.get::<bool>("again") == Some(true));
+    registry::register_chain("admin_dashboard", Arc::new(chain));
+    let admin = Arc::new(Admin::new());
+    tokio::spawn(admin.clone().serve("127.0.0.1:8100"));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let page = reqwest::get("http://127.0.0.1:8100/admin/dashboard").await.unwrap();
+    assert_eq!(page.status(), 200);
+    assert!(page.text().await.unwrap().contains("modulink dashboard"));
+    let chains: serde_json::Value = reqwest::get("http://127.0.0.1:8100/admin/chains").await.unwrap().json().await.unwrap();
+    let chain = chains.as_array().unwrap().iter().find(|c| c["name"] == "admin_dashboard").unwrap();
+    assert_eq!(chain["branches"], serde_json::json!([[0, 0]]));
+}
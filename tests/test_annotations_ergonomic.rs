@@ -0,0 +1,40 @@
+//! Test run annotations (ergonomic pattern)
+
+use futures::StreamExt;
+use modulink_rs::audit::AuditLog;
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::middleware::MetricsMiddleware;
+use modulink_rs::sinks::ChannelSink;
+use serde_json::json;
+use std::sync::Arc;
+
+fn slow_path_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        ctx.annotate("slow_path", true);
+        ctx.annotate("cache", "miss");
+        ctx.insert("result", 1)
+    }))
+}
+
+#[tokio::test]
+async fn test_annotations_reach_report_audit_and_metrics() {
+    let (tx, mut rx) = futures::channel::mpsc::channel(8);
+    let log = Arc::new(AuditLog::new(Arc::new(ChannelSink::new(tx))));
+    let metrics = Arc::new(MetricsMiddleware::new("quotes").label_annotation("cache", "cache"));
+    let mut chain = Chain::new();
+    chain.add_link(slow_path_link());
+    chain.use_middleware(Arc::new(log.middleware("quotes")));
+    chain.use_middleware(metrics.clone());
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert!(ctx.get::<bool>("slow_path").is_none());
+    assert_eq!(report.annotations["slow_path"], json!(true));
+    let record = rx.next().await.unwrap();
+    assert_eq!(record.annotations["cache"], json!("miss"));
+    assert_eq!(metrics.snapshot()[0].labels["cache"], "miss");
+
+    // Outside of a run there is nothing to annotate
+    assert!(!Context::new().annotate("slow_path", true));
+}
@@ -0,0 +1,149 @@
+//! Test the human-in-the-loop approval link (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::{meta, Context};
+use modulink_rs::std_links::{await_approval, ApprovalDecision, Approvals};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+async fn wait_for_pending(approvals: &Approvals) -> String {
+    for _ in 0..100 {
+        if let Some(pending) = approvals.pending().pop() {
+            return pending.id;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no run was parked");
+}
+
+fn refund_chain(approvals: Arc<Approvals>) -> Arc<Chain> {
+    let mut chain = Chain::new();
+    chain.add_link(await_approval(approvals, &["amount", "customer"]));
+    Arc::new(chain)
+}
+
+#[tokio::test]
+async fn test_approval_parks_run_until_decided() {
+    let approvals = Arc::new(Approvals::new());
+    let chain = refund_chain(approvals.clone());
+    let input = Context::new().insert("amount", 900).insert("customer", "c-1").insert("notes", "long text");
+    let run = tokio::spawn({
+        let chain = chain.clone();
+        async move { chain.run(input).await }
+    });
+
+    let id = wait_for_pending(&approvals).await;
+    let pending = approvals.get(&id).unwrap();
+    assert_eq!(pending.summary.len(), 2);
+    assert_eq!(pending.summary["amount"], json!(900));
+    assert!(!run.is_finished());
+
+    assert!(approvals.decide(&id, ApprovalDecision::reject().by("ops").reason("too large")));
+    assert!(!approvals.decide(&id, ApprovalDecision::approve()));
+    let ctx = run.await.unwrap();
+    assert_eq!(
+        ctx.get::<serde_json::Value>("approval"),
+        Some(json!({ "id": id, "approved": false, "by": "ops", "reason": "too large" }))
+    );
+    assert!(approvals.pending().is_empty());
+}
+
+#[tokio::test]
+async fn test_approval_ids_are_generated_and_dropped_runs_withdraw() {
+    let approvals = Arc::new(Approvals::new());
+    let chain = refund_chain(approvals.clone());
+    // Two runs reusing one request id both stay parked
+    let runs: Vec<_> = (0..2)
+        .map(|_| {
+            let chain = chain.clone();
+            tokio::spawn(async move { chain.run(Context::new().insert(meta::REQUEST_ID, "req-7")).await })
+        })
+        .collect();
+    for _ in 0..100 {
+        if approvals.pending().len() == 2 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let pending = approvals.pending();
+    assert_eq!(pending.len(), 2);
+    assert_ne!(pending[0].id, pending[1].id);
+    assert!(pending.iter().all(|p| p.request_id.as_deref() == Some("req-7")));
+    for run in runs {
+        run.abort();
+        let _ = run.await;
+    }
+    assert!(approvals.pending().is_empty());
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_approval_http_routes() {
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+
+    let approvals = Arc::new(Approvals::new());
+    let chain = refund_chain(approvals.clone());
+    let listener = HttpListener::for_chain(chain.clone(), "127.0.0.1:8097").with_approvals(approvals.clone());
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let run = tokio::spawn(client.post("http://127.0.0.1:8097/run").json(&json!({ "amount": 40 })).send());
+    let id = wait_for_pending(&approvals).await;
+
+    let listed: serde_json::Value = client.get("http://127.0.0.1:8097/approvals").send().await.unwrap().json().await.unwrap();
+    assert_eq!(listed[0]["id"], json!(id));
+    assert_eq!(listed[0]["summary"], json!({ "amount": 40 }));
+    let missing = client.post("http://127.0.0.1:8097/approvals/nope/approve").send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+
+    let approved = client
+        .post(format!("http://127.0.0.1:8097/approvals/{}/approve", id))
+        .json(&json!({ "by": "lead" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(approved.status(), 200);
+    let result: serde_json::Value = run.await.unwrap().unwrap().json().await.unwrap();
+    assert_eq!(result["approval"]["approved"], json!(true));
+    assert_eq!(result["approval"]["by"], json!("lead"));
+}
+
+#[cfg(all(feature = "http", feature = "jwt"))]
+#[tokio::test]
+async fn test_approval_routes_take_the_approver_from_the_jwt() {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use modulink_rs::auth::JwtValidator;
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+
+    let approvals = Arc::new(Approvals::new());
+    let chain = refund_chain(approvals.clone());
+    let listener = HttpListener::for_chain(chain, "127.0.0.1:8106")
+        .with_approvals(approvals.clone())
+        .with_jwt(Arc::new(JwtValidator::hs256(b"approvers")));
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let token = |claims: serde_json::Value| encode(&Header::default(), &claims, &EncodingKey::from_secret(b"approvers")).unwrap();
+    let requester = token(json!({ "sub": "ada", "exp": 4_000_000_000u64 }));
+    let run = tokio::spawn(client.post("http://127.0.0.1:8106/run").bearer_auth(&requester).json(&json!({ "amount": 40 })).send());
+    let id = wait_for_pending(&approvals).await;
+    assert_eq!(approvals.get(&id).unwrap().requested_by.as_deref(), Some("ada"));
+
+    let url = format!("http://127.0.0.1:8106/approvals/{}/approve", id);
+    assert_eq!(client.post(&url).json(&json!({ "by": "lead" })).send().await.unwrap().status(), 401);
+    assert_eq!(client.get("http://127.0.0.1:8106/approvals").send().await.unwrap().status(), 401);
+    // Tokens without the approver role, and the requester's own, are refused
+    assert_eq!(client.post(&url).bearer_auth(&requester).send().await.unwrap().status(), 403);
+    let own = token(json!({ "sub": "ada", "roles": ["approver"], "exp": 4_000_000_000u64 }));
+    assert_eq!(client.post(&url).bearer_auth(&own).send().await.unwrap().status(), 403);
+    assert_eq!(approvals.pending().len(), 1);
+
+    let lead = token(json!({ "sub": "lead", "roles": ["approver"], "exp": 4_000_000_000u64 }));
+    let approved = client.post(&url).bearer_auth(&lead).json(&json!({ "by": "ceo" })).send().await.unwrap();
+    assert_eq!(approved.status(), 200);
+    let result: serde_json::Value = run.await.unwrap().unwrap().json().await.unwrap();
+    assert_eq!(result["approval"]["by"], json!("lead"));
+}
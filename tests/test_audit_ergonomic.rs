@@ -0,0 +1,99 @@
+//! Test the hash-chained audit log (ergonomic pattern)
+
+use modulink_rs::audit::{verify, AuditLog, AuditRecord, GENESIS_HASH};
+use modulink_rs::chains::{Chain, RunError, RunStatus};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::ctx_tools;
+use modulink_rs::links::Link;
+use modulink_rs::sinks::FileSink;
+use serde_json::json;
+use std::sync::Arc;
+
+fn approve_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let amount = ctx.get::<u64>("amount").unwrap_or(0);
+        if amount > 100 {
+            ctx_tools::fail_run(RunError::limit_exceeded("amount over limit"));
+            ctx.record_decision("rejected: over limit")
+        } else {
+            ctx.record_decision("approved: under limit")
+        }
+    }))
+}
+
+fn read_records(path: &std::path::Path) -> Vec<AuditRecord> {
+    std::fs::read_to_string(path).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect()
+}
+
+#[tokio::test]
+async fn test_audit_records_are_hash_chained() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("audit.jsonl");
+    let log = Arc::new(AuditLog::new(Arc::new(FileSink::new(&path))));
+    let mut chain = Chain::new();
+    chain.add_link(approve_link());
+    chain.use_middleware(Arc::new(log.middleware("refunds").with_version("1.2.0")));
+
+    let input = Context::new()
+        .insert("amount", 40)
+        .insert(meta::AUTH, json!({"sub": "ada"}))
+        .with_tenant("acme");
+    let ctx = chain.run(input).await;
+    // Decisions in the input are not the run's own
+    chain.run(Context::new().insert("amount", 500).insert(meta::DECISIONS, json!(["approved: by the CFO"]))).await;
+
+    let records = read_records(&path);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].prev_hash, GENESIS_HASH);
+    assert_eq!(records[0].subject.as_deref(), Some("ada"));
+    assert_eq!(records[0].tenant.as_deref(), Some("acme"));
+    assert_eq!(records[0].chain_version.as_deref(), Some("1.2.0"));
+    assert_eq!(records[0].decisions, vec!["approved: under limit"]);
+    assert_eq!(records[0].status, RunStatus::Completed);
+    assert_eq!(Some(records[0].input_hash.clone()), ctx.get::<String>(meta::INPUT_HASH));
+    assert!(matches!(records[1].status, RunStatus::Failed(_)));
+    assert_eq!(records[1].decisions, vec!["rejected: over limit"]);
+    assert_eq!(records[1].prev_hash, records[0].hash);
+    assert_eq!(verify(&records), Ok(()));
+
+    // Resuming continues the same chain
+    let resumed = AuditLog::resume(Arc::new(FileSink::new(&path)), &records[1]);
+    let mut chain = Chain::new();
+    chain.add_link(approve_link());
+    chain.use_middleware(Arc::new(Arc::new(resumed).middleware("refunds")));
+    chain.run(Context::new()).await;
+    assert_eq!(verify(&read_records(&path)), Ok(()));
+}
+
+#[test]
+fn test_audit_tampering_is_detected() {
+    let record = |seq, prev_hash: &str| {
+        let mut r = AuditRecord {
+            seq,
+            timestamp_ms: 0,
+            chain: "refunds".to_string(),
+            chain_version: None,
+            subject: Some("ada".to_string()),
+            tenant: None,
+            request_id: None,
+            input_hash: String::new(),
+            decisions: vec!["approved".to_string()],
+            annotations: Default::default(),
+            status: RunStatus::Completed,
+            prev_hash: prev_hash.to_string(),
+            hash: String::new(),
+        };
+        r.hash = r.compute_hash();
+        r
+    };
+    let first = record(0, GENESIS_HASH);
+    let second = record(1, &first.hash);
+    let third = record(2, &second.hash);
+    let mut records = vec![first, second, third];
+    assert_eq!(verify(&records), Ok(()));
+
+    records[1].decisions = vec!["rejected".to_string()];
+    assert_eq!(verify(&records), Err(1));
+    records.remove(1);
+    assert_eq!(verify(&records), Err(1));
+}
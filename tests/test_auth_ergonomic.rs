@@ -0,0 +1,137 @@
+//! Test JWT validation in middleware and HttpListener (ergonomic pattern)
+#![cfg(feature = "jwt")]
+
+use jsonwebtoken::{encode, EncodingKey, Header};
+use modulink_rs::auth::{JwtMiddleware, JwtValidator};
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::links::Link;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SECRET: &[u8] = b"test-secret";
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs()
+}
+
+fn token(claims: Value) -> String {
+    encode(&Header::default(), &claims, &EncodingKey::from_secret(SECRET)).unwrap()
+}
+
+fn whoami_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let claims: Value = ctx.get(meta::AUTH).unwrap_or(Value::Null);
+        ctx.insert("user", claims["sub"].clone())
+    }))
+}
+
+fn validator() -> Arc<JwtValidator> {
+    Arc::new(JwtValidator::hs256(SECRET).with_audience(&["orders"]))
+}
+
+#[tokio::test]
+async fn test_middleware_injects_claims() {
+    let mut chain = Chain::new();
+    chain.add_link(whoami_link());
+    chain.use_middleware(Arc::new(JwtMiddleware::new(validator())));
+    let jwt = token(json!({"sub": "ada", "aud": "orders", "exp": now() + 60}));
+    let ctx = Context::new().insert(meta::AUTHORIZATION, format!("Bearer {}", jwt));
+
+    let (ctx, report) = chain.run_with_report(ctx).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<String>("user"), Some("ada".to_string()));
+    assert_eq!(ctx.get::<String>(meta::AUTHORIZATION), None);
+}
+
+#[tokio::test]
+async fn test_middleware_rejects_expired_and_wrong_audience() {
+    let mut chain = Chain::new();
+    chain.add_link(whoami_link());
+    chain.use_middleware(Arc::new(JwtMiddleware::new(validator())));
+
+    let expired = token(json!({"sub": "ada", "aud": "orders", "exp": now() - 3600}));
+    let (ctx, report) = chain.run_with_report(Context::new().insert(meta::AUTHORIZATION, format!("Bearer {}", expired))).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref e) if e.kind == ErrorKind::Unauthorized));
+    assert_eq!(ctx.get::<String>("user"), None);
+
+    let other = token(json!({"sub": "ada", "aud": "billing", "exp": now() + 60}));
+    let (_, report) = chain.run_with_report(Context::new().insert(meta::AUTHORIZATION, format!("Bearer {}", other))).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref e) if e.kind == ErrorKind::Forbidden));
+
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref e) if e.kind == ErrorKind::Unauthorized));
+}
+
+#[tokio::test]
+async fn test_jwks_validation() {
+    let jwks = json!({"keys": [{"kty": "oct", "kid": "k1", "alg": "HS256", "k": "dGVzdC1zZWNyZXQ"}]});
+    let validator = JwtValidator::from_jwks_json(&jwks.to_string()).unwrap();
+    let header = Header { kid: Some("k1".to_string()), ..Default::default() };
+    let jwt = encode(&header, &json!({"sub": "ada", "exp": now() + 60}), &EncodingKey::from_secret(SECRET)).unwrap();
+    assert_eq!(validator.validate(&jwt).unwrap()["sub"], "ada");
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_http_listener_jwt_option() {
+    use modulink_rs::links::ListenerAsync;
+    use modulink_rs::listeners::HttpListener;
+
+    let mut chain = Chain::new();
+    chain.add_link(whoami_link());
+    let listener = HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8091").with_jwt(validator());
+    tokio::spawn(async move { listener.start().await.unwrap() });
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+
+    let client = reqwest::Client::new();
+    let jwt = token(json!({"sub": "ada", "aud": "orders", "exp": now() + 60}));
+    let resp = client.post("http://127.0.0.1:8091/run").bearer_auth(jwt)
+        .json(&json!({"_auth": {"sub": "mallory"}})).send().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["user"], "ada");
+
+    let resp = client.post("http://127.0.0.1:8091/run").json(&json!({})).send().await.unwrap();
+    assert_eq!(resp.status(), 401);
+    let body: Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["kind"], "Unauthorized");
+}
+
+#[cfg(feature = "oidc")]
+#[tokio::test]
+async fn test_oidc_discovery_and_key_refresh() {
+    use axum::{extract::State, routing::get, Json, Router};
+    use modulink_rs::auth::OidcProvider;
+    use std::sync::RwLock;
+
+    let issuer = "http://127.0.0.1:8095";
+    let jwks = Arc::new(RwLock::new(json!({"keys": [{"kty": "oct", "kid": "k1", "alg": "HS256", "k": "dGVzdC1zZWNyZXQ"}]})));
+    let app = Router::new()
+        .route("/.well-known/openid-configuration", get(move || async move {
+            Json(json!({ "issuer": issuer, "jwks_uri": format!("{}/jwks", issuer) }))
+        }))
+        .route("/jwks", get(|State(jwks): State<Arc<RwLock<Value>>>| async move { Json(jwks.read().unwrap().clone()) }))
+        .with_state(jwks.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:8095").await.unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let provider = OidcProvider::discover(issuer, &["orders-api"]).await.unwrap();
+    let validator = provider.validator();
+    let signed = |kid: &str, secret: &[u8], claims: Value| {
+        let header = Header { kid: Some(kid.to_string()), ..Default::default() };
+        encode(&header, &claims, &EncodingKey::from_secret(secret)).unwrap()
+    };
+    let claims = json!({"sub": "ada", "aud": "orders-api", "iss": issuer, "exp": now() + 60});
+    assert_eq!(validator.validate(&signed("k1", SECRET, claims.clone())).unwrap()["sub"], "ada");
+    let foreign = json!({"sub": "ada", "aud": "orders-api", "iss": "https://other", "exp": now() + 60});
+    assert_eq!(validator.validate(&signed("k1", SECRET, foreign)).unwrap_err().kind, ErrorKind::Forbidden);
+
+    // Provider rotates to a new key; tokens signed with it validate after a refresh
+    *jwks.write().unwrap() = json!({"keys": [{"kty": "oct", "kid": "k2", "alg": "HS256", "k": "cm90YXRlZC1zZWNyZXQ"}]});
+    let rotated = signed("k2", b"rotated-secret", claims);
+    assert_eq!(validator.validate(&rotated).unwrap_err().kind, ErrorKind::Unauthorized);
+    provider.refresh_keys().await.unwrap();
+    assert_eq!(validator.validate(&rotated).unwrap()["sub"], "ada");
+}
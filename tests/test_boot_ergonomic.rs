@@ -0,0 +1,120 @@
+//! Test booting chains and listeners from an app definition (ergonomic pattern)
+#![cfg(feature = "http")]
+
+use modulink_rs::boot;
+use modulink_rs::context::Context;
+use modulink_rs::definitions::{AppDefinition, DefinitionError};
+use modulink_rs::registry;
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+fn register_links() {
+    registry::register_link("boot_test::greet", Arc::new(|ctx: Context| Box::pin(async move {
+        let name = ctx.get::<String>("name").unwrap_or_default();
+        ctx.insert("greeting", format!("hello {}", name))
+    })));
+}
+
+const APP: &str = r#"{
+    "chains": [ { "name": "greeter", "links": [ { "link": "boot_test::greet" } ] } ],
+    "listeners": [
+        { "type": "http", "chain": "greeter", "address": "127.0.0.1:8102",
+          "limits": { "max_body_bytes": 256, "rate_per_second": 0.01, "burst": 2 } },
+        { "type": "stdin", "chain": "greeter" }
+    ]
+}"#;
+
+#[tokio::test]
+async fn test_boot_from_file_serves_with_limits() {
+    register_links();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.json");
+    std::fs::write(&path, APP).unwrap();
+    let app = boot::from_file(&path).unwrap();
+    assert_eq!(app.chain("greeter").unwrap().name(), Some("greeter"));
+    let names: Vec<_> = app.listeners().iter().map(|l| l.name()).collect();
+    assert_eq!(names, vec!["http", "stdin"]);
+
+    // Serve only the HTTP listener; the stdin one would read the test runner's stdin.
+    let mut def = AppDefinition::from_json(APP).unwrap();
+    def.listeners.truncate(1);
+    let app = boot::App::build(&def, None).unwrap();
+    tokio::spawn(async move { app.run().await.unwrap() });
+    sleep(Duration::from_millis(300)).await;
+
+    let client = reqwest::Client::new();
+    let resp = client.post("http://127.0.0.1:8102/run").json(&serde_json::json!({"name": "ana"})).send().await.unwrap();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["greeting"], "hello ana");
+    let too_big = client.post("http://127.0.0.1:8102/run").json(&serde_json::json!({"name": "x".repeat(1000)})).send().await.unwrap();
+    assert_eq!(too_big.status(), 413);
+    let limited = client.post("http://127.0.0.1:8102/run").json(&serde_json::json!({"name": "bo"})).send().await.unwrap();
+    assert_eq!(limited.status(), 429);
+}
+
+#[test]
+fn test_boot_errors() {
+    register_links();
+    let unknown = AppDefinition::from_json(r#"{ "listeners": [ { "type": "stdin", "chain": "boot_test::missing" } ] }"#).unwrap();
+    assert!(matches!(boot::App::build(&unknown, None), Err(DefinitionError::UnknownChain(name)) if name == "boot_test::missing"));
+
+    let bad_address = AppDefinition::from_json(r#"{
+        "chains": [ { "name": "greeter", "links": [ { "link": "boot_test::greet" } ] } ],
+        "listeners": [ { "type": "http", "chain": "greeter", "address": "not an address" } ]
+    }"#).unwrap();
+    assert!(matches!(boot::App::build(&bad_address, None), Err(DefinitionError::Listener(_))));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.ini");
+    std::fs::write(&path, APP).unwrap();
+    assert!(matches!(boot::from_file(&path), Err(DefinitionError::Parse(_))));
+}
+
+#[test]
+fn test_registered_chains_can_be_served() {
+    registry::register_chain("boot_test::registered", Arc::new(modulink_rs::chains::Chain::new()));
+    let def = AppDefinition::from_json(r#"{ "listeners": [ { "type": "stdin", "chain": "boot_test::registered" } ] }"#).unwrap();
+    let app = boot::App::build(&def, None).unwrap();
+    assert_eq!(app.listeners().len(), 1);
+    assert!(app.chain("boot_test::registered").is_none());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_app_definition() {
+    let yaml = "
+chains:
+  - name: greeter
+    links:
+      - link: boot_test::greet
+listeners:
+  - type: http
+    chain: greeter
+    address: 127.0.0.1:8103
+    auth:
+      forward_authorization: true
+    limits: { rate_per_second: 5 }
+";
+    let def = AppDefinition::from_yaml(yaml).unwrap();
+    assert_eq!(def, AppDefinition::from_json(&serde_json::to_string(&def).unwrap()).unwrap());
+    assert_eq!(def.listeners[0].chain(), "greeter");
+}
+
+#[cfg(feature = "toml")]
+#[test]
+fn test_toml_app_definition() {
+    let toml = r#"
+[[chains]]
+name = "greeter"
+links = [ { link = "boot_test::greet" } ]
+
+[[listeners]]
+type = "http"
+chain = "greeter"
+address = "127.0.0.1:8104"
+limits = { max_body_bytes = 1024 }
+"#;
+    let def = AppDefinition::from_toml(toml).unwrap();
+    assert_eq!(def.chains[0].name, "greeter");
+    assert_eq!(def.listeners[0].chain(), "greeter");
+}
@@ -1,5 +1,7 @@
 //! Test branching logic in chains (ergonomic pattern)
 
+#![allow(clippy::type_complexity, clippy::let_and_return)]
+
 use modulink_rs::context::Context;
 use modulink_rs::chains::Chain;
 use std::sync::Arc;
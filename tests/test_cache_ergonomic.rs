@@ -0,0 +1,55 @@
+//! Test cache stores (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::cache::{CacheStats, CacheStore, MemoryCache, RedisCache, RedisClient};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_memory_cache_lru_and_ttl() {
+    let cache = MemoryCache::new(2);
+    cache.set("a", json!(1), None).await.unwrap();
+    cache.set("b", json!(2), None).await.unwrap();
+    // Touch "a" so "b" is the least recently used
+    assert_eq!(cache.get("a").await.unwrap(), Some(json!(1)));
+    cache.set("c", json!(3), None).await.unwrap();
+    assert_eq!(cache.get("b").await.unwrap(), None);
+    assert_eq!(cache.get("c").await.unwrap(), Some(json!(3)));
+
+    cache.set("short", json!("x"), Some(Duration::from_millis(10))).await.unwrap();
+    tokio::time::sleep(Duration::from_millis(30)).await;
+    assert_eq!(cache.get("short").await.unwrap(), None);
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 2, sets: 4, evictions: 2 });
+}
+
+#[derive(Default)]
+struct FakeRedis(Mutex<HashMap<String, Vec<u8>>>);
+
+#[async_trait]
+impl RedisClient for FakeRedis {
+    async fn get(&self, key: &str) -> std::io::Result<Option<Vec<u8>>> {
+        Ok(self.0.lock().unwrap().get(key).cloned())
+    }
+    async fn set(&self, key: &str, value: Vec<u8>, _ttl: Option<Duration>) -> std::io::Result<()> {
+        self.0.lock().unwrap().insert(key.to_string(), value);
+        Ok(())
+    }
+    async fn del(&self, key: &str) -> std::io::Result<()> {
+        self.0.lock().unwrap().remove(key);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_redis_cache_prefixes_keys() {
+    let redis = Arc::new(FakeRedis::default());
+    let cache = RedisCache::new(redis.clone()).with_prefix("modulink:");
+    cache.set("order:1", json!({ "total": 10 }), Some(Duration::from_secs(60))).await.unwrap();
+    assert!(redis.0.lock().unwrap().contains_key("modulink:order:1"));
+    assert_eq!(cache.get("order:1").await.unwrap(), Some(json!({ "total": 10 })));
+    cache.remove("order:1").await.unwrap();
+    assert_eq!(cache.get("order:1").await.unwrap(), None);
+    assert_eq!((cache.stats().hits, cache.stats().misses), (1, 1));
+}
@@ -0,0 +1,105 @@
+//! Test canary runs comparing two chain versions (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::canary::Canary;
+use modulink_rs::chains::{Chain, RunError, RunStatus, Shadow};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::sinks::BaseSink;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+struct CountingSink(Arc<AtomicUsize>);
+
+#[async_trait]
+impl BaseSink<Context> for CountingSink {
+    async fn deliver(&self, _ctx: &Context) -> std::io::Result<()> {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+    fn name(&self) -> &'static str {
+        "counting"
+    }
+}
+
+fn price(rate: f64) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move {
+        let amount = ctx.get::<f64>("amount").unwrap_or(0.0);
+        if amount < 0.0 && rate > 0.2 {
+            ctx_tools::fail_run(RunError::invalid_input("negative amount"));
+        }
+        ctx.insert("tax", (amount * rate * 100.0).round() / 100.0).insert("priced_at", rate.to_string())
+    }))
+}
+
+fn charge(charges: Arc<AtomicUsize>) -> Link {
+    Arc::new(move |ctx: Context| {
+        let charges = charges.clone();
+        Box::pin(async move {
+            charges.fetch_add(1, Ordering::SeqCst);
+            ctx.insert("charged", "ok")
+        })
+    })
+}
+
+fn chain(rate: f64, charges: Arc<AtomicUsize>, delivered: Arc<AtomicUsize>) -> Arc<Chain> {
+    let mut chain = Chain::new();
+    chain.add_link(price(rate));
+    chain.add_link_with(charge(charges), LinkSpec::new().name("charge").side_effects());
+    chain.pipe_to(Arc::new(CountingSink(delivered)));
+    Arc::new(chain)
+}
+
+#[tokio::test]
+async fn test_canary_suppresses_candidate_side_effects() {
+    let (charges, delivered) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let live = chain(0.2, charges.clone(), delivered.clone());
+    let candidate = chain(0.2, charges.clone(), delivered.clone());
+    let mock: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("charged", "ok") }));
+    let canary = Canary::new(live, candidate).shadow(Shadow::new().mock("charge", mock));
+
+    let (ctx, report) = canary.run(Context::new().insert("amount", 50.0)).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<String>("charged").as_deref(), Some("ok"));
+    // Only the live run charged and delivered
+    assert_eq!(charges.load(Ordering::SeqCst), 1);
+    assert_eq!(delivered.load(Ordering::SeqCst), 1);
+    assert_eq!(canary.report().diverged, 0);
+    let calls = canary.shadow_calls();
+    assert_eq!((calls.len(), calls[0].name.as_deref(), calls[0].mocked), (1, Some("charge"), true));
+    assert_eq!(calls[0].input["tax"], 10.0);
+
+    // compare() keeps both sides free of side effects
+    let comparison = canary.compare(Context::new().insert("amount", 10.0)).await;
+    assert!(!comparison.diverged());
+    assert_eq!((charges.load(Ordering::SeqCst), delivered.load(Ordering::SeqCst)), (1, 1));
+}
+
+#[tokio::test]
+async fn test_canary_reports_divergence_rates() {
+    let (charges, delivered) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let diverging = Arc::new(Mutex::new(Vec::new()));
+    let seen = diverging.clone();
+    let canary = Canary::new(chain(0.2, charges.clone(), delivered.clone()), chain(0.25, charges.clone(), delivered))
+        .ignore(["priced_at"])
+        .on_divergence(move |input, _| seen.lock().unwrap().push(input["amount"].clone()));
+
+    for amount in [0.0, 10.0, 0.0, -4.0] {
+        canary.compare(Context::new().insert("amount", amount)).await;
+    }
+    let comparison = canary.compare(Context::new().insert("amount", 20.0)).await;
+    assert_eq!(comparison.divergences[0].path, "tax");
+    assert_eq!((comparison.divergences[0].live.clone(), comparison.divergences[0].candidate.clone()), (Some(4.0.into()), Some(5.0.into())));
+
+    let report = canary.report();
+    assert_eq!((report.runs, report.diverged), (5, 3));
+    assert_eq!(report.divergence_rate, 0.6);
+    assert_eq!((report.status_mismatches, report.candidate_failures), (1, 1));
+    assert_eq!(report.fields.get("tax"), Some(&3));
+    assert!(!report.fields.contains_key("priced_at"));
+    // Unmocked side-effecting links are skipped on both sides
+    assert_eq!(charges.load(Ordering::SeqCst), 0);
+    assert_eq!(canary.shadow_calls().iter().filter(|call| !call.mocked).count(), 9);
+    assert_eq!(*diverging.lock().unwrap(), vec![serde_json::json!(10.0), serde_json::json!(-4.0), serde_json::json!(20.0)]);
+}
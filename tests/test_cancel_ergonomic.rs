@@ -0,0 +1,137 @@
+//! Test run cancellation (ergonomic pattern)
+
+use modulink_rs::chains::{CancellationToken, Chain, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::Link;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn set(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn cancel_with(token: &CancellationToken, key: &'static str) -> Link {
+    let token = token.clone();
+    Arc::new(move |ctx: Context| {
+        token.cancel();
+        Box::pin(async move { ctx.insert(key, true) })
+    })
+}
+
+#[tokio::test]
+async fn test_run_stops_before_next_link_once_cancelled() {
+    let token = CancellationToken::new();
+    let mut chain = Chain::new();
+    chain.add_link(set("validated"));
+    chain.add_link(cancel_with(&token, "reserved"));
+    chain.add_link(set("charged"));
+
+    let (ctx, report) = chain.run_with_cancel(Context::new(), &token).await;
+    assert_eq!(report.status, RunStatus::Cancelled);
+    assert_eq!(ctx.get::<bool>("reserved"), Some(true));
+    assert_eq!(ctx.get::<bool>("charged"), None);
+    assert_eq!(report.steps.len(), 2);
+}
+
+#[tokio::test]
+async fn test_token_cancelled_up_front_runs_no_links() {
+    let token = CancellationToken::new();
+    token.cancel();
+    let mut chain = Chain::new();
+    chain.add_link(set("validated"));
+
+    let (ctx, report) = chain.run_with_cancel(Context::new(), &token).await;
+    assert_eq!(report.status, RunStatus::Cancelled);
+    assert_eq!(ctx.get::<bool>("validated"), None);
+    assert!(report.steps.is_empty());
+}
+
+#[tokio::test]
+async fn test_uncancelled_run_completes() {
+    let token = CancellationToken::new();
+    let mut chain = Chain::new();
+    chain.add_link(set("validated"));
+
+    let (ctx, report) = chain.run_with_cancel(Context::new(), &token).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("validated"), Some(true));
+}
+
+#[tokio::test]
+async fn test_cancel_from_another_task_while_link_runs() {
+    let token = CancellationToken::new();
+    let checked = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    // Cooperates by polling `ctx_tools::is_cancelled`
+    chain.add_link({
+        let checked = checked.clone();
+        Arc::new(move |ctx: Context| {
+            let checked = checked.clone();
+            Box::pin(async move {
+                while !ctx_tools::is_cancelled() {
+                    checked.fetch_add(1, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                }
+                ctx.insert("stopped", true)
+            })
+        })
+    });
+    chain.add_link(set("charged"));
+
+    let cancel = {
+        let token = token.clone();
+        async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            token.cancel();
+        }
+    };
+    let ((ctx, report), ()) =
+        tokio::time::timeout(Duration::from_secs(5), async { tokio::join!(chain.run_with_cancel(Context::new(), &token), cancel) })
+            .await
+            .unwrap();
+    assert_eq!(report.status, RunStatus::Cancelled);
+    assert_eq!(ctx.get::<bool>("stopped"), Some(true));
+    assert_eq!(ctx.get::<bool>("charged"), None);
+    assert!(checked.load(Ordering::SeqCst) > 0);
+}
+
+#[tokio::test]
+async fn test_sub_chain_is_cancelled_with_parent() {
+    let token = CancellationToken::new();
+    let mut inner = Chain::new();
+    inner.add_link(cancel_with(&token, "inner_first"));
+    inner.add_link(set("inner_second"));
+
+    let mut outer = Chain::new();
+    outer.add_chain(Arc::new(inner));
+    outer.add_link(set("outer_after"));
+
+    let (ctx, report) = outer.run_with_cancel(Context::new(), &token).await;
+    assert_eq!(report.status, RunStatus::Cancelled);
+    assert_eq!(ctx.get::<bool>("inner_first"), Some(true));
+    assert_eq!(ctx.get::<bool>("inner_second"), None);
+    assert_eq!(ctx.get::<bool>("outer_after"), None);
+}
+
+#[tokio::test]
+async fn test_cancelled_future_resolves_on_cancel() {
+    let token = CancellationToken::new();
+    assert!(!token.is_cancelled());
+    let waiter = tokio::spawn({
+        let token = token.clone();
+        async move { token.cancelled().await }
+    });
+    tokio::task::yield_now().await;
+    token.cancel();
+    tokio::time::timeout(Duration::from_secs(5), waiter).await.unwrap().unwrap();
+    assert!(token.is_cancelled());
+    // Resolves right away once cancelled
+    token.cancelled().await;
+}
+
+#[test]
+fn test_is_cancelled_outside_of_a_run() {
+    assert!(!ctx_tools::is_cancelled());
+}
@@ -1,5 +1,7 @@
 //! Basic tests for modulink-rs Chain and Link (ergonomic pattern)
 
+#![allow(clippy::type_complexity, clippy::let_and_return)]
+
 use modulink_rs::chains::Chain;
 use modulink_rs::context::Context;
 use modulink_rs::middleware::logging_middleware;
@@ -0,0 +1,64 @@
+//! Test per-link metrics collection (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ChainMetrics, ErrorRoute, RunError, Shadow};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn sleeper(ms: u64) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move {
+        tokio::time::sleep(Duration::from_millis(ms)).await;
+        ctx
+    }))
+}
+
+#[tokio::test]
+async fn test_counts_durations_and_errors() {
+    let mut chain = Chain::new();
+    chain.add_link_with(sleeper(5), LinkSpec::new().name("load"));
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move {
+            if ctx.get::<bool>("bad") == Some(true) {
+                ctx_tools::fail_run(RunError::invalid_input("bad input"));
+            }
+            ctx
+        })),
+        LinkSpec::new().name("check"),
+    );
+    chain.add_link(sleeper(0));
+    assert_eq!(chain.metrics(), None);
+    chain.enable_metrics();
+
+    for bad in [false, false, true] {
+        chain.run(Context::new().insert("bad", bad)).await;
+    }
+    let metrics = chain.metrics().unwrap();
+    assert_eq!((metrics.runs, metrics.failed_runs), (3, 1));
+    let calls: Vec<_> = metrics.links.iter().map(|l| (l.link, l.name.as_deref(), l.calls, l.errors)).collect();
+    assert_eq!(calls, vec![(0, Some("load"), 3, 0), (1, Some("check"), 3, 1), (2, None, 2, 0)]);
+    let load = &metrics.links[0];
+    assert!(load.p50 >= Duration::from_millis(5));
+    assert!(load.p50 <= load.p95 && load.p95 <= load.max);
+
+    // Snapshots serialize for shipping elsewhere.
+    let json = serde_json::to_string(&metrics).unwrap();
+    assert_eq!(serde_json::from_str::<ChainMetrics>(&json).unwrap(), metrics);
+}
+
+#[tokio::test]
+async fn test_skipped_errors_and_shadow_runs() {
+    let mut chain = Chain::new();
+    chain.add_fallible_link(Arc::new(|_ctx: Context| Box::pin(async move { Err(RunError::internal("cache down")) })));
+    chain.add_link_with(sleeper(0), LinkSpec::new().name("notify").side_effects());
+    chain.route_errors(0, ErrorRoute::Skip);
+    chain.enable_metrics();
+
+    chain.run(Context::new()).await;
+    chain.run_shadow(Context::new(), &Shadow::new()).await;
+    let metrics = chain.metrics().unwrap();
+    assert_eq!((metrics.runs, metrics.failed_runs), (1, 0));
+    assert_eq!((metrics.links[0].calls, metrics.links[0].errors), (1, 1));
+    assert_eq!(metrics.links[1].calls, 1);
+}
@@ -1,6 +1,9 @@
 //! Test core chain composition and execution (ergonomic pattern)
 
+#![allow(clippy::type_complexity, clippy::let_and_return)]
+
 use modulink_rs::context::Context;
+use modulink_rs::links::Link;
 use modulink_rs::chains::Chain;
 use std::sync::Arc;
 
@@ -75,3 +78,31 @@ async fn test_chain_macro_ergonomic() {
     let result = chain.run(ctx).await;
     assert_eq!(result.get::<String>("error"), Some("missing input".to_string()));
 }
+
+#[tokio::test]
+async fn test_run_report() {
+    use modulink_rs::chains::RunStatus;
+    use modulink_rs::ctx_tools;
+    use modulink_rs::links::LinkSpec;
+
+    let flaky: Link = Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::record_retry();
+        ctx_tools::warn("provider slow, used cache");
+        ctx.insert("fetched", true)
+    }));
+    let mut chain = Chain::new();
+    chain.add_link_with(flaky, LinkSpec::new().name("fetch"));
+    chain.add_link(add_key_link("skipped", 1));
+    chain.add_link(add_key_link("done", 1));
+    chain.connect(0, 2, |ctx: &Context| ctx.get::<bool>("fetched") == Some(true));
+
+    let (ctx, report) = chain.run_report(Context::new()).await;
+    assert_eq!(ctx.get::<i32>("done"), Some(1));
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(report.steps.iter().map(|s| s.link).collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(report.steps[0].name.as_deref(), Some("fetch"));
+    assert!(report.duration >= report.steps.iter().map(|s| s.duration).sum::<std::time::Duration>());
+    assert_eq!(report.branches, vec![(0, 2)]);
+    assert_eq!(report.retries, 1);
+    assert_eq!(report.warnings, vec!["provider slow, used cache".to_string()]);
+}
@@ -0,0 +1,214 @@
+//! Test durable runs, checkpoints, and version migration (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, Checkpoint, CheckpointStore, FileCheckpointStore, MemoryCheckpointStore, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::definitions::ChainDefinition;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::registry;
+use std::sync::Arc;
+
+fn step(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn crash() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::internal("worker restarted"));
+        ctx
+    }))
+}
+
+#[tokio::test]
+async fn test_resume_continues_from_checkpoint() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let mut v1 = Chain::new();
+    v1.set_version("v1");
+    v1.enable_checkpoints(store.clone());
+    v1.add_link_with(step("charged"), LinkSpec::new().name("charge"));
+    v1.add_link_with(crash(), LinkSpec::new().name("ship"));
+    let (_, report) = v1.run_durable("order-1", Context::new().insert("order", 1)).await;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+    assert_eq!(report.version.as_deref(), Some("v1"));
+    assert_eq!(store.len(), 1);
+
+    let mut fixed = Chain::new();
+    fixed.set_version("v1");
+    fixed.enable_checkpoints(store.clone());
+    fixed.add_link_with(crash(), LinkSpec::new().name("charge"));
+    fixed.add_link_with(step("shipped"), LinkSpec::new().name("ship"));
+    let (ctx, report) = fixed.resume("order-1").await.unwrap();
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("charged"), Some(true));
+    assert_eq!(ctx.get::<bool>("shipped"), Some(true));
+    assert!(store.is_empty());
+    assert_eq!(fixed.resume("order-1").await.unwrap_err().kind(), std::io::ErrorKind::NotFound);
+}
+
+#[tokio::test]
+async fn test_resume_under_new_version_migrates_checkpoint() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let mut v1 = Chain::new();
+    v1.set_version("v1");
+    v1.enable_checkpoints(store.clone());
+    v1.add_link_with(step("charged"), LinkSpec::new().name("charge"));
+    v1.add_link_with(crash(), LinkSpec::new().name("dispatch"));
+    v1.run_durable("order-2", Context::new()).await;
+
+    let mut v2 = Chain::new();
+    v2.set_version("v2");
+    v2.enable_checkpoints(store.clone());
+    v2.add_link_with(crash(), LinkSpec::new().name("charge"));
+    v2.add_link_with(crash(), LinkSpec::new().name("notify"));
+    v2.add_link_with(step("shipped"), LinkSpec::new().name("ship"));
+
+    // Without a migration the renamed link cannot be found
+    let err = v2.resume("order-2").await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+
+    v2.on_version_change(|mut checkpoint| {
+        assert_eq!(checkpoint.version.as_deref(), Some("v1"));
+        if checkpoint.link_name.as_deref() == Some("dispatch") {
+            checkpoint.link_name = Some("ship".to_string());
+        }
+        checkpoint.ctx["migrated"] = true.into();
+        Ok(checkpoint)
+    });
+    let (ctx, report) = v2.resume("order-2").await.unwrap();
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(report.version.as_deref(), Some("v2"));
+    assert_eq!(ctx.get::<bool>("migrated"), Some(true));
+    assert_eq!(ctx.get::<bool>("shipped"), Some(true));
+}
+
+#[tokio::test]
+async fn test_definition_chains_are_versioned_by_hash() {
+    registry::register_link("checkpoint_step", step("done"));
+    let def = ChainDefinition::from_json(r#"{ "name": "durable", "links": [ { "link": "checkpoint_step" } ] }"#).unwrap();
+    let chain = def.build(None).unwrap();
+    assert_eq!(chain.version(), Some(def.version_hash().as_str()));
+    assert_eq!(def.version_hash().len(), 64);
+
+    let mut changed = def.clone();
+    changed.links.push(changed.links[0].clone());
+    assert_ne!(changed.version_hash(), def.version_hash());
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.version, Some(def.version_hash()));
+}
+
+#[tokio::test]
+async fn test_retry_from_step_patches_context() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let mut chain = Chain::new();
+    chain.enable_checkpoints(store.clone());
+    chain.add_link_with(step("charged"), LinkSpec::new().name("charge"));
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move {
+            if ctx.get::<String>("address").is_none() {
+                ctx_tools::fail_run(RunError::invalid_input("no address"));
+                return ctx;
+            }
+            ctx.insert("shipped", true)
+        })),
+        LinkSpec::new().name("ship"),
+    );
+    let (_, report) = chain.run_durable("order-3", Context::new().insert("order", 3).insert("typo", 1)).await;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+
+    let err = chain.retry_from_step("order-3", "pack", None).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let patch = serde_json::json!({ "address": "1 Main St", "typo": null });
+    let (ctx, report) = chain.retry_from_step("order-3", "ship", Some(patch)).await.unwrap();
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(report.steps.len(), 1);
+    assert_eq!(ctx.get::<i64>("order"), Some(3));
+    assert_eq!(ctx.get::<bool>("shipped"), Some(true));
+    assert!(!ctx.0.contains_key("typo"));
+    assert!(store.is_empty());
+}
+
+#[tokio::test]
+async fn test_file_store_survives_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let build = |last: Link| {
+        let mut chain = Chain::new();
+        chain.enable_checkpoints(Arc::new(FileCheckpointStore::new(dir.path().join("checkpoints")).unwrap()));
+        chain.add_link_with(step("charged"), LinkSpec::new().name("charge"));
+        chain.add_link_with(last, LinkSpec::new().name("ship"));
+        chain
+    };
+    let (_, report) = build(crash()).run_durable("tenant/order 3", Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+
+    // A new process: a fresh store over the same directory
+    let restarted = build(step("shipped"));
+    let (ctx, report) = restarted.resume("tenant/order 3").await.unwrap();
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("charged"), Some(true));
+    assert_eq!(ctx.get::<bool>("shipped"), Some(true));
+    assert_eq!(std::fs::read_dir(dir.path().join("checkpoints")).unwrap().count(), 0);
+}
+
+#[tokio::test]
+async fn test_file_store_operations() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = FileCheckpointStore::new(dir.path()).unwrap();
+    let checkpoint = |run_id: &str, wake_at_ms: Option<u64>, awaiting: Option<&str>| Checkpoint {
+        run_id: run_id.to_string(),
+        version: None,
+        link: 1,
+        link_name: None,
+        ctx: serde_json::json!({ "run": run_id }),
+        wake_at_ms,
+        awaiting_event: awaiting.map(str::to_string),
+    };
+    store.save(&checkpoint("a", Some(300), None)).await.unwrap();
+    store.save(&checkpoint("b", Some(100), None)).await.unwrap();
+    store.save(&checkpoint("c", None, Some("paid:c"))).await.unwrap();
+    store.save(&checkpoint("../escape", None, None)).await.unwrap();
+
+    assert_eq!(store.load("a").await.unwrap(), Some(checkpoint("a", Some(300), None)));
+    assert_eq!(store.load("missing").await.unwrap(), None);
+    assert!(store.load("../escape").await.unwrap().is_some());
+    assert!(!dir.path().parent().unwrap().join("escape.json").exists());
+
+    let due: Vec<String> = store.due(300).await.unwrap().into_iter().map(|c| c.run_id).collect();
+    assert_eq!(due, vec!["b", "a"]);
+    assert_eq!(store.awaiting("paid:c").await.unwrap()[0].run_id, "c");
+
+    // Saving again replaces the checkpoint
+    store.save(&checkpoint("a", None, None)).await.unwrap();
+    assert_eq!(store.due(300).await.unwrap().len(), 1);
+    store.remove("a").await.unwrap();
+    store.remove("a").await.unwrap();
+    assert_eq!(store.load("a").await.unwrap(), None);
+}
+
+#[tokio::test]
+async fn test_file_store_io_runs_on_the_executor() {
+    use modulink_rs::runtime::MockExecutor;
+
+    let dir = tempfile::tempdir().unwrap();
+    let exec = MockExecutor::new();
+    let store = Arc::new(FileCheckpointStore::new(dir.path()).unwrap().with_executor(Arc::new(exec.clone())));
+    let checkpoint = Checkpoint {
+        run_id: "r".to_string(),
+        version: None,
+        link: 0,
+        link_name: None,
+        ctx: serde_json::json!({}),
+        wake_at_ms: None,
+        awaiting_event: None,
+    };
+    let saving = tokio::spawn({
+        let store = store.clone();
+        async move { store.save(&checkpoint).await }
+    });
+
+    // The write waits for the executor's blocking pool
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!dir.path().join("r.json").exists());
+    exec.run_until_stalled();
+    saving.await.unwrap().unwrap();
+    assert!(dir.path().join("r.json").exists());
+}
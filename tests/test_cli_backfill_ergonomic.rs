@@ -0,0 +1,112 @@
+//! Test `modulink-cli backfill` (ergonomic pattern)
+#![cfg(feature = "cli")]
+
+use clap::Parser;
+use modulink_rs::chains::{Chain, RunError};
+use modulink_rs::cli::backfill::{source_files, Backfill, Cursor, Rate};
+use modulink_rs::cli::{self, Cli};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::LinkSpec;
+use modulink_rs::pipe::Connectors;
+use modulink_rs::registry;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn counting_chain(seen: Arc<AtomicUsize>) -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        if ctx.get::<bool>("bad") == Some(true) {
+            ctx_tools::fail_run(RunError::invalid_input("bad event"));
+        }
+        ctx
+    })));
+    chain.add_link_with(
+        Arc::new(move |ctx: Context| {
+            let seen = seen.clone();
+            Box::pin(async move {
+                seen.fetch_add(1, Ordering::SeqCst);
+                ctx
+            })
+        }),
+        LinkSpec::new().name("store").side_effects(),
+    );
+    chain
+}
+
+fn write_events(dir: &std::path::Path) {
+    std::fs::write(dir.join("a.jsonl"), "{\"id\":1}\n{\"id\":2,\"bad\":true}\n\nnot json\n{\"id\":3}\n").unwrap();
+    std::fs::write(dir.join("b.jsonl"), "{\"id\":4}\n{\"id\":5}\n").unwrap();
+    std::fs::write(dir.join("notes.txt"), "ignored\n").unwrap();
+}
+
+#[test]
+fn test_rates_and_sources_parse() {
+    assert_eq!("200/s".parse::<Rate>().unwrap().interval(), Duration::from_millis(5));
+    assert_eq!("60/m".parse::<Rate>().unwrap().interval(), Duration::from_secs(1));
+    assert_eq!("10".parse::<Rate>().unwrap().interval(), Duration::from_millis(100));
+    assert!("0/s".parse::<Rate>().is_err());
+    assert!("5/fortnight".parse::<Rate>().is_err());
+
+    let dir = tempfile::tempdir().unwrap();
+    write_events(dir.path());
+    let files = source_files(&format!("file://{}/*.jsonl", dir.path().display())).unwrap();
+    assert_eq!(files.iter().map(|f| f.file_name().unwrap().to_str().unwrap()).collect::<Vec<_>>(), vec!["a.jsonl", "b.jsonl"]);
+    assert!(source_files("kafka:events").is_err());
+}
+
+#[tokio::test]
+async fn test_backfill_replays_and_resumes_from_cursor() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events(dir.path());
+    let source = format!("file://{}/*.jsonl", dir.path().display());
+    let cursor = dir.path().join("cursor.json");
+    let seen = Arc::new(AtomicUsize::new(0));
+    let chain = counting_chain(seen.clone());
+
+    let dry = Backfill::new(&source).cursor(&cursor).dry_run(true).run("events", &chain).await.unwrap();
+    assert_eq!((dry.files, dry.replayed, dry.invalid, dry.failed.len()), (2, 5, 1, 1));
+    // The counting link is tagged as side-effecting, so the dry run skipped it
+    assert_eq!(seen.load(Ordering::SeqCst), 0);
+    assert!(!cursor.exists());
+
+    // Pretend an earlier backfill stopped after the first two lines of a.jsonl
+    let a = dir.path().join("a.jsonl").display().to_string();
+    Cursor { files: [(a.clone(), 2)].into() }.save(&cursor).unwrap();
+    let summary = Backfill::new(&source).cursor(&cursor).concurrency(4).run("events", &chain).await.unwrap();
+    assert_eq!((summary.replayed, summary.resumed, summary.invalid), (3, 2, 1));
+    assert!(summary.failed.is_empty());
+    assert_eq!(seen.load(Ordering::SeqCst), 3);
+    let saved = Cursor::load(&cursor).unwrap();
+    assert_eq!(saved.files[&a], 5);
+
+    // From scratch: failed runs are reported without stopping the backfill
+    std::fs::remove_file(&cursor).unwrap();
+    let summary = Backfill::new(&source).cursor(&cursor).concurrency(4).run("events", &chain).await.unwrap();
+    assert_eq!(summary.failed.len(), 1);
+    assert_eq!((summary.failed[0].line, summary.failed[0].error.message.as_str()), (2, "bad event"));
+    // A finished backfill started again has nothing left to replay
+    let again = Backfill::new(&source).cursor(&cursor).run("events", &chain).await.unwrap();
+    assert_eq!((again.replayed, again.resumed), (0, 7));
+}
+
+#[tokio::test]
+async fn test_backfill_command_honours_rate() {
+    let dir = tempfile::tempdir().unwrap();
+    write_events(dir.path());
+    let seen = Arc::new(AtomicUsize::new(0));
+    registry::register_chain("backfill_rate", Arc::new(counting_chain(seen.clone())));
+
+    let source = format!("file://{}/b.jsonl", dir.path().display());
+    let cursor = dir.path().join("cursor.json").display().to_string();
+    let cli = Cli::parse_from(["modulink-cli", "backfill", "--chain", "backfill_rate", "--source", &source, "--rate", "20/s", "--concurrency", "8", "--cursor", &cursor]);
+    let started = Instant::now();
+    cli::run(cli, &Connectors::default()).await.unwrap();
+    // Two starts at 20/s are 50ms apart
+    assert!(started.elapsed() >= Duration::from_millis(50));
+    assert_eq!(seen.load(Ordering::SeqCst), 2);
+
+    let cli = Cli::parse_from(["modulink-cli", "backfill", "--chain", "missing", "--source", &source]);
+    assert!(cli::run(cli, &Connectors::default()).await.is_err());
+}
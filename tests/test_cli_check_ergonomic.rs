@@ -0,0 +1,93 @@
+//! Test `modulink-cli check` (ergonomic pattern)
+#![cfg(feature = "cli")]
+
+use clap::Parser;
+use modulink_rs::cli::check::{self, Severity};
+use modulink_rs::cli::{self, Cli, CliError};
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::pipe::Connectors;
+use modulink_rs::registry::{self, LinkMetadata};
+use std::path::Path;
+use std::sync::Arc;
+
+fn write(dir: &Path, name: &str, json: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, json).unwrap();
+}
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+fn register_links() {
+    registry::register_link_with("check_lookup", noop(), LinkMetadata::new().provides(["order"]));
+    registry::register_link_with("check_refund", noop(), LinkMetadata::new().requires(["order"]));
+    registry::register_link_with("check_old", noop(), LinkMetadata::new().deprecated("use check_lookup"));
+}
+
+#[tokio::test]
+async fn test_check_passes_valid_definitions() {
+    register_links();
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "pipelines/refunds.json", r#"{
+        "chains": [ { "name": "refunds", "links": [ { "link": "check_lookup" }, { "link": "check_refund" } ] } ],
+        "listeners": [ { "type": "stdin", "chain": "refunds" } ]
+    }"#);
+    write(dir.path(), "pipelines/notes.txt", "not a definition");
+
+    let summary = check::check(&[dir.path().join("pipelines")]).unwrap();
+    assert_eq!((summary.files, summary.chains), (1, 1));
+    assert!(summary.diagnostics.is_empty(), "{:?}", summary.diagnostics);
+
+    let path = dir.path().join("pipelines").display().to_string();
+    cli::run(Cli::parse_from(["modulink-cli", "check", &path]), &Connectors::default()).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_check_reports_problems() {
+    register_links();
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "broken.json", "{ not json");
+    write(dir.path(), "empty.json", "{}");
+    write(dir.path(), "chains.json", r#"{
+        "chains": [
+            { "name": "unknown", "links": [ { "link": "check_nobody" } ] },
+            { "name": "unordered", "links": [ { "link": "check_refund" }, { "link": "check_lookup" } ] },
+            { "name": "branchy", "links": [ { "link": "check_lookup" } ], "branches": [ { "from": 0, "to": 5 } ] },
+            { "name": "old", "links": [ { "link": "check_old" } ] }
+        ],
+        "listeners": [ { "type": "stdin", "chain": "check_missing" } ]
+    }"#);
+
+    let summary = check::check(&[dir.path().to_path_buf()]).unwrap();
+    assert_eq!(summary.files, 3);
+    let errors: Vec<_> = summary.diagnostics.iter().filter(|d| d.severity == Severity::Error).collect();
+    let chains: Vec<_> = errors.iter().map(|d| d.chain.as_deref()).collect();
+    // Files in name order: broken.json, chains.json, empty.json
+    assert_eq!(chains, [None, Some("unknown"), Some("unordered"), Some("branchy"), None]);
+    assert!(errors[0].file.ends_with("broken.json"));
+    assert!(errors[1].to_string().ends_with("chains.json: error: chain 'unknown': no link registered as 'check_nobody'"));
+    assert!(errors[4].message.contains("check_missing"));
+
+    let warnings: Vec<_> = summary.diagnostics.iter().filter(|d| d.severity == Severity::Warning).collect();
+    assert_eq!(warnings.len(), 2);
+    assert_eq!(warnings[0].chain.as_deref(), Some("old"));
+    assert!(warnings[1].file.ends_with("empty.json"));
+
+    let path = dir.path().display().to_string();
+    let result = cli::run(Cli::parse_from(["modulink-cli", "check", &path]), &Connectors::default()).await;
+    assert!(matches!(result, Err(CliError::CheckFailed(5))));
+}
+
+#[tokio::test]
+async fn test_deny_warnings() {
+    register_links();
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "old.json", r#"{ "chains": [ { "name": "old", "links": [ { "link": "check_old" } ] } ] }"#);
+    let path = dir.path().display().to_string();
+    cli::run(Cli::parse_from(["modulink-cli", "check", &path]), &Connectors::default()).await.unwrap();
+    let strict = cli::run(Cli::parse_from(["modulink-cli", "check", "--deny-warnings", &path]), &Connectors::default()).await;
+    assert!(matches!(strict, Err(CliError::CheckFailed(1))));
+}
@@ -0,0 +1,17 @@
+//! Test `modulink-cli pipe` without registered chains (ergonomic pattern)
+#![cfg(feature = "cli")]
+
+use clap::{CommandFactory, Parser};
+use modulink_rs::cli::{self, Cli, CliError};
+use modulink_rs::pipe::Connectors;
+
+#[tokio::test]
+async fn test_stock_binary_explains_that_pipe_needs_registered_chains() {
+    let cli = Cli::parse_from(["modulink-cli", "pipe", "--from", "stdin", "--chain", "enrich", "--to", "stdout"]);
+    let err = cli::run(cli, &Connectors::default()).await.unwrap_err();
+    assert!(matches!(err, CliError::NoChains("pipe")));
+    assert!(err.to_string().contains("modulink_rs::cli::main_with"));
+
+    let help = Cli::command().render_help().to_string();
+    assert!(help.contains("need a project binary"));
+}
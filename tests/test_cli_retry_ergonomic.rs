@@ -0,0 +1,55 @@
+//! Test `modulink-cli retry` (ergonomic pattern)
+#![cfg(feature = "cli")]
+
+use clap::Parser;
+use modulink_rs::chains::{Chain, MemoryCheckpointStore, RunError, RunStatus};
+use modulink_rs::cli::{self, Cli, CliError};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::LinkSpec;
+use modulink_rs::pipe::Connectors;
+use modulink_rs::registry;
+use std::sync::Arc;
+
+#[tokio::test]
+async fn test_retry_resumes_failed_run_from_step() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let mut chain = Chain::new();
+    chain.enable_checkpoints(store.clone());
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move {
+            let attempts = ctx.get::<i64>("attempts").unwrap_or(0);
+            ctx.insert("attempts", attempts + 1)
+        })),
+        LinkSpec::new().name("count"),
+    );
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move {
+            if ctx.get::<bool>("approved") != Some(true) {
+                ctx_tools::fail_run(RunError::invalid_input("not approved"));
+            }
+            ctx
+        })),
+        LinkSpec::new().name("check"),
+    );
+    let chain = Arc::new(chain);
+    registry::register_chain("cli_retry_orders", chain.clone());
+    let (_, report) = chain.run_durable("cli-run-1", Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+
+    let unknown = cli::retry("cli-run-1", "check", Some("missing_chain"), None).await.unwrap_err();
+    assert!(matches!(unknown, CliError::UnknownChain(_)));
+    let again = Cli::try_parse_from(["modulink-cli", "retry", "--run", "cli-run-1", "--from-step", "check"]).unwrap();
+    assert!(matches!(cli::run(again, &Connectors::default()).await, Err(CliError::RunFailed(_))));
+
+    let dir = tempfile::tempdir().unwrap();
+    let patch = dir.path().join("patch.json");
+    std::fs::write(&patch, r#"{ "approved": true }"#).unwrap();
+    let (ctx, report) = cli::retry("cli-run-1", "count", None, Some(&patch)).await.unwrap();
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<i64>("attempts"), Some(2));
+    assert!(store.is_empty());
+
+    let gone = cli::retry("cli-run-1", "count", Some("cli_retry_orders"), None).await.unwrap_err();
+    assert!(matches!(gone, CliError::Io(e) if e.kind() == std::io::ErrorKind::NotFound));
+}
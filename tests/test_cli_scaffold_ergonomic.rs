@@ -0,0 +1,33 @@
+//! Test `modulink-cli new` scaffolding (ergonomic pattern)
+#![cfg(feature = "cli")]
+
+use modulink_rs::cli::scaffold::{generate, ScaffoldKind};
+use std::fs;
+
+#[test]
+fn test_new_link_chain_listener() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    fs::write(root.join("Cargo.toml"), "[package]\nname = \"my-app\"\n").unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/lib.rs"), "pub mod chains;\n").unwrap();
+
+    generate(ScaffoldKind::Link, "enrich_order", root).unwrap();
+    generate(ScaffoldKind::Chain, "orders", root).unwrap();
+    generate(ScaffoldKind::Listener, "sqs_source", root).unwrap();
+
+    let link = fs::read_to_string(root.join("src/links/enrich_order.rs")).unwrap();
+    assert!(link.contains("pub fn enrich_order() -> Link"));
+    assert!(link.contains("registry::register_link_with("));
+    let listener = fs::read_to_string(root.join("src/listeners/sqs_source.rs")).unwrap();
+    assert!(listener.contains("pub struct SqsSource"));
+    let test = fs::read_to_string(root.join("tests/test_orders.rs")).unwrap();
+    assert!(test.contains("my_app::chains::orders()"));
+    assert_eq!(fs::read_to_string(root.join("src/links/mod.rs")).unwrap(), "pub mod enrich_order;\n");
+    assert_eq!(fs::read_to_string(root.join("src/lib.rs")).unwrap(), "pub mod chains;\npub mod links;\npub mod listeners;\n");
+
+    let again = generate(ScaffoldKind::Link, "enrich_order", root).unwrap_err();
+    assert_eq!(again.kind(), std::io::ErrorKind::AlreadyExists);
+    let bad = generate(ScaffoldKind::Link, "EnrichOrder", root).unwrap_err();
+    assert_eq!(bad.kind(), std::io::ErrorKind::InvalidInput);
+}
@@ -0,0 +1,195 @@
+//! Test link combinators (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{race, Link};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn provider(name: &'static str, delay_ms: u64, finished: Arc<AtomicBool>) -> Link {
+    Arc::new(move |ctx: Context| {
+        let finished = finished.clone();
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+            finished.store(true, Ordering::SeqCst);
+            ctx.insert("provider", name)
+        })
+    })
+}
+
+fn failing_provider() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::internal("provider down"));
+        ctx
+    }))
+}
+
+#[tokio::test]
+async fn test_race_takes_first_success_and_cancels_rest() {
+    let slow_finished = Arc::new(AtomicBool::new(false));
+    let mut chain = Chain::new();
+    chain.add_link(race(vec![
+        failing_provider(),
+        provider("slow", 500, slow_finished.clone()),
+        provider("fast", 10, Arc::new(AtomicBool::new(false))),
+    ]));
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<String>("provider").as_deref(), Some("fast"));
+    tokio::time::sleep(Duration::from_millis(600)).await;
+    assert!(!slow_finished.load(Ordering::SeqCst));
+}
+
+#[tokio::test]
+async fn test_race_fails_when_every_alternative_fails() {
+    let mut chain = Chain::new();
+    chain.add_link(race(vec![failing_provider(), failing_provider()]));
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    match report.status {
+        RunStatus::Failed(err) => assert_eq!(err.kind, ErrorKind::Internal),
+        other => panic!("expected failure, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_hedge_starts_second_attempt_for_slow_calls() {
+    use modulink_rs::links::combinators::Hedge;
+    use std::sync::atomic::AtomicUsize;
+
+    // First call is slow, every later call is fast
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let lookup: Link = Arc::new(move |ctx: Context| {
+        let attempt = counter.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move {
+            let delay = if attempt == 0 { 500 } else { 5 };
+            tokio::time::sleep(Duration::from_millis(delay)).await;
+            ctx.insert("attempt", attempt)
+        })
+    });
+    let hedge = Hedge::new(Duration::from_millis(50));
+    let mut chain = Chain::new();
+    chain.add_link(hedge.wrap(lookup));
+
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<usize>("attempt"), Some(1));
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<usize>("attempt"), Some(2));
+
+    let stats = hedge.stats();
+    assert_eq!((stats.calls(), stats.hedged(), stats.hedge_wins()), (2, 1, 1));
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_link_options_fallback_on_timeout_and_failure() {
+    use modulink_rs::links::LinkOptions;
+
+    let slow = provider("slow", 500, Arc::new(AtomicBool::new(false)));
+    let cached: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("provider", "cache") }));
+    let mut chain = Chain::new();
+    chain.add_link(LinkOptions::new().timeout(Duration::from_millis(20)).fallback(cached).wrap(slow));
+    chain.add_link(LinkOptions::new().fallback_values([("segment", "unknown")]).wrap(failing_provider()));
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<String>("provider").as_deref(), Some("cache"));
+    assert_eq!(ctx.get::<String>("segment").as_deref(), Some("unknown"));
+    assert_eq!(report.warnings.len(), 2);
+
+    // Without a fallback, a timeout fails the run
+    let mut chain = Chain::new();
+    chain.add_link(LinkOptions::new().timeout(Duration::from_millis(20)).wrap(provider("slow", 500, Arc::new(AtomicBool::new(false)))));
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(err) if err.kind == ErrorKind::LimitExceeded));
+}
+
+#[tokio::test]
+async fn test_batch_by_size_and_window() {
+    use modulink_rs::links::batch::{Batch, BatchLink};
+    use std::sync::Mutex;
+
+    let sizes = Arc::new(Mutex::new(Vec::new()));
+    let recorded = sizes.clone();
+    let bulk: BatchLink<Context> = Arc::new(move |batch: Vec<Context>| {
+        recorded.lock().unwrap().push(batch.len());
+        let size = batch.len();
+        Box::pin(async move { batch.into_iter().map(|ctx| ctx.insert("batch_size", size)).collect() })
+    });
+    let mut chain = Chain::new();
+    chain.add_link(Batch::new(3, Duration::from_millis(50)).wrap(bulk));
+    let chain = Arc::new(chain);
+
+    let runs: Vec<_> = (0..4)
+        .map(|i| {
+            let chain = chain.clone();
+            tokio::spawn(async move { chain.run(Context::new().insert("i", i)).await })
+        })
+        .collect();
+    let mut results = Vec::new();
+    for run in runs {
+        let ctx = run.await.unwrap();
+        results.push((ctx.get::<i32>("i").unwrap(), ctx.get::<usize>("batch_size").unwrap()));
+    }
+    // Three runs fill a batch; the fourth is flushed when its window closes
+    let mut batch_sizes = sizes.lock().unwrap().clone();
+    batch_sizes.sort();
+    assert_eq!(batch_sizes, vec![1, 3]);
+    assert_eq!(results.iter().filter(|(_, size)| *size == 3).count(), 3);
+    let mut ids: Vec<i32> = results.iter().map(|(i, _)| *i).collect();
+    ids.sort();
+    assert_eq!(ids, vec![0, 1, 2, 3]);
+}
+
+#[tokio::test]
+async fn test_batch_failure_fails_every_run() {
+    use modulink_rs::links::batch::{Batch, BatchLink};
+
+    let broken: BatchLink<Context> = Arc::new(|_batch: Vec<Context>| Box::pin(async move { Vec::new() }));
+    let mut chain = Chain::new();
+    chain.add_link(Batch::new(1, Duration::from_millis(10)).wrap(broken));
+    let (ctx, report) = chain.run_with_report(Context::new().insert("i", 1)).await;
+    assert_eq!(ctx.get::<i32>("i"), Some(1));
+    assert!(matches!(report.status, RunStatus::Failed(err) if err.kind == ErrorKind::Internal));
+}
+
+#[tokio::test]
+async fn test_wrapped_links_run_in_the_scope_of_the_run() {
+    use modulink_rs::chains::{CancellationToken, SharedState};
+    use modulink_rs::links::{combinators::Hedge, LinkOptions};
+
+    struct Rates(f64);
+    // Cancels the run it belongs to, then reports what it sees of the run
+    let quote = |token: CancellationToken| -> Link {
+        Arc::new(move |ctx: Context| {
+            let token = token.clone();
+            Box::pin(async move {
+                token.cancel();
+                let rate = ctx_tools::state::<Rates>().map(|rates| rates.0);
+                ctx_tools::warn("quoted from cache");
+                ctx_tools::annotate("provider", "cache");
+                ctx.insert("rate", rate).insert("cancelled", ctx_tools::is_cancelled())
+            })
+        })
+    };
+    let state = SharedState::new();
+    state.insert(Rates(1.25));
+
+    let wrappers: Vec<fn(Link) -> Link> = vec![
+        |link| race(vec![link]),
+        |link| Hedge::new(Duration::from_secs(5)).wrap(link),
+        |link| LinkOptions::new().timeout(Duration::from_secs(5)).wrap(link),
+    ];
+    for wrap in wrappers {
+        let token = CancellationToken::new();
+        let mut chain = Chain::new();
+        chain.set_state(state.clone());
+        chain.add_link(wrap(quote(token.clone())));
+        let (ctx, report) = chain.run_with_cancel(Context::new(), &token).await;
+        assert_eq!(ctx.get::<f64>("rate"), Some(1.25));
+        assert_eq!(ctx.get::<bool>("cancelled"), Some(true));
+        assert_eq!(report.warnings, vec!["quoted from cache"]);
+        assert_eq!(report.annotations.get("provider"), Some(&serde_json::json!("cache")));
+    }
+}
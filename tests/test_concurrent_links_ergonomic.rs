@@ -0,0 +1,110 @@
+//! Test concurrent execution of commutative links (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::links::combinators::merge_changes;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// A link that only finishes once `of` links sharing `entered` have started.
+fn rendezvous(entered: &Arc<AtomicUsize>, of: usize, key: &'static str) -> Link {
+    let entered = entered.clone();
+    Arc::new(move |ctx: Context| {
+        let entered = entered.clone();
+        Box::pin(async move {
+            entered.fetch_add(1, Ordering::SeqCst);
+            while entered.load(Ordering::SeqCst) < of {
+                tokio::task::yield_now().await;
+            }
+            ctx.insert(key, true)
+        })
+    })
+}
+
+fn set(key: &'static str, value: i32) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, value) }))
+}
+
+fn commutative() -> LinkSpec {
+    LinkSpec::new().commutative()
+}
+
+#[tokio::test]
+async fn test_consecutive_commutative_links_run_concurrently() {
+    let entered = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.enable_concurrent_links(merge_changes);
+    chain.add_link(set("input", 1));
+    for key in ["profile", "orders", "risk"] {
+        chain.add_link_with(rendezvous(&entered, 3, key), commutative().name(key));
+    }
+    chain.add_link(set("after", 1));
+
+    let (ctx, report) = tokio::time::timeout(Duration::from_secs(5), chain.run_with_report(Context::new())).await.unwrap();
+    assert_eq!(report.status, RunStatus::Completed);
+    for key in ["profile", "orders", "risk"] {
+        assert_eq!(ctx.get::<bool>(key), Some(true), "{}", key);
+    }
+    assert_eq!(ctx.get::<i32>("after"), Some(1));
+    let steps: Vec<usize> = report.steps.iter().map(|step| step.link).collect();
+    assert_eq!(steps, [0, 1, 2, 3, 4]);
+}
+
+#[tokio::test]
+async fn test_tagged_links_run_in_order_without_a_merge() {
+    let entered = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.add_link_with(rendezvous(&entered, 2, "a"), commutative());
+    chain.add_link_with(rendezvous(&entered, 2, "b"), commutative());
+    assert!(tokio::time::timeout(Duration::from_millis(100), chain.run(Context::new())).await.is_err());
+}
+
+#[tokio::test]
+async fn test_group_ends_at_a_branch_source() {
+    let mut chain = Chain::new();
+    chain.enable_concurrent_links(merge_changes);
+    chain.add_link_with(set("a", 1), commutative());
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move {
+            let seen = ctx.get::<i32>("a").is_some();
+            ctx.insert("saw_a", seen)
+        })),
+        commutative(),
+    );
+    chain.add_link_with(set("skipped", 1), commutative());
+    chain.add_link(set("end", 1));
+    chain.connect(0, 3, |_: &Context| true);
+
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<i32>("a"), Some(1));
+    assert_eq!(ctx.get::<bool>("saw_a"), None);
+    assert_eq!(ctx.get::<i32>("skipped"), None);
+    assert_eq!(ctx.get::<i32>("end"), Some(1));
+}
+
+#[tokio::test]
+async fn test_group_members_see_the_shared_input() {
+    let mut chain = Chain::new();
+    chain.enable_concurrent_links(merge_changes);
+    chain.add_link_with(set("a", 1), commutative());
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move {
+            let seen = ctx.get::<i32>("a").is_some();
+            ctx.insert("saw_a", seen)
+        })),
+        commutative(),
+    );
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<i32>("a"), Some(1));
+    assert_eq!(ctx.get::<bool>("saw_a"), Some(false));
+}
+
+#[test]
+fn test_commutative_flag_serializes_only_when_set() {
+    let spec = serde_json::to_value(LinkSpec::new()).unwrap();
+    assert!(spec.get("commutative").is_none());
+    let spec = serde_json::to_value(LinkSpec::new().commutative()).unwrap();
+    assert_eq!(spec["commutative"], true);
+}
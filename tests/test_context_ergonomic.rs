@@ -1,5 +1,7 @@
 //! Test context mutation and retrieval (ergonomic pattern)
 
+#![allow(clippy::type_complexity, clippy::let_and_return)]
+
 use modulink_rs::context::Context;
 use std::sync::Arc;
 
@@ -17,3 +19,30 @@ async fn test_context_inserts_and_gets() {
     let ctx = link(ctx).await;
     assert_eq!(ctx.get::<i32>("foo"), Some(42));
 }
+
+#[test]
+fn test_context_query() {
+    use modulink_rs::definitions::Expression;
+    use serde_json::json;
+
+    let ctx = Context::new()
+        .insert("items", json!([
+            { "id": "a", "price": 5, "tags": ["sale"] },
+            { "id": "b", "price": 25, "tags": [] },
+            { "id": "c", "price": 40, "tags": ["new"] },
+        ]))
+        .insert("order", json!({ "id": "o-1", "customer": { "id": "u-9" } }));
+    assert_eq!(ctx.query("$.items[?(@.price > 10)].id").unwrap(), vec![json!("b"), json!("c")]);
+    assert_eq!(ctx.query("$.items[-1]['id']").unwrap(), vec![json!("c")]);
+    assert_eq!(ctx.query("$.items[*].tags[0]").unwrap(), vec![json!("sale"), json!("new")]);
+    assert_eq!(ctx.query("$.order..id").unwrap(), vec![json!("o-1"), json!("u-9")]);
+    assert_eq!(ctx.query("$.items[?(@.price > 10 && @.id != 'c')].price").unwrap(), vec![json!(25)]);
+    assert!(ctx.query("$.missing").unwrap().is_empty());
+    assert!(ctx.query("items").is_err());
+    assert!(ctx.query("$.items[?(@.price >)]").is_err());
+
+    // Queries inside condition expressions
+    assert!(Expression::parse("$.items[?(@.price > 30)]").unwrap().eval(&ctx));
+    assert!(!Expression::parse("$.items[?(@.price > 100)]").unwrap().eval(&ctx));
+    assert!(Expression::parse("$.order.customer.id == 'u-9' && ctx.order.id == 'o-1'").unwrap().eval(&ctx));
+}
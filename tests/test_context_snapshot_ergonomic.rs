@@ -0,0 +1,71 @@
+//! Test rolling a context back to a checkpoint inside a link (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, RunStatus};
+use modulink_rs::context::{Context, ContextMutable};
+use modulink_rs::links::Link;
+use std::sync::Arc;
+
+// Applies each coupon in turn; an unknown coupon undoes all of them.
+fn apply_coupons() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let checkpoint = ctx.checkpoint();
+        let mut ctx = ctx;
+        for coupon in ctx.get::<Vec<String>>("coupons").unwrap_or_default() {
+            let total = ctx.get::<i64>("total").unwrap_or(0);
+            ctx = match coupon.as_str() {
+                "TEN" => ctx.insert("total", total - 10).record_decision("coupon TEN"),
+                _ => return ctx.rollback_to(&checkpoint).insert("coupon_error", coupon),
+            };
+        }
+        ctx
+    }))
+}
+
+fn chain() -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(apply_coupons());
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("charged", true) })));
+    chain
+}
+
+#[tokio::test]
+async fn test_rollback_discards_tentative_changes_and_the_run_goes_on() {
+    let input = Context::new().insert("total", 100).insert("coupons", ["TEN", "TEN", "BOGUS"]);
+    let (ctx, report) = chain().run_with_report(input).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<i64>("total"), Some(100));
+    assert_eq!(ctx.get::<Vec<String>>("_decisions"), None);
+    assert_eq!(ctx.get::<String>("coupon_error").as_deref(), Some("BOGUS"));
+    assert_eq!(ctx.get::<bool>("charged"), Some(true));
+
+    let ctx = chain().run(Context::new().insert("total", 100).insert("coupons", ["TEN"])).await;
+    assert_eq!(ctx.get::<i64>("total"), Some(90));
+    assert_eq!(ctx.get::<String>("coupon_error"), None);
+}
+
+#[test]
+fn test_checkpoint_can_be_reused() {
+    let ctx = Context::new().insert("a", 1);
+    let checkpoint = ctx.checkpoint();
+    let ctx = ctx.insert("a", 2).insert("b", 3);
+    assert_eq!(checkpoint.changed_keys(&ctx.0), ["a", "b"]);
+    let ctx = ctx.rollback_to(&checkpoint);
+    assert!(checkpoint.changed_keys(&ctx.0).is_empty());
+    let ctx = ctx.insert("c", 4).rollback_to(&checkpoint);
+    assert_eq!(ctx.get::<i64>("a"), Some(1));
+    assert_eq!(ctx.0.len(), 1);
+}
+
+#[test]
+fn test_mutable_context_rollback() {
+    let mut ctx = ContextMutable::new();
+    ctx.insert("a", 1);
+    let checkpoint = ctx.checkpoint();
+    ctx.insert("a", 2);
+    ctx.0.remove("a");
+    ctx.insert("b", true);
+    assert_eq!(checkpoint.changed_keys(&ctx.0), ["a", "b"]);
+    ctx.rollback_to(&checkpoint);
+    assert_eq!(ctx.get::<i64>("a"), Some(1));
+    assert_eq!(ctx.get::<bool>("b"), None);
+}
@@ -0,0 +1,75 @@
+//! Test hashing, signing, and id links (ergonomic pattern)
+#![cfg(feature = "crypto")]
+
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::std_links::{hmac_sha256, sha256, sign_ed25519, ulid, uuid_v4, verify_ed25519};
+use serde_json::json;
+
+// RFC 8032, test 1.
+const SECRET: [u8; 32] = [
+    0x9d, 0x61, 0xb1, 0x9d, 0xef, 0xfd, 0x5a, 0x60, 0xba, 0x84, 0x4a, 0xf4, 0x92, 0xec, 0x2c, 0xc4, 0x44, 0x49, 0xc5, 0x69, 0x7b, 0x32, 0x69, 0x19,
+    0x70, 0x3b, 0xac, 0x03, 0x1c, 0xae, 0x7f, 0x60,
+];
+const PUBLIC: [u8; 32] = [
+    0xd7, 0x5a, 0x98, 0x01, 0x82, 0xb1, 0x0a, 0xb7, 0xd5, 0x4b, 0xfe, 0xd3, 0xc9, 0x64, 0x07, 0x3a, 0x0e, 0xe1, 0x72, 0xf3, 0xda, 0xa6, 0x23, 0x25,
+    0xaf, 0x02, 0x1a, 0x68, 0xf7, 0x07, 0x51, 0x1a,
+];
+
+#[tokio::test]
+async fn test_hashes() {
+    let mut chain = Chain::new();
+    chain.add_link(sha256("body", "digest"));
+    chain.add_link(hmac_sha256(b"key", "body", "mac"));
+    chain.add_link(sha256("order", "order_digest"));
+    let ctx = chain.run(Context::new().insert("body", "The quick brown fox jumps over the lazy dog").insert("order", json!({ "id": 1 }))).await;
+
+    assert_eq!(ctx.get::<String>("digest").unwrap(), "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592");
+    assert_eq!(ctx.get::<String>("mac").unwrap(), "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8");
+    // Non-text values are hashed as JSON.
+    let mut json_chain = Chain::new();
+    json_chain.add_link(sha256("text", "digest"));
+    let ctx_text = json_chain.run(Context::new().insert("text", r#"{"id":1}"#)).await;
+    assert_eq!(ctx.get::<String>("order_digest"), ctx_text.get::<String>("digest"));
+
+    let report = chain.run_with_report(Context::new()).await.1;
+    assert!(matches!(report.status, RunStatus::Failed(err) if err.kind == ErrorKind::InvalidInput));
+}
+
+#[tokio::test]
+async fn test_sign_and_verify() {
+    let mut chain = Chain::new();
+    chain.add_link(sign_ed25519(&SECRET, "message", "signature"));
+    chain.add_link(verify_ed25519(&PUBLIC, "message", "signature", "valid").unwrap());
+    let ctx = chain.run(Context::new().insert("message", "")).await;
+    assert_eq!(
+        ctx.get::<String>("signature").unwrap(),
+        "e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b"
+    );
+    assert_eq!(ctx.get::<bool>("valid"), Some(true));
+
+    let mut verify = Chain::new();
+    verify.add_link(verify_ed25519(&PUBLIC, "message", "signature", "valid").unwrap());
+    let tampered = ctx.clone().insert("message", "changed");
+    assert_eq!(verify.run(tampered).await.get::<bool>("valid"), Some(false));
+    let garbage = ctx.insert("signature", "not hex");
+    assert_eq!(verify.run(garbage).await.get::<bool>("valid"), Some(false));
+}
+
+#[tokio::test]
+async fn test_ids() {
+    let mut chain = Chain::new();
+    chain.add_link(uuid_v4("request_id"));
+    chain.add_link(ulid("event_id"));
+    let first = chain.run(Context::new()).await;
+    let second = chain.run(Context::new()).await;
+
+    let uuid = first.get::<String>("request_id").unwrap();
+    assert_eq!(uuid.len(), 36);
+    assert_eq!(&uuid[14..15], "4");
+    assert_ne!(first.get::<String>("request_id"), second.get::<String>("request_id"));
+    let (a, b) = (first.get::<String>("event_id").unwrap(), second.get::<String>("event_id").unwrap());
+    assert_eq!(a.len(), 26);
+    // The first 10 characters encode the creation time.
+    assert!(a[..10] <= b[..10], "{} then {}", a, b);
+}
@@ -0,0 +1,87 @@
+//! Test structured child runs via ctx_tools::spawn_child (generic pattern)
+
+use modulink_rs::chains::{ChainGeneric, RunError, RunStatus};
+use modulink_rs::context::ContextMutable;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::LinkGeneric;
+use modulink_rs::runtime::{self, ExecutorObj, MockExecutor};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+type MyContext = ContextMutable;
+
+fn spawn_child_link(child: Arc<ChainGeneric<MyContext>>) -> LinkGeneric<MyContext> {
+    Arc::new(move |mut ctx: MyContext| {
+        let child = child.clone();
+        Box::pin(async move {
+            let _handle = ctx_tools::spawn_child(child, ctx.clone());
+            ctx.insert("spawned", true);
+            ctx
+        })
+    })
+}
+
+fn slow_flag_link(exec: ExecutorObj, flag: Arc<AtomicBool>) -> LinkGeneric<MyContext> {
+    Arc::new(move |ctx: MyContext| {
+        let exec = exec.clone();
+        let flag = flag.clone();
+        Box::pin(async move {
+            exec.sleep(Duration::from_secs(10)).await;
+            flag.store(true, Ordering::SeqCst);
+            ctx
+        })
+    })
+}
+
+fn panic_link() -> LinkGeneric<MyContext> {
+    Arc::new(|_ctx: MyContext| Box::pin(async move { panic!("child failed") }))
+}
+
+fn chain_with(exec: &MockExecutor, links: Vec<LinkGeneric<MyContext>>) -> Arc<ChainGeneric<MyContext>> {
+    let mut chain = ChainGeneric::<MyContext>::new();
+    chain.set_executor(Arc::new(exec.clone()));
+    for link in links {
+        chain.add_link(link);
+    }
+    Arc::new(chain)
+}
+
+#[test]
+fn test_parent_waits_for_children_and_reports_status() {
+    let exec = MockExecutor::new();
+    let flag = Arc::new(AtomicBool::new(false));
+    let child = chain_with(&exec, vec![slow_flag_link(Arc::new(exec.clone()), flag.clone())]);
+    let failing = chain_with(&exec, vec![panic_link()]);
+    let parent = chain_with(&exec, vec![spawn_child_link(child), spawn_child_link(failing)]);
+
+    let handle = runtime::spawn(&exec, async move { parent.run_with_report(MyContext::new()).await });
+    exec.run_until_stalled();
+    assert_eq!(exec.pending_tasks(), 2, "parent should wait on the slow child");
+    exec.advance(Duration::from_secs(10));
+
+    let (ctx, report) = futures::executor::block_on(handle).unwrap();
+    assert!(flag.load(Ordering::SeqCst));
+    assert_eq!(ctx.get::<bool>("spawned"), Some(true));
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(report.children.len(), 2);
+    assert_eq!(report.children[0].status, RunStatus::Completed);
+    assert_eq!(report.children[1].status, RunStatus::Failed(RunError::panicked("child failed")));
+}
+
+#[test]
+fn test_aborting_parent_cancels_children() {
+    let exec = MockExecutor::new();
+    let flag = Arc::new(AtomicBool::new(false));
+    let child = chain_with(&exec, vec![slow_flag_link(Arc::new(exec.clone()), flag.clone())]);
+    let parent = chain_with(&exec, vec![spawn_child_link(child)]);
+
+    let handle = runtime::spawn(&exec, async move { parent.run(MyContext::new()).await });
+    exec.run_until_stalled();
+    handle.abort();
+    exec.run_until_stalled();
+    exec.advance(Duration::from_secs(10));
+
+    assert!(!flag.load(Ordering::SeqCst));
+    assert_eq!(exec.pending_tasks(), 0);
+}
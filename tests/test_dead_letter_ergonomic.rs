@@ -0,0 +1,141 @@
+//! Test dead-letter sinks for failed runs (ergonomic pattern)
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use modulink_rs::chains::{Chain, ErrorKind, MemoryCheckpointStore, RunError};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::sinks::{ChannelDeadLetters, ChannelSink, DeadLetterSink, FailedRun, Failure, FileDeadLetters};
+use std::sync::{Arc, Mutex};
+
+fn reject_odd() -> Link {
+    Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            if ctx.get::<u64>("n").unwrap_or(0) % 2 == 1 {
+                ctx_tools::fail_run(RunError::invalid_input("odd input"));
+                return ctx;
+            }
+            ctx.insert("ok", true)
+        })
+    })
+}
+
+fn batch_chain() -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link_with(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("seen", true) })), LinkSpec::new().name("load"));
+    chain.add_link_with(reject_odd(), LinkSpec::new().name("validate"));
+    chain
+}
+
+#[tokio::test]
+async fn test_failed_runs_go_to_file_and_read_back() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("failed.jsonl");
+    let mut chain = batch_chain();
+    chain.dead_letter_to(Arc::new(FileDeadLetters::new(&path)));
+
+    let batch = (0..6u64).map(|n| Context::new().insert("n", n)).collect();
+    let results = chain.run_all(batch, 3).await;
+    assert_eq!(results.iter().filter(|r| r.is_err()).count(), 3);
+
+    let mut failed: Vec<FailedRun<Context>> = FileDeadLetters::read(&path).unwrap();
+    failed.sort_by_key(|f| f.ctx.get::<u64>("n"));
+    assert_eq!(failed.iter().map(|f| f.ctx.get::<u64>("n").unwrap()).collect::<Vec<_>>(), vec![1, 3, 5]);
+    let first = &failed[0];
+    assert_eq!(first.failure.link, Some(1));
+    assert_eq!(first.failure.link_name.as_deref(), Some("validate"));
+    assert_eq!(first.failure.error.kind, ErrorKind::InvalidInput);
+    assert_eq!(first.failure.run_id, None);
+    // The context as the run left it
+    assert_eq!(first.ctx.get::<bool>("seen"), Some(true));
+
+    // Reprocess after fixing the data
+    let mut fixed = batch_chain();
+    fixed.dead_letter_to(Arc::new(FileDeadLetters::new(dir.path().join("again.jsonl"))));
+    for failed in failed {
+        let n = failed.ctx.get::<u64>("n").unwrap();
+        assert!(fixed.try_run(failed.ctx.insert("n", n + 1)).await.is_ok());
+    }
+    assert!(FileDeadLetters::read::<Context>(dir.path().join("again.jsonl")).unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn test_channel_dead_letters_and_regular_sinks() {
+    let (tx, mut failures) = futures::channel::mpsc::channel(8);
+    let (results_tx, mut results) = futures::channel::mpsc::channel(8);
+    let mut chain = batch_chain();
+    chain.dead_letter_to(Arc::new(ChannelDeadLetters::new(tx)));
+    chain.pipe_to(Arc::new(ChannelSink::new(results_tx)));
+
+    chain.run(Context::new().insert("n", 2)).await;
+    chain.run(Context::new().insert("n", 7)).await;
+
+    let failed = failures.next().await.unwrap();
+    assert_eq!(failed.ctx.get::<u64>("n"), Some(7));
+    assert_eq!(failed.failure.error.message, "odd input");
+    assert!(failures.try_next().is_err());
+    // Both runs still reach the regular sink
+    assert_eq!(results.next().await.unwrap().get::<u64>("n"), Some(2));
+    assert_eq!(results.next().await.unwrap().get::<u64>("n"), Some(7));
+}
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<Failure>>);
+
+#[async_trait]
+impl DeadLetterSink for Recorder {
+    async fn dead_letter(&self, _ctx: &Context, failure: &Failure) -> std::io::Result<()> {
+        self.0.lock().unwrap().push(failure.clone());
+        Ok(())
+    }
+    fn name(&self) -> &'static str {
+        "recorder"
+    }
+}
+
+#[tokio::test]
+async fn test_durable_runs_carry_run_id() {
+    let recorder = Arc::new(Recorder::default());
+    let mut chain = batch_chain();
+    chain.enable_checkpoints(Arc::new(MemoryCheckpointStore::new()));
+    chain.dead_letter_to(recorder.clone());
+
+    chain.run_durable("batch-7", Context::new().insert("n", 1)).await;
+    chain.run_durable("batch-8", Context::new().insert("n", 2)).await;
+    let failures = recorder.0.lock().unwrap();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0].run_id.as_deref(), Some("batch-7"));
+}
+
+#[tokio::test]
+async fn test_file_dead_letters_io_runs_on_the_executor() {
+    use modulink_rs::runtime::MockExecutor;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("failed.jsonl");
+    let exec = MockExecutor::new();
+    let sink = Arc::new(FileDeadLetters::new(&path).with_executor(Arc::new(exec.clone())));
+    let failure = Failure { link: Some(0), link_name: None, error: RunError::internal("boom"), run_id: None };
+    let writing = tokio::spawn({
+        let sink = sink.clone();
+        async move { sink.dead_letter(&Context::new().insert("n", 1), &failure).await }
+    });
+
+    // The append waits for the executor's blocking pool
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!path.exists());
+    exec.run_until_stalled();
+    writing.await.unwrap().unwrap();
+
+    let loading = tokio::spawn({
+        let sink = sink.clone();
+        async move { sink.load::<Context>().await }
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!loading.is_finished());
+    exec.run_until_stalled();
+    let failed = loading.await.unwrap().unwrap();
+    assert_eq!(failed[0].ctx.get::<u64>("n"), Some(1));
+    assert_eq!(failed[0].failure.error.message, "boom");
+}
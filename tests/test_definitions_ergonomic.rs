@@ -0,0 +1,165 @@
+//! Test chain definitions and the sandbox profile (ergonomic pattern)
+
+use modulink_rs::chains::{ErrorKind, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::definitions::{ChainDefinition, DefinitionError, SandboxProfile, WasmHost, WasmModule};
+use modulink_rs::links::Link;
+use modulink_rs::registry;
+use std::sync::Arc;
+
+fn register_links() {
+    registry::register_link("defs_count", Arc::new(|ctx: Context| Box::pin(async move {
+        let n = ctx.get::<u32>("n").unwrap_or(0) + 1;
+        ctx.insert("n", n).insert("again", n < 3)
+    })));
+    registry::register_link("defs_wipe", Arc::new(|_ctx: Context| Box::pin(async move { Context::new() })));
+}
+
+struct EchoWasm;
+
+impl WasmHost for EchoWasm {
+    fn instantiate(&self, module: &WasmModule) -> Result<Link, String> {
+        let function = module.function.clone();
+        Ok(Arc::new(move |ctx: Context| {
+            let function = function.clone();
+            Box::pin(async move { ctx.insert("wasm", function) })
+        }))
+    }
+}
+
+const LOOPING: &str = r#"{
+    "name": "counter",
+    "links": [ { "link": "defs_count" }, { "wasm": { "module": "tag.wasm", "function": "tag" } } ],
+    "branches": [ { "from": 1, "to": 0, "when": { "key": "again", "equals": true } } ]
+}"#;
+
+#[tokio::test]
+async fn test_definition_builds_and_runs() {
+    register_links();
+    let def = ChainDefinition::from_json(LOOPING).unwrap();
+    let chain = def.build(Some(&EchoWasm)).unwrap();
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<u32>("n"), Some(3));
+    assert_eq!(ctx.get::<String>("wasm"), Some("tag".to_string()));
+
+    assert!(matches!(def.build(None), Err(DefinitionError::Wasm(_))));
+    let unknown = ChainDefinition::from_json(r#"{ "name": "x", "links": [ { "link": "defs_missing" } ] }"#).unwrap();
+    assert_eq!(unknown.build(None).err(), Some(DefinitionError::UnknownLink("defs_missing".to_string())));
+}
+
+#[tokio::test]
+async fn test_sandbox_profile_restricts_definitions() {
+    register_links();
+    let def = ChainDefinition::from_json(LOOPING).unwrap();
+    let profile = SandboxProfile::new().allow_link("defs_count");
+    // WASM is opt-in
+    assert!(matches!(profile.check(&def), Err(DefinitionError::NotAllowed(_))));
+    let profile = profile.allow_wasm();
+    assert!(def.build_sandboxed(&profile, Some(&EchoWasm)).is_ok());
+
+    let wipe = ChainDefinition::from_json(r#"{ "name": "x", "links": [ { "link": "defs_wipe" } ] }"#).unwrap();
+    assert!(matches!(wipe.build_sandboxed(&profile, None), Err(DefinitionError::NotAllowed(_))));
+    assert!(matches!(profile.clone().deny_wasm().check(&def), Err(DefinitionError::NotAllowed(_))));
+    let no_loops = SandboxProfile { max_loops: 0, ..profile.clone() };
+    assert!(matches!(no_loops.check(&def), Err(DefinitionError::NotAllowed(_))));
+    let long = ChainDefinition::from_json(&serde_json::json!({
+        "name": "long",
+        "links": [ { "link": "defs_count" } ],
+        "branches": [ { "from": 0, "to": 0, "when": "ctx.n < 2 && ".repeat(100) + "true" } ]
+    }).to_string()).unwrap();
+    assert!(matches!(profile.check(&long), Err(DefinitionError::NotAllowed(_))));
+
+    // An unconditional loop is stopped by the step limit
+    let forever = ChainDefinition::from_json(r#"{
+        "name": "forever",
+        "links": [ { "link": "defs_count" } ],
+        "branches": [ { "from": 0, "to": 0 } ]
+    }"#).unwrap();
+    let chain = forever.build_sandboxed(&profile, None).unwrap();
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref e) if e.kind == ErrorKind::LimitExceeded));
+}
+
+// `levels` inline chains nested in each other around one `defs_count` link.
+fn nested(levels: usize, branches: serde_json::Value) -> ChainDefinition {
+    let mut def = serde_json::json!({ "name": "inner", "links": [ { "link": "defs_count" } ], "branches": branches });
+    for level in 0..levels {
+        def = serde_json::json!({ "name": format!("level{}", level), "links": [ { "chain": def } ] });
+    }
+    ChainDefinition::from_json(&def.to_string()).unwrap()
+}
+
+#[tokio::test]
+async fn test_sandbox_profile_covers_inline_chains() {
+    register_links();
+    let profile = SandboxProfile::new().allow_link("defs_count");
+
+    // Inline chains run under the profile's limits too
+    let forever = nested(1, serde_json::json!([ { "from": 0, "to": 0 } ]));
+    let chain = forever.build_sandboxed(&profile, None).unwrap();
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref e) if e.kind == ErrorKind::LimitExceeded));
+
+    // Nesting is capped
+    assert!(profile.check(&nested(profile.max_depth, serde_json::json!([]))).is_ok());
+    assert!(matches!(profile.check(&nested(profile.max_depth + 1, serde_json::json!([]))), Err(DefinitionError::NotAllowed(_))));
+
+    // Links and loops are counted across the whole tree
+    let small = SandboxProfile { max_links: 3, max_loops: 0, ..profile.clone() };
+    assert!(small.check(&nested(2, serde_json::json!([]))).is_ok());
+    assert!(matches!(small.check(&nested(3, serde_json::json!([]))), Err(DefinitionError::NotAllowed(_))));
+    assert!(matches!(small.check(&nested(1, serde_json::json!([ { "from": 0, "to": 0, "when": { "key": "again" } } ]))), Err(DefinitionError::NotAllowed(_))));
+}
+
+#[test]
+fn test_link_metadata_and_deprecation_warnings() {
+    use modulink_rs::registry::LinkMetadata;
+    let meta = LinkMetadata::new().version("2.1.0").description("Look up an order").deprecated("use defs_lookup_v3");
+    registry::register_link_with("defs_lookup_v2", Arc::new(|ctx: Context| Box::pin(async move { ctx })), meta.clone());
+    assert_eq!(registry::link_metadata("defs_lookup_v2"), Some(meta.clone()));
+    assert_eq!(meta.to_string(), "v2.1.0 - Look up an order - [deprecated: use defs_lookup_v3]");
+
+    let def = ChainDefinition::from_json(r#"{ "name": "orders", "links": [ { "link": "defs_lookup_v2" } ] }"#).unwrap();
+    assert_eq!(def.warnings(), vec!["chain 'orders' uses deprecated link 'defs_lookup_v2': use defs_lookup_v3"]);
+    assert!(def.build(None).is_ok());
+}
+
+#[tokio::test]
+async fn test_definition_branch_expressions() {
+    use modulink_rs::definitions::Expression;
+
+    register_links();
+    let def = ChainDefinition::from_json(r#"{
+        "name": "retry",
+        "links": [ { "link": "defs_count" }, { "link": "defs_count" } ],
+        "branches": [ { "from": 0, "to": 0, "when": "ctx.again == true && ctx.n < 2" } ]
+    }"#).unwrap();
+    let ctx = def.build(None).unwrap().run(Context::new()).await;
+    assert_eq!(ctx.get::<u32>("n"), Some(3));
+
+    let bad = ChainDefinition::from_json(r#"{
+        "name": "bad", "links": [ { "link": "defs_count" } ],
+        "branches": [ { "from": 0, "to": 0, "when": "ctx.n <" } ]
+    }"#).unwrap();
+    assert!(matches!(bad.build(None), Err(DefinitionError::Parse(_))));
+
+    let expr = Expression::parse(r#"!(ctx.order.paid) || ctx.items[0].sku == 'gift-card'"#).unwrap();
+    let paid = Context::new().insert("order", serde_json::json!({ "paid": true }));
+    assert!(!expr.eval(&paid));
+    assert!(expr.eval(&paid.insert("items", serde_json::json!([{ "sku": "gift-card" }]))));
+    assert!(expr.eval(&Context::new()));
+    assert!(Expression::parse("status == 1").is_err());
+}
+
+#[test]
+fn test_deeply_nested_expressions_are_rejected() {
+    use modulink_rs::definitions::expr::MAX_DEPTH;
+    use modulink_rs::definitions::Expression;
+
+    assert!(Expression::parse(&"!".repeat(100_000)).is_err());
+    assert!(Expression::parse(&"(".repeat(100_000)).is_err());
+    assert!(Expression::parse(&vec!["true"; 100_000].join(" || ")).is_err());
+    assert!(Expression::parse(&format!("{}true{}", "$[?(".repeat(10_000), ")]".repeat(10_000))).is_err());
+    let nested = format!("{}true{}", "(".repeat(MAX_DEPTH - 1), ")".repeat(MAX_DEPTH - 1));
+    assert!(Expression::parse(&nested).unwrap().eval(&Context::new()));
+}
@@ -0,0 +1,47 @@
+//! Test the chain documentation generator (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ResourceLimits};
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::middleware::LoggingMiddleware;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+fn refunds() -> Chain {
+    let mut chain = Chain::new();
+    chain.set_name("refunds");
+    chain.declare_input(["order_id"]);
+    chain.add_link_with(noop(), LinkSpec::new().description("Look up the order").requires(["order_id"]).provides(["order"]));
+    chain.add_link_with(noop(), LinkSpec::new().description("Issue <refund>").requires(["order"]).provides(["refund_id"]));
+    chain.connect(1, 0, |ctx: &Context| ctx.get::<bool>("retry").unwrap_or(false));
+    chain.use_middleware(Arc::new(LoggingMiddleware));
+    chain.set_limits(ResourceLimits::new().with_max_wall_time(Duration::from_secs(5)));
+    chain
+}
+
+#[test]
+fn test_document_markdown() {
+    let md = refunds().document();
+    assert!(md.starts_with("# refunds\n"));
+    assert!(md.contains("```mermaid\nflowchart TD\n    L0[\"0: Look up the order\"]\n"));
+    assert!(md.contains("    L0 --> L1\n"));
+    assert!(md.contains("    L1 -. branch .-> L0\n"));
+    assert!(md.contains("## Input\n\n`order_id`\n"));
+    assert!(md.contains("| 1 | Issue <refund> | `order` | `refund_id` |\n"));
+    assert!(md.contains("## Middleware\n\n- LoggingMiddleware\n"));
+    assert!(md.contains("- Wall time limit: 5s\n"));
+}
+
+#[test]
+fn test_document_html_escapes() {
+    let html = refunds().document_html();
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<h1>refunds</h1>"));
+    assert!(html.contains("<td>Issue &lt;refund&gt;</td>"));
+    assert!(html.contains("<pre class=\"mermaid\">\nflowchart TD\n"));
+    assert!(html.contains("<li>LoggingMiddleware</li>"));
+}
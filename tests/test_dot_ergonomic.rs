@@ -0,0 +1,93 @@
+//! Test exporting chains as Graphviz DOT (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorRoute};
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::Arc;
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+#[test]
+fn test_to_dot_draws_links_and_edges() {
+    let mut chain = Chain::new();
+    chain.set_name("refunds");
+    chain.add_link_with(noop(), LinkSpec::new().name("score"));
+    chain.add_link_with(noop(), LinkSpec::new().description("Manual \"deep\" review"));
+    chain.add_link_with(noop(), LinkSpec::new().name("refund").side_effects());
+    chain.add_link(noop());
+    chain.connect_described(0, 2, "ctx.score >= 0.8", |ctx: &Context| ctx.get::<f64>("score").unwrap_or(0.0) >= 0.8);
+    chain.connect(1, 0, |ctx: &Context| ctx.get::<bool>("retry") == Some(true));
+    chain.jump(2, 3);
+    chain.route_errors(0, ErrorRoute::Jump(3));
+
+    let dot = chain.to_dot();
+    assert_eq!(dot, [
+        "digraph \"refunds\" {",
+        "    node [shape=box];",
+        "    L0 [label=\"0: score\"];",
+        "    L1 [label=\"1: Manual \\\"deep\\\" review\"];",
+        "    L2 [label=\"2: refund\", style=bold];",
+        "    L3 [label=\"Link 3\"];",
+        "    L0 -> L1;",
+        "    L1 -> L2;",
+        "    L2 -> L3;",
+        "    L0 -> L2 [style=dashed, label=\"ctx.score >= 0.8\"];",
+        "    L1 -> L0 [style=dashed, label=\"branch\"];",
+        "    L2 -> L3 [style=dashed, label=\"always\"];",
+        "    L0 -> L3 [style=dotted, color=red, label=\"on error\"];",
+        "}",
+        "",
+    ].join("\n"));
+}
+
+#[test]
+fn test_unnamed_chain() {
+    let mut chain = Chain::new();
+    chain.add_link(noop());
+    assert_eq!(chain.to_dot(), "digraph \"chain\" {\n    node [shape=box];\n    L0 [label=\"Link 0\"];\n}\n");
+}
+
+#[cfg(feature = "cli")]
+mod cli {
+    use clap::Parser;
+    use modulink_rs::chains::Chain;
+    use modulink_rs::cli::{self, Cli, CliError, GraphFormat};
+    use modulink_rs::pipe::Connectors;
+    use modulink_rs::registry;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_visualize_definition_file() {
+        registry::register_link("dot_step", super::noop());
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.json");
+        std::fs::write(&path, r#"{ "chains": [
+            { "name": "a", "links": [ { "link": "dot_step" }, { "link": "dot_step" } ],
+              "branches": [ { "from": 1, "to": 0, "when": "ctx.again" } ] },
+            { "name": "b", "links": [ { "wasm": { "module": "score.wasm", "function": "score" } } ] }
+        ] }"#).unwrap();
+
+        let all = cli::visualize(None, Some(&path), GraphFormat::Dot).unwrap();
+        assert!(all.starts_with("digraph \"a\" {"));
+        assert!(all.contains("    L1 -> L0 [style=dashed, label=\"ctx.again\"];\n"));
+        assert!(all.contains("digraph \"b\" {"));
+        let one = cli::visualize(Some("b"), Some(&path), GraphFormat::Dot).unwrap();
+        assert!(one.starts_with("digraph \"b\" {") && !one.contains("\"a\""));
+        assert!(matches!(cli::visualize(Some("c"), Some(&path), GraphFormat::Dot), Err(CliError::InvalidArgument(_))));
+    }
+
+    #[tokio::test]
+    async fn test_visualize_registered_chain() {
+        let mut chain = Chain::new();
+        chain.set_name("dot_registered");
+        chain.add_link(super::noop());
+        registry::register_chain("dot_registered", Arc::new(chain));
+        assert!(cli::visualize(Some("dot_registered"), None, GraphFormat::Dot).unwrap().starts_with("digraph \"dot_registered\""));
+        assert!(matches!(cli::visualize(Some("dot_nobody"), None, GraphFormat::Dot), Err(CliError::UnknownChain(_))));
+        assert!(matches!(cli::visualize(None, None, GraphFormat::Dot), Err(CliError::InvalidArgument(_))));
+        let cli = Cli::parse_from(["modulink-cli", "visualize", "dot_registered"]);
+        cli::run(cli, &Connectors::default()).await.unwrap();
+    }
+}
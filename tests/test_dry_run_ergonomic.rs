@@ -0,0 +1,54 @@
+//! Test walking a chain's routing without running its links (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn counted(calls: Arc<AtomicUsize>) -> Link {
+    Arc::new(move |ctx: Context| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        Box::pin(async move { ctx })
+    })
+}
+
+fn routing(calls: Arc<AtomicUsize>) -> Chain {
+    let mut chain = Chain::new();
+    for name in ["classify", "manual_review", "auto_approve", "notify"] {
+        chain.add_link_with(counted(calls.clone()), LinkSpec::new().name(name));
+    }
+    chain.connect(0, 2, |ctx: &Context| ctx.get::<f64>("score").unwrap_or(0.0) >= 0.8);
+    chain.jump(1, 3);
+    chain
+}
+
+#[test]
+fn test_dry_run_follows_branches_without_running_links() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let chain = routing(calls.clone());
+
+    let high = chain.dry_run(&Context::new().insert("score", 0.9));
+    let names: Vec<_> = high.iter().map(|step| step.name.as_deref().unwrap()).collect();
+    assert_eq!(names, vec!["classify", "auto_approve", "notify"]);
+    assert_eq!(high[0].branch, Some(2));
+
+    let low = chain.dry_run(&Context::new().insert("score", 0.1));
+    assert_eq!(low.iter().map(|step| step.link).collect::<Vec<_>>(), vec![0, 1, 3]);
+    assert_eq!(low[1].branch, Some(3));
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn test_dry_run_stops_at_loops() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(counted(calls.clone()));
+    chain.add_link(counted(calls.clone()));
+    chain.connect(1, 0, |ctx: &Context| ctx.get::<bool>("retry") == Some(true));
+
+    let looping = chain.dry_run(&Context::new().insert("retry", true));
+    assert_eq!(looping.iter().map(|step| (step.link, step.branch)).collect::<Vec<_>>(), vec![(0, None), (1, Some(0))]);
+    assert_eq!(chain.dry_run(&Context::new()).len(), 2);
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
@@ -1,6 +1,9 @@
 //! Test error propagation through links (ergonomic pattern)
 
+#![allow(clippy::type_complexity)]
+
 use modulink_rs::context::Context;
+use modulink_rs::links::Link;
 use modulink_rs::chains::Chain;
 use std::sync::Arc;
 
@@ -18,3 +21,36 @@ async fn test_chain_error_propagation() {
     let ctx = Context::new();
     let _ = chain.run(ctx).await;
 }
+
+#[tokio::test]
+async fn test_run_error_carries_link_path() {
+    use modulink_rs::chains::{PathStep, RunError, RunStatus};
+    use modulink_rs::ctx_tools;
+    use modulink_rs::links::LinkSpec;
+
+    let noop: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx }));
+    let fail: Link = Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::internal("refund rejected"));
+        ctx
+    }));
+    let mut chain = Chain::new();
+    chain.add_link_with(noop.clone(), LinkSpec::new().name("lookup"));
+    chain.add_link_with(noop, LinkSpec::new().name("route"));
+    chain.add_link_with(fail.clone(), LinkSpec::new().name("charge"));
+    chain.add_link_with(fail, LinkSpec::new().name("refund"));
+    chain.connect(1, 3, |ctx: &Context| ctx.get::<bool>("refund") == Some(true));
+
+    let (_, report) = chain.run_with_report(Context::new().insert("refund", true)).await;
+    let RunStatus::Failed(err) = report.status else { panic!("run should fail") };
+    assert_eq!(
+        err.path,
+        vec![
+            PathStep { link: 0, name: Some("lookup".into()), branch: None },
+            PathStep { link: 1, name: Some("route".into()), branch: Some(3) },
+            PathStep { link: 3, name: Some("refund".into()), branch: None },
+        ]
+    );
+    assert_eq!(err.to_string(), "Internal: refund rejected [path: 0:lookup -> 1:route (branch to 3) -> 3:refund]");
+    let json = serde_json::to_value(&err).unwrap();
+    assert_eq!(json["path"][1]["branch"], 3);
+}
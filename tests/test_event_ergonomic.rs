@@ -0,0 +1,138 @@
+//! Test waiting for external events (ergonomic pattern)
+
+use futures::StreamExt;
+use modulink_rs::chains::{Chain, CheckpointStore, MemoryCheckpointStore, RunStatus};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::links::Link;
+use modulink_rs::std_links::{await_event, Events};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn mark_shipped() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("shipped", true) }))
+}
+
+async fn wait_for_waiter(events: &Events, key: &str) {
+    for _ in 0..100 {
+        if events.waiting().iter().any(|k| k == key) {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no run waited for {}", key);
+}
+
+#[tokio::test]
+async fn test_in_memory_run_waits_for_matching_event() {
+    let events = Arc::new(Events::new());
+    let mut chain = Chain::new();
+    chain.add_link(await_event(events.clone(), "payment:{order_id}"));
+    chain.add_link(mark_shipped());
+    let chain = Arc::new(chain);
+    let run = tokio::spawn({
+        let chain = chain.clone();
+        async move { chain.run(Context::new().insert("order_id", "o-1").insert(meta::TENANT, "acme")).await }
+    });
+
+    wait_for_waiter(&events, "payment:o-1").await;
+    assert_eq!(events.deliver("payment:o-2", json!({ "paid": true })).await.unwrap(), 0);
+    assert!(!run.is_finished());
+    let payload = json!({ "paid": true, "amount": 30, "_tenant": "other", "_auth": { "sub": "admin" } });
+    assert_eq!(events.deliver("payment:o-1", payload).await.unwrap(), 1);
+    let ctx = run.await.unwrap();
+    // Reserved keys in the payload are dropped
+    assert_eq!(ctx.get::<String>(meta::TENANT).as_deref(), Some("acme"));
+    assert_eq!(ctx.get::<serde_json::Value>(meta::AUTH), None);
+    assert_eq!(ctx.get::<bool>("paid"), Some(true));
+    assert_eq!(ctx.get::<i64>("amount"), Some(30));
+    assert_eq!(ctx.get::<bool>("shipped"), Some(true));
+    assert!(events.waiting().is_empty());
+
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+}
+
+#[tokio::test]
+async fn test_durable_run_is_checkpointed_and_resumed_on_event() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let mut chain = Chain::new();
+    chain.enable_checkpoints(store.clone());
+    let events = Arc::new(Events::new());
+    chain.add_link(await_event(events.clone(), "payment:{order_id}"));
+    chain.add_link(mark_shipped());
+    let chain = Arc::new(chain);
+    events.set_chain(chain.clone());
+    let mut outcomes = chain.subscribe();
+
+    let (_, report) = chain.run_durable("order-9", Context::new().insert("order_id", 9)).await;
+    assert_eq!(report.status, RunStatus::AwaitingEvent { key: "payment:9".to_string() });
+    assert_eq!(store.load("order-9").await.unwrap().unwrap().awaiting_event.as_deref(), Some("payment:9"));
+    outcomes.next().await.unwrap();
+
+    // Events arriving as queue messages carry their key in a field
+    let deliver = events.clone().deliver_link("event_key");
+    let ctx = deliver(Context::new().insert("event_key", "payment:9").insert("paid", true)).await;
+    assert_eq!(ctx.get::<usize>("resumed"), Some(1));
+
+    let done = tokio::time::timeout(Duration::from_secs(2), outcomes.next()).await.unwrap().unwrap();
+    assert_eq!(done.report.status, RunStatus::Completed);
+    assert_eq!(done.ctx.get::<bool>("paid"), Some(true));
+    assert_eq!(done.ctx.get::<bool>("shipped"), Some(true));
+    assert!(store.is_empty());
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_http_callback_delivers_event() {
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+
+    let events = Arc::new(Events::new());
+    let mut chain = Chain::new();
+    chain.add_link(await_event(events.clone(), "callback:{job}"));
+    let chain = Arc::new(chain);
+    let listener = HttpListener::for_chain(chain.clone(), "127.0.0.1:8098").with_events(events.clone());
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let run = tokio::spawn(client.post("http://127.0.0.1:8098/run").json(&json!({ "job": "j1" })).send());
+    wait_for_waiter(&events, "callback:j1").await;
+    let missing = client.post("http://127.0.0.1:8098/events/callback:j2").json(&json!({})).send().await.unwrap();
+    assert_eq!(missing.status(), 404);
+    let delivered = client.post("http://127.0.0.1:8098/events/callback:j1").json(&json!("done")).send().await.unwrap();
+    assert_eq!(delivered.status(), 200);
+    let result: serde_json::Value = run.await.unwrap().unwrap().json().await.unwrap();
+    assert_eq!(result["event"], json!("done"));
+}
+
+#[cfg(all(feature = "http", feature = "jwt"))]
+#[tokio::test]
+async fn test_http_callback_requires_the_listener_jwt() {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use modulink_rs::auth::JwtValidator;
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+
+    let events = Arc::new(Events::new());
+    let mut chain = Chain::new();
+    chain.add_link(await_event(events.clone(), "callback:{job}"));
+    let listener = HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8107")
+        .with_events(events.clone())
+        .with_jwt(Arc::new(JwtValidator::hs256(b"callbacks")));
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let jwt = encode(&Header::default(), &json!({ "sub": "payments", "exp": 4_000_000_000u64 }), &EncodingKey::from_secret(b"callbacks")).unwrap();
+    let run = tokio::spawn(client.post("http://127.0.0.1:8107/run").bearer_auth(&jwt).json(&json!({ "job": "j1" })).send());
+    wait_for_waiter(&events, "callback:j1").await;
+
+    let url = "http://127.0.0.1:8107/events/callback:j1";
+    assert_eq!(client.post(url).json(&json!({ "_auth": { "sub": "admin" } })).send().await.unwrap().status(), 401);
+    assert_eq!(events.waiting(), vec!["callback:j1".to_string()]);
+    let delivered = client.post(url).bearer_auth(&jwt).json(&json!({ "ok": true, "_auth": { "sub": "admin" } })).send().await.unwrap();
+    assert_eq!(delivered.status(), 200);
+    let result: serde_json::Value = run.await.unwrap().unwrap().json().await.unwrap();
+    assert_eq!(result["ok"], json!(true));
+    assert_eq!(result["_auth"]["sub"], json!("payments"));
+}
@@ -0,0 +1,101 @@
+//! Test fallible links and error routing (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, ErrorRoute, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{FallibleLink, Link};
+use std::sync::Arc;
+
+fn check_qty() -> FallibleLink {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        match ctx.get::<u32>("qty") {
+            Some(qty) if qty > 0 => Ok(ctx.insert("checked", true)),
+            _ => Err(RunError::invalid_input("qty must be positive")),
+        }
+    }))
+}
+
+fn mark(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn chain() -> Chain {
+    let mut chain = Chain::new();
+    chain.add_fallible_link(check_qty());
+    chain.add_link(mark("charged"));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        let reason = ctx_tools::last_error().map(|err| err.message);
+        ctx.insert("handled", reason)
+    })));
+    chain
+}
+
+#[tokio::test]
+async fn test_ok_result_continues_the_run() {
+    let ctx = chain().try_run(Context::new().insert("qty", 2)).await.unwrap();
+    assert_eq!(ctx.get::<bool>("checked"), Some(true));
+    assert_eq!(ctx.get::<bool>("charged"), Some(true));
+    assert_eq!(ctx.get::<Option<String>>("handled"), Some(None));
+}
+
+#[tokio::test]
+async fn test_errors_abort_by_default() {
+    let chain = chain();
+    assert_eq!(chain.error_route(0), ErrorRoute::Abort);
+    let err = chain.try_run(Context::new()).await.unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+    assert_eq!(err.message, "qty must be positive");
+    assert_eq!(err.path.len(), 1);
+
+    let (ctx, report) = chain.run_with_report(Context::new().insert("qty", 0)).await;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+    assert_eq!(ctx.get::<u32>("qty"), Some(0));
+    assert_eq!(ctx.get::<bool>("charged"), None);
+}
+
+#[tokio::test]
+async fn test_skip_continues_with_the_input_context() {
+    let mut chain = chain();
+    chain.set_error_route(ErrorRoute::Skip);
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("checked"), None);
+    assert_eq!(ctx.get::<bool>("charged"), Some(true));
+    assert_eq!(ctx.get::<String>("handled").as_deref(), Some("qty must be positive"));
+    assert_eq!(report.warnings, ["link 0 failed, skipped: InvalidInput: qty must be positive"]);
+}
+
+#[tokio::test]
+async fn test_jump_continues_at_the_handler() {
+    let mut chain = chain();
+    chain.route_errors(0, ErrorRoute::Jump(2));
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("charged"), None);
+    assert_eq!(ctx.get::<String>("handled").as_deref(), Some("qty must be positive"));
+    assert_eq!(report.branches, [(0, 2)]);
+    assert_eq!(report.steps.iter().map(|s| s.link).collect::<Vec<_>>(), [0, 2]);
+}
+
+#[tokio::test]
+async fn test_link_route_overrides_chain_route() {
+    let mut chain = chain();
+    chain.set_error_route(ErrorRoute::Skip);
+    chain.route_errors(0, ErrorRoute::Abort);
+    assert!(chain.try_run(Context::new()).await.is_err());
+}
+
+#[tokio::test]
+async fn test_last_error_does_not_leak_into_later_runs() {
+    let mut chain = chain();
+    chain.set_error_route(ErrorRoute::Skip);
+    chain.run(Context::new()).await;
+    let ctx = chain.run(Context::new().insert("qty", 1)).await;
+    assert_eq!(chain.pool_stats().reused, 1);
+    assert_eq!(ctx.get::<Option<String>>("handled"), Some(None));
+}
+
+#[test]
+fn test_error_route_serializes_as_snake_case() {
+    assert_eq!(serde_json::to_value(ErrorRoute::Jump(3)).unwrap(), serde_json::json!({ "jump": 3 }));
+}
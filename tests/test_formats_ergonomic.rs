@@ -0,0 +1,113 @@
+//! Test XML/YAML conversion links (ergonomic pattern)
+#![cfg(any(feature = "xml", feature = "yaml"))]
+
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::Context;
+use serde_json::{json, Value};
+
+fn failed_kind(status: RunStatus) -> Option<ErrorKind> {
+    match status {
+        RunStatus::Failed(err) => Some(err.kind),
+        _ => None,
+    }
+}
+
+#[cfg(feature = "xml")]
+mod xml {
+    use super::*;
+    use modulink_rs::std_links::{parse_xml, to_xml};
+
+    const ORDER: &str = r#"<?xml version="1.0"?>
+        <order id="o-7" priority="high">
+            <customer>Ana &amp; Co</customer>
+            <item sku="A-1">2</item>
+            <item sku="B-2">1</item>
+            <note/>
+            <memo><![CDATA[<fragile>]]></memo>
+        </order>"#;
+
+    #[tokio::test]
+    async fn test_parse_xml() {
+        let mut chain = Chain::new();
+        chain.add_link(parse_xml("body", "order"));
+        let ctx = chain.run(Context::new().insert("body", ORDER)).await;
+        assert_eq!(
+            ctx.get::<Value>("order").unwrap(),
+            json!({ "order": {
+                "@id": "o-7",
+                "@priority": "high",
+                "customer": "Ana & Co",
+                "item": [{ "@sku": "A-1", "#text": "2" }, { "@sku": "B-2", "#text": "1" }],
+                "note": null,
+                "memo": "<fragile>",
+            }})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_to_xml_round_trips() {
+        let mut chain = Chain::new();
+        chain.add_link(to_xml("order", "body"));
+        chain.add_link(parse_xml("body", "parsed"));
+        let order = json!({ "order": { "@id": 7, "customer": "Ana & Co", "item": ["a", "b"], "paid": true, "note": null } });
+        let ctx = chain.run(Context::new().insert("order", order)).await;
+
+        let body = ctx.get::<String>("body").unwrap();
+        assert!(body.starts_with(r#"<order id="7">"#), "{}", body);
+        assert!(body.contains("<customer>Ana &amp; Co</customer>"), "{}", body);
+        assert!(body.contains("<item>a</item><item>b</item>"), "{}", body);
+        assert_eq!(
+            ctx.get::<Value>("parsed").unwrap(),
+            json!({ "order": { "@id": "7", "customer": "Ana & Co", "item": ["a", "b"], "paid": "true", "note": null } })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_xml_errors() {
+        let mut parse = Chain::new();
+        parse.add_link(parse_xml("body", "order"));
+        for body in ["<order><item></order>", "<a/><b/>", "just text"] {
+            let report = parse.run_with_report(Context::new().insert("body", body)).await.1;
+            assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput), "{}", body);
+        }
+        let report = parse.run_with_report(Context::new()).await.1;
+        assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+
+        let mut write = Chain::new();
+        write.add_link(to_xml("order", "body"));
+        for order in [json!({ "a": 1, "b": 2 }), json!([1, 2]), json!({ "order": { "@id": { "nested": 1 } } })] {
+            let report = write.run_with_report(Context::new().insert("order", order.clone())).await.1;
+            assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput), "{}", order);
+        }
+    }
+}
+
+#[cfg(feature = "yaml")]
+mod yaml {
+    use super::*;
+    use modulink_rs::std_links::{parse_yaml, to_yaml};
+
+    #[tokio::test]
+    async fn test_yaml_round_trip() {
+        let mut chain = Chain::new();
+        chain.add_link(parse_yaml("manifest", "config"));
+        chain.add_link(to_yaml("config", "written"));
+        let manifest = "name: orders\nreplicas: 3\nports:\n  - 80\n  - 443\ntls: true\n";
+        let ctx = chain.run(Context::new().insert("manifest", manifest)).await;
+
+        let config = ctx.get::<Value>("config").unwrap();
+        assert_eq!(config, json!({ "name": "orders", "replicas": 3, "ports": [80, 443], "tls": true }));
+        let written = ctx.get::<String>("written").unwrap();
+        assert_eq!(serde_yaml::from_str::<Value>(&written).unwrap(), config);
+    }
+
+    #[tokio::test]
+    async fn test_yaml_errors() {
+        let mut chain = Chain::new();
+        chain.add_link(parse_yaml("manifest", "config"));
+        for manifest in [json!("name: [unclosed"), json!(42)] {
+            let report = chain.run_with_report(Context::new().insert("manifest", manifest)).await.1;
+            assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+        }
+    }
+}
@@ -0,0 +1,116 @@
+//! Test the unary gRPC call link (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::std_links::{grpc_call, GrpcCall, GrpcClient, GrpcCode, GrpcStatus, GrpcStubs};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+#[derive(Deserialize)]
+struct QuoteRequest {
+    sku: String,
+    quantity: u32,
+}
+
+#[derive(Serialize)]
+struct Quote {
+    price: f64,
+    currency: String,
+}
+
+fn quotes() -> GrpcStubs {
+    GrpcStubs::new().method("/pricing.v1.Pricing/Quote", |req: QuoteRequest| async move {
+        if req.sku == "gone" {
+            return Err(GrpcStatus::new(GrpcCode::NotFound, "no such sku"));
+        }
+        Ok(Quote { price: 2.5 * req.quantity as f64, currency: "EUR".to_string() })
+    })
+}
+
+fn failed_kind(status: RunStatus) -> Option<ErrorKind> {
+    match status {
+        RunStatus::Failed(err) => Some(err.kind),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn test_grpc_call_maps_request_and_merges_response() {
+    let mut chain = Chain::new();
+    chain.add_link(grpc_call(
+        Arc::new(quotes()),
+        GrpcCall::new("/pricing.v1.Pricing/Quote").field("sku").field_as("qty", "quantity"),
+    ));
+    let ctx = chain.run(Context::new().insert("sku", "A-1").insert("qty", 4)).await;
+    assert_eq!(ctx.get::<f64>("price"), Some(10.0));
+    assert_eq!(ctx.get::<String>("currency").as_deref(), Some("EUR"));
+    assert_eq!(ctx.get::<String>("sku").as_deref(), Some("A-1"));
+}
+
+#[tokio::test]
+async fn test_grpc_call_response_under_key() {
+    let mut chain = Chain::new();
+    chain.add_link(grpc_call(
+        Arc::new(quotes()),
+        GrpcCall::new("/pricing.v1.Pricing/Quote").field("sku").field("quantity").response_into("quote"),
+    ));
+    let ctx = chain.run(Context::new().insert("sku", "A-1").insert("quantity", 1)).await;
+    assert_eq!(ctx.get::<Value>("quote"), Some(json!({ "price": 2.5, "currency": "EUR" })));
+    assert_eq!(ctx.get::<f64>("price"), None);
+}
+
+#[tokio::test]
+async fn test_grpc_status_maps_to_error_kind() {
+    let mut chain = Chain::new();
+    chain.add_link(grpc_call(Arc::new(quotes()), GrpcCall::new("/pricing.v1.Pricing/Quote").field("sku").field("quantity")));
+
+    let report = chain.run_with_report(Context::new().insert("sku", "gone").insert("quantity", 1)).await.1;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::Internal));
+    // Missing context key
+    let report = chain.run_with_report(Context::new().insert("sku", "A-1")).await.1;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+    // The stub cannot decode a request of the wrong shape
+    let report = chain.run_with_report(Context::new().insert("sku", 7).insert("quantity", 1)).await.1;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+
+    let mut unknown = Chain::new();
+    unknown.add_link(grpc_call(Arc::new(quotes()), GrpcCall::new("/pricing.v1.Pricing/Refund")));
+    let report = unknown.run_with_report(Context::new()).await.1;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::Internal));
+
+    assert_eq!(GrpcCode::from_i32(16).error_kind(), ErrorKind::Unauthorized);
+    assert_eq!(GrpcCode::from_i32(7).error_kind(), ErrorKind::Forbidden);
+    assert_eq!(GrpcCode::from_i32(8).error_kind(), ErrorKind::LimitExceeded);
+    assert_eq!(GrpcCode::from_i32(99), GrpcCode::Unknown);
+    assert_eq!(GrpcCode::from_i32(-1), GrpcCode::Unknown);
+}
+
+// A client recording raw requests, as a dynamic (descriptor-based) client would see them.
+#[derive(Default)]
+struct Recorder(Mutex<Vec<(String, Value)>>);
+
+#[async_trait]
+impl GrpcClient for Recorder {
+    async fn call(&self, method: &str, request: Value) -> Result<Value, GrpcStatus> {
+        self.0.lock().unwrap().push((method.to_string(), request));
+        Ok(Value::Null)
+    }
+}
+
+#[tokio::test]
+async fn test_dotted_fields_build_nested_messages() {
+    let recorder = Arc::new(Recorder::default());
+    let mut chain = Chain::new();
+    chain.add_link(grpc_call(
+        recorder.clone(),
+        GrpcCall::new("/users.v1.Users/Update").field_as("user_id", "user.id").field_as("email", "user.contact.email").field("reason"),
+    ));
+    let ctx = Context::new().insert("user_id", "u-1").insert("email", "a@example.com").insert("reason", "moved");
+    assert!(chain.try_run(ctx).await.is_ok());
+
+    let calls = recorder.0.lock().unwrap();
+    assert_eq!(calls[0].0, "/users.v1.Users/Update");
+    assert_eq!(calls[0].1, json!({ "user": { "id": "u-1", "contact": { "email": "a@example.com" } }, "reason": "moved" }));
+}
@@ -0,0 +1,106 @@
+//! Test the built-in HttpListener options (ergonomic pattern)
+#![cfg(feature = "http")]
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, ListenerAsync};
+use modulink_rs::listeners::{CorsConfig, HttpListener};
+use std::sync::Arc;
+use tokio::time::{sleep, Duration};
+
+fn echo_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let input = ctx.get::<String>("input").unwrap_or_default();
+        ctx.insert("output", input)
+    }))
+}
+
+async fn serve(listener: HttpListener) {
+    tokio::spawn(async move { listener.start().await.unwrap() });
+    sleep(Duration::from_millis(300)).await;
+}
+
+#[tokio::test]
+async fn test_cors_preflight_and_headers() {
+    let mut chain = Chain::new();
+    chain.add_link(echo_link());
+    let cors = CorsConfig::new().allow_origin("https://app.example.com").max_age(Duration::from_secs(600));
+    serve(HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8092").with_cors(cors)).await;
+
+    let client = reqwest::Client::new();
+    let preflight = client.request(reqwest::Method::OPTIONS, "http://127.0.0.1:8092/run")
+        .header("Origin", "https://app.example.com")
+        .header("Access-Control-Request-Method", "POST")
+        .header("Access-Control-Request-Headers", "content-type")
+        .send().await.unwrap();
+    assert!(preflight.status().is_success());
+    assert_eq!(preflight.headers()["access-control-allow-origin"], "https://app.example.com");
+    assert_eq!(preflight.headers()["access-control-max-age"], "600");
+
+    let resp = client.post("http://127.0.0.1:8092/run")
+        .header("Origin", "https://evil.example.com")
+        .json(&serde_json::json!({"input": "hi"}))
+        .send().await.unwrap();
+    assert!(resp.headers().get("access-control-allow-origin").is_none());
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["output"], "hi");
+}
+
+#[tokio::test]
+async fn test_request_id_and_traceparent_propagate_into_context() {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        let seen = ctx.get::<String>("_request_id").unwrap_or_default();
+        ctx.insert("seen_request_id", seen)
+    })));
+    serve(HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8093")).await;
+    let client = reqwest::Client::new();
+
+    let resp = client.post("http://127.0.0.1:8093/run")
+        .header("X-Request-Id", "req-42")
+        .json(&serde_json::json!({ "_request_id": "spoofed" }))
+        .send().await.unwrap();
+    assert_eq!(resp.headers()["x-request-id"], "req-42");
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["seen_request_id"], "req-42");
+
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+    let resp = client.post("http://127.0.0.1:8093/run")
+        .header("traceparent", traceparent)
+        .json(&serde_json::json!({}))
+        .send().await.unwrap();
+    assert_eq!(resp.headers()["x-request-id"], "4bf92f3577b34da6a3ce929d0e0e4736");
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["_traceparent"], traceparent);
+
+    let resp = client.post("http://127.0.0.1:8093/run")
+        .header("traceparent", "garbage")
+        .json(&serde_json::json!({}))
+        .send().await.unwrap();
+    let generated = resp.headers()["x-request-id"].to_str().unwrap().to_string();
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["seen_request_id"], generated);
+    assert!(body.get("_traceparent").is_none());
+}
+
+#[tokio::test]
+async fn test_body_limit_and_compression() {
+    let mut chain = Chain::new();
+    chain.add_link(echo_link());
+    serve(HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8094").with_max_body_bytes(1024).with_compression()).await;
+    let client = reqwest::Client::new();
+
+    let resp = client.post("http://127.0.0.1:8094/run")
+        .json(&serde_json::json!({ "input": "x".repeat(4096) }))
+        .send().await.unwrap();
+    assert_eq!(resp.status(), 413);
+    let body: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(body["error"]["kind"], "LimitExceeded");
+
+    let resp = client.post("http://127.0.0.1:8094/run")
+        .header("Accept-Encoding", "gzip")
+        .json(&serde_json::json!({ "input": "y".repeat(512) }))
+        .send().await.unwrap();
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers()["content-encoding"], "gzip");
+}
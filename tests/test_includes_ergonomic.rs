@@ -0,0 +1,92 @@
+//! Test definitions split across files (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::definitions::{AppDefinition, ChainDefinition, DefinitionError, LinkDefinition, SandboxProfile};
+use modulink_rs::registry;
+use std::path::Path;
+use std::sync::Arc;
+
+fn write(dir: &Path, name: &str, json: &str) {
+    let path = dir.join(name);
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, json).unwrap();
+}
+
+fn register_links() {
+    registry::register_link("includes_require_id", Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("validated", true) })));
+    registry::register_link("includes_lookup", Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("found", true) })));
+    registry::register_link("includes_charge", Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("charged", true) })));
+}
+
+#[tokio::test]
+async fn test_includes_and_use_references() {
+    register_links();
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "common/validation.json", r#"{
+        "chains": [ { "name": "validate_input", "links": [ { "link": "includes_require_id" } ] } ]
+    }"#);
+    write(dir.path(), "payments.json", r#"{
+        "chains": [ { "name": "charge", "links": [ { "link": "includes_charge" } ] } ],
+        "listeners": [ { "type": "stdin", "chain": "charge" } ]
+    }"#);
+    write(dir.path(), "app.json", r#"{
+        "include": ["payments.json"],
+        "chains": [ { "name": "orders", "links": [
+            { "use": "common/validation.json#validate_input" },
+            { "link": "includes_lookup" },
+            { "use": "charge" }
+        ] } ]
+    }"#);
+
+    let def = AppDefinition::from_file(dir.path().join("app.json")).unwrap();
+    assert!(def.include.is_empty());
+    assert_eq!(def.chains.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(), ["orders", "charge"]);
+    assert_eq!(def.listeners.len(), 1);
+    let orders = &def.chains[0];
+    assert!(matches!(&orders.links[0], LinkDefinition::Chain { chain } if chain.name == "validate_input"));
+    assert!(matches!(&orders.links[2], LinkDefinition::Chain { chain } if chain.name == "charge"));
+
+    let ctx = orders.build(None).unwrap().run(Context::new()).await;
+    assert_eq!(ctx.get::<bool>("validated"), Some(true));
+    assert_eq!(ctx.get::<bool>("found"), Some(true));
+    assert_eq!(ctx.get::<bool>("charged"), Some(true));
+}
+
+#[test]
+fn test_include_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    write(dir.path(), "a.json", r#"{ "include": ["b.json"] }"#);
+    write(dir.path(), "b.json", r#"{ "include": ["a.json"] }"#);
+    assert!(matches!(AppDefinition::from_file(dir.path().join("a.json")), Err(DefinitionError::Include(_))));
+
+    write(dir.path(), "twice.json", r#"{ "include": ["one.json"], "chains": [ { "name": "x", "links": [] } ] }"#);
+    write(dir.path(), "one.json", r#"{ "chains": [ { "name": "x", "links": [] } ] }"#);
+    let err = AppDefinition::from_file(dir.path().join("twice.json")).unwrap_err();
+    assert_eq!(err.to_string(), "include: chain 'x' is defined twice");
+
+    write(dir.path(), "loop.json", r#"{ "chains": [
+        { "name": "x", "links": [ { "use": "y" } ] },
+        { "name": "y", "links": [ { "use": "x" } ] }
+    ] }"#);
+    assert!(matches!(AppDefinition::from_file(dir.path().join("loop.json")), Err(DefinitionError::Include(msg)) if msg.contains("uses itself")));
+
+    write(dir.path(), "missing.json", r#"{ "chains": [ { "name": "x", "links": [ { "use": "one.json#nope" } ] } ] }"#);
+    assert!(matches!(AppDefinition::from_file(dir.path().join("missing.json")), Err(DefinitionError::UnknownChain(name)) if name == "one.json#nope"));
+}
+
+#[tokio::test]
+async fn test_use_falls_back_to_registry_chains() {
+    let mut registered = Chain::new();
+    registered.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("audited", true) })));
+    registry::register_chain("includes_audit", Arc::new(registered));
+
+    let def = ChainDefinition::from_json(r#"{ "name": "x", "links": [ { "use": "includes_audit" } ] }"#).unwrap();
+    let ctx = def.build(None).unwrap().run(Context::new()).await;
+    assert_eq!(ctx.get::<bool>("audited"), Some(true));
+
+    let unknown = ChainDefinition::from_json(r#"{ "name": "x", "links": [ { "use": "includes_nobody" } ] }"#).unwrap();
+    assert!(matches!(unknown.build(None), Err(DefinitionError::UnknownChain(_))));
+    assert!(matches!(SandboxProfile::new().check(&def), Err(DefinitionError::NotAllowed(_))));
+    assert!(SandboxProfile::new().allow_link("includes_audit").check(&def).is_ok());
+}
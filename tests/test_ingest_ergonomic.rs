@@ -0,0 +1,186 @@
+//! Test CSV/Parquet ingestion links (ergonomic pattern)
+#![cfg(any(feature = "csv", feature = "parquet"))]
+
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::std_links::{Chunking, ReadFile};
+use serde_json::{json, Value};
+use std::path::Path;
+use std::sync::Arc;
+
+fn failed_kind(status: RunStatus) -> Option<ErrorKind> {
+    match status {
+        RunStatus::Failed(err) => Some(err.kind),
+        _ => None,
+    }
+}
+
+// A chain reading a page per pass until the cursor runs out, collecting every page.
+fn paged(read: Link) -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(read);
+    chain.add_link(Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            let mut pages = ctx.get::<Vec<Vec<Value>>>("pages").unwrap_or_default();
+            pages.push(ctx.get::<Vec<Value>>("rows").unwrap());
+            ctx.insert("pages", pages)
+        })
+    }));
+    chain.connect(1, 0, |ctx: &Context| ctx.get::<usize>("cursor").is_some());
+    chain
+}
+
+fn path_ctx(path: &Path) -> Context {
+    Context::new().insert("path", path.to_str().unwrap())
+}
+
+#[cfg(feature = "csv")]
+mod csv_files {
+    use super::*;
+    use modulink_rs::std_links::read_csv;
+
+    fn orders_csv(dir: &Path) -> std::path::PathBuf {
+        let path = dir.join("orders.csv");
+        std::fs::write(&path, "id,total,paid,note\no-1,12,true,first\no-2,3.5,false,\no-3,7,true,007\n").unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn test_read_csv_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = orders_csv(dir.path());
+        let mut chain = Chain::new();
+        chain.add_link(read_csv(ReadFile::new("path").into_key("orders")));
+
+        let ctx = chain.run(path_ctx(&path)).await;
+        let rows = ctx.get::<Vec<Value>>("orders").unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0], json!({ "id": "o-1", "total": 12, "paid": true, "note": "first" }));
+        assert_eq!(rows[1]["total"], 3.5);
+        assert_eq!(rows[1]["note"], "");
+        assert_eq!(rows[2]["note"], 7);
+
+        let mut strings = Chain::new();
+        strings.add_link(read_csv(ReadFile::new("path").keep_strings()));
+        let ctx = strings.run(path_ctx(&path)).await;
+        assert_eq!(ctx.get::<Vec<Value>>("rows").unwrap()[2]["note"], "007");
+    }
+
+    #[tokio::test]
+    async fn test_read_csv_chunks_and_delimiter() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("scores.tsv");
+        std::fs::write(&path, "name\tscore\na\t1\nb\t2\nc\t3\nd\t4\ne\t5\n").unwrap();
+        let mut chain = Chain::new();
+        chain.add_link(read_csv(ReadFile::new("path").with_delimiter(b'\t').with_chunking(Chunking::Chunks(2))));
+
+        let ctx = chain.run(path_ctx(&path)).await;
+        let chunks = ctx.get::<Vec<Vec<Value>>>("rows").unwrap();
+        assert_eq!(chunks.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+        assert_eq!(chunks[2][0], json!({ "name": "e", "score": 5 }));
+    }
+
+    #[tokio::test]
+    async fn test_read_csv_paged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = orders_csv(dir.path());
+        let chain = paged(read_csv(ReadFile::new("path").with_chunking(Chunking::Paged { rows: 2, cursor: "cursor".into() })));
+
+        let ctx = chain.run(path_ctx(&path)).await;
+        let pages = ctx.get::<Vec<Vec<Value>>>("pages").unwrap();
+        assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(pages[1][0]["id"], "o-3");
+        assert_eq!(ctx.get::<Value>("cursor"), Some(Value::Null));
+    }
+
+    #[tokio::test]
+    async fn test_read_csv_on_the_executor() {
+        use modulink_rs::runtime::MockExecutor;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = orders_csv(dir.path());
+        let exec = MockExecutor::new();
+        let mut chain = Chain::new();
+        chain.add_link(read_csv(ReadFile::new("path").with_executor(Arc::new(exec.clone()))));
+        let run = tokio::spawn(async move { chain.run(path_ctx(&path)).await });
+
+        // The read waits for the executor's blocking pool
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!run.is_finished());
+        exec.run_until_stalled();
+        assert_eq!(run.await.unwrap().get::<Vec<Value>>("rows").unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_read_csv_errors() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut chain = Chain::new();
+        chain.add_link(read_csv(ReadFile::new("path")));
+
+        let report = chain.run_with_report(Context::new()).await.1;
+        assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+        let report = chain.run_with_report(path_ctx(&dir.path().join("missing.csv"))).await.1;
+        assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+
+        let ragged = dir.path().join("ragged.csv");
+        std::fs::write(&ragged, "a,b\n1,2\n3\n").unwrap();
+        let report = chain.run_with_report(path_ctx(&ragged)).await.1;
+        assert_eq!(failed_kind(report.status), Some(ErrorKind::Internal));
+    }
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_files {
+    use super::*;
+    use modulink_rs::std_links::read_parquet;
+    use parquet::data_type::{ByteArray, ByteArrayType, Int64Type};
+    use parquet::file::properties::WriterProperties;
+    use parquet::file::writer::SerializedFileWriter;
+    use parquet::schema::parser::parse_message_type;
+
+    fn write_parquet(path: &Path, ids: &[&str], totals: &[i64]) {
+        let schema = Arc::new(parse_message_type("message order { REQUIRED BYTE_ARRAY id (UTF8); REQUIRED INT64 total; }").unwrap());
+        let file = std::fs::File::create(path).unwrap();
+        let mut writer = SerializedFileWriter::new(file, schema, Arc::new(WriterProperties::builder().build())).unwrap();
+        let mut group = writer.next_row_group().unwrap();
+        let mut column = group.next_column().unwrap().unwrap();
+        let ids: Vec<ByteArray> = ids.iter().map(|id| ByteArray::from(*id)).collect();
+        column.typed::<ByteArrayType>().write_batch(&ids, None, None).unwrap();
+        column.close().unwrap();
+        let mut column = group.next_column().unwrap().unwrap();
+        column.typed::<Int64Type>().write_batch(totals, None, None).unwrap();
+        column.close().unwrap();
+        group.close().unwrap();
+        writer.close().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_parquet_rows_and_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("orders.parquet");
+        write_parquet(&path, &["o-1", "o-2", "o-3"], &[12, 3, 7]);
+
+        let mut chain = Chain::new();
+        chain.add_link(read_parquet(ReadFile::new("path")));
+        let ctx = chain.run(path_ctx(&path)).await;
+        let rows = ctx.get::<Vec<Value>>("rows").unwrap();
+        assert_eq!(rows, vec![json!({ "id": "o-1", "total": 12 }), json!({ "id": "o-2", "total": 3 }), json!({ "id": "o-3", "total": 7 })]);
+
+        let chain = paged(read_parquet(ReadFile::new("path").with_chunking(Chunking::Paged { rows: 2, cursor: "cursor".into() })));
+        let ctx = chain.run(path_ctx(&path)).await;
+        let pages = ctx.get::<Vec<Vec<Value>>>("pages").unwrap();
+        assert_eq!(pages.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_read_parquet_rejects_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("not.parquet");
+        std::fs::write(&path, "id,total\n").unwrap();
+        let mut chain = Chain::new();
+        chain.add_link(read_parquet(ReadFile::new("path")));
+        let report = chain.run_with_report(path_ctx(&path)).await.1;
+        assert_eq!(failed_kind(report.status), Some(ErrorKind::Internal));
+    }
+}
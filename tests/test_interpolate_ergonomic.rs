@@ -0,0 +1,74 @@
+//! Test environment and secret references in definitions (ergonomic pattern)
+
+use modulink_rs::definitions::{AppDefinition, ChainDefinition, DefinitionError, FileSecrets, Interpolation, ListenerDefinition};
+
+fn vars() -> Interpolation {
+    Interpolation::new().with_vars([("STAGE", "prod"), ("EMPTY", ""), ("MAX_BODY", "4096")])
+}
+
+#[test]
+fn test_placeholders() {
+    let interpolation = vars();
+    assert_eq!(interpolation.interpolate("orders-${STAGE}").unwrap(), "orders-prod");
+    assert_eq!(interpolation.interpolate("${REGION:-eu-west-1}/${STAGE:-dev}").unwrap(), "eu-west-1/prod");
+    assert_eq!(interpolation.interpolate("${EMPTY:-fallback}|${EMPTY}|").unwrap(), "fallback||");
+    assert_eq!(interpolation.interpolate("costs $$5, $ stays").unwrap(), "costs $5, $ stays");
+    assert!(matches!(interpolation.interpolate("${MISSING}"), Err(DefinitionError::Unresolved(msg)) if msg.contains("MISSING")));
+    assert!(matches!(interpolation.interpolate("${STAGE"), Err(DefinitionError::Unresolved(_))));
+}
+
+#[test]
+fn test_chain_definition_references() {
+    let json = r#"{ "name": "orders-${STAGE}", "input": ["${KEY:-order_id}"], "links": [] }"#;
+    let def = ChainDefinition::from_json_with(json, &vars()).unwrap();
+    assert_eq!(def.name, "orders-prod");
+    assert_eq!(def.input, vec!["order_id"]);
+    // Plain parsing leaves references alone, so untrusted definitions cannot read the environment.
+    assert_eq!(ChainDefinition::from_json(json).unwrap().name, "orders-${STAGE}");
+}
+
+#[test]
+fn test_app_file_with_env_and_secrets() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::create_dir(dir.path().join("jwt")).unwrap();
+    std::fs::write(dir.path().join("jwt/orders"), "s3cr3t\n").unwrap();
+    let path = dir.path().join("app.json");
+    std::fs::write(&path, r#"{
+        "listeners": [ {
+            "type": "http", "chain": "orders-${STAGE}", "address": "0.0.0.0:${PORT:-8080}",
+            "auth": { "jwt": { "hs256_secret": "secret://jwt/orders" } },
+            "limits": { "max_body_bytes": ${MAX_BODY} }
+        } ]
+    }"#).unwrap();
+
+    let interpolation = vars().with_secrets(FileSecrets::new(dir.path()));
+    let def = AppDefinition::from_file_with(&path, &interpolation).unwrap();
+    let ListenerDefinition::Http { chain, address, auth, limits } = &def.listeners[0] else { panic!("not http") };
+    assert_eq!((chain.as_str(), address.as_str()), ("orders-prod", "0.0.0.0:8080"));
+    assert_eq!(auth.jwt.as_ref().unwrap().hs256_secret.as_deref(), Some("s3cr3t"));
+    assert_eq!(limits.max_body_bytes, Some(4096));
+
+    let missing = vars().with_secrets(FileSecrets::new(dir.path().join("nowhere")));
+    assert!(matches!(AppDefinition::from_file_with(&path, &missing), Err(DefinitionError::Unresolved(msg)) if msg.contains("jwt/orders")));
+}
+
+#[test]
+fn test_secret_names_stay_in_their_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let secrets = FileSecrets::new(dir.path());
+    let interpolation = Interpolation::new().with_secrets(secrets);
+    let mut value = serde_json::json!({ "key": "secret://../etc/passwd" });
+    assert!(interpolation.resolve_secrets(&mut value).is_err());
+    let mut value = serde_json::json!({ "key": "secret:///etc/passwd" });
+    assert!(interpolation.resolve_secrets(&mut value).is_err());
+}
+
+#[cfg(feature = "yaml")]
+#[test]
+fn test_yaml_app_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("app.yaml");
+    std::fs::write(&path, "listeners:\n  - type: stdin\n    chain: ${CHAIN:-orders}\n").unwrap();
+    let def = AppDefinition::from_file_with(&path, &vars()).unwrap();
+    assert_eq!(def.listeners[0].chain(), "orders");
+}
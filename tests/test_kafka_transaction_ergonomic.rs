@@ -0,0 +1,136 @@
+//! Test exactly-once Kafka consume-transform-produce (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::chains::{Chain, RunError};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::listeners::{BaseListenerAsync, KafkaRecord, KafkaRecordConsumer, KafkaTransactionalListener, KafkaTransactionalProducer};
+use modulink_rs::sinks::KafkaProducer;
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Broker {
+    queue: Mutex<Vec<KafkaRecord>>,
+    log: Mutex<Vec<String>>,
+}
+
+impl Broker {
+    fn with_messages(messages: &[&str]) -> Arc<Self> {
+        let queue = messages
+            .iter()
+            .enumerate()
+            .map(|(i, m)| KafkaRecord { topic: "in".to_string(), partition: 0, offset: i as i64, payload: m.as_bytes().to_vec() })
+            .collect();
+        Arc::new(Broker { queue: Mutex::new(queue), log: Mutex::default() })
+    }
+    fn log(&self, entry: String) {
+        self.log.lock().unwrap().push(entry);
+    }
+}
+
+struct Consumer(Arc<Broker>);
+
+#[async_trait]
+impl KafkaRecordConsumer for Consumer {
+    async fn recv_record(&self, topic: &str) -> std::io::Result<Option<KafkaRecord>> {
+        assert_eq!(topic, "in");
+        let mut queue = self.0.queue.lock().unwrap();
+        Ok(if queue.is_empty() { None } else { Some(queue.remove(0)) })
+    }
+    fn group_id(&self) -> &str {
+        "workers"
+    }
+    async fn rewind(&self, record: &KafkaRecord) -> std::io::Result<()> {
+        self.0.log(format!("rewind {}", record.offset));
+        self.0.queue.lock().unwrap().insert(0, record.clone());
+        Ok(())
+    }
+}
+
+struct Producer(Arc<Broker>);
+
+#[async_trait]
+impl KafkaProducer for Producer {
+    async fn send(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> std::io::Result<()> {
+        let payload = String::from_utf8(payload).unwrap();
+        if payload.contains("broken") {
+            return Err(std::io::Error::other("broker unavailable"));
+        }
+        self.0.log(format!("send {} {:?}", topic, key));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KafkaTransactionalProducer for Producer {
+    async fn begin_transaction(&self) -> std::io::Result<()> {
+        self.0.log("begin".to_string());
+        Ok(())
+    }
+    async fn send_offsets(&self, group_id: &str, topic: &str, partition: i32, offset: i64) -> std::io::Result<()> {
+        self.0.log(format!("offsets {} {}/{} {}", group_id, topic, partition, offset));
+        Ok(())
+    }
+    async fn commit_transaction(&self) -> std::io::Result<()> {
+        self.0.log("commit".to_string());
+        Ok(())
+    }
+    async fn abort_transaction(&self) -> std::io::Result<()> {
+        self.0.log("abort".to_string());
+        Ok(())
+    }
+}
+
+fn chain() -> Arc<Chain> {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        if ctx.get::<bool>("reject").unwrap_or(false) {
+            ctx_tools::fail_run(RunError::internal("rejected"));
+        }
+        ctx.insert("processed", true)
+    })));
+    Arc::new(chain)
+}
+
+#[tokio::test]
+async fn test_results_and_offsets_commit_together() {
+    let broker = Broker::with_messages(&[r#"{"id":"a"}"#, r#"{"reject":true}"#, "not json"]);
+    let listener = KafkaTransactionalListener::new(Arc::new(Consumer(broker.clone())), Arc::new(Producer(broker.clone())), chain(), "in", "out")
+        .with_key(|ctx: &Context| ctx.get::<String>("id"));
+    listener.start().await.unwrap();
+    assert_eq!(
+        *broker.log.lock().unwrap(),
+        vec![
+            "begin", "send out Some(\"a\")", "offsets workers in/0 1", "commit",
+            "begin", "offsets workers in/0 2", "commit",
+            "begin", "offsets workers in/0 3", "commit",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn test_produce_failure_aborts_and_rewinds() {
+    let broker = Broker::with_messages(&[r#"{"id":"a"}"#, r#"{"id":"broken"}"#]);
+    let listener = KafkaTransactionalListener::new(Arc::new(Consumer(broker.clone())), Arc::new(Producer(broker.clone())), chain(), "in", "out");
+    assert!(listener.start().await.is_err());
+    assert_eq!(
+        *broker.log.lock().unwrap(),
+        vec!["begin", "send out None", "offsets workers in/0 1", "commit", "begin", "abort", "rewind 1"]
+    );
+    // The failed message is delivered again on restart
+    assert_eq!(broker.queue.lock().unwrap()[0].offset, 1);
+}
+
+#[tokio::test]
+async fn test_reserved_keys_are_dropped_from_messages() {
+    let broker = Broker::with_messages(&[r#"{"id":"a","_tenant":"globex","_auth":{"sub":"root"}}"#]);
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        let leaked = ctx.0.keys().any(|key| key.starts_with('_'));
+        ctx.insert("leaked", leaked)
+    })));
+    let listener = KafkaTransactionalListener::new(Arc::new(Consumer(broker.clone())), Arc::new(Producer(broker.clone())), Arc::new(chain), "in", "out")
+        .with_key(|ctx: &Context| Some(format!("{}:{}", ctx.get::<String>("id")?, ctx.get::<bool>("leaked")?)));
+    listener.start().await.unwrap();
+    assert_eq!(broker.log.lock().unwrap()[1], "send out Some(\"a:false\")");
+}
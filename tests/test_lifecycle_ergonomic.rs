@@ -0,0 +1,90 @@
+//! Test chain lifecycle hooks (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::chains::{Chain, Initialize, Shutdown};
+use modulink_rs::context::Context;
+use modulink_rs::middleware::Middleware;
+use std::sync::{Arc, Mutex};
+
+struct Recorder {
+    name: &'static str,
+    log: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Initialize for Recorder {
+    async fn init(&self) -> std::io::Result<()> {
+        self.log.lock().unwrap().push(format!("init {}", self.name));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Shutdown for Recorder {
+    async fn shutdown(&self) -> std::io::Result<()> {
+        self.log.lock().unwrap().push(format!("shutdown {}", self.name));
+        if self.name == "pool" {
+            return Err(std::io::Error::other("pool already closed"));
+        }
+        Ok(())
+    }
+}
+
+impl Middleware<Context> for Recorder {
+    fn initializer(&self) -> Option<&dyn Initialize> {
+        Some(self)
+    }
+    fn shutdown_hook(&self) -> Option<&dyn Shutdown> {
+        Some(self)
+    }
+}
+
+#[tokio::test]
+async fn test_warm_up_initializes_middleware_then_links() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut chain = Chain::new();
+    chain.add_initializer(Arc::new(Recorder { name: "pool", log: log.clone() }));
+    chain.use_middleware(Arc::new(Recorder { name: "tracing", log: log.clone() }));
+    chain.use_middleware_if(Arc::new(Recorder { name: "debug", log: log.clone() }), |_: &Context| false);
+    chain.warm_up().await.unwrap();
+    assert_eq!(*log.lock().unwrap(), vec!["init tracing", "init debug", "init pool"]);
+}
+
+#[tokio::test]
+async fn test_shutdown_runs_every_hook_in_reverse() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut chain = Chain::new();
+    chain.use_middleware(Arc::new(Recorder { name: "tracing", log: log.clone() }));
+    chain.add_shutdown(Arc::new(Recorder { name: "pool", log: log.clone() }));
+    chain.add_shutdown(Arc::new(Recorder { name: "buffer", log: log.clone() }));
+    let err = chain.shutdown().await.unwrap_err();
+    assert_eq!(err.to_string(), "pool already closed");
+    assert_eq!(*log.lock().unwrap(), vec!["shutdown buffer", "shutdown pool", "shutdown tracing"]);
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_http_listener_graceful_shutdown_runs_chain_hooks() {
+    use modulink_rs::links::ListenerAsync;
+    use modulink_rs::listeners::HttpListener;
+
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let mut chain = Chain::new();
+    chain.add_initializer(Arc::new(Recorder { name: "templates", log: log.clone() }));
+    chain.add_shutdown(Arc::new(Recorder { name: "buffer", log: log.clone() }));
+    let (tx, rx) = tokio::sync::oneshot::channel::<()>();
+    let rx = Arc::new(tokio::sync::Mutex::new(Some(rx)));
+    let listener = HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8096").with_graceful_shutdown(move || {
+        let rx = rx.clone();
+        async move {
+            if let Some(rx) = rx.lock().await.take() {
+                let _ = rx.await;
+            }
+        }
+    });
+    let server = tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    let _ = tx.send(());
+    server.await.unwrap().unwrap();
+    assert_eq!(*log.lock().unwrap(), vec!["init templates", "shutdown buffer"]);
+}
@@ -0,0 +1,69 @@
+//! Test per-run ResourceLimits (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, ResourceLimits, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::Link;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn assert_limit_exceeded(status: &RunStatus) {
+    match status {
+        RunStatus::Failed(err) => assert_eq!(err.kind, ErrorKind::LimitExceeded),
+        other => panic!("expected LimitExceeded, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_wall_time_limit() {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        ctx
+    })));
+    chain.set_limits(ResourceLimits::new().with_max_wall_time(Duration::from_millis(50)));
+    let (ctx, report) = chain.run_with_report(Context::new().insert("a", 1)).await;
+    assert_limit_exceeded(&report.status);
+    assert!(ctx.0.is_empty());
+}
+
+#[tokio::test]
+async fn test_context_size_limit_stops_chain() {
+    let grow: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("blob", "x".repeat(2048)) }));
+    let after: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("after", true) }));
+    let mut chain = Chain::new();
+    chain.add_link(grow);
+    chain.add_link(after);
+    chain.set_limits(ResourceLimits::new().with_max_context_bytes(1024));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_limit_exceeded(&report.status);
+    assert_eq!(ctx.get::<bool>("after"), None);
+
+    let (_, report) = chain.run_with_report(Context::new().insert("input", "y".repeat(4096))).await;
+    assert_limit_exceeded(&report.status);
+}
+
+#[tokio::test]
+async fn test_child_limit() {
+    let child = Arc::new(Chain::new());
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(move |ctx: Context| {
+        let child = child.clone();
+        Box::pin(async move {
+            let handles: Vec<_> = (0..3).map(|_| ctx_tools::spawn_child(child.clone(), Context::new())).collect();
+            let mut started = 0;
+            for handle in handles {
+                if handle.await.is_ok() {
+                    started += 1;
+                }
+            }
+            ctx.insert("started", started)
+        })
+    }));
+    chain.set_limits(ResourceLimits::new().with_max_children(2));
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_limit_exceeded(&report.status);
+    assert_eq!(ctx.get::<u32>("started"), Some(2));
+    assert_eq!(report.children.len(), 2);
+}
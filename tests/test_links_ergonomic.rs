@@ -1,5 +1,7 @@
 //! Test link behavior in isolation (ergonomic pattern)
 
+#![allow(clippy::let_and_return)]
+
 use modulink_rs::context::Context;
 use modulink_rs::links::Link;
 use std::sync::Arc;
@@ -2,6 +2,8 @@
 //
 // This test follows the API and requirements in docs/LISTENER_API_DESIGN.md.
 
+#![allow(clippy::io_other_error)]
+
 use modulink_rs::context::Context;
 use modulink_rs::links::ListenerAsync;
 use std::sync::Arc;
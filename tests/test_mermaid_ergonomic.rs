@@ -0,0 +1,63 @@
+//! Test exporting chains as Mermaid flowcharts (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorRoute};
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::Arc;
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+#[test]
+fn test_to_mermaid_draws_links_and_branches() {
+    let mut chain = Chain::new();
+    chain.add_link_with(noop(), LinkSpec::new().name("score"));
+    chain.add_link_with(noop(), LinkSpec::new().description("Manual \"deep\" review"));
+    chain.add_link_with(noop(), LinkSpec::new().name("refund").side_effects());
+    chain.add_link(noop());
+    chain.connect_described(0, 2, "ctx.score >= 0.8", |ctx: &Context| ctx.get::<f64>("score").unwrap_or(0.0) >= 0.8);
+    chain.connect(1, 0, |ctx: &Context| ctx.get::<bool>("retry") == Some(true));
+    chain.jump(2, 3);
+    chain.route_errors(0, ErrorRoute::Jump(3));
+
+    assert_eq!(chain.to_mermaid(), [
+        "flowchart TD",
+        "    L0[\"0: score\"]",
+        "    L1[\"1: Manual #quot;deep#quot; review\"]",
+        "    L2[\"2: refund\"]",
+        "    L3[\"Link 3\"]",
+        "    L0 --> L1",
+        "    L1 --> L2",
+        "    L2 --> L3",
+        "    L0 -.->|\"ctx.score >= 0.8\"| L2",
+        "    L1 -.->|branch| L0",
+        "    L2 -.->|always| L3",
+        "    L0 -.->|on error| L3",
+        "    classDef sideEffects stroke-width:3px",
+        "    class L2 sideEffects",
+        "    linkStyle 6 stroke:red",
+        "",
+    ].join("\n"));
+}
+
+#[test]
+fn test_plain_chain() {
+    let mut chain = Chain::new();
+    chain.add_link(noop());
+    chain.add_link(noop());
+    assert_eq!(chain.to_mermaid(), "flowchart TD\n    L0[\"Link 0\"]\n    L1[\"Link 1\"]\n    L0 --> L1\n");
+}
+
+#[cfg(feature = "cli")]
+#[test]
+fn test_visualize_as_mermaid() {
+    use modulink_rs::cli::{self, GraphFormat};
+    use modulink_rs::registry;
+
+    let mut chain = Chain::new();
+    chain.add_link(noop());
+    registry::register_chain("mermaid_registered", Arc::new(chain));
+    let out = cli::visualize(Some("mermaid_registered"), None, GraphFormat::Mermaid).unwrap();
+    assert_eq!(out, "flowchart TD\n    L0[\"Link 0\"]\n");
+}
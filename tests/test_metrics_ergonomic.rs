@@ -0,0 +1,48 @@
+//! Test context-derived labels in the metrics middleware (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, RunError};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::ctx_tools;
+use modulink_rs::links::Link;
+use modulink_rs::middleware::metrics::OTHER;
+use modulink_rs::middleware::MetricsMiddleware;
+use std::sync::Arc;
+
+fn fail_when_flagged() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        if ctx.get::<bool>("fail") == Some(true) {
+            ctx_tools::fail_run(RunError::internal("flagged"));
+        }
+        ctx
+    }))
+}
+
+#[tokio::test]
+async fn test_metrics_labels_with_cardinality_cap() {
+    let metrics = Arc::new(
+        MetricsMiddleware::new("orders")
+            .label_key("tenant", meta::TENANT)
+            .label_key("route", "_http.path")
+            .with_max_values(2),
+    );
+    let mut chain = Chain::new();
+    chain.add_link(fail_when_flagged());
+    chain.use_middleware(metrics.clone());
+
+    let http = serde_json::json!({ "method": "POST", "path": "/run" });
+    for (tenant, fail) in [("acme", false), ("acme", true), ("globex", false), ("initech", false), ("umbrella", false)] {
+        let ctx = Context::new().with_tenant(tenant).insert(meta::HTTP, &http).insert("fail", fail);
+        chain.run(ctx).await;
+    }
+
+    let series = metrics.snapshot();
+    let find = |tenant: &str| series.iter().find(|s| s.labels["tenant"] == tenant).unwrap();
+    assert_eq!(series.len(), 3);
+    assert_eq!(find("acme").runs, 2);
+    assert_eq!(find("acme").failures, 1);
+    assert_eq!(find("acme").labels["route"], "/run");
+    assert_eq!(find("acme").labels["chain"], "orders");
+    assert_eq!(find("globex").runs, 1);
+    // Past the cap of two tenants, new values collapse into one series
+    assert_eq!(find(OTHER).runs, 2);
+}
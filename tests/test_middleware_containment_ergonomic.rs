@@ -0,0 +1,122 @@
+//! Test middleware failure containment (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::middleware::{Containment, Middleware};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+struct PanicsBefore;
+
+impl Middleware<Context> for PanicsBefore {
+    fn before<'a>(&'a self, _ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async { panic!("exporter offline") })
+    }
+}
+
+struct FailsAfter;
+
+impl Middleware<Context> for FailsAfter {
+    fn after<'a>(&'a self, _ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async {
+            ctx_tools::fail_run(RunError::internal("audit write failed"));
+        })
+    }
+}
+
+struct PanicsOnStart;
+
+impl Middleware<Context> for PanicsOnStart {
+    fn on_run_start<'a>(&'a self, _ctx: Context) -> Pin<Box<dyn Future<Output = Context> + Send + 'a>>
+    where
+        Context: Send + 'a,
+    {
+        panic!("rewrite failed")
+    }
+}
+
+fn chain(mw: Arc<dyn Middleware<Context>>, containment: Containment) -> Chain {
+    let mut chain = Chain::new();
+    chain.use_contained_middleware(mw, containment);
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("done", true) })));
+    chain
+}
+
+#[tokio::test]
+async fn test_contained_panic_becomes_a_warning() {
+    let chain = chain(Arc::new(PanicsBefore), Containment::LogAndContinue);
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("done"), Some(true));
+    assert_eq!(report.warnings, ["middleware PanicsBefore failed in before: Panicked: exporter offline"]);
+}
+
+#[tokio::test]
+async fn test_fail_run_turns_a_panic_into_a_run_error() {
+    let chain = chain(Arc::new(PanicsBefore), Containment::FailRun);
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    let RunStatus::Failed(err) = report.status else { panic!("run should fail") };
+    assert_eq!(err.kind, ErrorKind::Panicked);
+    assert_eq!(err.message, "exporter offline");
+    assert_eq!(ctx.get::<bool>("done"), None);
+}
+
+#[tokio::test]
+async fn test_contained_run_failure_is_cleared() {
+    let contained = chain(Arc::new(FailsAfter), Containment::LogAndContinue);
+    let (_, report) = contained.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(report.warnings.len(), 1);
+
+    let failing = chain(Arc::new(FailsAfter), Containment::FailRun);
+    let (_, report) = failing.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref err) if err.message == "audit write failed"));
+}
+
+#[tokio::test]
+async fn test_contained_run_start_panic_keeps_the_context() {
+    let chain = chain(Arc::new(PanicsOnStart), Containment::LogAndContinue);
+    let (ctx, report) = chain.run_with_report(Context::new().insert("order", 7)).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<u32>("order"), Some(7));
+    assert_eq!(ctx.get::<bool>("done"), Some(true));
+}
+
+#[tokio::test]
+async fn test_earlier_failure_is_not_cleared() {
+    let mut chain = Chain::new();
+    chain.use_contained_middleware(Arc::new(FailsAfter), Containment::LogAndContinue);
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::invalid_input("bad order"));
+        ctx
+    })));
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref err) if err.message == "bad order"));
+}
+
+// Waits in `after` long enough for concurrent middleware to fail the run meanwhile
+struct SlowAfter;
+
+impl Middleware<Context> for SlowAfter {
+    fn after<'a>(&'a self, _ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        })
+    }
+}
+
+#[tokio::test]
+async fn test_concurrent_failures_are_not_blamed_on_a_contained_hook() {
+    use modulink_rs::middleware::MiddlewareExecution;
+
+    let mut chain = Chain::new();
+    chain.set_middleware_execution(MiddlewareExecution::Concurrent);
+    chain.use_contained_middleware(Arc::new(SlowAfter), Containment::LogAndContinue);
+    chain.use_middleware(Arc::new(FailsAfter));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("done", true) })));
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref err) if err.message == "audit write failed"));
+    assert!(report.warnings.is_empty());
+}
@@ -1,5 +1,7 @@
 //! Test middleware before/after hooks (ergonomic pattern)
 
+#![allow(clippy::type_complexity, clippy::bool_assert_comparison)]
+
 use modulink_rs::context::{Context};
 use modulink_rs::chains::{Chain};
 use std::sync::{Arc, Mutex};
@@ -51,3 +53,18 @@ async fn test_middleware_hooks() {
     assert_eq!(*before.lock().unwrap(), true);
     assert_eq!(*after.lock().unwrap(), true);
 }
+
+#[tokio::test]
+async fn test_middleware_if_predicate() {
+    let mut chain = Chain::new();
+    chain.add_link(dummy_link());
+    let mw = TestMiddleware::new();
+    let before = mw.before_called.clone();
+    chain.use_middleware_if(Arc::new(mw), |ctx: &Context| ctx.get::<bool>("debug") == Some(true));
+
+    let _ = chain.run(Context::new()).await;
+    assert!(!*before.lock().unwrap());
+    let _ = chain.run(Context::new().insert("debug", true)).await;
+    assert!(*before.lock().unwrap());
+    assert_eq!(chain.middleware_names(), vec!["TestMiddleware".to_string()]);
+}
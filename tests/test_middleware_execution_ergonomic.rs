@@ -0,0 +1,72 @@
+//! Test sequential and concurrent middleware execution (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::middleware::{Middleware, MiddlewareExecution};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+// Each `before` hook waits until every middleware has entered its own `before` hook,
+// which only happens when the hooks run concurrently.
+struct Rendezvous {
+    entered: Arc<AtomicUsize>,
+    of: usize,
+}
+
+impl Middleware<Context> for Rendezvous {
+    fn before<'a>(&'a self, _ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            self.entered.fetch_add(1, Ordering::SeqCst);
+            while self.entered.load(Ordering::SeqCst) < self.of {
+                tokio::task::yield_now().await;
+            }
+        })
+    }
+}
+
+struct Record(&'static str, Arc<Mutex<Vec<&'static str>>>);
+
+impl Middleware<Context> for Record {
+    fn before<'a>(&'a self, _ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move { self.1.lock().unwrap().push(self.0) })
+    }
+}
+
+fn chain(execution: MiddlewareExecution) -> Chain {
+    let mut chain = Chain::new();
+    chain.set_middleware_execution(execution);
+    let entered = Arc::new(AtomicUsize::new(0));
+    for _ in 0..3 {
+        chain.use_middleware(Arc::new(Rendezvous { entered: entered.clone(), of: 3 }));
+    }
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("done", true) })));
+    chain
+}
+
+#[tokio::test]
+async fn test_concurrent_hooks_run_together() {
+    let chain = chain(MiddlewareExecution::Concurrent);
+    let ctx = tokio::time::timeout(Duration::from_secs(5), chain.run(Context::new())).await.unwrap();
+    assert_eq!(ctx.get::<bool>("done"), Some(true));
+}
+
+#[tokio::test]
+async fn test_sequential_hooks_run_one_at_a_time() {
+    let chain = chain(MiddlewareExecution::Sequential);
+    assert!(tokio::time::timeout(Duration::from_millis(100), chain.run(Context::new())).await.is_err());
+}
+
+#[tokio::test]
+async fn test_sequential_is_the_default_and_keeps_order() {
+    let mut chain = Chain::new();
+    assert_eq!(chain.middleware_execution(), MiddlewareExecution::Sequential);
+    let order = Arc::new(Mutex::new(Vec::new()));
+    chain.use_middleware(Arc::new(Record("metrics", order.clone())));
+    chain.use_middleware(Arc::new(Record("audit", order.clone())));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx })));
+    chain.run(Context::new()).await;
+    assert_eq!(*order.lock().unwrap(), ["metrics", "audit"]);
+}
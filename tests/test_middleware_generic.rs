@@ -1,5 +1,7 @@
 //! Test middleware before/after hooks (generic pattern)
 
+#![allow(clippy::bool_assert_comparison)]
+
 use modulink_rs::context::ContextMutable;
 use modulink_rs::chains::ChainGeneric;
 use modulink_rs::links::LinkGeneric;
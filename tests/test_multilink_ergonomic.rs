@@ -1,5 +1,7 @@
 //! Test multiple links in a chain (ergonomic pattern)
 
+#![allow(clippy::type_complexity, clippy::let_and_return)]
+
 use modulink_rs::context::Context;
 use modulink_rs::chains::Chain;
 use std::sync::Arc;
@@ -0,0 +1,64 @@
+//! Test named links and name-based branching (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ValidationError};
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::Arc;
+
+fn mark(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn validate() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let valid = ctx.get::<u32>("qty").is_some_and(|qty| qty > 0);
+        ctx.insert("valid", valid)
+    }))
+}
+
+#[tokio::test]
+async fn test_connect_named_branches_by_name() {
+    let mut chain = Chain::new();
+    chain.add_named_link("validate", validate());
+    chain.add_named_link("charge", mark("charged"));
+    chain.add_named_link("handle_error", mark("rejected"));
+    chain.connect_named("validate", "handle_error", |ctx: &Context| ctx.get::<bool>("valid") == Some(false)).unwrap();
+
+    let ctx = chain.run(Context::new().insert("qty", 0)).await;
+    assert_eq!(ctx.get::<bool>("charged"), None);
+    assert_eq!(ctx.get::<bool>("rejected"), Some(true));
+    let ctx = chain.run(Context::new().insert("qty", 1)).await;
+    assert_eq!(ctx.get::<bool>("charged"), Some(true));
+}
+
+#[test]
+fn test_link_lookup_by_name() {
+    let mut chain = Chain::new();
+    chain.add_link(mark("a"));
+    chain.add_named_link("validate", validate());
+    chain.add_link_with(mark("b"), LinkSpec::new().name("charge"));
+    assert_eq!(chain.link_position("validate"), Some(1));
+    assert_eq!(chain.link_position("charge"), Some(2));
+    assert_eq!(chain.link_position("missing"), None);
+    assert_eq!(chain.link_name(1), Some("validate"));
+    assert_eq!(chain.link_name(0), None);
+    assert_eq!(chain.link_name(9), None);
+}
+
+#[test]
+fn test_connect_named_rejects_unknown_links() {
+    let mut chain = Chain::new();
+    chain.add_named_link("validate", validate());
+    let err = chain.connect_named("validate", "handle_error", |_: &Context| true).unwrap_err();
+    assert_eq!(err, ValidationError::UnknownLink("handle_error".to_string()));
+    assert_eq!(err.to_string(), "no link is named 'handle_error'");
+    assert!(chain.branches.is_empty());
+}
+
+#[test]
+fn test_validate_reports_duplicate_names() {
+    let mut chain = Chain::new();
+    chain.add_named_link("step", mark("a"));
+    chain.add_named_link("step", mark("b"));
+    assert_eq!(chain.validate(), Err(vec![ValidationError::DuplicateName("step".to_string())]));
+}
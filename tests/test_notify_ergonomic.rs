@@ -0,0 +1,146 @@
+//! Test notification links and context templates (ergonomic pattern)
+
+use modulink_rs::context::Context;
+use modulink_rs::std_links::template::render;
+use serde_json::json;
+
+#[test]
+fn test_templates_fill_from_context() {
+    let ctx = Context::new()
+        .insert("order_id", "o-17")
+        .insert("total", 12.5)
+        .insert("customer", json!({ "name": "Ada", "tags": ["vip"] }))
+        .insert("a.b", "dotted");
+
+    assert_eq!(render("Order {order_id}: {total}", &ctx).unwrap(), "Order o-17: 12.5");
+    assert_eq!(render("Hi {customer.name} ({customer.tags.0})", &ctx).unwrap(), "Hi Ada (vip)");
+    assert_eq!(render("{a.b}", &ctx).unwrap(), "dotted");
+    assert_eq!(render("{{literal}} {order_id}", &ctx).unwrap(), "{literal} o-17");
+    assert_eq!(render("no fields", &ctx).unwrap(), "no fields");
+
+    assert!(render("{missing}", &ctx).unwrap_err().contains("'missing'"));
+    assert!(render("{customer.age}", &ctx).is_err());
+    assert!(render("open {order_id", &ctx).unwrap_err().contains("unclosed"));
+    assert!(render("stray } brace", &ctx).unwrap_err().contains("unmatched"));
+}
+
+#[cfg(feature = "slack")]
+#[tokio::test]
+async fn test_notify_slack_posts_rendered_text() {
+    use axum::{extract::State, routing::post, Json, Router};
+    use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+    use modulink_rs::std_links::notify_slack;
+    use serde_json::Value;
+    use std::sync::{Arc, Mutex};
+
+    let received: Arc<Mutex<Vec<Value>>> = Arc::default();
+    let app = Router::new()
+        .route("/hook", post(|State(received): State<Arc<Mutex<Vec<Value>>>>, Json(body): Json<Value>| async move {
+            received.lock().unwrap().push(body);
+        }))
+        .route("/down", post(|| async { axum::http::StatusCode::SERVICE_UNAVAILABLE }))
+        .with_state(received.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut chain = Chain::new();
+    chain.add_link(notify_slack(&format!("http://{}/hook", addr), "order *{order_id}* failed"));
+    let (ctx, report) = chain.run_with_report(Context::new().insert("order_id", "o-17")).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<String>("order_id").as_deref(), Some("o-17"));
+    assert_eq!(received.lock().unwrap()[0], json!({ "text": "order *o-17* failed" }));
+
+    let report = chain.run_with_report(Context::new()).await.1;
+    assert!(matches!(report.status, RunStatus::Failed(err) if err.kind == ErrorKind::InvalidInput));
+
+    let mut down = Chain::new();
+    down.add_link(notify_slack(&format!("http://{}/down", addr), "hello"));
+    let report = down.run_with_report(Context::new()).await.1;
+    assert!(matches!(report.status, RunStatus::Failed(err) if err.kind == ErrorKind::Internal));
+}
+
+#[cfg(feature = "email")]
+mod email {
+    use super::*;
+    use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+    use modulink_rs::std_links::{notify_email, EmailNotification, Mailer};
+    use std::sync::Arc;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::net::TcpListener;
+
+    // A minimal SMTP server accepting one message; resolves to (recipients, message data).
+    async fn smtp_server() -> (u16, tokio::task::JoinHandle<(Vec<String>, String)>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (read, mut write) = stream.into_split();
+            let mut lines = BufReader::new(read).lines();
+            let (mut recipients, mut data) = (Vec::new(), String::new());
+            write.write_all(b"220 test ESMTP\r\n").await.unwrap();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250 test\r\n"
+                } else if command.starts_with("RCPT TO:") {
+                    recipients.push(line[8..].trim_matches(['<', '>', ' ']).to_string());
+                    b"250 OK\r\n"
+                } else if command == "DATA" {
+                    write.write_all(b"354 go on\r\n").await.unwrap();
+                    while let Some(line) = lines.next_line().await.unwrap() {
+                        if line == "." {
+                            break;
+                        }
+                        data.push_str(&line);
+                        data.push('\n');
+                    }
+                    b"250 queued\r\n"
+                } else if command == "QUIT" {
+                    write.write_all(b"221 bye\r\n").await.unwrap();
+                    break;
+                } else {
+                    b"250 OK\r\n"
+                };
+                write.write_all(reply).await.unwrap();
+            }
+            (recipients, data)
+        });
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn test_notify_email_sends_rendered_message() {
+        let (port, server) = smtp_server().await;
+        let mut chain = Chain::new();
+        chain.add_link(notify_email(
+            Arc::new(Mailer::unencrypted("127.0.0.1", port)),
+            EmailNotification::new("alerts@example.com", "{owner}", "Order {order_id} needs review", "Total: {total}")
+                .with_to("ops@example.com"),
+        ));
+        let ctx = Context::new().insert("owner", "ada@example.com").insert("order_id", "o-17").insert("total", 99);
+        let report = chain.run_with_report(ctx).await.1;
+        assert_eq!(report.status, RunStatus::Completed);
+
+        let (recipients, data) = server.await.unwrap();
+        assert_eq!(recipients, vec!["ada@example.com", "ops@example.com"]);
+        assert!(data.contains("Subject: Order o-17 needs review"));
+        assert!(data.contains("From: alerts@example.com"));
+        assert!(data.contains("Total: 99"));
+    }
+
+    #[tokio::test]
+    async fn test_notify_email_rejects_bad_input() {
+        let mailer = Arc::new(Mailer::unencrypted("127.0.0.1", 9));
+        let mut chain = Chain::new();
+        chain.add_link(notify_email(mailer, EmailNotification::new("alerts@example.com", "{owner}", "hi", "body")));
+
+        for ctx in [Context::new(), Context::new().insert("owner", "not an address")] {
+            let report = chain.run_with_report(ctx).await.1;
+            assert!(matches!(report.status, RunStatus::Failed(err) if err.kind == ErrorKind::InvalidInput));
+        }
+        // Nothing listens on port 9, so sending fails
+        let report = chain.run_with_report(Context::new().insert("owner", "ada@example.com")).await.1;
+        assert!(matches!(report.status, RunStatus::Failed(err) if err.kind == ErrorKind::Internal));
+    }
+}
@@ -0,0 +1,120 @@
+//! Test error-handling chains attached with on_error (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, RunError, RunStatus, Shadow};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn failed_kind(status: &RunStatus) -> Option<ErrorKind> {
+    match status {
+        RunStatus::Failed(err) => Some(err.kind),
+        _ => None,
+    }
+}
+
+// An error chain recording the error it was given into the context.
+fn recorder(calls: Arc<AtomicUsize>) -> Arc<Chain> {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(move |ctx: Context| {
+        let calls = calls.clone();
+        Box::pin(async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            let error = ctx_tools::last_error().unwrap();
+            let link = error.path.last().map(|step| step.link);
+            ctx.insert("error", error.message).insert("error_kind", format!("{:?}", error.kind)).insert("failed_link", link)
+        })
+    }));
+    Arc::new(chain)
+}
+
+fn step(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+#[tokio::test]
+async fn test_failed_run_goes_through_error_chain() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(step("reserved"));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::invalid_input("card declined"));
+        ctx.insert("charged", false)
+    })));
+    chain.add_link(step("shipped"));
+    chain.on_error(recorder(calls.clone()));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::InvalidInput));
+    assert_eq!(ctx.get::<bool>("reserved"), Some(true));
+    assert_eq!(ctx.get::<bool>("shipped"), None);
+    assert_eq!(ctx.get::<String>("error").as_deref(), Some("card declined"));
+    assert_eq!(ctx.get::<usize>("failed_link"), Some(1));
+    assert_eq!(report.children.len(), 1);
+    assert_eq!(report.children[0].status, RunStatus::Completed);
+
+    // Successful runs never reach it.
+    let mut ok = Chain::new();
+    ok.add_link(step("done"));
+    ok.on_error(recorder(calls.clone()));
+    ok.run(Context::new()).await;
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_panic_and_fallible_errors_are_routed() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(step("reserved"));
+    chain.add_link(Arc::new(|_ctx: Context| Box::pin(async move { panic!("ledger unreachable") })));
+    chain.on_error(recorder(calls.clone()));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::Panicked));
+    assert_eq!(ctx.get::<bool>("reserved"), Some(true));
+    assert_eq!(ctx.get::<String>("error").as_deref(), Some("ledger unreachable"));
+    assert_eq!(ctx.get::<String>("error_kind").as_deref(), Some("Panicked"));
+
+    let mut fallible = Chain::new();
+    fallible.add_fallible_link(Arc::new(|_ctx: Context| Box::pin(async move { Err(RunError::forbidden("no access")) })));
+    fallible.on_error(recorder(calls.clone()));
+    let result = fallible.try_run(Context::new()).await;
+    assert_eq!(result.unwrap_err().kind, ErrorKind::Forbidden);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_failing_error_chain_is_reported() {
+    let mut handler = Chain::new();
+    handler.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::internal("compensation failed"));
+        ctx
+    })));
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|_ctx: Context| Box::pin(async move { panic!("boom") })));
+    chain.on_error(Arc::new(handler));
+
+    let report = chain.run_with_report(Context::new()).await.1;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::Panicked));
+    assert!(report.warnings.iter().any(|w| w.contains("compensation failed")), "{:?}", report.warnings);
+    assert_eq!(failed_kind(&report.children[0].status), Some(ErrorKind::Internal));
+}
+
+#[tokio::test]
+async fn test_shadow_runs_skip_error_chain() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move {
+            ctx_tools::fail_run(RunError::internal("down"));
+            ctx
+        })),
+        LinkSpec::new().name("charge"),
+    );
+    chain.on_error(recorder(calls.clone()));
+    let shadow = Shadow::new();
+    let report = chain.run_shadow(Context::new(), &shadow).await.1;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::Internal));
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+}
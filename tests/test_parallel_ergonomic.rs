@@ -0,0 +1,90 @@
+//! Test parallel link groups (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::combinators::merge_changes;
+use modulink_rs::links::Link;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+// A link that only finishes once all `of` links sharing `entered` have started.
+fn rendezvous(entered: Arc<AtomicUsize>, of: usize, key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| {
+        let entered = entered.clone();
+        Box::pin(async move {
+            entered.fetch_add(1, Ordering::SeqCst);
+            while entered.load(Ordering::SeqCst) < of {
+                tokio::task::yield_now().await;
+            }
+            ctx.insert(key, true)
+        })
+    })
+}
+
+fn set(key: &'static str, value: i32) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, value) }))
+}
+
+#[tokio::test]
+async fn test_parallel_links_run_concurrently() {
+    let entered = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.add_parallel(
+        vec![rendezvous(entered.clone(), 3, "profile"), rendezvous(entered.clone(), 3, "orders"), rendezvous(entered, 3, "risk")],
+        merge_changes,
+    );
+    chain.add_link(set("after", 1));
+
+    let ctx = tokio::time::timeout(Duration::from_secs(5), chain.run(Context::new())).await.unwrap();
+    for key in ["profile", "orders", "risk"] {
+        assert_eq!(ctx.get::<bool>(key), Some(true), "{}", key);
+    }
+    assert_eq!(ctx.get::<i32>("after"), Some(1));
+    assert_eq!(chain.link_count(), 2);
+}
+
+#[tokio::test]
+async fn test_merge_changes_applies_changes_in_link_order() {
+    let remove: Link = Arc::new(|ctx: Context| Box::pin(async move {
+        let mut ctx = ctx;
+        ctx.0.remove("stale");
+        ctx
+    }));
+    let mut chain = Chain::new();
+    chain.add_parallel(vec![set("score", 1), remove, set("score", 2), set("extra", 3)], merge_changes);
+
+    let ctx = chain.run(Context::new().insert("stale", true).insert("kept", "yes").insert("score", 0)).await;
+    assert_eq!(ctx.get::<i32>("score"), Some(2));
+    assert_eq!(ctx.get::<i32>("extra"), Some(3));
+    assert_eq!(ctx.get::<String>("kept").as_deref(), Some("yes"));
+    assert_eq!(ctx.get::<bool>("stale"), None);
+}
+
+#[tokio::test]
+async fn test_custom_merge_sees_original_and_results() {
+    let mut chain = Chain::new();
+    chain.add_parallel(vec![set("n", 1), set("n", 2), set("n", 3)], |original: Context, results: Vec<Context>| {
+        let total: i32 = results.iter().filter_map(|ctx| ctx.get::<i32>("n")).sum();
+        original.insert("total", total)
+    });
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<i32>("total"), Some(6));
+    assert_eq!(ctx.get::<i32>("n"), None);
+}
+
+#[tokio::test]
+async fn test_failing_parallel_link_fails_the_run() {
+    let fail: Link = Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::internal("risk service down"));
+        ctx
+    }));
+    let mut chain = Chain::new();
+    chain.add_parallel(vec![set("a", 1), fail], merge_changes);
+    chain.add_link(set("after", 1));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref err) if err.message == "risk service down"));
+    assert_eq!(ctx.get::<i32>("after"), None);
+}
@@ -0,0 +1,61 @@
+//! Test PII detection and masking (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::std_links::{mask_pii, Email, PatternDetector, PiiFinding, PiiScan};
+use serde_json::{json, Value};
+use std::sync::Arc;
+
+fn finding(path: &str, detector: &str) -> PiiFinding {
+    PiiFinding { path: path.to_string(), detector: detector.to_string() }
+}
+
+fn customer() -> Context {
+    Context::new()
+        .insert("order_id", "o-4111")
+        .insert("note", "call +1 (415) 555-0132 or mail ana@example.com")
+        .insert("customer", json!({ "name": "Ana", "cards": ["4111 1111 1111 1111", "1234 5678 9012 3456"] }))
+        .insert("total", 42)
+}
+
+async fn scan(scan: PiiScan, ctx: Context) -> Context {
+    let mut chain = Chain::new();
+    chain.add_link(mask_pii(scan));
+    chain.run(ctx).await
+}
+
+#[tokio::test]
+async fn test_masks_whole_context() {
+    let ctx = scan(PiiScan::new(), customer()).await;
+    assert_eq!(ctx.get::<String>("note").as_deref(), Some("call ***************** or mail ***************"));
+    // Only numbers passing the Luhn check are card numbers.
+    assert_eq!(ctx.get::<Value>("customer").unwrap(), json!({ "name": "Ana", "cards": ["*******************", "1234 5678 9012 3456"] }));
+    assert_eq!(ctx.get::<String>("order_id").as_deref(), Some("o-4111"));
+    assert_eq!(ctx.get::<u32>("total"), Some(42));
+    assert_eq!(
+        ctx.get::<Vec<PiiFinding>>("pii").unwrap(),
+        vec![finding("customer.cards.0", "credit_card"), finding("note", "email"), finding("note", "phone")]
+    );
+}
+
+#[tokio::test]
+async fn test_tag_only_and_selected_keys() {
+    let ctx = scan(PiiScan::new().keys(["note"]).tag_only().into_key("flags"), customer()).await;
+    assert_eq!(ctx.get::<String>("note").unwrap(), customer().get::<String>("note").unwrap());
+    assert_eq!(ctx.get::<Vec<PiiFinding>>("flags").unwrap(), vec![finding("note", "email"), finding("note", "phone")]);
+
+    let clean = scan(PiiScan::new(), Context::new().insert("note", "nothing here, order 2024-01-15")).await;
+    assert_eq!(clean.get::<Vec<PiiFinding>>("pii").unwrap(), vec![]);
+}
+
+#[tokio::test]
+async fn test_custom_detectors() {
+    let iban = PatternDetector::new("iban", r"\b[A-Z]{2}\d{2}(?: ?[A-Z0-9]{1,4}){3,8}\b").unwrap();
+    let ctx = scan(
+        PiiScan::new().with_detectors(vec![Arc::new(Email), Arc::new(iban)]),
+        Context::new().insert("memo", "pay DE89 3704 0044 0532 0130 00, ref +1 415 555 0132"),
+    )
+    .await;
+    assert_eq!(ctx.get::<String>("memo").as_deref(), Some("pay ***************************, ref +1 415 555 0132"));
+    assert_eq!(ctx.get::<Vec<PiiFinding>>("pii").unwrap(), vec![finding("memo", "iban")]);
+}
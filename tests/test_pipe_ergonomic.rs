@@ -0,0 +1,80 @@
+//! Test listener → chain → sink pipes built from specs (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::listeners::KafkaConsumer;
+use modulink_rs::pipe::{self, Connectors, PipeError, SinkSpec, SourceSpec};
+use modulink_rs::registry;
+use std::sync::{Arc, Mutex};
+
+fn enrich_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let id = ctx.get::<i64>("id").unwrap_or(0);
+        ctx.insert("enriched", id * 10)
+    }))
+}
+
+struct QueueConsumer {
+    messages: Mutex<Vec<Vec<u8>>>,
+}
+
+#[async_trait]
+impl KafkaConsumer for QueueConsumer {
+    async fn recv(&self, topic: &str) -> std::io::Result<Option<Vec<u8>>> {
+        assert_eq!(topic, "orders");
+        let mut messages = self.messages.lock().unwrap();
+        Ok(if messages.is_empty() { None } else { Some(messages.remove(0)) })
+    }
+}
+
+#[test]
+fn test_spec_parsing() {
+    assert_eq!("stdin".parse::<SourceSpec>().unwrap(), SourceSpec::Stdin);
+    assert_eq!("http:0.0.0.0:8080".parse::<SourceSpec>().unwrap(), SourceSpec::Http("0.0.0.0:8080".to_string()));
+    assert_eq!("file:/tmp/out.jsonl".parse::<SinkSpec>().unwrap(), SinkSpec::File("/tmp/out.jsonl".into()));
+    assert!(matches!("kafka:".parse::<SourceSpec>(), Err(PipeError::InvalidSpec(_))));
+    assert!(matches!("ftp:host".parse::<SinkSpec>(), Err(PipeError::InvalidSpec(_))));
+}
+
+#[tokio::test]
+async fn test_kafka_to_file_pipe() {
+    let mut chain = Chain::new();
+    chain.add_link(enrich_link());
+    registry::register_chain("pipe_enrich", Arc::new(chain));
+
+    let consumer = QueueConsumer {
+        messages: Mutex::new(vec![
+            br#"{"id": 1}"#.to_vec(),
+            b"not json".to_vec(),
+            br#"{"id": 2}"#.to_vec(),
+        ]),
+    };
+    let connectors = Connectors { kafka_consumer: Some(Arc::new(consumer)), ..Default::default() };
+    let dir = tempfile::tempdir().unwrap();
+    let out = dir.path().join("out.jsonl");
+
+    pipe::run_pipe("kafka:orders", "pipe_enrich", &format!("file:{}", out.display()), &connectors)
+        .await
+        .unwrap();
+
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&out)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["enriched"], 20);
+}
+
+#[tokio::test]
+async fn test_pipe_reports_missing_pieces() {
+    let connectors = Connectors::default();
+    let err = pipe::run_pipe("stdin", "no_such_chain", "stdout", &connectors).await.unwrap_err();
+    assert!(matches!(err, PipeError::UnknownChain(name) if name == "no_such_chain"));
+
+    registry::register_chain("pipe_noop", Arc::new(Chain::new()));
+    let err = pipe::run_pipe("kafka:orders", "pipe_noop", "stdout", &connectors).await.unwrap_err();
+    assert!(matches!(err, PipeError::MissingConnector("Kafka consumer")));
+}
@@ -0,0 +1,67 @@
+//! Test RBAC policy middleware and guarded links (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::links::Link;
+use modulink_rs::policy::{guard_link, PolicyMiddleware, PolicyObj, RolePolicy};
+use serde_json::json;
+use std::sync::Arc;
+
+fn mark_link(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn refunds_chain(policy: PolicyObj) -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(mark_link("looked_up"));
+    chain.add_link(guard_link(policy.clone(), "refunds", "issue_refund", mark_link("refunded")));
+    chain.add_link(mark_link("notified"));
+    chain.use_middleware(Arc::new(PolicyMiddleware::new(policy, "refunds")));
+    chain
+}
+
+fn policy() -> PolicyObj {
+    Arc::new(
+        RolePolicy::new()
+            .allow_chain("support", "refunds")
+            .allow_chain("finance", "*")
+            .allow_link("finance", "refunds", "issue_refund"),
+    )
+}
+
+#[tokio::test]
+async fn test_role_policy_grants_chain_and_link() {
+    let chain = refunds_chain(policy());
+    let ctx = Context::new().insert(meta::AUTH, json!({"sub": "bo", "roles": "finance"}));
+    let (ctx, report) = chain.run_with_report(ctx).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("refunded"), Some(true));
+    assert_eq!(ctx.get::<bool>("notified"), Some(true));
+}
+
+#[tokio::test]
+async fn test_role_policy_denials_are_structured() {
+    let chain = refunds_chain(policy());
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref e) if e.kind == ErrorKind::Unauthorized));
+    assert_eq!(ctx.get::<bool>("looked_up"), None);
+
+    // Support may run the chain but not the guarded refund link
+    let ctx = Context::new().insert(meta::AUTH, json!({"sub": "ada", "roles": ["support"]}));
+    let (ctx, report) = chain.run_with_report(ctx).await;
+    match report.status {
+        RunStatus::Failed(err) => {
+            assert_eq!(err.kind, ErrorKind::Forbidden);
+            assert!(err.message.contains("issue_refund"));
+        }
+        other => panic!("expected Forbidden, got {:?}", other),
+    }
+    assert_eq!(ctx.get::<bool>("looked_up"), Some(true));
+    assert_eq!(ctx.get::<bool>("refunded"), None);
+    assert_eq!(ctx.get::<bool>("notified"), None);
+
+    let ctx = Context::new().insert(meta::AUTH, json!({"sub": "eve", "roles": ["guest"]}));
+    let (_, report) = chain.run_with_report(ctx).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref e) if e.kind == ErrorKind::Forbidden));
+}
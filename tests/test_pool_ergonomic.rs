@@ -0,0 +1,72 @@
+//! Test reuse of per-run state across runs (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ResourceLimits, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use std::sync::Arc;
+
+fn chain() -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        if ctx.get::<bool>("fail") == Some(true) {
+            ctx_tools::fail_run(RunError::invalid_input("asked to fail"));
+        }
+        ctx_tools::warn("checked");
+        ctx_tools::annotate("tenant", "t1");
+        ctx.insert("done", true)
+    })));
+    chain
+}
+
+#[tokio::test]
+async fn test_sequential_runs_reuse_run_state() {
+    let chain = chain();
+    for _ in 0..5 {
+        chain.run(Context::new()).await;
+    }
+    let stats = chain.pool_stats();
+    assert_eq!((stats.allocated, stats.reused, stats.idle), (1, 4, 1));
+}
+
+#[tokio::test]
+async fn test_reused_state_starts_clean() {
+    let chain = chain();
+    let (_, failed) = chain.run_with_report(Context::new().insert("fail", true)).await;
+    assert!(matches!(failed.status, RunStatus::Failed(_)));
+
+    let (_, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(chain.pool_stats().reused, 1);
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(report.steps.len(), 1);
+    assert_eq!(report.warnings, vec!["checked".to_string()]);
+    assert_eq!(report.annotations.len(), 1);
+}
+
+#[tokio::test]
+async fn test_concurrent_runs_and_new_limits() {
+    let mut chain = chain();
+    chain.set_limits(ResourceLimits::new().with_max_children(2));
+    let chain = Arc::new(chain);
+    let runs: Vec<_> = (0..4).map(|_| tokio::spawn({
+        let chain = chain.clone();
+        async move { chain.run(Context::new()).await }
+    })).collect();
+    for run in runs {
+        assert_eq!(run.await.unwrap().get::<bool>("done"), Some(true));
+    }
+    let stats = chain.pool_stats();
+    assert_eq!(stats.allocated + stats.reused, 4);
+    assert!(stats.idle >= 1 && stats.idle as u64 <= stats.allocated);
+}
+
+#[tokio::test]
+async fn test_pool_capacity_zero_disables_reuse() {
+    let mut chain = chain();
+    chain.set_pool_capacity(0);
+    for _ in 0..3 {
+        let (_, report) = chain.run_with_report(Context::new()).await;
+        assert_eq!(report.warnings, vec!["checked".to_string()]);
+    }
+    let stats = chain.pool_stats();
+    assert_eq!((stats.allocated, stats.reused, stats.idle), (3, 0, 0));
+}
@@ -0,0 +1,97 @@
+//! Test noise and generalization for analytics sinks (ergonomic pattern)
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::sinks::{ChannelSink, FieldRule, PrivacyPolicy, PrivacySink};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+fn regions() -> FieldRule {
+    let groups = BTreeMap::from([("lisbon".to_string(), "EU".to_string()), ("porto".to_string(), "EU".to_string())]);
+    FieldRule::Generalize { groups, other: "rest".to_string() }
+}
+
+#[test]
+fn test_rules_on_nested_fields_and_arrays() {
+    let policy = PrivacyPolicy::new()
+        .field("user.age", FieldRule::Bucket { step: 10.0 })
+        .field("user.city", regions())
+        .field("user.email", FieldRule::Suppress)
+        .field("orders.card", FieldRule::Prefix { keep: 4 })
+        .field("scores", FieldRule::Bucket { step: 0.5 });
+    let ctx = Context::new()
+        .insert("user", json!({ "age": 42, "city": "porto", "email": "ana@example.com" }))
+        .insert("orders", json!([{ "card": "4111111111111111" }, { "card": "5500" }]))
+        .insert("scores", [0.2, 0.7, 1.9])
+        .insert("untouched", "kept");
+
+    let private = policy.apply(&ctx);
+    assert_eq!(private.get::<serde_json::Value>("user"), Some(json!({ "age": 40, "city": "EU" })));
+    assert_eq!(private.get::<serde_json::Value>("orders"), Some(json!([{ "card": "4111************" }, { "card": "5500" }])));
+    assert_eq!(private.get::<Vec<f64>>("scores"), Some(vec![0.0, 0.5, 1.5]));
+    assert_eq!(private.get::<String>("untouched").as_deref(), Some("kept"));
+    // The original is left alone
+    assert_eq!(ctx.get::<serde_json::Value>("user").unwrap()["age"], 42);
+
+    let other = policy.apply(&Context::new().insert("user", json!({ "city": "oslo", "age": "forty" })));
+    assert_eq!(other.get::<serde_json::Value>("user"), Some(json!({ "city": "rest", "age": null })));
+}
+
+#[test]
+fn test_laplace_noise() {
+    let policy = PrivacyPolicy::new().field("visits", FieldRule::Laplace { epsilon: 0.5, sensitivity: 1.0 }).with_seed(7);
+    let same = PrivacyPolicy::new().field("visits", FieldRule::Laplace { epsilon: 0.5, sensitivity: 1.0 }).with_seed(7);
+    let ctx = Context::new().insert("visits", 1000);
+
+    let samples: Vec<i64> = (0..2000).map(|_| policy.apply(&ctx).get::<i64>("visits").unwrap()).collect();
+    assert_eq!(same.apply(&ctx).get::<i64>("visits"), Some(samples[0]));
+    assert!(samples.iter().any(|&v| v != 1000));
+    // Scale 2: mean absolute deviation 2, centered on the true value
+    let mean = samples.iter().sum::<i64>() as f64 / samples.len() as f64;
+    let deviation = samples.iter().map(|&v| (v - 1000).abs()).sum::<i64>() as f64 / samples.len() as f64;
+    assert!((mean - 1000.0).abs() < 0.5, "mean {}", mean);
+    assert!((1.5..2.5).contains(&deviation), "deviation {}", deviation);
+
+    let float = PrivacyPolicy::new().field("avg", FieldRule::Laplace { epsilon: 1.0, sensitivity: 0.1 });
+    let noisy = float.apply(&Context::new().insert("avg", 3.25)).get::<f64>("avg").unwrap();
+    assert!(noisy != 3.25 && (noisy - 3.25).abs() < 5.0);
+}
+
+#[tokio::test]
+async fn test_privacy_sink_leaves_the_run_result_exact() {
+    let (tx, mut rx) = mpsc::channel(4);
+    let policy = PrivacyPolicy::new().field("email", FieldRule::Suppress).field("age", FieldRule::Bucket { step: 10.0 });
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("age", 37) })));
+    chain.pipe_to(Arc::new(PrivacySink::new(policy, Arc::new(ChannelSink::new(tx)))));
+
+    let ctx = chain.run(Context::new().insert("email", "ana@example.com")).await;
+    assert_eq!(ctx.get::<i64>("age"), Some(37));
+    assert_eq!(ctx.get::<String>("email").as_deref(), Some("ana@example.com"));
+    let delivered = rx.next().await.unwrap();
+    assert_eq!(delivered.get::<i64>("age"), Some(30));
+    assert_eq!(delivered.get::<String>("email"), None);
+}
+
+#[test]
+fn test_rules_deserialize() {
+    let rule: FieldRule = serde_json::from_value(json!({ "rule": "generalize", "groups": { "porto": "EU" } })).unwrap();
+    assert_eq!(rule, FieldRule::Generalize { groups: BTreeMap::from([("porto".into(), "EU".into())]), other: "other".into() });
+    let rule: FieldRule = serde_json::from_value(json!({ "rule": "laplace", "epsilon": 0.1, "sensitivity": 1.0 })).unwrap();
+    assert_eq!(rule, FieldRule::Laplace { epsilon: 0.1, sensitivity: 1.0 });
+}
+
+#[test]
+fn test_invalid_rules_are_rejected() {
+    for (epsilon, sensitivity) in [(0.0, 1.0), (-1.0, 1.0), (f64::NAN, 1.0), (f64::INFINITY, 1.0), (1.0, 0.0), (1.0, -2.0)] {
+        let rule = FieldRule::Laplace { epsilon, sensitivity };
+        assert!(rule.validate().is_err(), "{:?}", rule);
+        let built = std::panic::catch_unwind(|| PrivacyPolicy::new().field("spend", rule));
+        assert!(built.is_err(), "epsilon {} sensitivity {}", epsilon, sensitivity);
+    }
+    assert!(FieldRule::Bucket { step: 0.0 }.validate().is_err());
+    assert!(FieldRule::Laplace { epsilon: 0.1, sensitivity: 1.0 }.validate().is_ok());
+}
@@ -0,0 +1,100 @@
+//! Test Kafka/NATS producer links (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::sinks::KafkaProducer;
+use modulink_rs::std_links::{kafka_produce, nats_publish, NatsPublisher, Payload, Publish};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+
+type Sent = (String, Option<String>, Value);
+
+#[derive(Default)]
+struct Broker {
+    sent: Mutex<Vec<Sent>>,
+    down: bool,
+}
+
+impl Broker {
+    fn record(&self, to: &str, key: Option<&str>, payload: &[u8]) -> std::io::Result<()> {
+        if self.down {
+            return Err(std::io::Error::other("broker unreachable"));
+        }
+        self.sent.lock().unwrap().push((to.to_string(), key.map(str::to_string), serde_json::from_slice(payload).unwrap()));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KafkaProducer for Broker {
+    async fn send(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> std::io::Result<()> {
+        self.record(topic, key, &payload)
+    }
+}
+
+#[async_trait]
+impl NatsPublisher for Broker {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> std::io::Result<()> {
+        self.record(subject, None, &payload)
+    }
+}
+
+fn order() -> Context {
+    Context::new().insert("order_id", "o-17").insert("region", "eu").insert("total", 42).insert("internal", "secret")
+}
+
+#[tokio::test]
+async fn test_kafka_produce_mid_chain() {
+    let broker = Arc::new(Broker::default());
+    let mut chain = Chain::new();
+    chain.add_link(kafka_produce(
+        broker.clone(),
+        Publish::to("orders-{region}").with_key("{order_id}").with_payload(Payload::Keys(vec!["order_id".into(), "total".into()])),
+    ));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("after", true) })));
+
+    let ctx = chain.run(order()).await;
+    assert_eq!(ctx.get::<bool>("after"), Some(true));
+    assert_eq!(ctx.get::<String>("internal").as_deref(), Some("secret"));
+    let sent = broker.sent.lock().unwrap();
+    assert_eq!(sent[0], ("orders-eu".to_string(), Some("o-17".to_string()), json!({ "order_id": "o-17", "total": 42 })));
+}
+
+#[tokio::test]
+async fn test_nats_publish_payloads() {
+    let broker = Arc::new(Broker::default());
+    let mut chain = Chain::new();
+    chain.add_link(nats_publish(broker.clone(), Publish::to("orders.{region}.created")));
+    chain.add_link(nats_publish(broker.clone(), Publish::to("totals").with_payload(Payload::Key("total".into()))));
+    chain.run(order()).await;
+
+    let sent = broker.sent.lock().unwrap();
+    assert_eq!(sent[0].0, "orders.eu.created");
+    assert_eq!(sent[0].1, None);
+    assert_eq!(sent[0].2["internal"], "secret");
+    assert_eq!(sent[1], ("totals".to_string(), None, json!(42)));
+}
+
+#[tokio::test]
+async fn test_publish_failures_fail_the_run() {
+    let failed_kind = |status: RunStatus| match status {
+        RunStatus::Failed(err) => Some(err.kind),
+        _ => None,
+    };
+    let broker = Arc::new(Broker::default());
+    let mut chain = Chain::new();
+    chain.add_link(nats_publish(broker.clone(), Publish::to("orders.{region}").with_payload(Payload::Key("total".into()))));
+
+    let report = chain.run_with_report(Context::new().insert("total", 1)).await.1;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+    let report = chain.run_with_report(Context::new().insert("region", "eu")).await.1;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::InvalidInput));
+    assert!(broker.sent.lock().unwrap().is_empty());
+
+    let down = Arc::new(Broker { down: true, ..Broker::default() });
+    let mut chain = Chain::new();
+    chain.add_link(kafka_produce(down, Publish::to("orders")));
+    let report = chain.run_with_report(order()).await.1;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::Internal));
+}
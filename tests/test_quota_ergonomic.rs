@@ -0,0 +1,192 @@
+//! Test per-key quotas (ergonomic pattern)
+
+use modulink_rs::admin::Admin;
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::quota::{QuotaKey, QuotaLimits, QuotaMiddleware, Quotas};
+use serde_json::json;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+fn as_key(sub: &str) -> Context {
+    Context::new().insert(meta::AUTH, json!({ "sub": sub }))
+}
+
+fn chain_with(middleware: QuotaMiddleware) -> Chain {
+    let mut chain = Chain::new();
+    chain.use_middleware(Arc::new(middleware));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("done", true) })));
+    chain
+}
+
+fn failed_kind(status: &RunStatus) -> Option<ErrorKind> {
+    match status {
+        RunStatus::Failed(err) => Some(err.kind),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn test_requests_per_day_are_enforced_per_key() {
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(2)));
+    let chain = chain_with(QuotaMiddleware::new(quotas.clone()));
+
+    for _ in 0..2 {
+        assert_eq!(chain.run_with_report(as_key("alice")).await.1.status, RunStatus::Completed);
+    }
+    let (ctx, report) = chain.run_with_report(as_key("alice")).await;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::QuotaExceeded));
+    assert_eq!(ctx.get::<bool>("done"), None);
+    assert_eq!(ErrorKind::QuotaExceeded.http_status(), 429);
+
+    // Other keys have their own budget
+    assert_eq!(chain.run_with_report(as_key("bob")).await.1.status, RunStatus::Completed);
+    assert_eq!(quotas.usage("alice").requests, 2);
+    assert_eq!(quotas.usage("bob").requests, 1);
+}
+
+#[tokio::test]
+async fn test_per_key_limits_override_default() {
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(1)));
+    quotas.set_limits("paid", QuotaLimits::new());
+    let chain = chain_with(QuotaMiddleware::new(quotas.clone()));
+
+    for _ in 0..5 {
+        assert_eq!(chain.run_with_report(as_key("paid")).await.1.status, RunStatus::Completed);
+    }
+    assert_eq!(quotas.usage("paid").limits, QuotaLimits::new());
+}
+
+#[tokio::test]
+async fn test_compute_budget_counts_run_time() {
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_compute_per_day(Duration::from_millis(15))));
+    let mut chain = Chain::new();
+    chain.use_middleware(Arc::new(QuotaMiddleware::new(quotas.clone())));
+    chain.add_link(Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            ctx
+        })
+    }));
+
+    assert_eq!(chain.run_with_report(as_key("alice")).await.1.status, RunStatus::Completed);
+    assert!(quotas.usage("alice").compute >= Duration::from_millis(20));
+    let report = chain.run_with_report(as_key("alice")).await.1;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::QuotaExceeded));
+    // The rejected run's time is not counted
+    assert!(quotas.usage("alice").compute < Duration::from_millis(40));
+}
+
+#[tokio::test]
+async fn test_compute_is_charged_to_the_admitted_key() {
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new()));
+    let mut chain = Chain::new();
+    chain.use_middleware(Arc::new(QuotaMiddleware::new(quotas.clone())));
+    chain.add_link(Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+            ctx.insert(meta::AUTH, json!({ "sub": "bob" }))
+        })
+    }));
+
+    let (_, report) = chain.run_with_report(as_key("alice")).await;
+    assert_eq!(report.annotations.get(modulink_rs::quota::QUOTA_KEY_ANNOTATION), Some(&json!("alice")));
+    assert!(quotas.usage("alice").compute >= Duration::from_millis(10));
+    assert_eq!(quotas.usage("bob").compute, Duration::ZERO);
+}
+
+#[tokio::test]
+async fn test_runs_without_key() {
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(1)));
+    let chain = chain_with(QuotaMiddleware::new(quotas.clone()));
+    for _ in 0..3 {
+        assert_eq!(chain.run_with_report(Context::new()).await.1.status, RunStatus::Completed);
+    }
+    assert!(quotas.all_usage().is_empty());
+
+    let strict = chain_with(QuotaMiddleware::new(quotas).require_key());
+    let report = strict.run_with_report(Context::new()).await.1;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::Unauthorized));
+}
+
+#[tokio::test]
+async fn test_key_from_tenant() {
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(1)));
+    let chain = chain_with(QuotaMiddleware::new(quotas.clone()).with_key(QuotaKey::ContextKey(meta::TENANT.to_string())));
+
+    assert_eq!(chain.run_with_report(Context::new().with_tenant("acme")).await.1.status, RunStatus::Completed);
+    let report = chain.run_with_report(Context::new().with_tenant("acme")).await.1;
+    assert_eq!(failed_kind(&report.status), Some(ErrorKind::QuotaExceeded));
+    assert_eq!(quotas.usage("acme").requests, 1);
+}
+
+#[test]
+fn test_usage_resets_each_day_and_on_demand() {
+    let quotas = Quotas::new(QuotaLimits::new().with_requests_per_day(1));
+    let day = UNIX_EPOCH + Duration::from_secs(19_800 * 86_400 + 3_600);
+    let next_day = day + Duration::from_secs(86_400);
+
+    assert!(quotas.acquire_at("alice", day).is_ok());
+    assert!(quotas.acquire_at("alice", day + Duration::from_secs(60)).is_err());
+    assert_eq!(quotas.usage_at("alice", day).day, 19_800);
+    assert!(quotas.acquire_at("alice", next_day).is_ok());
+    assert_eq!(quotas.usage_at("alice", next_day).requests, 1);
+
+    assert!(quotas.acquire("bob").is_ok());
+    assert!(quotas.acquire("bob").is_err());
+    assert!(quotas.reset("bob"));
+    assert!(!quotas.reset("carol"));
+    assert!(quotas.acquire("bob").is_ok());
+    assert!(SystemTime::now() > next_day);
+}
+
+#[tokio::test]
+async fn test_admin_inspects_and_resets_quotas() {
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(1)));
+    let admin = Admin::new();
+    assert!(admin.quota_usage().is_err());
+    admin.manage_quotas(quotas.clone());
+
+    let chain = chain_with(QuotaMiddleware::new(quotas.clone()));
+    chain.run(as_key("alice")).await;
+    let usage = admin.quota_usage().unwrap();
+    assert_eq!(usage.len(), 1);
+    assert_eq!((usage[0].key.as_str(), usage[0].requests), ("alice", 1));
+
+    assert!(admin.reset_quota("alice").unwrap());
+    assert_eq!(admin.quota("alice").unwrap().requests, 0);
+    assert_eq!(chain.run_with_report(as_key("alice")).await.1.status, RunStatus::Completed);
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_quota_routes_and_listener_status() {
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+    use serde_json::Value;
+
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(1)));
+    // The listener has no JWT validator here, so the key comes from the body
+    let middleware = QuotaMiddleware::new(quotas.clone()).with_key(QuotaKey::ContextKey("api_key".to_string()));
+    let chain = Arc::new(chain_with(middleware));
+    let admin = Arc::new(Admin::new());
+    admin.manage_quotas(quotas);
+    let listener = HttpListener::for_chain(chain, "127.0.0.1:8101").with_admin(admin);
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let base = "http://127.0.0.1:8101";
+    let run = || client.post(format!("{}/run", base)).json(&json!({ "api_key": "acme" })).send();
+    assert_eq!(run().await.unwrap().status(), 200);
+    assert_eq!(run().await.unwrap().status(), 429);
+
+    let usage: Value = client.get(format!("{}/admin/quotas/acme", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(usage["requests"], 1);
+    assert_eq!(usage["limits"]["requests_per_day"], 1);
+    let all: Value = client.get(format!("{}/admin/quotas", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(all[0]["key"], "acme");
+
+    let reset: Value = client.post(format!("{}/admin/quotas/acme/reset", base)).send().await.unwrap().json().await.unwrap();
+    assert_eq!(reset["reset"], true);
+    assert_eq!(run().await.unwrap().status(), 200);
+}
@@ -0,0 +1,89 @@
+//! Test chain and link rate limits (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, RateLimit, RateLimiter, RunStatus};
+use modulink_rs::context::Context;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn failed_kind(status: RunStatus) -> Option<ErrorKind> {
+    match status {
+        RunStatus::Failed(err) => Some(err.kind),
+        _ => None,
+    }
+}
+
+fn counter() -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        let n = ctx.get::<u32>("n").unwrap_or(0);
+        ctx.insert("n", n + 1)
+    })));
+    chain
+}
+
+#[test]
+fn test_token_bucket() {
+    let limiter = RateLimiter::per_second(10.0).with_burst(2);
+    assert!(limiter.try_acquire().is_ok());
+    assert!(limiter.try_acquire().is_ok());
+    let wait = limiter.try_acquire().unwrap_err();
+    assert!(wait > Duration::from_millis(50) && wait <= Duration::from_millis(100), "{:?}", wait);
+    // Reservations queue up behind each other.
+    let first = limiter.reserve();
+    let second = limiter.reserve();
+    assert!(second > first, "{:?} then {:?}", first, second);
+}
+
+#[tokio::test]
+async fn test_chain_rate_limit_rejects() {
+    let limiter = Arc::new(RateLimiter::per_second(20.0).with_burst(2));
+    let mut chain = counter();
+    chain.set_rate_limit(RateLimit::reject(limiter.clone()));
+    // A second chain on the same limiter shares its budget.
+    let mut other = counter();
+    other.set_rate_limit(RateLimit::reject(limiter));
+
+    assert_eq!(chain.run_with_report(Context::new()).await.1.status, RunStatus::Completed);
+    assert_eq!(other.run_with_report(Context::new()).await.1.status, RunStatus::Completed);
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(failed_kind(report.status), Some(ErrorKind::RateLimited));
+    assert_eq!(ctx.get::<u32>("n"), None);
+    assert_eq!(ErrorKind::RateLimited.http_status(), 429);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    assert_eq!(chain.run_with_report(Context::new()).await.1.status, RunStatus::Completed);
+}
+
+#[tokio::test]
+async fn test_chain_rate_limit_waits() {
+    let mut chain = counter();
+    chain.set_rate_limit(RateLimit::wait(Arc::new(RateLimiter::per_second(20.0).with_burst(1))));
+    let chain = Arc::new(chain);
+
+    let started = Instant::now();
+    let runs = (0..4).map(|_| {
+        let chain = chain.clone();
+        tokio::spawn(async move { chain.run_with_report(Context::new()).await.1.status })
+    });
+    for status in futures::future::join_all(runs).await {
+        assert_eq!(status.unwrap(), RunStatus::Completed);
+    }
+    // One permit right away, then one every 50ms.
+    assert!(started.elapsed() >= Duration::from_millis(140), "{:?}", started.elapsed());
+}
+
+#[tokio::test]
+async fn test_link_rate_limit() {
+    let mut chain = counter();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("after", true) })));
+    chain.connect(0, 0, |ctx: &Context| ctx.get::<u32>("n").unwrap_or(0) < 3);
+    chain.rate_limit_link(0, RateLimit::reject(Arc::new(RateLimiter::per_second(1.0).with_burst(2))));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(failed_kind(report.status.clone()), Some(ErrorKind::RateLimited));
+    assert_eq!(ctx.get::<u32>("n"), Some(2));
+    match report.status {
+        RunStatus::Failed(err) => assert!(err.message.starts_with("link 0 is over its rate limit"), "{}", err.message),
+        other => panic!("unexpected {:?}", other),
+    }
+}
@@ -0,0 +1,90 @@
+//! Test the namespaced registry and self-registration (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::registry::{self, namespace_of, LinkMetadata, Registry};
+use std::sync::Arc;
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+modulink_rs::register_link!("reg_test::enrich", Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("enriched", true) })));
+modulink_rs::register_link!("reg_test::old", noop(), LinkMetadata::new().deprecated("use reg_test::enrich").provides(["enriched"]));
+modulink_rs::register_chain!("reg_test::pipeline", {
+    // Self-registered chains are built on first lookup, so they can use registered links.
+    let mut chain = Chain::new();
+    chain.add_link(registry::get_link("reg_test::enrich").unwrap());
+    chain
+});
+
+#[tokio::test]
+async fn test_self_registered_links_and_chains() {
+    assert!(registry::link_names().contains(&"reg_test::enrich".to_string()));
+    assert!(registry::chain_names().contains(&"reg_test::pipeline".to_string()));
+    let chain = registry::get_chain("reg_test::pipeline").unwrap();
+    assert_eq!(chain.run(Context::new()).await.get::<bool>("enriched"), Some(true));
+    // Built once, then shared.
+    assert!(Arc::ptr_eq(&chain, &registry::get_chain("reg_test::pipeline").unwrap()));
+    assert!(Registry::global().namespaces().contains(&"reg_test".to_string()));
+}
+
+#[test]
+fn test_namespaces() {
+    let registry = Registry::new();
+    registry.register_link("ping", noop());
+    let billing = registry.namespace("billing");
+    billing.register_link("charge", noop());
+    billing.register_link_with("refund", noop(), LinkMetadata::new().side_effects().provides(["refund_id"]));
+    billing.namespace("eu").register_link("vat", noop());
+    billing.register_chain("invoice", Arc::new(Chain::new()));
+
+    assert_eq!(registry.link_names(), vec!["billing::charge", "billing::eu::vat", "billing::refund", "ping"]);
+    assert_eq!(billing.link_names(), vec!["charge", "refund"]);
+    assert!(billing.get_link("charge").is_some());
+    assert!(billing.get_chain("invoice").is_some());
+    assert!(registry.get_chain("billing::invoice").is_some());
+    assert_eq!(registry.namespaces(), vec!["billing", "billing::eu"]);
+    assert_eq!(registry.links(Some("")).iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec!["ping"]);
+    assert_eq!(registry.links(None).len(), 4);
+    assert_eq!(namespace_of("billing::eu::vat"), Some("billing::eu"));
+    assert_eq!(namespace_of("ping"), None);
+
+    let refunds = registry.find_links(|_, meta| meta.provides.iter().any(|key| key == "refund_id"));
+    assert_eq!(refunds.len(), 1);
+    assert_eq!(refunds[0].name, "billing::refund");
+    assert!(refunds[0].metadata.side_effects);
+
+    // Separate registries do not share entries.
+    assert!(registry::get_link("billing::charge").is_none());
+}
+
+#[test]
+fn test_concurrent_registration() {
+    let registry = Arc::new(Registry::new());
+    let threads: Vec<_> = (0..8)
+        .map(|t| {
+            let registry = registry.clone();
+            std::thread::spawn(move || {
+                let ns = registry.namespace(format!("t{}", t));
+                for i in 0..50 {
+                    ns.register_link(&format!("l{}", i), noop());
+                    assert!(ns.get_link(&format!("l{}", i)).is_some());
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+    assert_eq!(registry.link_names().len(), 400);
+    assert_eq!(registry.namespaces().len(), 8);
+    assert_eq!(registry.namespace("t3").link_names().len(), 50);
+}
+
+#[test]
+fn test_deprecated_self_registered_link() {
+    let deprecated = Registry::global().find_links(|name, meta| name.starts_with("reg_test::") && meta.deprecated.is_some());
+    assert_eq!(deprecated.iter().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec!["reg_test::old"]);
+}
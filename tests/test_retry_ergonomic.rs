@@ -0,0 +1,190 @@
+//! Test link retry policies (ergonomic pattern)
+
+use modulink_rs::chains::{Backoff, Chain, ErrorKind, ErrorRoute, RetryPolicy, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{FallibleLink, Link};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// A link failing the run until it has been called `succeed_on` times; it marks the
+// context first, to show that retries start from the link's input.
+fn flaky(calls: Arc<AtomicU32>, succeed_on: u32) -> Link {
+    Arc::new(move |ctx: Context| {
+        let calls = calls.clone();
+        Box::pin(async move {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            let seen = ctx.get::<u32>("seen").unwrap_or(0);
+            let ctx = ctx.insert("seen", seen + 1);
+            if call < succeed_on {
+                ctx_tools::fail_run(RunError::internal(format!("call {} failed", call)));
+            }
+            ctx
+        })
+    })
+}
+
+fn flaky_fallible(calls: Arc<AtomicU32>, succeed_on: u32) -> FallibleLink {
+    Arc::new(move |ctx: Context| {
+        let calls = calls.clone();
+        Box::pin(async move {
+            let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call < succeed_on {
+                return Err(RunError::internal("upstream unavailable"));
+            }
+            Ok(ctx.insert("fetched", true))
+        })
+    })
+}
+
+#[tokio::test]
+async fn test_chain_wide_policy_retries_until_success() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(flaky(calls.clone(), 3));
+    chain.set_retry_policy(RetryPolicy::new(5));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(ctx.get::<u32>("seen"), Some(1));
+    assert_eq!(report.retries, 2);
+    assert_eq!(report.attempts.get(&0), Some(&3));
+    assert_eq!(report.warnings.len(), 2);
+    assert!(report.warnings[0].starts_with("link 0 failed on attempt 1, retrying"));
+}
+
+#[tokio::test]
+async fn test_run_fails_once_attempts_run_out() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(flaky(calls.clone(), 10));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("after", true) })));
+    chain.retry_link(0, RetryPolicy::new(3));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    match report.status {
+        RunStatus::Failed(err) => assert_eq!(err.message, "call 3 failed"),
+        other => panic!("expected failure, got {:?}", other),
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    assert_eq!(ctx.get::<bool>("after"), None);
+    assert_eq!(report.attempts.get(&0), Some(&3));
+}
+
+#[tokio::test]
+async fn test_links_without_policy_run_once() {
+    let (first, second) = (Arc::new(AtomicU32::new(0)), Arc::new(AtomicU32::new(0)));
+    let mut chain = Chain::new();
+    chain.add_link(flaky(first.clone(), 2));
+    chain.add_link(flaky(second.clone(), 2));
+    chain.retry_link(1, RetryPolicy::new(3));
+    assert!(chain.retry_policy(0).is_none());
+    assert_eq!(chain.retry_policy(1).map(|policy| policy.max_attempts), Some(3));
+
+    let report = chain.run_with_report(Context::new()).await.1;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+    assert_eq!(first.load(Ordering::SeqCst), 1);
+    assert_eq!(second.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn test_per_link_policy_overrides_chain_wide() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(flaky(calls.clone(), 2));
+    chain.set_retry_policy(RetryPolicy::new(4));
+    chain.retry_link(0, RetryPolicy::new(1));
+
+    let report = chain.run_with_report(Context::new()).await.1;
+    assert!(matches!(report.status, RunStatus::Failed(_)));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(report.retries, 0);
+    assert!(report.attempts.is_empty());
+}
+
+#[tokio::test]
+async fn test_fallible_link_is_retried_before_routing() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let mut chain = Chain::new();
+    chain.add_fallible_link(flaky_fallible(calls.clone(), 2));
+    chain.set_retry_policy(RetryPolicy::new(2));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("fetched"), Some(true));
+    assert_eq!(report.attempts.get(&0), Some(&2));
+
+    // Out of attempts, the last error follows the link's error route
+    let calls = Arc::new(AtomicU32::new(0));
+    let mut chain = Chain::new();
+    chain.add_fallible_link(flaky_fallible(calls.clone(), 10));
+    chain.set_retry_policy(RetryPolicy::new(2));
+    chain.set_error_route(ErrorRoute::Skip);
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(ctx.get::<bool>("fetched"), None);
+    assert!(report.warnings.last().unwrap().starts_with("link 0 failed, skipped"));
+}
+
+#[tokio::test]
+async fn test_input_errors_are_not_retried() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let link: Link = {
+        let calls = calls.clone();
+        Arc::new(move |ctx: Context| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Box::pin(async move {
+                ctx_tools::fail_run(RunError::invalid_input("bad order"));
+                ctx
+            })
+        })
+    };
+    let mut chain = Chain::new();
+    chain.add_link(link);
+    chain.set_retry_policy(RetryPolicy::new(3));
+
+    let report = chain.run_with_report(Context::new()).await.1;
+    assert!(matches!(report.status, RunStatus::Failed(RunError { kind: ErrorKind::InvalidInput, .. })));
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_backoff_waits_between_attempts() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(flaky(calls, 3));
+    chain.set_retry_policy(RetryPolicy::new(3).with_backoff(Backoff::Fixed(Duration::from_millis(30))));
+
+    let started = Instant::now();
+    let report = chain.run_with_report(Context::new()).await.1;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert!(started.elapsed() >= Duration::from_millis(60));
+}
+
+#[test]
+fn test_exponential_backoff_is_capped() {
+    let policy = RetryPolicy::new(10).with_backoff(Backoff::Exponential {
+        initial: Duration::from_millis(100),
+        max: Duration::from_millis(500),
+    });
+    assert_eq!(policy.delay(1), Duration::from_millis(100));
+    assert_eq!(policy.delay(2), Duration::from_millis(200));
+    assert_eq!(policy.delay(3), Duration::from_millis(400));
+    assert_eq!(policy.delay(4), Duration::from_millis(500));
+    assert_eq!(policy.delay(40), Duration::from_millis(500));
+}
+
+#[test]
+fn test_policy_from_json() {
+    let policy: RetryPolicy = serde_json::from_value(serde_json::json!({
+        "max_attempts": 3,
+        "backoff": { "fixed": { "secs": 1, "nanos": 0 } },
+        "jitter": 0.5
+    }))
+    .unwrap();
+    assert_eq!(policy, RetryPolicy::new(3).with_backoff(Backoff::Fixed(Duration::from_secs(1))).with_jitter(0.5));
+}
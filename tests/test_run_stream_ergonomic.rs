@@ -0,0 +1,137 @@
+//! Test running a chain over streams and batches of contexts (ergonomic pattern)
+
+use futures::StreamExt;
+use modulink_rs::chains::{Chain, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn doubling_chain() -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            let n = ctx.get::<u64>("n").unwrap();
+            // Later inputs finish first
+            tokio::time::sleep(Duration::from_millis(40 - n * 4)).await;
+            ctx.insert("doubled", n * 2)
+        })
+    }));
+    chain
+}
+
+#[tokio::test]
+async fn test_run_stream_yields_every_result() {
+    let chain = doubling_chain();
+    let input = futures::stream::iter((0..10u64).map(|n| Context::new().insert("n", n)));
+
+    let results: Vec<Context> = chain.run_stream(input, 10).collect().await;
+    let mut doubled: Vec<u64> = results.iter().map(|ctx| ctx.get::<u64>("doubled").unwrap()).collect();
+    // Completion order, not input order
+    assert_ne!(doubled, (0..10).map(|n| n * 2).collect::<Vec<_>>());
+    doubled.sort();
+    assert_eq!(doubled, (0..10).map(|n| n * 2).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn test_run_stream_limits_concurrency() {
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    let (r, p) = (running.clone(), peak.clone());
+    chain.add_link(Arc::new(move |ctx: Context| {
+        let (running, peak) = (r.clone(), p.clone());
+        Box::pin(async move {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            ctx
+        })
+    }));
+
+    let input = futures::stream::iter((0..20).map(|n| Context::new().insert("n", n)));
+    assert_eq!(chain.run_stream(input, 3).count().await, 20);
+    assert_eq!(peak.load(Ordering::SeqCst), 3);
+
+    // 0 runs one at a time
+    peak.store(0, Ordering::SeqCst);
+    let input = futures::stream::iter((0..5).map(|n| Context::new().insert("n", n)));
+    assert_eq!(chain.run_stream(input, 0).count().await, 5);
+    assert_eq!(peak.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_run_stream_is_lazy_and_keeps_failed_runs() {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            if ctx.get::<bool>("bad") == Some(true) {
+                ctx_tools::fail_run(RunError::invalid_input("bad input"));
+                return ctx;
+            }
+            ctx.insert("ok", true)
+        })
+    }));
+    let mut outcomes = chain.subscribe();
+
+    // An unbounded source, consumed only as far as the output is read
+    let input = futures::stream::iter(0..).map(|n: u64| Context::new().insert("n", n).insert("bad", n == 1));
+    let results: Vec<Context> = chain.run_stream(input, 1).take(3).collect().await;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[1].get::<bool>("ok"), None);
+    assert_eq!(results[2].get::<bool>("ok"), Some(true));
+
+    let statuses: Vec<RunStatus> = outcomes.by_ref().take(3).map(|outcome| outcome.report.status).collect().await;
+    assert_eq!(statuses.iter().filter(|status| matches!(status, RunStatus::Failed(_))).count(), 1);
+}
+
+#[tokio::test]
+async fn test_run_all_keeps_batch_order_and_errors() {
+    let mut chain = doubling_chain();
+    chain.add_link(Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            if ctx.get::<u64>("n") == Some(3) {
+                ctx_tools::fail_run(RunError::invalid_input("3 is not allowed"));
+            }
+            ctx
+        })
+    }));
+
+    let batch: Vec<Context> = (0..8u64).map(|n| Context::new().insert("n", n)).collect();
+    let results = chain.run_all(batch, 4).await;
+    assert_eq!(results.len(), 8);
+    for (n, result) in results.iter().enumerate() {
+        match result {
+            Ok(ctx) => assert_eq!(ctx.get::<u64>("doubled"), Some(n as u64 * 2)),
+            Err(err) => {
+                assert_eq!(n, 3);
+                assert_eq!(err.message, "3 is not allowed");
+            }
+        }
+    }
+    assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+    assert!(chain.run_all(Vec::new(), 4).await.is_empty());
+}
+
+#[tokio::test]
+async fn test_run_all_bounds_concurrency() {
+    let running = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    let (r, p) = (running.clone(), peak.clone());
+    chain.add_link(Arc::new(move |ctx: Context| {
+        let (running, peak) = (r.clone(), p.clone());
+        Box::pin(async move {
+            peak.fetch_max(running.fetch_add(1, Ordering::SeqCst) + 1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            running.fetch_sub(1, Ordering::SeqCst);
+            ctx
+        })
+    }));
+
+    let results = chain.run_all((0..12).map(|n| Context::new().insert("n", n)).collect(), 2).await;
+    assert!(results.iter().all(Result::is_ok));
+    assert_eq!(peak.load(Ordering::SeqCst), 2);
+}
@@ -0,0 +1,62 @@
+//! Test per-run middleware injection (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::middleware::Middleware;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+struct Tracer(&'static str, Arc<Mutex<Vec<String>>>);
+
+impl Middleware<Context> for Tracer {
+    fn before<'a>(&'a self, ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        let request = ctx.get::<u32>("request").unwrap_or_default();
+        Box::pin(async move { self.1.lock().unwrap().push(format!("{}:{}", self.0, request)) })
+    }
+}
+
+fn chain(log: &Arc<Mutex<Vec<String>>>) -> Arc<Chain> {
+    let mut chain = Chain::new();
+    chain.use_middleware(Arc::new(Tracer("shared", log.clone())));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("done", true) })));
+    Arc::new(chain)
+}
+
+#[tokio::test]
+async fn test_extra_middleware_runs_for_that_run_only() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let chain = chain(&log);
+    let verbose: Arc<dyn Middleware<Context>> = Arc::new(Tracer("verbose", log.clone()));
+
+    let (ctx, report) = chain.run_with(Context::new().insert("request", 1), &[verbose]).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("done"), Some(true));
+    chain.run(Context::new().insert("request", 2)).await;
+
+    assert_eq!(*log.lock().unwrap(), ["shared:1", "verbose:1", "shared:2"]);
+    assert_eq!(chain.middleware_names(), ["Tracer"]);
+}
+
+#[tokio::test]
+async fn test_concurrent_runs_keep_their_own_middleware() {
+    let log = Arc::new(Mutex::new(Vec::new()));
+    let chain = chain(&log);
+    let verbose: Arc<dyn Middleware<Context>> = Arc::new(Tracer("verbose", log.clone()));
+
+    let runs = (1..=4).map(|request| {
+        let (chain, verbose) = (chain.clone(), verbose.clone());
+        tokio::spawn(async move {
+            let extra = if request % 2 == 0 { vec![verbose] } else { Vec::new() };
+            chain.run_with(Context::new().insert("request", request), &extra).await
+        })
+    });
+    for run in runs.collect::<Vec<_>>() {
+        run.await.unwrap();
+    }
+
+    let mut verbose: Vec<String> = log.lock().unwrap().iter().filter(|entry| entry.starts_with("verbose")).cloned().collect();
+    verbose.sort();
+    assert_eq!(verbose, ["verbose:2", "verbose:4"]);
+    assert_eq!(log.lock().unwrap().len(), 6);
+}
@@ -0,0 +1,77 @@
+//! Test that chains run without tokio and that executors are injectable (generic pattern)
+
+use modulink_rs::chains::ChainGeneric;
+use modulink_rs::context::ContextMutable;
+use modulink_rs::links::LinkGeneric;
+use modulink_rs::runtime::{self, Executor, JoinError, MockExecutor, ThreadExecutor};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn add_one_link_mut() -> LinkGeneric<ContextMutable> {
+    Arc::new(|mut ctx: ContextMutable| Box::pin(async move {
+        let val = ctx.get::<i32>("val").unwrap_or(0);
+        ctx.insert("val", val + 1);
+        ctx
+    }))
+}
+
+#[test]
+fn test_chain_runs_on_futures_executor() {
+    let mut chain = ChainGeneric::<ContextMutable>::new();
+    chain.add_link(add_one_link_mut());
+    chain.add_link(add_one_link_mut());
+    let result = futures::executor::block_on(chain.run(ContextMutable::new()));
+    assert_eq!(result.get::<i32>("val"), Some(2));
+}
+
+#[test]
+fn test_thread_executor_spawn_and_sleep() {
+    let exec = ThreadExecutor;
+    let handle = runtime::spawn(&exec, async move {
+        ThreadExecutor.sleep(Duration::from_millis(5)).await;
+        42
+    });
+    assert_eq!(futures::executor::block_on(handle), Ok(42));
+    let blocking = runtime::spawn_blocking(&exec, || 7);
+    assert_eq!(futures::executor::block_on(blocking), Ok(7));
+}
+
+#[test]
+fn test_mock_executor_injected_into_chain() {
+    let exec = MockExecutor::new();
+    let mut chain = ChainGeneric::<ContextMutable>::new();
+    chain.add_link(add_one_link_mut());
+    chain.set_executor(Arc::new(exec.clone()));
+    let chain = Arc::new(chain);
+    let run_chain = chain.clone();
+    let sleep = chain.executor().sleep(Duration::from_secs(30));
+    let handle = runtime::spawn(chain.executor().as_ref(), async move {
+        sleep.await;
+        run_chain.run(ContextMutable::new()).await
+    });
+    exec.run_until_stalled();
+    assert_eq!(exec.pending_tasks(), 1);
+    exec.advance(Duration::from_secs(30));
+    assert_eq!(exec.pending_tasks(), 0);
+    let result = futures::executor::block_on(handle).unwrap();
+    assert_eq!(result.get::<i32>("val"), Some(1));
+}
+
+#[test]
+fn test_mock_executor_timeout_and_abort() {
+    let exec = MockExecutor::new();
+    let timed = {
+        let exec = exec.clone();
+        runtime::spawn(&exec.clone(), async move {
+            runtime::timeout(&exec, Duration::from_secs(1), futures::future::pending::<()>()).await
+        })
+    };
+    exec.run_until_stalled();
+    exec.advance(Duration::from_secs(1));
+    assert_eq!(futures::executor::block_on(timed), Ok(None));
+
+    let aborted = runtime::spawn(&exec, futures::future::pending::<()>());
+    aborted.abort();
+    exec.run_until_stalled();
+    assert_eq!(futures::executor::block_on(aborted), Err(JoinError::Aborted));
+}
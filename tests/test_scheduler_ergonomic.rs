@@ -0,0 +1,103 @@
+//! Test wait links, parked durable runs, and the scheduler (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, CheckpointStore, MemoryCheckpointStore, RunStatus, Scheduler};
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::listeners::BaseListenerAsync;
+use modulink_rs::std_links::{wait_for, wait_until, Wait};
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+fn mark(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn reminder_chain(store: Arc<MemoryCheckpointStore>, wait: Duration) -> Arc<Chain> {
+    let mut chain = Chain::new();
+    chain.enable_checkpoints(store);
+    chain.add_link(mark("invoiced"));
+    chain.add_link(wait_for(wait));
+    chain.add_link(mark("reminded"));
+    Arc::new(chain)
+}
+
+#[tokio::test]
+async fn test_durable_wait_parks_and_scheduler_resumes() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let chain = reminder_chain(store.clone(), Duration::from_millis(100));
+    let started = Instant::now();
+    let (ctx, report) = chain.run_durable("invoice-1", Context::new()).await;
+    assert!(started.elapsed() < Duration::from_millis(100));
+    let RunStatus::Parked { wake_at_ms } = report.status else { panic!("expected a parked run") };
+    assert_eq!(ctx.get::<bool>("reminded"), None);
+    let checkpoint = store.load("invoice-1").await.unwrap().unwrap();
+    assert_eq!((checkpoint.link, checkpoint.wake_at_ms), (2, Some(wake_at_ms)));
+
+    let scheduler = Scheduler::new(chain.clone());
+    assert!(scheduler.run_due().await.unwrap().is_empty());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    let resumed = scheduler.run_due().await.unwrap();
+    assert_eq!(resumed.len(), 1);
+    assert_eq!(resumed[0].0, "invoice-1");
+    assert_eq!(resumed[0].1.status, RunStatus::Completed);
+    assert!(store.is_empty());
+}
+
+#[tokio::test]
+async fn test_scheduler_listener_polls_for_due_runs() {
+    let store = Arc::new(MemoryCheckpointStore::new());
+    let chain = reminder_chain(store.clone(), Duration::from_millis(50));
+    let mut outcomes = chain.subscribe();
+    chain.run_durable("invoice-2", Context::new()).await;
+    let scheduler = Scheduler::new(chain.clone()).with_poll_interval(Duration::from_millis(20));
+    let poller = tokio::spawn(async move { scheduler.start().await });
+
+    use futures::StreamExt;
+    let parked = outcomes.next().await.unwrap();
+    assert!(matches!(parked.report.status, RunStatus::Parked { .. }));
+    let done = tokio::time::timeout(Duration::from_secs(2), outcomes.next()).await.unwrap().unwrap();
+    assert_eq!(done.report.status, RunStatus::Completed);
+    assert_eq!(done.ctx.get::<bool>("reminded"), Some(true));
+    poller.abort();
+}
+
+#[tokio::test]
+async fn test_wait_outside_durable_runs_sleeps() {
+    let mut chain = Chain::new();
+    chain.add_link(wait_until(SystemTime::now() + Duration::from_millis(50)));
+    chain.add_link(mark("done"));
+    let started = Instant::now();
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert!(started.elapsed() >= Duration::from_millis(40));
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("done"), Some(true));
+
+    // A time already past does not wait at all
+    let mut chain = Chain::new();
+    chain.add_link(wait_until(SystemTime::now() - Duration::from_secs(60)));
+    let started = Instant::now();
+    chain.run(Context::new()).await;
+    assert!(started.elapsed() < Duration::from_millis(40));
+}
+
+#[tokio::test]
+async fn test_wait_sleeps_on_the_given_executor() {
+    use modulink_rs::runtime::MockExecutor;
+
+    let exec = MockExecutor::new();
+    let hour = Duration::from_secs(3600);
+    let mut chain = Chain::new();
+    chain.add_link(Wait::after(hour).with_executor(Arc::new(exec.clone())).link());
+    chain.add_link(mark("reminded"));
+    let run = tokio::spawn(async move { chain.run(Context::new()).await });
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !run.is_finished() {
+            exec.advance(hour);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert_eq!(run.await.unwrap().get::<bool>("reminded"), Some(true));
+}
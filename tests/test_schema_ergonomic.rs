@@ -0,0 +1,76 @@
+//! Test context schema inference and drift detection (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::schema::{ContextSchema, Drift, JsonType, SchemaMiddleware};
+use serde_json::json;
+use std::sync::{Arc, Mutex};
+
+fn enrich() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let total = ctx.get::<f64>("amount").unwrap_or(0.0) * 1.2;
+        ctx.insert("total", total)
+    }))
+}
+
+fn chain_with(schema: Arc<SchemaMiddleware>) -> Chain {
+    let mut chain = Chain::new();
+    chain.add_link_with(enrich(), LinkSpec::new().name("enrich"));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx })));
+    chain.use_middleware(schema);
+    chain
+}
+
+#[tokio::test]
+async fn test_infers_schema_per_link() {
+    let schema = Arc::new(SchemaMiddleware::new());
+    let chain = chain_with(schema.clone());
+    chain.run(Context::new().insert("amount", 10).insert("items", json!([{ "sku": "a" }]))).await;
+    chain.run(Context::new().insert("amount", 2.5).insert("note", json!(null))).await;
+
+    let observed = schema.observed();
+    assert_eq!(observed.points.keys().collect::<Vec<_>>(), vec!["#1", "enrich", "input"]);
+    let input = &observed.points["input"];
+    assert_eq!(input.properties["amount"].types, [JsonType::Integer, JsonType::Number].into());
+    assert_eq!(input.properties["items"].items.as_ref().unwrap().properties["sku"].types, [JsonType::String].into());
+    assert!(observed.points["enrich"].properties.contains_key("total"));
+
+    let json_schema = observed.to_json_schema();
+    assert_eq!(json_schema["input"]["properties"]["amount"]["type"], "number");
+    assert_eq!(json_schema["input"]["properties"]["items"]["items"]["properties"]["sku"]["type"], "string");
+    assert_eq!(ContextSchema::from_json(&observed.to_json()).unwrap(), observed);
+}
+
+#[tokio::test]
+async fn test_flags_drift_against_baseline() {
+    let learn = Arc::new(SchemaMiddleware::new());
+    let chain = chain_with(learn.clone());
+    chain.run(Context::new().insert("amount", 1.5).insert("customer", json!({ "id": "c1" }))).await;
+    let baseline = ContextSchema::from_json(&learn.observed().to_json()).unwrap();
+
+    let reported = Arc::new(Mutex::new(Vec::new()));
+    let sink = reported.clone();
+    let check = Arc::new(SchemaMiddleware::new().with_baseline(baseline).on_drift(move |d: &Drift| sink.lock().unwrap().push(d.clone())));
+    let chain = chain_with(check.clone());
+
+    // Integers where numbers were seen are not drift
+    let (_, report) = chain.run_with_report(Context::new().insert("amount", 3).insert("customer", json!({ "id": "c2" }))).await;
+    assert!(report.warnings.is_empty());
+
+    let drifted = Context::new().insert("amount", "3.00").insert("customer", json!({ "id": "c3", "tier": "gold" }));
+    let (_, report) = chain.run_with_report(drifted.clone()).await;
+    assert!(report.warnings.iter().any(|w| w.contains("'amount' is string, baseline number")));
+    chain.run(drifted).await;
+
+    let drift = check.drift();
+    assert!(drift.contains(&Drift::TypeChanged {
+        point: "input".to_string(),
+        path: "amount".to_string(),
+        baseline: [JsonType::Number].into(),
+        observed: [JsonType::String].into(),
+    }));
+    assert!(drift.contains(&Drift::NewField { point: "enrich".to_string(), path: "customer.tier".to_string(), types: [JsonType::String].into() }));
+    // Each distinct drift is reported once, however many runs show it
+    assert_eq!(reported.lock().unwrap().len(), drift.len());
+}
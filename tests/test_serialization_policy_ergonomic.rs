@@ -0,0 +1,78 @@
+//! Test the serialization error policy of Context::insert (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, RunStatus};
+use modulink_rs::context::{meta, Context, ContextMutable, SerializationPolicy};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// serde_json only serializes maps with string keys
+fn unserializable() -> HashMap<(i32, i32), i32> {
+    HashMap::from([((1, 2), 3)])
+}
+
+fn chain(policy: SerializationPolicy) -> Chain {
+    let mut chain = Chain::new();
+    chain.set_serialization_policy(policy);
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        ctx.insert("grid", unserializable()).insert("after", true)
+    })));
+    chain
+}
+
+#[test]
+fn test_try_insert_returns_context_on_error() {
+    let ctx = Context::new().insert("a", 1);
+    let err = ctx.try_insert("grid", unserializable()).unwrap_err();
+    assert_eq!(err.key, "grid");
+    assert!(err.to_string().starts_with("could not serialize 'grid'"));
+    assert_eq!(err.ctx.get::<i32>("a"), Some(1));
+
+    let ctx = err.ctx.try_insert("b", 2).unwrap();
+    assert_eq!(ctx.get::<i32>("b"), Some(2));
+}
+
+#[test]
+fn test_mutable_try_insert_leaves_context_unchanged() {
+    let mut ctx = ContextMutable::new();
+    ctx.insert("grid", 1);
+    assert!(ctx.try_insert("grid", unserializable()).is_err());
+    assert_eq!(ctx.get::<i32>("grid"), Some(1));
+}
+
+#[test]
+#[should_panic(expected = "could not serialize 'grid'")]
+fn test_insert_panics_outside_of_a_run() {
+    Context::new().insert("grid", unserializable());
+}
+
+#[tokio::test]
+async fn test_default_policy_is_panic() {
+    assert_eq!(Chain::new().serialization_policy(), SerializationPolicy::Panic);
+}
+
+#[tokio::test]
+async fn test_skip_policy_warns_and_continues() {
+    let (ctx, report) = chain(SerializationPolicy::Skip).run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<Value>("grid"), None);
+    assert_eq!(ctx.get::<bool>("after"), Some(true));
+    assert_eq!(ctx.get::<Value>(meta::SERIALIZATION_ERRORS), None);
+    assert_eq!(report.warnings.len(), 1);
+    assert!(report.warnings[0].starts_with("could not serialize 'grid'"));
+}
+
+#[tokio::test]
+async fn test_record_error_policy_records_the_key() {
+    let (ctx, report) = chain(SerializationPolicy::RecordError).run_with_report(Context::new()).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("after"), Some(true));
+    let errors = ctx.get::<Value>(meta::SERIALIZATION_ERRORS).unwrap();
+    assert_eq!(errors.as_object().unwrap().keys().collect::<Vec<_>>(), ["grid"]);
+    assert_eq!(report.warnings.len(), 1);
+}
+
+#[test]
+fn test_policy_serializes_as_snake_case() {
+    assert_eq!(serde_json::to_value(SerializationPolicy::RecordError).unwrap(), json!("record_error"));
+}
@@ -0,0 +1,63 @@
+//! Test side-effect tagging and shadow runs (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, RunStatus, Shadow};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::registry::LinkMetadata;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn effect(counter: Arc<AtomicUsize>, key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| {
+        let counter = counter.clone();
+        Box::pin(async move {
+            counter.fetch_add(1, Ordering::SeqCst);
+            ctx.insert(key, "sent")
+        })
+    })
+}
+
+#[tokio::test]
+async fn test_shadow_run_skips_or_mocks_tagged_links() {
+    let (emails, webhooks) = (Arc::new(AtomicUsize::new(0)), Arc::new(AtomicUsize::new(0)));
+    let mut chain = Chain::new();
+    chain.add_link_with(
+        Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("total", 42).insert("shadow", ctx_tools::is_shadow()) })),
+        LinkSpec::new().name("price"),
+    );
+    chain.add_link_with(effect(emails.clone(), "email"), LinkSpec::new().name("email").side_effects());
+    chain.add_link_with(effect(webhooks.clone(), "webhook"), LinkSpec::new().name("webhook").side_effects());
+    let mut events = chain.subscribe();
+
+    let mock: Link = Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("webhook", "mocked") }));
+    let shadow = Shadow::new().mock("webhook", mock);
+    let (ctx, report) = chain.run_shadow(Context::new(), &shadow).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("shadow"), Some(true));
+    assert_eq!(ctx.get::<String>("email"), None);
+    assert_eq!(ctx.get::<String>("webhook").as_deref(), Some("mocked"));
+    assert_eq!((emails.load(Ordering::SeqCst), webhooks.load(Ordering::SeqCst)), (0, 0));
+    // Shadow results are not published to subscribers
+    assert!(events.try_next().is_err());
+
+    let calls = shadow.calls();
+    assert_eq!(calls.iter().map(|c| (c.name.as_deref().unwrap(), c.mocked)).collect::<Vec<_>>(), vec![("email", false), ("webhook", true)]);
+    assert_eq!(calls[0].input["total"], 42);
+
+    // A normal run performs the effects
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<bool>("shadow"), Some(false));
+    assert_eq!((emails.load(Ordering::SeqCst), webhooks.load(Ordering::SeqCst)), (1, 1));
+}
+
+#[test]
+fn test_side_effects_tag_in_specs_and_metadata() {
+    let spec = LinkMetadata::new().description("sends the receipt").side_effects().spec();
+    assert!(spec.side_effects);
+    assert_eq!(LinkMetadata::new().side_effects().to_string(), "[side effects]");
+    // Untagged specs serialize as before
+    assert!(!serde_json::to_string(&LinkSpec::new().name("a")).unwrap().contains("side_effects"));
+    let tagged: LinkSpec = serde_json::from_str(r#"{"name":"b","requires":[],"provides":[],"side_effects":true}"#).unwrap();
+    assert!(tagged.side_effects);
+}
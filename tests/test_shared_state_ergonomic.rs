@@ -0,0 +1,122 @@
+//! Test cross-run shared state (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, SharedState};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::Link;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Default)]
+struct Counter(AtomicU64);
+
+#[derive(Default)]
+struct PriceCache(Mutex<HashMap<String, f64>>);
+
+fn count() -> Link {
+    Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            let counter = ctx_tools::state::<Counter>().expect("counter in shared state");
+            ctx.insert("count", counter.0.fetch_add(1, Ordering::SeqCst) + 1)
+        })
+    })
+}
+
+#[tokio::test]
+async fn test_state_persists_across_runs() {
+    let state = SharedState::new();
+    state.insert(Counter::default());
+    let mut chain = Chain::new();
+    chain.set_state(state.clone());
+    chain.add_link(count());
+
+    for expected in 1..=3u64 {
+        let ctx = chain.run(Context::new()).await;
+        assert_eq!(ctx.get::<u64>("count"), Some(expected));
+    }
+    assert_eq!(state.get::<Counter>().unwrap().0.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn test_state_shared_by_chains_and_concurrent_runs() {
+    let state = SharedState::new();
+    state.insert(Counter::default());
+    let mut first = Chain::new();
+    first.set_state(state.clone());
+    first.add_link(count());
+    let mut second = Chain::new();
+    second.set_state(state.clone());
+    second.add_link(count());
+
+    let runs = (0..10).map(|i| {
+        let chain = if i % 2 == 0 { &first } else { &second };
+        chain.run(Context::new())
+    });
+    futures::future::join_all(runs).await;
+    assert_eq!(state.get::<Counter>().unwrap().0.load(Ordering::SeqCst), 10);
+}
+
+#[tokio::test]
+async fn test_links_can_fill_a_cache() {
+    let lookup: Link = Arc::new(|ctx: Context| {
+        Box::pin(async move {
+            let cache = ctx_tools::state::<PriceCache>().unwrap();
+            let sku = ctx.get::<String>("sku").unwrap();
+            let (price, cached) = {
+                let mut prices = cache.0.lock().unwrap();
+                match prices.get(&sku) {
+                    Some(price) => (*price, true),
+                    None => {
+                        prices.insert(sku.clone(), 9.5);
+                        (9.5, false)
+                    }
+                }
+            };
+            ctx.insert("price", price).insert("cached", cached)
+        })
+    });
+    let state = SharedState::new();
+    state.get_or_insert_with(PriceCache::default);
+    let mut chain = Chain::new();
+    chain.set_state(state);
+    chain.add_link(lookup);
+
+    let ctx = chain.run(Context::new().insert("sku", "A-1")).await;
+    assert_eq!(ctx.get::<bool>("cached"), Some(false));
+    let ctx = chain.run(Context::new().insert("sku", "A-1")).await;
+    assert_eq!(ctx.get::<bool>("cached"), Some(true));
+    assert_eq!(ctx.get::<f64>("price"), Some(9.5));
+}
+
+#[tokio::test]
+async fn test_chain_without_state() {
+    let probe: Link = Arc::new(|ctx: Context| {
+        Box::pin(async move { ctx.insert("has_counter", ctx_tools::state::<Counter>().is_some()) })
+    });
+    let mut chain = Chain::new();
+    chain.add_link(probe);
+    assert!(chain.state().is_none());
+
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<bool>("has_counter"), Some(false));
+    assert!(ctx_tools::state::<Counter>().is_none());
+}
+
+#[test]
+fn test_typed_accessors() {
+    let state = SharedState::new();
+    assert!(state.is_empty());
+    assert!(state.insert(String::from("v1")).is_none());
+    assert_eq!(state.insert(String::from("v2")).as_deref().map(String::as_str), Some("v1"));
+    state.insert(7u32);
+    assert_eq!(state.len(), 2);
+    assert!(state.contains::<u32>());
+    assert!(!state.contains::<u64>());
+
+    assert_eq!(*state.get_or_insert_with(|| 1u32), 7);
+    assert_eq!(*state.get_or_insert_with(|| 1u64), 1);
+    assert_eq!(state.remove::<u32>().map(|v| *v), Some(7));
+    assert!(state.get::<u32>().is_none());
+    assert_eq!(state.get::<String>().as_deref().map(String::as_str), Some("v2"));
+}
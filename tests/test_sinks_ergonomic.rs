@@ -0,0 +1,172 @@
+//! Test sinks attached with pipe_to (ergonomic pattern)
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::sinks::{ChannelSink, FileSink, KafkaProducer, KafkaSink};
+use std::sync::{Arc, Mutex};
+
+fn greet_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let name = ctx.get::<String>("name").unwrap_or_default();
+        ctx.insert("greeting", format!("hello {}", name))
+    }))
+}
+
+type Sent = (String, Option<String>, Vec<u8>);
+
+#[derive(Default)]
+struct RecordingProducer {
+    sent: Mutex<Vec<Sent>>,
+}
+
+#[async_trait]
+impl KafkaProducer for RecordingProducer {
+    async fn send(&self, topic: &str, key: Option<&str>, payload: Vec<u8>) -> std::io::Result<()> {
+        self.sent.lock().unwrap().push((topic.to_string(), key.map(str::to_string), payload));
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_channel_and_file_sinks_receive_results() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.jsonl");
+    let (tx, mut rx) = futures::channel::mpsc::channel(8);
+
+    let mut chain = Chain::new();
+    chain.add_link(greet_link());
+    chain.pipe_to(Arc::new(ChannelSink::new(tx)));
+    chain.pipe_to(Arc::new(FileSink::new(&path)));
+
+    let _ = chain.run(Context::new().insert("name", "ada")).await;
+    let _ = chain.run(Context::new().insert("name", "bob")).await;
+
+    let first = rx.next().await.unwrap();
+    assert_eq!(first.get::<String>("greeting"), Some("hello ada".to_string()));
+    let lines: Vec<serde_json::Value> = std::fs::read_to_string(&path)
+        .unwrap()
+        .lines()
+        .map(|l| serde_json::from_str(l).unwrap())
+        .collect();
+    assert_eq!(lines.len(), 2);
+    assert_eq!(lines[1]["greeting"], "hello bob");
+}
+
+#[tokio::test]
+async fn test_file_sink_io_runs_on_the_executor() {
+    use modulink_rs::runtime::MockExecutor;
+    use modulink_rs::sinks::BaseSink;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("out.jsonl");
+    let exec = MockExecutor::new();
+    let sink = Arc::new(FileSink::new(&path).with_executor(Arc::new(exec.clone())));
+    let delivering = tokio::spawn({
+        let sink = sink.clone();
+        async move { sink.deliver(&Context::new().insert("name", "ada")).await }
+    });
+
+    // The append waits for the executor's blocking pool
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    assert!(!path.exists());
+    exec.run_until_stalled();
+    delivering.await.unwrap().unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"name\":\"ada\"}\n");
+}
+
+#[tokio::test]
+async fn test_kafka_sink_uses_producer_and_key() {
+    let producer = Arc::new(RecordingProducer::default());
+    let sink = KafkaSink::new(producer.clone(), "greetings").with_key(|ctx: &Context| ctx.get::<String>("name"));
+    let mut chain = Chain::new();
+    chain.add_link(greet_link());
+    chain.pipe_to(Arc::new(sink));
+
+    let _ = chain.run(Context::new().insert("name", "ada")).await;
+
+    let sent = producer.sent.lock().unwrap();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].0, "greetings");
+    assert_eq!(sent[0].1.as_deref(), Some("ada"));
+    let payload: serde_json::Value = serde_json::from_slice(&sent[0].2).unwrap();
+    assert_eq!(payload["greeting"], "hello ada");
+}
+
+#[cfg(feature = "http-sink")]
+#[tokio::test]
+async fn test_http_sink_posts_json() {
+    use axum::{extract::State, routing::post, Json, Router};
+    use modulink_rs::sinks::HttpSink;
+
+    let received: Arc<Mutex<Vec<serde_json::Value>>> = Arc::default();
+    let app = Router::new()
+        .route("/ingest", post(|State(received): State<Arc<Mutex<Vec<serde_json::Value>>>>, Json(body): Json<serde_json::Value>| async move {
+            received.lock().unwrap().push(body);
+        }))
+        .with_state(received.clone());
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let mut chain = Chain::new();
+    chain.add_link(greet_link());
+    chain.pipe_to(Arc::new(HttpSink::new(format!("http://{}/ingest", addr))));
+    let _ = chain.run(Context::new().insert("name", "ada")).await;
+
+    assert_eq!(received.lock().unwrap()[0]["greeting"], "hello ada");
+}
+
+#[derive(Default)]
+struct DeltaSink {
+    changes: Mutex<Vec<modulink_rs::chains::Change>>,
+}
+
+#[async_trait]
+impl modulink_rs::sinks::Sink for DeltaSink {
+    async fn deliver(&self, _ctx: &Context) -> std::io::Result<()> {
+        panic!("journaled runs deliver changes");
+    }
+    fn deliver_changes<'a>(
+        &'a self,
+        _ctx: &'a Context,
+        changes: &'a [modulink_rs::chains::Change],
+    ) -> modulink_rs::runtime::BoxFuture<'a, std::io::Result<()>> {
+        Box::pin(async move {
+            self.changes.lock().unwrap().extend_from_slice(changes);
+            Ok(())
+        })
+    }
+    fn name(&self) -> &'static str {
+        "delta"
+    }
+}
+
+#[tokio::test]
+async fn test_journal_delivers_key_level_changes() {
+    use modulink_rs::chains::Change;
+    use serde_json::json;
+
+    let drop_name: Link = Arc::new(|ctx: Context| Box::pin(async move {
+        let mut ctx = ctx;
+        ctx.0.remove("name");
+        ctx
+    }));
+    let sink = Arc::new(DeltaSink::default());
+    let mut chain = Chain::new();
+    chain.add_link(greet_link());
+    chain.add_link(drop_name);
+    chain.enable_journal();
+    chain.pipe_to(sink.clone());
+
+    let (_, report) = chain.run_with_report(Context::new().insert("name", "ada").insert("id", 7)).await;
+    let expected = vec![
+        Change::Set { link: 0, key: "greeting".into(), value: json!("hello ada") },
+        Change::Remove { link: 1, key: "name".into() },
+    ];
+    assert_eq!(report.journal, expected);
+    assert_eq!(*sink.changes.lock().unwrap(), expected);
+    assert_eq!(serde_json::to_value(&expected[1]).unwrap(), json!({ "op": "remove", "link": 1, "key": "name" }));
+}
@@ -0,0 +1,97 @@
+//! Test link-level SLO tracking and alerts (ergonomic pattern)
+
+use async_trait::async_trait;
+use modulink_rs::chains::{Chain, RunError};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::middleware::slo::{Alert, AlertHandler, AlertState, Objective, Slo, SloMiddleware};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default)]
+struct Recorder(Mutex<Vec<Alert>>);
+
+#[async_trait]
+impl AlertHandler for Recorder {
+    async fn alert(&self, alert: &Alert) {
+        self.0.lock().unwrap().push(alert.clone());
+    }
+}
+
+fn charge() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        if let Some(ms) = ctx.get::<u64>("delay_ms") {
+            tokio::time::sleep(Duration::from_millis(ms)).await;
+        }
+        if ctx.get::<bool>("decline") == Some(true) {
+            ctx_tools::fail_run(RunError::internal("card declined"));
+        }
+        ctx
+    }))
+}
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+#[tokio::test]
+async fn test_error_rate_slo_fires_and_resolves() {
+    let alerts = Arc::new(Recorder::default());
+    let slos = Arc::new(
+        SloMiddleware::new(alerts.clone())
+            .slo(Slo::for_link("charge").error_rate(0.3).window(Duration::from_secs(60)).min_samples(4))
+            .slo(Slo::for_link("ship").error_rate(0.3).min_samples(1)),
+    );
+    let mut chain = Chain::new();
+    chain.add_link_with(charge(), LinkSpec::new().name("charge"));
+    chain.add_link_with(noop(), LinkSpec::new().name("ship"));
+    chain.use_middleware(slos.clone());
+
+    for decline in [false, false, true, true] {
+        chain.run(Context::new().insert("decline", decline)).await;
+    }
+    let fired = alerts.0.lock().unwrap().clone();
+    assert_eq!(fired.len(), 1);
+    assert_eq!((fired[0].link.as_str(), fired[0].objective, fired[0].state), ("charge", Objective::ErrorRate, AlertState::Firing));
+    assert_eq!((fired[0].observed, fired[0].samples), (0.5, 4));
+
+    // A second breached evaluation does not alert again; recovery does
+    chain.run(Context::new().insert("decline", true)).await;
+    for _ in 0..5 {
+        chain.run(Context::new()).await;
+    }
+    let all = alerts.0.lock().unwrap().clone();
+    assert_eq!(all.len(), 2);
+    assert_eq!(all[1].state, AlertState::Resolved);
+
+    let status = slos.status();
+    assert_eq!(status[0].samples, 10);
+    assert!(!status[0].error_rate_breached);
+    // "ship" never ran after a declined charge and never failed
+    assert_eq!((status[1].samples, status[1].error_rate), (7, 0.0));
+}
+
+#[tokio::test]
+async fn test_latency_slo_over_sliding_window() {
+    let alerts = Arc::new(Recorder::default());
+    let slos = Arc::new(
+        SloMiddleware::new(alerts.clone())
+            .slo(Slo::for_link("charge").latency(0.5, Duration::from_millis(30)).window(Duration::from_millis(200)).min_samples(2)),
+    );
+    let mut chain = Chain::new();
+    chain.add_link_with(charge(), LinkSpec::new().name("charge"));
+    chain.use_middleware(slos.clone());
+
+    chain.run(Context::new().insert("delay_ms", 60)).await;
+    chain.run(Context::new().insert("delay_ms", 60)).await;
+    assert_eq!(alerts.0.lock().unwrap()[0].objective, Objective::Latency);
+    assert!(slos.status()[0].latency_ms.unwrap() >= 60.0);
+
+    // Slow samples age out of the window
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    chain.run(Context::new()).await;
+    chain.run(Context::new()).await;
+    assert_eq!(slos.status()[0].samples, 2);
+    assert_eq!(alerts.0.lock().unwrap().last().unwrap().state, AlertState::Resolved);
+}
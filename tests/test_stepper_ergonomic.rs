@@ -0,0 +1,106 @@
+//! Test advancing a chain one link at a time (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, ErrorRoute, RunError, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::links::{FallibleLink, Link, LinkSpec};
+use std::sync::Arc;
+
+fn mark(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn check_qty() -> FallibleLink {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        match ctx.get::<u32>("qty") {
+            Some(qty) if qty > 0 => Ok(ctx.insert("checked", true)),
+            _ => Err(RunError::invalid_input("qty must be positive")),
+        }
+    }))
+}
+
+fn review() -> Chain {
+    let mut chain = Chain::new();
+    let classify: Link = Arc::new(|ctx: Context| Box::pin(async move {
+        let score = ctx.get::<f64>("score").unwrap_or(0.5);
+        ctx.insert("score", score)
+    }));
+    chain.add_link_with(classify, LinkSpec::new().name("classify"));
+    chain.add_link_with(mark("reviewed"), LinkSpec::new().name("manual_review"));
+    chain.add_link_with(mark("approved"), LinkSpec::new().name("auto_approve"));
+    chain.connect(0, 2, |ctx: &Context| ctx.get::<f64>("score").unwrap_or(0.0) >= 0.8);
+    chain
+}
+
+#[tokio::test]
+async fn test_steps_see_changes_made_between_them() {
+    let chain = review();
+    let mut stepper = chain.stepper(Context::new());
+    assert_eq!(stepper.next_link(), Some(0));
+
+    let first = stepper.step().await.unwrap();
+    assert_eq!((first.link, first.name.as_deref()), (0, Some("classify")));
+    assert_eq!(stepper.ctx().get::<f64>("score"), Some(0.5));
+    assert_eq!(stepper.next_link(), Some(1));
+
+    // Raising the score after the link ran does not re-route the step already taken
+    let ctx = stepper.ctx().clone().insert("score", 0.9);
+    *stepper.ctx_mut() = ctx;
+    assert_eq!(stepper.step().await.unwrap().link, 1);
+    assert_eq!(stepper.step().await.unwrap().link, 2);
+    assert!(stepper.is_done());
+    assert!(stepper.step().await.is_none());
+
+    let (ctx, report) = stepper.finish().await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<f64>("score"), Some(0.9));
+    assert_eq!(report.steps.iter().map(|s| s.link).collect::<Vec<_>>(), [0, 1, 2]);
+}
+
+#[tokio::test]
+async fn test_steps_follow_branches() {
+    let chain = review();
+    let mut stepper = chain.stepper(Context::new().insert("score", 0.9));
+    assert_eq!(stepper.step().await.unwrap().branch, Some(2));
+    assert_eq!(stepper.next_link(), Some(2));
+    let (ctx, report) = stepper.finish().await;
+    assert_eq!(ctx.get::<bool>("reviewed"), None);
+    assert_eq!(ctx.get::<bool>("approved"), Some(true));
+    assert_eq!(report.branches, [(0, 2)]);
+}
+
+#[tokio::test]
+async fn test_abort_stops_the_run() {
+    let chain = review();
+    let mut stepper = chain.stepper(Context::new());
+    stepper.step().await;
+    let (ctx, report) = stepper.abort().await;
+    assert_eq!(report.status, RunStatus::Cancelled);
+    assert_eq!(ctx.get::<bool>("reviewed"), None);
+    assert_eq!(report.steps.len(), 1);
+}
+
+#[tokio::test]
+async fn test_errors_are_routed_as_in_a_run() {
+    let mut chain = Chain::new();
+    chain.add_fallible_link(check_qty());
+    chain.add_link(mark("charged"));
+    chain.add_link(mark("handled"));
+
+    let mut stepper = chain.stepper(Context::new());
+    stepper.step().await;
+    assert!(stepper.is_done());
+    let (ctx, report) = stepper.abort().await;
+    match report.status {
+        RunStatus::Failed(err) => assert_eq!(err.kind, ErrorKind::InvalidInput),
+        status => panic!("expected a failed run, got {:?}", status),
+    }
+    assert_eq!(ctx.get::<bool>("charged"), None);
+
+    chain.route_errors(0, ErrorRoute::Jump(2));
+    let mut stepper = chain.stepper(Context::new());
+    assert_eq!(stepper.step().await.unwrap().branch, Some(2));
+    let (ctx, report) = stepper.finish().await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("charged"), None);
+    assert_eq!(ctx.get::<bool>("handled"), Some(true));
+}
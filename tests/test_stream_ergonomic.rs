@@ -0,0 +1,166 @@
+//! Test streaming links (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::stream::{collect, pipeline, Producer, Stage, EMIT_BUFFER};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+fn count_to(n: u32) -> Producer<u32> {
+    Arc::new(move |ctx: Context, mut emitter| {
+        Box::pin(async move {
+            for i in 1..=n {
+                emitter.emit(i).await;
+            }
+            ctx.insert("produced", n)
+        })
+    })
+}
+
+#[tokio::test]
+async fn test_collect_gathers_emitted_items_in_order() {
+    let mut chain = Chain::new();
+    chain.add_link(collect("numbers", count_to(100)));
+
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<Vec<u32>>("numbers"), Some((1..=100).collect()));
+    assert_eq!(ctx.get::<u32>("produced"), Some(100));
+}
+
+#[tokio::test]
+async fn test_collect_with_no_items_stores_empty_array() {
+    let ctx = collect("numbers", count_to(0))(Context::new()).await;
+    assert_eq!(ctx.get::<Vec<u32>>("numbers"), Some(vec![]));
+}
+
+#[tokio::test]
+async fn test_collect_from_cloned_emitters() {
+    let producer: Producer<String> = Arc::new(|ctx: Context, emitter| {
+        Box::pin(async move {
+            let workers = (0..3).map(|w| {
+                let mut emitter = emitter.clone();
+                async move {
+                    emitter.emit(format!("w{}", w)).await;
+                }
+            });
+            futures::future::join_all(workers).await;
+            ctx
+        })
+    });
+
+    let ctx = collect("hits", producer)(Context::new()).await;
+    let mut hits = ctx.get::<Vec<String>>("hits").unwrap();
+    hits.sort();
+    assert_eq!(hits, vec!["w0", "w1", "w2"]);
+}
+
+#[tokio::test]
+async fn test_pipeline_stage_starts_before_producer_finishes() {
+    let processed = Arc::new(AtomicUsize::new(0));
+    // The producer only emits the second item once the stage has handled the first.
+    let producer: Producer<u32> = {
+        let processed = processed.clone();
+        Arc::new(move |ctx: Context, mut emitter| {
+            let processed = processed.clone();
+            Box::pin(async move {
+                emitter.emit(1).await;
+                while processed.load(Ordering::SeqCst) < 1 {
+                    tokio::task::yield_now().await;
+                }
+                emitter.emit(2).await;
+                ctx
+            })
+        })
+    };
+    let stage: Stage<u32, u32> = {
+        let processed = processed.clone();
+        Arc::new(move |n| {
+            let processed = processed.clone();
+            Box::pin(async move {
+                processed.fetch_add(1, Ordering::SeqCst);
+                n * 10
+            })
+        })
+    };
+
+    let link = pipeline("results", producer, stage, 1);
+    let ctx = tokio::time::timeout(Duration::from_secs(5), link(Context::new())).await.unwrap();
+    assert_eq!(ctx.get::<Vec<u32>>("results"), Some(vec![10, 20]));
+}
+
+#[tokio::test]
+async fn test_pipeline_keeps_emission_order_with_concurrency() {
+    // Earlier items take longer, so they would finish last without ordering.
+    let stage: Stage<u32, String> = Arc::new(|n| {
+        Box::pin(async move {
+            tokio::time::sleep(Duration::from_millis(u64::from(10 - n) * 2)).await;
+            format!("item-{}", n)
+        })
+    });
+
+    let mut chain = Chain::new();
+    chain.add_link(pipeline("items", count_to(8), stage, 4));
+    let ctx = chain.run(Context::new()).await;
+
+    let expected: Vec<String> = (1..=8).map(|n| format!("item-{}", n)).collect();
+    assert_eq!(ctx.get::<Vec<String>>("items"), Some(expected));
+}
+
+#[tokio::test]
+async fn test_pipeline_limits_items_in_stage() {
+    let in_stage = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let stage: Stage<u32, u32> = {
+        let (in_stage, peak) = (in_stage.clone(), peak.clone());
+        Arc::new(move |n| {
+            let (in_stage, peak) = (in_stage.clone(), peak.clone());
+            Box::pin(async move {
+                let now = in_stage.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                in_stage.fetch_sub(1, Ordering::SeqCst);
+                n
+            })
+        })
+    };
+
+    let ctx = pipeline("out", count_to(12), stage, 3)(Context::new()).await;
+    assert_eq!(ctx.get::<Vec<u32>>("out").map(|out| out.len()), Some(12));
+    assert!(peak.load(Ordering::SeqCst) <= 3);
+}
+
+#[tokio::test]
+async fn test_emit_applies_backpressure() {
+    let emitted = Arc::new(AtomicUsize::new(0));
+    let producer: Producer<u32> = {
+        let emitted = emitted.clone();
+        Arc::new(move |ctx: Context, mut emitter| {
+            let emitted = emitted.clone();
+            Box::pin(async move {
+                for i in 0..(EMIT_BUFFER as u32 * 4) {
+                    emitter.emit(i).await;
+                    emitted.fetch_add(1, Ordering::SeqCst);
+                }
+                ctx
+            })
+        })
+    };
+    // The first item blocks in the stage until the producer has had time to run ahead.
+    let stage: Stage<u32, u32> = {
+        let emitted = emitted.clone();
+        Arc::new(move |n| {
+            let emitted = emitted.clone();
+            Box::pin(async move {
+                if n == 0 {
+                    tokio::time::sleep(Duration::from_millis(50)).await;
+                    assert!(emitted.load(Ordering::SeqCst) <= EMIT_BUFFER + 2);
+                }
+                n
+            })
+        })
+    };
+
+    let ctx = pipeline("out", producer, stage, 1)(Context::new()).await;
+    assert_eq!(ctx.get::<Vec<u32>>("out").map(|out| out.len()), Some(EMIT_BUFFER * 4));
+}
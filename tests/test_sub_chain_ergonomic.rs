@@ -0,0 +1,102 @@
+//! Test embedding a chain as a step of another chain (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, RunError, RunStatus, Shadow};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::middleware::Middleware;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+struct CountBefore(Arc<AtomicUsize>);
+
+impl Middleware<Context> for CountBefore {
+    fn before<'a>(&'a self, _ctx: &'a Context) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>> {
+        Box::pin(async move {
+            self.0.fetch_add(1, Ordering::SeqCst);
+        })
+    }
+}
+
+fn set(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+fn pricing(calls: &Arc<AtomicUsize>) -> Arc<Chain> {
+    let mut chain = Chain::new();
+    chain.set_name("pricing");
+    chain.declare_input(["order"]);
+    chain.use_middleware(Arc::new(CountBefore(calls.clone())));
+    chain.add_link_with(set("subtotal"), LinkSpec::new().requires(["order"]).provides(["subtotal"]));
+    chain.add_link_with(set("tax"), LinkSpec::new().requires(["subtotal"]).provides(["tax"]));
+    Arc::new(chain)
+}
+
+#[tokio::test]
+async fn test_sub_chain_runs_as_one_step_with_its_middleware() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let mut chain = Chain::new();
+    chain.add_link(set("validated"));
+    chain.add_chain(pricing(&calls));
+    chain.add_link(set("charged"));
+
+    let (ctx, report) = chain.run_with_report(Context::new().insert("order", 1)).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    for key in ["validated", "subtotal", "tax", "charged"] {
+        assert_eq!(ctx.get::<bool>(key), Some(true), "{}", key);
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(report.steps.len(), 3);
+    assert_eq!(report.steps[1].name.as_deref(), Some("pricing"));
+}
+
+#[tokio::test]
+async fn test_sub_chain_spec_summarizes_the_chain() {
+    let mut chain = Chain::new();
+    chain.declare_input(["order"]);
+    chain.add_chain(pricing(&Arc::default()));
+    chain.add_link_with(set("charged"), LinkSpec::new().requires(["tax"]));
+    assert_eq!(chain.link_position("pricing"), Some(0));
+    assert_eq!(chain.link_specs()[0].requires, ["order"]);
+    assert_eq!(chain.link_specs()[0].provides, ["subtotal", "tax"]);
+    assert_eq!(chain.validate(), Ok(()));
+
+    let mut missing_input = Chain::new();
+    missing_input.add_chain(pricing(&Arc::default()));
+    assert!(missing_input.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_failed_sub_run_fails_the_parent() {
+    let mut inner = Chain::new();
+    inner.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::warn("card declined once");
+        ctx_tools::fail_run(RunError::invalid_input("card declined"));
+        ctx
+    })));
+    let mut chain = Chain::new();
+    chain.add_chain(Arc::new(inner));
+    chain.add_link(set("shipped"));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    let RunStatus::Failed(err) = report.status else { panic!("run should fail") };
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+    assert_eq!(report.warnings, ["card declined once"]);
+    assert_eq!(ctx.get::<bool>("shipped"), None);
+}
+
+#[tokio::test]
+async fn test_side_effecting_sub_chain_is_skipped_in_shadow_runs() {
+    let mut inner = Chain::new();
+    inner.set_name("notify");
+    inner.add_link_with(set("emailed"), LinkSpec::new().name("email").side_effects());
+    let mut chain = Chain::new();
+    chain.add_chain(Arc::new(inner));
+
+    let shadow = Shadow::new();
+    let (ctx, _) = chain.run_shadow(Context::new(), &shadow).await;
+    assert_eq!(ctx.get::<bool>("emailed"), None);
+    assert_eq!(shadow.calls()[0].name.as_deref(), Some("notify"));
+}
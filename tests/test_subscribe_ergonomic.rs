@@ -0,0 +1,48 @@
+//! Test result broadcast subscriptions (ergonomic pattern)
+
+use futures::StreamExt;
+use modulink_rs::chains::{Chain, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use std::sync::Arc;
+
+fn double_link() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move {
+        let x = ctx.get::<i32>("x").unwrap_or(0);
+        ctx.insert("x", x * 2)
+    }))
+}
+
+#[tokio::test]
+async fn test_subscribers_receive_every_run() {
+    let mut chain = Chain::new();
+    chain.add_link(double_link());
+    let mut first = chain.subscribe();
+    let mut second = chain.subscribe();
+
+    let _ = chain.run(Context::new().insert("x", 1)).await;
+    let _ = chain.run(Context::new().insert("x", 5)).await;
+
+    for rx in [&mut first, &mut second] {
+        let a = rx.next().await.unwrap();
+        let b = rx.next().await.unwrap();
+        assert_eq!(a.ctx.get::<i32>("x"), Some(2));
+        assert_eq!(b.ctx.get::<i32>("x"), Some(10));
+        assert_eq!(a.report.status, RunStatus::Completed);
+    }
+}
+
+#[tokio::test]
+async fn test_full_or_dropped_subscribers_do_not_block_runs() {
+    let mut chain = Chain::new();
+    chain.add_link(double_link());
+    let mut slow = chain.subscribe_with_capacity(0);
+    let dropped = chain.subscribe();
+    drop(dropped);
+
+    for x in 0..5 {
+        let _ = chain.run(Context::new().insert("x", x)).await;
+    }
+    let first = slow.next().await.unwrap();
+    assert_eq!(first.ctx.get::<i32>("x"), Some(0));
+}
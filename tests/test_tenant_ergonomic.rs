@@ -0,0 +1,216 @@
+//! Test tenant metadata and cross-tenant guard middleware (ergonomic pattern)
+
+use modulink_rs::cache::{CacheStore, MemoryCache};
+use modulink_rs::chains::{Chain, Checkpoint, CheckpointStore, ErrorKind, MemoryCheckpointStore, RunStatus};
+use modulink_rs::context::{meta, Context};
+use modulink_rs::links::Link;
+use modulink_rs::quota::{QuotaLimits, QuotaMiddleware, Quotas};
+use modulink_rs::tenant::{TenantCache, TenantCheckpointStore, TenantGuardMiddleware, TenantQuotas, TenantScope};
+use serde_json::json;
+use std::sync::Arc;
+
+fn store_key_link(tenant: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move {
+        ctx.insert(TenantScope::for_tenant(tenant).key("checkpoint"), true)
+    }))
+}
+
+fn mark_link(key: &'static str) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, true) }))
+}
+
+#[test]
+fn test_tenant_scope_keys() {
+    let scope = TenantScope::for_tenant("acme");
+    let key = scope.key("queue/jobs");
+    assert_eq!(key, "tenant/acme/queue/jobs");
+    assert_eq!(scope.strip(&key), Some("queue/jobs"));
+    assert_eq!(TenantScope::for_tenant("globex").strip(&key), None);
+    assert_eq!(Context::new().with_tenant("acme").tenant(), Some("acme".to_string()));
+}
+
+#[tokio::test]
+async fn test_same_tenant_access_completes() {
+    let mut chain = Chain::new();
+    chain.add_link(store_key_link("acme"));
+    chain.add_link(mark_link("done"));
+    chain.use_middleware(Arc::new(TenantGuardMiddleware::new().require_tenant()));
+
+    let (ctx, report) = chain.run_with_report(Context::new().with_tenant("acme")).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<bool>("done"), Some(true));
+}
+
+#[tokio::test]
+async fn test_cross_tenant_access_fails_run() {
+    let mut chain = Chain::new();
+    chain.add_link(store_key_link("globex"));
+    chain.add_link(mark_link("done"));
+    chain.use_middleware(Arc::new(TenantGuardMiddleware::new()));
+
+    let (ctx, report) = chain.run_with_report(Context::new().with_tenant("acme")).await;
+    match report.status {
+        RunStatus::Failed(err) => assert_eq!(err.kind, ErrorKind::Forbidden),
+        other => panic!("expected failure, got {:?}", other),
+    }
+    assert_eq!(ctx.get::<bool>("done"), None);
+}
+
+#[tokio::test]
+async fn test_missing_tenant_rejected_when_required() {
+    let mut chain = Chain::new();
+    chain.add_link(mark_link("done"));
+    chain.use_middleware(Arc::new(TenantGuardMiddleware::new().require_tenant()));
+
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref err) if err.kind.http_status() == 403));
+    assert_eq!(ctx.get::<bool>("done"), None);
+}
+
+#[tokio::test]
+async fn test_values_that_look_like_scoped_keys_are_data() {
+    let mut chain = Chain::new();
+    chain.add_link(mark_link("done"));
+    chain.use_middleware(Arc::new(TenantGuardMiddleware::new()));
+
+    let ctx = Context::new().with_tenant("acme").insert("note", "moved from tenant/globex/invoices");
+    let (_, report) = chain.run_with_report(ctx.insert("path", "tenant/globex/x")).await;
+    assert_eq!(report.status, RunStatus::Completed);
+}
+
+#[tokio::test]
+async fn test_tenant_comes_from_the_auth_claim() {
+    let tenant_link: Link = Arc::new(|ctx: Context| Box::pin(async move {
+        let tenant = ctx.tenant();
+        ctx.insert("seen_tenant", tenant)
+    }));
+    let mut chain = Chain::new();
+    chain.add_link(tenant_link);
+    chain.use_middleware(Arc::new(TenantGuardMiddleware::new().require_tenant().with_claim("org")));
+
+    let claimed = Context::new().insert(meta::AUTH, json!({ "sub": "ada", "org": "acme" })).with_tenant("globex");
+    let (ctx, report) = chain.run_with_report(claimed).await;
+    assert_eq!(report.status, RunStatus::Completed);
+    assert_eq!(ctx.get::<String>("seen_tenant").as_deref(), Some("acme"));
+
+    // A tenant without a claim to back it is not trusted
+    let unclaimed = Context::new().insert(meta::AUTH, json!({ "sub": "ada" })).with_tenant("globex");
+    let (_, report) = chain.run_with_report(unclaimed).await;
+    assert!(matches!(report.status, RunStatus::Failed(ref err) if err.kind == ErrorKind::Forbidden));
+}
+
+fn checkpoint(run_id: &str, tenant: &str) -> Checkpoint {
+    Checkpoint {
+        run_id: run_id.to_string(),
+        version: None,
+        link: 1,
+        link_name: None,
+        ctx: json!({ "_tenant": tenant }),
+        wake_at_ms: Some(10),
+        awaiting_event: None,
+    }
+}
+
+#[tokio::test]
+async fn test_tenant_checkpoint_stores_share_one_backend() {
+    let shared = Arc::new(MemoryCheckpointStore::new());
+    let acme = TenantCheckpointStore::new(TenantScope::for_tenant("acme"), shared.clone());
+    let globex = TenantCheckpointStore::new(TenantScope::for_tenant("globex"), shared.clone());
+
+    acme.save(&checkpoint("run-1", "acme")).await.unwrap();
+    globex.save(&checkpoint("run-1", "globex")).await.unwrap();
+    assert_eq!(shared.len(), 2);
+    assert!(shared.load("tenant/acme/run-1").await.unwrap().is_some());
+    assert_eq!(acme.load("run-1").await.unwrap().unwrap().run_id, "run-1");
+    assert_eq!(acme.due(100).await.unwrap().len(), 1);
+
+    let err = acme.save(&checkpoint("run-2", "globex")).await.unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    acme.remove("run-1").await.unwrap();
+    assert!(acme.load("run-1").await.unwrap().is_none());
+    assert!(globex.load("run-1").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn test_tenant_cache_and_quotas() {
+    let shared = Arc::new(MemoryCache::new(100));
+    let acme = TenantCache::new(TenantScope::for_tenant("acme"), shared.clone());
+    let globex = TenantCache::new(TenantScope::for_tenant("globex"), shared.clone());
+    acme.set("idempotency:order-1", json!("done"), None).await.unwrap();
+    assert_eq!(acme.get("idempotency:order-1").await.unwrap(), Some(json!("done")));
+    assert_eq!(globex.get("idempotency:order-1").await.unwrap(), None);
+    assert_eq!(shared.get("tenant/acme/idempotency:order-1").await.unwrap(), Some(json!("done")));
+
+    let quotas = Arc::new(Quotas::new(QuotaLimits::new().with_requests_per_day(1)));
+    let mut chain = Chain::new();
+    chain.add_link(mark_link("done"));
+    chain.use_middleware(Arc::new(QuotaMiddleware::new(quotas.clone()).per_tenant()));
+    let request = |tenant: &str| Context::new().insert(meta::AUTH, json!({ "sub": "key-1" })).with_tenant(tenant);
+    assert_eq!(chain.run_with_report(request("acme")).await.1.status, RunStatus::Completed);
+    assert_eq!(chain.run_with_report(request("globex")).await.1.status, RunStatus::Completed);
+    assert!(matches!(chain.run_with_report(request("acme")).await.1.status, RunStatus::Failed(_)));
+
+    let acme = TenantQuotas::new(TenantScope::for_tenant("acme"), quotas.clone());
+    assert_eq!(acme.usage("key-1").requests, 1);
+    assert_eq!(acme.all_usage().iter().map(|u| u.key.as_str()).collect::<Vec<_>>(), vec!["key-1"]);
+    assert!(acme.reset("key-1"));
+    assert_eq!(quotas.usage("tenant/globex/key-1").requests, 1);
+}
+
+#[cfg(feature = "http")]
+#[tokio::test]
+async fn test_http_listener_drops_reserved_keys_from_the_body() {
+    use modulink_rs::listeners::{BaseListenerAsync, HttpListener};
+
+    let mut chain = Chain::new();
+    chain.add_link(mark_link("done"));
+    let listener = HttpListener::for_chain(Arc::new(chain), "127.0.0.1:8108");
+    tokio::spawn(async move { listener.start().await });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let body = json!({ "order": 1, "_tenant": "globex", "_decisions": ["approved"], "_anything": 1 });
+    let resp = reqwest::Client::new().post("http://127.0.0.1:8108/run").json(&body).send().await.unwrap();
+    let result: serde_json::Value = resp.json().await.unwrap();
+    assert_eq!(result["order"], json!(1));
+    assert_eq!(result["done"], json!(true));
+    for key in [meta::TENANT, meta::DECISIONS, "_anything"] {
+        assert!(result.get(key).is_none(), "{} was taken from the body", key);
+    }
+}
+
+struct Messages(std::sync::Mutex<Vec<&'static str>>);
+
+#[async_trait::async_trait]
+impl modulink_rs::listeners::KafkaConsumer for Messages {
+    async fn recv(&self, _topic: &str) -> std::io::Result<Option<Vec<u8>>> {
+        let mut messages = self.0.lock().unwrap();
+        Ok(if messages.is_empty() { None } else { Some(messages.remove(0).as_bytes().to_vec()) })
+    }
+}
+
+#[tokio::test]
+async fn test_kafka_listener_drops_reserved_keys_from_messages() {
+    use modulink_rs::listeners::{BaseListenerAsync, KafkaListener};
+
+    let seen = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let handler: Link = {
+        let seen = seen.clone();
+        Arc::new(move |ctx: Context| {
+            let seen = seen.clone();
+            Box::pin(async move {
+                seen.lock().unwrap().push(ctx.clone());
+                ctx
+            })
+        })
+    };
+    let message = r#"{"order": 1, "_tenant": "globex", "_auth": {"sub": "root"}, "_anything": 1}"#;
+    let listener = KafkaListener { consumer: Arc::new(Messages(std::sync::Mutex::new(vec![message]))), topic: "orders".to_string(), handler };
+    listener.start().await.unwrap();
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert_eq!(seen[0].get::<i64>("order"), Some(1));
+    for key in [meta::TENANT, meta::AUTH, "_anything"] {
+        assert!(!seen[0].0.contains_key(key), "{} was taken from the message", key);
+    }
+}
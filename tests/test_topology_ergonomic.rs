@@ -0,0 +1,76 @@
+//! Test describing a chain's shape as a graph (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, EdgeKind, ErrorRoute, Topology};
+use modulink_rs::context::Context;
+use modulink_rs::definitions::ChainDefinition;
+use modulink_rs::links::{Link, LinkSpec};
+use modulink_rs::registry;
+use std::sync::Arc;
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+#[test]
+fn test_topology_lists_links_and_edges() {
+    let mut chain = Chain::new();
+    chain.set_name("refunds");
+    chain.declare_input(["order_id"]);
+    chain.add_link_with(noop(), LinkSpec::new().name("lookup").provides(["order"]));
+    chain.add_link_with(noop(), LinkSpec::new().name("review").description("Manual review").requires(["order"]));
+    chain.add_link_with(noop(), LinkSpec::new().name("refund").side_effects());
+    chain.add_link_with(noop(), LinkSpec::new().name("notify"));
+    chain.connect_described(0, 2, "order.total < 50", |ctx: &Context| ctx.get::<bool>("small") == Some(true));
+    chain.connect(1, 0, |ctx: &Context| ctx.get::<bool>("retry") == Some(true));
+    chain.jump(2, 3);
+    chain.route_errors(0, ErrorRoute::Jump(3));
+
+    let topology = chain.topology();
+    assert_eq!(topology.name.as_deref(), Some("refunds"));
+    assert_eq!(topology.input, ["order_id"]);
+    assert_eq!(topology.nodes.len(), 4);
+    assert_eq!(topology.node("review").unwrap().spec.description.as_deref(), Some("Manual review"));
+    assert!(topology.node("refund").unwrap().spec.side_effects);
+
+    let edges: Vec<_> = topology.edges.iter().map(|e| (e.source, e.target, e.kind, e.condition.as_deref())).collect();
+    assert_eq!(edges, [
+        (0, 1, EdgeKind::Next, None),
+        (1, 2, EdgeKind::Next, None),
+        (2, 3, EdgeKind::Next, None),
+        (0, 2, EdgeKind::Branch, Some("order.total < 50")),
+        (1, 0, EdgeKind::Branch, None),
+        (2, 3, EdgeKind::Jump, None),
+        (0, 3, EdgeKind::Error, None),
+    ]);
+    assert_eq!(topology.edges_from(0).count(), 3);
+}
+
+#[test]
+fn test_topology_round_trips_through_json() {
+    let mut chain = Chain::new();
+    chain.add_link_with(noop(), LinkSpec::new().name("a"));
+    chain.add_link(noop());
+    let topology = chain.topology();
+    let json = topology.to_json();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["nodes"][0]["name"], "a");
+    assert_eq!(value["edges"][0]["kind"], "next");
+    assert_eq!(serde_json::from_str::<Topology>(&json).unwrap(), topology);
+}
+
+#[test]
+fn test_definition_branches_are_described() {
+    registry::register_link("topology_step", noop());
+    let def = ChainDefinition::from_json(r#"{
+        "name": "routed",
+        "links": [ { "link": "topology_step" }, { "link": "topology_step" }, { "link": "topology_step" } ],
+        "branches": [
+            { "from": 0, "to": 2, "when": "ctx.score >= 0.8" },
+            { "from": 1, "to": 0, "when": { "key": "retry", "equals": true } },
+            { "from": 2, "to": 1, "when": { "key": "again" } }
+        ]
+    }"#).unwrap();
+    let topology = def.build(None).unwrap().topology();
+    let conditions: Vec<_> = topology.edges.iter().filter(|e| e.kind == EdgeKind::Branch).map(|e| e.condition.as_deref().unwrap()).collect();
+    assert_eq!(conditions, ["ctx.score >= 0.8", "retry == true", "again"]);
+}
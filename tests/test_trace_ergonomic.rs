@@ -0,0 +1,117 @@
+//! Test execution trace recording and replay (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, ExecutionTrace, RunError, RunStatus, Shadow, TraceRecorder};
+use modulink_rs::context::Context;
+use modulink_rs::ctx_tools;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn set(key: &'static str, value: i64) -> Link {
+    Arc::new(move |ctx: Context| Box::pin(async move { ctx.insert(key, value) }))
+}
+
+// price -> discount (buggy or fixed) -> charge (side effect)
+fn checkout(discount_pct: i64, charges: Arc<AtomicUsize>) -> Chain {
+    let mut chain = Chain::new();
+    chain.set_name("checkout");
+    chain.add_link_with(set("price", 200), LinkSpec::new().name("price"));
+    chain.add_link_with(
+        Arc::new(move |ctx: Context| Box::pin(async move {
+            let price = ctx.get::<i64>("price").unwrap();
+            ctx.insert("total", price - price * discount_pct / 100)
+        })),
+        LinkSpec::new().name("discount"),
+    );
+    chain.add_link_with(
+        Arc::new(move |ctx: Context| {
+            let charges = charges.clone();
+            Box::pin(async move {
+                charges.fetch_add(1, Ordering::SeqCst);
+                ctx.insert("charged", true)
+            })
+        }),
+        LinkSpec::new().name("charge").side_effects(),
+    );
+    chain
+}
+
+#[tokio::test]
+async fn test_trace_records_steps_and_branches() {
+    let recorder = Arc::new(TraceRecorder::new());
+    let mut chain = Chain::new();
+    chain.add_link_with(set("a", 1), LinkSpec::new().name("first"));
+    chain.add_link(set("b", 2));
+    chain.add_link(set("c", 3));
+    chain.connect(0, 2, |ctx: &Context| ctx.get::<bool>("skip") == Some(true));
+    chain.record_traces(recorder.clone());
+
+    chain.run(Context::new().insert("skip", true)).await;
+    let trace = recorder.last().unwrap();
+    assert_eq!(trace.status, RunStatus::Completed);
+    assert_eq!(trace.input, serde_json::json!({"skip": true}));
+    assert_eq!(trace.steps.iter().map(|s| s.link).collect::<Vec<_>>(), vec![0, 2]);
+    assert_eq!(trace.steps[0].name.as_deref(), Some("first"));
+    assert_eq!(trace.steps[0].branch, Some(2));
+    assert_eq!(trace.steps[1].branch, None);
+    assert_eq!(trace.steps[1].before, serde_json::json!({"skip": true, "a": 1}));
+    assert_eq!(trace.output, serde_json::json!({"skip": true, "a": 1, "c": 3}));
+    assert_eq!(ExecutionTrace::from_json(&trace.to_json()).unwrap(), trace);
+
+    // Shadow runs are not traced; the recorder keeps the latest traces.
+    chain.run_shadow(Context::new(), &Shadow::new()).await;
+    assert_eq!(recorder.traces().len(), 1);
+    let small = Arc::new(TraceRecorder::with_capacity(2));
+    chain.record_traces(small.clone());
+    for i in 0..3 {
+        chain.run(Context::new().insert("i", i)).await;
+    }
+    assert_eq!(small.traces().iter().map(|t| t.input["i"].as_i64().unwrap()).collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[tokio::test]
+async fn test_replay_finds_divergence_without_side_effects() {
+    let charges = Arc::new(AtomicUsize::new(0));
+    let recorder = Arc::new(TraceRecorder::new());
+    let mut buggy = checkout(150, charges.clone());
+    buggy.record_traces(recorder.clone());
+    buggy.run(Context::new()).await;
+    let trace = ExecutionTrace::from_json(&recorder.last().unwrap().to_json()).unwrap();
+    assert_eq!(trace.chain.as_deref(), Some("checkout"));
+    assert_eq!(trace.output["total"], -100);
+
+    // Replaying against the same code reproduces the run.
+    assert!(buggy.replay(&trace).await.unwrap().first_divergence().is_none());
+
+    let fixed = checkout(15, charges.clone());
+    let replay = fixed.replay(&trace).await.unwrap();
+    let diverged = replay.first_divergence().unwrap();
+    assert_eq!(diverged.name.as_deref(), Some("discount"));
+    assert_eq!(diverged.replayed["total"], 170);
+    assert!(replay.steps[2].skipped);
+    assert_eq!(charges.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn test_failed_steps_are_traced_and_replayed() {
+    let recorder = Arc::new(TraceRecorder::new());
+    let mut chain = Chain::new();
+    chain.add_link(set("a", 1));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move {
+        ctx_tools::fail_run(RunError::invalid_input("a is odd"));
+        ctx
+    })));
+    chain.add_link(set("b", 2));
+    chain.record_traces(recorder.clone());
+    chain.run(Context::new()).await;
+
+    let trace = recorder.last().unwrap();
+    assert!(matches!(&trace.status, RunStatus::Failed(err) if err.kind == ErrorKind::InvalidInput));
+    assert_eq!(trace.steps.len(), 2);
+    let replay = chain.replay(&trace).await.unwrap();
+    assert_eq!(replay.steps[1].error.as_ref().map(|e| e.kind), Some(ErrorKind::InvalidInput));
+
+    let mut other = Chain::new();
+    other.add_link(set("a", 1));
+    assert!(other.replay(&trace).await.is_err());
+}
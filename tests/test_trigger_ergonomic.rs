@@ -0,0 +1,71 @@
+//! Test debounce/throttle trigger wrappers (ergonomic pattern)
+
+use modulink_rs::context::Context;
+use modulink_rs::links::Link;
+use modulink_rs::listeners::{Debounce, Throttle};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+fn recorder(runs: Arc<Mutex<Vec<Vec<i64>>>>) -> Link {
+    Arc::new(move |ctx: Context| {
+        let runs = runs.clone();
+        Box::pin(async move {
+            runs.lock().unwrap().push(ctx.get::<Vec<i64>>("events").unwrap_or_default());
+            ctx
+        })
+    })
+}
+
+fn event(n: i64) -> Context {
+    Context::new().insert("events", vec![n])
+}
+
+fn merge_events(earlier: Context, latest: Context) -> Context {
+    let mut events = earlier.get::<Vec<i64>>("events").unwrap_or_default();
+    events.extend(latest.get::<Vec<i64>>("events").unwrap_or_default());
+    latest.insert("events", events)
+}
+
+#[tokio::test]
+async fn test_debounce_collapses_burst_into_latest() {
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let handler = Debounce::new(Duration::from_millis(50)).wrap(recorder(runs.clone()));
+    for n in 1..=5 {
+        let ctx = handler(event(n)).await;
+        assert_eq!(ctx.get::<Vec<i64>>("events"), Some(vec![n]));
+        tokio::time::sleep(Duration::from_millis(5)).await;
+    }
+    assert!(runs.lock().unwrap().is_empty());
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(*runs.lock().unwrap(), vec![vec![5]]);
+}
+
+#[tokio::test]
+async fn test_debounce_merges_events() {
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let handler = Debounce::new(Duration::from_millis(50)).merge_with(merge_events).wrap(recorder(runs.clone()));
+    for n in 1..=3 {
+        handler(event(n)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    handler(event(4)).await;
+    tokio::time::sleep(Duration::from_millis(150)).await;
+    assert_eq!(*runs.lock().unwrap(), vec![vec![1, 2, 3], vec![4]]);
+}
+
+#[tokio::test]
+async fn test_throttle_runs_leading_and_trailing() {
+    let runs = Arc::new(Mutex::new(Vec::new()));
+    let handler = Throttle::new(Duration::from_millis(80)).merge_with(merge_events).wrap(recorder(runs.clone()));
+    for n in 1..=4 {
+        handler(event(n)).await;
+    }
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(*runs.lock().unwrap(), vec![vec![1]]);
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(*runs.lock().unwrap(), vec![vec![1], vec![2, 3, 4]]);
+    // The interval has closed, so the next event runs right away
+    handler(event(5)).await;
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert_eq!(runs.lock().unwrap().last(), Some(&vec![5]));
+}
@@ -0,0 +1,42 @@
+//! Test the type-state TypedChainBuilder (generic pattern)
+
+use modulink_rs::chains::TypedChainBuilder;
+
+#[derive(Debug, Clone, PartialEq)]
+struct Request {
+    body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Parsed {
+    words: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Counted {
+    words: Vec<String>,
+    count: usize,
+}
+
+async fn parse(req: Request) -> Parsed {
+    Parsed { words: req.body.split_whitespace().map(str::to_string).collect() }
+}
+
+async fn count(parsed: Parsed) -> Counted {
+    let count = parsed.words.len();
+    Counted { words: parsed.words, count }
+}
+
+#[tokio::test]
+async fn test_typed_chain_runs_steps_in_order() {
+    let chain = TypedChainBuilder::<Request>::new()
+        .link(parse)
+        .link(count)
+        .link(|c: Counted| async move { format!("{} words, first: {}", c.count, c.words[0]) })
+        .build();
+    let out = chain.run(Request { body: "hello typed world".to_string() }).await;
+    assert_eq!(out, "3 words, first: hello");
+
+    let again = chain.clone();
+    assert_eq!(again.run(Request { body: "one".to_string() }).await, "1 words, first: one");
+}
@@ -0,0 +1,140 @@
+//! Test declared requires/provides keys and Chain::validate (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorRoute, ResourceLimits, ValidationError, ValidationWarning};
+use modulink_rs::context::Context;
+use modulink_rs::links::{Link, LinkSpec};
+use std::sync::Arc;
+
+fn noop() -> Link {
+    Arc::new(|ctx: Context| Box::pin(async move { ctx }))
+}
+
+#[test]
+fn test_validate_linear_chain() {
+    let mut chain = Chain::new();
+    chain.declare_input(["order_id"]);
+    chain.add_link_with(noop(), LinkSpec::new().requires(["order_id"]).provides(["order"]));
+    chain.add_link(noop());
+    chain.add_link_with(noop(), LinkSpec::new().requires(["order"]).provides(["score"]));
+    assert_eq!(chain.validate(), Ok(()));
+
+    chain.add_link_with(noop(), LinkSpec::new().requires(["score", "customer"]));
+    assert_eq!(
+        chain.validate(),
+        Err(vec![ValidationError::MissingKey { link: 3, key: "customer".to_string() }])
+    );
+}
+
+#[test]
+fn test_validate_follows_branches() {
+    let mut chain = Chain::new();
+    chain.add_link_with(noop(), LinkSpec::new().provides(["order"]));
+    chain.add_link_with(noop(), LinkSpec::new().provides(["discount"]));
+    chain.add_link_with(noop(), LinkSpec::new().requires(["order", "discount"]));
+    assert_eq!(chain.validate(), Ok(()));
+
+    // Skipping link 1 leaves `discount` missing on one path
+    chain.connect(0, 2, |ctx: &Context| ctx.get::<bool>("vip").unwrap_or(false));
+    assert_eq!(
+        chain.validate(),
+        Err(vec![ValidationError::MissingKey { link: 2, key: "discount".to_string() }])
+    );
+
+    chain.connect(2, 7, |_: &Context| true);
+    assert_eq!(chain.validate().unwrap_err().len(), 2);
+}
+
+#[test]
+fn test_endless_loop_is_an_error() {
+    let mut chain = Chain::new();
+    for _ in 0..4 {
+        chain.add_link(noop());
+    }
+    // 1 -> 2 -> 1 with no way out
+    chain.jump(2, 1);
+    let report = chain.validation_report();
+    assert_eq!(report.errors, vec![ValidationError::EndlessLoop(vec![1, 2])]);
+    assert_eq!(report.loops, vec![vec![1, 2]]);
+    assert!(report.warnings.contains(&ValidationWarning::Unreachable(3)));
+    assert_eq!(chain.validate(), Err(vec![ValidationError::EndlessLoop(vec![1, 2])]));
+
+    // A conditional branch taken first gives the loop an exit
+    let mut chain = Chain::new();
+    for _ in 0..4 {
+        chain.add_link(noop());
+    }
+    chain.connect(2, 3, |ctx: &Context| ctx.get::<bool>("done").unwrap_or(false));
+    chain.jump(2, 1);
+    let report = chain.validation_report();
+    assert!(report.is_ok());
+    assert_eq!(report.loops, vec![vec![1, 2]]);
+}
+
+#[test]
+fn test_self_loop_without_exit() {
+    let mut chain = Chain::new();
+    chain.add_link(noop());
+    chain.jump(0, 0);
+    assert_eq!(chain.validate(), Err(vec![ValidationError::EndlessLoop(vec![0])]));
+}
+
+#[test]
+fn test_conditional_loop_warns_without_step_limit() {
+    let mut chain = Chain::new();
+    chain.add_link(noop());
+    chain.add_link(noop());
+    chain.connect(1, 0, |ctx: &Context| ctx.get::<bool>("retry").unwrap_or(false));
+
+    let report = chain.validation_report();
+    assert!(report.is_ok());
+    assert_eq!(report.loops, vec![vec![0, 1]]);
+    assert_eq!(report.warnings, vec![ValidationWarning::UnboundedLoop(vec![0, 1])]);
+
+    chain.set_limits(ResourceLimits::new().with_max_steps(50));
+    let report = chain.validation_report();
+    assert!(report.warnings.is_empty());
+    assert_eq!(report.loops, vec![vec![0, 1]]);
+}
+
+#[test]
+fn test_unreachable_links() {
+    let mut chain = Chain::new();
+    for _ in 0..4 {
+        chain.add_link(noop());
+    }
+    chain.jump(0, 3);
+    let report = chain.validation_report();
+    assert!(report.is_ok());
+    assert_eq!(report.warnings, vec![ValidationWarning::Unreachable(1), ValidationWarning::Unreachable(2)]);
+    assert!(report.loops.is_empty());
+
+    // An error handler is reachable through its error route
+    chain.route_errors(0, ErrorRoute::Jump(2));
+    assert_eq!(chain.validation_report().warnings, vec![ValidationWarning::Unreachable(1)]);
+}
+
+#[test]
+fn test_jump_skips_fall_through_for_data_flow() {
+    let mut chain = Chain::new();
+    chain.add_link(noop());
+    chain.add_link_with(noop(), LinkSpec::new().provides(["discount"]));
+    chain.add_link_with(noop(), LinkSpec::new().requires(["discount"]));
+    // Link 1 never runs, so `discount` is missing at 2
+    chain.jump(0, 2);
+    assert_eq!(
+        chain.validate(),
+        Err(vec![ValidationError::MissingKey { link: 2, key: "discount".to_string() }])
+    );
+}
+
+#[tokio::test]
+async fn test_jump_is_always_taken() {
+    let mut chain = Chain::new();
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("a", true) })));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("b", true) })));
+    chain.add_link(Arc::new(|ctx: Context| Box::pin(async move { ctx.insert("c", true) })));
+    chain.jump(0, 2);
+    let ctx = chain.run(Context::new()).await;
+    assert_eq!(ctx.get::<bool>("b"), None);
+    assert_eq!(ctx.get::<bool>("c"), Some(true));
+}
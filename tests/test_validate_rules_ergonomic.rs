@@ -0,0 +1,110 @@
+//! Test the rule-based validation link (ergonomic pattern)
+
+use modulink_rs::chains::{Chain, ErrorKind, RunStatus};
+use modulink_rs::context::Context;
+use modulink_rs::definitions::{ChainDefinition, DefinitionError};
+use modulink_rs::std_links::{validate, Rule, Rules, Violation};
+use serde_json::json;
+
+fn order() -> Context {
+    Context::new()
+        .insert("order", json!({ "id": "o-1", "lines": [{ "sku": "A-1", "qty": 2 }] }))
+        .insert("email", "ana@example.com")
+        .insert("status", "paid")
+        .insert("starts", 10)
+        .insert("ends", 12)
+}
+
+fn order_rules() -> Rules {
+    Rules::new()
+        .required("order.id")
+        .required("order.lines.0.sku")
+        .pattern("email", r"^[^@\s]+@[^@\s]+$")
+        .range("order.lines.0.qty", Some(1.0), Some(100.0))
+        .one_of("status", ["new", "paid", "shipped"])
+        .check("ctx.ends >= ctx.starts", "ends before it starts")
+        .when("ctx.status == 'shipped'", Rules::new().required("tracking"))
+}
+
+async fn violations(rules: Rules, ctx: Context) -> Vec<Violation> {
+    let mut chain = Chain::new();
+    chain.add_link(validate(rules).unwrap());
+    chain.run(ctx).await.get::<Vec<Violation>>("violations").unwrap()
+}
+
+#[tokio::test]
+async fn test_valid_context_has_no_violations() {
+    assert_eq!(violations(order_rules(), order()).await, vec![]);
+    // Rules about a field other than `required` pass when it is absent.
+    assert_eq!(violations(order_rules(), order().insert("email", json!(null))).await, vec![]);
+}
+
+#[tokio::test]
+async fn test_every_violation_is_listed() {
+    let ctx = order()
+        .insert("order", json!({ "lines": [{ "sku": "A-1", "qty": 0 }] }))
+        .insert("email", "not an email")
+        .insert("status", "shipped")
+        .insert("ends", 9);
+    let found = violations(order_rules(), ctx).await;
+    let summary: Vec<(&str, Option<&str>)> = found.iter().map(|v| (v.rule.as_str(), v.field.as_deref())).collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("required", Some("order.id")),
+            ("pattern", Some("email")),
+            ("range", Some("order.lines.0.qty")),
+            ("check", None),
+            ("required", Some("tracking")),
+        ]
+    );
+    assert_eq!(found[3].message, "ends before it starts");
+
+    let found = violations(order_rules(), order().insert("status", "lost").insert("email", 7)).await;
+    assert_eq!(found.iter().map(|v| v.rule.as_str()).collect::<Vec<_>>(), vec!["pattern", "one_of"]);
+    assert_eq!(found[0].message, "'email' must be text");
+}
+
+#[tokio::test]
+async fn test_fail_run_and_custom_key() {
+    let mut chain = Chain::new();
+    chain.add_link(validate(Rules::new().required("email").into_key("problems").fail_run()).unwrap());
+    let (ctx, report) = chain.run_with_report(Context::new()).await;
+    match report.status {
+        RunStatus::Failed(err) => {
+            assert_eq!(err.kind, ErrorKind::InvalidInput);
+            assert_eq!(err.message, "'email' is required");
+        }
+        other => panic!("expected a failed run, got {:?}", other),
+    }
+    assert_eq!(ctx.get::<Vec<Violation>>("problems").unwrap().len(), 1);
+}
+
+#[test]
+fn test_invalid_rules_are_rejected() {
+    assert!(validate(Rules::new().pattern("email", "(unclosed")).is_err());
+    assert!(validate(Rules::new().check("ctx.a >", "broken")).is_err());
+    assert!(validate(Rules::new().when("ctx.ok", Rules::new().check("nope", "bad"))).is_err());
+}
+
+#[tokio::test]
+async fn test_rules_in_a_chain_definition() {
+    let def = ChainDefinition::from_json(
+        r#"{ "name": "intake", "links": [ { "validate": { "rules": [
+            { "rule": "required", "field": "email" },
+            { "rule": "range", "field": "qty", "min": 1 },
+            { "rule": "when", "expr": "ctx.gift == true", "then": [ { "rule": "required", "field": "message" } ] }
+        ] } } ] }"#,
+    )
+    .unwrap();
+    let chain = def.build(None).unwrap();
+    let ctx = chain.run(Context::new().insert("qty", 0).insert("gift", true)).await;
+    let found = ctx.get::<Vec<Violation>>("violations").unwrap();
+    assert_eq!(found.iter().map(|v| v.field.as_deref().unwrap()).collect::<Vec<_>>(), vec!["email", "qty", "message"]);
+
+    let rules: Rules = serde_json::from_value(json!({ "rules": [{ "rule": "one_of", "field": "s", "values": [1, 2] }] })).unwrap();
+    assert_eq!(rules.rules, vec![Rule::OneOf { field: "s".into(), values: vec![json!(1), json!(2)] }]);
+
+    let broken = ChainDefinition::from_json(r#"{ "name": "x", "links": [ { "validate": { "rules": [ { "rule": "pattern", "field": "a", "regex": "[" } ] } } ] }"#).unwrap();
+    assert!(matches!(broken.build(None), Err(DefinitionError::Parse(_))));
+}
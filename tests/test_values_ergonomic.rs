@@ -0,0 +1,87 @@
+//! Test the timestamp, duration, and bytes encodings of context values (ergonomic pattern)
+
+use modulink_rs::context::values::{self, format_rfc3339, parse_rfc3339};
+use modulink_rs::context::{Context, ContextMutable};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::{Duration, UNIX_EPOCH};
+
+#[test]
+fn test_time_round_trips_as_rfc3339() {
+    let at = UNIX_EPOCH + Duration::from_millis(1_714_566_600_250);
+    let ctx = Context::new().insert_time("created_at", at);
+    assert_eq!(ctx.get::<String>("created_at").as_deref(), Some("2024-05-01T12:30:00.25Z"));
+    assert_eq!(ctx.get_time("created_at"), Some(at));
+}
+
+#[test]
+fn test_rfc3339_formats_edge_dates() {
+    assert_eq!(format_rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00Z");
+    assert_eq!(format_rfc3339(UNIX_EPOCH + Duration::from_secs(951_782_400)), "2000-02-29T00:00:00Z");
+    assert_eq!(format_rfc3339(UNIX_EPOCH - Duration::from_millis(500)), "1969-12-31T23:59:59.5Z");
+    assert_eq!(format_rfc3339(UNIX_EPOCH + Duration::new(0, 1)), "1970-01-01T00:00:00.000000001Z");
+}
+
+#[test]
+fn test_rfc3339_parses_offsets_to_utc() {
+    let utc = parse_rfc3339("2024-05-01T12:30:00Z").unwrap();
+    assert_eq!(parse_rfc3339("2024-05-01T14:30:00+02:00"), Some(utc));
+    assert_eq!(parse_rfc3339("2024-05-01 07:30:00-05:00"), Some(utc));
+    assert_eq!(parse_rfc3339("1969-12-31T23:59:59.5Z"), Some(UNIX_EPOCH - Duration::from_millis(500)));
+}
+
+#[test]
+fn test_rfc3339_rejects_invalid_timestamps() {
+    for invalid in ["2024-05-01", "2024-05-01T12:30:00", "2024-02-30T00:00:00Z", "2024-13-01T00:00:00Z", "2024-05-01T12:30:00.Z", "2024-05-01T12:30:00+2:00", "yesterday at noon!!"] {
+        assert_eq!(parse_rfc3339(invalid), None, "{}", invalid);
+    }
+}
+
+#[test]
+fn test_duration_round_trips_as_millis() {
+    let ctx = Context::new().insert_duration("timeout", Duration::from_millis(1500));
+    assert_eq!(ctx.get::<u64>("timeout"), Some(1500));
+    assert_eq!(ctx.get_duration("timeout"), Some(Duration::from_millis(1500)));
+    assert_eq!(ctx.insert("timeout", "soon").get_duration("timeout"), None);
+}
+
+#[test]
+fn test_bytes_round_trip_as_base64() {
+    let ctx = Context::new().insert_bytes("blob", b"hello\x00");
+    assert_eq!(ctx.get::<String>("blob").as_deref(), Some("aGVsbG8A"));
+    assert_eq!(ctx.get_bytes("blob"), Some(b"hello\x00".to_vec()));
+    assert_eq!(ctx.insert("blob", "not base64!").get_bytes("blob"), None);
+}
+
+#[test]
+fn test_mutable_context_uses_the_same_encodings() {
+    let mut ctx = ContextMutable::new();
+    ctx.insert_time("at", UNIX_EPOCH);
+    ctx.insert_duration("timeout", Duration::from_secs(1));
+    ctx.insert_bytes("blob", [1, 2, 3]);
+    assert_eq!(ctx.get::<String>("at").as_deref(), Some("1970-01-01T00:00:00Z"));
+    assert_eq!(ctx.get_duration("timeout"), Some(Duration::from_secs(1)));
+    assert_eq!(ctx.get_bytes("blob"), Some(vec![1, 2, 3]));
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Upload {
+    #[serde(with = "values::as_rfc3339")]
+    at: std::time::SystemTime,
+    #[serde(with = "values::as_millis")]
+    took: Duration,
+    #[serde(with = "values::as_base64")]
+    body: Vec<u8>,
+}
+
+#[test]
+fn test_typed_fields_match_context_encodings() {
+    let upload = Upload { at: UNIX_EPOCH, took: Duration::from_millis(20), body: vec![255] };
+    let value = serde_json::to_value(&upload).unwrap();
+    assert_eq!(value, json!({ "at": "1970-01-01T00:00:00Z", "took": 20, "body": "/w==" }));
+    assert_eq!(serde_json::from_value::<Upload>(value.clone()).unwrap(), upload);
+
+    let ctx: Context = serde_json::from_value(value).unwrap();
+    assert_eq!(ctx.get_time("at"), Some(UNIX_EPOCH));
+    assert_eq!(ctx.get_bytes("body"), Some(vec![255]));
+}
@@ -0,0 +1,141 @@
+//! Test the signed, retrying webhook sink (ergonomic pattern)
+#![cfg(feature = "http-sink")]
+
+use axum::body::Bytes;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use futures::StreamExt;
+use modulink_rs::chains::{Backoff, Chain, RetryPolicy};
+use modulink_rs::context::Context;
+use modulink_rs::sinks::webhook_sink::{signature, DeadLetter, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use modulink_rs::sinks::{ChannelSink, Sink, WebhookSink};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default)]
+struct Hook {
+    // Status answered to each request until it runs out, then 200
+    statuses: Mutex<Vec<u16>>,
+    received: Mutex<Vec<(HeaderMap, Bytes)>>,
+}
+
+async fn serve(hook: Arc<Hook>) -> String {
+    let app = Router::new()
+        .route("/hook", post(|State(hook): State<Arc<Hook>>, headers: HeaderMap, body: Bytes| async move {
+            hook.received.lock().unwrap().push((headers, body));
+            let mut statuses = hook.statuses.lock().unwrap();
+            let status = if statuses.is_empty() { 200 } else { statuses.remove(0) };
+            StatusCode::from_u16(status).unwrap()
+        }))
+        .with_state(hook);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}/hook", addr)
+}
+
+fn quick_retries(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy::new(max_attempts).with_backoff(Backoff::Exponential { initial: Duration::from_millis(5), max: Duration::from_millis(20) })
+}
+
+#[tokio::test]
+async fn test_deliveries_are_signed() {
+    let hook = Arc::new(Hook::default());
+    let url = serve(hook.clone()).await;
+    let mut chain = Chain::new();
+    chain.pipe_to(Arc::new(WebhookSink::new(url).with_secret("s3cret")));
+    chain.run(Context::new().insert("order", 42)).await;
+
+    let received = hook.received.lock().unwrap();
+    let (headers, body) = &received[0];
+    let timestamp: u64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+    assert_eq!(headers[SIGNATURE_HEADER].to_str().unwrap(), signature(b"s3cret", timestamp, body));
+    assert_ne!(headers[SIGNATURE_HEADER].to_str().unwrap(), signature(b"other", timestamp, body));
+    assert_eq!(serde_json::from_slice::<Value>(body).unwrap()["order"], 42);
+}
+
+#[test]
+fn test_signature_format() {
+    let sig = signature(b"key", 0, b"{}");
+    assert!(sig.starts_with("sha256="));
+    assert_eq!(sig.len(), "sha256=".len() + 64);
+    assert_eq!(sig, signature(b"key", 0, b"{}"));
+    assert_ne!(sig, signature(b"key", 1, b"{}"));
+}
+
+#[tokio::test]
+async fn test_server_errors_are_retried() {
+    let hook = Arc::new(Hook { statuses: Mutex::new(vec![503, 500]), ..Hook::default() });
+    let url = serve(hook.clone()).await;
+    let sink = WebhookSink::new(url).with_retry(quick_retries(3));
+
+    assert!(Sink::<Context>::deliver(&sink, &Context::new().insert("n", 1)).await.is_ok());
+    assert_eq!(hook.received.lock().unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn test_client_errors_are_not_retried() {
+    let hook = Arc::new(Hook { statuses: Mutex::new(vec![400]), ..Hook::default() });
+    let url = serve(hook.clone()).await;
+    let sink = WebhookSink::new(url).with_retry(quick_retries(3));
+
+    let err = Sink::<Context>::deliver(&sink, &Context::new()).await.unwrap_err();
+    assert!(err.to_string().contains("400"));
+    assert_eq!(hook.received.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_go_to_dead_letter() {
+    let hook = Arc::new(Hook { statuses: Mutex::new(vec![500; 10]), ..Hook::default() });
+    let url = serve(hook.clone()).await;
+    let (tx, mut rx) = futures::channel::mpsc::channel::<Value>(4);
+    let sink = WebhookSink::new(url.clone()).with_retry(quick_retries(2)).with_dead_letter(Arc::new(ChannelSink::new(tx)));
+
+    assert!(Sink::<Context>::deliver(&sink, &Context::new().insert("order", 7)).await.is_ok());
+    let letter: DeadLetter = serde_json::from_value(rx.next().await.unwrap()).unwrap();
+    assert_eq!(letter.url, url);
+    assert_eq!(letter.attempts, 2);
+    assert!(letter.error.contains("500"));
+    assert_eq!(letter.payload["order"], 7);
+}
+
+#[tokio::test]
+async fn test_each_url_delivered_on_its_own() {
+    let good = Arc::new(Hook::default());
+    let good_url = serve(good.clone()).await;
+    // Nothing listens on port 9 (discard), so the second URL fails to connect
+    let sink = WebhookSink::new(good_url).with_url("http://127.0.0.1:9/hook").with_retry(quick_retries(1));
+
+    let err = Sink::<Context>::deliver(&sink, &Context::new()).await.unwrap_err();
+    assert!(err.to_string().contains("127.0.0.1:9"));
+    assert_eq!(good.received.lock().unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn test_retry_backoff_uses_the_executor() {
+    use modulink_rs::runtime::MockExecutor;
+
+    let hook = Arc::new(Hook { statuses: Mutex::new(vec![503]), ..Hook::default() });
+    let url = serve(hook.clone()).await;
+    let exec = MockExecutor::new();
+    let hour = Duration::from_secs(3600);
+    let sink = WebhookSink::new(url)
+        .with_retry(RetryPolicy::new(2).with_backoff(Backoff::Fixed(hour)))
+        .with_executor(Arc::new(exec.clone()));
+    let delivery = tokio::spawn(async move { Sink::<Context>::deliver(&sink, &Context::new()).await });
+
+    // The hour-long backoff passes on the executor's clock, not the wall clock
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !delivery.is_finished() {
+            exec.advance(hour);
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await
+    .unwrap();
+    assert!(delivery.await.unwrap().is_ok());
+    assert_eq!(hook.received.lock().unwrap().len(), 2);
+}
@@ -0,0 +1,54 @@
+//! Test windowed aggregation state (ergonomic pattern)
+
+use modulink_rs::chains::Chain;
+use modulink_rs::context::Context;
+use modulink_rs::links::window::WindowState;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn event(user: &str, ts: u64, amount: i64, page: &str) -> Context {
+    Context::new().insert("user", user).insert("ts", ts).insert("amount", amount).insert("page", page)
+}
+
+fn window(state: WindowState) -> Arc<WindowState> {
+    Arc::new(state.key_by("user").event_time("ts").count("count").sum("amount", "total").distinct("page", "pages"))
+}
+
+#[tokio::test]
+async fn test_tumbling_window_resets_per_window_and_key() {
+    let state = window(WindowState::tumbling(Duration::from_secs(10)));
+    let mut chain = Chain::new();
+    chain.add_link(state.clone().link());
+
+    chain.run(event("a", 1_000, 5, "/x")).await;
+    chain.run(event("b", 2_000, 100, "/x")).await;
+    let ctx = chain.run(event("a", 9_000, 7, "/y")).await;
+    assert_eq!(ctx.get::<u64>("count"), Some(2));
+    assert_eq!(ctx.get::<f64>("total"), Some(12.0));
+    assert_eq!(ctx.get::<u64>("pages"), Some(2));
+
+    let ctx = chain.run(event("a", 10_000, 1, "/x")).await;
+    assert_eq!(ctx.get::<u64>("count"), Some(1));
+    assert_eq!(ctx.get::<f64>("total"), Some(1.0));
+    assert_eq!(state.aggregates("b").unwrap()["count"], 1);
+    assert!(state.aggregates("c").is_none());
+}
+
+#[tokio::test]
+async fn test_sliding_window_covers_trailing_size() {
+    let state = window(WindowState::sliding(Duration::from_secs(10)));
+    let mut chain = Chain::new();
+    chain.add_link(state.clone().link());
+
+    chain.run(event("a", 1_000, 1, "/x")).await;
+    chain.run(event("a", 8_000, 2, "/x")).await;
+    let ctx = chain.run(event("a", 12_000, 3, "/y")).await;
+    assert_eq!(ctx.get::<u64>("count"), Some(2));
+    assert_eq!(ctx.get::<f64>("total"), Some(5.0));
+    assert_eq!(ctx.get::<u64>("pages"), Some(2));
+
+    // A late event is aggregated over the window leading up to its own time
+    let ctx = chain.run(event("a", 9_000, 4, "/x")).await;
+    assert_eq!(ctx.get::<u64>("count"), Some(2));
+    assert_eq!(ctx.get::<f64>("total"), Some(6.0));
+}